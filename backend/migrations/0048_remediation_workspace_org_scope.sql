@@ -0,0 +1,14 @@
+-- key: migration -> remediation-workspace-org-scope
+ALTER TABLE runtime_vm_remediation_workspaces
+    ADD COLUMN IF NOT EXISTS organization_id INTEGER REFERENCES organizations(id) ON DELETE CASCADE;
+
+ALTER TABLE runtime_vm_remediation_workspaces
+    DROP CONSTRAINT IF EXISTS runtime_vm_remediation_workspaces_workspace_key_key;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_runtime_vm_remediation_workspaces_org_key
+    ON runtime_vm_remediation_workspaces(organization_id, workspace_key)
+    WHERE organization_id IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_runtime_vm_remediation_workspaces_unscoped_key
+    ON runtime_vm_remediation_workspaces(workspace_key)
+    WHERE organization_id IS NULL;
@@ -0,0 +1,3 @@
+-- key: migration -> vector-db-search-dimension
+ALTER TABLE vector_dbs
+    ADD COLUMN IF NOT EXISTS dimension INTEGER CHECK (dimension IS NULL OR dimension > 0);
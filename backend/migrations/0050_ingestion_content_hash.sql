@@ -0,0 +1,4 @@
+-- key: migration -> ingestion-content-hash-dedupe
+ALTER TABLE ingestion_jobs
+    ADD COLUMN IF NOT EXISTS content_hash TEXT,
+    ADD COLUMN IF NOT EXISTS force_next_run BOOLEAN NOT NULL DEFAULT FALSE;
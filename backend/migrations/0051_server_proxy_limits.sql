@@ -0,0 +1,4 @@
+-- key: migration -> proxy-body-limit-overrides
+ALTER TABLE mcp_servers
+    ADD COLUMN IF NOT EXISTS proxy_max_request_bytes BIGINT CHECK (proxy_max_request_bytes IS NULL OR proxy_max_request_bytes > 0),
+    ADD COLUMN IF NOT EXISTS proxy_max_response_bytes BIGINT CHECK (proxy_max_response_bytes IS NULL OR proxy_max_response_bytes > 0);
@@ -0,0 +1,17 @@
+-- key: migration -> server-secret-versions
+ALTER TABLE server_secrets
+    ADD COLUMN IF NOT EXISTS current_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS server_secret_versions (
+    id SERIAL PRIMARY KEY,
+    secret_id INTEGER NOT NULL REFERENCES server_secrets(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL CHECK (version > 0),
+    value BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (secret_id, version)
+);
+
+INSERT INTO server_secret_versions (secret_id, version, value, created_at)
+SELECT id, current_version, value, COALESCE(created_at, NOW())
+FROM server_secrets
+ON CONFLICT (secret_id, version) DO NOTHING;
@@ -0,0 +1,10 @@
+-- key: migration -> secret-envelope-master-key-id
+ALTER TABLE server_secrets
+    ADD COLUMN IF NOT EXISTS master_key_id TEXT;
+
+ALTER TABLE server_secret_versions
+    ADD COLUMN IF NOT EXISTS master_key_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_server_secret_versions_master_key
+    ON server_secret_versions(master_key_id)
+    WHERE master_key_id IS NOT NULL;
@@ -0,0 +1,12 @@
+-- key: migration -> organization-member-roles
+UPDATE organization_members
+SET role = 'member'
+WHERE role NOT IN ('owner', 'admin', 'member', 'viewer');
+
+-- drop first so a re-run against a database that already has the constraint succeeds
+ALTER TABLE organization_members
+    DROP CONSTRAINT IF EXISTS organization_members_role_check;
+
+ALTER TABLE organization_members
+    ADD CONSTRAINT organization_members_role_check
+    CHECK (role IN ('owner', 'admin', 'member', 'viewer'));
@@ -0,0 +1,12 @@
+-- key: migration -> marketplace-listings
+CREATE TABLE IF NOT EXISTS marketplace_listings (
+    server_id INTEGER PRIMARY KEY REFERENCES mcp_servers(id) ON DELETE CASCADE,
+    build_artifact_run_id INTEGER REFERENCES build_artifact_runs(id) ON DELETE SET NULL,
+    manifest_digest TEXT,
+    manifest_tag TEXT,
+    registry_image TEXT,
+    readiness JSONB NOT NULL DEFAULT '[]'::jsonb,
+    published_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,16 @@
+-- key: migration -> evaluation-runs
+CREATE TABLE IF NOT EXISTS evaluation_runs (
+    id SERIAL PRIMARY KEY,
+    server_id INTEGER NOT NULL REFERENCES mcp_servers(id) ON DELETE CASCADE,
+    triggered_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS evaluation_runs_server_idx
+    ON evaluation_runs (server_id, created_at DESC);
+
+ALTER TABLE evaluation_results
+    ADD COLUMN IF NOT EXISTS run_id INTEGER REFERENCES evaluation_runs(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS evaluation_results_run_idx
+    ON evaluation_results (run_id);
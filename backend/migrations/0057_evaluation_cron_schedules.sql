@@ -0,0 +1,3 @@
+-- key: migration -> evaluation-cron-schedules
+ALTER TABLE evaluation_certifications
+    ADD COLUMN IF NOT EXISTS schedule_cron TEXT;
@@ -0,0 +1,21 @@
+-- key: migration -> workflow-step-retries
+ALTER TABLE workflow_steps
+    ADD COLUMN IF NOT EXISTS retry_policy JSONB;
+
+CREATE TABLE IF NOT EXISTS workflow_runs (
+    id BIGSERIAL PRIMARY KEY,
+    workflow_id INTEGER NOT NULL REFERENCES workflows(id) ON DELETE CASCADE,
+    triggered_by INTEGER NOT NULL REFERENCES users(id),
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'succeeded', 'failed')),
+    input JSONB NOT NULL,
+    output JSONB,
+    error TEXT,
+    failed_step_position INTEGER,
+    step_attempts JSONB NOT NULL DEFAULT '[]'::jsonb,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_runs_workflow_id
+    ON workflow_runs(workflow_id, started_at DESC);
@@ -0,0 +1,3 @@
+-- key: migration -> workflow-branching
+ALTER TABLE workflow_steps
+    ADD COLUMN IF NOT EXISTS routing JSONB;
@@ -0,0 +1,32 @@
+-- key: migration -> file-store-blobs
+CREATE TABLE IF NOT EXISTS file_blobs (
+    digest TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 0 CHECK (ref_count >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_file_blobs_unreferenced
+    ON file_blobs(digest) WHERE ref_count = 0;
+
+ALTER TABLE server_files
+    ADD COLUMN IF NOT EXISTS blob_digest TEXT REFERENCES file_blobs(digest);
+
+-- Reference counts follow server_files rows, including cascaded deletes.
+CREATE OR REPLACE FUNCTION server_files_blob_refcount()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' AND NEW.blob_digest IS NOT NULL THEN
+        UPDATE file_blobs SET ref_count = ref_count + 1 WHERE digest = NEW.blob_digest;
+    ELSIF TG_OP = 'DELETE' AND OLD.blob_digest IS NOT NULL THEN
+        UPDATE file_blobs SET ref_count = ref_count - 1 WHERE digest = OLD.blob_digest;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_server_files_blob_refcount
+AFTER INSERT OR DELETE ON server_files
+FOR EACH ROW
+EXECUTE FUNCTION server_files_blob_refcount();
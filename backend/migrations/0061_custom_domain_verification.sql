@@ -0,0 +1,8 @@
+-- key: migration -> domain-verification
+ALTER TABLE custom_domains
+    ADD COLUMN IF NOT EXISTS verification_status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (verification_status IN ('pending', 'verified')),
+    ADD COLUMN IF NOT EXISTS verification_token TEXT,
+    ADD COLUMN IF NOT EXISTS verification_requested_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS verification_checked_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ;
@@ -0,0 +1,16 @@
+-- key: migration -> service-health-dependencies
+ALTER TABLE service_integrations
+    ADD COLUMN IF NOT EXISTS health_status TEXT NOT NULL DEFAULT 'unknown'
+        CHECK (health_status IN ('healthy', 'degraded', 'down', 'unknown')),
+    ADD COLUMN IF NOT EXISTS health_checked_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS service_dependencies (
+    service_id INTEGER NOT NULL REFERENCES service_integrations(id) ON DELETE CASCADE,
+    depends_on_id INTEGER NOT NULL REFERENCES service_integrations(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (service_id, depends_on_id),
+    CHECK (service_id <> depends_on_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_dependencies_depends_on
+    ON service_dependencies(depends_on_id);
@@ -0,0 +1,7 @@
+-- key: migration -> invocation-timeouts
+ALTER TABLE invocation_traces
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'succeeded'
+        CHECK (status IN ('succeeded', 'failed', 'timed_out')),
+    ADD COLUMN IF NOT EXISTS timeout_ms BIGINT;
+
+UPDATE invocation_traces SET status = 'failed' WHERE output_text IS NULL;
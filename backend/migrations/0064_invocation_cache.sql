@@ -0,0 +1,7 @@
+-- key: migration -> invocation-cache
+ALTER TABLE mcp_servers
+    ADD COLUMN IF NOT EXISTS invocation_cache_ttl_secs INTEGER
+        CHECK (invocation_cache_ttl_secs > 0);
+
+ALTER TABLE server_capabilities
+    ADD COLUMN IF NOT EXISTS deterministic BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,4 @@
+-- key: migration -> job-queue-priority
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS priority TEXT NOT NULL DEFAULT 'normal'
+        CHECK (priority IN ('high', 'normal', 'low'));
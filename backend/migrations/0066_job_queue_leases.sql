@@ -0,0 +1,13 @@
+-- key: migration -> job-queue-leases
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS lease_expires_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS requeue_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS last_error TEXT;
+
+-- rows left mid-flight by the old worker had no lease; give them one that is already due
+UPDATE job_queue SET lease_expires_at = NOW() WHERE status = 'processing';
+
+CREATE INDEX IF NOT EXISTS idx_job_queue_lease
+    ON job_queue(lease_expires_at)
+    WHERE status = 'processing';
@@ -0,0 +1,8 @@
+-- key: migration -> job-queue-dedupe
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS dedupe_key TEXT,
+    ADD COLUMN IF NOT EXISTS completed_at TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_job_queue_dedupe_key
+    ON job_queue(dedupe_key)
+    WHERE status IN ('queued', 'processing', 'completed');
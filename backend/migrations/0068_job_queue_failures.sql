@@ -0,0 +1,3 @@
+-- key: migration -> job-queue-poison
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS failure_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+-- key: migration -> build-attestations
+CREATE TABLE IF NOT EXISTS build_artifact_attestations (
+    id SERIAL PRIMARY KEY,
+    manifest_digest TEXT NOT NULL UNIQUE,
+    build_artifact_run_id INTEGER REFERENCES build_artifact_runs(id) ON DELETE SET NULL,
+    key_id TEXT NOT NULL,
+    -- DSSE envelope wrapping the in-toto provenance statement
+    envelope JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE promotion_tracks
+    ADD COLUMN IF NOT EXISTS require_attestation BOOLEAN NOT NULL DEFAULT FALSE;
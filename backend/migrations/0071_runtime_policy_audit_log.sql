@@ -0,0 +1,19 @@
+-- key: migration -> runtime-policy-audit
+CREATE TABLE IF NOT EXISTS runtime_policy_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    server_id INTEGER NOT NULL REFERENCES mcp_servers(id) ON DELETE CASCADE,
+    decision_id INTEGER REFERENCES runtime_policy_decisions(id) ON DELETE SET NULL,
+    candidate_backend TEXT NOT NULL,
+    backend TEXT NOT NULL,
+    executor_name TEXT,
+    executors_considered TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],
+    -- not-required | required | approved
+    governance_verdict TEXT NOT NULL,
+    allowed BOOLEAN NOT NULL,
+    veto_reasons TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],
+    policy_version TEXT NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_runtime_policy_audit_log_server
+    ON runtime_policy_audit_log(server_id, recorded_at DESC);
@@ -0,0 +1,2 @@
+-- key: migration -> api-key service principal lookup
+CREATE INDEX IF NOT EXISTS idx_mcp_servers_api_key ON mcp_servers(api_key);
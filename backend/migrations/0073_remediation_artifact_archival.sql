@@ -0,0 +1,37 @@
+-- key: migration -> remediation-artifact-archival
+ALTER TABLE runtime_vm_remediation_artifacts
+    ADD COLUMN IF NOT EXISTS archived_blob_digest TEXT REFERENCES file_blobs(digest),
+    ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_runtime_vm_remediation_artifacts_unarchived
+    ON runtime_vm_remediation_artifacts(created_at) WHERE archived_blob_digest IS NULL;
+
+-- Archived artifacts hold a blob reference just as server_files rows do, so the
+-- blob store never prunes an archive that is still pointed at.
+CREATE OR REPLACE FUNCTION remediation_artifact_blob_refcount()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        IF NEW.archived_blob_digest IS NOT NULL THEN
+            UPDATE file_blobs SET ref_count = ref_count + 1 WHERE digest = NEW.archived_blob_digest;
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        IF OLD.archived_blob_digest IS NOT NULL THEN
+            UPDATE file_blobs SET ref_count = ref_count - 1 WHERE digest = OLD.archived_blob_digest;
+        END IF;
+    ELSIF OLD.archived_blob_digest IS DISTINCT FROM NEW.archived_blob_digest THEN
+        IF NEW.archived_blob_digest IS NOT NULL THEN
+            UPDATE file_blobs SET ref_count = ref_count + 1 WHERE digest = NEW.archived_blob_digest;
+        END IF;
+        IF OLD.archived_blob_digest IS NOT NULL THEN
+            UPDATE file_blobs SET ref_count = ref_count - 1 WHERE digest = OLD.archived_blob_digest;
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_remediation_artifact_blob_refcount
+AFTER INSERT OR UPDATE OF archived_blob_digest OR DELETE ON runtime_vm_remediation_artifacts
+FOR EACH ROW
+EXECUTE FUNCTION remediation_artifact_blob_refcount();
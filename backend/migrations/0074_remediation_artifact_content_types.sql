@@ -0,0 +1,31 @@
+-- key: migration -> remediation-artifact-content-types
+ALTER TABLE runtime_vm_remediation_artifacts
+    ADD COLUMN IF NOT EXISTS content_type TEXT NOT NULL DEFAULT 'application/json',
+    ADD COLUMN IF NOT EXISTS render_hint TEXT NOT NULL DEFAULT 'json';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'chk_runtime_vm_remediation_artifact_render_hint'
+            AND conrelid = 'runtime_vm_remediation_artifacts'::regclass
+    ) THEN
+        ALTER TABLE runtime_vm_remediation_artifacts
+            ADD CONSTRAINT chk_runtime_vm_remediation_artifact_render_hint
+            CHECK (render_hint IN ('text', 'json', 'markdown', 'binary-pointer'));
+    END IF;
+END;
+$$;
+
+-- existing rows: external files become pointers, bare strings plain text
+UPDATE runtime_vm_remediation_artifacts
+SET content_type = 'application/octet-stream',
+    render_hint = 'binary-pointer'
+WHERE uri IS NOT NULL;
+
+UPDATE runtime_vm_remediation_artifacts
+SET content_type = 'text/plain',
+    render_hint = 'text'
+WHERE uri IS NULL
+    AND jsonb_typeof(metadata) = 'string';
@@ -0,0 +1,3 @@
+-- key: migration -> remediation-playbook-payload-schema
+ALTER TABLE runtime_vm_remediation_playbooks
+    ADD COLUMN IF NOT EXISTS automation_payload_schema JSONB;
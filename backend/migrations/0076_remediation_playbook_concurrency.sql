@@ -0,0 +1,170 @@
+-- key: migration -> remediation-playbook-concurrency
+ALTER TABLE runtime_vm_remediation_playbooks
+    ADD COLUMN IF NOT EXISTS max_concurrent_runs INTEGER;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1
+        FROM pg_constraint
+        WHERE conname = 'chk_runtime_vm_remediation_playbooks_max_concurrent_runs'
+            AND conrelid = 'runtime_vm_remediation_playbooks'::regclass
+    ) THEN
+        ALTER TABLE runtime_vm_remediation_playbooks
+            ADD CONSTRAINT chk_runtime_vm_remediation_playbooks_max_concurrent_runs
+            CHECK (max_concurrent_runs IS NULL OR max_concurrent_runs > 0);
+    END IF;
+END;
+$$;
+
+-- blocked runs wait for a free slot under their playbook's limit
+ALTER TABLE runtime_vm_remediation_runs
+    DROP CONSTRAINT IF EXISTS chk_runtime_vm_remediation_status;
+ALTER TABLE runtime_vm_remediation_runs
+    ADD CONSTRAINT chk_runtime_vm_remediation_status
+    CHECK (status IN ('blocked', 'pending', 'running', 'completed', 'failed', 'cancelled'));
+
+CREATE INDEX IF NOT EXISTS idx_runtime_vm_remediation_runs_blocked
+    ON runtime_vm_remediation_runs(playbook_id, started_at)
+    WHERE status = 'blocked';
+
+-- A run holds a slot while it is running, or pending without a rejected approval.
+-- Both functions lock the playbook row before counting, so concurrent enqueues and
+-- completions for the same playbook are serialized and every count is current.
+CREATE OR REPLACE FUNCTION remediation_playbook_active_runs(target_playbook BIGINT)
+RETURNS BIGINT AS $$
+DECLARE
+    active BIGINT;
+BEGIN
+    SELECT COUNT(*)
+    INTO active
+    FROM runtime_vm_remediation_runs
+    WHERE playbook_id = target_playbook
+        AND (status = 'running' OR (status = 'pending' AND approval_state <> 'rejected'));
+    RETURN active;
+END;
+$$ LANGUAGE plpgsql VOLATILE;
+
+CREATE OR REPLACE FUNCTION remediation_run_admission()
+RETURNS TRIGGER AS $$
+DECLARE
+    run_limit INTEGER;
+BEGIN
+    IF NEW.playbook_id IS NULL OR NEW.status <> 'pending' THEN
+        RETURN NEW;
+    END IF;
+    SELECT max_concurrent_runs
+    INTO run_limit
+    FROM runtime_vm_remediation_playbooks
+    WHERE id = NEW.playbook_id
+    FOR UPDATE;
+    IF run_limit IS NOT NULL
+        AND remediation_playbook_active_runs(NEW.playbook_id) >= run_limit THEN
+        NEW.status := 'blocked';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_remediation_run_admission ON runtime_vm_remediation_runs;
+CREATE TRIGGER trg_remediation_run_admission
+BEFORE INSERT ON runtime_vm_remediation_runs
+FOR EACH ROW
+EXECUTE FUNCTION remediation_run_admission();
+
+-- Promotes the oldest blocked runs into the free slots; returns how many were released.
+CREATE OR REPLACE FUNCTION release_blocked_remediation_runs(target_playbook BIGINT)
+RETURNS INTEGER AS $$
+DECLARE
+    run_limit INTEGER;
+    free_slots BIGINT;
+    released INTEGER;
+BEGIN
+    SELECT max_concurrent_runs
+    INTO run_limit
+    FROM runtime_vm_remediation_playbooks
+    WHERE id = target_playbook
+    FOR UPDATE;
+    IF NOT FOUND THEN
+        RETURN 0;
+    END IF;
+    IF run_limit IS NOT NULL THEN
+        free_slots := GREATEST(run_limit - remediation_playbook_active_runs(target_playbook), 0);
+    END IF;
+
+    WITH next_runs AS (
+        SELECT id
+        FROM runtime_vm_remediation_runs
+        WHERE playbook_id = target_playbook
+            AND status = 'blocked'
+        ORDER BY started_at, id
+        LIMIT free_slots
+        FOR UPDATE SKIP LOCKED
+    )
+    UPDATE runtime_vm_remediation_runs AS runs
+    SET status = 'pending',
+        version = runs.version + 1,
+        updated_at = NOW()
+    FROM next_runs
+    WHERE runs.id = next_runs.id;
+    GET DIAGNOSTICS released = ROW_COUNT;
+    RETURN released;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION remediation_run_release()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.playbook_id IS NULL THEN
+        RETURN NULL;
+    END IF;
+    IF (OLD.status = 'running' OR (OLD.status = 'pending' AND OLD.approval_state <> 'rejected'))
+        AND NOT (NEW.status = 'running' OR (NEW.status = 'pending' AND NEW.approval_state <> 'rejected')) THEN
+        PERFORM release_blocked_remediation_runs(NEW.playbook_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_remediation_run_release ON runtime_vm_remediation_runs;
+CREATE TRIGGER trg_remediation_run_release
+AFTER UPDATE OF status, approval_state ON runtime_vm_remediation_runs
+FOR EACH ROW
+EXECUTE FUNCTION remediation_run_release();
+
+-- raising or clearing a limit frees slots immediately
+CREATE OR REPLACE FUNCTION remediation_playbook_limit_release()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.max_concurrent_runs IS DISTINCT FROM OLD.max_concurrent_runs THEN
+        PERFORM release_blocked_remediation_runs(NEW.id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_remediation_playbook_limit_release ON runtime_vm_remediation_playbooks;
+CREATE TRIGGER trg_remediation_playbook_limit_release
+AFTER UPDATE OF max_concurrent_runs ON runtime_vm_remediation_playbooks
+FOR EACH ROW
+EXECUTE FUNCTION remediation_playbook_limit_release();
+
+-- deleting a playbook drops its limit, so nothing stays blocked behind it
+CREATE OR REPLACE FUNCTION remediation_playbook_delete_release()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE runtime_vm_remediation_runs
+    SET status = 'pending',
+        version = version + 1,
+        updated_at = NOW()
+    WHERE playbook_id = OLD.id
+        AND status = 'blocked';
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_remediation_playbook_delete_release ON runtime_vm_remediation_playbooks;
+CREATE TRIGGER trg_remediation_playbook_delete_release
+BEFORE DELETE ON runtime_vm_remediation_playbooks
+FOR EACH ROW
+EXECUTE FUNCTION remediation_playbook_delete_release();
@@ -0,0 +1,47 @@
+-- key: migration -> remediation-run-scheduling
+ALTER TABLE runtime_vm_remediation_runs
+    ADD COLUMN IF NOT EXISTS scheduled_for TIMESTAMPTZ;
+
+-- scheduled runs wait for their time before they are queued
+ALTER TABLE runtime_vm_remediation_runs
+    DROP CONSTRAINT IF EXISTS chk_runtime_vm_remediation_status;
+ALTER TABLE runtime_vm_remediation_runs
+    ADD CONSTRAINT chk_runtime_vm_remediation_status
+    CHECK (status IN ('scheduled', 'blocked', 'pending', 'running', 'completed', 'failed', 'cancelled'));
+
+CREATE INDEX IF NOT EXISTS idx_runtime_vm_remediation_runs_scheduled
+    ON runtime_vm_remediation_runs(scheduled_for)
+    WHERE status = 'scheduled';
+
+-- A scheduled run that comes due goes through the same concurrency admission as a new run.
+CREATE OR REPLACE FUNCTION remediation_run_admission()
+RETURNS TRIGGER AS $$
+DECLARE
+    run_limit INTEGER;
+BEGIN
+    IF NEW.playbook_id IS NULL OR NEW.status <> 'pending' THEN
+        RETURN NEW;
+    END IF;
+    IF TG_OP = 'UPDATE' THEN
+        IF OLD.status <> 'scheduled' THEN
+            RETURN NEW;
+        END IF;
+    END IF;
+    SELECT max_concurrent_runs
+    INTO run_limit
+    FROM runtime_vm_remediation_playbooks
+    WHERE id = NEW.playbook_id
+    FOR UPDATE;
+    IF run_limit IS NOT NULL
+        AND remediation_playbook_active_runs(NEW.playbook_id) >= run_limit THEN
+        NEW.status := 'blocked';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_remediation_run_admission ON runtime_vm_remediation_runs;
+CREATE TRIGGER trg_remediation_run_admission
+BEFORE INSERT OR UPDATE OF status ON runtime_vm_remediation_runs
+FOR EACH ROW
+EXECUTE FUNCTION remediation_run_admission();
@@ -0,0 +1,37 @@
+-- key: migration -> remediation-maintenance-windows
+CREATE TABLE IF NOT EXISTS remediation_maintenance_windows (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    recurrence TEXT NOT NULL,
+    duration_minutes INTEGER NOT NULL,
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_remediation_maintenance_windows_duration
+        CHECK (duration_minutes > 0 AND duration_minutes <= 10080)
+);
+
+CREATE INDEX IF NOT EXISTS idx_remediation_maintenance_windows_org
+    ON remediation_maintenance_windows(organization_id)
+    WHERE enabled;
+
+-- every run admitted outside a window, with who forced it and why
+CREATE TABLE IF NOT EXISTS remediation_maintenance_overrides (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    remediation_run_id BIGINT NOT NULL REFERENCES runtime_vm_remediation_runs(id) ON DELETE CASCADE,
+    playbook_id BIGINT REFERENCES runtime_vm_remediation_playbooks(id) ON DELETE SET NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    actor_role TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    effective_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_remediation_maintenance_overrides_reason
+        CHECK (length(btrim(reason)) > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_remediation_maintenance_overrides_org
+    ON remediation_maintenance_overrides(organization_id, created_at DESC);
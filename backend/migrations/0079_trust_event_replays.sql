@@ -0,0 +1,12 @@
+-- key: migration -> trust-event-replays
+-- the last replay applied per instance, so replaying an unchanged range is a no-op
+CREATE TABLE IF NOT EXISTS runtime_vm_trust_replays (
+    runtime_vm_instance_id BIGINT PRIMARY KEY REFERENCES runtime_vm_instances(id) ON DELETE CASCADE,
+    last_event_id BIGINT NOT NULL,
+    registry_version BIGINT NOT NULL,
+    replayed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    replayed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_runtime_vm_trust_history_triggered_at
+    ON runtime_vm_trust_history(triggered_at, id);
@@ -0,0 +1,8 @@
+-- key: migration -> remediation-artifact-fingerprints
+-- Rows written before this migration keep a NULL fingerprint and are never deduplicated.
+ALTER TABLE runtime_vm_remediation_artifacts
+    ADD COLUMN IF NOT EXISTS content_fingerprint TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_runtime_vm_remediation_artifacts_fingerprint
+    ON runtime_vm_remediation_artifacts (remediation_run_id, content_fingerprint)
+    WHERE content_fingerprint IS NOT NULL;
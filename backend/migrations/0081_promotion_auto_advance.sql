@@ -0,0 +1,13 @@
+-- key: migration -> promotion-auto-advance
+CREATE TABLE IF NOT EXISTS promotion_auto_advance_rules (
+    promotion_track_id INTEGER PRIMARY KEY REFERENCES promotion_tracks(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    min_dwell_seconds BIGINT NOT NULL CHECK (min_dwell_seconds >= 0),
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_promotions_active_track
+    ON artifact_promotions (promotion_track_id)
+    WHERE status = 'active';
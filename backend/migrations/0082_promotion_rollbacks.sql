@@ -0,0 +1,14 @@
+-- key: migration -> promotion-rollback
+CREATE TABLE IF NOT EXISTS promotion_rollbacks (
+    id BIGSERIAL PRIMARY KEY,
+    promotion_id BIGINT NOT NULL REFERENCES artifact_promotions(id) ON DELETE CASCADE,
+    from_stage TEXT NOT NULL,
+    to_stage TEXT,
+    restored_promotion_id BIGINT REFERENCES artifact_promotions(id) ON DELETE SET NULL,
+    reason TEXT NOT NULL,
+    rolled_back_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_promotion_rollbacks_promotion
+    ON promotion_rollbacks (promotion_id);
@@ -0,0 +1,3 @@
+-- key: migration -> proxy-transforms
+ALTER TABLE mcp_servers
+    ADD COLUMN IF NOT EXISTS proxy_transforms JSONB;
@@ -0,0 +1,18 @@
+-- key: migration -> organization-audit-log
+CREATE TABLE IF NOT EXISTS organization_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    category TEXT NOT NULL,
+    action TEXT NOT NULL,
+    actor TEXT,
+    target TEXT,
+    details JSONB NOT NULL DEFAULT '{}'::JSONB,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_audit_log_org
+    ON organization_audit_log (organization_id, id DESC);
+
+-- provider keys registered on behalf of an organization audit into its log
+ALTER TABLE provider_keys
+    ADD COLUMN IF NOT EXISTS organization_id INTEGER REFERENCES organizations(id) ON DELETE SET NULL;
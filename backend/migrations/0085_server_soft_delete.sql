@@ -0,0 +1,7 @@
+-- key: migration -> server-soft-delete
+ALTER TABLE mcp_servers
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_mcp_servers_deleted_at
+    ON mcp_servers (deleted_at)
+    WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,12 @@
+-- key: migration -> capability-snapshots
+CREATE TABLE IF NOT EXISTS server_capability_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    server_id INTEGER NOT NULL REFERENCES mcp_servers(id) ON DELETE CASCADE,
+    capabilities JSONB NOT NULL,
+    -- changes against the previous snapshot; NULL for a server's first snapshot
+    diff JSONB,
+    taken_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_capability_snapshots_server
+    ON server_capability_snapshots (server_id, id DESC);
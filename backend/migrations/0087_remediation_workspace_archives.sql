@@ -0,0 +1,15 @@
+-- key: migration -> remediation-workspace-archives
+CREATE TABLE IF NOT EXISTS runtime_vm_remediation_workspace_archives (
+    workspace_id BIGINT PRIMARY KEY REFERENCES runtime_vm_remediation_workspaces(id) ON DELETE CASCADE,
+    -- restored onto the workspace when it leaves the archive
+    previous_lifecycle_state TEXT NOT NULL,
+    -- lifecycle console snapshot (workspace, revision, runs) frozen at archival time
+    snapshot JSONB NOT NULL,
+    archived_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- the console listing skips archived workspaces unless asked for them
+CREATE INDEX IF NOT EXISTS idx_runtime_vm_remediation_workspaces_live
+    ON runtime_vm_remediation_workspaces (id)
+    WHERE lifecycle_state <> 'archived';
@@ -0,0 +1,8 @@
+-- key: migration -> webhook-sealed-secrets
+-- signing secrets are stored sealed; the plaintext column only holds rows written before
+-- this migration until the dispatcher seals them
+ALTER TABLE webhook_endpoints
+    ADD COLUMN IF NOT EXISTS sealed_secret BYTEA;
+
+ALTER TABLE webhook_endpoints
+    ALTER COLUMN secret DROP NOT NULL;
@@ -0,0 +1,12 @@
+-- key: migration -> remediation-run-organization
+-- runs carry the organization of their instance's server so tenant metrics need no lookup
+ALTER TABLE runtime_vm_remediation_runs
+    ADD COLUMN IF NOT EXISTS organization_id INTEGER REFERENCES organizations(id) ON DELETE SET NULL;
+
+UPDATE runtime_vm_remediation_runs r
+SET organization_id = s.organization_id
+FROM runtime_vm_instances i
+JOIN mcp_servers s ON s.id = i.server_id
+WHERE i.id = r.runtime_vm_instance_id
+  AND r.organization_id IS NULL
+  AND s.organization_id IS NOT NULL;
@@ -0,0 +1,23 @@
+-- key: migration -> server-purge-foreign-keys
+-- the retention sweep hard-deletes servers, so every reference must either follow the server
+-- out or let go of it; vm instances keep their remediation runs and trust history
+ALTER TABLE usage_metrics
+    DROP CONSTRAINT IF EXISTS usage_metrics_server_id_fkey,
+    ADD CONSTRAINT usage_metrics_server_id_fkey
+        FOREIGN KEY (server_id) REFERENCES mcp_servers(id) ON DELETE CASCADE;
+
+ALTER TABLE context_sessions
+    DROP CONSTRAINT IF EXISTS context_sessions_server_id_fkey,
+    ADD CONSTRAINT context_sessions_server_id_fkey
+        FOREIGN KEY (server_id) REFERENCES mcp_servers(id) ON DELETE CASCADE;
+
+ALTER TABLE workflow_steps
+    DROP CONSTRAINT IF EXISTS workflow_steps_server_id_fkey,
+    ADD CONSTRAINT workflow_steps_server_id_fkey
+        FOREIGN KEY (server_id) REFERENCES mcp_servers(id) ON DELETE CASCADE;
+
+ALTER TABLE runtime_vm_instances
+    ALTER COLUMN server_id DROP NOT NULL,
+    DROP CONSTRAINT IF EXISTS runtime_vm_instances_server_id_fkey,
+    ADD CONSTRAINT runtime_vm_instances_server_id_fkey
+        FOREIGN KEY (server_id) REFERENCES mcp_servers(id) ON DELETE SET NULL;
@@ -0,0 +1,4 @@
+-- key: migration -> ingestion-source-etag
+-- the source's ETag from the last complete ingest, sent back as If-None-Match
+ALTER TABLE ingestion_jobs
+    ADD COLUMN IF NOT EXISTS source_etag TEXT;
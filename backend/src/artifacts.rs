@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
+pub mod attestation;
+
 // key: artifact-persistence -> build_artifact_runs,build_artifact_platforms
 #[derive(Debug, Clone)]
 pub struct ArtifactPlatformRecord {
@@ -38,10 +40,11 @@ pub struct ArtifactPersistenceRequest {
     pub platforms: Vec<ArtifactPlatformRecord>,
 }
 
+/// Returns the id of the inserted `build_artifact_runs` row.
 pub async fn record_build_artifacts(
     pool: &PgPool,
     request: ArtifactPersistenceRequest,
-) -> Result<(), sqlx::Error> {
+) -> Result<i32, sqlx::Error> {
     let mut tx = pool.begin().await?;
     let run_id: i32 = sqlx::query_scalar(
         r#"
@@ -124,5 +127,6 @@ pub async fn record_build_artifacts(
         .await?;
     }
 
-    tx.commit().await
+    tx.commit().await?;
+    Ok(run_id)
 }
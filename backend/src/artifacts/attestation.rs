@@ -0,0 +1,462 @@
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use base64::engine::general_purpose::STANDARD as Base64Engine;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgExecutor;
+use sqlx::{FromRow, PgPool};
+use thiserror::Error;
+
+// key: artifact-attestation -> signed slsa provenance per manifest digest
+
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+pub const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+pub const BUILD_TYPE: &str = "https://mcp-host.dev/build/docker-from-git@v1";
+/// DSSE payload type for in-toto statements.
+pub const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AttestationError {
+    #[error("invalid signing key: {0}")]
+    InvalidKey(String),
+    #[error("manifest digest `{0}` is not of the form sha256:<hex>")]
+    InvalidDigest(String),
+    #[error("malformed envelope: {0}")]
+    Malformed(String),
+    #[error("no signature from a trusted key")]
+    UntrustedSignature,
+    #[error("statement subject `{found}` does not match digest `{expected}`")]
+    SubjectMismatch { expected: String, found: String },
+}
+
+/// What the build pipeline knows about a finished image.
+#[derive(Debug, Clone)]
+pub struct ProvenanceInput<'a> {
+    pub image: &'a str,
+    pub manifest_digest: &'a str,
+    pub source_repo: &'a str,
+    pub source_branch: Option<&'a str>,
+    pub source_revision: Option<&'a str>,
+    pub builder_id: &'a str,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Builds an in-toto statement with a SLSA v1 provenance predicate whose subject is
+/// the manifest digest.
+pub fn provenance_statement(input: &ProvenanceInput<'_>) -> Result<Value, AttestationError> {
+    let (algorithm, hex_digest) = split_digest(input.manifest_digest)?;
+    Ok(json!({
+        "_type": STATEMENT_TYPE,
+        "subject": [{
+            "name": input.image,
+            "digest": { algorithm: hex_digest },
+        }],
+        "predicateType": PREDICATE_TYPE,
+        "predicate": {
+            "buildDefinition": {
+                "buildType": BUILD_TYPE,
+                "externalParameters": {
+                    "source": {
+                        "repository": input.source_repo,
+                        "ref": input.source_branch,
+                    },
+                },
+                "resolvedDependencies": [{
+                    "uri": input.source_repo,
+                    "digest": input.source_revision.map(|revision| json!({ "gitCommit": revision })),
+                }],
+            },
+            "runDetails": {
+                "builder": { "id": input.builder_id },
+                "metadata": {
+                    "startedOn": input.started_at,
+                    "finishedOn": input.completed_at,
+                },
+            },
+        },
+    }))
+}
+
+fn split_digest(manifest_digest: &str) -> Result<(&str, &str), AttestationError> {
+    match manifest_digest.split_once(':') {
+        Some(("sha256", hex_digest))
+            if hex_digest.len() == 64 && hex_digest.bytes().all(|b| b.is_ascii_hexdigit()) =>
+        {
+            Ok(("sha256", hex_digest))
+        }
+        _ => Err(AttestationError::InvalidDigest(manifest_digest.to_string())),
+    }
+}
+
+/// A DSSE envelope; `payload` is the base64 statement and each `sig` is base64.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope {
+    pub payload_type: String,
+    pub payload: String,
+    pub signatures: Vec<EnvelopeSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnvelopeSignature {
+    pub keyid: String,
+    pub sig: String,
+}
+
+/// DSSE pre-authentication encoding; the signature covers the payload type as well
+/// as the payload so neither can be swapped independently.
+fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut encoded = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+/// Hex SHA-256 of the raw public key.
+pub fn key_id(public: &PublicKey) -> String {
+    hex::encode(Sha256::digest(public.as_bytes()))
+}
+
+pub struct AttestationSigner {
+    keypair: Keypair,
+    key_id: String,
+}
+
+impl AttestationSigner {
+    pub fn from_seed(seed: &[u8]) -> Result<Self, AttestationError> {
+        let secret =
+            SecretKey::from_bytes(seed).map_err(|e| AttestationError::InvalidKey(e.to_string()))?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            key_id: key_id(&public),
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    /// `None` when no signing key is configured, which disables attestation.
+    pub fn from_config() -> Result<Option<Self>, AttestationError> {
+        let Some(encoded) = config::BUILD_ATTESTATION_SIGNING_KEY.as_deref() else {
+            return Ok(None);
+        };
+        let seed = Base64Engine
+            .decode(encoded)
+            .map_err(|e| AttestationError::InvalidKey(e.to_string()))?;
+        Self::from_seed(&seed).map(Some)
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.keypair.public
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn sign(&self, statement: &Value) -> Envelope {
+        let payload = serde_json::to_vec(statement).expect("statement serializes");
+        let signature = self.keypair.sign(&pae(PAYLOAD_TYPE, &payload));
+        Envelope {
+            payload_type: PAYLOAD_TYPE.to_string(),
+            payload: Base64Engine.encode(&payload),
+            signatures: vec![EnvelopeSignature {
+                keyid: self.key_id.clone(),
+                sig: Base64Engine.encode(signature.to_bytes()),
+            }],
+        }
+    }
+}
+
+/// The configured signer's key plus `BUILD_ATTESTATION_TRUST_ROOTS`, so attestations
+/// made with a rotated-out key keep verifying. Undecodable roots are skipped.
+pub fn trusted_keys() -> Vec<PublicKey> {
+    let mut keys: Vec<PublicKey> = config::BUILD_ATTESTATION_TRUST_ROOTS
+        .iter()
+        .filter_map(|encoded| match Base64Engine.decode(encoded) {
+            Ok(bytes) => PublicKey::from_bytes(&bytes).ok(),
+            Err(_) => None,
+        })
+        .collect();
+    match AttestationSigner::from_config() {
+        Ok(Some(signer)) => keys.push(signer.public_key()),
+        Ok(None) => {}
+        Err(err) => tracing::warn!(%err, "ignoring invalid build attestation signing key"),
+    }
+    keys
+}
+
+/// Checks that a trusted key signed the envelope and that its statement is about
+/// `manifest_digest`. Returns the decoded statement.
+pub fn verify_envelope(
+    envelope: &Envelope,
+    trusted: &[PublicKey],
+    manifest_digest: &str,
+) -> Result<Value, AttestationError> {
+    if envelope.payload_type != PAYLOAD_TYPE {
+        return Err(AttestationError::Malformed(format!(
+            "unexpected payload type `{}`",
+            envelope.payload_type
+        )));
+    }
+    let payload = Base64Engine
+        .decode(&envelope.payload)
+        .map_err(|e| AttestationError::Malformed(format!("payload: {e}")))?;
+    let message = pae(&envelope.payload_type, &payload);
+    let signed_by_trusted = envelope.signatures.iter().any(|entry| {
+        let Some(signature) = Base64Engine
+            .decode(&entry.sig)
+            .ok()
+            .and_then(|bytes| Signature::from_bytes(&bytes).ok())
+        else {
+            return false;
+        };
+        trusted
+            .iter()
+            .any(|key| key.verify_strict(&message, &signature).is_ok())
+    });
+    if !signed_by_trusted {
+        return Err(AttestationError::UntrustedSignature);
+    }
+
+    let statement: Value = serde_json::from_slice(&payload)
+        .map_err(|e| AttestationError::Malformed(format!("statement: {e}")))?;
+    if statement["_type"] != STATEMENT_TYPE || statement["predicateType"] != PREDICATE_TYPE {
+        return Err(AttestationError::Malformed(
+            "not an in-toto SLSA provenance statement".into(),
+        ));
+    }
+    let (algorithm, hex_digest) = split_digest(manifest_digest)?;
+    let subjects = statement["subject"].as_array().cloned().unwrap_or_default();
+    if !subjects
+        .iter()
+        .any(|subject| subject["digest"][algorithm] == hex_digest)
+    {
+        let found = subjects
+            .first()
+            .and_then(|subject| subject["digest"][algorithm].as_str())
+            .map(|found| format!("{algorithm}:{found}"))
+            .unwrap_or_default();
+        return Err(AttestationError::SubjectMismatch {
+            expected: manifest_digest.to_string(),
+            found,
+        });
+    }
+    Ok(statement)
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredAttestation {
+    pub manifest_digest: String,
+    pub build_artifact_run_id: Option<i32>,
+    pub key_id: String,
+    pub envelope: sqlx::types::Json<Envelope>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One attestation per digest; rebuilding the same digest replaces it.
+pub async fn store_attestation(
+    pool: &PgPool,
+    manifest_digest: &str,
+    build_artifact_run_id: Option<i32>,
+    key_id: &str,
+    envelope: &Envelope,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO build_artifact_attestations (
+            manifest_digest,
+            build_artifact_run_id,
+            key_id,
+            envelope
+        ) VALUES ($1, $2, $3, $4)
+        ON CONFLICT (manifest_digest) DO UPDATE
+        SET build_artifact_run_id = EXCLUDED.build_artifact_run_id,
+            key_id = EXCLUDED.key_id,
+            envelope = EXCLUDED.envelope,
+            created_at = NOW()
+        "#,
+    )
+    .bind(manifest_digest)
+    .bind(build_artifact_run_id)
+    .bind(key_id)
+    .bind(sqlx::types::Json(envelope))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn load_attestation<'e, E: PgExecutor<'e>>(
+    executor: E,
+    manifest_digest: &str,
+) -> Result<Option<StoredAttestation>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT manifest_digest, build_artifact_run_id, key_id, envelope, created_at
+        FROM build_artifact_attestations
+        WHERE manifest_digest = $1
+        "#,
+    )
+    .bind(manifest_digest)
+    .fetch_optional(executor)
+    .await
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttestationResponse {
+    pub manifest_digest: String,
+    pub build_artifact_run_id: Option<i32>,
+    pub key_id: String,
+    pub envelope: Envelope,
+    pub statement: Option<Value>,
+    pub verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// GET /api/artifacts/:id/attestation, where `:id` is the manifest digest.
+pub async fn get_attestation(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, role }: AuthUser,
+    Path(manifest_digest): Path<String>,
+) -> AppResult<Json<AttestationResponse>> {
+    if role != "admin" {
+        let owned: Option<i32> = sqlx::query_scalar(
+            r#"
+            SELECT runs.id
+            FROM build_artifact_runs runs
+            JOIN mcp_servers servers ON runs.server_id = servers.id
+            WHERE runs.manifest_digest = $1 AND servers.owner_id = $2
+            LIMIT 1
+            "#,
+        )
+        .bind(&manifest_digest)
+        .bind(user_id)
+        .fetch_optional(&pool)
+        .await?;
+        if owned.is_none() {
+            return Err(AppError::NotFound);
+        }
+    }
+    let Some(stored) = load_attestation(&pool, &manifest_digest).await? else {
+        return Err(AppError::NotFound);
+    };
+    let envelope = stored.envelope.0;
+    let (statement, verification_error) =
+        match verify_envelope(&envelope, &trusted_keys(), &manifest_digest) {
+            Ok(statement) => (Some(statement), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+    Ok(Json(AttestationResponse {
+        manifest_digest: stored.manifest_digest,
+        build_artifact_run_id: stored.build_artifact_run_id,
+        key_id: stored.key_id,
+        verified: verification_error.is_none(),
+        statement,
+        verification_error,
+        envelope,
+        created_at: stored.created_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn signer(seed: u8) -> AttestationSigner {
+        AttestationSigner::from_seed(&[seed; 32]).unwrap()
+    }
+
+    fn statement() -> Value {
+        provenance_statement(&ProvenanceInput {
+            image: "registry.local/mcp-custom-7:latest",
+            manifest_digest: DIGEST,
+            source_repo: "https://github.com/example/server.git",
+            source_branch: Some("main"),
+            source_revision: Some("3f786850e387550fdab836ed7e6dc881de23001b"),
+            builder_id: "mcp-host/build-worker",
+            started_at: Utc::now(),
+            completed_at: Utc::now(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn signed_statement_round_trips() {
+        let signer = signer(7);
+        let envelope = signer.sign(&statement());
+        assert_eq!(envelope.signatures[0].keyid, signer.key_id());
+
+        let verified = verify_envelope(&envelope, &[signer.public_key()], DIGEST).unwrap();
+        assert_eq!(verified["predicateType"], PREDICATE_TYPE);
+        assert_eq!(
+            verified["predicate"]["buildDefinition"]["resolvedDependencies"][0]["digest"]
+                ["gitCommit"],
+            "3f786850e387550fdab836ed7e6dc881de23001b"
+        );
+        assert_eq!(
+            verified["predicate"]["runDetails"]["builder"]["id"],
+            "mcp-host/build-worker"
+        );
+    }
+
+    #[test]
+    fn tampered_statement_is_rejected() {
+        let signer = signer(7);
+        let mut envelope = signer.sign(&statement());
+        let mut forged = statement();
+        forged["predicate"]["buildDefinition"]["externalParameters"]["source"]["repository"] =
+            json!("https://github.com/attacker/server.git");
+        envelope.payload = Base64Engine.encode(serde_json::to_vec(&forged).unwrap());
+
+        assert_eq!(
+            verify_envelope(&envelope, &[signer.public_key()], DIGEST),
+            Err(AttestationError::UntrustedSignature)
+        );
+    }
+
+    #[test]
+    fn untrusted_key_and_foreign_digest_are_rejected() {
+        let envelope = signer(7).sign(&statement());
+        assert_eq!(
+            verify_envelope(&envelope, &[signer(8).public_key()], DIGEST),
+            Err(AttestationError::UntrustedSignature)
+        );
+
+        let other = format!("sha256:{}", "0".repeat(64));
+        assert!(matches!(
+            verify_envelope(&envelope, &[signer(7).public_key()], &other),
+            Err(AttestationError::SubjectMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn malformed_digests_are_refused() {
+        for digest in [
+            "latest",
+            "sha256:abc",
+            "md5:9f86d081884c7d659a2feaa0c55ad015",
+        ] {
+            assert_eq!(
+                split_digest(digest),
+                Err(AttestationError::InvalidDigest(digest.to_string()))
+            );
+        }
+    }
+}
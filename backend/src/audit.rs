@@ -7,6 +7,8 @@ use uuid::Uuid;
 use crate::keys::events::{ProviderKeyAuditEvent, ProviderKeyAuditEventType};
 use crate::keys::models::ProviderKeyState;
 
+pub mod organization;
+
 /// key: audit-provider-key-filter
 /// Filter envelope applied to BYOK audit queries sourced by CLI and console workflows.
 #[derive(Clone, Debug, Default, Deserialize)]
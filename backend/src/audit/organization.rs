@@ -0,0 +1,304 @@
+use axum::{
+    extract::{Extension, Query},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool, QueryBuilder};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::organizations::{AdminRole, OrgAccess};
+
+// key: organization-audit-log -> unified per-org audit trail
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    Billing,
+    Keys,
+    Remediation,
+    Promotions,
+}
+
+impl AuditCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Billing => "billing",
+            Self::Keys => "keys",
+            Self::Remediation => "remediation",
+            Self::Promotions => "promotions",
+        }
+    }
+}
+
+/// How a writer names the organization an entry belongs to. Subsystems that only know a
+/// provider key or an artifact let the insert resolve the organization; when none is linked,
+/// nothing is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditScope {
+    Organization(i32),
+    /// The organization the provider key was registered for.
+    ProviderKey(Uuid),
+    /// The organization owning the server the artifact run was built for.
+    ArtifactRun(i32),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub scope: AuditScope,
+    pub category: AuditCategory,
+    pub action: &'static str,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    pub details: Value,
+}
+
+impl AuditRecord {
+    pub fn new(scope: AuditScope, category: AuditCategory, action: &'static str) -> Self {
+        Self {
+            scope,
+            category,
+            action,
+            actor: None,
+            target: None,
+            details: Value::Object(Default::default()),
+        }
+    }
+
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Actor reference for an authenticated user.
+    pub fn user(self, user_id: i32) -> Self {
+        self.actor(format!("user:{user_id}"))
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Writes `record` in the background. Callers never wait on the audit log and never fail
+/// because of it; a failed write is logged and dropped.
+pub fn record(pool: &PgPool, record: AuditRecord) {
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        if let Err(err) = write(&pool, &record).await {
+            tracing::warn!(
+                ?err,
+                category = record.category.as_str(),
+                action = record.action,
+                "failed to write organization audit entry"
+            );
+        }
+    });
+}
+
+async fn write(pool: &PgPool, record: &AuditRecord) -> sqlx::Result<u64> {
+    let mut builder = QueryBuilder::new(
+        "INSERT INTO organization_audit_log (organization_id, category, action, actor, target, details) SELECT ",
+    );
+    match record.scope {
+        AuditScope::Organization(organization_id) => builder.push_bind(organization_id),
+        AuditScope::ProviderKey(_) => builder.push("k.organization_id"),
+        AuditScope::ArtifactRun(_) => builder.push("s.organization_id"),
+    };
+    builder.push(", ");
+    builder.push_bind(record.category.as_str());
+    builder.push(", ");
+    builder.push_bind(record.action);
+    builder.push(", ");
+    builder.push_bind(record.actor.as_deref());
+    builder.push(", ");
+    builder.push_bind(record.target.as_deref());
+    builder.push(", ");
+    builder.push_bind(&record.details);
+    match record.scope {
+        AuditScope::Organization(_) => {}
+        AuditScope::ProviderKey(key_id) => {
+            builder.push(" FROM provider_keys k WHERE k.organization_id IS NOT NULL AND k.id = ");
+            builder.push_bind(key_id);
+        }
+        AuditScope::ArtifactRun(run_id) => {
+            builder.push(
+                " FROM build_artifact_runs r JOIN mcp_servers s ON s.id = r.server_id \
+                 WHERE s.organization_id IS NOT NULL AND r.id = ",
+            );
+            builder.push_bind(run_id);
+        }
+    }
+    builder
+        .build()
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected())
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct OrganizationAuditEntry {
+    pub id: i64,
+    pub organization_id: i32,
+    pub category: String,
+    pub action: String,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    pub details: Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditLogQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub category: Option<AuditCategory>,
+    /// `next_cursor` of the previous page.
+    #[serde(default)]
+    pub cursor: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLogPage {
+    /// Newest first.
+    pub entries: Vec<OrganizationAuditEntry>,
+    /// Present while older entries remain.
+    pub next_cursor: Option<i64>,
+}
+
+impl AuditLogPage {
+    /// Builds a page from up to `limit + 1` rows; the extra row only signals that more exist.
+    fn from_rows(mut entries: Vec<OrganizationAuditEntry>, limit: usize) -> Self {
+        let next_cursor = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|entry| entry.id)
+        } else {
+            None
+        };
+        Self {
+            entries,
+            next_cursor,
+        }
+    }
+}
+
+pub async fn list_entries(
+    pool: &PgPool,
+    organization_id: i32,
+    query: &AuditLogQuery,
+) -> sqlx::Result<AuditLogPage> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let mut builder = QueryBuilder::new(
+        "SELECT id, organization_id, category, action, actor, target, details, occurred_at \
+         FROM organization_audit_log WHERE organization_id = ",
+    );
+    builder.push_bind(organization_id);
+    if let Some(from) = query.from {
+        builder.push(" AND occurred_at >= ");
+        builder.push_bind(from);
+    }
+    if let Some(to) = query.to {
+        builder.push(" AND occurred_at <= ");
+        builder.push_bind(to);
+    }
+    if let Some(category) = query.category {
+        builder.push(" AND category = ");
+        builder.push_bind(category.as_str());
+    }
+    if let Some(cursor) = query.cursor {
+        builder.push(" AND id < ");
+        builder.push_bind(cursor);
+    }
+    builder.push(" ORDER BY id DESC LIMIT ");
+    builder.push_bind(limit + 1);
+    let rows = builder
+        .build_query_as::<OrganizationAuditEntry>()
+        .fetch_all(pool)
+        .await?;
+    Ok(AuditLogPage::from_rows(rows, limit as usize))
+}
+
+/// GET /api/organizations/:id/audit. Organization admins only.
+pub async fn list_organization_audit(
+    Extension(pool): Extension<PgPool>,
+    access: OrgAccess<AdminRole>,
+    Query(query): Query<AuditLogQuery>,
+) -> AppResult<Json<AuditLogPage>> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::BadRequest("`from` must not be after `to`".into()));
+        }
+    }
+    let page = list_entries(&pool, access.organization_id, &query)
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "DB error listing organization audit log");
+            AppError::Db(e)
+        })?;
+    Ok(Json(page))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(id: i64) -> OrganizationAuditEntry {
+        OrganizationAuditEntry {
+            id,
+            organization_id: 1,
+            category: "billing".into(),
+            action: "subscription_upserted".into(),
+            actor: Some("user:1".into()),
+            target: None,
+            details: json!({}),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn pages_point_at_the_oldest_entry_only_when_more_remain() {
+        let page = AuditLogPage::from_rows(vec![entry(9), entry(7), entry(4)], 2);
+        let ids: Vec<i64> = page.entries.iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, vec![9, 7]);
+        assert_eq!(page.next_cursor, Some(7));
+
+        let last = AuditLogPage::from_rows(vec![entry(4)], 2);
+        assert_eq!(last.entries.len(), 1);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn categories_use_their_query_names() {
+        for category in [
+            AuditCategory::Billing,
+            AuditCategory::Keys,
+            AuditCategory::Remediation,
+            AuditCategory::Promotions,
+        ] {
+            assert_eq!(
+                serde_json::to_value(category).unwrap(),
+                json!(category.as_str())
+            );
+        }
+    }
+}
@@ -8,12 +8,13 @@ use axum::{
     Json,
 };
 use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, EncodingKey, Header};
 use rand_core::OsRng;
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use tracing::error;
 
+pub mod jwt;
+
 #[derive(Deserialize)]
 pub struct RegisterRequest {
     pub email: String,
@@ -106,13 +107,7 @@ pub async fn login_user(
         role: role.clone(),
         exp,
     };
-    let secret = crate::config::JWT_SECRET.as_str();
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(|e| {
+    let token = crate::config::JWT_KEYS.sign(&claims).map_err(|e| {
         error!(?e, "Token encoding error");
         AppError::Message("Token error".into())
     })?;
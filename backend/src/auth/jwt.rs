@@ -0,0 +1,386 @@
+use jsonwebtoken::errors::{Error as JwtError, ErrorKind};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+// key: auth-jwt-keys -> signing key plus rotated verification keys
+
+/// Algorithms accepted for session tokens.
+pub fn parse_algorithm(value: &str) -> Result<Algorithm, String> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "HS256" => Ok(Algorithm::HS256),
+        "RS256" => Ok(Algorithm::RS256),
+        "ES256" => Ok(Algorithm::ES256),
+        other => Err(format!(
+            "unsupported JWT algorithm `{other}`; expected HS256, RS256 or ES256"
+        )),
+    }
+}
+
+/// Key that issues new tokens.
+#[derive(Clone)]
+pub struct SigningKey {
+    algorithm: Algorithm,
+    key: EncodingKey,
+}
+
+impl SigningKey {
+    pub fn hmac(secret: &[u8]) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            key: EncodingKey::from_secret(secret),
+        }
+    }
+
+    /// `pem` is a PKCS#8 private key for RS256 or ES256.
+    pub fn from_pem(algorithm: Algorithm, pem: &[u8]) -> Result<Self, String> {
+        let key = match algorithm {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(pem),
+            Algorithm::ES256 => EncodingKey::from_ec_pem(pem),
+            other => return Err(format!("{other:?} does not use a PEM signing key")),
+        }
+        .map_err(|err| format!("invalid {algorithm:?} private key: {err}"))?;
+        Ok(Self { algorithm, key })
+    }
+}
+
+/// Key that tokens are checked against.
+#[derive(Clone)]
+pub struct VerificationKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+impl VerificationKey {
+    pub fn hmac(secret: &[u8]) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            key: DecodingKey::from_secret(secret),
+        }
+    }
+
+    /// `pem` is a public key for RS256 or ES256.
+    pub fn from_pem(algorithm: Algorithm, pem: &[u8]) -> Result<Self, String> {
+        let key = match algorithm {
+            Algorithm::RS256 => DecodingKey::from_rsa_pem(pem),
+            Algorithm::ES256 => DecodingKey::from_ec_pem(pem),
+            other => return Err(format!("{other:?} does not use a PEM verification key")),
+        }
+        .map_err(|err| format!("invalid {algorithm:?} public key: {err}"))?;
+        Ok(Self { algorithm, key })
+    }
+
+    /// Parses `ALG:value`, where the value is the secret for HS256 and the path of a PEM
+    /// public key for RS256 and ES256.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (algorithm, value) = spec
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| "expected `ALG:value`".to_string())?;
+        let algorithm = parse_algorithm(algorithm)?;
+        if value.is_empty() {
+            return Err(format!("empty {algorithm:?} key"));
+        }
+        match algorithm {
+            Algorithm::HS256 => Ok(Self::hmac(value.as_bytes())),
+            _ => {
+                let pem =
+                    std::fs::read(value).map_err(|err| format!("failed to read {value}: {err}"))?;
+                Self::from_pem(algorithm, &pem)
+            }
+        }
+    }
+}
+
+/// The signing key and every key a presented token may be signed with. Previous keys only
+/// verify; dropping one from the set ends its overlap window and invalidates the tokens it
+/// signed. An expected audience or issuer, when set, is stamped on issued tokens and
+/// required on presented ones; unset, either claim is accepted as-is.
+#[derive(Clone)]
+pub struct JwtKeys {
+    signing: SigningKey,
+    current: VerificationKey,
+    previous: Vec<VerificationKey>,
+    audience: Option<String>,
+    issuer: Option<String>,
+}
+
+/// Why a presented token was refused. Audience and issuer mismatches are kept apart so
+/// a token minted for another service is distinguishable from a forged or stale one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenRejection {
+    Audience,
+    Issuer,
+    Invalid,
+}
+
+impl TokenRejection {
+    pub fn from_error(err: &JwtError) -> Self {
+        match err.kind() {
+            ErrorKind::InvalidAudience => Self::Audience,
+            ErrorKind::InvalidIssuer => Self::Issuer,
+            ErrorKind::MissingRequiredClaim(claim) if claim == "aud" => Self::Audience,
+            ErrorKind::MissingRequiredClaim(claim) if claim == "iss" => Self::Issuer,
+            _ => Self::Invalid,
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::Audience => "Invalid token audience",
+            Self::Issuer => "Invalid token issuer",
+            Self::Invalid => "Invalid token",
+        }
+    }
+}
+
+impl JwtKeys {
+    pub fn new(
+        signing: SigningKey,
+        current: VerificationKey,
+        previous: Vec<VerificationKey>,
+    ) -> Self {
+        Self {
+            signing,
+            current,
+            previous,
+            audience: None,
+            issuer: None,
+        }
+    }
+
+    pub fn hmac(secret: &str) -> Self {
+        Self::new(
+            SigningKey::hmac(secret.as_bytes()),
+            VerificationKey::hmac(secret.as_bytes()),
+            Vec::new(),
+        )
+    }
+
+    pub fn with_previous(mut self, previous: Vec<VerificationKey>) -> Self {
+        self.previous = previous;
+        self
+    }
+
+    pub fn with_audience(mut self, audience: Option<String>) -> Self {
+        self.audience = audience;
+        self
+    }
+
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.signing.algorithm
+    }
+
+    /// Claims that already carry `aud` or `iss` keep them.
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, JwtError> {
+        let mut claims = serde_json::to_value(claims).map_err(JwtError::from)?;
+        if let Value::Object(map) = &mut claims {
+            if let Some(audience) = &self.audience {
+                map.entry("aud")
+                    .or_insert_with(|| Value::String(audience.clone()));
+            }
+            if let Some(issuer) = &self.issuer {
+                map.entry("iss")
+                    .or_insert_with(|| Value::String(issuer.clone()));
+            }
+        }
+        encode(
+            &Header::new(self.signing.algorithm),
+            &claims,
+            &self.signing.key,
+        )
+    }
+
+    fn validation(&self, algorithm: Algorithm) -> Validation {
+        let mut validation = Validation::new(algorithm);
+        if let Some(audience) = &self.audience {
+            validation.set_audience(&[audience]);
+            validation.required_spec_claims.insert("aud".to_string());
+        }
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+            validation.required_spec_claims.insert("iss".to_string());
+        }
+        validation
+    }
+
+    /// Tries the current key, then each previous key, that uses the token's algorithm. A
+    /// key whose signature matches decides the outcome, so an expired token is reported
+    /// as expired rather than as unverifiable.
+    pub fn verify<T: DeserializeOwned>(&self, token: &str) -> Result<T, JwtError> {
+        let algorithm = decode_header(token)?.alg;
+        let validation = self.validation(algorithm);
+        let mut last_error = JwtError::from(ErrorKind::InvalidAlgorithm);
+        for key in std::iter::once(&self.current).chain(&self.previous) {
+            if key.algorithm != algorithm {
+                continue;
+            }
+            match decode::<T>(token, &key.key, &validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(err) if matches!(err.kind(), ErrorKind::InvalidSignature) => last_error = err,
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use serde::Deserialize;
+
+    const RS256_PRIVATE: &[u8] = include_bytes!("../../tests/fixtures/jwt/rs256_private.pem");
+    const RS256_PUBLIC: &[u8] = include_bytes!("../../tests/fixtures/jwt/rs256_public.pem");
+    const ES256_PRIVATE: &[u8] = include_bytes!("../../tests/fixtures/jwt/es256_private.pem");
+    const ES256_PUBLIC: &[u8] = include_bytes!("../../tests/fixtures/jwt/es256_public.pem");
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Claims {
+        sub: i32,
+        role: String,
+        exp: i64,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            sub: 7,
+            role: "user".into(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp(),
+        }
+    }
+
+    #[test]
+    fn previous_key_validates_during_overlap_and_fails_after_removal() {
+        let expected = claims();
+        let old = JwtKeys::hmac("old-secret");
+        let token = old.sign(&expected).unwrap();
+
+        let rotated =
+            JwtKeys::hmac("new-secret").with_previous(vec![VerificationKey::hmac(b"old-secret")]);
+        assert_eq!(rotated.verify::<Claims>(&token).unwrap(), expected);
+        let fresh = rotated.sign(&claims()).unwrap();
+        assert!(rotated.verify::<Claims>(&fresh).is_ok());
+
+        let overlap_over = JwtKeys::hmac("new-secret");
+        let err = overlap_over.verify::<Claims>(&token).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidSignature));
+        assert!(overlap_over.verify::<Claims>(&fresh).is_ok());
+    }
+
+    #[test]
+    fn asymmetric_keys_sign_and_verify() {
+        for (algorithm, private, public) in [
+            (Algorithm::RS256, RS256_PRIVATE, RS256_PUBLIC),
+            (Algorithm::ES256, ES256_PRIVATE, ES256_PUBLIC),
+        ] {
+            let keys = JwtKeys::new(
+                SigningKey::from_pem(algorithm, private).unwrap(),
+                VerificationKey::from_pem(algorithm, public).unwrap(),
+                Vec::new(),
+            );
+            let expected = claims();
+            let token = keys.sign(&expected).unwrap();
+            assert_eq!(decode_header(&token).unwrap().alg, algorithm);
+            assert_eq!(keys.verify::<Claims>(&token).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn hmac_tokens_survive_a_switch_to_asymmetric_keys() {
+        let token = JwtKeys::hmac("legacy-secret").sign(&claims()).unwrap();
+        let keys = JwtKeys::new(
+            SigningKey::from_pem(Algorithm::ES256, ES256_PRIVATE).unwrap(),
+            VerificationKey::from_pem(Algorithm::ES256, ES256_PUBLIC).unwrap(),
+            vec![VerificationKey::parse("HS256:legacy-secret").unwrap()],
+        );
+        assert!(keys.verify::<Claims>(&token).is_ok());
+
+        let without_legacy = keys.with_previous(Vec::new());
+        let err = without_legacy.verify::<Claims>(&token).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidAlgorithm));
+    }
+
+    #[test]
+    fn expired_token_reports_expiry_not_signature() {
+        let keys = JwtKeys::hmac("new-secret").with_previous(vec![VerificationKey::hmac(b"old")]);
+        let expired = Claims {
+            exp: (Utc::now() - Duration::hours(1)).timestamp(),
+            ..claims()
+        };
+        let token = keys.sign(&expired).unwrap();
+        let err = keys.verify::<Claims>(&token).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::ExpiredSignature));
+    }
+
+    fn scoped(keys: JwtKeys) -> JwtKeys {
+        keys.with_audience(Some("mcp-host".into()))
+            .with_issuer(Some("https://auth.example.com".into()))
+    }
+
+    #[test]
+    fn matching_audience_and_issuer_are_accepted() {
+        let keys = scoped(JwtKeys::hmac("secret"));
+        let token = keys.sign(&claims()).unwrap();
+        let stamped: Value = keys.verify(&token).unwrap();
+        assert_eq!(stamped["aud"], "mcp-host");
+        assert_eq!(stamped["iss"], "https://auth.example.com");
+        assert!(keys.verify::<Claims>(&token).is_ok());
+    }
+
+    #[test]
+    fn mismatched_audience_is_rejected() {
+        let keys = scoped(JwtKeys::hmac("secret"));
+        let foreign = JwtKeys::hmac("secret")
+            .with_audience(Some("billing-service".into()))
+            .with_issuer(Some("https://auth.example.com".into()));
+        let token = foreign.sign(&claims()).unwrap();
+        let err = keys.verify::<Claims>(&token).unwrap_err();
+        assert_eq!(TokenRejection::from_error(&err), TokenRejection::Audience);
+
+        // a token without the claim does not slip past a configured audience
+        let bare = JwtKeys::hmac("secret").sign(&claims()).unwrap();
+        let err = keys.verify::<Claims>(&bare).unwrap_err();
+        assert_eq!(TokenRejection::from_error(&err), TokenRejection::Audience);
+    }
+
+    #[test]
+    fn mismatched_issuer_is_rejected() {
+        let keys = scoped(JwtKeys::hmac("secret"));
+        let foreign = JwtKeys::hmac("secret")
+            .with_audience(Some("mcp-host".into()))
+            .with_issuer(Some("https://other.example.com".into()));
+        let token = foreign.sign(&claims()).unwrap();
+        let err = keys.verify::<Claims>(&token).unwrap_err();
+        assert_eq!(TokenRejection::from_error(&err), TokenRejection::Issuer);
+        assert_eq!(TokenRejection::Issuer.message(), "Invalid token issuer");
+    }
+
+    #[test]
+    fn unconfigured_audience_and_issuer_accept_any_token() {
+        let keys = JwtKeys::hmac("secret");
+        let plain = keys.sign(&claims()).unwrap();
+        let stamped: Value = keys.verify(&plain).unwrap();
+        assert!(stamped.get("aud").is_none());
+        let scoped_token = scoped(JwtKeys::hmac("secret")).sign(&claims()).unwrap();
+        assert!(keys.verify::<Claims>(&plain).is_ok());
+        assert!(keys.verify::<Claims>(&scoped_token).is_ok());
+    }
+
+    #[test]
+    fn key_specs_are_validated() {
+        assert!(VerificationKey::parse("HS256:secret").is_ok());
+        assert!(VerificationKey::parse("secret").is_err());
+        assert!(VerificationKey::parse("HS512:secret").is_err());
+        assert!(VerificationKey::parse("RS256:/nonexistent/key.pem").is_err());
+    }
+}
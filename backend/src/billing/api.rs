@@ -1,13 +1,14 @@
-use axum::{
-    extract::{Extension, Path},
-    http::StatusCode,
-    Json,
-};
+use axum::{extract::Extension, http::StatusCode, Json};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::audit::organization::{self, AuditCategory, AuditRecord, AuditScope};
+use crate::organizations::{AdminRole, OrgAccess, OrgRole, ViewerRole};
+
+use super::export::{render_usage_csv, UsageExportFormat};
 use super::{
     BillingPlan, BillingPlanCatalogEntry, BillingQuotaOutcome, BillingService,
     OrganizationSubscription,
@@ -40,8 +41,9 @@ pub async fn list_plan_catalog(
 
 pub async fn get_subscription(
     Extension(pool): Extension<PgPool>,
-    Path(organization_id): Path<i32>,
+    access: OrgAccess<ViewerRole>,
 ) -> Result<Json<Option<SubscriptionEnvelope>>, StatusCode> {
+    let organization_id = access.organization_id;
     let service = BillingService::new(pool.clone());
     let subscription = service
         .active_subscription(organization_id, Utc::now())
@@ -55,9 +57,10 @@ pub async fn get_subscription(
 
 pub async fn upsert_subscription(
     Extension(pool): Extension<PgPool>,
-    Path(organization_id): Path<i32>,
+    access: OrgAccess<AdminRole>,
     Json(payload): Json<UpsertSubscriptionRequest>,
 ) -> Result<Json<SubscriptionEnvelope>, StatusCode> {
+    let organization_id = access.organization_id;
     let service = BillingService::new(pool.clone());
     let status = payload.status.unwrap_or_else(|| "active".to_string());
     let record = service
@@ -69,6 +72,17 @@ pub async fn upsert_subscription(
         )
         .await
         .map_err(|_| StatusCode::NOT_IMPLEMENTED)?;
+    organization::record(
+        &pool,
+        AuditRecord::new(
+            AuditScope::Organization(organization_id),
+            AuditCategory::Billing,
+            "subscription_upserted",
+        )
+        .user(access.user_id)
+        .target(format!("subscription:{}", record.id))
+        .details(json!({ "plan_id": record.plan_id, "status": record.status })),
+    );
 
     let plan = sqlx::query_as::<_, BillingPlan>("SELECT * FROM billing_plans WHERE id = $1")
         .bind(payload.plan_id)
@@ -84,12 +98,17 @@ pub async fn upsert_subscription(
 
 pub async fn check_quota(
     Extension(pool): Extension<PgPool>,
-    Path(organization_id): Path<i32>,
+    access: OrgAccess<ViewerRole>,
     Json(payload): Json<QuotaCheckRequest>,
 ) -> Result<Json<QuotaCheckResponse>, StatusCode> {
+    let organization_id = access.organization_id;
     let service = BillingService::new(pool);
     let requested = payload.requested_quantity.unwrap_or(0);
     let record_usage = payload.record_usage.unwrap_or(false);
+    // Recording usage mutates the ledger, so it needs the same role as other billing writes.
+    if record_usage && !access.role.satisfies(OrgRole::Admin) {
+        return Err(StatusCode::FORBIDDEN);
+    }
     let outcome = service
         .enforce_quota(
             organization_id,
@@ -104,6 +123,44 @@ pub async fn check_quota(
     Ok(Json(QuotaCheckResponse { outcome, recorded }))
 }
 
+pub async fn export_usage(
+    Extension(pool): Extension<PgPool>,
+    access: OrgAccess<ViewerRole>,
+    Query(query): Query<UsageExportQuery>,
+) -> Result<Response, StatusCode> {
+    let organization_id = access.organization_id;
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or_else(|| to - Duration::days(30));
+    if from >= to {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let service = BillingService::new(pool);
+    let lines = service
+        .usage_export(organization_id, from, to)
+        .await
+        .map_err(|_| StatusCode::NOT_IMPLEMENTED)?;
+
+    match query.format {
+        UsageExportFormat::Json => Ok(Json(lines).into_response()),
+        UsageExportFormat::Csv => {
+            let filename = format!(
+                "attachment; filename=\"org-{organization_id}-usage-{}-{}.csv\"",
+                from.format("%Y%m%d"),
+                to.format("%Y%m%d")
+            );
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, filename),
+                ],
+                render_usage_csv(&lines),
+            )
+                .into_response())
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct SubscriptionEnvelope {
     pub subscription: OrganizationSubscription,
@@ -132,3 +189,13 @@ pub struct QuotaCheckResponse {
     pub outcome: BillingQuotaOutcome,
     pub recorded: bool,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UsageExportQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub format: UsageExportFormat,
+}
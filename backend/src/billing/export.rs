@@ -0,0 +1,93 @@
+use serde::Deserialize;
+
+use super::models::UsageExportLine;
+
+/// key: billing-usage-export -> report encodings
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+const CSV_HEADER: [&str; 5] = [
+    "entitlement_key",
+    "window_count",
+    "total_quantity",
+    "first_window_start",
+    "last_window_end",
+];
+
+/// Render line items as RFC 4180 CSV with a header row; an empty report is
+/// just the header.
+pub fn render_usage_csv(lines: &[UsageExportLine]) -> String {
+    let mut out = String::new();
+    push_csv_row(&mut out, CSV_HEADER.iter().map(|field| field.to_string()));
+    for line in lines {
+        push_csv_row(
+            &mut out,
+            [
+                line.entitlement_key.clone(),
+                line.window_count.to_string(),
+                line.total_quantity.to_string(),
+                line.first_window_start.to_rfc3339(),
+                line.last_window_end.to_rfc3339(),
+            ],
+        );
+    }
+    out
+}
+
+fn push_csv_row(out: &mut String, fields: impl IntoIterator<Item = String>) {
+    for (idx, field) in fields.into_iter().enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        out.push_str(&quote_csv_field(&field));
+    }
+    out.push_str("\r\n");
+}
+
+fn quote_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn csv_quotes_fields_with_delimiters() {
+        let line = UsageExportLine {
+            entitlement_key: "runtime,\"gpu\"".into(),
+            window_count: 2,
+            total_quantity: 7,
+            first_window_start: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            last_window_end: Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+        };
+        let csv = render_usage_csv(&[line]);
+        let rows: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(
+            rows[0],
+            "entitlement_key,window_count,total_quantity,first_window_start,last_window_end"
+        );
+        assert_eq!(
+            rows[1],
+            "\"runtime,\"\"gpu\"\"\",2,7,2024-01-01T00:00:00+00:00,2024-02-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn csv_for_empty_report_is_header_only() {
+        assert_eq!(
+            render_usage_csv(&[]),
+            "entitlement_key,window_count,total_quantity,first_window_start,last_window_end\r\n"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 pub mod adapters;
 pub mod api;
+pub mod export;
 pub mod models;
 pub mod reconciliation;
 pub mod scheduler;
@@ -7,14 +8,16 @@ pub mod service;
 
 pub use adapters::{BillingProviderAdapter, StripeLikeAdapter, UsageReconciliationRecord};
 pub use api::{
-    check_quota as billing_check_quota, get_subscription as billing_get_subscription,
-    list_plan_catalog as billing_list_plan_catalog, list_plans as billing_list_plans,
-    upsert_subscription as billing_upsert_subscription, QuotaCheckRequest, QuotaCheckResponse,
-    SubscriptionEnvelope, UpsertSubscriptionRequest,
+    check_quota as billing_check_quota, export_usage as billing_export_usage,
+    get_subscription as billing_get_subscription, list_plan_catalog as billing_list_plan_catalog,
+    list_plans as billing_list_plans, upsert_subscription as billing_upsert_subscription,
+    QuotaCheckRequest, QuotaCheckResponse, SubscriptionEnvelope, UpsertSubscriptionRequest,
+    UsageExportQuery,
 };
+pub use export::{render_usage_csv, UsageExportFormat};
 pub use models::{
     BillingPlan, BillingPlanCatalogEntry, BillingQuotaOutcome, OrganizationSubscription,
-    PlanEntitlement, SubscriptionUsageWindow,
+    PlanEntitlement, SubscriptionUsageWindow, UsageExportLine,
 };
 pub use reconciliation::{start_reconciliation_worker, ReconciliationHandle, ReconciliationJob};
 pub use scheduler::{
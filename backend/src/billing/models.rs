@@ -86,3 +86,13 @@ pub struct BillingPlanCatalogEntry {
     pub plan: BillingPlan,
     pub entitlements: Vec<PlanEntitlement>,
 }
+
+/// key: billing-usage-export -> per-metric reconciliation line item
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, PartialEq)]
+pub struct UsageExportLine {
+    pub entitlement_key: String,
+    pub window_count: i64,
+    pub total_quantity: i64,
+    pub first_window_start: DateTime<Utc>,
+    pub last_window_end: DateTime<Utc>,
+}
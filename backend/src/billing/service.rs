@@ -4,10 +4,12 @@ use sqlx::{PgPool, Row};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::telemetry::tenant::TENANT_METRICS;
+
 use super::adapters::UsageReconciliationRecord;
 use super::models::{
     BillingPlan, BillingPlanCatalogEntry, BillingQuotaOutcome, OrganizationSubscription,
-    PlanEntitlement, SubscriptionUsageWindow,
+    PlanEntitlement, SubscriptionUsageWindow, UsageExportLine,
 };
 
 /// key: billing-service -> subscription lifecycle
@@ -193,6 +195,7 @@ impl BillingService {
         let now = Utc::now();
         let Some((subscription, _plan)) = self.active_subscription(organization_id, now).await?
         else {
+            TENANT_METRICS.quota_denied(organization_id);
             return Ok(BillingQuotaOutcome {
                 allowed: false,
                 entitlement_key: entitlement_key.to_string(),
@@ -235,6 +238,7 @@ impl BillingService {
                 allowed = false;
                 remaining = Some(limit.saturating_sub(current_used));
                 notes.push(format!("billing:quota-exceeded:{entitlement_key}"));
+                TENANT_METRICS.quota_denied(organization_id);
             } else {
                 remaining = Some(limit - future_used);
                 notes.push(format!(
@@ -336,6 +340,41 @@ impl BillingService {
         .await
     }
 
+    /// Aggregate ledger windows starting inside `[from, to)` into per-entitlement
+    /// totals. Provider usage settled by reconciliation lands in the same ledger,
+    /// so the export covers both locally recorded and reconciled usage.
+    pub async fn usage_export(
+        &self,
+        organization_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<UsageExportLine>> {
+        let lines = sqlx::query_as::<_, UsageExportLine>(
+            r#"
+            SELECT
+                l.entitlement_key,
+                COUNT(*) AS window_count,
+                COALESCE(SUM(l.used_quantity), 0)::BIGINT AS total_quantity,
+                MIN(l.window_start) AS first_window_start,
+                MAX(l.window_end) AS last_window_end
+            FROM subscription_usage_ledger l
+            JOIN organization_subscriptions s ON s.id = l.subscription_id
+            WHERE s.organization_id = $1
+              AND l.window_start >= $2
+              AND l.window_start < $3
+            GROUP BY l.entitlement_key
+            ORDER BY l.entitlement_key ASC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lines)
+    }
+
     pub async fn mark_subscription_overdue(
         &self,
         organization_id: i32,
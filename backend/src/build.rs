@@ -1,8 +1,15 @@
+use crate::artifacts::attestation::{
+    provenance_statement, store_attestation, AttestationSigner, ProvenanceInput,
+};
 use crate::artifacts::{
     record_build_artifacts, ArtifactPersistenceRequest, ArtifactPlatformRecord,
 };
-use crate::config::{REGISTRY_ARCH_TARGETS, REGISTRY_AUTH_DOCKERCONFIG};
+use crate::config::validation::{flag, setting};
+use crate::config::{
+    BUILD_ATTESTATION_BUILDER_ID, REGISTRY_ARCH_TARGETS, REGISTRY_AUTH_DOCKERCONFIG,
+};
 use crate::servers::{add_metric, set_status, SetStatusError};
+use crate::telemetry::tenant::{server_org, TENANT_METRICS};
 use crate::telemetry::MetricError;
 use async_trait::async_trait;
 use base64::engine::general_purpose::STANDARD as Base64Engine;
@@ -15,6 +22,7 @@ use bollard::query_parameters::{
 use bollard::Docker;
 use bytes::Bytes;
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use coalesce::{BuildAdmission, BuildCoalescer};
 use futures_util::{stream, StreamExt, TryStreamExt};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -38,6 +46,9 @@ use tokio::fs;
 use tokio::time::{sleep, Duration as TokioDuration};
 use url::Url;
 
+pub mod coalesce;
+pub mod gc;
+
 #[derive(Clone, Copy)]
 enum LangBuilder {
     Node,
@@ -106,11 +117,7 @@ struct BuildCacheConfig {
 impl BuildCacheConfig {
     // key: build-cache-config -> REGISTRY_BUILD_DISABLE_CACHE,REGISTRY_BUILD_CACHE_FROM
     fn from_env() -> Self {
-        let disable_cache = std::env::var("REGISTRY_BUILD_DISABLE_CACHE")
-            .ok()
-            .map(|value| value.trim().to_ascii_lowercase())
-            .map(|value| matches!(value.as_str(), "1" | "true" | "yes" | "on"))
-            .unwrap_or(false);
+        let disable_cache = flag("REGISTRY_BUILD_DISABLE_CACHE");
         let cache_from = std::env::var("REGISTRY_BUILD_CACHE_FROM")
             .ok()
             .map(|value| {
@@ -262,10 +269,7 @@ fn desired_platform_targets() -> Vec<PlatformTarget> {
 }
 
 fn registry_build_parallelism() -> Option<usize> {
-    std::env::var("REGISTRY_BUILD_PARALLELISM")
-        .ok()
-        .and_then(|value| value.trim().parse::<usize>().ok())
-        .filter(|value| *value > 0)
+    setting("REGISTRY_BUILD_PARALLELISM")
 }
 
 fn compute_build_parallelism(target_count: usize) -> usize {
@@ -754,11 +758,7 @@ async fn prune_stale_manifest_entries<L: BuildLogSink + ?Sized, M: MetricRecorde
 const DEFAULT_REGISTRY_PUSH_RETRIES: usize = 3;
 
 fn registry_push_retry_limit() -> usize {
-    std::env::var("REGISTRY_PUSH_RETRIES")
-        .ok()
-        .and_then(|raw| raw.parse::<usize>().ok())
-        .filter(|&value| value > 0)
-        .unwrap_or(DEFAULT_REGISTRY_PUSH_RETRIES)
+    setting("REGISTRY_PUSH_RETRIES").unwrap_or(DEFAULT_REGISTRY_PUSH_RETRIES)
 }
 
 static MANIFEST_HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
@@ -881,19 +881,15 @@ fn registry_auth_host(registry: &str) -> Option<String> {
 }
 
 fn credential_max_age() -> std::time::Duration {
-    std::env::var("REGISTRY_AUTH_MAX_AGE_SECONDS")
-        .ok()
-        .and_then(|raw| raw.parse::<u64>().ok())
-        .map(std::time::Duration::from_secs)
-        .unwrap_or_else(|| std::time::Duration::from_secs(DEFAULT_CREDENTIAL_MAX_AGE_SECS))
+    std::time::Duration::from_secs(
+        setting("REGISTRY_AUTH_MAX_AGE_SECONDS").unwrap_or(DEFAULT_CREDENTIAL_MAX_AGE_SECS),
+    )
 }
 
 fn credential_rotation_lead_time() -> std::time::Duration {
-    std::env::var("REGISTRY_AUTH_ROTATE_LEAD_SECONDS")
-        .ok()
-        .and_then(|raw| raw.parse::<u64>().ok())
-        .map(std::time::Duration::from_secs)
-        .unwrap_or_else(|| std::time::Duration::from_secs(DEFAULT_CREDENTIAL_ROTATE_LEAD_SECS))
+    std::time::Duration::from_secs(
+        setting("REGISTRY_AUTH_ROTATE_LEAD_SECONDS").unwrap_or(DEFAULT_CREDENTIAL_ROTATE_LEAD_SECS),
+    )
 }
 
 fn chrono_from_std(duration: std::time::Duration) -> ChronoDuration {
@@ -2023,6 +2019,7 @@ pub struct BuildPlatformArtifact {
     pub credential_health_status: CredentialHealthStatus,
 }
 
+#[derive(Clone)]
 pub struct BuildArtifacts {
     pub local_image: String,
     pub registry_image: Option<String>,
@@ -2042,13 +2039,119 @@ pub struct BuildArtifacts {
     pub credential_health_status: CredentialHealthStatus,
 }
 
+/// Sign and store a provenance attestation for the pushed manifest. Skipped when no
+/// signing key is configured or the image never reached a registry; failures are
+/// logged and do not fail the build.
+async fn attest_build(
+    pool: &PgPool,
+    server_id: i32,
+    artifact_run_id: Option<i32>,
+    artifacts: &BuildArtifacts,
+) {
+    let Some(manifest_digest) = artifacts.manifest_digest.as_deref() else {
+        return;
+    };
+    let signer = match AttestationSigner::from_config() {
+        Ok(Some(signer)) => signer,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::error!(%err, %server_id, "build attestation signing key unusable");
+            insert_log(pool, server_id, &format!("Attestation skipped: {err}")).await;
+            return;
+        }
+    };
+    let input = ProvenanceInput {
+        image: artifacts
+            .registry_image
+            .as_deref()
+            .unwrap_or(&artifacts.local_image),
+        manifest_digest,
+        source_repo: &artifacts.source_repo,
+        source_branch: artifacts.source_branch.as_deref(),
+        source_revision: artifacts.source_revision.as_deref(),
+        builder_id: &BUILD_ATTESTATION_BUILDER_ID,
+        started_at: artifacts.started_at,
+        completed_at: artifacts.completed_at,
+    };
+    let envelope = match provenance_statement(&input) {
+        Ok(statement) => signer.sign(&statement),
+        Err(err) => {
+            insert_log(pool, server_id, &format!("Attestation skipped: {err}")).await;
+            return;
+        }
+    };
+    match store_attestation(
+        pool,
+        manifest_digest,
+        artifact_run_id,
+        signer.key_id(),
+        &envelope,
+    )
+    .await
+    {
+        Ok(()) => insert_log(pool, server_id, "Provenance attestation signed").await,
+        Err(err) => {
+            tracing::error!(?err, %server_id, "failed to store build attestation");
+            insert_log(pool, server_id, "Failed to store provenance attestation").await;
+        }
+    }
+}
+
+pub type BuildOutcome = Result<Option<BuildArtifacts>, Arc<SetStatusError>>;
+
+static BUILDS: Lazy<BuildCoalescer<(String, Option<String>), BuildOutcome>> =
+    Lazy::new(BuildCoalescer::default);
+
 /// Clone a git repository and build a Docker image.
-/// Returns the build artifacts on success.
+/// Returns the build artifacts on success. A request for a server whose build of the
+/// same repository and branch is already running shares that build's result instead
+/// of starting another.
 pub async fn build_from_git(
     pool: &PgPool,
     server_id: i32,
     repo_url: &str,
     branch: Option<&str>,
+) -> BuildOutcome {
+    let key = (repo_url.to_string(), branch.map(str::to_string));
+    let (outcome, admission) = BUILDS
+        .run(server_id, key, || {
+            let pool = pool.clone();
+            let repo_url = repo_url.to_string();
+            let branch = branch.map(str::to_string);
+            async move {
+                let started = std::time::Instant::now();
+                let outcome = run_build_from_git(&pool, server_id, &repo_url, branch.as_deref())
+                    .await
+                    .map_err(Arc::new);
+                TENANT_METRICS.build_duration(
+                    server_org(&pool, server_id).await,
+                    started.elapsed().as_secs_f64(),
+                );
+                outcome
+            }
+        })
+        .await;
+    if admission == BuildAdmission::Coalesced {
+        tracing::info!(
+            target: "build.metrics",
+            event_type = "build_coalesced",
+            %server_id,
+            "build request attached to in-flight build"
+        );
+        let details = json!({ "repo": repo_url, "branch": branch });
+        if let Err(err) = add_metric(pool, server_id, "build_coalesced", Some(&details)).await {
+            tracing::warn!(?err, %server_id, "failed to record build_coalesced metric");
+        }
+    }
+    outcome
+}
+
+#[tracing::instrument(name = "build.from_git", skip(pool, repo_url, branch))]
+async fn run_build_from_git(
+    pool: &PgPool,
+    server_id: i32,
+    repo_url: &str,
+    branch: Option<&str>,
 ) -> Result<Option<BuildArtifacts>, SetStatusError> {
     insert_log(pool, server_id, "Cloning repository").await;
     let build_started_at = Utc::now();
@@ -2502,15 +2605,20 @@ pub async fn build_from_git(
             .collect(),
     };
 
-    if let Err(err) = record_build_artifacts(pool, persistence_request).await {
-        tracing::error!(?err, %server_id, "failed to persist build artifacts");
-        insert_log(
-            pool,
-            server_id,
-            "Failed to persist build artifact metadata; consult server logs",
-        )
-        .await;
-    }
+    let artifact_run_id = match record_build_artifacts(pool, persistence_request).await {
+        Ok(run_id) => Some(run_id),
+        Err(err) => {
+            tracing::error!(?err, %server_id, "failed to persist build artifacts");
+            insert_log(
+                pool,
+                server_id,
+                "Failed to persist build artifact metadata; consult server logs",
+            )
+            .await;
+            None
+        }
+    };
+    attest_build(pool, server_id, artifact_run_id, &artifacts).await;
 
     insert_log(pool, server_id, "Cleaning up").await;
     Ok(Some(artifacts))
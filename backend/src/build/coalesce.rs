@@ -0,0 +1,163 @@
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+// key: build-coalesce -> one in-flight build per server
+
+struct InFlight<K, T> {
+    key: K,
+    generation: u64,
+    build: Shared<BoxFuture<'static, T>>,
+}
+
+struct Slots<K, T> {
+    next_generation: u64,
+    in_flight: HashMap<i32, InFlight<K, T>>,
+}
+
+/// How a request was served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildAdmission {
+    /// Ran a build of its own.
+    Started,
+    /// Attached to an identical build already in flight and shares its result.
+    Coalesced,
+}
+
+/// Runs at most one build per server at a time. A request whose `key` (the build
+/// inputs) matches the in-flight build waits for that build's result; a request with
+/// different inputs is queued behind it. Once a build finishes its slot is cleared, so
+/// the next request always builds fresh.
+pub struct BuildCoalescer<K, T> {
+    slots: Arc<Mutex<Slots<K, T>>>,
+}
+
+impl<K, T> Default for BuildCoalescer<K, T> {
+    fn default() -> Self {
+        Self {
+            slots: Arc::new(Mutex::new(Slots {
+                next_generation: 0,
+                in_flight: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl<K, T> BuildCoalescer<K, T>
+where
+    K: PartialEq + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    pub async fn run<F, Fut>(&self, server_id: i32, key: K, build: F) -> (T, BuildAdmission)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let (shared, admission) = {
+            let mut slots = self.slots.lock().expect("build coalescer mutex poisoned");
+            match slots.in_flight.get(&server_id) {
+                Some(current) if current.key == key => {
+                    (current.build.clone(), BuildAdmission::Coalesced)
+                }
+                current => {
+                    let previous = current.map(|current| current.build.clone());
+                    let generation = slots.next_generation;
+                    slots.next_generation += 1;
+                    let slots_handle = self.slots.clone();
+                    let build = build();
+                    let shared = async move {
+                        if let Some(previous) = previous {
+                            previous.await;
+                        }
+                        let output = build.await;
+                        let mut slots =
+                            slots_handle.lock().expect("build coalescer mutex poisoned");
+                        if slots
+                            .in_flight
+                            .get(&server_id)
+                            .is_some_and(|current| current.generation == generation)
+                        {
+                            slots.in_flight.remove(&server_id);
+                        }
+                        output
+                    }
+                    .boxed()
+                    .shared();
+                    slots.in_flight.insert(
+                        server_id,
+                        InFlight {
+                            key,
+                            generation,
+                            build: shared.clone(),
+                        },
+                    );
+                    (shared, BuildAdmission::Started)
+                }
+            }
+        };
+        (shared.await, admission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn counting_build(
+        builds: &Arc<AtomicUsize>,
+        label: &'static str,
+    ) -> impl FnOnce() -> BoxFuture<'static, &'static str> {
+        let builds = builds.clone();
+        move || {
+            async move {
+                builds.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                label
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn simultaneous_requests_share_one_build() {
+        let coalescer = BuildCoalescer::<&str, &str>::default();
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        let (first, second) = tokio::join!(
+            coalescer.run(7, "repo@main", counting_build(&builds, "first")),
+            coalescer.run(7, "repo@main", counting_build(&builds, "second")),
+        );
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+        assert_eq!(first, ("first", BuildAdmission::Started));
+        assert_eq!(second, ("first", BuildAdmission::Coalesced));
+
+        // a request after completion builds again
+        let third = coalescer
+            .run(7, "repo@main", counting_build(&builds, "third"))
+            .await;
+        assert_eq!(third, ("third", BuildAdmission::Started));
+        assert_eq!(builds.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn different_inputs_queue_and_other_servers_run_in_parallel() {
+        let coalescer = BuildCoalescer::<&str, &str>::default();
+        let builds = Arc::new(AtomicUsize::new(0));
+        let started = tokio::time::Instant::now();
+
+        let (main, feature, other) = tokio::join!(
+            coalescer.run(7, "repo@main", counting_build(&builds, "main")),
+            coalescer.run(7, "repo@feature", counting_build(&builds, "feature")),
+            coalescer.run(8, "repo@main", counting_build(&builds, "other")),
+        );
+        assert_eq!(builds.load(Ordering::SeqCst), 3);
+        assert_eq!(main.0, "main");
+        assert_eq!(feature, ("feature", BuildAdmission::Started));
+        assert_eq!(other.0, "other");
+        // the queued build ran after the first one, not alongside it
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,509 @@
+use super::{
+    load_registry_auth_header, registry_location, ManifestPublishError, MANIFEST_HTTP_CLIENT,
+};
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use axum::{extract::Extension, Json};
+use reqwest::header::{ACCEPT, AUTHORIZATION};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+// key: registry-gc -> delete superseded manifests no build or promotion references
+
+const MANIFEST_MEDIA_TYPES: &str = "application/vnd.docker.distribution.manifest.list.v2+json, \
+application/vnd.docker.distribution.manifest.v2+json, \
+application/vnd.oci.image.index.v1+json, \
+application/vnd.oci.image.manifest.v1+json";
+
+#[derive(Debug, Error)]
+pub enum RegistryGcError {
+    #[error("registry gc requires registry credentials for {0}")]
+    MissingCredentials(String),
+    #[error("failed to parse registry url: {0}")]
+    InvalidRegistryUrl(String),
+    #[error("http error while talking to registry: {0}")]
+    Http(String),
+    #[error("registry rejected request: {0}")]
+    Remote(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+fn registry_error(err: ManifestPublishError) -> RegistryGcError {
+    match err {
+        ManifestPublishError::MissingCredentials(host) => RegistryGcError::MissingCredentials(host),
+        ManifestPublishError::InvalidRegistryUrl(url) => RegistryGcError::InvalidRegistryUrl(url),
+        ManifestPublishError::Http(message) => RegistryGcError::Http(message),
+        ManifestPublishError::Remote(message) => RegistryGcError::Remote(message),
+    }
+}
+
+/// Why a digest was kept.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionReason {
+    /// Referenced by a promotion that has not been rolled back.
+    LivePromotion,
+    /// Built within the retention window.
+    WithinRetention,
+    /// The most recent successful build of some server.
+    LatestBuild,
+}
+
+/// Digests the database still needs.
+#[derive(Debug, Clone, Default)]
+pub struct GcReferences {
+    pub live_promotions: HashSet<String>,
+    pub within_retention: HashSet<String>,
+    pub latest_builds: HashSet<String>,
+}
+
+impl GcReferences {
+    pub async fn load(pool: &PgPool, retention: Duration) -> Result<Self, sqlx::Error> {
+        let live_promotions: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT manifest_digest FROM artifact_promotions WHERE status <> 'rolled_back'",
+        )
+        .fetch_all(pool)
+        .await?;
+        let within_retention: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT manifest_digest
+            FROM build_artifact_runs
+            WHERE manifest_digest IS NOT NULL
+              AND COALESCE(completed_at, started_at) >= NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(retention.as_secs_f64())
+        .fetch_all(pool)
+        .await?;
+        let latest_builds: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT ON (server_id) manifest_digest
+            FROM build_artifact_runs
+            WHERE manifest_digest IS NOT NULL AND status = 'succeeded'
+            ORDER BY server_id, completed_at DESC NULLS LAST
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Self {
+            live_promotions: live_promotions.into_iter().collect(),
+            within_retention: within_retention.into_iter().collect(),
+            latest_builds: latest_builds.into_iter().collect(),
+        })
+    }
+
+    /// Live promotions win over the other reasons so reports show the strongest one.
+    pub fn retention_reason(&self, digest: &str) -> Option<RetentionReason> {
+        if self.live_promotions.contains(digest) {
+            Some(RetentionReason::LivePromotion)
+        } else if self.latest_builds.contains(digest) {
+            Some(RetentionReason::LatestBuild)
+        } else if self.within_retention.contains(digest) {
+            Some(RetentionReason::WithinRetention)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RetainedDigest {
+    pub digest: String,
+    pub tags: Vec<String>,
+    pub reason: RetentionReason,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CollectedDigest {
+    pub digest: String,
+    pub tags: Vec<String>,
+    /// Set when the registry refused the delete.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcReport {
+    pub repository: String,
+    pub dry_run: bool,
+    pub tags_scanned: usize,
+    pub retained: Vec<RetainedDigest>,
+    /// Deleted digests, or the digests that would be deleted on a dry run.
+    pub collected: Vec<CollectedDigest>,
+}
+
+/// One repository on a registry, with the credentials used to reach it.
+pub struct RegistryRepository {
+    base: Url,
+    repository: String,
+    auth: String,
+}
+
+impl RegistryRepository {
+    /// Resolves credentials from `REGISTRY_AUTH_DOCKERCONFIG`, as image pushes do.
+    pub fn from_docker_config(registry: &str, repository: &str) -> Result<Self, RegistryGcError> {
+        let location = registry_location(registry, repository).map_err(registry_error)?;
+        let auth = load_registry_auth_header(&location.auth_host)
+            .ok_or_else(|| RegistryGcError::MissingCredentials(location.host.clone()))?;
+        Ok(Self {
+            base: location.base,
+            repository: location.repository,
+            auth,
+        })
+    }
+
+    pub fn with_auth(
+        registry: &str,
+        repository: &str,
+        auth: String,
+    ) -> Result<Self, RegistryGcError> {
+        let location = registry_location(registry, repository).map_err(registry_error)?;
+        Ok(Self {
+            base: location.base,
+            repository: location.repository,
+            auth,
+        })
+    }
+
+    fn url(&self, path: &str) -> Url {
+        let mut url = self.base.clone();
+        url.set_path(&format!("/v2/{}/{path}", self.repository));
+        url
+    }
+
+    async fn list_tags(&self) -> Result<Vec<String>, RegistryGcError> {
+        let mut url = self.url("tags/list");
+        url.set_query(Some("n=10000"));
+        let response = MANIFEST_HTTP_CLIENT
+            .get(url)
+            .header(AUTHORIZATION, &self.auth)
+            .send()
+            .await
+            .map_err(|err| RegistryGcError::Http(err.to_string()))?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|err| RegistryGcError::Http(err.to_string()))?;
+        if !status.is_success() {
+            return Err(RegistryGcError::Remote(format!("{status}: {body}")));
+        }
+        let json: Value =
+            serde_json::from_str(&body).map_err(|err| RegistryGcError::Http(err.to_string()))?;
+        Ok(json
+            .get("tags")
+            .and_then(|tags| tags.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|tag| tag.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// `None` when the tag disappeared between listing and lookup.
+    async fn tag_digest(&self, tag: &str) -> Result<Option<String>, RegistryGcError> {
+        let response = MANIFEST_HTTP_CLIENT
+            .head(self.url(&format!("manifests/{tag}")))
+            .header(AUTHORIZATION, &self.auth)
+            .header(ACCEPT, MANIFEST_MEDIA_TYPES)
+            .send()
+            .await
+            .map_err(|err| RegistryGcError::Http(err.to_string()))?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(RegistryGcError::Remote(format!(
+                "{status} resolving tag {tag}"
+            )));
+        }
+        response
+            .headers()
+            .get("docker-content-digest")
+            .and_then(|value| value.to_str().ok())
+            .map(|digest| Some(digest.trim().to_string()))
+            .ok_or_else(|| {
+                RegistryGcError::Remote(format!("no Docker-Content-Digest for tag {tag}"))
+            })
+    }
+
+    async fn delete_manifest(&self, digest: &str) -> Result<(), String> {
+        let response = MANIFEST_HTTP_CLIENT
+            .delete(self.url(&format!("manifests/{digest}")))
+            .header(AUTHORIZATION, &self.auth)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        let status = response.status();
+        if status.is_success() || status == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("{status}: {body}"))
+    }
+}
+
+/// Deletes every tagged digest in `repository` that `references` does not retain.
+/// Deleting a digest removes all tags that point at it. With `dry_run` nothing is
+/// deleted and the report lists what would be.
+pub async fn collect_garbage(
+    registry: &RegistryRepository,
+    references: &GcReferences,
+    dry_run: bool,
+) -> Result<GcReport, RegistryGcError> {
+    let tags = registry.list_tags().await?;
+    let mut digests: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for tag in &tags {
+        if let Some(digest) = registry.tag_digest(tag).await? {
+            digests.entry(digest).or_default().push(tag.clone());
+        }
+    }
+
+    let mut report = GcReport {
+        repository: registry.repository.clone(),
+        dry_run,
+        tags_scanned: tags.len(),
+        retained: Vec::new(),
+        collected: Vec::new(),
+    };
+    for (digest, tags) in digests {
+        if let Some(reason) = references.retention_reason(&digest) {
+            report.retained.push(RetainedDigest {
+                digest,
+                tags,
+                reason,
+            });
+            continue;
+        }
+        let error = if dry_run {
+            None
+        } else {
+            registry.delete_manifest(&digest).await.err()
+        };
+        tracing::info!(
+            target: "registry.gc",
+            repository = %registry.repository,
+            %digest,
+            ?tags,
+            dry_run,
+            error = error.as_deref(),
+            "collected unreferenced manifest",
+        );
+        report.collected.push(CollectedDigest {
+            digest,
+            tags,
+            error,
+        });
+    }
+    Ok(report)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegistryGcRequest {
+    /// Defaults to the `REGISTRY` builds push to.
+    pub registry: Option<String>,
+    pub repository: String,
+    /// Defaults to `true`; deletes only when explicitly `false`.
+    pub dry_run: Option<bool>,
+    pub retention_days: Option<u64>,
+}
+
+/// POST /api/admin/registry/gc
+pub async fn run_registry_gc(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { role, .. }: AuthUser,
+    Json(payload): Json<RegistryGcRequest>,
+) -> AppResult<Json<GcReport>> {
+    if role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+    let registry = payload
+        .registry
+        .or_else(|| std::env::var("REGISTRY").ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| AppError::BadRequest("no registry given and REGISTRY is unset".into()))?;
+    let retention_days = payload
+        .retention_days
+        .unwrap_or(*config::REGISTRY_GC_RETENTION_DAYS);
+    let retention = Duration::from_secs(retention_days.saturating_mul(24 * 60 * 60));
+
+    let repository = RegistryRepository::from_docker_config(&registry, payload.repository.trim())
+        .map_err(gc_error)?;
+    let references = GcReferences::load(&pool, retention).await?;
+    let report = collect_garbage(&repository, &references, payload.dry_run.unwrap_or(true))
+        .await
+        .map_err(gc_error)?;
+    Ok(Json(report))
+}
+
+fn gc_error(err: RegistryGcError) -> AppError {
+    match err {
+        RegistryGcError::MissingCredentials(_) | RegistryGcError::InvalidRegistryUrl(_) => {
+            AppError::BadRequest(err.to_string())
+        }
+        RegistryGcError::Http(_) | RegistryGcError::Remote(_) => {
+            AppError::BadGateway(err.to_string())
+        }
+        RegistryGcError::Database(err) => AppError::Db(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    const AUTH: &str = "Basic dXNlcjpwYXNz";
+
+    struct MockRegistry {
+        server: MockServer,
+    }
+
+    impl MockRegistry {
+        /// Serves `tags`, each resolving to the paired digest.
+        async fn start(tags: &[(&str, &str)]) -> Self {
+            let server = MockServer::start_async().await;
+            let names: Vec<&str> = tags.iter().map(|(tag, _)| *tag).collect();
+            server
+                .mock_async(|when, then| {
+                    when.method("GET")
+                        .path("/v2/mcp-custom-7/tags/list")
+                        .header("authorization", AUTH);
+                    then.status(200)
+                        .json_body(serde_json::json!({ "name": "mcp-custom-7", "tags": names }));
+                })
+                .await;
+            for (tag, digest) in tags {
+                server
+                    .mock_async(|when, then| {
+                        when.method("HEAD")
+                            .path(format!("/v2/mcp-custom-7/manifests/{tag}"))
+                            .header("authorization", AUTH);
+                        then.status(200).header("docker-content-digest", *digest);
+                    })
+                    .await;
+            }
+            Self { server }
+        }
+
+        async fn expect_delete(&self, digest: &str) -> httpmock::Mock<'_> {
+            self.server
+                .mock_async(|when, then| {
+                    when.method("DELETE")
+                        .path(format!("/v2/mcp-custom-7/manifests/{digest}"))
+                        .header("authorization", AUTH);
+                    then.status(202);
+                })
+                .await
+        }
+
+        fn repository(&self) -> RegistryRepository {
+            RegistryRepository::with_auth(
+                &format!("http://{}", self.server.address()),
+                "mcp-custom-7",
+                AUTH.to_string(),
+            )
+            .unwrap()
+        }
+    }
+
+    fn references() -> GcReferences {
+        GcReferences {
+            live_promotions: HashSet::from(["sha256:promoted".to_string()]),
+            within_retention: HashSet::from(["sha256:recent".to_string()]),
+            latest_builds: HashSet::from(["sha256:latest".to_string()]),
+        }
+    }
+
+    const TAGS: [(&str, &str); 5] = [
+        ("latest", "sha256:latest"),
+        ("v1", "sha256:promoted"),
+        ("v2", "sha256:recent"),
+        ("v0", "sha256:stale"),
+        ("old", "sha256:stale"),
+    ];
+
+    #[tokio::test]
+    async fn dry_run_reports_without_deleting() {
+        let registry = MockRegistry::start(&TAGS).await;
+        let stale = registry.expect_delete("sha256:stale").await;
+        let promoted = registry.expect_delete("sha256:promoted").await;
+
+        let report = collect_garbage(&registry.repository(), &references(), true)
+            .await
+            .unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.tags_scanned, 5);
+        assert_eq!(
+            report.collected,
+            vec![CollectedDigest {
+                digest: "sha256:stale".into(),
+                tags: vec!["v0".into(), "old".into()],
+                error: None,
+            }]
+        );
+        stale.assert_hits_async(0).await;
+        promoted.assert_hits_async(0).await;
+    }
+
+    #[tokio::test]
+    async fn gc_deletes_only_unreferenced_digests() {
+        let registry = MockRegistry::start(&TAGS).await;
+        let stale = registry.expect_delete("sha256:stale").await;
+        let kept: Vec<_> = futures_util::future::join_all(
+            ["sha256:latest", "sha256:promoted", "sha256:recent"]
+                .map(|digest| registry.expect_delete(digest)),
+        )
+        .await;
+
+        let report = collect_garbage(&registry.repository(), &references(), false)
+            .await
+            .unwrap();
+
+        stale.assert_hits_async(1).await;
+        for mock in kept {
+            mock.assert_hits_async(0).await;
+        }
+        let reasons: Vec<(&str, RetentionReason)> = report
+            .retained
+            .iter()
+            .map(|retained| (retained.digest.as_str(), retained.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("sha256:latest", RetentionReason::LatestBuild),
+                ("sha256:promoted", RetentionReason::LivePromotion),
+                ("sha256:recent", RetentionReason::WithinRetention),
+            ]
+        );
+        assert_eq!(report.collected.len(), 1);
+        assert_eq!(report.collected[0].error, None);
+    }
+
+    #[test]
+    fn live_promotion_outranks_other_reasons() {
+        let mut references = references();
+        references.latest_builds.insert("sha256:promoted".into());
+        assert_eq!(
+            references.retention_reason("sha256:promoted"),
+            Some(RetentionReason::LivePromotion)
+        );
+        assert_eq!(references.retention_reason("sha256:stale"), None);
+    }
+}
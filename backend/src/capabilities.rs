@@ -1,12 +1,16 @@
+use crate::db::mcp_servers;
 use crate::extractor::AuthUser;
+use crate::invocations::cache::INVOCATION_CACHE;
 use axum::{
     extract::{Extension, Path},
     http::StatusCode,
     Json,
 };
-use serde::Serialize;
-use sqlx::{PgPool, Row};
-use tracing::error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::BTreeMap;
+use tracing::{error, warn};
 
 #[derive(Serialize)]
 pub struct Capability {
@@ -15,23 +19,58 @@ pub struct Capability {
     pub description: Option<String>,
 }
 
-pub async fn list_capabilities(
-    Extension(pool): Extension<PgPool>,
-    AuthUser { user_id, .. }: AuthUser,
-    Path(server_id): Path<i32>,
-) -> Result<Json<Vec<Capability>>, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+/// Latest intelligence observation for a capability, across backends and tiers.
+#[derive(Debug, Clone)]
+pub struct ObservedCapability {
+    pub name: String,
+    pub score: f32,
+    pub confidence: f32,
+    pub status: String,
+    pub last_observed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilitySource {
+    DeclaredOnly,
+    ObservedOnly,
+    Both,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciledCapability {
+    pub name: String,
+    pub source: CapabilitySource,
+    pub description: Option<String>,
+    pub score: Option<f32>,
+    pub confidence: Option<f32>,
+    pub status: Option<String>,
+    pub last_observed_at: Option<DateTime<Utc>>,
+}
+
+async fn ensure_server_owner(
+    pool: &PgPool,
+    server_id: i32,
+    user_id: i32,
+) -> Result<(), (StatusCode, String)> {
+    let owned = mcp_servers::is_live_owned_by(pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error verifying server ownership");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
+    Ok(())
+}
+
+pub async fn list_capabilities(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(server_id): Path<i32>,
+) -> Result<Json<Vec<Capability>>, (StatusCode, String)> {
+    ensure_server_owner(&pool, server_id, user_id).await?;
     let rows = sqlx::query(
         "SELECT id, name, description FROM server_capabilities WHERE server_id = $1 ORDER BY id",
     )
@@ -53,27 +92,389 @@ pub async fn list_capabilities(
     Ok(Json(caps))
 }
 
+/// Merges declared capabilities with the latest intelligence observations so
+/// claims that were never demonstrated stand out.
+pub fn reconcile_capabilities(
+    declared: Vec<Capability>,
+    observed: Vec<ObservedCapability>,
+) -> Vec<ReconciledCapability> {
+    let mut merged: BTreeMap<String, ReconciledCapability> = BTreeMap::new();
+    for cap in declared {
+        merged
+            .entry(cap.name.clone())
+            .or_insert(ReconciledCapability {
+                name: cap.name,
+                source: CapabilitySource::DeclaredOnly,
+                description: cap.description,
+                score: None,
+                confidence: None,
+                status: None,
+                last_observed_at: None,
+            });
+    }
+    for obs in observed {
+        let entry = merged
+            .entry(obs.name.clone())
+            .or_insert(ReconciledCapability {
+                name: obs.name,
+                source: CapabilitySource::ObservedOnly,
+                description: None,
+                score: None,
+                confidence: None,
+                status: None,
+                last_observed_at: None,
+            });
+        if entry
+            .last_observed_at
+            .is_some_and(|seen| seen >= obs.last_observed_at)
+        {
+            continue;
+        }
+        if entry.source == CapabilitySource::DeclaredOnly {
+            entry.source = CapabilitySource::Both;
+        }
+        entry.score = Some(obs.score);
+        entry.confidence = Some(obs.confidence);
+        entry.status = Some(obs.status);
+        entry.last_observed_at = Some(obs.last_observed_at);
+    }
+    merged.into_values().collect()
+}
+
+pub async fn reconciled_capabilities(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(server_id): Path<i32>,
+) -> Result<Json<Vec<ReconciledCapability>>, (StatusCode, String)> {
+    ensure_server_owner(&pool, server_id, user_id).await?;
+    let declared = sqlx::query(
+        "SELECT id, name, description FROM server_capabilities WHERE server_id = $1 ORDER BY id",
+    )
+    .bind(server_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error fetching capabilities");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?
+    .into_iter()
+    .map(|r| Capability {
+        id: r.get("id"),
+        name: r.get("name"),
+        description: r.try_get("description").ok(),
+    })
+    .collect();
+    let observed = sqlx::query(
+        r#"
+        SELECT DISTINCT ON (capability)
+            capability, score::FLOAT8 AS score, confidence::FLOAT8 AS confidence, status, last_observed_at
+        FROM capability_intelligence_scores
+        WHERE server_id = $1
+        ORDER BY capability, last_observed_at DESC
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error fetching capability intelligence");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?
+    .into_iter()
+    .map(|r| ObservedCapability {
+        name: r.get("capability"),
+        score: r.get::<f64, _>("score") as f32,
+        confidence: r.get::<f64, _>("confidence") as f32,
+        status: r.get("status"),
+        last_observed_at: r.get("last_observed_at"),
+    })
+    .collect();
+    Ok(Json(reconcile_capabilities(declared, observed)))
+}
+
+/// One capability as recorded in a deployment snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotCapability {
+    pub name: String,
+    pub description: Option<String>,
+    pub deterministic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModifiedCapability {
+    pub name: String,
+    pub before: SnapshotCapability,
+    pub after: SnapshotCapability,
+}
+
+/// What changed between two capability snapshots. Removals are breaking for callers that
+/// relied on the capability.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CapabilityDiff {
+    pub added: Vec<SnapshotCapability>,
+    pub removed: Vec<SnapshotCapability>,
+    pub modified: Vec<ModifiedCapability>,
+}
+
+impl CapabilityDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty()
+    }
+}
+
+/// Capabilities declared by a manifest, one per name (the last declaration wins), sorted by name.
+pub fn manifest_capabilities(manifest: &serde_json::Value) -> Option<Vec<SnapshotCapability>> {
+    let caps = manifest.get("capabilities").and_then(|v| v.as_array())?;
+    let mut by_name = BTreeMap::new();
+    for cap in caps {
+        if let Some(name) = cap.get("name").and_then(|v| v.as_str()) {
+            by_name.insert(
+                name.to_string(),
+                SnapshotCapability {
+                    name: name.to_string(),
+                    description: cap
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    deterministic: cap
+                        .get("deterministic")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true),
+                },
+            );
+        }
+    }
+    Some(by_name.into_values().collect())
+}
+
+/// Compares two snapshots by capability name; each list comes out sorted by name.
+pub fn diff_capabilities(
+    previous: &[SnapshotCapability],
+    current: &[SnapshotCapability],
+) -> CapabilityDiff {
+    let before: BTreeMap<&str, &SnapshotCapability> = previous
+        .iter()
+        .map(|cap| (cap.name.as_str(), cap))
+        .collect();
+    let after: BTreeMap<&str, &SnapshotCapability> =
+        current.iter().map(|cap| (cap.name.as_str(), cap)).collect();
+    let mut diff = CapabilityDiff::default();
+    for (name, cap) in &after {
+        match before.get(name) {
+            None => diff.added.push((*cap).clone()),
+            Some(old) if old != cap => diff.modified.push(ModifiedCapability {
+                name: name.to_string(),
+                before: (*old).clone(),
+                after: (*cap).clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (name, cap) in &before {
+        if !after.contains_key(name) {
+            diff.removed.push((*cap).clone());
+        }
+    }
+    diff
+}
+
+/// Replaces the server's declared capabilities with the manifest's and snapshots them. When
+/// the set differs from the previous snapshot a `capabilities_changed` metric event carries
+/// the diff to the console.
 pub async fn sync_capabilities(pool: &PgPool, server_id: i32, manifest: &serde_json::Value) {
-    if let Some(caps) = manifest.get("capabilities").and_then(|v| v.as_array()) {
-        if let Ok(mut tx) = pool.begin().await {
-            let _ = sqlx::query("DELETE FROM server_capabilities WHERE server_id = $1")
-                .bind(server_id)
-                .execute(&mut *tx)
-                .await;
-            for cap in caps {
-                if let Some(name) = cap.get("name").and_then(|v| v.as_str()) {
-                    let desc = cap.get("description").and_then(|v| v.as_str());
-                    let _ = sqlx::query(
-                        "INSERT INTO server_capabilities (server_id, name, description) VALUES ($1, $2, $3)",
-                    )
-                    .bind(server_id)
-                    .bind(name)
-                    .bind(desc)
-                    .execute(&mut *tx)
-                    .await;
-                }
-            }
-            let _ = tx.commit().await;
+    // a new manifest means a new deployment, so earlier results may be stale
+    INVOCATION_CACHE.invalidate_server(server_id);
+    let Some(caps) = manifest_capabilities(manifest) else {
+        return;
+    };
+    let Ok(mut tx) = pool.begin().await else {
+        return;
+    };
+    let _ = sqlx::query("DELETE FROM server_capabilities WHERE server_id = $1")
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await;
+    for cap in &caps {
+        let _ = sqlx::query(
+            "INSERT INTO server_capabilities (server_id, name, description, deterministic) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(server_id)
+        .bind(&cap.name)
+        .bind(&cap.description)
+        .bind(cap.deterministic)
+        .execute(&mut *tx)
+        .await;
+    }
+    let diff = match snapshot_capabilities(&mut tx, server_id, &caps).await {
+        Ok(diff) => diff,
+        Err(e) => {
+            warn!(?e, %server_id, "failed to snapshot capabilities");
+            None
         }
+    };
+    if tx.commit().await.is_err() {
+        return;
+    }
+    if let Some(diff) = diff.filter(|diff| !diff.is_empty()) {
+        let details = serde_json::json!({
+            "added": diff.added,
+            "removed": diff.removed,
+            "modified": diff.modified,
+            "breaking": diff.is_breaking(),
+        });
+        let _ = crate::servers::add_metric(pool, server_id, "capabilities_changed", Some(&details))
+            .await;
+    }
+}
+
+/// Records `caps` as the server's latest snapshot and returns the diff against the one
+/// before it, or `None` for the first snapshot.
+async fn snapshot_capabilities(
+    tx: &mut Transaction<'_, Postgres>,
+    server_id: i32,
+    caps: &[SnapshotCapability],
+) -> Result<Option<CapabilityDiff>, sqlx::Error> {
+    let previous: Option<serde_json::Value> = sqlx::query_scalar(
+        "SELECT capabilities FROM server_capability_snapshots WHERE server_id = $1 ORDER BY id DESC LIMIT 1",
+    )
+    .bind(server_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let diff = previous
+        .and_then(|value| serde_json::from_value::<Vec<SnapshotCapability>>(value).ok())
+        .map(|previous| diff_capabilities(&previous, caps));
+    sqlx::query(
+        "INSERT INTO server_capability_snapshots (server_id, capabilities, diff) VALUES ($1, $2, $3)",
+    )
+    .bind(server_id)
+    .bind(serde_json::to_value(caps).unwrap_or_default())
+    .bind(diff.as_ref().and_then(|diff| serde_json::to_value(diff).ok()))
+    .execute(&mut **tx)
+    .await?;
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn declared(id: i32, name: &str) -> Capability {
+        Capability {
+            id,
+            name: name.into(),
+            description: Some(format!("{name} capability")),
+        }
+    }
+
+    fn observed(name: &str, score: f32, age_minutes: i64) -> ObservedCapability {
+        ObservedCapability {
+            name: name.into(),
+            score,
+            confidence: 0.85,
+            status: "healthy".into(),
+            last_observed_at: Utc::now() - Duration::minutes(age_minutes),
+        }
+    }
+
+    #[test]
+    fn declared_capability_without_observations_is_declared_only() {
+        let merged = reconcile_capabilities(
+            vec![declared(1, "search"), declared(2, "summarize")],
+            vec![observed("search", 82.0, 5)],
+        );
+
+        let summarize = merged.iter().find(|c| c.name == "summarize").unwrap();
+        assert_eq!(summarize.source, CapabilitySource::DeclaredOnly);
+        assert_eq!(summarize.score, None);
+        assert_eq!(summarize.last_observed_at, None);
+
+        let search = merged.iter().find(|c| c.name == "search").unwrap();
+        assert_eq!(search.source, CapabilitySource::Both);
+        assert_eq!(search.score, Some(82.0));
+        assert_eq!(search.description.as_deref(), Some("search capability"));
+    }
+
+    fn snapshot(name: &str, description: &str) -> SnapshotCapability {
+        SnapshotCapability {
+            name: name.into(),
+            description: Some(description.into()),
+            deterministic: true,
+        }
+    }
+
+    #[test]
+    fn added_capability_is_not_breaking() {
+        let previous = vec![snapshot("search", "Search docs")];
+        let current = vec![
+            snapshot("search", "Search docs"),
+            snapshot("summarize", "Summaries"),
+        ];
+        let diff = diff_capabilities(&previous, &current);
+        assert_eq!(diff.added, vec![snapshot("summarize", "Summaries")]);
+        assert!(diff.removed.is_empty());
+        assert!(diff.modified.is_empty());
+        assert!(!diff.is_breaking());
+    }
+
+    #[test]
+    fn removed_capability_is_breaking() {
+        let previous = vec![
+            snapshot("search", "Search docs"),
+            snapshot("summarize", "Summaries"),
+        ];
+        let mut current = vec![snapshot("search", "Search the docs")];
+        current[0].deterministic = false;
+        let diff = diff_capabilities(&previous, &current);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec![snapshot("summarize", "Summaries")]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].name, "search");
+        assert_eq!(diff.modified[0].before, snapshot("search", "Search docs"));
+        assert!(!diff.modified[0].after.deterministic);
+        assert!(diff.is_breaking());
+    }
+
+    #[test]
+    fn unchanged_manifest_yields_an_empty_diff() {
+        // declaration order and duplicates do not count as changes
+        let first = manifest_capabilities(&serde_json::json!({
+            "capabilities": [
+                { "name": "search", "description": "Search docs" },
+                { "name": "summarize", "description": "Summaries" }
+            ]
+        }))
+        .unwrap();
+        let second = manifest_capabilities(&serde_json::json!({
+            "capabilities": [
+                { "name": "summarize", "description": "Summaries", "deterministic": true },
+                { "name": "search", "description": "stale" },
+                { "name": "search", "description": "Search docs" }
+            ]
+        }))
+        .unwrap();
+        let diff = diff_capabilities(&first, &second);
+        assert!(diff.is_empty());
+        assert!(!diff.is_breaking());
+        assert_eq!(manifest_capabilities(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn observed_capability_without_declaration_is_observed_only() {
+        let merged = reconcile_capabilities(
+            vec![declared(1, "search")],
+            vec![observed("runtime", 40.0, 30), observed("runtime", 70.0, 1)],
+        );
+
+        assert_eq!(merged.len(), 2);
+        let runtime = merged.iter().find(|c| c.name == "runtime").unwrap();
+        assert_eq!(runtime.source, CapabilitySource::ObservedOnly);
+        assert_eq!(runtime.description, None);
+        assert_eq!(runtime.score, Some(70.0), "latest observation wins");
+        assert_eq!(runtime.confidence, Some(0.85));
     }
 }
@@ -1,14 +1,33 @@
+pub mod reload;
+pub mod validation;
+
 use once_cell::sync::Lazy;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-
-use crate::runtime::{LibvirtAuthConfig, LibvirtProvisioningConfig};
+use std::time::Duration;
+
+use crate::auth::jwt::{parse_algorithm, JwtKeys, SigningKey, VerificationKey};
+use crate::cors::{self, AllowedOrigins};
+use crate::db::pool::DbPoolConfig;
+use crate::lifecycle_console::verdict_selection::VerdictStrategy;
+use crate::metrics_access::AllowedSource;
+use crate::proxy::routing::RoutingStrategy;
+use crate::remediation::gate_context::GateContextMerge;
+use crate::remediation::note_templates::NoteTemplates;
+use crate::runtime::{LibvirtAuthConfig, LibvirtProvisioningConfig, LibvirtResourceProfile};
 use serde_json::{json, Value};
+use validation::{flag, setting};
 
-/// Secret used for JWT signing. Must be set via the `JWT_SECRET` env variable.
+/// Secret used for JWT signing. Must be set via the `JWT_SECRET` env variable when
+/// `JWT_ALGORITHM` is HS256, the default.
 pub static JWT_SECRET: Lazy<String> =
     Lazy::new(|| std::env::var("JWT_SECRET").expect("JWT_SECRET must be set"));
 
+/// Keys for issuing and verifying session tokens. Malformed settings stop the server.
+pub static JWT_KEYS: Lazy<JwtKeys> = Lazy::new(|| {
+    jwt_keys_from_env().unwrap_or_else(|err| panic!("invalid JWT key configuration: {err}"))
+});
+
 /// Container runtime backend. Defaults to `docker`.
 pub static CONTAINER_RUNTIME: Lazy<String> =
     Lazy::new(|| std::env::var("CONTAINER_RUNTIME").unwrap_or_else(|_| "docker".to_string()));
@@ -65,42 +84,19 @@ pub static BIND_ADDRESS: Lazy<String> =
     Lazy::new(|| std::env::var("BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0".to_string()));
 
 /// Port the HTTP server should listen on. Defaults to `3000`.
-pub static BIND_PORT: Lazy<u16> = Lazy::new(|| {
-    std::env::var("BIND_PORT")
-        .ok()
-        .and_then(|value| value.parse::<u16>().ok())
-        .unwrap_or(3000)
-});
+pub static BIND_PORT: Lazy<u16> = Lazy::new(|| setting::<u16>("BIND_PORT").unwrap_or(3000));
 
 /// When set to a truthy value, allows the application to continue running even if database
 /// migrations fail. Defaults to `false`.
-pub static ALLOW_MIGRATION_FAILURE: Lazy<bool> = Lazy::new(|| {
-    std::env::var("ALLOW_MIGRATION_FAILURE")
-        .ok()
-        .map(|value| {
-            let normalized = value.trim().to_ascii_lowercase();
-            matches!(normalized.as_str(), "1" | "true" | "yes")
-        })
-        .unwrap_or(false)
-});
+pub static ALLOW_MIGRATION_FAILURE: Lazy<bool> = Lazy::new(|| flag("ALLOW_MIGRATION_FAILURE"));
 
 /// key: billing-config -> renewal scan cadence
-pub static BILLING_RENEWAL_SCAN_INTERVAL_SECS: Lazy<u64> = Lazy::new(|| {
-    std::env::var("BILLING_RENEWAL_SCAN_INTERVAL_SECS")
-        .ok()
-        .and_then(|value| value.parse::<u64>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or(300)
-});
+pub static BILLING_RENEWAL_SCAN_INTERVAL_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("BILLING_RENEWAL_SCAN_INTERVAL_SECS").unwrap_or(300));
 
 /// key: billing-config -> grace window before suspension/downgrade
-pub static BILLING_PAST_DUE_GRACE_DAYS: Lazy<i64> = Lazy::new(|| {
-    std::env::var("BILLING_PAST_DUE_GRACE_DAYS")
-        .ok()
-        .and_then(|value| value.parse::<i64>().ok())
-        .filter(|value| *value >= 0)
-        .unwrap_or(3)
-});
+pub static BILLING_PAST_DUE_GRACE_DAYS: Lazy<i64> =
+    Lazy::new(|| setting::<i64>("BILLING_PAST_DUE_GRACE_DAYS").unwrap_or(3));
 
 /// key: billing-config -> optional fallback plan code for automatic downgrades
 pub static BILLING_FALLBACK_PLAN_CODE: Lazy<Option<String>> =
@@ -123,13 +119,8 @@ pub static VM_HYPERVISOR_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
 });
 
 /// Number of log lines to request when fetching VM logs.
-pub static VM_LOG_TAIL_LINES: Lazy<usize> = Lazy::new(|| {
-    std::env::var("VM_LOG_TAIL_LINES")
-        .ok()
-        .and_then(|value| value.parse::<usize>().ok())
-        .filter(|value| *value >= 64)
-        .unwrap_or(500)
-});
+pub static VM_LOG_TAIL_LINES: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("VM_LOG_TAIL_LINES").unwrap_or(500));
 
 /// Allowed attestation measurements for trusted workloads.
 pub static VM_ATTESTATION_MEASUREMENTS: Lazy<HashSet<String>> = Lazy::new(|| {
@@ -172,14 +163,522 @@ pub static VM_ATTESTATION_TRUST_ROOTS: Lazy<Vec<String>> = Lazy::new(|| {
 });
 
 /// Maximum age (seconds) for attestation evidence before remediation is required.
-pub static VM_ATTESTATION_MAX_AGE_SECONDS: Lazy<u64> = Lazy::new(|| {
-    std::env::var("VM_ATTESTATION_MAX_AGE_SECONDS")
+pub static VM_ATTESTATION_MAX_AGE_SECONDS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("VM_ATTESTATION_MAX_AGE_SECONDS").unwrap_or(300));
+
+/// Seconds of clock drift tolerated when checking attestation timestamps, in both
+/// directions: evidence this far in the future is accepted, and stale evidence gets the
+/// same grace.
+pub static VM_ATTESTATION_CLOCK_SKEW_SECONDS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("VM_ATTESTATION_CLOCK_SKEW_SECONDS").unwrap_or(30));
+
+/// Seconds a governance promotion gate evaluation is reused for the same manifest digest
+/// and tier; `0` disables the cache.
+pub static GOVERNANCE_GATE_CACHE_TTL_SECONDS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("GOVERNANCE_GATE_CACHE_TTL_SECONDS").unwrap_or(5));
+
+/// Evidence formats the VM executor accepts, each backed by its own verifier.
+/// Comma-separated subset of `tpm`, `amd-sev-snp` and `intel-tdx`; defaults to all three.
+pub static VM_ATTESTATION_FORMATS: Lazy<Vec<String>> = Lazy::new(|| {
+    let formats = std::env::var("VM_ATTESTATION_FORMATS")
         .ok()
-        .and_then(|value| value.parse::<u64>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or(300)
+        .map(|value| {
+            value
+                .split(',')
+                .map(|item| item.trim().to_ascii_lowercase())
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if formats.is_empty() {
+        ["tpm", "amd-sev-snp", "intel-tdx"]
+            .map(str::to_string)
+            .to_vec()
+    } else {
+        formats
+    }
+});
+
+/// key: remediation-config -> promotion gate context merge
+///
+/// How a promotion request's `gate_context` combines with each plan target's own context on the
+/// runs it stages: `request_wins` (default), `target_wins`, or `deep_merge`. Unknown values are
+/// rejected at startup.
+pub static PROMOTION_GATE_CONTEXT_MERGE: Lazy<GateContextMerge> =
+    Lazy::new(|| setting("PROMOTION_GATE_CONTEXT_MERGE").unwrap_or_default());
+
+/// key: remediation-config -> promotion note templates
+///
+/// JSON object mapping a template name to a note template, e.g.
+/// `{"audit": "requested_by={requester} revision={revision} schema={schema_status}"}`, selected
+/// by promotion requests through `note_templates`. Provided via `PROMOTION_NOTE_TEMPLATES`;
+/// empty by default. A malformed template or unknown variable aborts startup.
+pub static PROMOTION_NOTE_TEMPLATES: Lazy<NoteTemplates> = Lazy::new(|| {
+    let value = json_from_env("PROMOTION_NOTE_TEMPLATES", json!({}));
+    NoteTemplates::from_json(&value)
+        .unwrap_or_else(|err| panic!("PROMOTION_NOTE_TEMPLATES is invalid: {err}"))
 });
 
+/// key: remediation-config -> workspace gate dependency graph
+///
+/// JSON object mapping a workspace gate (`schema`, `policy`, `simulation`, `promotion`) to the
+/// gates that must have passed before it may be applied. Provided via
+/// `REMEDIATION_GATE_DEPENDENCIES`; defaults to schema → policy → simulation → promotion.
+pub static REMEDIATION_GATE_DEPENDENCIES: Lazy<HashMap<String, Vec<String>>> = Lazy::new(|| {
+    let value = json_from_env(
+        "REMEDIATION_GATE_DEPENDENCIES",
+        json!({
+            "policy": ["schema"],
+            "simulation": ["policy"],
+            "promotion": ["simulation"]
+        }),
+    );
+    serde_json::from_value(value).unwrap_or_else(|err| {
+        panic!("REMEDIATION_GATE_DEPENDENCIES must map gate names to arrays of gate names: {err}")
+    })
+});
+
+/// key: remediation-config -> validation snapshot retention cadence
+pub static REMEDIATION_SNAPSHOT_PRUNE_INTERVAL_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("REMEDIATION_SNAPSHOT_PRUNE_INTERVAL_SECS").unwrap_or(3600));
+
+/// key: remediation-config -> run artifact retention age
+///
+/// Days a non-essential remediation artifact stays inline before the retention sweep archives
+/// or prunes it. `0` disables the sweep.
+pub static REMEDIATION_ARTIFACT_RETENTION_DAYS: Lazy<i64> =
+    Lazy::new(|| setting::<i64>("REMEDIATION_ARTIFACT_RETENTION_DAYS").unwrap_or(30));
+
+/// key: remediation-config -> run artifact retention cadence
+pub static REMEDIATION_ARTIFACT_SWEEP_INTERVAL_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("REMEDIATION_ARTIFACT_SWEEP_INTERVAL_SECS").unwrap_or(3600));
+
+/// key: remediation-config -> run artifact retention mode
+///
+/// What happens to an aged artifact: `archive` moves its metadata to the blob store and keeps
+/// a pointer row, `prune` deletes the row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactRetentionMode {
+    Archive,
+    Prune,
+}
+
+impl ArtifactRetentionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactRetentionMode::Archive => "archive",
+            ArtifactRetentionMode::Prune => "prune",
+        }
+    }
+}
+
+fn parse_artifact_retention_mode() -> ArtifactRetentionMode {
+    match read_optional_env("REMEDIATION_ARTIFACT_RETENTION_MODE") {
+        Some(raw) => match raw.to_ascii_lowercase().as_str() {
+            "archive" => ArtifactRetentionMode::Archive,
+            "prune" => ArtifactRetentionMode::Prune,
+            other => panic!(
+                "unsupported REMEDIATION_ARTIFACT_RETENTION_MODE value '{other}'; expected 'archive' or 'prune'"
+            ),
+        },
+        None => ArtifactRetentionMode::Archive,
+    }
+}
+
+pub static REMEDIATION_ARTIFACT_RETENTION_MODE: Lazy<ArtifactRetentionMode> =
+    Lazy::new(parse_artifact_retention_mode);
+
+/// key: ingestion-config -> batch size cap
+///
+/// Maximum number of documents the ingestion worker coalesces into a single vector store flush.
+pub static INGESTION_BATCH_MAX_SIZE: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INGESTION_BATCH_MAX_SIZE").unwrap_or(32));
+
+/// key: ingestion-config -> batch flush window
+pub static INGESTION_BATCH_FLUSH_INTERVAL_MS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("INGESTION_BATCH_FLUSH_INTERVAL_MS").unwrap_or(2000));
+
+/// key: ingestion-config -> bounded queue capacity
+///
+/// Pending documents allowed ahead of the batcher before producers wait on the vector store.
+pub static INGESTION_QUEUE_CAPACITY: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INGESTION_QUEUE_CAPACITY").unwrap_or(256));
+
+/// key: ingestion-config -> batched JSON wire format
+///
+/// Send each flush to `/ingest` as one `{"documents": [...]}` JSON body instead of one raw text
+/// request per document. Only for vector stores that accept it; off by default.
+pub static INGESTION_JSON_BATCHES: Lazy<bool> = Lazy::new(|| flag("INGESTION_JSON_BATCHES"));
+
+/// key: ingestion-config -> target chunk size
+///
+/// Characters per chunk the streaming extractor aims for before embedding.
+pub static INGESTION_CHUNK_SIZE: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INGESTION_CHUNK_SIZE").unwrap_or(2000));
+
+/// key: ingestion-config -> chunk overlap
+pub static INGESTION_CHUNK_OVERLAP: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INGESTION_CHUNK_OVERLAP").unwrap_or(200));
+
+/// key: ingestion-config -> chunk boundary tolerance
+///
+/// How far a chunk may stretch or shrink to end on a paragraph or sentence break.
+pub static INGESTION_CHUNK_BOUNDARY_TOLERANCE: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INGESTION_CHUNK_BOUNDARY_TOLERANCE").unwrap_or(200));
+
+/// key: domain-verification -> DNS-over-HTTPS resolver
+///
+/// JSON DoH endpoint used to look up `_mcp-challenge` TXT records.
+pub static DOMAIN_VERIFICATION_DOH_URL: Lazy<String> = Lazy::new(|| {
+    std::env::var("DOMAIN_VERIFICATION_DOH_URL")
+        .unwrap_or_else(|_| "https://cloudflare-dns.com/dns-query".to_string())
+});
+
+/// key: domain-verification -> minimum seconds between checks
+pub static DOMAIN_VERIFICATION_CHECK_INTERVAL_SECS: Lazy<i64> =
+    Lazy::new(|| setting::<i64>("DOMAIN_VERIFICATION_CHECK_INTERVAL_SECS").unwrap_or(30));
+
+/// key: invocation-timeout -> default upstream deadline in milliseconds
+pub static INVOCATION_TIMEOUT_MS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("INVOCATION_TIMEOUT_MS").unwrap_or(30_000));
+
+/// key: invocation-timeout -> ceiling for per-invocation overrides
+pub static INVOCATION_MAX_TIMEOUT_MS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("INVOCATION_MAX_TIMEOUT_MS").unwrap_or(300_000));
+
+/// key: invocation-batch -> most invocations of one batch in flight at once
+pub static INVOCATION_BATCH_MAX_CONCURRENCY: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INVOCATION_BATCH_MAX_CONCURRENCY").unwrap_or(8));
+
+/// key: invocation-cache -> maximum cached invocation results held in memory
+pub static INVOCATION_CACHE_MAX_ENTRIES: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("INVOCATION_CACHE_MAX_ENTRIES").unwrap_or(10_000));
+
+/// key: job-queue-leases -> seconds a claimed job may run before it is requeued
+pub static JOB_LEASE_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("JOB_LEASE_SECS").unwrap_or(300));
+
+/// key: job-queue-leases -> requeues allowed before a job is dead-lettered
+pub static JOB_MAX_REQUEUES: Lazy<i32> =
+    Lazy::new(|| setting::<i32>("JOB_MAX_REQUEUES").unwrap_or(3));
+
+/// key: job-queue-dedupe -> seconds a completed job keeps absorbing its dedupe key (0 = in-flight only)
+pub static JOB_DEDUPE_WINDOW_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("JOB_DEDUPE_WINDOW_SECS").unwrap_or(300));
+
+/// key: job-queue-poison -> handler failures before a job is dead-lettered
+pub static JOB_MAX_FAILURES: Lazy<i32> =
+    Lazy::new(|| setting::<i32>("JOB_MAX_FAILURES").unwrap_or(3));
+
+/// key: telemetry-tenant-metrics -> cap on orgs with their own `org_id` label; the rest
+/// share `other`
+pub static TENANT_METRICS_MAX_ORGS: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("TENANT_METRICS_MAX_ORGS").unwrap_or(50));
+
+/// key: telemetry-tenant-metrics -> samples an org records under `other` before it earns
+/// its own label
+pub static TENANT_METRICS_MIN_EVENTS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("TENANT_METRICS_MIN_EVENTS").unwrap_or(10));
+
+/// key: telemetry-otel -> OTLP/HTTP collector base url; unset disables trace export
+pub static OTEL_EXPORTER_OTLP_ENDPOINT: Lazy<Option<String>> =
+    Lazy::new(|| read_optional_env("OTEL_EXPORTER_OTLP_ENDPOINT"));
+
+/// key: telemetry-otel -> service.name resource attribute on exported spans
+pub static OTEL_SERVICE_NAME: Lazy<String> = Lazy::new(|| {
+    read_optional_env("OTEL_SERVICE_NAME").unwrap_or_else(|| "mcp-host-backend".to_string())
+});
+
+/// key: probes -> per-check deadline for /readyz dependency checks
+pub static READINESS_CHECK_TIMEOUT_MS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("READINESS_CHECK_TIMEOUT_MS").unwrap_or(2000));
+
+/// key: metrics-access -> bearer token `/metrics` scrapers must present; unset leaves it open
+pub static METRICS_BEARER_TOKEN: Lazy<Option<String>> =
+    Lazy::new(|| read_optional_env("METRICS_BEARER_TOKEN"));
+
+/// key: metrics-access -> comma-separated addresses or CIDR networks allowed to scrape
+/// `/metrics`; empty allows any source
+pub static METRICS_ALLOWED_SOURCES: Lazy<Vec<AllowedSource>> = Lazy::new(|| {
+    read_optional_env("METRICS_ALLOWED_SOURCES")
+        .map(|value| {
+            value
+                .split(',')
+                .filter(|entry| !entry.trim().is_empty())
+                .map(|entry| {
+                    AllowedSource::parse(entry)
+                        .unwrap_or_else(|err| panic!("METRICS_ALLOWED_SOURCES: {err}"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+});
+
+/// key: cors -> `*` (the default, for development) or comma-separated browser origins
+pub static CORS_ALLOWED_ORIGINS: Lazy<AllowedOrigins> = Lazy::new(|| {
+    AllowedOrigins::parse(
+        read_optional_env("CORS_ALLOWED_ORIGINS")
+            .as_deref()
+            .unwrap_or("*"),
+    )
+    .unwrap_or_else(|err| panic!("CORS_ALLOWED_ORIGINS: {err}"))
+});
+
+/// key: cors -> refuse to start with a wildcard origin; set in production
+pub static CORS_REQUIRE_ALLOWLIST: Lazy<bool> = Lazy::new(|| flag("CORS_REQUIRE_ALLOWLIST"));
+
+/// key: cors -> send `Access-Control-Allow-Credentials` so cookies reach the API
+pub static CORS_ALLOW_CREDENTIALS: Lazy<bool> = Lazy::new(|| flag("CORS_ALLOW_CREDENTIALS"));
+
+/// key: cors -> methods allowed in preflights
+pub static CORS_ALLOWED_METHODS: Lazy<Vec<axum::http::Method>> = Lazy::new(|| {
+    cors::parse_methods(
+        read_optional_env("CORS_ALLOWED_METHODS")
+            .as_deref()
+            .unwrap_or(cors::DEFAULT_METHODS),
+    )
+    .unwrap_or_else(|err| panic!("CORS_ALLOWED_METHODS: {err}"))
+});
+
+/// key: cors -> request headers allowed in preflights
+pub static CORS_ALLOWED_HEADERS: Lazy<Vec<axum::http::HeaderName>> = Lazy::new(|| {
+    cors::parse_headers(
+        read_optional_env("CORS_ALLOWED_HEADERS")
+            .as_deref()
+            .unwrap_or(cors::DEFAULT_HEADERS),
+    )
+    .unwrap_or_else(|err| panic!("CORS_ALLOWED_HEADERS: {err}"))
+});
+
+/// key: cors -> seconds a browser may cache a preflight answer
+pub static CORS_MAX_AGE_SECONDS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("CORS_MAX_AGE_SECONDS").unwrap_or(600));
+
+/// key: request-limits -> seconds before a non-streaming request is answered with 504
+pub static REQUEST_TIMEOUT_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("REQUEST_TIMEOUT_SECS").unwrap_or(60));
+
+/// key: request-limits -> concurrent requests before new ones are shed with 503
+pub static MAX_IN_FLIGHT_REQUESTS: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("MAX_IN_FLIGHT_REQUESTS").unwrap_or(512));
+
+/// key: webhooks-dispatch -> delivery attempts before an event is dead-lettered
+pub static WEBHOOK_MAX_ATTEMPTS: Lazy<i32> =
+    Lazy::new(|| setting::<i32>("WEBHOOK_MAX_ATTEMPTS").unwrap_or(8));
+
+/// key: webhooks-dispatch -> first retry delay, doubled per failed attempt
+pub static WEBHOOK_RETRY_BASE_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("WEBHOOK_RETRY_BASE_SECS").unwrap_or(10));
+
+/// key: webhooks-dispatch -> longest delay between retries
+pub static WEBHOOK_RETRY_MAX_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("WEBHOOK_RETRY_MAX_SECS").unwrap_or(3600));
+
+/// key: webhooks-dispatch -> per-delivery request timeout
+pub static WEBHOOK_REQUEST_TIMEOUT_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("WEBHOOK_REQUEST_TIMEOUT_SECS").unwrap_or(10));
+
+/// key: webhooks-dispatch -> how often due deliveries are polled
+pub static WEBHOOK_POLL_INTERVAL_MS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("WEBHOOK_POLL_INTERVAL_MS").unwrap_or(1000));
+
+/// key: webhooks-dispatch -> allow loopback, private and link-local targets; local development only
+pub static WEBHOOK_ALLOW_PRIVATE_TARGETS: Lazy<bool> =
+    Lazy::new(|| flag("WEBHOOK_ALLOW_PRIVATE_TARGETS"));
+
+/// key: artifact-attestation -> base64 Ed25519 seed that signs build provenance
+pub static BUILD_ATTESTATION_SIGNING_KEY: Lazy<Option<String>> =
+    Lazy::new(|| read_optional_env("BUILD_ATTESTATION_SIGNING_KEY"));
+
+/// key: artifact-attestation -> extra base64 Ed25519 public keys accepted when verifying
+pub static BUILD_ATTESTATION_TRUST_ROOTS: Lazy<Vec<String>> = Lazy::new(|| {
+    std::env::var("BUILD_ATTESTATION_TRUST_ROOTS")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+});
+
+/// key: artifact-attestation -> builder.id recorded in provenance statements
+pub static BUILD_ATTESTATION_BUILDER_ID: Lazy<String> = Lazy::new(|| {
+    read_optional_env("BUILD_ATTESTATION_BUILDER_ID")
+        .unwrap_or_else(|| "mcp-host/build-worker".to_string())
+});
+
+/// key: registry-gc -> days a build's digest is kept regardless of references
+pub static REGISTRY_GC_RETENTION_DAYS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("REGISTRY_GC_RETENTION_DAYS").unwrap_or(14));
+
+/// key: lifecycle-console -> seconds an SSE stream reuses a looked-up override actor email
+pub static LIFECYCLE_CONSOLE_ACTOR_CACHE_TTL_SECONDS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("LIFECYCLE_CONSOLE_ACTOR_CACHE_TTL_SECONDS").unwrap_or(300));
+
+/// key: lifecycle-console -> validate every retry ledger entry and report the malformed ones
+pub static LIFECYCLE_CONSOLE_STRICT_RETRY_LEDGER: Lazy<bool> =
+    Lazy::new(|| flag("LIFECYCLE_CONSOLE_STRICT_RETRY_LEDGER"));
+
+/// key: lifecycle-console -> deepest JSON nesting the metadata walkers descend into
+pub static LIFECYCLE_CONSOLE_JSON_MAX_DEPTH: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("LIFECYCLE_CONSOLE_JSON_MAX_DEPTH").unwrap_or(64));
+
+/// key: lifecycle-console -> promotion verdict attribution
+///
+/// Which posture a run without a recorded verdict is shown with: `most_recent` (default),
+/// `matching_stage`, or `most_restrictive`. Unknown values are rejected at startup.
+pub static LIFECYCLE_CONSOLE_VERDICT_STRATEGY: Lazy<VerdictStrategy> =
+    Lazy::new(|| setting("LIFECYCLE_CONSOLE_VERDICT_STRATEGY").unwrap_or_default());
+
+/// key: lifecycle-console -> JSON keys (`api_token`) or dotted paths (`credentials.password`)
+/// masked in lifecycle snapshots for viewers without an unredacted role
+pub static LIFECYCLE_CONSOLE_REDACTED_FIELDS: Lazy<Vec<String>> = Lazy::new(|| {
+    std::env::var("LIFECYCLE_CONSOLE_REDACTED_FIELDS")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .filter_map(|item| {
+                    let trimmed = item.trim();
+                    if trimmed.is_empty() {
+                        None
+                    } else {
+                        Some(trimmed.to_string())
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+});
+
+/// key: lifecycle-console -> user roles that see lifecycle snapshots unredacted
+pub static LIFECYCLE_CONSOLE_UNREDACTED_ROLES: Lazy<Vec<String>> = Lazy::new(|| {
+    std::env::var("LIFECYCLE_CONSOLE_UNREDACTED_ROLES")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .filter_map(|item| {
+                    let trimmed = item.trim();
+                    if trimmed.is_empty() {
+                        None
+                    } else {
+                        Some(trimmed.to_string())
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_else(|| vec!["admin".to_string()])
+});
+
+/// key: release-train -> promotion track tiers, lowest first
+///
+/// Comma-separated via `PROMOTION_TRACK_TIERS`. Track tiers must be one of these, and track
+/// stages named after a tier must follow this order.
+pub static PROMOTION_TRACK_TIERS: Lazy<Vec<String>> = Lazy::new(|| {
+    std::env::var("PROMOTION_TRACK_TIERS")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .filter_map(|item| {
+                    let trimmed = item.trim();
+                    if trimmed.is_empty() {
+                        None
+                    } else {
+                        Some(trimmed.to_lowercase())
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|tiers| !tiers.is_empty())
+        .unwrap_or_else(|| {
+            vec![
+                "pilot".to_string(),
+                "staging".to_string(),
+                "production".to_string(),
+            ]
+        })
+});
+
+/// key: release-train -> promotion auto-advance sweep cadence
+pub static PROMOTION_AUTO_ADVANCE_INTERVAL_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("PROMOTION_AUTO_ADVANCE_INTERVAL_SECS").unwrap_or(60));
+
+/// key: lifecycle-console -> promotion veto reason aliases
+///
+/// JSON object mapping a canonical veto code to the phrasings producers use for it, e.g.
+/// `{"attestation.pending": ["attestation pending", "pending attestation"]}`. Provided via
+/// `PROMOTION_VETO_REASON_ALIASES`; empty by default, so reasons are reported as emitted.
+pub static PROMOTION_VETO_REASON_ALIASES: Lazy<HashMap<String, Vec<String>>> = Lazy::new(|| {
+    let value = json_from_env("PROMOTION_VETO_REASON_ALIASES", json!({}));
+    serde_json::from_value(value).unwrap_or_else(|err| {
+        panic!("PROMOTION_VETO_REASON_ALIASES must map codes to arrays of phrasings: {err}")
+    })
+});
+
+/// key: proxy-config -> default request body cap
+///
+/// Largest invoke request body, in bytes, forwarded to a backing MCP server unless the server
+/// carries its own `proxy_max_request_bytes` override.
+pub static PROXY_MAX_REQUEST_BYTES: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("PROXY_MAX_REQUEST_BYTES").unwrap_or(2 * 1024 * 1024));
+
+/// key: proxy-config -> default response body cap
+pub static PROXY_MAX_RESPONSE_BYTES: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("PROXY_MAX_RESPONSE_BYTES").unwrap_or(16 * 1024 * 1024));
+
+/// key: proxy-config -> circuit breaker failure threshold
+///
+/// Consecutive upstream failures that open a server's proxy circuit breaker.
+pub static PROXY_BREAKER_FAILURE_THRESHOLD: Lazy<u32> =
+    Lazy::new(|| setting::<u32>("PROXY_BREAKER_FAILURE_THRESHOLD").unwrap_or(5));
+
+/// key: proxy-config -> circuit breaker cooldown
+pub static PROXY_BREAKER_COOLDOWN_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("PROXY_BREAKER_COOLDOWN_SECS").unwrap_or(30));
+
+/// key: proxy-config -> upstream pool idle connections
+///
+/// Idle keep-alive connections each upstream server's client keeps for reuse.
+pub static PROXY_POOL_MAX_IDLE_PER_HOST: Lazy<usize> =
+    Lazy::new(|| setting::<usize>("PROXY_POOL_MAX_IDLE_PER_HOST").unwrap_or(32));
+
+/// key: proxy-config -> upstream pool idle timeout
+pub static PROXY_POOL_IDLE_TIMEOUT_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("PROXY_POOL_IDLE_TIMEOUT_SECS").unwrap_or(90));
+
+/// key: proxy-config -> upstream TCP keep-alive
+///
+/// Interval between TCP keep-alive probes on upstream connections; `0` turns them off.
+pub static PROXY_TCP_KEEPALIVE_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("PROXY_TCP_KEEPALIVE_SECS").unwrap_or(60));
+
+/// key: proxy-config -> upstream connect timeout
+pub static PROXY_CONNECT_TIMEOUT_MS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("PROXY_CONNECT_TIMEOUT_MS").unwrap_or(2_000));
+
+/// key: proxy-config -> capability routing strategy
+///
+/// How capability invocations pick a server: `best_score` (default), `round_robin`, or
+/// `weighted`. Unknown values are rejected at startup.
+pub static PROXY_ROUTING_STRATEGY: Lazy<RoutingStrategy> =
+    Lazy::new(|| setting("PROXY_ROUTING_STRATEGY").unwrap_or(RoutingStrategy::BestScore));
+
+/// key: server-config -> soft-delete sweep cadence
+pub static SERVER_DELETE_SWEEP_INTERVAL_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("SERVER_DELETE_SWEEP_INTERVAL_SECS").unwrap_or(3600));
+
+/// key: secrets-config -> version retention
+///
+/// Number of versions kept per server secret; rotations prune anything older.
+pub static SECRET_VERSION_RETENTION: Lazy<i32> =
+    Lazy::new(|| setting::<i32>("SECRET_VERSION_RETENTION").unwrap_or(5));
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VmProvisionerDriver {
     Http,
@@ -271,21 +770,28 @@ pub fn libvirt_provisioning_config_from_env() -> LibvirtProvisioningConfig {
     };
 
     let default_isolation_tier = read_optional_env("LIBVIRT_DEFAULT_ISOLATION_TIER");
-    let default_memory_mib = std::env::var("LIBVIRT_DEFAULT_MEMORY_MIB")
-        .ok()
-        .and_then(|value| value.trim().parse::<u64>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or(4096);
-    let default_vcpu_count = std::env::var("LIBVIRT_DEFAULT_VCPU_COUNT")
-        .ok()
-        .and_then(|value| value.trim().parse::<u32>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or(4);
-    let log_tail = std::env::var("LIBVIRT_LOG_TAIL")
-        .ok()
-        .and_then(|value| value.trim().parse::<usize>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or_else(|| *VM_LOG_TAIL_LINES);
+    let default_memory_mib = setting::<u64>("LIBVIRT_DEFAULT_MEMORY_MIB").unwrap_or(4096);
+    let default_vcpu_count = setting::<u32>("LIBVIRT_DEFAULT_VCPU_COUNT").unwrap_or(4);
+    let resource_profiles: HashMap<String, LibvirtResourceProfile> =
+        serde_json::from_value(json_from_env(
+            "LIBVIRT_RESOURCE_PROFILES",
+            json!({
+                "small": { "vcpu_count": 2, "memory_mib": 2048 },
+                "medium": { "vcpu_count": default_vcpu_count, "memory_mib": default_memory_mib },
+                "large": { "vcpu_count": 8, "memory_mib": 16384 }
+            }),
+        ))
+        .unwrap_or_else(|err| {
+            panic!("LIBVIRT_RESOURCE_PROFILES must map names to vcpu_count/memory_mib: {err}")
+        });
+    let default_resource_profile = read_optional_env("LIBVIRT_DEFAULT_RESOURCE_PROFILE")
+        .unwrap_or_else(|| "medium".to_string());
+    if !resource_profiles.contains_key(&default_resource_profile) {
+        panic!(
+            "LIBVIRT_DEFAULT_RESOURCE_PROFILE '{default_resource_profile}' is not defined in LIBVIRT_RESOURCE_PROFILES"
+        );
+    }
+    let log_tail = setting::<usize>("LIBVIRT_LOG_TAIL").unwrap_or_else(|| *VM_LOG_TAIL_LINES);
 
     let network_template = json_from_env(
         "LIBVIRT_NETWORK_TEMPLATE",
@@ -312,6 +818,8 @@ pub fn libvirt_provisioning_config_from_env() -> LibvirtProvisioningConfig {
         default_isolation_tier,
         default_memory_mib,
         default_vcpu_count,
+        resource_profiles,
+        default_resource_profile,
         log_tail,
         network_template,
         volume_template,
@@ -322,3 +830,105 @@ pub fn libvirt_provisioning_config_from_env() -> LibvirtProvisioningConfig {
 
 pub static LIBVIRT_PROVISIONING_CONFIG: Lazy<LibvirtProvisioningConfig> =
     Lazy::new(|| libvirt_provisioning_config_from_env());
+
+/// key: auth-jwt-keys -> `JWT_ALGORITHM` picks the signing key: `JWT_SECRET` for HS256,
+/// or the PEM files in `JWT_PRIVATE_KEY_FILE` and `JWT_PUBLIC_KEY_FILE` for RS256 and
+/// ES256. `JWT_PREVIOUS_KEYS` lists rotated-out `ALG:value` keys that still verify.
+/// `JWT_AUDIENCE` and `JWT_ISSUER` are stamped on and required of tokens when set.
+pub fn jwt_keys_from_env() -> Result<JwtKeys, String> {
+    let algorithm = parse_algorithm(
+        read_optional_env("JWT_ALGORITHM")
+            .as_deref()
+            .unwrap_or("HS256"),
+    )?;
+    let keys = match algorithm {
+        jsonwebtoken::Algorithm::HS256 => JwtKeys::hmac(JWT_SECRET.as_str()),
+        _ => {
+            let read_pem = |key: &str| {
+                let path = read_optional_env(key)
+                    .ok_or_else(|| format!("{key} must be set for {algorithm:?}"))?;
+                fs::read(&path).map_err(|err| format!("failed to read {key} ({path}): {err}"))
+            };
+            JwtKeys::new(
+                SigningKey::from_pem(algorithm, &read_pem("JWT_PRIVATE_KEY_FILE")?)?,
+                VerificationKey::from_pem(algorithm, &read_pem("JWT_PUBLIC_KEY_FILE")?)?,
+                Vec::new(),
+            )
+        }
+    };
+    let previous = read_optional_env("JWT_PREVIOUS_KEYS")
+        .map(|value| {
+            value
+                .split(',')
+                .filter(|spec| !spec.trim().is_empty())
+                .enumerate()
+                .map(|(index, spec)| {
+                    VerificationKey::parse(spec)
+                        .map_err(|err| format!("JWT_PREVIOUS_KEYS entry {index}: {err}"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    Ok(keys
+        .with_previous(previous)
+        .with_audience(read_optional_env("JWT_AUDIENCE"))
+        .with_issuer(read_optional_env("JWT_ISSUER")))
+}
+
+/// key: db-pool -> postgres pool sizing; unset values keep the defaults
+pub fn db_pool_config_from_env() -> DbPoolConfig {
+    let defaults = DbPoolConfig::default();
+    let max_connections =
+        setting::<u32>("DB_POOL_MAX_CONNECTIONS").unwrap_or(defaults.max_connections);
+    let min_connections = setting::<u32>("DB_POOL_MIN_CONNECTIONS")
+        .unwrap_or(defaults.min_connections)
+        .min(max_connections);
+    let acquire_timeout = setting::<u64>("DB_POOL_ACQUIRE_TIMEOUT_SECS")
+        .map(Duration::from_secs)
+        .unwrap_or(defaults.acquire_timeout);
+    // 0 disables idle reaping
+    let idle_timeout = match setting::<u64>("DB_POOL_IDLE_TIMEOUT_SECS") {
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None => defaults.idle_timeout,
+    };
+    DbPoolConfig {
+        max_connections,
+        min_connections,
+        acquire_timeout,
+        idle_timeout,
+    }
+}
+
+pub static DB_POOL_CONFIG: Lazy<DbPoolConfig> = Lazy::new(db_pool_config_from_env);
+
+/// key: db-pool -> seconds between pool utilization samples
+pub static DB_POOL_STATS_INTERVAL_SECS: Lazy<u64> =
+    Lazy::new(|| setting::<u64>("DB_POOL_STATS_INTERVAL_SECS").unwrap_or(30));
+
+/// key: marketplace-config -> publish intelligence floor
+///
+/// When set, every capability score must reach this value before a listing can
+/// be published; otherwise the per-capability `intelligence::minimum_threshold` applies.
+pub static MARKETPLACE_PUBLISH_MIN_SCORE: Lazy<Option<f32>> =
+    Lazy::new(|| setting::<f32>("MARKETPLACE_PUBLISH_MIN_SCORE"));
+
+/// key: marketplace-config -> publish intelligence confidence
+pub static MARKETPLACE_PUBLISH_MIN_CONFIDENCE: Lazy<f32> =
+    Lazy::new(|| setting::<f32>("MARKETPLACE_PUBLISH_MIN_CONFIDENCE").unwrap_or(0.5));
+
+/// key: evaluation-config -> regression tolerance
+///
+/// Largest score drop between two evaluation runs that is not reported as a regression.
+pub static EVALUATION_REGRESSION_TOLERANCE: Lazy<f64> =
+    Lazy::new(|| setting::<f64>("EVALUATION_REGRESSION_TOLERANCE").unwrap_or(0.05));
+
+/// key: evaluation-config -> cron schedule timezone
+///
+/// Wall clock used to evaluate evaluation cron schedules: `UTC` or a fixed offset such as `+02:00`.
+pub static EVALUATION_SCHEDULE_TZ: Lazy<chrono::FixedOffset> = Lazy::new(|| {
+    read_optional_env("EVALUATION_SCHEDULE_TZ")
+        .and_then(|value| crate::evaluations::cron::parse_utc_offset(&value))
+        .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).expect("zero offset"))
+});
@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::{PoisonError, RwLock};
+
+use axum::Json;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{info, warn};
+
+use super::validation::{self, ConfigErrors};
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+
+// key: config-reload -> non-secret settings re-read on SIGHUP
+
+/// Env vars re-read by [`reload`]. Everything else, including secrets and bind settings,
+/// only changes on restart.
+pub const RELOADABLE_VARS: &[&str] = &[
+    "REMEDIATION_POLL_MIN_INTERVAL_MS",
+    "REMEDIATION_POLL_MAX_INTERVAL_MS",
+    "LIFECYCLE_CONSOLE_MAX_LIMIT",
+    "LIFECYCLE_CONSOLE_MAX_RUN_LIMIT",
+    "REMEDIATION_SNAPSHOT_RETENTION",
+    "SERVER_DELETE_RETENTION_HOURS",
+    "INVOCATION_BATCH_MAX_ITEMS",
+];
+
+/// The reloadable settings in effect. Field names are the lowercased env vars; consumers
+/// read them through [`current`] each time they apply one, so a reload reaches them
+/// without a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReloadableSettings {
+    /// Shortest wait, in milliseconds, between remediation worker polls. The worker starts
+    /// here and returns here whenever it finds work.
+    pub remediation_poll_min_interval_ms: u64,
+    /// Longest wait, in milliseconds, an idle remediation worker backs off to. Values below
+    /// the floor are raised to it.
+    pub remediation_poll_max_interval_ms: u64,
+    /// Most workspaces a lifecycle console page returns.
+    pub lifecycle_console_max_limit: u32,
+    /// Most recent runs the lifecycle console returns per workspace.
+    pub lifecycle_console_max_run_limit: u32,
+    /// Validation snapshots kept per `(revision, snapshot_type)` by the retention sweep; at
+    /// least `1` so the latest snapshot of each type always survives.
+    pub remediation_snapshot_retention: i64,
+    /// Hours a deleted server can still be restored before the sweep removes it for good.
+    pub server_delete_retention_hours: i64,
+    /// Most invocations one batch may hold.
+    pub invocation_batch_max_items: usize,
+}
+
+impl ReloadableSettings {
+    /// Reads every reloadable setting from the process environment, rejecting the whole set
+    /// if any value is invalid.
+    pub fn from_env() -> Result<Self, ConfigErrors> {
+        Self::from_lookup(|var| std::env::var(var).ok())
+    }
+
+    /// Reads every reloadable setting from `lookup`, rejecting the whole set if any value is
+    /// invalid.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigErrors> {
+        let lookup = |var: &str| {
+            lookup(var)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        validation::validate_vars(RELOADABLE_VARS, lookup)?;
+        Ok(Self {
+            remediation_poll_min_interval_ms: parsed(lookup("REMEDIATION_POLL_MIN_INTERVAL_MS"))
+                .unwrap_or(250),
+            remediation_poll_max_interval_ms: parsed(lookup("REMEDIATION_POLL_MAX_INTERVAL_MS"))
+                .unwrap_or(10_000),
+            lifecycle_console_max_limit: parsed(lookup("LIFECYCLE_CONSOLE_MAX_LIMIT"))
+                .unwrap_or(100),
+            lifecycle_console_max_run_limit: parsed(lookup("LIFECYCLE_CONSOLE_MAX_RUN_LIMIT"))
+                .unwrap_or(10),
+            remediation_snapshot_retention: parsed(lookup("REMEDIATION_SNAPSHOT_RETENTION"))
+                .unwrap_or(20),
+            server_delete_retention_hours: parsed(lookup("SERVER_DELETE_RETENTION_HOURS"))
+                .unwrap_or(168),
+            invocation_batch_max_items: parsed(lookup("INVOCATION_BATCH_MAX_ITEMS")).unwrap_or(50),
+        })
+    }
+
+    /// The settings keyed by env var.
+    fn by_var(&self) -> BTreeMap<String, Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(fields)) => fields
+                .into_iter()
+                .map(|(field, value)| (field.to_ascii_uppercase(), value))
+                .collect(),
+            _ => BTreeMap::new(),
+        }
+    }
+}
+
+/// A validated value; unset keeps the caller's default.
+fn parsed<T: FromStr>(raw: Option<String>) -> Option<T> {
+    raw?.parse().ok()
+}
+
+struct Loaded {
+    settings: ReloadableSettings,
+    loaded_at: DateTime<Utc>,
+    reloads: u64,
+    last_reload_error: Option<String>,
+}
+
+static LOADED: Lazy<RwLock<Loaded>> = Lazy::new(|| {
+    RwLock::new(Loaded {
+        settings: ReloadableSettings::from_env().unwrap_or_else(|err| panic!("{err}")),
+        loaded_at: Utc::now(),
+        reloads: 0,
+        last_reload_error: None,
+    })
+});
+
+/// The reloadable settings in effect right now.
+pub fn current() -> ReloadableSettings {
+    LOADED
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .settings
+}
+
+/// Re-reads the reloadable settings and returns the env vars whose values changed. Entries in
+/// `.env` win over the process environment, so edits to the file apply without touching it.
+/// If any value is invalid nothing is applied and the error is kept for [`effective`].
+pub fn reload() -> Result<Vec<String>, ConfigErrors> {
+    let env_file = reloadable_env_file_entries();
+    let next = ReloadableSettings::from_lookup(|var| {
+        env_file
+            .get(var)
+            .cloned()
+            .or_else(|| std::env::var(var).ok())
+    });
+    let mut loaded = LOADED.write().unwrap_or_else(PoisonError::into_inner);
+    match next {
+        Ok(next) => {
+            let before = loaded.settings.by_var();
+            let changed = next
+                .by_var()
+                .into_iter()
+                .filter(|(var, value)| before.get(var) != Some(value))
+                .map(|(var, _)| var)
+                .collect();
+            loaded.settings = next;
+            loaded.loaded_at = Utc::now();
+            loaded.reloads += 1;
+            loaded.last_reload_error = None;
+            Ok(changed)
+        }
+        Err(err) => {
+            loaded.last_reload_error = Some(err.to_string());
+            Err(err)
+        }
+    }
+}
+
+/// The reloadable entries of `.env`, if there is one. Other entries, secrets included, wait
+/// for a restart.
+fn reloadable_env_file_entries() -> HashMap<String, String> {
+    let Ok(entries) = dotenvy::dotenv_iter() else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter(|(var, _)| RELOADABLE_VARS.contains(&var.as_str()))
+        .collect()
+}
+
+/// Calls [`reload`] on every SIGHUP. The signal handler is installed before this returns, so
+/// a SIGHUP sent afterwards never falls through to the default terminate action.
+pub fn spawn_sighup_reload() -> std::io::Result<()> {
+    let mut hangups = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            match reload() {
+                Ok(changed) => info!(?changed, "reloaded configuration on SIGHUP"),
+                Err(err) => warn!(%err, "rejected configuration reload; previous values kept"),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    /// Reloadable settings in effect, keyed by env var.
+    pub settings: BTreeMap<String, Value>,
+    /// When these values were read: startup, or the latest successful reload.
+    pub loaded_at: DateTime<Utc>,
+    /// Successful reloads since startup.
+    pub reloads: u64,
+    /// Why the latest reload was rejected, if it was.
+    pub last_reload_error: Option<String>,
+}
+
+pub fn effective() -> EffectiveConfig {
+    let loaded = LOADED.read().unwrap_or_else(PoisonError::into_inner);
+    EffectiveConfig {
+        settings: loaded.settings.by_var(),
+        loaded_at: loaded.loaded_at,
+        reloads: loaded.reloads,
+        last_reload_error: loaded.last_reload_error.clone(),
+    }
+}
+
+/// `GET /api/admin/config/effective`
+pub async fn effective_config(AuthUser { role, .. }: AuthUser) -> AppResult<Json<EffectiveConfig>> {
+    if role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+    Ok(Json(effective()))
+}
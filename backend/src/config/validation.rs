@@ -0,0 +1,629 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::auth::jwt::parse_algorithm;
+use crate::cors::{self, AllowedOrigins};
+use crate::lifecycle_console::verdict_selection::VerdictStrategy;
+use crate::metrics_access::AllowedSource;
+use crate::proxy::routing::RoutingStrategy;
+use crate::remediation::gate_context::GateContextMerge;
+use crate::remediation::note_templates::NoteTemplates;
+use crate::remediation::WorkspaceGateGraph;
+use crate::runtime::LibvirtResourceProfile;
+use crate::vault::LocalKeyWrapper;
+
+// key: config-validation -> startup check and typed accessors for env-backed settings
+
+const U16: i128 = u16::MAX as i128;
+const U32: i128 = u32::MAX as i128;
+const U64: i128 = u64::MAX as i128;
+const USIZE: i128 = usize::MAX as i128;
+const I32: i128 = i32::MAX as i128;
+const I64: i128 = i64::MAX as i128;
+
+const TRUTHY: &[&str] = &["1", "true", "yes", "on"];
+const FALSY: &[&str] = &["0", "false", "no", "off"];
+
+/// Longest slice of an offending value quoted back in a [`ConfigIssue`].
+const QUOTED_VALUE_CHARS: usize = 64;
+
+/// What a setting's value must look like. Unset and blank values always pass and keep the
+/// setting's default.
+#[derive(Debug, Clone, Copy)]
+pub enum Rule {
+    Int {
+        min: i128,
+        max: i128,
+    },
+    Float {
+        min: f64,
+        max: f64,
+    },
+    /// A boolean spelled as one of `1`/`true`/`yes`/`on` or `0`/`false`/`no`/`off`.
+    Flag,
+    /// One of the listed words, compared case-insensitively.
+    OneOf(&'static [&'static str]),
+    Json,
+    /// A setting-specific parser returning why the value was rejected.
+    Check(fn(&str) -> Result<(), String>),
+}
+
+impl Rule {
+    pub fn check(&self, raw: &str) -> Result<(), String> {
+        match *self {
+            Rule::Int { min, max } => match raw.parse::<i128>() {
+                Ok(value) if (min..=max).contains(&value) => Ok(()),
+                _ if max < I64 => Err(format!("expected an integer from {min} to {max}")),
+                _ => Err(format!("expected an integer of at least {min}")),
+            },
+            Rule::Float { min, max } => match raw.parse::<f64>() {
+                Ok(value) if (min..=max).contains(&value) => Ok(()),
+                _ if max.is_finite() => Err(format!("expected a number from {min} to {max}")),
+                _ => Err(format!("expected a number of at least {min}")),
+            },
+            Rule::Flag => {
+                let normalized = raw.to_ascii_lowercase();
+                if TRUTHY.contains(&normalized.as_str()) || FALSY.contains(&normalized.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected one of {}, {}",
+                        TRUTHY.join(", "),
+                        FALSY.join(", ")
+                    ))
+                }
+            }
+            Rule::OneOf(words) => {
+                if words.iter().any(|word| word.eq_ignore_ascii_case(raw)) {
+                    Ok(())
+                } else {
+                    Err(format!("expected one of {}", words.join(", ")))
+                }
+            }
+            Rule::Json => serde_json::from_str::<Value>(raw)
+                .map(drop)
+                .map_err(|err| format!("expected JSON: {err}")),
+            Rule::Check(check) => check(raw),
+        }
+    }
+}
+
+/// Every env-backed setting with a typed value, in `config` order. Accessors look their
+/// rule up here, so a new setting read through [`setting`] or [`flag`] must be listed.
+pub const SETTINGS: &[(&str, Rule)] = &[
+    ("JWT_ALGORITHM", Rule::Check(jwt_algorithm)),
+    ("BIND_PORT", int(0, U16)),
+    ("BILLING_RENEWAL_SCAN_INTERVAL_SECS", int(1, U64)),
+    ("BILLING_PAST_DUE_GRACE_DAYS", int(0, I64)),
+    ("VM_LOG_TAIL_LINES", int(64, USIZE)),
+    ("VM_ATTESTATION_MAX_AGE_SECONDS", int(1, U64)),
+    ("VM_ATTESTATION_CLOCK_SKEW_SECONDS", int(0, U64)),
+    ("GOVERNANCE_GATE_CACHE_TTL_SECONDS", int(0, U64)),
+    ("REMEDIATION_SNAPSHOT_RETENTION", int(1, I64)),
+    ("REMEDIATION_SNAPSHOT_PRUNE_INTERVAL_SECS", int(1, U64)),
+    ("REMEDIATION_POLL_MIN_INTERVAL_MS", int(1, U64)),
+    ("REMEDIATION_POLL_MAX_INTERVAL_MS", int(1, U64)),
+    ("REMEDIATION_ARTIFACT_RETENTION_DAYS", int(0, I64)),
+    ("REMEDIATION_ARTIFACT_SWEEP_INTERVAL_SECS", int(1, U64)),
+    ("INGESTION_BATCH_MAX_SIZE", int(1, USIZE)),
+    ("INGESTION_BATCH_FLUSH_INTERVAL_MS", int(1, U64)),
+    ("INGESTION_QUEUE_CAPACITY", int(1, USIZE)),
+    ("INGESTION_JSON_BATCHES", Rule::Flag),
+    ("INGESTION_CHUNK_SIZE", int(1, USIZE)),
+    ("INGESTION_CHUNK_OVERLAP", int(0, USIZE)),
+    ("INGESTION_CHUNK_BOUNDARY_TOLERANCE", int(0, USIZE)),
+    ("DOMAIN_VERIFICATION_CHECK_INTERVAL_SECS", int(0, I64)),
+    ("INVOCATION_TIMEOUT_MS", int(1, U64)),
+    ("INVOCATION_MAX_TIMEOUT_MS", int(1, U64)),
+    ("INVOCATION_BATCH_MAX_ITEMS", int(1, USIZE)),
+    ("INVOCATION_BATCH_MAX_CONCURRENCY", int(1, USIZE)),
+    ("INVOCATION_CACHE_MAX_ENTRIES", int(0, USIZE)),
+    ("JOB_LEASE_SECS", int(1, U64)),
+    ("JOB_MAX_REQUEUES", int(0, I32)),
+    ("JOB_DEDUPE_WINDOW_SECS", int(0, U64)),
+    ("JOB_MAX_FAILURES", int(1, I32)),
+    ("TENANT_METRICS_MAX_ORGS", int(0, USIZE)),
+    ("TENANT_METRICS_MIN_EVENTS", int(1, U64)),
+    ("READINESS_CHECK_TIMEOUT_MS", int(1, U64)),
+    ("CORS_MAX_AGE_SECONDS", int(0, U64)),
+    ("REQUEST_TIMEOUT_SECS", int(1, U64)),
+    ("MAX_IN_FLIGHT_REQUESTS", int(1, USIZE)),
+    ("WEBHOOK_MAX_ATTEMPTS", int(1, I32)),
+    ("WEBHOOK_RETRY_BASE_SECS", int(0, U64)),
+    ("WEBHOOK_RETRY_MAX_SECS", int(0, U64)),
+    ("WEBHOOK_REQUEST_TIMEOUT_SECS", int(1, U64)),
+    ("WEBHOOK_POLL_INTERVAL_MS", int(1, U64)),
+    ("WEBHOOK_ALLOW_PRIVATE_TARGETS", Rule::Flag),
+    ("REGISTRY_GC_RETENTION_DAYS", int(0, U64)),
+    ("LIFECYCLE_CONSOLE_MAX_LIMIT", int(1, U32)),
+    ("LIFECYCLE_CONSOLE_MAX_RUN_LIMIT", int(1, U32)),
+    ("LIFECYCLE_CONSOLE_ACTOR_CACHE_TTL_SECONDS", int(0, U64)),
+    ("LIFECYCLE_CONSOLE_JSON_MAX_DEPTH", int(1, USIZE)),
+    ("PROMOTION_AUTO_ADVANCE_INTERVAL_SECS", int(1, U64)),
+    ("PROXY_MAX_REQUEST_BYTES", int(1, USIZE)),
+    ("PROXY_MAX_RESPONSE_BYTES", int(1, USIZE)),
+    ("PROXY_BREAKER_FAILURE_THRESHOLD", int(1, U32)),
+    ("PROXY_BREAKER_COOLDOWN_SECS", int(1, U64)),
+    ("PROXY_POOL_MAX_IDLE_PER_HOST", int(0, USIZE)),
+    ("PROXY_POOL_IDLE_TIMEOUT_SECS", int(1, U64)),
+    ("PROXY_TCP_KEEPALIVE_SECS", int(0, U64)),
+    ("PROXY_CONNECT_TIMEOUT_MS", int(1, U64)),
+    // a century; longer windows overflow the restore cutoff arithmetic
+    ("SERVER_DELETE_RETENTION_HOURS", int(1, 24 * 365 * 100)),
+    ("SERVER_DELETE_SWEEP_INTERVAL_SECS", int(1, U64)),
+    ("SECRET_VERSION_RETENTION", int(1, I32)),
+    ("LIBVIRT_DEFAULT_MEMORY_MIB", int(1, U64)),
+    ("LIBVIRT_DEFAULT_VCPU_COUNT", int(1, U32)),
+    ("LIBVIRT_LOG_TAIL", int(1, USIZE)),
+    ("DB_POOL_MAX_CONNECTIONS", int(1, U32)),
+    ("DB_POOL_MIN_CONNECTIONS", int(0, U32)),
+    ("DB_POOL_ACQUIRE_TIMEOUT_SECS", int(1, U64)),
+    ("DB_POOL_IDLE_TIMEOUT_SECS", int(0, U64)),
+    ("DB_POOL_STATS_INTERVAL_SECS", int(1, U64)),
+    ("MARKETPLACE_PUBLISH_MIN_SCORE", float(0.0, 100.0)),
+    ("MARKETPLACE_PUBLISH_MIN_CONFIDENCE", float(0.0, 1.0)),
+    ("EVALUATION_REGRESSION_TOLERANCE", float(0.0, f64::INFINITY)),
+    ("ALLOW_MIGRATION_FAILURE", Rule::Flag),
+    ("CORS_REQUIRE_ALLOWLIST", Rule::Flag),
+    ("CORS_ALLOW_CREDENTIALS", Rule::Flag),
+    ("LIFECYCLE_CONSOLE_STRICT_RETRY_LEDGER", Rule::Flag),
+    ("REGISTRY_BUILD_DISABLE_CACHE", Rule::Flag),
+    ("REGISTRY_BUILD_PARALLELISM", int(1, USIZE)),
+    ("REGISTRY_PUSH_RETRIES", int(1, USIZE)),
+    ("REGISTRY_AUTH_MAX_AGE_SECONDS", int(0, U64)),
+    ("REGISTRY_AUTH_ROTATE_LEAD_SECONDS", int(0, U64)),
+    (
+        "PROMOTION_GATE_CONTEXT_MERGE",
+        Rule::Check(parses::<GateContextMerge>),
+    ),
+    (
+        "LIFECYCLE_CONSOLE_VERDICT_STRATEGY",
+        Rule::Check(parses::<VerdictStrategy>),
+    ),
+    (
+        "PROXY_ROUTING_STRATEGY",
+        Rule::Check(parses::<RoutingStrategy>),
+    ),
+    (
+        "REMEDIATION_ARTIFACT_RETENTION_MODE",
+        Rule::OneOf(&["archive", "prune"]),
+    ),
+    ("VM_PROVISIONER_DRIVER", Rule::OneOf(&["http", "libvirt"])),
+    ("EVALUATION_SCHEDULE_TZ", Rule::Check(utc_offset)),
+    ("METRICS_ALLOWED_SOURCES", Rule::Check(metrics_sources)),
+    ("CORS_ALLOWED_ORIGINS", Rule::Check(cors_origins)),
+    ("CORS_ALLOWED_METHODS", Rule::Check(cors_methods)),
+    ("CORS_ALLOWED_HEADERS", Rule::Check(cors_headers)),
+    ("PROMOTION_NOTE_TEMPLATES", Rule::Check(note_templates)),
+    (
+        "REMEDIATION_GATE_DEPENDENCIES",
+        Rule::Check(gate_dependencies),
+    ),
+    (
+        "PROMOTION_VETO_REASON_ALIASES",
+        Rule::Check(json_as::<HashMap<String, Vec<String>>>),
+    ),
+    (
+        "LIBVIRT_RESOURCE_PROFILES",
+        Rule::Check(json_as::<HashMap<String, LibvirtResourceProfile>>),
+    ),
+    ("LIBVIRT_NETWORK_TEMPLATE", Rule::Json),
+    ("LIBVIRT_VOLUME_TEMPLATE", Rule::Json),
+    ("LIBVIRT_GPU_POLICY", Rule::Json),
+    ("VAULT_MASTER_KEYS", Rule::Check(vault_master_keys)),
+];
+
+/// A check spanning several settings, given their trimmed, non-blank values. It names the
+/// setting to blame and why. Values already rejected by their own [`SETTINGS`] rule are
+/// skipped here so each mistake is reported once.
+type CrossCheck = fn(&dyn Fn(&str) -> Option<String>) -> Result<(), (&'static str, String)>;
+
+const CROSS_CHECKS: &[CrossCheck] = &[libvirt_default_profile, vault_active_master_key];
+
+const fn int(min: i128, max: i128) -> Rule {
+    Rule::Int { min, max }
+}
+
+const fn float(min: f64, max: f64) -> Rule {
+    Rule::Float { min, max }
+}
+
+fn parses<T>(raw: &str) -> Result<(), String>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    raw.parse::<T>().map(drop).map_err(|err| err.to_string())
+}
+
+/// Valid JSON that also deserializes into the type the setting is read as.
+fn json_as<T: DeserializeOwned>(raw: &str) -> Result<(), String> {
+    let value: Value = serde_json::from_str(raw).map_err(|err| format!("expected JSON: {err}"))?;
+    serde_json::from_value::<T>(value)
+        .map(drop)
+        .map_err(|err| format!("unexpected JSON shape: {err}"))
+}
+
+fn jwt_algorithm(raw: &str) -> Result<(), String> {
+    parse_algorithm(raw).map(drop)
+}
+
+fn utc_offset(raw: &str) -> Result<(), String> {
+    crate::evaluations::cron::parse_utc_offset(raw)
+        .map(drop)
+        .ok_or_else(|| "expected `UTC` or a fixed offset such as `+02:00`".to_string())
+}
+
+fn metrics_sources(raw: &str) -> Result<(), String> {
+    raw.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .try_for_each(|entry| AllowedSource::parse(entry).map(drop))
+}
+
+fn cors_origins(raw: &str) -> Result<(), String> {
+    AllowedOrigins::parse(raw).map(drop)
+}
+
+fn cors_methods(raw: &str) -> Result<(), String> {
+    cors::parse_methods(raw).map(drop)
+}
+
+fn cors_headers(raw: &str) -> Result<(), String> {
+    cors::parse_headers(raw).map(drop)
+}
+
+fn note_templates(raw: &str) -> Result<(), String> {
+    let value: Value = serde_json::from_str(raw).map_err(|err| format!("expected JSON: {err}"))?;
+    NoteTemplates::from_json(&value).map(drop)
+}
+
+fn gate_dependencies(raw: &str) -> Result<(), String> {
+    let value: Value = serde_json::from_str(raw).map_err(|err| format!("expected JSON: {err}"))?;
+    WorkspaceGateGraph::from_json(&value).map(drop)
+}
+
+fn vault_master_keys(raw: &str) -> Result<(), String> {
+    LocalKeyWrapper::from_spec(raw, None)
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+fn libvirt_default_profile(
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), (&'static str, String)> {
+    let profiles = match lookup("LIBVIRT_RESOURCE_PROFILES") {
+        Some(raw) => match serde_json::from_str::<HashMap<String, LibvirtResourceProfile>>(&raw) {
+            Ok(profiles) => profiles.into_keys().collect(),
+            Err(_) => return Ok(()),
+        },
+        None => vec![
+            "small".to_string(),
+            "medium".to_string(),
+            "large".to_string(),
+        ],
+    };
+    let (var, default) = match lookup("LIBVIRT_DEFAULT_RESOURCE_PROFILE") {
+        Some(default) => ("LIBVIRT_DEFAULT_RESOURCE_PROFILE", default),
+        None => ("LIBVIRT_RESOURCE_PROFILES", "medium".to_string()),
+    };
+    if profiles.contains(&default) {
+        Ok(())
+    } else {
+        Err((
+            var,
+            format!("LIBVIRT_RESOURCE_PROFILES does not define the default profile `{default}`"),
+        ))
+    }
+}
+
+fn vault_active_master_key(
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), (&'static str, String)> {
+    let (Some(keys), Some(active)) = (
+        lookup("VAULT_MASTER_KEYS"),
+        lookup("VAULT_ACTIVE_MASTER_KEY_ID"),
+    ) else {
+        return Ok(());
+    };
+    if vault_master_keys(&keys).is_err() {
+        return Ok(());
+    }
+    LocalKeyWrapper::from_spec(&keys, Some(&active))
+        .map(drop)
+        .map_err(|err| ("VAULT_ACTIVE_MASTER_KEY_ID", err.to_string()))
+}
+
+fn rule_for(var: &str) -> Option<Rule> {
+    SETTINGS
+        .iter()
+        .find(|(name, _)| *name == var)
+        .map(|(_, rule)| *rule)
+}
+
+fn read(var: &str) -> Option<String> {
+    std::env::var(var)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Typed accessor for a setting listed in [`SETTINGS`]: `None` when it is unset, blank, or
+/// breaks its rule. Startup has already refused to run with a broken value (see
+/// [`validate_process_env`]), so callers fall back to their default only for unset settings.
+pub fn setting<T: FromStr>(var: &str) -> Option<T> {
+    let raw = read(var)?;
+    let rule = rule_for(var);
+    debug_assert!(
+        rule.is_some(),
+        "{var} is not listed in config::validation::SETTINGS"
+    );
+    if rule.is_some_and(|rule| rule.check(&raw).is_err()) {
+        return None;
+    }
+    raw.parse().ok()
+}
+
+/// Typed accessor for a [`Rule::Flag`] setting; unset means `false`.
+pub fn flag(var: &str) -> bool {
+    debug_assert!(
+        matches!(rule_for(var), Some(Rule::Flag)),
+        "{var} is not listed as a flag in config::validation::SETTINGS"
+    );
+    read(var).is_some_and(|raw| TRUTHY.contains(&raw.to_ascii_lowercase().as_str()))
+}
+
+/// One setting whose value breaks its rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub var: &'static str,
+    pub value: String,
+    pub problem: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut quoted: String = self.value.chars().take(QUOTED_VALUE_CHARS).collect();
+        if quoted.len() < self.value.len() {
+            quoted.push_str("...");
+        }
+        write!(f, "{}={:?}: {}", self.var, quoted, self.problem)
+    }
+}
+
+/// Every invalid setting found in one pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigErrors {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} invalid configuration value(s):", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Checks every setting in [`SETTINGS`], then the settings that depend on each other, against
+/// the values `lookup` returns, collecting all violations instead of stopping at the first.
+pub fn validate_env(lookup: impl Fn(&str) -> Option<String>) -> Result<(), ConfigErrors> {
+    let lookup = |var: &str| {
+        lookup(var)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let mut issues = match check_settings(SETTINGS.iter(), lookup) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.issues,
+    };
+    for cross_check in CROSS_CHECKS {
+        if let Err((var, problem)) = cross_check(&lookup) {
+            issues.push(ConfigIssue {
+                var,
+                value: lookup(var).unwrap_or_default(),
+                problem,
+            });
+        }
+    }
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigErrors { issues })
+    }
+}
+
+/// [`validate_env`] over the process environment; run once before serving.
+pub fn validate_process_env() -> Result<(), ConfigErrors> {
+    validate_env(|var| std::env::var(var).ok())
+}
+
+/// Checks only `vars` against the values `lookup` returns, e.g. before a config reload.
+pub fn validate_vars(
+    vars: &[&str],
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<(), ConfigErrors> {
+    check_settings(
+        SETTINGS.iter().filter(|(var, _)| vars.contains(var)),
+        lookup,
+    )
+}
+
+fn check_settings<'a>(
+    settings: impl Iterator<Item = &'a (&'static str, Rule)>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<(), ConfigErrors> {
+    let issues: Vec<ConfigIssue> = settings
+        .filter_map(|&(var, rule)| {
+            let raw = lookup(var)?;
+            let raw = raw.trim();
+            if raw.is_empty() {
+                return None;
+            }
+            rule.check(raw).err().map(|problem| ConfigIssue {
+                var,
+                value: raw.to_string(),
+                problem,
+            })
+        })
+        .collect();
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigErrors { issues })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    fn validate(values: &[(&str, &str)]) -> Result<(), ConfigErrors> {
+        let values: HashMap<String, String> = values
+            .iter()
+            .map(|(var, value)| (var.to_string(), value.to_string()))
+            .collect();
+        validate_env(|var| values.get(var).cloned())
+    }
+
+    #[test]
+    fn unset_blank_and_valid_values_pass() {
+        assert_eq!(validate(&[]), Ok(()));
+        assert_eq!(
+            validate(&[
+                ("BIND_PORT", " 8080 "),
+                ("JOB_LEASE_SECS", ""),
+                ("CORS_ALLOW_CREDENTIALS", "Yes"),
+                ("PROXY_ROUTING_STRATEGY", "round_robin"),
+                ("VM_PROVISIONER_DRIVER", "LIBVIRT"),
+                ("MARKETPLACE_PUBLISH_MIN_CONFIDENCE", "0.75"),
+                ("EVALUATION_SCHEDULE_TZ", "+02:00"),
+                ("LIBVIRT_GPU_POLICY", r#"{"enabled": true}"#),
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn multiple_invalid_values_are_reported_together() {
+        let err = validate(&[
+            ("BIND_PORT", "70000"),
+            ("JOB_LEASE_SECS", "0"),
+            ("INGESTION_CHUNK_SIZE", "512"),
+            ("CORS_ALLOW_CREDENTIALS", "maybe"),
+            ("MARKETPLACE_PUBLISH_MIN_CONFIDENCE", "1.5"),
+            ("REGISTRY_PUSH_RETRIES", "three"),
+            ("PROXY_ROUTING_STRATEGY", "fastest"),
+            ("PROMOTION_NOTE_TEMPLATES", r#"{"audit": "by {approver}"}"#),
+            ("REMEDIATION_GATE_DEPENDENCIES", "{schema:"),
+        ])
+        .unwrap_err();
+
+        let vars: Vec<&str> = err.issues.iter().map(|issue| issue.var).collect();
+        assert_eq!(
+            vars,
+            vec![
+                "BIND_PORT",
+                "JOB_LEASE_SECS",
+                "MARKETPLACE_PUBLISH_MIN_CONFIDENCE",
+                "CORS_ALLOW_CREDENTIALS",
+                "REGISTRY_PUSH_RETRIES",
+                "PROXY_ROUTING_STRATEGY",
+                "PROMOTION_NOTE_TEMPLATES",
+                "REMEDIATION_GATE_DEPENDENCIES",
+            ]
+        );
+        assert_eq!(err.issues[0].problem, "expected an integer from 0 to 65535");
+        assert_eq!(err.issues[1].problem, "expected an integer of at least 1");
+
+        let report = err.to_string();
+        assert!(report.starts_with("8 invalid configuration value(s):"));
+        for issue in &err.issues {
+            assert!(report.contains(&format!("\n  {}=", issue.var)), "{report}");
+        }
+        assert!(report.contains(r#"BIND_PORT="70000": expected an integer from 0 to 65535"#));
+        assert!(report.contains("unknown variable `approver`"), "{report}");
+    }
+
+    #[test]
+    fn structured_values_must_match_what_config_reads() {
+        let key = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+        assert_eq!(
+            validate(&[
+                ("REMEDIATION_GATE_DEPENDENCIES", r#"{"policy": ["schema"]}"#),
+                (
+                    "LIBVIRT_RESOURCE_PROFILES",
+                    r#"{"tiny": {"vcpu_count": 1, "memory_mib": 512}}"#
+                ),
+                ("LIBVIRT_DEFAULT_RESOURCE_PROFILE", "tiny"),
+                ("VAULT_MASTER_KEYS", &format!("k1:{key},k2:{key}")),
+                ("VAULT_ACTIVE_MASTER_KEY_ID", "k1"),
+            ]),
+            Ok(())
+        );
+
+        let err = validate(&[
+            ("REMEDIATION_GATE_DEPENDENCIES", "[1]"),
+            (
+                "PROMOTION_VETO_REASON_ALIASES",
+                r#"{"attestation.pending": "pending"}"#,
+            ),
+            (
+                "LIBVIRT_RESOURCE_PROFILES",
+                r#"{"tiny": {"vcpu_count": 1, "memory_mib": 512}}"#,
+            ),
+            ("VAULT_MASTER_KEYS", "k1:c2hvcnQ="),
+        ])
+        .unwrap_err();
+        let vars: Vec<&str> = err.issues.iter().map(|issue| issue.var).collect();
+        assert_eq!(
+            vars,
+            vec![
+                "REMEDIATION_GATE_DEPENDENCIES",
+                "PROMOTION_VETO_REASON_ALIASES",
+                "VAULT_MASTER_KEYS",
+                "LIBVIRT_RESOURCE_PROFILES",
+            ]
+        );
+        assert!(err.issues[0].problem.starts_with("unexpected JSON shape"));
+        assert!(err.issues[3].problem.contains("default profile `medium`"));
+
+        let err = validate(&[(
+            "REMEDIATION_GATE_DEPENDENCIES",
+            r#"{"promotion": ["simulaton"]}"#,
+        )])
+        .unwrap_err();
+        assert!(err.issues[0].problem.contains("unknown gate `simulaton`"));
+
+        let err = validate(&[
+            ("LIBVIRT_DEFAULT_RESOURCE_PROFILE", "huge"),
+            ("VAULT_MASTER_KEYS", &format!("k1:{key}")),
+            ("VAULT_ACTIVE_MASTER_KEY_ID", "k9"),
+        ])
+        .unwrap_err();
+        let vars: Vec<&str> = err.issues.iter().map(|issue| issue.var).collect();
+        assert_eq!(
+            vars,
+            vec![
+                "LIBVIRT_DEFAULT_RESOURCE_PROFILE",
+                "VAULT_ACTIVE_MASTER_KEY_ID"
+            ]
+        );
+    }
+
+    #[test]
+    fn settings_are_listed_once() {
+        let mut seen = HashSet::new();
+        for (var, _) in SETTINGS {
+            assert!(seen.insert(*var), "{var} is listed twice");
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use crate::config;
+use axum::{
+    extract::State,
+    http::{header, HeaderName, HeaderValue, Method, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+// key: cors -> origin allowlist for browser consoles on the API routes
+
+pub const DEFAULT_METHODS: &str = "GET,POST,PUT,PATCH,DELETE,OPTIONS";
+/// `last-event-id` lets an `EventSource` resume a stream after reconnecting.
+pub const DEFAULT_HEADERS: &str = "authorization,content-type,accept,x-api-key,last-event-id";
+
+/// Origins allowed to call the API from a browser. `Any` is the development default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+impl AllowedOrigins {
+    /// `*` or a comma-separated list of `scheme://host[:port]` origins.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.trim() == "*" {
+            return Ok(Self::Any);
+        }
+        let origins = value
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(parse_origin)
+            .collect::<Result<Vec<_>, _>>()?;
+        if origins.is_empty() {
+            return Err("no origins listed".into());
+        }
+        Ok(Self::List(origins))
+    }
+
+    fn allows(&self, origin: &HeaderValue) -> bool {
+        match self {
+            Self::Any => true,
+            Self::List(origins) => origins.contains(origin),
+        }
+    }
+}
+
+fn parse_origin(origin: &str) -> Result<HeaderValue, String> {
+    let origin = origin.trim_end_matches('/');
+    let valid = origin.split_once("://").is_some_and(|(scheme, host)| {
+        matches!(scheme, "http" | "https") && !host.is_empty() && !host.contains('/')
+    });
+    if !valid {
+        return Err(format!(
+            "invalid origin `{origin}`; expected scheme://host[:port]"
+        ));
+    }
+    HeaderValue::from_str(origin).map_err(|_| format!("invalid origin `{origin}`"))
+}
+
+pub fn parse_methods(value: &str) -> Result<Vec<Method>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|method| !method.is_empty())
+        .map(|method| {
+            Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .map_err(|_| format!("invalid method `{method}`"))
+        })
+        .collect()
+}
+
+pub fn parse_headers(value: &str) -> Result<Vec<HeaderName>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("invalid header `{name}`"))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct CorsPolicy {
+    origins: AllowedOrigins,
+    methods: Vec<Method>,
+    headers: Vec<HeaderName>,
+    allow_credentials: bool,
+    max_age: Duration,
+}
+
+impl CorsPolicy {
+    /// Browsers refuse credentialed responses for a wildcard origin, so that combination
+    /// is a configuration error rather than a silently broken console.
+    pub fn new(
+        origins: AllowedOrigins,
+        methods: Vec<Method>,
+        headers: Vec<HeaderName>,
+        allow_credentials: bool,
+        max_age: Duration,
+    ) -> Result<Self, String> {
+        if allow_credentials && origins == AllowedOrigins::Any {
+            return Err("CORS credentials require an explicit origin allowlist".into());
+        }
+        Ok(Self {
+            origins,
+            methods,
+            headers,
+            allow_credentials,
+            max_age,
+        })
+    }
+
+    /// Panics on an invalid combination, and on a wildcard origin when
+    /// `CORS_REQUIRE_ALLOWLIST` locks the server down for production.
+    pub fn from_config() -> Self {
+        let origins = config::CORS_ALLOWED_ORIGINS.clone();
+        if *config::CORS_REQUIRE_ALLOWLIST && origins == AllowedOrigins::Any {
+            panic!("CORS_REQUIRE_ALLOWLIST is set but CORS_ALLOWED_ORIGINS allows any origin");
+        }
+        Self::new(
+            origins,
+            config::CORS_ALLOWED_METHODS.clone(),
+            config::CORS_ALLOWED_HEADERS.clone(),
+            *config::CORS_ALLOW_CREDENTIALS,
+            Duration::from_secs(*config::CORS_MAX_AGE_SECONDS),
+        )
+        .unwrap_or_else(|err| panic!("invalid CORS configuration: {err}"))
+    }
+
+    pub fn allows_any_origin(&self) -> bool {
+        self.origins == AllowedOrigins::Any
+    }
+
+    fn layer(&self) -> CorsLayer {
+        let allow_origin = match &self.origins {
+            AllowedOrigins::Any => AllowOrigin::any(),
+            AllowedOrigins::List(origins) => AllowOrigin::list(origins.clone()),
+        };
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(self.methods.clone())
+            .allow_headers(self.headers.clone())
+            .allow_credentials(self.allow_credentials)
+            .max_age(self.max_age)
+    }
+
+    /// Wraps `router` so preflights are answered and responses carry the CORS headers.
+    /// Responses stream through untouched, so `text/event-stream` bodies are never buffered.
+    pub fn apply(self, router: Router) -> Router {
+        let layer = self.layer();
+        router.layer(layer).layer(middleware::from_fn_with_state(
+            Arc::new(self),
+            reject_disallowed_preflight,
+        ))
+    }
+}
+
+/// Answers 403 to a preflight from an origin outside the allowlist. Other requests pass
+/// through without CORS headers, which is enough for the browser to withhold the response
+/// while same-origin and non-browser callers keep working.
+async fn reject_disallowed_preflight<B>(
+    State(policy): State<Arc<CorsPolicy>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let is_preflight = request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    if is_preflight {
+        if let Some(origin) = request.headers().get(header::ORIGIN) {
+            if !policy.origins.allows(origin) {
+                tracing::debug!(?origin, "rejected CORS preflight from disallowed origin");
+                return StatusCode::FORBIDDEN.into_response();
+            }
+        }
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get};
+    use tower::ServiceExt;
+
+    const CONSOLE: &str = "https://console.example.com";
+
+    fn policy(origins: &str, allow_credentials: bool) -> CorsPolicy {
+        CorsPolicy::new(
+            AllowedOrigins::parse(origins).unwrap(),
+            parse_methods(DEFAULT_METHODS).unwrap(),
+            parse_headers(DEFAULT_HEADERS).unwrap(),
+            allow_credentials,
+            Duration::from_secs(600),
+        )
+        .unwrap()
+    }
+
+    fn app(policy: CorsPolicy) -> Router {
+        policy.apply(
+            Router::new()
+                .route("/api/servers", get(|| async { "[]" }))
+                .route(
+                    "/api/events/stream",
+                    get(|| async {
+                        (
+                            [(header::CONTENT_TYPE, "text/event-stream")],
+                            "data: hi\n\n",
+                        )
+                    }),
+                ),
+        )
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/servers")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn get_from(uri: &str, origin: &str) -> Request<Body> {
+        Request::builder()
+            .uri(uri)
+            .header(header::ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn header_value(response: &Response, name: HeaderName) -> Option<&str> {
+        response
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn allowed_origin_gets_cors_headers() {
+        let app = app(policy(CONSOLE, true));
+
+        let response = app.clone().oneshot(preflight(CONSOLE)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(CONSOLE)
+        );
+        assert_eq!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_METHODS)
+                .unwrap()
+                .contains("POST")
+        );
+        assert!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_HEADERS)
+                .unwrap()
+                .contains("authorization")
+        );
+
+        let response = app
+            .oneshot(get_from("/api/servers", CONSOLE))
+            .await
+            .unwrap();
+        assert_eq!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(CONSOLE)
+        );
+    }
+
+    #[tokio::test]
+    async fn event_streams_carry_cors_headers() {
+        let response = app(policy(CONSOLE, true))
+            .oneshot(get_from("/api/events/stream", CONSOLE))
+            .await
+            .unwrap();
+        assert_eq!(
+            header_value(&response, header::CONTENT_TYPE),
+            Some("text/event-stream")
+        );
+        assert_eq!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(CONSOLE)
+        );
+        assert_eq!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+    }
+
+    #[tokio::test]
+    async fn disallowed_origin_is_rejected() {
+        let app = app(policy(CONSOLE, true));
+
+        let response = app
+            .clone()
+            .oneshot(preflight("https://evil.example.net"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+
+        let response = app
+            .oneshot(get_from("/api/servers", "https://evil.example.net"))
+            .await
+            .unwrap();
+        assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[tokio::test]
+    async fn wildcard_allows_any_origin_without_credentials() {
+        let response = app(policy("*", false))
+            .oneshot(preflight("http://localhost:5173"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("*")
+        );
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(AllowedOrigins::parse("console.example.com").is_err());
+        assert!(AllowedOrigins::parse("https://console.example.com/app").is_err());
+        assert_eq!(
+            AllowedOrigins::parse("https://console.example.com/").unwrap(),
+            AllowedOrigins::List(vec![HeaderValue::from_static(CONSOLE)])
+        );
+        assert!(CorsPolicy::new(
+            AllowedOrigins::Any,
+            Vec::new(),
+            Vec::new(),
+            true,
+            Duration::ZERO
+        )
+        .is_err());
+    }
+}
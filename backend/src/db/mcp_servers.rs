@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+// key: server-soft-delete -> deleted_at lifecycle for mcp_servers
+
+/// A server as it appears in listings.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ServerSummary {
+    pub id: i32,
+    pub name: String,
+    pub server_type: String,
+    pub status: String,
+    pub use_gpu: bool,
+    pub organization_id: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RestoreOutcome {
+    Restored(ServerSummary),
+    /// Deleted before the retention cutoff; only the sweep can act on it now.
+    RetentionExpired {
+        deleted_at: DateTime<Utc>,
+    },
+    NotFound,
+}
+
+/// Whether `owner_id` owns `server_id` and the server is not soft-deleted. Every ownership
+/// check goes through here so a deleted server stops answering until it is restored.
+pub async fn is_live_owned_by(
+    pool: &PgPool,
+    server_id: i32,
+    owner_id: i32,
+) -> Result<bool, sqlx::Error> {
+    let id: Option<i32> = sqlx::query_scalar(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(server_id)
+    .bind(owner_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(id.is_some())
+}
+
+/// `(id, owner_id, organization_id)` of the live server holding `api_key`; a soft-deleted
+/// server's key stops authenticating.
+pub async fn live_by_api_key(
+    pool: &PgPool,
+    api_key: &str,
+) -> Result<Option<(i32, i32, Option<i32>)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, owner_id, organization_id FROM mcp_servers \
+         WHERE api_key = $1 AND deleted_at IS NULL",
+    )
+    .bind(api_key)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Servers that are not soft-deleted, for one owner or for everyone.
+pub async fn list_servers(
+    pool: &PgPool,
+    owner_id: Option<i32>,
+) -> Result<Vec<ServerSummary>, sqlx::Error> {
+    sqlx::query_as::<_, ServerSummary>(
+        r#"
+        SELECT id, name, server_type, status, use_gpu, organization_id
+        FROM mcp_servers
+        WHERE deleted_at IS NULL AND ($1::INT IS NULL OR owner_id = $1)
+        ORDER BY id
+        "#,
+    )
+    .bind(owner_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Marks the owner's server deleted. Returns `None` when it is missing or already deleted.
+pub async fn soft_delete(
+    pool: &PgPool,
+    server_id: i32,
+    owner_id: i32,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        UPDATE mcp_servers
+        SET deleted_at = NOW()
+        WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL
+        RETURNING deleted_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(owner_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Brings back a server deleted after `cutoff`.
+pub async fn restore(
+    pool: &PgPool,
+    server_id: i32,
+    owner_id: i32,
+    cutoff: DateTime<Utc>,
+) -> Result<RestoreOutcome, sqlx::Error> {
+    let restored = sqlx::query_as::<_, ServerSummary>(
+        r#"
+        UPDATE mcp_servers
+        SET deleted_at = NULL
+        WHERE id = $1 AND owner_id = $2 AND deleted_at > $3
+        RETURNING id, name, server_type, status, use_gpu, organization_id
+        "#,
+    )
+    .bind(server_id)
+    .bind(owner_id)
+    .bind(cutoff)
+    .fetch_optional(pool)
+    .await?;
+    if let Some(server) = restored {
+        return Ok(RestoreOutcome::Restored(server));
+    }
+    let deleted_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT deleted_at FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NOT NULL",
+    )
+    .bind(server_id)
+    .bind(owner_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(match deleted_at {
+        Some(deleted_at) => RestoreOutcome::RetentionExpired { deleted_at },
+        None => RestoreOutcome::NotFound,
+    })
+}
+
+/// Ids of servers deleted at or before `cutoff`, oldest deletion first.
+pub async fn expired_deletions(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+) -> Result<Vec<i32>, sqlx::Error> {
+    sqlx::query_scalar("SELECT id FROM mcp_servers WHERE deleted_at <= $1 ORDER BY deleted_at, id")
+        .bind(cutoff)
+        .fetch_all(pool)
+        .await
+}
+
+/// Permanently removes one server if it is still deleted at or before `cutoff`. Rows that
+/// reference it follow their foreign keys from here on. Returns false when a restore got there
+/// first.
+pub async fn purge(pool: &PgPool, id: i32, cutoff: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM mcp_servers WHERE id = $1 AND deleted_at <= $2")
+        .bind(id)
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
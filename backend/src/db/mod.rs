@@ -1,3 +1,7 @@
+pub mod mcp_servers;
+pub mod pool;
+pub mod remediation_maintenance_windows;
+pub mod runtime_policy_audit;
 pub mod runtime_vm_accelerator_posture;
 pub mod runtime_vm_attestations;
 pub mod runtime_vm_remediation_artifacts;
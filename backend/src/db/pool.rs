@@ -0,0 +1,109 @@
+use serde::Serialize;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// key: db-pool -> postgres pool sizing and utilization metrics
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbPoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// How long a caller waits for a free connection before the query fails.
+    pub acquire_timeout: Duration,
+    /// Idle connections above `min_connections` are closed after this; `None` keeps them.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for DbPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(600)),
+        }
+    }
+}
+
+impl DbPoolConfig {
+    pub fn options(&self) -> PgPoolOptions {
+        PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections.min(self.max_connections))
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    pub max_connections: u32,
+    /// Time the sampler itself waited for a connection; `None` when it timed out.
+    pub acquire_wait_ms: Option<u64>,
+}
+
+impl PoolStats {
+    pub fn saturated(&self) -> bool {
+        self.in_use >= self.max_connections
+    }
+}
+
+/// Reads the pool counters, then times one checkout as a proxy for the wait callers
+/// currently see. Counters are read first so the probe connection is not counted.
+pub async fn sample_pool(pool: &PgPool, max_connections: u32) -> PoolStats {
+    let size = pool.size();
+    let idle = u32::try_from(pool.num_idle()).unwrap_or(u32::MAX).min(size);
+    let started = Instant::now();
+    let acquire_wait_ms = match pool.acquire().await {
+        Ok(_connection) => Some(started.elapsed().as_millis() as u64),
+        Err(err) => {
+            warn!(%err, "database pool stats probe could not acquire a connection");
+            None
+        }
+    };
+    PoolStats {
+        size,
+        idle,
+        in_use: size - idle,
+        max_connections,
+        acquire_wait_ms,
+    }
+}
+
+pub fn spawn_pool_stats(pool: PgPool, max_connections: u32, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let stats = sample_pool(&pool, max_connections).await;
+            if stats.saturated() {
+                warn!(
+                    target: "db.pool.metrics",
+                    event_type = "db_pool_saturated",
+                    size = stats.size,
+                    idle = stats.idle,
+                    in_use = stats.in_use,
+                    max_connections = stats.max_connections,
+                    acquire_wait_ms = stats.acquire_wait_ms,
+                    "database pool saturated"
+                );
+            } else {
+                info!(
+                    target: "db.pool.metrics",
+                    event_type = "db_pool_stats",
+                    size = stats.size,
+                    idle = stats.idle,
+                    in_use = stats.in_use,
+                    max_connections = stats.max_connections,
+                    acquire_wait_ms = stats.acquire_wait_ms,
+                    "database pool stats"
+                );
+            }
+        }
+    });
+}
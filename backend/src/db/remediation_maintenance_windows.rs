@@ -0,0 +1,207 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Executor, PgPool, Postgres};
+
+// key: remediation-db -> maintenance-windows
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RemediationMaintenanceWindow {
+    pub id: i64,
+    pub organization_id: i32,
+    pub name: String,
+    /// Five-field cron expression for when each window opens.
+    pub recurrence: String,
+    pub duration_minutes: i32,
+    /// `UTC` or a fixed offset such as `+02:00`; the recurrence is read in this wall clock.
+    pub timezone: String,
+    pub enabled: bool,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RemediationMaintenanceOverride {
+    pub id: i64,
+    pub organization_id: i32,
+    pub remediation_run_id: i64,
+    pub playbook_id: Option<i64>,
+    pub actor_id: Option<i32>,
+    pub actor_role: String,
+    pub reason: String,
+    /// When the run was due to start; the time that fell outside every window.
+    pub effective_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct CreateMaintenanceWindow<'a> {
+    pub organization_id: i32,
+    pub name: &'a str,
+    pub recurrence: &'a str,
+    pub duration_minutes: i32,
+    pub timezone: &'a str,
+    pub enabled: bool,
+    pub created_by: Option<i32>,
+}
+
+pub struct NewMaintenanceOverride<'a> {
+    pub organization_id: i32,
+    pub remediation_run_id: i64,
+    pub playbook_id: Option<i64>,
+    pub actor_id: i32,
+    pub actor_role: &'a str,
+    pub reason: &'a str,
+    pub effective_at: DateTime<Utc>,
+}
+
+pub async fn create_window(
+    pool: &PgPool,
+    window: CreateMaintenanceWindow<'_>,
+) -> Result<RemediationMaintenanceWindow, sqlx::Error> {
+    sqlx::query_as::<_, RemediationMaintenanceWindow>(
+        r#"
+        INSERT INTO remediation_maintenance_windows (
+            organization_id,
+            name,
+            recurrence,
+            duration_minutes,
+            timezone,
+            enabled,
+            created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING
+            id,
+            organization_id,
+            name,
+            recurrence,
+            duration_minutes,
+            timezone,
+            enabled,
+            created_by,
+            created_at,
+            updated_at
+        "#,
+    )
+    .bind(window.organization_id)
+    .bind(window.name)
+    .bind(window.recurrence)
+    .bind(window.duration_minutes)
+    .bind(window.timezone)
+    .bind(window.enabled)
+    .bind(window.created_by)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn list_windows(
+    pool: &PgPool,
+    organization_id: i32,
+    enabled_only: bool,
+) -> Result<Vec<RemediationMaintenanceWindow>, sqlx::Error> {
+    sqlx::query_as::<_, RemediationMaintenanceWindow>(
+        r#"
+        SELECT
+            id,
+            organization_id,
+            name,
+            recurrence,
+            duration_minutes,
+            timezone,
+            enabled,
+            created_by,
+            created_at,
+            updated_at
+        FROM remediation_maintenance_windows
+        WHERE organization_id = $1
+          AND (NOT $2 OR enabled)
+        ORDER BY id
+        "#,
+    )
+    .bind(organization_id)
+    .bind(enabled_only)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn delete_window(
+    pool: &PgPool,
+    organization_id: i32,
+    window_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM remediation_maintenance_windows WHERE id = $1 AND organization_id = $2",
+    )
+    .bind(window_id)
+    .bind(organization_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn insert_override<'c, E>(
+    executor: E,
+    record: NewMaintenanceOverride<'_>,
+) -> Result<RemediationMaintenanceOverride, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    sqlx::query_as::<_, RemediationMaintenanceOverride>(
+        r#"
+        INSERT INTO remediation_maintenance_overrides (
+            organization_id,
+            remediation_run_id,
+            playbook_id,
+            actor_id,
+            actor_role,
+            reason,
+            effective_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING
+            id,
+            organization_id,
+            remediation_run_id,
+            playbook_id,
+            actor_id,
+            actor_role,
+            reason,
+            effective_at,
+            created_at
+        "#,
+    )
+    .bind(record.organization_id)
+    .bind(record.remediation_run_id)
+    .bind(record.playbook_id)
+    .bind(record.actor_id)
+    .bind(record.actor_role)
+    .bind(record.reason)
+    .bind(record.effective_at)
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn list_overrides(
+    pool: &PgPool,
+    organization_id: i32,
+) -> Result<Vec<RemediationMaintenanceOverride>, sqlx::Error> {
+    sqlx::query_as::<_, RemediationMaintenanceOverride>(
+        r#"
+        SELECT
+            id,
+            organization_id,
+            remediation_run_id,
+            playbook_id,
+            actor_id,
+            actor_role,
+            reason,
+            effective_at,
+            created_at
+        FROM remediation_maintenance_overrides
+        WHERE organization_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+// key: runtime-policy-audit -> decision audit log
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RuntimePolicyAuditRecord {
+    pub id: i64,
+    pub server_id: i32,
+    pub decision_id: Option<i32>,
+    pub candidate_backend: String,
+    pub backend: String,
+    pub executor_name: Option<String>,
+    pub executors_considered: Vec<String>,
+    pub governance_verdict: String,
+    pub allowed: bool,
+    pub veto_reasons: Vec<String>,
+    pub policy_version: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewRuntimePolicyAudit<'a> {
+    pub server_id: i32,
+    pub decision_id: Option<i32>,
+    pub candidate_backend: &'a str,
+    pub backend: &'a str,
+    pub executor_name: Option<&'a str>,
+    pub executors_considered: &'a [String],
+    pub governance_verdict: &'a str,
+    pub allowed: bool,
+    pub veto_reasons: &'a [String],
+    pub policy_version: &'a str,
+}
+
+pub async fn insert_audit_entry(
+    pool: &PgPool,
+    entry: NewRuntimePolicyAudit<'_>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO runtime_policy_audit_log (
+            server_id,
+            decision_id,
+            candidate_backend,
+            backend,
+            executor_name,
+            executors_considered,
+            governance_verdict,
+            allowed,
+            veto_reasons,
+            policy_version
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id
+        "#,
+    )
+    .bind(entry.server_id)
+    .bind(entry.decision_id)
+    .bind(entry.candidate_backend)
+    .bind(entry.backend)
+    .bind(entry.executor_name)
+    .bind(entry.executors_considered)
+    .bind(entry.governance_verdict)
+    .bind(entry.allowed)
+    .bind(entry.veto_reasons)
+    .bind(entry.policy_version)
+    .fetch_one(pool)
+    .await
+}
+
+/// Newest entries first.
+pub async fn list_for_server(
+    pool: &PgPool,
+    server_id: i32,
+    limit: i64,
+) -> Result<Vec<RuntimePolicyAuditRecord>, sqlx::Error> {
+    sqlx::query_as::<_, RuntimePolicyAuditRecord>(
+        r#"
+        SELECT
+            id,
+            server_id,
+            decision_id,
+            candidate_backend,
+            backend,
+            executor_name,
+            executors_considered,
+            governance_verdict,
+            allowed,
+            veto_reasons,
+            policy_version,
+            recorded_at
+        FROM runtime_policy_audit_log
+        WHERE server_id = $1
+        ORDER BY recorded_at DESC, id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(server_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
@@ -11,39 +11,76 @@ pub struct RuntimeVmRemediationArtifact {
     pub artifact_type: String,
     pub uri: Option<String>,
     pub metadata: Value,
+    pub content_type: String,
+    /// `text`, `json`, `markdown`, or `binary-pointer`; tells the console how to render it.
+    pub render_hint: String,
     pub recorded_by: Option<i32>,
     pub created_at: DateTime<Utc>,
+    /// Set once the metadata has moved to the blob store; `metadata` then holds a stub.
+    pub archived_blob_digest: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Hash of the artifact type and content; `None` for artifacts recorded as repeatable.
+    pub content_fingerprint: Option<String>,
 }
 
+pub struct InsertRemediationArtifact<'a> {
+    pub remediation_run_id: i64,
+    pub artifact_type: &'a str,
+    pub uri: Option<&'a str>,
+    pub metadata: &'a Value,
+    pub content_type: &'a str,
+    pub render_hint: &'a str,
+    pub recorded_by: Option<i32>,
+    /// When set, an artifact with the same fingerprint on the same run is reused instead of
+    /// inserting a second row.
+    pub content_fingerprint: Option<&'a str>,
+}
+
+/// Inserts the artifact and returns its id. With a fingerprint that the run already holds,
+/// nothing is written and the existing artifact's id is returned.
 pub async fn insert_artifact<'c, E>(
     executor: E,
-    remediation_run_id: i64,
-    artifact_type: &str,
-    uri: Option<&str>,
-    metadata: &Value,
-    recorded_by: Option<i32>,
+    artifact: InsertRemediationArtifact<'_>,
 ) -> Result<i64, sqlx::Error>
 where
     E: Executor<'c, Database = Postgres>,
 {
     let record = sqlx::query_scalar(
         r#"
-        INSERT INTO runtime_vm_remediation_artifacts (
-            remediation_run_id,
-            artifact_type,
-            uri,
-            metadata,
-            recorded_by
+        WITH inserted AS (
+            INSERT INTO runtime_vm_remediation_artifacts (
+                remediation_run_id,
+                artifact_type,
+                uri,
+                metadata,
+                content_type,
+                render_hint,
+                recorded_by,
+                content_fingerprint
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (remediation_run_id, content_fingerprint)
+                WHERE content_fingerprint IS NOT NULL
+                DO NOTHING
+            RETURNING id
         )
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id
+        SELECT id FROM inserted
+        UNION ALL
+        SELECT id
+        FROM runtime_vm_remediation_artifacts
+        WHERE remediation_run_id = $1
+            AND content_fingerprint = $8
+        LIMIT 1
         "#,
     )
-    .bind(remediation_run_id)
-    .bind(artifact_type)
-    .bind(uri)
-    .bind(metadata)
-    .bind(recorded_by)
+    .bind(artifact.remediation_run_id)
+    .bind(artifact.artifact_type)
+    .bind(artifact.uri)
+    .bind(artifact.metadata)
+    .bind(artifact.content_type)
+    .bind(artifact.render_hint)
+    .bind(artifact.recorded_by)
+    .bind(artifact.content_fingerprint)
     .fetch_one(executor)
     .await?;
 
@@ -62,8 +99,13 @@ pub async fn list_artifacts(
             artifact_type,
             uri,
             metadata,
+            content_type,
+            render_hint,
             recorded_by,
-            created_at
+            created_at,
+            archived_blob_digest,
+            archived_at,
+            content_fingerprint
         FROM runtime_vm_remediation_artifacts
         WHERE remediation_run_id = $1
         ORDER BY created_at
@@ -73,3 +115,90 @@ pub async fn list_artifacts(
     .fetch_all(pool)
     .await
 }
+
+// key: remediation-db -> artifact-retention
+pub async fn list_archivable(
+    pool: &PgPool,
+    created_before: DateTime<Utc>,
+    exempt_types: &[&str],
+    limit: i64,
+) -> Result<Vec<RuntimeVmRemediationArtifact>, sqlx::Error> {
+    sqlx::query_as::<_, RuntimeVmRemediationArtifact>(
+        r#"
+        SELECT
+            id,
+            remediation_run_id,
+            artifact_type,
+            uri,
+            metadata,
+            content_type,
+            render_hint,
+            recorded_by,
+            created_at,
+            archived_blob_digest,
+            archived_at,
+            content_fingerprint
+        FROM runtime_vm_remediation_artifacts
+        WHERE archived_blob_digest IS NULL
+            AND created_at < $1
+            AND NOT (artifact_type = ANY($2))
+        ORDER BY created_at
+        LIMIT $3
+        "#,
+    )
+    .bind(created_before)
+    .bind(exempt_types)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Points the artifact at its archived blob and swaps the metadata for `stub`. Returns
+/// `false` when another sweep archived it first.
+pub async fn mark_archived<'c, E>(
+    executor: E,
+    artifact_id: i64,
+    blob_digest: &str,
+    stub: &Value,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE runtime_vm_remediation_artifacts
+        SET archived_blob_digest = $2,
+            archived_at = NOW(),
+            metadata = $3
+        WHERE id = $1
+            AND archived_blob_digest IS NULL
+        "#,
+    )
+    .bind(artifact_id)
+    .bind(blob_digest)
+    .bind(stub)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn prune_aged(
+    pool: &PgPool,
+    created_before: DateTime<Utc>,
+    exempt_types: &[&str],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM runtime_vm_remediation_artifacts
+        WHERE created_at < $1
+            AND NOT (artifact_type = ANY($2))
+        "#,
+    )
+    .bind(created_before)
+    .bind(exempt_types)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
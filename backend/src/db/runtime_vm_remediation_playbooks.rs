@@ -14,7 +14,11 @@ pub struct RuntimeVmRemediationPlaybook {
     pub owner_id: i32,
     pub approval_required: bool,
     pub sla_duration_seconds: Option<i32>,
+    /// Fleet-wide cap on runs holding a slot; runs enqueued past it wait as `blocked`.
+    pub max_concurrent_runs: Option<i32>,
     pub metadata: Value,
+    /// JSON Schema every run's `automation_payload` must satisfy; `None` skips the check.
+    pub automation_payload_schema: Option<Value>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub version: i64,
@@ -34,7 +38,9 @@ pub async fn list_playbooks(
             owner_id,
             approval_required,
             sla_duration_seconds,
+            max_concurrent_runs,
             metadata,
+            automation_payload_schema,
             created_at,
             updated_at,
             version
@@ -54,7 +60,9 @@ pub struct CreateRuntimeVmRemediationPlaybook<'a> {
     pub owner_id: i32,
     pub approval_required: bool,
     pub sla_duration_seconds: Option<i32>,
+    pub max_concurrent_runs: Option<i32>,
     pub metadata: &'a Value,
+    pub automation_payload_schema: Option<&'a Value>,
 }
 
 pub async fn create_playbook<'c, E>(
@@ -74,9 +82,11 @@ where
             owner_id,
             approval_required,
             sla_duration_seconds,
-            metadata
+            metadata,
+            automation_payload_schema,
+            max_concurrent_runs
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
         RETURNING
             id,
             playbook_key,
@@ -86,7 +96,9 @@ where
             owner_id,
             approval_required,
             sla_duration_seconds,
+            max_concurrent_runs,
             metadata,
+            automation_payload_schema,
             created_at,
             updated_at,
             version
@@ -100,6 +112,8 @@ where
     .bind(input.approval_required)
     .bind(input.sla_duration_seconds)
     .bind(input.metadata)
+    .bind(input.automation_payload_schema)
+    .bind(input.max_concurrent_runs)
     .fetch_one(executor)
     .await
 }
@@ -137,7 +151,9 @@ pub async fn get_by_key(
             owner_id,
             approval_required,
             sla_duration_seconds,
+            max_concurrent_runs,
             metadata,
+            automation_payload_schema,
             created_at,
             updated_at,
             version
@@ -165,7 +181,9 @@ pub async fn get_by_id(
             owner_id,
             approval_required,
             sla_duration_seconds,
+            max_concurrent_runs,
             metadata,
+            automation_payload_schema,
             created_at,
             updated_at,
             version
@@ -185,7 +203,10 @@ pub struct UpdateRuntimeVmRemediationPlaybook<'a> {
     pub owner_id: Option<i32>,
     pub approval_required: Option<bool>,
     pub sla_duration_seconds: Option<Option<i32>>,
+    pub max_concurrent_runs: Option<Option<i32>>,
     pub metadata: Option<&'a Value>,
+    /// `Some(None)` clears the schema.
+    pub automation_payload_schema: Option<Option<&'a Value>>,
     pub expected_version: i64,
 }
 
@@ -199,6 +220,10 @@ where
 {
     let should_update_sla = update.sla_duration_seconds.is_some();
     let sla_value = update.sla_duration_seconds.flatten();
+    let should_update_schema = update.automation_payload_schema.is_some();
+    let schema_value = update.automation_payload_schema.flatten();
+    let should_update_limit = update.max_concurrent_runs.is_some();
+    let limit_value = update.max_concurrent_runs.flatten();
     let record = sqlx::query_as::<_, RuntimeVmRemediationPlaybook>(
         r#"
         UPDATE runtime_vm_remediation_playbooks
@@ -213,6 +238,14 @@ where
                 ELSE sla_duration_seconds
             END,
             metadata = COALESCE($10, metadata),
+            automation_payload_schema = CASE
+                WHEN $11 THEN $12
+                ELSE automation_payload_schema
+            END,
+            max_concurrent_runs = CASE
+                WHEN $13 THEN $14
+                ELSE max_concurrent_runs
+            END,
             version = version + 1
         WHERE id = $1
           AND version = $2
@@ -225,7 +258,9 @@ where
             owner_id,
             approval_required,
             sla_duration_seconds,
+            max_concurrent_runs,
             metadata,
+            automation_payload_schema,
             created_at,
             updated_at,
             version
@@ -241,6 +276,10 @@ where
     .bind(should_update_sla)
     .bind(sla_value)
     .bind(update.metadata)
+    .bind(should_update_schema)
+    .bind(schema_value)
+    .bind(should_update_limit)
+    .bind(limit_value)
     .fetch_optional(executor)
     .await?;
 
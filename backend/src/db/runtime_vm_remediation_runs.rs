@@ -30,6 +30,8 @@ pub struct RuntimeVmRemediationRun {
     pub cancelled_at: Option<DateTime<Utc>>,
     pub cancellation_reason: Option<String>,
     pub failure_reason: Option<String>,
+    /// Set for deferred runs; the run waits as `scheduled` until this time.
+    pub scheduled_for: Option<DateTime<Utc>>,
     pub analytics_duration_ms: Option<i64>,
     pub analytics_execution_started_at: Option<DateTime<Utc>>,
     pub analytics_execution_completed_at: Option<DateTime<Utc>>,
@@ -38,6 +40,9 @@ pub struct RuntimeVmRemediationRun {
     pub analytics_override_actor_id: Option<i32>,
     pub analytics_artifact_hash: Option<String>,
     pub analytics_promotion_verdict_id: Option<i64>,
+    /// Organization of the instance's server when the run was created; labels tenant metrics.
+    #[serde(skip)]
+    pub organization_id: Option<i32>,
 }
 
 pub struct ListRuntimeVmRemediationRuns<'a> {
@@ -45,14 +50,54 @@ pub struct ListRuntimeVmRemediationRuns<'a> {
     pub status: Option<&'a str>,
     pub workspace_id: Option<i64>,
     pub workspace_revision_id: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
-pub async fn list_runs(
-    pool: &PgPool,
-    filter: ListRuntimeVmRemediationRuns<'_>,
-) -> Result<Vec<RuntimeVmRemediationRun>, sqlx::Error> {
+/// Builds the filtered, newest-first run query shared by the array and streaming list
+/// endpoints, so both honour the same filters and pagination.
+pub fn list_runs_query<'a>(
+    filter: &ListRuntimeVmRemediationRuns<'a>,
+) -> QueryBuilder<'a, Postgres> {
     let mut builder = QueryBuilder::new(
-        "SELECT id, runtime_vm_instance_id, playbook, playbook_id, status, automation_payload, \\n         approval_required, started_at, completed_at, last_error, assigned_owner_id, sla_deadline, \\n         approval_state, approval_decided_at, approval_notes, metadata, workspace_id, \\n         workspace_revision_id, promotion_gate_context, version, updated_at, cancelled_at, \\n         cancellation_reason, failure_reason FROM runtime_vm_remediation_runs",
+        r#"
+        SELECT
+            id,
+            runtime_vm_instance_id,
+            playbook,
+            playbook_id,
+            status,
+            automation_payload,
+            approval_required,
+            started_at,
+            completed_at,
+            last_error,
+            assigned_owner_id,
+            sla_deadline,
+            approval_state,
+            approval_decided_at,
+            approval_notes,
+            metadata,
+            workspace_id,
+            workspace_revision_id,
+            promotion_gate_context,
+            version,
+            updated_at,
+            cancelled_at,
+            cancellation_reason,
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
+        FROM runtime_vm_remediation_runs
+        "#,
     );
     if filter.runtime_vm_instance_id.is_some()
         || filter.status.is_some()
@@ -96,9 +141,26 @@ pub async fn list_runs(
         builder.push_bind(revision_id);
     }
 
-    builder.push(" ORDER BY started_at DESC");
+    // id breaks ties so limit/offset pages are stable
+    builder.push(" ORDER BY started_at DESC, id DESC");
+
+    if let Some(limit) = filter.limit {
+        builder.push(" LIMIT ");
+        builder.push_bind(limit.max(0));
+    }
+    if let Some(offset) = filter.offset {
+        builder.push(" OFFSET ");
+        builder.push_bind(offset.max(0));
+    }
 
     builder
+}
+
+pub async fn list_runs(
+    pool: &PgPool,
+    filter: ListRuntimeVmRemediationRuns<'_>,
+) -> Result<Vec<RuntimeVmRemediationRun>, sqlx::Error> {
+    list_runs_query(&filter)
         .build_query_as::<RuntimeVmRemediationRun>()
         .fetch_all(pool)
         .await
@@ -135,6 +197,7 @@ pub async fn get_run_by_id(
             cancelled_at,
             cancellation_reason,
             failure_reason,
+            scheduled_for,
             analytics_duration_ms,
             analytics_execution_started_at,
             analytics_execution_completed_at,
@@ -142,7 +205,8 @@ pub async fn get_run_by_id(
             analytics_retry_ledger,
             analytics_override_actor_id,
             analytics_artifact_hash,
-            analytics_promotion_verdict_id
+            analytics_promotion_verdict_id,
+            organization_id
         FROM runtime_vm_remediation_runs
         WHERE id = $1
         "#,
@@ -164,6 +228,8 @@ pub struct EnsureRemediationRunRequest<'a> {
     pub workspace_id: Option<i64>,
     pub workspace_revision_id: Option<i64>,
     pub promotion_gate_context: Option<&'a Value>,
+    /// A future time defers the run; `None` or a past time queues it immediately.
+    pub scheduled_for: Option<DateTime<Utc>>,
 }
 
 pub async fn ensure_remediation_run<'c, E>(
@@ -190,13 +256,15 @@ where
                 metadata,
                 workspace_id,
                 workspace_revision_id,
-                promotion_gate_context
+                promotion_gate_context,
+                scheduled_for,
+                organization_id
             )
             SELECT
                 $1,
                 $2,
                 $3,
-                'pending',
+                CASE WHEN $12 > NOW() THEN 'scheduled' ELSE 'pending' END,
                 $4,
                 $5,
                 CASE WHEN $5 THEN 'pending' ELSE 'auto-approved' END,
@@ -209,12 +277,19 @@ where
                 COALESCE($8, '{}'::JSONB),
                 $9,
                 $10,
-                COALESCE($11, '{}'::JSONB)
+                COALESCE($11, '{}'::JSONB),
+                $12,
+                (
+                    SELECT s.organization_id
+                    FROM runtime_vm_instances i
+                    JOIN mcp_servers s ON s.id = i.server_id
+                    WHERE i.id = $1
+                )
             WHERE NOT EXISTS (
                 SELECT 1
                 FROM runtime_vm_remediation_runs
                 WHERE runtime_vm_instance_id = $1
-                  AND status IN ('pending', 'running')
+                  AND status IN ('scheduled', 'blocked', 'pending', 'running')
             )
             RETURNING
                 id,
@@ -240,7 +315,17 @@ where
                 updated_at,
                 cancelled_at,
                 cancellation_reason,
-                failure_reason
+                failure_reason,
+                scheduled_for,
+                analytics_duration_ms,
+                analytics_execution_started_at,
+                analytics_execution_completed_at,
+                analytics_retry_count,
+                analytics_retry_ledger,
+                analytics_override_actor_id,
+                analytics_artifact_hash,
+                analytics_promotion_verdict_id,
+                organization_id
         )
         SELECT
             id,
@@ -266,7 +351,17 @@ where
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         FROM inserted
         "#,
     )
@@ -281,6 +376,7 @@ where
     .bind(request.workspace_id)
     .bind(request.workspace_revision_id)
     .bind(request.promotion_gate_context)
+    .bind(request.scheduled_for)
     .fetch_optional(executor)
     .await?;
 
@@ -320,10 +416,20 @@ where
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         FROM runtime_vm_remediation_runs
         WHERE runtime_vm_instance_id = $1
-          AND status IN ('pending', 'running')
+          AND status IN ('scheduled', 'blocked', 'pending', 'running')
         ORDER BY started_at DESC
         LIMIT 1
         "#,
@@ -333,6 +439,100 @@ where
     .await
 }
 
+/// Queues scheduled runs whose time has come. A run over its playbook's concurrency
+/// limit lands in `blocked` instead of `pending`.
+pub async fn release_due_scheduled_runs<'c, E>(executor: E) -> Result<u64, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        WITH due AS (
+            SELECT id
+            FROM runtime_vm_remediation_runs
+            WHERE status = 'scheduled'
+              AND scheduled_for <= NOW()
+            ORDER BY scheduled_for, id
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE runtime_vm_remediation_runs AS runs
+        SET
+            status = 'pending',
+            version = runs.version + 1,
+            updated_at = NOW()
+        FROM due
+        WHERE runs.id = due.id
+        "#,
+    )
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Cancels a run that has not started executing. Returns `None` when the run does not
+/// exist or has already started or finished.
+pub async fn cancel_unstarted_run<'c, E>(
+    executor: E,
+    run_id: i64,
+    reason: Option<&str>,
+) -> Result<Option<RuntimeVmRemediationRun>, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    sqlx::query_as::<_, RuntimeVmRemediationRun>(
+        r#"
+        UPDATE runtime_vm_remediation_runs
+        SET
+            status = 'cancelled',
+            cancelled_at = NOW(),
+            cancellation_reason = $2,
+            version = version + 1,
+            updated_at = NOW()
+        WHERE id = $1
+          AND status IN ('scheduled', 'blocked', 'pending')
+        RETURNING
+            id,
+            runtime_vm_instance_id,
+            playbook,
+            playbook_id,
+            status,
+            automation_payload,
+            approval_required,
+            started_at,
+            completed_at,
+            last_error,
+            assigned_owner_id,
+            sla_deadline,
+            approval_state,
+            approval_decided_at,
+            approval_notes,
+            metadata,
+            workspace_id,
+            workspace_revision_id,
+            promotion_gate_context,
+            version,
+            updated_at,
+            cancelled_at,
+            cancellation_reason,
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
+        "#,
+    )
+    .bind(run_id)
+    .bind(reason)
+    .fetch_optional(executor)
+    .await
+}
+
 pub async fn try_acquire_next_run<'c, E>(
     executor: E,
 ) -> Result<Option<RuntimeVmRemediationRun>, sqlx::Error>
@@ -381,7 +581,16 @@ where
             runs.updated_at,
             runs.cancelled_at,
             runs.cancellation_reason,
-            runs.failure_reason
+            runs.failure_reason,
+            runs.scheduled_for,
+            runs.analytics_duration_ms,
+            runs.analytics_execution_started_at,
+            runs.analytics_execution_completed_at,
+            runs.analytics_retry_count,
+            runs.analytics_retry_ledger,
+            runs.analytics_override_actor_id,
+            runs.analytics_artifact_hash,
+            runs.analytics_promotion_verdict_id
         "#,
     )
     .fetch_optional(executor)
@@ -444,7 +653,17 @@ where
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         "#,
     )
     .bind(run_id)
@@ -506,7 +725,17 @@ where
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         "#,
     )
     .bind(run_id)
@@ -565,7 +794,17 @@ where
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         "#,
     )
     .bind(run_id)
@@ -578,6 +817,34 @@ where
     Ok(record)
 }
 
+/// Merges executor-reported resource usage into the run's metadata under `resource_usage`.
+pub async fn record_run_resource_usage<'c, E>(
+    executor: E,
+    run_id: i64,
+    usage: &Value,
+) -> Result<bool, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let result = sqlx::query(
+        r#"
+        UPDATE runtime_vm_remediation_runs
+        SET
+            metadata = COALESCE(metadata, '{}'::jsonb)
+                || jsonb_build_object('resource_usage', $2::jsonb),
+            version = version + 1,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(run_id)
+    .bind(usage)
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub struct UpdateApprovalState<'a> {
     pub run_id: i64,
     pub new_state: &'a str,
@@ -628,7 +895,17 @@ where
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         "#,
     )
     .bind(update.run_id)
@@ -12,6 +12,7 @@ pub struct RuntimeVmRemediationWorkspace {
     pub display_name: String,
     pub description: Option<String>,
     pub owner_id: i32,
+    pub organization_id: Option<i32>,
     pub lifecycle_state: String,
     pub active_revision_id: Option<i64>,
     pub metadata: Value,
@@ -99,6 +100,7 @@ pub struct CreateWorkspace<'a> {
     pub display_name: &'a str,
     pub description: Option<&'a str>,
     pub owner_id: i32,
+    pub organization_id: Option<i32>,
     pub plan: &'a Value,
     pub metadata: Option<&'a Value>,
     pub lineage_tags: &'a [&'a str],
@@ -177,12 +179,13 @@ pub async fn create_workspace(
             display_name,
             description,
             owner_id,
+            organization_id,
             lifecycle_state,
             metadata,
             lineage_tags
         )
-        VALUES ($1, $2, $3, $4, 'draft', COALESCE($5, '{}'::JSONB), $6)
-        RETURNING id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        VALUES ($1, $2, $3, $4, $5, 'draft', COALESCE($6, '{}'::JSONB), $7)
+        RETURNING id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                   active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         "#,
     )
@@ -190,6 +193,7 @@ pub async fn create_workspace(
     .bind(params.display_name)
     .bind(params.description)
     .bind(params.owner_id)
+    .bind(params.organization_id)
     .bind(params.metadata)
     .bind(params.lineage_tags)
     .fetch_one(&mut *tx)
@@ -231,7 +235,7 @@ pub async fn create_workspace(
             version = version + 1,
             updated_at = NOW()
         WHERE id = $1
-        RETURNING id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        RETURNING id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                   active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         "#,
     )
@@ -247,23 +251,34 @@ pub async fn create_workspace(
         .ok_or_else(|| sqlx::Error::RowNotFound)
 }
 
+/// Workspaces `user_id` may see: those in organizations they belong to, plus their own
+/// unscoped workspaces.
 pub async fn list_workspaces(
     pool: &PgPool,
+    user_id: i32,
 ) -> Result<Vec<RuntimeVmRemediationWorkspace>, sqlx::Error> {
     sqlx::query_as::<_, RuntimeVmRemediationWorkspace>(
         r#"
-        SELECT id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        SELECT id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         FROM runtime_vm_remediation_workspaces
+        WHERE (organization_id IS NULL AND owner_id = $1)
+           OR organization_id IN (
+               SELECT organization_id FROM organization_members WHERE user_id = $1
+           )
         ORDER BY created_at DESC
         "#,
     )
+    .bind(user_id)
     .fetch_all(pool)
     .await
 }
 
-pub async fn list_workspace_details(pool: &PgPool) -> Result<Vec<WorkspaceDetails>, sqlx::Error> {
-    let workspaces = list_workspaces(pool).await?;
+pub async fn list_workspace_details(
+    pool: &PgPool,
+    user_id: i32,
+) -> Result<Vec<WorkspaceDetails>, sqlx::Error> {
+    let workspaces = list_workspaces(pool, user_id).await?;
     let mut details = Vec::with_capacity(workspaces.len());
     for workspace in workspaces {
         if let Some(view) = load_workspace_details(pool, workspace.id).await? {
@@ -280,19 +295,22 @@ pub async fn get_workspace(
     load_workspace_details(pool, workspace_id).await
 }
 
+/// Looks up a workspace by key within an organization scope; `None` targets unscoped workspaces.
 pub async fn get_workspace_by_key(
     pool: &PgPool,
+    organization_id: Option<i32>,
     workspace_key: &str,
 ) -> Result<Option<WorkspaceDetails>, sqlx::Error> {
     let workspace = sqlx::query_as::<_, RuntimeVmRemediationWorkspace>(
         r#"
-        SELECT id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        SELECT id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         FROM runtime_vm_remediation_workspaces
-        WHERE workspace_key = $1
+        WHERE workspace_key = $1 AND organization_id IS NOT DISTINCT FROM $2
         "#,
     )
     .bind(workspace_key)
+    .bind(organization_id)
     .fetch_optional(pool)
     .await?;
 
@@ -312,7 +330,7 @@ pub async fn create_revision(
 
     let current = sqlx::query_as::<_, RuntimeVmRemediationWorkspace>(
         r#"
-        SELECT id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        SELECT id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         FROM runtime_vm_remediation_workspaces
         WHERE id = $1
@@ -378,7 +396,7 @@ pub async fn create_revision(
             version = version + 1,
             updated_at = NOW()
         WHERE id = $1 AND version = $3
-        RETURNING id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        RETURNING id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                   active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         "#,
     )
@@ -672,7 +690,7 @@ pub async fn apply_promotion(
                 version = version + 1,
                 updated_at = NOW()
             WHERE id = $1 AND version = $3
-            RETURNING id, workspace_key, display_name, description, owner_id, lifecycle_state,
+            RETURNING id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                       active_revision_id, metadata, lineage_tags, created_at, updated_at, version
             "#,
         )
@@ -708,13 +726,180 @@ pub async fn apply_promotion(
     load_workspace_details(pool, params.workspace_id).await
 }
 
+/// Cold-storage record for an archived workspace. The workspace row and its runs stay in place;
+/// `snapshot` freezes the lifecycle console view taken at archival time.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RuntimeVmRemediationWorkspaceArchive {
+    pub workspace_id: i64,
+    pub previous_lifecycle_state: String,
+    pub snapshot: Value,
+    pub archived_by: Option<i32>,
+    pub archived_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveWorkspace<'a> {
+    pub workspace_id: i64,
+    pub archived_by: i32,
+    pub snapshot: &'a Value,
+    pub expected_workspace_version: i64,
+}
+
+/// Moves a workspace to the archive. Returns `None` when the workspace is missing, already
+/// archived, or no longer at `expected_workspace_version`.
+pub async fn archive_workspace(
+    pool: &PgPool,
+    params: ArchiveWorkspace<'_>,
+) -> Result<Option<RuntimeVmRemediationWorkspaceArchive>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous_state: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT lifecycle_state
+        FROM runtime_vm_remediation_workspaces
+        WHERE id = $1 AND version = $2 AND lifecycle_state <> 'archived'
+        FOR UPDATE
+        "#,
+    )
+    .bind(params.workspace_id)
+    .bind(params.expected_workspace_version)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(previous_state) = previous_state else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE runtime_vm_remediation_workspaces
+        SET lifecycle_state = 'archived',
+            version = version + 1,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(params.workspace_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let archive = sqlx::query_as::<_, RuntimeVmRemediationWorkspaceArchive>(
+        r#"
+        INSERT INTO runtime_vm_remediation_workspace_archives (
+            workspace_id, previous_lifecycle_state, snapshot, archived_by
+        ) VALUES ($1, $2, $3, $4)
+        RETURNING workspace_id, previous_lifecycle_state, snapshot, archived_by, archived_at
+        "#,
+    )
+    .bind(params.workspace_id)
+    .bind(&previous_state)
+    .bind(params.snapshot)
+    .bind(params.archived_by)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(archive))
+}
+
+pub async fn get_workspace_archive(
+    pool: &PgPool,
+    workspace_id: i64,
+) -> Result<Option<RuntimeVmRemediationWorkspaceArchive>, sqlx::Error> {
+    sqlx::query_as::<_, RuntimeVmRemediationWorkspaceArchive>(
+        r#"
+        SELECT workspace_id, previous_lifecycle_state, snapshot, archived_by, archived_at
+        FROM runtime_vm_remediation_workspace_archives
+        WHERE workspace_id = $1
+        "#,
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Takes a workspace out of the archive, putting back the lifecycle state it had before.
+/// Returns `None` when the workspace is not archived.
+pub async fn restore_archived_workspace(
+    pool: &PgPool,
+    workspace_id: i64,
+) -> Result<Option<RuntimeVmRemediationWorkspace>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous_state: Option<String> = sqlx::query_scalar(
+        r#"
+        DELETE FROM runtime_vm_remediation_workspace_archives
+        WHERE workspace_id = $1
+        RETURNING previous_lifecycle_state
+        "#,
+    )
+    .bind(workspace_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(previous_state) = previous_state else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    let workspace = sqlx::query_as::<_, RuntimeVmRemediationWorkspace>(
+        r#"
+        UPDATE runtime_vm_remediation_workspaces
+        SET lifecycle_state = $2,
+            version = version + 1,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
+                  active_revision_id, metadata, lineage_tags, created_at, updated_at, version
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(&previous_state)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(workspace))
+}
+
+/// Deletes validation snapshots beyond the newest `keep_latest` per `(revision, snapshot_type)`.
+/// `keep_latest` is clamped to at least one so the most recent snapshot of each type is retained.
+pub async fn prune_validation_snapshots(
+    pool: &PgPool,
+    keep_latest: i64,
+) -> Result<u64, sqlx::Error> {
+    let keep_latest = keep_latest.max(1);
+    let result = sqlx::query(
+        r#"
+        DELETE FROM runtime_vm_remediation_workspace_validation_snapshots
+        WHERE id IN (
+            SELECT id
+            FROM (
+                SELECT id,
+                       ROW_NUMBER() OVER (
+                           PARTITION BY workspace_revision_id, snapshot_type
+                           ORDER BY recorded_at DESC, id DESC
+                       ) AS position
+                FROM runtime_vm_remediation_workspace_validation_snapshots
+            ) ranked
+            WHERE ranked.position > $1
+        )
+        "#,
+    )
+    .bind(keep_latest)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
 async fn load_workspace_details(
     pool: &PgPool,
     workspace_id: i64,
 ) -> Result<Option<WorkspaceDetails>, sqlx::Error> {
     let workspace = sqlx::query_as::<_, RuntimeVmRemediationWorkspace>(
         r#"
-        SELECT id, workspace_key, display_name, description, owner_id, lifecycle_state,
+        SELECT id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state,
                active_revision_id, metadata, lineage_tags, created_at, updated_at, version
         FROM runtime_vm_remediation_workspaces
         WHERE id = $1
@@ -176,6 +176,94 @@ pub async fn history_for_instance(
     Ok(rows.iter().map(map_row).collect())
 }
 
+/// Events triggered within `[from, to)`, oldest first, optionally for one instance.
+pub async fn events_between(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    runtime_vm_instance_id: Option<i64>,
+) -> Result<Vec<RuntimeVmTrustEvent>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            id,
+            runtime_vm_instance_id,
+            attestation_id,
+            previous_status,
+            current_status,
+            previous_lifecycle_state,
+            current_lifecycle_state,
+            transition_reason,
+            remediation_state,
+            remediation_attempts,
+            freshness_deadline,
+            provenance_ref,
+            provenance,
+            triggered_at,
+            metadata,
+            created_at
+        FROM runtime_vm_trust_history
+        WHERE triggered_at >= $1
+          AND triggered_at < $2
+          AND ($3::BIGINT IS NULL OR runtime_vm_instance_id = $3)
+        ORDER BY triggered_at ASC, id ASC
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(runtime_vm_instance_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(map_row).collect())
+}
+
+/// The last replay applied to an instance: `(last_event_id, registry_version)`.
+pub async fn replay_mark(
+    pool: &PgPool,
+    runtime_vm_instance_id: i64,
+) -> Result<Option<(i64, i64)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT last_event_id, registry_version FROM runtime_vm_trust_replays WHERE runtime_vm_instance_id = $1",
+    )
+    .bind(runtime_vm_instance_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn record_replay_mark(
+    pool: &PgPool,
+    runtime_vm_instance_id: i64,
+    last_event_id: i64,
+    registry_version: i64,
+    replayed_by: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO runtime_vm_trust_replays (
+            runtime_vm_instance_id,
+            last_event_id,
+            registry_version,
+            replayed_by
+        )
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (runtime_vm_instance_id) DO UPDATE
+        SET
+            last_event_id = EXCLUDED.last_event_id,
+            registry_version = EXCLUDED.registry_version,
+            replayed_by = EXCLUDED.replayed_by,
+            replayed_at = NOW()
+        "#,
+    )
+    .bind(runtime_vm_instance_id)
+    .bind(last_event_id)
+    .bind(registry_version)
+    .bind(replayed_by)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 fn map_row(row: &PgRow) -> RuntimeVmTrustEvent {
     RuntimeVmTrustEvent {
         id: row.get("id"),
@@ -17,6 +17,15 @@ pub struct RuntimeVmTrustRegistryState {
     pub updated_at: DateTime<Utc>,
 }
 
+/// One instance of a server with its trust state, if the registry has a row for it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct InstanceTrustState {
+    pub runtime_vm_instance_id: i64,
+    pub instance_id: String,
+    /// `None` while the instance has no registry row, i.e. its trust is still unknown.
+    pub state: Option<RuntimeVmTrustRegistryState>,
+}
+
 #[derive(Debug, Clone)]
 pub struct UpsertRuntimeVmTrustRegistryState<'a> {
     pub runtime_vm_instance_id: i64,
@@ -236,6 +245,55 @@ pub async fn apply_transition(
     }
 }
 
+/// Returns every instance of `server_id`, including those the registry has not seen yet.
+pub async fn list_states_for_server<'c, E>(
+    executor: E,
+    server_id: i32,
+) -> Result<Vec<InstanceTrustState>, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            instances.id AS vm_instance_id,
+            instances.instance_id,
+            registry.runtime_vm_instance_id,
+            registry.attestation_status,
+            registry.lifecycle_state,
+            registry.remediation_state,
+            registry.remediation_attempts,
+            registry.freshness_deadline,
+            registry.provenance_ref,
+            registry.provenance,
+            registry.version,
+            registry.updated_at
+        FROM runtime_vm_instances instances
+        LEFT JOIN runtime_vm_trust_registry registry
+            ON registry.runtime_vm_instance_id = instances.id
+        WHERE instances.server_id = $1
+        ORDER BY instances.id
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let has_registry_row = row
+                .get::<Option<i64>, _>("runtime_vm_instance_id")
+                .is_some();
+            InstanceTrustState {
+                runtime_vm_instance_id: row.get("vm_instance_id"),
+                instance_id: row.get("instance_id"),
+                state: has_registry_row.then(|| map_row(row)),
+            }
+        })
+        .collect())
+}
+
 fn map_row(row: &PgRow) -> RuntimeVmTrustRegistryState {
     RuntimeVmTrustRegistryState {
         runtime_vm_instance_id: row.get("runtime_vm_instance_id"),
@@ -1,11 +1,12 @@
 use crate::capabilities;
 use crate::policy::{PolicyDecision, RuntimeBackend};
 use crate::proxy;
+use crate::runtime::ResourceUsage;
 use crate::servers::{add_metric, set_status};
 use bollard::models::{ContainerCreateBody, HostConfig};
 use bollard::query_parameters::{
     CreateContainerOptionsBuilder, LogsOptionsBuilder, RemoveContainerOptionsBuilder,
-    StopContainerOptionsBuilder,
+    StatsOptionsBuilder, StopContainerOptionsBuilder,
 };
 use bollard::Docker;
 use serde_json::Value;
@@ -373,6 +374,40 @@ pub fn delete_vector_db_task(id: i32, pool: PgPool) {
 }
 
 /// Fetch the latest logs for a container.
+/// Reads the container's cgroup counters through a one-shot stats call.
+pub async fn resource_usage(server_id: i32) -> Option<ResourceUsage> {
+    use futures_util::StreamExt;
+
+    let docker = Docker::connect_with_local_defaults().ok()?;
+    let name = format!("mcp-server-{server_id}");
+    let stats = docker
+        .stats(
+            &name,
+            Some(
+                StatsOptionsBuilder::default()
+                    .stream(false)
+                    .one_shot(true)
+                    .build(),
+            ),
+        )
+        .next()
+        .await?
+        .ok()?;
+
+    let cpu_seconds = stats
+        .cpu_stats
+        .as_ref()
+        .and_then(|cpu| cpu.cpu_usage.as_ref())
+        .and_then(|usage| usage.total_usage)
+        .map(|nanos| nanos as f64 / 1_000_000_000.0);
+    // cgroup v2 hosts do not expose a high-water mark, so peak memory stays unmeasured there
+    let peak_memory_bytes = stats
+        .memory_stats
+        .as_ref()
+        .and_then(|memory| memory.max_usage);
+    ResourceUsage::measured(cpu_seconds, peak_memory_bytes)
+}
+
 pub async fn fetch_logs(server_id: i32) -> Result<String, bollard::errors::Error> {
     use futures_util::StreamExt;
 
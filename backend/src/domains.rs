@@ -1,12 +1,14 @@
-use crate::{extractor::AuthUser, proxy};
+use crate::{config, db::mcp_servers, extractor::AuthUser, proxy};
+use async_trait::async_trait;
 use axum::{
     extract::{Extension, Path},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Row};
-use tracing::error;
+use serde_json::Value;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use tracing::{error, warn};
 
 #[derive(Serialize)]
 pub struct Domain {
@@ -25,16 +27,13 @@ pub async fn list_domains(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> Result<Json<Vec<Domain>>, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     let rows = sqlx::query(
@@ -67,16 +66,13 @@ pub async fn create_domain(
     if payload.domain.trim().is_empty() {
         return Err((StatusCode::BAD_REQUEST, "Domain required".into()));
     }
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     sqlx::query("INSERT INTO custom_domains (server_id, domain) VALUES ($1, $2)")
@@ -97,16 +93,13 @@ pub async fn delete_domain(
     AuthUser { user_id, .. }: AuthUser,
     Path((server_id, domain_id)): Path<(i32, i32)>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     let result = sqlx::query("DELETE FROM custom_domains WHERE id = $1 AND server_id = $2")
@@ -124,3 +117,388 @@ pub async fn delete_domain(
     proxy::rebuild_for_server(&pool, server_id).await;
     Ok(StatusCode::NO_CONTENT)
 }
+
+// key: domain-verification -> DNS TXT ownership challenge
+
+/// Label prepended to the domain for the challenge record.
+pub const CHALLENGE_LABEL: &str = "_mcp-challenge";
+
+#[derive(Serialize, Debug)]
+pub struct DomainVerification {
+    pub domain_id: i32,
+    pub domain: String,
+    pub status: String,
+    pub record_name: String,
+    pub record_value: Option<String>,
+    pub requested_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub checked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub verified_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl DomainVerification {
+    fn from_row(row: &PgRow) -> Self {
+        let domain: String = row.get("domain");
+        let token: Option<String> = row.get("verification_token");
+        Self {
+            domain_id: row.get("id"),
+            record_name: challenge_record_name(&domain),
+            domain,
+            status: row.get("verification_status"),
+            record_value: token.as_deref().map(challenge_record_value),
+            requested_at: row.get("verification_requested_at"),
+            checked_at: row.get("verification_checked_at"),
+            verified_at: row.get("verified_at"),
+        }
+    }
+}
+
+pub fn challenge_record_name(domain: &str) -> String {
+    format!("{CHALLENGE_LABEL}.{}", domain.trim().trim_end_matches('.'))
+}
+
+pub fn challenge_record_value(token: &str) -> String {
+    format!("mcp-verification={token}")
+}
+
+/// Looks up TXT records; stubbed in tests.
+#[async_trait]
+pub trait TxtResolver: Send + Sync {
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, String>;
+}
+
+/// Resolves TXT records through a JSON DNS-over-HTTPS endpoint.
+pub struct DohTxtResolver {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl DohTxtResolver {
+    pub fn from_config() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: config::DOMAIN_VERIFICATION_DOH_URL.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl TxtResolver for DohTxtResolver {
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, String> {
+        let body: Value = self
+            .client
+            .get(&self.endpoint)
+            .query(&[("name", name), ("type", "TXT")])
+            .header("accept", "application/dns-json")
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(parse_doh_txt_answers(&body))
+    }
+}
+
+/// Extracts TXT strings from a DoH JSON answer, joining multi-string records.
+pub fn parse_doh_txt_answers(body: &Value) -> Vec<String> {
+    body.get("Answer")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|answer| answer.get("type").and_then(Value::as_u64) == Some(16))
+        .filter_map(|answer| answer.get("data").and_then(Value::as_str))
+        .map(|data| {
+            let trimmed = data.trim();
+            if trimmed.starts_with('"') {
+                trimmed.split('"').skip(1).step_by(2).collect::<String>()
+            } else {
+                trimmed.to_string()
+            }
+        })
+        .collect()
+}
+
+const VERIFICATION_COLUMNS: &str =
+    "cd.id, cd.domain, cd.verification_status, cd.verification_token, \
+     cd.verification_requested_at, cd.verification_checked_at, cd.verified_at";
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    error!(?e, "DB error during domain verification");
+    (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+}
+
+async fn load_owned_domain(
+    pool: &PgPool,
+    domain_id: i32,
+    user_id: i32,
+) -> Result<PgRow, (StatusCode, String)> {
+    sqlx::query(&format!(
+        "SELECT {VERIFICATION_COLUMNS} FROM custom_domains cd \
+         JOIN mcp_servers s ON s.id = cd.server_id \
+         WHERE cd.id = $1 AND s.owner_id = $2"
+    ))
+    .bind(domain_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?
+    .ok_or((StatusCode::NOT_FOUND, "Domain not found".into()))
+}
+
+/// Issues a challenge token, reusing the outstanding one so repeated calls are stable.
+pub async fn initiate_verification_for(
+    pool: &PgPool,
+    domain_id: i32,
+    user_id: i32,
+) -> Result<DomainVerification, (StatusCode, String)> {
+    let row = load_owned_domain(pool, domain_id, user_id).await?;
+    let status: String = row.get("verification_status");
+    let token: Option<String> = row.get("verification_token");
+    if status == "verified" || token.is_some() {
+        return Ok(DomainVerification::from_row(&row));
+    }
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let row = sqlx::query(&format!(
+        "UPDATE custom_domains cd SET verification_token = COALESCE(cd.verification_token, $2), \
+         verification_requested_at = COALESCE(cd.verification_requested_at, NOW()) \
+         WHERE cd.id = $1 RETURNING {VERIFICATION_COLUMNS}"
+    ))
+    .bind(domain_id)
+    .bind(&token)
+    .fetch_one(pool)
+    .await
+    .map_err(db_error)?;
+    Ok(DomainVerification::from_row(&row))
+}
+
+/// Looks for the challenge record and marks the domain verified when it is published.
+///
+/// Verified domains return immediately. Otherwise checks closer together than
+/// `min_interval_secs` are rejected with 429 before any lookup happens.
+pub async fn check_verification_for(
+    pool: &PgPool,
+    resolver: &dyn TxtResolver,
+    domain_id: i32,
+    user_id: i32,
+    min_interval_secs: i64,
+) -> Result<DomainVerification, (StatusCode, String)> {
+    let row = load_owned_domain(pool, domain_id, user_id).await?;
+    let current = DomainVerification::from_row(&row);
+    if current.status == "verified" {
+        return Ok(current);
+    }
+    let Some(expected) = current.record_value.clone() else {
+        return Err((
+            StatusCode::CONFLICT,
+            "Verification has not been initiated".into(),
+        ));
+    };
+
+    // claim the check slot atomically so concurrent callers cannot bypass the limit
+    let claimed = sqlx::query(
+        "UPDATE custom_domains SET verification_checked_at = NOW() \
+         WHERE id = $1 AND (verification_checked_at IS NULL \
+             OR verification_checked_at <= NOW() - make_interval(secs => $2::double precision))",
+    )
+    .bind(domain_id)
+    .bind(min_interval_secs as f64)
+    .execute(pool)
+    .await
+    .map_err(db_error)?;
+    if claimed.rows_affected() == 0 {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("Verification checks are limited to one every {min_interval_secs} seconds"),
+        ));
+    }
+
+    let records = match resolver.lookup_txt(&current.record_name).await {
+        Ok(records) => records,
+        Err(err) => {
+            warn!(%err, domain_id, "TXT lookup failed");
+            Vec::new()
+        }
+    };
+    let verified = records.iter().any(|record| record.trim() == expected);
+    let row = sqlx::query(&format!(
+        "UPDATE custom_domains cd SET \
+             verification_status = CASE WHEN $2 THEN 'verified' ELSE cd.verification_status END, \
+             verified_at = CASE WHEN $2 THEN COALESCE(cd.verified_at, NOW()) ELSE cd.verified_at END \
+         WHERE cd.id = $1 RETURNING {VERIFICATION_COLUMNS}"
+    ))
+    .bind(domain_id)
+    .bind(verified)
+    .fetch_one(pool)
+    .await
+    .map_err(db_error)?;
+    Ok(DomainVerification::from_row(&row))
+}
+
+pub async fn initiate_verification(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(domain_id): Path<i32>,
+) -> Result<Json<DomainVerification>, (StatusCode, String)> {
+    initiate_verification_for(&pool, domain_id, user_id)
+        .await
+        .map(Json)
+}
+
+pub async fn check_verification(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(domain_id): Path<i32>,
+) -> Result<Json<DomainVerification>, (StatusCode, String)> {
+    let resolver = DohTxtResolver::from_config();
+    check_verification_for(
+        &pool,
+        &resolver,
+        domain_id,
+        user_id,
+        *config::DOMAIN_VERIFICATION_CHECK_INTERVAL_SECS,
+    )
+    .await
+    .map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    struct StubResolver {
+        records: Vec<String>,
+        lookups: Mutex<Vec<String>>,
+    }
+
+    impl StubResolver {
+        fn new(records: Vec<String>) -> Self {
+            Self {
+                records,
+                lookups: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TxtResolver for StubResolver {
+        async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, String> {
+            self.lookups.lock().unwrap().push(name.to_string());
+            Ok(self.records.clone())
+        }
+    }
+
+    async fn seed_domain(pool: &PgPool) -> (i32, i32) {
+        let user_id: i32 = sqlx::query_scalar(
+            "INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id",
+        )
+        .bind("domains@example.com")
+        .bind("hash")
+        .fetch_one(pool)
+        .await
+        .expect("user");
+        let server_id: i32 = sqlx::query_scalar(
+            "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, 'web', 'router', '{}'::jsonb, 'active', 'key') RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("server");
+        let domain_id: i32 = sqlx::query_scalar(
+            "INSERT INTO custom_domains (server_id, domain) VALUES ($1, 'api.example.com') RETURNING id",
+        )
+        .bind(server_id)
+        .fetch_one(pool)
+        .await
+        .expect("domain");
+        (user_id, domain_id)
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn published_record_verifies_domain(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let (user_id, domain_id) = seed_domain(&pool).await;
+
+        let challenge = initiate_verification_for(&pool, domain_id, user_id)
+            .await
+            .unwrap();
+        assert_eq!(challenge.status, "pending");
+        assert_eq!(challenge.record_name, "_mcp-challenge.api.example.com");
+        let value = challenge.record_value.clone().unwrap();
+        let again = initiate_verification_for(&pool, domain_id, user_id)
+            .await
+            .unwrap();
+        assert_eq!(again.record_value.as_deref(), Some(value.as_str()));
+
+        let resolver = StubResolver::new(vec!["unrelated".into(), value]);
+        let result = check_verification_for(&pool, &resolver, domain_id, user_id, 0)
+            .await
+            .unwrap();
+        assert_eq!(result.status, "verified");
+        let verified_at = result.verified_at.expect("verified timestamp");
+        assert_eq!(
+            *resolver.lookups.lock().unwrap(),
+            vec!["_mcp-challenge.api.example.com".to_string()]
+        );
+
+        // verified domains short-circuit without another lookup or rate limit
+        let repeat = check_verification_for(&pool, &resolver, domain_id, user_id, 3600)
+            .await
+            .unwrap();
+        assert_eq!(repeat.verified_at, Some(verified_at));
+        assert_eq!(resolver.lookups.lock().unwrap().len(), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn missing_record_stays_pending_and_is_rate_limited(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let (user_id, domain_id) = seed_domain(&pool).await;
+        let resolver = StubResolver::new(vec!["mcp-verification=stale".into()]);
+
+        let err = check_verification_for(&pool, &resolver, domain_id, user_id, 0)
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::CONFLICT);
+
+        initiate_verification_for(&pool, domain_id, user_id)
+            .await
+            .unwrap();
+        let result = check_verification_for(&pool, &resolver, domain_id, user_id, 3600)
+            .await
+            .unwrap();
+        assert_eq!(result.status, "pending");
+        assert!(result.verified_at.is_none());
+        assert!(result.checked_at.is_some());
+
+        let err = check_verification_for(&pool, &resolver, domain_id, user_id, 3600)
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resolver.lookups.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn doh_answers_are_unquoted_and_joined() {
+        let body = json!({
+            "Status": 0,
+            "Answer": [
+                {"name": "_mcp-challenge.example.com", "type": 16, "data": "\"mcp-verification=\" \"abc\""},
+                {"name": "_mcp-challenge.example.com", "type": 16, "data": "\"other\""},
+                {"name": "example.com", "type": 5, "data": "alias.example.com."}
+            ]
+        });
+        assert_eq!(
+            parse_doh_txt_answers(&body),
+            vec!["mcp-verification=abc".to_string(), "other".to_string()]
+        );
+        assert!(parse_doh_txt_answers(&json!({"Status": 3})).is_empty());
+        assert_eq!(
+            challenge_record_name("Example.com."),
+            "_mcp-challenge.Example.com"
+        );
+    }
+}
@@ -28,6 +28,12 @@ pub enum AppError {
     Conflict(String),
     #[error("bad gateway: {0}")]
     BadGateway(String),
+    #[error("gateway timeout: {0}")]
+    GatewayTimeout(String),
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+    #[error("service unavailable: {0}")]
+    ServiceUnavailable(String),
     #[error("{0}")]
     Message(String),
 }
@@ -47,6 +53,9 @@ impl IntoResponse for AppError {
                     AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
                     AppError::Conflict(_) => StatusCode::CONFLICT,
                     AppError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+                    AppError::GatewayTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+                    AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                    AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
                     AppError::Db(_)
                     | AppError::Docker(_)
                     | AppError::Vault(_)
@@ -1,10 +1,14 @@
+use crate::db::mcp_servers;
 use crate::error::{AppError, AppResult};
+use crate::evaluations::cron::CronSchedule;
+use crate::evaluations::regression::{compare_run_metrics, load_run_metrics, RegressionReport};
 use crate::evaluations::{
     CertificationPlanDelta, CertificationStatus, CertificationUpsert, EvaluationCertification,
 };
 use crate::extractor::AuthUser;
+use crate::proxy::pool::UPSTREAM_CLIENTS;
 use axum::{
-    extract::{Extension, Path},
+    extract::{Extension, Path, Query},
     Json,
 };
 use chrono::{DateTime, Utc};
@@ -86,12 +90,7 @@ pub async fn list_tests(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> AppResult<Json<Vec<EvaluationTest>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id=$1 AND owner_id=$2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await?;
-    if rec.is_none() {
+    if !mcp_servers::is_live_owned_by(&pool, server_id, user_id).await? {
         return Err(AppError::NotFound);
     }
     let rows = sqlx::query(
@@ -120,7 +119,7 @@ pub async fn create_test(
 ) -> AppResult<Json<EvaluationTest>> {
     let rec = sqlx::query(
         "INSERT INTO evaluation_tests (server_id, question, expected_answer) \
-         SELECT id, $2, $3 FROM mcp_servers WHERE id=$1 AND owner_id=$4 RETURNING id, created_at",
+         SELECT id, $2, $3 FROM mcp_servers WHERE id=$1 AND owner_id=$4 AND deleted_at IS NULL RETURNING id, created_at",
     )
     .bind(server_id)
     .bind(&payload.question)
@@ -142,12 +141,7 @@ pub async fn list_results(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> AppResult<Json<Vec<EvaluationResult>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id=$1 AND owner_id=$2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await?;
-    if rec.is_none() {
+    if !mcp_servers::is_live_owned_by(&pool, server_id, user_id).await? {
         return Err(AppError::NotFound);
     }
     let rows = sqlx::query(
@@ -171,6 +165,7 @@ pub async fn list_results(
 
 #[derive(Serialize)]
 pub struct RunSummary {
+    pub run_id: i32,
     pub results: Vec<EvaluationResult>,
 }
 
@@ -179,15 +174,24 @@ pub async fn run_tests(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> AppResult<Json<RunSummary>> {
-    let row = sqlx::query("SELECT api_key FROM mcp_servers WHERE id=$1 AND owner_id=$2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await?;
+    let row = sqlx::query(
+        "SELECT api_key FROM mcp_servers WHERE id=$1 AND owner_id=$2 AND deleted_at IS NULL",
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await?;
     let Some(row) = row else {
         return Err(AppError::NotFound);
     };
     let api_key: String = row.get("api_key");
+    let run_id: i32 = sqlx::query_scalar(
+        "INSERT INTO evaluation_runs (server_id, triggered_by) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await?;
     let tests = sqlx::query(
         "SELECT id, question, expected_answer FROM evaluation_tests WHERE server_id=$1",
     )
@@ -200,8 +204,8 @@ pub async fn run_tests(
         let question: String = row.get("question");
         let expected: String = row.get("expected_answer");
         let input = serde_json::json!({"question": question});
-        let client = reqwest::Client::new();
-        let resp_text = match client
+        let resp_text = match UPSTREAM_CLIENTS
+            .client(server_id)
             .post(format!("http://mcp-server-{server_id}:8080/invoke"))
             .header("Authorization", format!("Bearer {}", api_key))
             .json(&input)
@@ -213,11 +217,12 @@ pub async fn run_tests(
         };
         let score = jaro_winkler(&expected, &resp_text);
         let rec = sqlx::query(
-            "INSERT INTO evaluation_results (test_id, response, score) VALUES ($1,$2,$3) RETURNING id, created_at"
+            "INSERT INTO evaluation_results (test_id, response, score, run_id) VALUES ($1,$2,$3,$4) RETURNING id, created_at"
         )
         .bind(test_id)
         .bind(&resp_text)
         .bind(score)
+        .bind(run_id)
         .fetch_one(&pool)
         .await?;
         results.push(EvaluationResult {
@@ -228,7 +233,43 @@ pub async fn run_tests(
             created_at: rec.get("created_at"),
         });
     }
-    Ok(Json(RunSummary { results }))
+    Ok(Json(RunSummary { run_id, results }))
+}
+
+#[derive(Deserialize)]
+pub struct CompareRunsQuery {
+    pub baseline: i32,
+    pub candidate: i32,
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+}
+
+pub async fn compare_runs(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Query(query): Query<CompareRunsQuery>,
+) -> AppResult<Json<RegressionReport>> {
+    let tolerance = query
+        .tolerance
+        .unwrap_or(*crate::config::EVALUATION_REGRESSION_TOLERANCE);
+    if !tolerance.is_finite() || tolerance < 0.0 {
+        return Err(AppError::BadRequest(
+            "tolerance must be a non-negative number".into(),
+        ));
+    }
+    let baseline = load_run_metrics(&pool, query.baseline, user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let candidate = load_run_metrics(&pool, query.candidate, user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(compare_run_metrics(
+        query.baseline,
+        query.candidate,
+        &baseline,
+        &candidate,
+        tolerance,
+    )))
 }
 
 pub async fn list_all_results(
@@ -443,6 +484,76 @@ pub async fn override_certification_plan(
     Ok(Json(updated))
 }
 
+#[derive(Deserialize)]
+pub struct CertificationScheduleRequest {
+    /// Five-field cron expression; `null` reverts to cadence-based refreshes.
+    pub cron: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CertificationSchedule {
+    pub certification_id: i32,
+    pub schedule_cron: Option<String>,
+    pub next_refresh_at: Option<DateTime<Utc>>,
+    pub timezone: String,
+}
+
+pub async fn set_certification_schedule(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(certification_id): Path<i32>,
+    Json(payload): Json<CertificationScheduleRequest>,
+) -> AppResult<Json<CertificationSchedule>> {
+    ensure_certification_access(&pool, certification_id, user_id).await?;
+    let offset = *crate::config::EVALUATION_SCHEDULE_TZ;
+
+    let row = match payload.cron.as_deref().map(str::trim) {
+        Some(expression) => {
+            let schedule = CronSchedule::parse(expression)
+                .map_err(|err| AppError::BadRequest(err.to_string()))?;
+            let next = schedule
+                .next_after(Utc::now(), offset)
+                .ok_or_else(|| AppError::BadRequest("cron expression never fires".into()))?;
+            sqlx::query(
+                "UPDATE evaluation_certifications SET schedule_cron = $2, next_refresh_at = $3, updated_at = NOW() \
+                 WHERE id = $1 RETURNING schedule_cron, next_refresh_at",
+            )
+            .bind(certification_id)
+            .bind(schedule.expression())
+            .bind(next)
+            .fetch_optional(&pool)
+            .await?
+        }
+        None => {
+            sqlx::query(
+                r#"
+                UPDATE evaluation_certifications
+                SET
+                    schedule_cron = NULL,
+                    next_refresh_at = CASE
+                        WHEN refresh_cadence_seconds IS NOT NULL THEN NOW() + make_interval(secs => refresh_cadence_seconds::double precision)
+                        ELSE NULL
+                    END,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING schedule_cron, next_refresh_at
+                "#,
+            )
+            .bind(certification_id)
+            .fetch_optional(&pool)
+            .await?
+        }
+    };
+    let row = row.ok_or(AppError::NotFound)?;
+
+    Ok(Json(CertificationSchedule {
+        certification_id,
+        schedule_cron: row.get("schedule_cron"),
+        next_refresh_at: row.get("next_refresh_at"),
+        timezone: offset.to_string(),
+    }))
+}
+
 struct RunAccess {
     manifest_digest: Option<String>,
 }
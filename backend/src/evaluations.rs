@@ -1,3 +1,5 @@
+pub mod cron;
+pub mod regression;
 pub mod scheduler;
 
 use std::collections::HashMap;
@@ -325,6 +327,7 @@ pub async fn retry_certification(
             valid_from = NOW(),
             valid_until = NULL,
             next_refresh_at = CASE
+                WHEN schedule_cron IS NOT NULL THEN next_refresh_at
                 WHEN refresh_cadence_seconds IS NOT NULL THEN NOW() + make_interval(secs => refresh_cadence_seconds::double precision)
                 ELSE NULL
             END,
@@ -0,0 +1,401 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Timelike, Utc,
+};
+use thiserror::Error;
+
+// key: evaluation-scheduler -> cron expressions
+
+/// Upper bound on candidate minutes inspected before giving up on an expression
+/// that can never fire (e.g. `0 0 31 2 *`).
+const MAX_SEARCH_STEPS: usize = 200_000;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CronError {
+    #[error("cron expression must have 5 fields, found {0}")]
+    FieldCount(usize),
+    #[error("invalid {field} field `{value}`")]
+    InvalidField { field: &'static str, value: String },
+}
+
+/// Five-field cron schedule (`minute hour day-of-month month day-of-week`).
+///
+/// Supports `*`, lists, ranges, steps, month/weekday names, and the `@hourly`,
+/// `@daily`, `@weekly`, `@monthly`, `@yearly` shorthands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+struct FieldSpec {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    aliases: &'static [&'static str],
+    /// Numeric value of the first alias (months start at 1, weekdays at 0).
+    alias_base: u32,
+}
+
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    min: 0,
+    max: 59,
+    aliases: &[],
+    alias_base: 0,
+};
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    min: 0,
+    max: 23,
+    aliases: &[],
+    alias_base: 0,
+};
+const DAY_OF_MONTH: FieldSpec = FieldSpec {
+    name: "day-of-month",
+    min: 1,
+    max: 31,
+    aliases: &[],
+    alias_base: 0,
+};
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    min: 1,
+    max: 12,
+    aliases: &[
+        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+    ],
+    alias_base: 1,
+};
+// Day-of-week accepts 0-7 where both 0 and 7 mean Sunday.
+const DAY_OF_WEEK: FieldSpec = FieldSpec {
+    name: "day-of-week",
+    min: 0,
+    max: 7,
+    aliases: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+    alias_base: 0,
+};
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let trimmed = expression.trim();
+        let expanded = match trimmed.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ => trimmed,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(CronError::FieldCount(fields.len()));
+        }
+
+        let minutes = parse_field(fields[0], &MINUTE)?;
+        let hours = parse_field(fields[1], &HOUR)?;
+        let days_of_month = parse_field(fields[2], &DAY_OF_MONTH)?;
+        let months = parse_field(fields[3], &MONTH)?;
+        let mut days_of_week = parse_field(fields[4], &DAY_OF_WEEK)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: trimmed.to_string(),
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            day_of_month_restricted: fields[2] != "*",
+            day_of_week_restricted: fields[4] != "*",
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// First fire time strictly after `after`, evaluated in the wall clock of
+    /// `offset` and returned in UTC.
+    pub fn next_after(&self, after: DateTime<Utc>, offset: FixedOffset) -> Option<DateTime<Utc>> {
+        let local = after.with_timezone(&offset).naive_local();
+        let mut candidate = local.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        for _ in 0..MAX_SEARCH_STEPS {
+            if !self.month_matches(candidate.month()) {
+                candidate = first_of_next_month(candidate.date())?;
+                continue;
+            }
+            if !self.day_matches(candidate.date()) {
+                candidate = candidate.date().succ_opt()?.and_time(NaiveTime::MIN);
+                continue;
+            }
+            if self.hours & (1 << candidate.hour()) == 0 {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << candidate.minute()) == 0 {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            return offset
+                .from_local_datetime(&candidate)
+                .single()
+                .map(|value| value.with_timezone(&Utc));
+        }
+        None
+    }
+
+    fn month_matches(&self, month: u32) -> bool {
+        self.months & (1 << month) != 0
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        // Classic cron semantics: when both day fields are restricted either may match.
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDateTime> {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    Some(NaiveDate::from_ymd_opt(year, month, 1)?.and_time(NaiveTime::MIN))
+}
+
+fn parse_field(raw: &str, spec: &FieldSpec) -> Result<u64, CronError> {
+    let invalid = || CronError::InvalidField {
+        field: spec.name,
+        value: raw.to_string(),
+    };
+    let mut mask = 0u64;
+    for item in raw.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (start, end) = if range == "*" {
+            (spec.min, spec.max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, spec).ok_or_else(invalid)?,
+                parse_value(end, spec).ok_or_else(invalid)?,
+            )
+        } else {
+            let start = parse_value(range, spec).ok_or_else(invalid)?;
+            // `5/15` means "from 5 to the end of the range every 15".
+            let end = if item.contains('/') { spec.max } else { start };
+            (start, end)
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, spec: &FieldSpec) -> Option<u32> {
+    let value = match raw.parse::<u32>() {
+        Ok(value) => value,
+        Err(_) => {
+            let upper = raw.to_ascii_uppercase();
+            let index = spec.aliases.iter().position(|alias| *alias == upper)? as u32;
+            index + spec.alias_base
+        }
+    };
+    (spec.min..=spec.max).contains(&value).then_some(value)
+}
+
+/// Parse a scheduler timezone: `UTC`/`Z` or a fixed offset such as `+02:00`.
+pub fn parse_utc_offset(raw: &str) -> Option<FixedOffset> {
+    let trimmed = raw.trim();
+    if trimmed.eq_ignore_ascii_case("utc") || trimmed.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match trimmed.chars().next()? {
+        '+' => (1, &trimmed[1..]),
+        '-' => (-1, &trimmed[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Outcome of checking a cron-scheduled certification during a scheduler tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CronFireDecision {
+    /// No fire time is stored yet; persist `next` without dispatching.
+    Initialize { next: DateTime<Utc> },
+    /// The stored fire time has not arrived.
+    NotDue,
+    /// Dispatch a refresh and store `next` as the following fire time.
+    Fire { next: DateTime<Utc> },
+    /// The previous refresh is still running; drop this fire and wait for `next`.
+    SkipOverlap { next: DateTime<Utc> },
+}
+
+pub fn plan_cron_fire(
+    schedule: &CronSchedule,
+    offset: FixedOffset,
+    next_refresh_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    in_progress: bool,
+) -> Option<CronFireDecision> {
+    let Some(due_at) = next_refresh_at else {
+        return schedule
+            .next_after(now, offset)
+            .map(|next| CronFireDecision::Initialize { next });
+    };
+    if due_at > now {
+        return Some(CronFireDecision::NotDue);
+    }
+    let next = schedule.next_after(now, offset)?;
+    Some(if in_progress {
+        CronFireDecision::SkipOverlap { next }
+    } else {
+        CronFireDecision::Fire { next }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn daily_expression_fires_next_morning() {
+        let schedule = CronSchedule::parse("0 3 * * *").unwrap();
+        let utc_offset = FixedOffset::east_opt(0).unwrap();
+        assert_eq!(
+            schedule.next_after(utc(2024, 5, 1, 10, 0), utc_offset),
+            Some(utc(2024, 5, 2, 3, 0))
+        );
+        assert_eq!(
+            schedule.next_after(utc(2024, 5, 1, 2, 59), utc_offset),
+            Some(utc(2024, 5, 1, 3, 0))
+        );
+        // Exactly at the fire time moves on to the next day.
+        assert_eq!(
+            schedule.next_after(utc(2024, 5, 1, 3, 0), utc_offset),
+            Some(utc(2024, 5, 2, 3, 0))
+        );
+    }
+
+    #[test]
+    fn daily_expression_honours_configured_offset() {
+        let schedule = CronSchedule::parse("0 3 * * *").unwrap();
+        let berlin_summer = parse_utc_offset("+02:00").unwrap();
+        assert_eq!(
+            schedule.next_after(utc(2024, 5, 1, 10, 0), berlin_summer),
+            Some(utc(2024, 5, 2, 1, 0))
+        );
+    }
+
+    #[test]
+    fn parses_lists_ranges_steps_and_names() {
+        let schedule = CronSchedule::parse("*/15 9-17 * JAN,jul MON-FRI").unwrap();
+        let utc_offset = FixedOffset::east_opt(0).unwrap();
+        // 2024-07-06 is a Saturday; next weekday slot is Monday 09:00.
+        assert_eq!(
+            schedule.next_after(utc(2024, 7, 6, 12, 0), utc_offset),
+            Some(utc(2024, 7, 8, 9, 0))
+        );
+        assert_eq!(
+            schedule.next_after(utc(2024, 7, 8, 9, 0), utc_offset),
+            Some(utc(2024, 7, 8, 9, 15))
+        );
+        assert_eq!(
+            CronSchedule::parse("@daily")
+                .unwrap()
+                .next_after(utc(2024, 1, 1, 0, 0), utc_offset),
+            Some(utc(2024, 1, 2, 0, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert_eq!(
+            CronSchedule::parse("0 3 * *"),
+            Err(CronError::FieldCount(4))
+        );
+        assert!(CronSchedule::parse("61 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 5-2 * * *").is_err());
+        assert!(parse_utc_offset("Europe/Berlin").is_none());
+    }
+
+    #[test]
+    fn overlapping_fire_is_skipped_until_next_slot() {
+        let schedule = CronSchedule::parse("0 3 * * *").unwrap();
+        let utc_offset = FixedOffset::east_opt(0).unwrap();
+        let now = utc(2024, 5, 2, 3, 1);
+        let due = Some(utc(2024, 5, 2, 3, 0));
+
+        assert_eq!(
+            plan_cron_fire(&schedule, utc_offset, due, now, true),
+            Some(CronFireDecision::SkipOverlap {
+                next: utc(2024, 5, 3, 3, 0)
+            })
+        );
+        assert_eq!(
+            plan_cron_fire(&schedule, utc_offset, due, now, false),
+            Some(CronFireDecision::Fire {
+                next: utc(2024, 5, 3, 3, 0)
+            })
+        );
+        assert_eq!(
+            plan_cron_fire(
+                &schedule,
+                utc_offset,
+                Some(utc(2024, 5, 3, 3, 0)),
+                now,
+                true
+            ),
+            Some(CronFireDecision::NotDue)
+        );
+        assert_eq!(
+            plan_cron_fire(&schedule, utc_offset, None, now, false),
+            Some(CronFireDecision::Initialize {
+                next: utc(2024, 5, 3, 3, 0)
+            })
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+
+// key: evaluation-regression -> run-to-run metric deltas
+
+/// Aggregate metric emitted alongside the per-test scores of every run.
+pub const MEAN_SCORE_METRIC: &str = "mean_score";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub baseline: Option<f64>,
+    pub candidate: Option<f64>,
+    pub delta: Option<f64>,
+    pub regressed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegressionReport {
+    pub baseline_run_id: i32,
+    pub candidate_run_id: i32,
+    pub tolerance: f64,
+    pub metrics: Vec<MetricDelta>,
+    pub regressions: Vec<String>,
+    pub passed: bool,
+}
+
+/// Compare per-metric scores where higher is better. A metric regresses when it
+/// drops by more than `tolerance` or disappears from the candidate run.
+pub fn compare_run_metrics(
+    baseline_run_id: i32,
+    candidate_run_id: i32,
+    baseline: &BTreeMap<String, f64>,
+    candidate: &BTreeMap<String, f64>,
+    tolerance: f64,
+) -> RegressionReport {
+    let mut names: Vec<&String> = baseline.keys().chain(candidate.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let metrics: Vec<MetricDelta> = names
+        .into_iter()
+        .map(|name| {
+            let before = baseline.get(name).copied();
+            let after = candidate.get(name).copied();
+            let delta = before.zip(after).map(|(before, after)| after - before);
+            let regressed = match (before, delta) {
+                (Some(_), Some(delta)) => delta < -tolerance,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            MetricDelta {
+                metric: name.clone(),
+                baseline: before,
+                candidate: after,
+                delta,
+                regressed,
+            }
+        })
+        .collect();
+
+    let regressions: Vec<String> = metrics
+        .iter()
+        .filter(|metric| metric.regressed)
+        .map(|metric| metric.metric.clone())
+        .collect();
+
+    RegressionReport {
+        baseline_run_id,
+        candidate_run_id,
+        tolerance,
+        passed: regressions.is_empty(),
+        metrics,
+        regressions,
+    }
+}
+
+/// Load `test:<id>` scores plus the run mean for a run owned by `user_id`.
+/// Returns `None` when the run does not exist or belongs to another owner.
+pub async fn load_run_metrics(
+    pool: &PgPool,
+    run_id: i32,
+    user_id: i32,
+) -> Result<Option<BTreeMap<String, f64>>, sqlx::Error> {
+    let owned: Option<i32> = sqlx::query_scalar(
+        "SELECT runs.id FROM evaluation_runs runs \
+         JOIN mcp_servers servers ON runs.server_id = servers.id \
+         WHERE runs.id = $1 AND servers.owner_id = $2",
+    )
+    .bind(run_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    if owned.is_none() {
+        return Ok(None);
+    }
+
+    let rows = sqlx::query(
+        "SELECT test_id, AVG(score) AS score FROM evaluation_results \
+         WHERE run_id = $1 GROUP BY test_id",
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut metrics = BTreeMap::new();
+    for row in &rows {
+        let test_id: i32 = row.get("test_id");
+        metrics.insert(format!("test:{test_id}"), row.get::<f64, _>("score"));
+    }
+    if !metrics.is_empty() {
+        let mean = metrics.values().sum::<f64>() / metrics.len() as f64;
+        metrics.insert(MEAN_SCORE_METRIC.to_string(), mean);
+    }
+    Ok(Some(metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(entries: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn improved_run_reports_no_regressions() {
+        let baseline = metrics(&[("test:1", 0.70), ("test:2", 0.80), ("mean_score", 0.75)]);
+        let candidate = metrics(&[("test:1", 0.85), ("test:2", 0.90), ("mean_score", 0.875)]);
+        let report = compare_run_metrics(1, 2, &baseline, &candidate, 0.05);
+        assert!(report.passed);
+        assert!(report.regressions.is_empty());
+        assert_eq!(report.metrics.len(), 3);
+    }
+
+    #[test]
+    fn regressed_run_flags_metric() {
+        let baseline = metrics(&[("test:1", 0.90), ("test:2", 0.80)]);
+        let candidate = metrics(&[("test:1", 0.60), ("test:2", 0.81)]);
+        let report = compare_run_metrics(1, 2, &baseline, &candidate, 0.05);
+        assert!(!report.passed);
+        assert_eq!(report.regressions, vec!["test:1".to_string()]);
+        let delta = report.metrics[0].delta.unwrap();
+        assert!((delta + 0.30).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drop_within_tolerance_is_not_flagged() {
+        let baseline = metrics(&[("test:1", 0.90)]);
+        let candidate = metrics(&[("test:1", 0.87)]);
+        let report = compare_run_metrics(1, 2, &baseline, &candidate, 0.05);
+        assert!(report.passed);
+        assert!(!report.metrics[0].regressed);
+    }
+
+    #[test]
+    fn missing_candidate_metric_counts_as_regression() {
+        let baseline = metrics(&[("test:1", 0.90), ("test:2", 0.50)]);
+        let candidate = metrics(&[("test:1", 0.90), ("test:3", 0.40)]);
+        let report = compare_run_metrics(1, 2, &baseline, &candidate, 0.05);
+        assert_eq!(report.regressions, vec!["test:2".to_string()]);
+    }
+}
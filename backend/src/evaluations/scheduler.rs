@@ -3,15 +3,16 @@ use std::time::Duration as StdDuration;
 
 use chrono::{DateTime, Utc};
 use serde_json::Value;
-use sqlx::{PgPool, Row};
+use sqlx::{postgres::PgRow, PgPool, Row};
 use tokio::{sync::mpsc::Sender, time};
 use tracing::{debug, info, warn};
 
+use super::cron::{plan_cron_fire, CronFireDecision, CronSchedule};
 use crate::db::runtime_vm_trust_registry::{
     get_state as get_registry_state, upsert_state as upsert_registry_state,
     UpsertRuntimeVmTrustRegistryState,
 };
-use crate::job_queue::{enqueue_job, Job};
+use crate::job_queue::{enqueue_job_with_priority, Job, JobPriority, QueuedJob};
 use crate::policy::trust::{evaluate_placement_gate, TrustPlacementGate};
 
 const SCAN_INTERVAL_SECS: u64 = 60;
@@ -39,7 +40,7 @@ pub struct TrustTransitionSignal {
 }
 
 // key: evaluation-scheduler -> periodic refresh coordination
-pub fn spawn(pool: PgPool, job_tx: Sender<Job>) {
+pub fn spawn(pool: PgPool, job_tx: Sender<QueuedJob>) {
     tokio::spawn(async move {
         let mut ticker = time::interval(StdDuration::from_secs(SCAN_INTERVAL_SECS));
         loop {
@@ -53,7 +54,7 @@ pub fn spawn(pool: PgPool, job_tx: Sender<Job>) {
 
 pub async fn handle_trust_transition(
     pool: &PgPool,
-    job_tx: &Sender<Job>,
+    job_tx: &Sender<QueuedJob>,
     signal: &TrustTransitionSignal,
 ) -> Result<(), sqlx::Error> {
     let rows = sqlx::query(
@@ -84,7 +85,7 @@ pub async fn handle_trust_transition(
                 if status == "pending" {
                     continue;
                 }
-                schedule_refresh(pool, job_tx, certification_id).await?;
+                schedule_refresh(pool, job_tx, certification_id, None).await?;
             }
         }
         "untrusted" | "unknown" => {
@@ -162,7 +163,29 @@ async fn ensure_remediation_lifecycle(
     }
 }
 
-async fn scan_and_schedule(pool: &PgPool, job_tx: &Sender<Job>) -> Result<(), sqlx::Error> {
+struct RefreshCandidate {
+    certification_id: i32,
+    last_attestation_status: Option<String>,
+    fallback_launched_at: Option<DateTime<Utc>>,
+    remediation_attempts: i32,
+    server_id: i32,
+    next_refresh_at: Option<DateTime<Utc>>,
+}
+
+impl RefreshCandidate {
+    fn from_row(row: &PgRow) -> Self {
+        Self {
+            certification_id: row.get("id"),
+            last_attestation_status: row.try_get("last_attestation_status").unwrap_or(None),
+            fallback_launched_at: row.try_get("fallback_launched_at").unwrap_or(None),
+            remediation_attempts: row.try_get("remediation_attempts").unwrap_or(0),
+            server_id: row.get("server_id"),
+            next_refresh_at: None,
+        }
+    }
+}
+
+async fn scan_and_schedule(pool: &PgPool, job_tx: &Sender<QueuedJob>) -> Result<(), sqlx::Error> {
     let rows = sqlx::query(
         r#"
         SELECT
@@ -176,6 +199,7 @@ async fn scan_and_schedule(pool: &PgPool, job_tx: &Sender<Job>) -> Result<(), sq
         WHERE ec.next_refresh_at IS NOT NULL
           AND ec.next_refresh_at <= NOW() + make_interval(mins => $1::double precision)
           AND ec.status <> 'pending'
+          AND ec.schedule_cron IS NULL
         ORDER BY ec.next_refresh_at ASC
         LIMIT $2
         "#,
@@ -185,18 +209,22 @@ async fn scan_and_schedule(pool: &PgPool, job_tx: &Sender<Job>) -> Result<(), sq
     .fetch_all(pool)
     .await?;
 
+    let mut candidates: Vec<RefreshCandidate> =
+        rows.iter().map(RefreshCandidate::from_row).collect();
+    candidates.extend(collect_cron_candidates(pool).await?);
+
     let mut placement_cache: HashMap<i32, Option<TrustPlacementGate>> = HashMap::new();
 
-    for row in rows {
-        let certification_id: i32 = row.get("id");
-        let last_attestation_status: Option<String> =
-            row.try_get("last_attestation_status").unwrap_or(None);
-        let fallback_launched_at: Option<DateTime<Utc>> =
-            row.try_get("fallback_launched_at").unwrap_or(None);
-        let remediation_attempts: i32 = row.try_get("remediation_attempts").unwrap_or(0);
-        let server_id: i32 = row.get("server_id");
-
-        if matches!(last_attestation_status.as_deref(), Some("untrusted")) {
+    for candidate in candidates {
+        let certification_id = candidate.certification_id;
+        let remediation_attempts = candidate.remediation_attempts;
+        let fallback_launched_at = candidate.fallback_launched_at;
+        let server_id = candidate.server_id;
+
+        if matches!(
+            candidate.last_attestation_status.as_deref(),
+            Some("untrusted")
+        ) {
             record_trust_block(
                 pool,
                 certification_id,
@@ -236,20 +264,127 @@ async fn scan_and_schedule(pool: &PgPool, job_tx: &Sender<Job>) -> Result<(), sq
             }
         }
 
-        schedule_refresh(pool, job_tx, certification_id).await?;
+        schedule_refresh(pool, job_tx, certification_id, candidate.next_refresh_at).await?;
+    }
+
+    Ok(())
+}
+
+/// Cron-scheduled certifications fire only once their stored slot has passed and
+/// never overlap: a refresh that is still pending or queued drops the fire.
+async fn collect_cron_candidates(pool: &PgPool) -> Result<Vec<RefreshCandidate>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            ec.id,
+            ec.schedule_cron,
+            ec.next_refresh_at,
+            ec.status,
+            ec.last_attestation_status,
+            ec.fallback_launched_at,
+            ec.remediation_attempts,
+            bar.server_id,
+            EXISTS (
+                SELECT 1 FROM job_queue jq
+                WHERE jq.payload ? 'EvaluationRefresh'
+                  AND (jq.payload -> 'EvaluationRefresh' ->> 'certification_id')::int = ec.id
+            ) AS job_in_flight
+        FROM evaluation_certifications ec
+        JOIN build_artifact_runs bar ON ec.build_artifact_run_id = bar.id
+        WHERE ec.schedule_cron IS NOT NULL
+          AND (ec.next_refresh_at IS NULL OR ec.next_refresh_at <= NOW())
+        ORDER BY ec.next_refresh_at ASC NULLS FIRST
+        LIMIT $1
+        "#,
+    )
+    .bind(MAX_BATCH)
+    .fetch_all(pool)
+    .await?;
+
+    let offset = *crate::config::EVALUATION_SCHEDULE_TZ;
+    let now = Utc::now();
+    let mut candidates = Vec::new();
+    for row in rows {
+        let certification_id: i32 = row.get("id");
+        let expression: String = row.get("schedule_cron");
+        let schedule = match CronSchedule::parse(&expression) {
+            Ok(schedule) => schedule,
+            Err(err) => {
+                warn!(%certification_id, %expression, %err, "skipping invalid evaluation cron schedule");
+                continue;
+            }
+        };
+        let status: String = row.get("status");
+        let job_in_flight: bool = row.get("job_in_flight");
+        let in_progress = status == "pending" || job_in_flight;
+        let next_refresh_at: Option<DateTime<Utc>> = row.get("next_refresh_at");
+
+        match plan_cron_fire(&schedule, offset, next_refresh_at, now, in_progress) {
+            Some(CronFireDecision::Fire { next }) => {
+                let mut candidate = RefreshCandidate::from_row(&row);
+                candidate.next_refresh_at = Some(next);
+                candidates.push(candidate);
+            }
+            Some(CronFireDecision::Initialize { next }) => {
+                set_next_refresh(pool, certification_id, next, None).await?;
+            }
+            Some(CronFireDecision::SkipOverlap { next }) => {
+                let note = format!(
+                    "{} skipped cron refresh ({}) because the previous run is still in progress",
+                    now.to_rfc3339(),
+                    schedule.expression(),
+                );
+                set_next_refresh(pool, certification_id, next, Some(&note)).await?;
+                info!(%certification_id, %next, "skipped overlapping cron evaluation refresh");
+            }
+            Some(CronFireDecision::NotDue) => {}
+            None => {
+                warn!(%certification_id, %expression, "evaluation cron schedule never fires");
+            }
+        }
     }
 
+    Ok(candidates)
+}
+
+async fn set_next_refresh(
+    pool: &PgPool,
+    certification_id: i32,
+    next_refresh_at: DateTime<Utc>,
+    note: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE evaluation_certifications
+        SET
+            governance_notes = CASE
+                WHEN $3::text IS NULL THEN governance_notes
+                WHEN governance_notes IS NULL OR governance_notes = '' THEN $3
+                ELSE governance_notes || E'\n' || $3
+            END,
+            next_refresh_at = $2,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(certification_id)
+    .bind(next_refresh_at)
+    .bind(note)
+    .execute(pool)
+    .await?;
     Ok(())
 }
 
 async fn schedule_refresh(
     pool: &PgPool,
-    job_tx: &Sender<Job>,
+    job_tx: &Sender<QueuedJob>,
     certification_id: i32,
+    next_refresh_at: Option<DateTime<Utc>>,
 ) -> Result<(), sqlx::Error> {
     let job = Job::EvaluationRefresh { certification_id };
-    enqueue_job(pool, &job).await;
-    if let Err(err) = job_tx.send(job).await {
+    // evidence refreshes are background work and yield to deploys and remediation
+    enqueue_job_with_priority(pool, &job, JobPriority::Low).await;
+    if let Err(err) = job_tx.send(job.with_priority(JobPriority::Low)).await {
         warn!(?err, %certification_id, "failed to dispatch evaluation refresh job");
         return Ok(());
     }
@@ -267,6 +402,9 @@ async fn schedule_refresh(
                 ELSE governance_notes || E'\n' || $2
             END,
             next_refresh_at = CASE
+                WHEN $4::timestamptz IS NOT NULL THEN $4
+                -- Cron schedules are re-seeded by the next scan when no slot is supplied.
+                WHEN schedule_cron IS NOT NULL THEN NULL
                 WHEN refresh_cadence_seconds IS NOT NULL THEN NOW() + make_interval(secs => refresh_cadence_seconds::double precision)
                 ELSE NOW() + make_interval(mins => $3::double precision)
             END,
@@ -277,6 +415,7 @@ async fn schedule_refresh(
     .bind(certification_id)
     .bind(&note)
     .bind(FALLBACK_MINUTES)
+    .bind(next_refresh_at)
     .execute(pool)
     .await?;
 
@@ -400,8 +539,8 @@ pub async fn lineage(
 
 #[cfg(test)]
 mod tests {
-    use super::{handle_trust_transition, TrustTransitionSignal};
-    use crate::job_queue::Job;
+    use super::{handle_trust_transition, scan_and_schedule, TrustTransitionSignal};
+    use crate::job_queue::{Job, JobPriority, QueuedJob};
     use chrono::{DateTime, Duration, Utc};
     use sqlx::PgPool;
     use tokio::sync::mpsc::channel;
@@ -457,7 +596,7 @@ mod tests {
             .await
             .expect("seed job");
 
-        let (tx, mut rx) = channel::<Job>(1);
+        let (tx, mut rx) = channel::<QueuedJob>(1);
         let signal = TrustTransitionSignal {
             server_id,
             vm_instance_id: 42,
@@ -510,7 +649,7 @@ mod tests {
             .await
             .expect("set fail");
 
-        let (tx, mut rx) = channel::<Job>(4);
+        let (tx, mut rx) = channel::<QueuedJob>(4);
         let signal = TrustTransitionSignal {
             server_id,
             vm_instance_id: 42,
@@ -533,7 +672,8 @@ mod tests {
             .expect("handle transition");
 
         let dispatched = rx.recv().await.expect("job dispatched");
-        match dispatched {
+        assert_eq!(dispatched.priority, JobPriority::Low);
+        match dispatched.job {
             Job::EvaluationRefresh {
                 certification_id: queued,
             } => {
@@ -557,4 +697,54 @@ mod tests {
             .expect("next refresh")
             .gt(&(Utc::now() - Duration::minutes(1))));
     }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn cron_schedule_skips_fire_while_previous_run_in_progress(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let (_server_id, certification_id) = seed_certification(&pool).await;
+
+        let due_at = Utc::now() - Duration::minutes(5);
+        sqlx::query(
+            "UPDATE evaluation_certifications SET schedule_cron = '0 3 * * *', status = 'pending', next_refresh_at = $2 WHERE id = $1",
+        )
+        .bind(certification_id)
+        .bind(due_at)
+        .execute(&pool)
+        .await
+        .expect("set cron schedule");
+
+        let (tx, mut rx) = channel::<QueuedJob>(4);
+        scan_and_schedule(&pool, &tx).await.expect("scan");
+
+        assert!(
+            rx.try_recv().is_err(),
+            "overlapping cron fire must not dispatch a refresh"
+        );
+        let (notes, next_refresh_at, queued_jobs): (Option<String>, Option<DateTime<Utc>>, i64) =
+            sqlx::query_as(
+                "SELECT governance_notes, next_refresh_at, (SELECT COUNT(*) FROM job_queue) FROM evaluation_certifications WHERE id = $1",
+            )
+            .bind(certification_id)
+            .fetch_one(&pool)
+            .await
+            .expect("fetch certification");
+        assert_eq!(queued_jobs, 0);
+        assert!(notes.unwrap_or_default().contains("skipped cron refresh"));
+        assert!(next_refresh_at.expect("next slot") > Utc::now());
+
+        sqlx::query(
+            "UPDATE evaluation_certifications SET status = 'pass', next_refresh_at = $2 WHERE id = $1",
+        )
+        .bind(certification_id)
+        .bind(due_at)
+        .execute(&pool)
+        .await
+        .expect("complete previous run");
+        scan_and_schedule(&pool, &tx).await.expect("scan");
+        assert!(matches!(
+            rx.recv().await.map(|queued| queued.job),
+            Some(Job::EvaluationRefresh { certification_id: queued }) if queued == certification_id
+        ));
+    }
 }
@@ -1,12 +1,17 @@
-// key: auth-extractor -> jwt-expiry-enforcement
+// key: auth-extractor -> jwt-expiry-enforcement, api-key service principals
+use crate::auth::jwt::TokenRejection;
+use crate::db::mcp_servers;
 use axum::async_trait;
 use axum::{
     extract::FromRequestParts,
     http::{request::Parts, StatusCode},
 };
 use chrono::Utc;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::Deserialize;
+use sqlx::PgPool;
+
+/// Header carrying a server's `api_key` for service-to-service calls.
+pub const API_KEY_HEADER: &str = "x-api-key";
 
 #[derive(Deserialize)]
 struct Claims {
@@ -28,39 +33,138 @@ where
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let token_opt = if let Some(cookie_header) = parts.headers.get(axum::http::header::COOKIE) {
-            let cookies = cookie_header.to_str().unwrap_or("");
-            cookies.split(';').find_map(|c| {
-                let c = c.trim();
-                c.strip_prefix("auth_token=").map(|s| s.to_string())
-            })
-        } else if let Some(authz) = parts.headers.get(axum::http::header::AUTHORIZATION) {
-            authz
-                .to_str()
-                .ok()
-                .and_then(|s| s.strip_prefix("Bearer ").map(|s| s.to_string()))
-        } else {
-            None
-        };
-        let token = token_opt.ok_or((StatusCode::UNAUTHORIZED, "Missing token".into()))?;
-        let secret = crate::config::JWT_SECRET.as_str();
-        let decoded = decode::<Claims>(
-            &token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".into()))?;
+        let token =
+            session_token(parts).ok_or((StatusCode::UNAUTHORIZED, "Missing token".into()))?;
+        let claims = crate::config::JWT_KEYS
+            .verify::<Claims>(&token)
+            .map_err(|err| {
+                let rejection = TokenRejection::from_error(&err);
+                (StatusCode::UNAUTHORIZED, rejection.message().into())
+            })?;
         let now = Utc::now().timestamp().max(0);
-        if decoded.claims.exp <= now {
+        if claims.exp <= now {
             return Err((StatusCode::UNAUTHORIZED, "Expired token".into()));
         }
         Ok(AuthUser {
-            user_id: decoded.claims.sub,
-            role: decoded.claims.role,
+            user_id: claims.sub,
+            role: claims.role,
         })
     }
 }
 
+fn session_token(parts: &Parts) -> Option<String> {
+    if let Some(cookie_header) = parts.headers.get(axum::http::header::COOKIE) {
+        let cookies = cookie_header.to_str().unwrap_or("");
+        cookies.split(';').find_map(|c| {
+            let c = c.trim();
+            c.strip_prefix("auth_token=").map(|s| s.to_string())
+        })
+    } else if let Some(authz) = parts.headers.get(axum::http::header::AUTHORIZATION) {
+        authz
+            .to_str()
+            .ok()
+            .and_then(|s| s.strip_prefix("Bearer ").map(|s| s.to_string()))
+    } else {
+        None
+    }
+}
+
+/// A server calling in with its `api_key`. It acts for the server's owner but may only
+/// touch that server's own resources.
+#[derive(Debug, Clone)]
+pub struct ServicePrincipal {
+    pub server_id: i32,
+    pub owner_id: i32,
+    pub organization_id: Option<i32>,
+}
+
+impl ServicePrincipal {
+    pub async fn resolve(pool: &PgPool, api_key: &str) -> Result<Option<Self>, sqlx::Error> {
+        let row = mcp_servers::live_by_api_key(pool, api_key).await?;
+        Ok(row.map(|(server_id, owner_id, organization_id)| Self {
+            server_id,
+            owner_id,
+            organization_id,
+        }))
+    }
+
+    pub fn owns_server(&self, server_id: i32) -> bool {
+        self.server_id == server_id
+    }
+
+    pub async fn owns_vm_instance(
+        &self,
+        pool: &PgPool,
+        runtime_vm_instance_id: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let server_id: Option<i32> =
+            sqlx::query_scalar("SELECT server_id FROM runtime_vm_instances WHERE id = $1")
+                .bind(runtime_vm_instance_id)
+                .fetch_optional(pool)
+                .await?;
+        Ok(server_id.is_some_and(|server_id| self.owns_server(server_id)))
+    }
+}
+
+/// Either a signed-in user (JWT) or a service (`X-API-Key`). Sending both is rejected so
+/// a request never runs with an ambiguous identity.
+pub enum Principal {
+    User(AuthUser),
+    Service(ServicePrincipal),
+}
+
+impl Principal {
+    /// The user a principal acts for; a service acts for its server's owner.
+    pub fn acting_user_id(&self) -> i32 {
+        match self {
+            Principal::User(user) => user.user_id,
+            Principal::Service(service) => service.owner_id,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if !parts.headers.contains_key(API_KEY_HEADER) {
+            return AuthUser::from_request_parts(parts, state)
+                .await
+                .map(Principal::User);
+        }
+        if session_token(parts).is_some() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Send either a token or an API key, not both".into(),
+            ));
+        }
+        let api_key = parts.headers[API_KEY_HEADER]
+            .to_str()
+            .ok()
+            .filter(|value| !value.is_empty())
+            .ok_or((StatusCode::UNAUTHORIZED, "Invalid API key".into()))?;
+        let pool = parts.extensions.get::<PgPool>().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database unavailable".into(),
+        ))?;
+        match ServicePrincipal::resolve(pool, api_key).await {
+            Ok(Some(service)) => Ok(Principal::Service(service)),
+            Ok(None) => Err((StatusCode::UNAUTHORIZED, "Invalid API key".into())),
+            Err(err) => {
+                tracing::error!(?err, "failed to resolve API key");
+                Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Database unavailable".into(),
+                ))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +193,20 @@ mod tests {
         assert_eq!(user.role, "user");
     }
 
+    #[tokio::test]
+    async fn api_key_and_token_together_are_rejected() {
+        let request = Request::builder()
+            .header("Authorization", "Bearer anything")
+            .header(API_KEY_HEADER, "server-key")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let mut parts = request.into_parts().0;
+        let Err((status, _)) = Principal::from_request_parts(&mut parts, &()).await else {
+            panic!("ambiguous credentials must be rejected");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn invalid_token_rejected() {
         std::env::set_var("JWT_SECRET", "secret");
@@ -1,13 +1,18 @@
 use axum::{
     extract::{Extension, Multipart, Path},
-    http::{header, HeaderMap, StatusCode},
-    response::IntoResponse,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use sqlx::{PgPool, Row};
-use tokio::{fs, io::AsyncWriteExt};
-use tracing::error;
+use std::path::PathBuf;
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
+use tracing::{error, warn};
 
 #[derive(Serialize)]
 pub struct FileInfo {
@@ -16,6 +21,167 @@ pub struct FileInfo {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+// key: file-store-blobs -> content-addressed storage behind server_files
+
+/// Stores each unique upload once under its SHA-256 digest. `server_files` rows
+/// reference blobs by digest and a trigger keeps `file_blobs.ref_count` in step,
+/// so callers keep working with names while identical content shares one file.
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+#[derive(Debug)]
+pub struct StoredFile {
+    pub id: i32,
+    pub digest: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Default for BlobStore {
+    fn default() -> Self {
+        Self::new("storage/blobs")
+    }
+}
+
+impl BlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn digest(data: &[u8]) -> String {
+        hex::encode(Sha256::digest(data))
+    }
+
+    fn blob_path(&self, digest: &str) -> PathBuf {
+        self.root.join(&digest[..2]).join(digest)
+    }
+
+    /// Records `name` for `server_id`, writing the content only if no blob holds it yet.
+    pub async fn store(
+        &self,
+        pool: &PgPool,
+        server_id: i32,
+        name: &str,
+        data: &[u8],
+    ) -> Result<StoredFile, (StatusCode, String)> {
+        let mut tx = pool.begin().await.map_err(db_error)?;
+        let digest = self.put_blob(&mut tx, data).await?;
+        let path_text = self.blob_path(&digest).to_string_lossy().into_owned();
+        let rec = sqlx::query(
+            "INSERT INTO server_files (server_id, name, path, blob_digest) VALUES ($1,$2,$3,$4) \
+             RETURNING id, created_at",
+        )
+        .bind(server_id)
+        .bind(name)
+        .bind(&path_text)
+        .bind(&digest)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(StoredFile {
+            id: rec.get("id"),
+            digest,
+            created_at: rec.get("created_at"),
+        })
+    }
+
+    /// Writes `data` as a blob inside `tx` and returns its digest. The blob starts without
+    /// references; the caller's row pointing at it must take one before the transaction
+    /// commits, or the next prune frees it.
+    pub(crate) async fn put_blob(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        data: &[u8],
+    ) -> Result<String, (StatusCode, String)> {
+        let digest = Self::digest(data);
+        let path = self.blob_path(&digest);
+        lock_digest(tx, &digest).await?;
+        sqlx::query(
+            "INSERT INTO file_blobs (digest, path, size_bytes) VALUES ($1, $2, $3) \
+             ON CONFLICT (digest) DO NOTHING",
+        )
+        .bind(&digest)
+        .bind(path.to_string_lossy().as_ref())
+        .bind(data.len() as i64)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if fs::metadata(&path).await.is_err() {
+            write_blob(&path, data).await?;
+        }
+        Ok(digest)
+    }
+
+    pub(crate) async fn read_blob(&self, digest: &str) -> std::io::Result<Vec<u8>> {
+        fs::read(self.blob_path(digest)).await
+    }
+
+    /// Removes blobs whose last reference has been dropped, returning how many were freed.
+    pub async fn prune_unreferenced(&self, pool: &PgPool) -> Result<usize, (StatusCode, String)> {
+        let candidates: Vec<String> =
+            sqlx::query_scalar("SELECT digest FROM file_blobs WHERE ref_count = 0")
+                .fetch_all(pool)
+                .await
+                .map_err(db_error)?;
+        let mut pruned = 0;
+        for digest in candidates {
+            let mut tx = pool.begin().await.map_err(db_error)?;
+            lock_digest(&mut tx, &digest).await?;
+            let path: Option<String> = sqlx::query_scalar(
+                "DELETE FROM file_blobs WHERE digest = $1 AND ref_count = 0 RETURNING path",
+            )
+            .bind(&digest)
+            .fetch_optional(&mut tx)
+            .await
+            .map_err(db_error)?;
+            if let Some(path) = path {
+                // remove while holding the lock so a concurrent store rewrites it afterwards
+                if let Err(e) = fs::remove_file(&path).await {
+                    warn!(?e, %digest, "failed removing unreferenced blob");
+                }
+                pruned += 1;
+            }
+            tx.commit().await.map_err(db_error)?;
+        }
+        Ok(pruned)
+    }
+}
+
+/// Serializes store and prune for one digest across concurrent requests.
+async fn lock_digest(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    digest: &str,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(digest)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+    Ok(())
+}
+
+async fn write_blob(path: &std::path::Path, data: &[u8]) -> Result<(), (StatusCode, String)> {
+    let write_error = |e: std::io::Error| {
+        error!(?e, "Failed writing blob");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Write error".to_string())
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(write_error)?;
+    }
+    let staging = path.with_extension("partial");
+    let mut f = fs::File::create(&staging).await.map_err(write_error)?;
+    f.write_all(data).await.map_err(write_error)?;
+    f.sync_all().await.map_err(write_error)?;
+    fs::rename(&staging, path).await.map_err(write_error)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    error!(?e, "DB error in blob store");
+    (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+}
+
 pub async fn list_files(
     Path(server_id): Path<i32>,
     Extension(pool): Extension<PgPool>,
@@ -46,13 +212,7 @@ pub async fn upload_file(
     Extension(pool): Extension<PgPool>,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let dir = format!("storage/{server_id}");
-    if fs::create_dir_all(&dir).await.is_err() {
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Failed to create dir".into(),
-        ));
-    }
+    let store = BlobStore::default();
     while let Some(field) = multipart.next_field().await.unwrap_or(None) {
         let file_name = field
             .file_name()
@@ -62,40 +222,157 @@ pub async fn upload_file(
             error!(?e, "Failed reading upload field");
             (StatusCode::BAD_REQUEST, "Read error".into())
         })?;
-        let path = format!("{}/{}", dir, file_name);
-        let mut f = fs::File::create(&path).await.map_err(|e| {
-            error!(?e, "Failed creating file");
-            (StatusCode::INTERNAL_SERVER_ERROR, "Write error".into())
-        })?;
-        f.write_all(&data).await.map_err(|e| {
-            error!(?e, "Failed writing file");
-            (StatusCode::INTERNAL_SERVER_ERROR, "Write error".into())
-        })?;
-        let rec = sqlx::query("INSERT INTO server_files (server_id, name, path) VALUES ($1,$2,$3) RETURNING id, created_at")
-            .bind(server_id)
-            .bind(&file_name)
-            .bind(&path)
-            .fetch_one(&pool)
-            .await
-            .map_err(|e| {
-                error!(?e, "DB error inserting file record");
-                (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-            })?;
+        let stored = store.store(&pool, server_id, &file_name, &data).await?;
         return Ok((
             StatusCode::CREATED,
             Json(FileInfo {
-                id: rec.get("id"),
+                id: stored.id,
                 name: file_name,
-                created_at: rec.get("created_at"),
+                created_at: stored.created_at,
             }),
         ));
     }
     Err((StatusCode::BAD_REQUEST, "No file".into()))
 }
 
+// key: file-store-ranges -> RFC 9110 byte-range downloads
+
+/// Requests naming more ranges than this are served whole rather than as multipart.
+const MAX_BYTE_RANGES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ByteRangeRequest {
+    /// No usable `Range` header; serve the whole body with 200.
+    Full,
+    /// Inclusive `(first, last)` byte offsets to serve with 206.
+    Ranges(Vec<(u64, u64)>),
+    /// Well-formed but no range overlaps the content; respond 416.
+    Unsatisfiable,
+}
+
+/// Parses a `Range` header against a body of `len` bytes.
+///
+/// Syntactically invalid headers and non-`bytes` units are ignored, as the spec
+/// allows, so the caller falls back to a full response.
+pub fn parse_byte_ranges(header: &str, len: u64) -> ByteRangeRequest {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return ByteRangeRequest::Full;
+    };
+    let mut ranges = Vec::new();
+    let mut parts = 0;
+    for part in spec.split(',') {
+        parts += 1;
+        if parts > MAX_BYTE_RANGES {
+            return ByteRangeRequest::Full;
+        }
+        let Some((first, last)) = part.trim().split_once('-') else {
+            return ByteRangeRequest::Full;
+        };
+        let (first, last) = (first.trim(), last.trim());
+        let range = if first.is_empty() {
+            let Ok(suffix) = last.parse::<u64>() else {
+                return ByteRangeRequest::Full;
+            };
+            (suffix > 0 && len > 0).then(|| (len.saturating_sub(suffix), len - 1))
+        } else {
+            let Ok(first) = first.parse::<u64>() else {
+                return ByteRangeRequest::Full;
+            };
+            let last = if last.is_empty() {
+                u64::MAX
+            } else {
+                match last.parse::<u64>() {
+                    Ok(last) if last >= first => last,
+                    _ => return ByteRangeRequest::Full,
+                }
+            };
+            (first < len).then(|| (first, last.min(len - 1)))
+        };
+        ranges.extend(range);
+    }
+    if ranges.is_empty() {
+        ByteRangeRequest::Unsatisfiable
+    } else {
+        ByteRangeRequest::Ranges(ranges)
+    }
+}
+
+async fn read_range(file: &mut fs::File, (first, last): (u64, u64)) -> std::io::Result<Vec<u8>> {
+    file.seek(std::io::SeekFrom::Start(first)).await?;
+    let mut buf = vec![0; (last - first + 1) as usize];
+    file.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+/// Builds the download response for `path`, honoring an optional `Range` header.
+async fn ranged_file_response(
+    path: &str,
+    mut headers: HeaderMap,
+    range: Option<&str>,
+) -> std::io::Result<Response> {
+    let mut file = fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    let request = range.map_or(ByteRangeRequest::Full, |value| {
+        parse_byte_ranges(value, len)
+    });
+
+    let (status, body) = match request {
+        ByteRangeRequest::Full => {
+            let mut data = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut data).await?;
+            (StatusCode::OK, data)
+        }
+        ByteRangeRequest::Unsatisfiable => {
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes */{len}")) {
+                headers.insert(header::CONTENT_RANGE, value);
+            }
+            (StatusCode::RANGE_NOT_SATISFIABLE, Vec::new())
+        }
+        ByteRangeRequest::Ranges(ranges) if ranges.len() == 1 => {
+            let (first, last) = ranges[0];
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes {first}-{last}/{len}")) {
+                headers.insert(header::CONTENT_RANGE, value);
+            }
+            (
+                StatusCode::PARTIAL_CONTENT,
+                read_range(&mut file, ranges[0]).await?,
+            )
+        }
+        ByteRangeRequest::Ranges(ranges) => {
+            let boundary = uuid::Uuid::new_v4().simple().to_string();
+            let part_type = headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("application/octet-stream")
+                .to_string();
+            let mut body = Vec::new();
+            for (first, last) in ranges {
+                body.extend_from_slice(
+                    format!(
+                        "\r\n--{boundary}\r\nContent-Type: {part_type}\r\nContent-Range: bytes {first}-{last}/{len}\r\n\r\n"
+                    )
+                    .as_bytes(),
+                );
+                body.extend(read_range(&mut file, (first, last)).await?);
+            }
+            body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+            if let Ok(value) =
+                HeaderValue::from_str(&format!("multipart/byteranges; boundary={boundary}"))
+            {
+                headers.insert(header::CONTENT_TYPE, value);
+            }
+            (StatusCode::PARTIAL_CONTENT, body)
+        }
+    };
+
+    Ok((status, headers, body).into_response())
+}
+
 pub async fn download_file(
     Path((server_id, file_id)): Path<(i32, i32)>,
     Extension(pool): Extension<PgPool>,
+    request_headers: HeaderMap,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let row = sqlx::query("SELECT name, path FROM server_files WHERE id = $1 AND server_id = $2")
         .bind(file_id)
@@ -111,10 +388,6 @@ pub async fn download_file(
     };
     let name: String = r.get("name");
     let path: String = r.get("path");
-    let data = fs::read(&path).await.map_err(|e| {
-        error!(?e, "File read error");
-        (StatusCode::INTERNAL_SERVER_ERROR, "Read error".into())
-    })?;
     let mut headers = HeaderMap::new();
     headers.insert(
         header::CONTENT_TYPE,
@@ -124,27 +397,40 @@ pub async fn download_file(
     if let Ok(val) = header::HeaderValue::from_str(&disposition) {
         headers.insert(header::CONTENT_DISPOSITION, val);
     }
-    Ok((headers, data))
+    let range = request_headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    ranged_file_response(&path, headers, range)
+        .await
+        .map_err(|e| {
+            error!(?e, "File read error");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Read error".into())
+        })
 }
 
 pub async fn delete_file(
     Path((server_id, file_id)): Path<(i32, i32)>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let row = sqlx::query("SELECT path FROM server_files WHERE id = $1 AND server_id = $2")
-        .bind(file_id)
-        .bind(server_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error fetching file for deletion");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
+    let row =
+        sqlx::query("SELECT path, blob_digest FROM server_files WHERE id = $1 AND server_id = $2")
+            .bind(file_id)
+            .bind(server_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| {
+                error!(?e, "DB error fetching file for deletion");
+                (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+            })?;
     let Some(r) = row else {
         return Err((StatusCode::NOT_FOUND, "File not found".into()));
     };
     let path: String = r.get("path");
-    let _ = fs::remove_file(&path).await;
+    let blob_digest: Option<String> = r.get("blob_digest");
+    if blob_digest.is_none() {
+        // files uploaded before the blob store own their path outright
+        let _ = fs::remove_file(&path).await;
+    }
     sqlx::query("DELETE FROM server_files WHERE id = $1")
         .bind(file_id)
         .execute(&pool)
@@ -153,5 +439,161 @@ pub async fn delete_file(
             error!(?e, "DB error deleting file record");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
+    if blob_digest.is_some() {
+        BlobStore::default().prune_unreferenced(&pool).await?;
+    }
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_byte_ranges, ranged_file_response, BlobStore, ByteRangeRequest};
+    use axum::http::{header, HeaderMap, StatusCode};
+    use sqlx::PgPool;
+
+    async fn range_fixture(contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("range-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, contents).await.unwrap();
+        path
+    }
+
+    async fn fetch(path: &std::path::Path, range: &str) -> (StatusCode, HeaderMap, Vec<u8>) {
+        let response = ranged_file_response(path.to_str().unwrap(), HeaderMap::new(), Some(range))
+            .await
+            .unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, headers, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn single_range_returns_partial_content() {
+        let path = range_fixture(b"0123456789abcdef").await;
+        let (status, headers, body) = fetch(&path, "bytes=2-5").await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, b"2345");
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes 2-5/16");
+        assert_eq!(headers[header::ACCEPT_RANGES], "bytes");
+
+        let (status, _, body) = fetch(&path, "bytes=10-").await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, b"abcdef");
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn suffix_and_multi_ranges_are_served() {
+        let path = range_fixture(b"0123456789abcdef").await;
+        let (status, headers, body) = fetch(&path, "bytes=-4").await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, b"cdef");
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes 12-15/16");
+
+        let (status, headers, body) = fetch(&path, "bytes=0-1, -2").await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        let content_type = headers[header::CONTENT_TYPE].to_str().unwrap();
+        assert!(content_type.starts_with("multipart/byteranges; boundary="));
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("Content-Range: bytes 0-1/16\r\n\r\n01\r\n"));
+        assert!(body.contains("Content-Range: bytes 14-15/16\r\n\r\nef\r\n"));
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn unsatisfiable_and_malformed_ranges() {
+        let path = range_fixture(b"0123456789").await;
+        let (status, headers, body) = fetch(&path, "bytes=20-30").await;
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes */10");
+        assert!(body.is_empty());
+
+        let (status, headers, body) = fetch(&path, "bytes=5-2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"0123456789");
+        assert!(headers.get(header::CONTENT_RANGE).is_none());
+
+        assert_eq!(parse_byte_ranges("items=0-1", 10), ByteRangeRequest::Full);
+        assert_eq!(parse_byte_ranges("bytes=abc", 10), ByteRangeRequest::Full);
+        assert_eq!(
+            parse_byte_ranges("bytes=-0", 10),
+            ByteRangeRequest::Unsatisfiable
+        );
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    async fn seed_server(pool: &PgPool) -> i32 {
+        let user_id: i32 = sqlx::query_scalar(
+            "INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id",
+        )
+        .bind("files@example.com")
+        .bind("hash")
+        .fetch_one(pool)
+        .await
+        .expect("user");
+        sqlx::query_scalar(
+            "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, 'files', 'router', '{}'::jsonb, 'active', 'key') RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("server")
+    }
+
+    async fn ref_count(pool: &PgPool, digest: &str) -> Option<i32> {
+        sqlx::query_scalar("SELECT ref_count FROM file_blobs WHERE digest = $1")
+            .bind(digest)
+            .fetch_optional(pool)
+            .await
+            .expect("ref count")
+    }
+
+    async fn delete_reference(pool: &PgPool, file_id: i32) {
+        sqlx::query("DELETE FROM server_files WHERE id = $1")
+            .bind(file_id)
+            .execute(pool)
+            .await
+            .expect("delete reference");
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn identical_uploads_share_one_refcounted_blob(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let server_id = seed_server(&pool).await;
+        let root = std::env::temp_dir().join(format!("blobs-{}", uuid::Uuid::new_v4()));
+        let store = BlobStore::new(&root);
+
+        let first = store
+            .store(&pool, server_id, "report.txt", b"same bytes")
+            .await
+            .expect("first store");
+        let second = store
+            .store(&pool, server_id, "copy-of-report.txt", b"same bytes")
+            .await
+            .expect("second store");
+
+        assert_eq!(first.digest, second.digest);
+        assert_ne!(first.id, second.id);
+        let blobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM file_blobs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(blobs, 1);
+        assert_eq!(ref_count(&pool, &first.digest).await, Some(2));
+        let blob_path = store.blob_path(&first.digest);
+        assert_eq!(tokio::fs::read(&blob_path).await.unwrap(), b"same bytes");
+
+        delete_reference(&pool, first.id).await;
+        assert_eq!(store.prune_unreferenced(&pool).await.unwrap(), 0);
+        assert_eq!(ref_count(&pool, &first.digest).await, Some(1));
+        assert!(blob_path.exists(), "blob survives while referenced");
+
+        delete_reference(&pool, second.id).await;
+        assert_eq!(store.prune_unreferenced(&pool).await.unwrap(), 1);
+        assert_eq!(ref_count(&pool, &first.digest).await, None);
+        assert!(!blob_path.exists(), "last reference removes the blob");
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
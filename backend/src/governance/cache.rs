@@ -0,0 +1,85 @@
+// key: governance-gate-cache
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::engine::GovernanceGateEvaluation;
+
+/// Short-lived memo of promotion gate evaluations, keyed on manifest digest and tier.
+/// Any workflow, run or promotion change clears the whole cache. The generation counter
+/// stops an evaluation that raced with a clear from storing what it read before the change.
+#[derive(Debug)]
+pub(crate) struct GovernanceGateCache {
+    ttl: Option<Duration>,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    generation: u64,
+    entries: HashMap<(String, String), (Instant, GovernanceGateEvaluation)>,
+}
+
+impl GovernanceGateCache {
+    pub(crate) fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl: ttl.filter(|ttl| !ttl.is_zero()),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Current generation, or `None` when caching is disabled.
+    pub(crate) fn generation(&self) -> Option<u64> {
+        self.ttl?;
+        Some(self.lock().generation)
+    }
+
+    pub(crate) fn get(&self, digest: &str, tier: &str) -> Option<GovernanceGateEvaluation> {
+        let ttl = self.ttl?;
+        let mut state = self.lock();
+        let key = (digest.to_string(), tier.to_string());
+        match state.entries.get(&key) {
+            Some((stored_at, evaluation)) if stored_at.elapsed() < ttl => Some(evaluation.clone()),
+            Some(_) => {
+                state.entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(
+        &self,
+        generation: u64,
+        digest: &str,
+        tier: &str,
+        evaluation: &GovernanceGateEvaluation,
+    ) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        let mut state = self.lock();
+        if state.generation != generation {
+            return;
+        }
+        state
+            .entries
+            .retain(|_, (stored_at, _)| stored_at.elapsed() < ttl);
+        state.entries.insert(
+            (digest.to_string(), tier.to_string()),
+            (Instant::now(), evaluation.clone()),
+        );
+    }
+
+    pub(crate) fn invalidate(&self) {
+        let mut state = self.lock();
+        state.generation = state.generation.wrapping_add(1);
+        state.entries.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state
+            .lock()
+            .expect("governance gate cache mutex poisoned")
+    }
+}
@@ -1,16 +1,31 @@
 // key: governance-workflows
+use std::sync::Arc;
+use std::time::Duration;
+
 use serde_json::json;
 use sqlx::{PgPool, Postgres, Row, Transaction};
 use thiserror::Error;
 
+use super::cache::GovernanceGateCache;
 use super::models::{
     CreateGovernanceWorkflow, GovernanceAuditLogEntry, GovernanceRunDetail, GovernanceRunStatus,
     GovernanceStepRunDetail, GovernanceWorkflow, GovernanceWorkflowKind, RunStatusUpdateRequest,
     StartWorkflowRunRequest,
 };
 
-#[derive(Debug, Clone, Default)]
-pub struct GovernanceEngine;
+/// Clones share one promotion gate cache.
+#[derive(Debug, Clone)]
+pub struct GovernanceEngine {
+    gate_cache: Arc<GovernanceGateCache>,
+}
+
+impl Default for GovernanceEngine {
+    fn default() -> Self {
+        Self::with_gate_cache_ttl(Some(Duration::from_secs(
+            *crate::config::GOVERNANCE_GATE_CACHE_TTL_SECONDS,
+        )))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct GovernanceGateEvaluation {
@@ -46,6 +61,20 @@ impl GovernanceEngine {
         Self::default()
     }
 
+    /// Engine whose promotion gate evaluations are reused for `ttl`; `None` or a zero
+    /// duration evaluates every time.
+    pub fn with_gate_cache_ttl(ttl: Option<Duration>) -> Self {
+        Self {
+            gate_cache: Arc::new(GovernanceGateCache::new(ttl)),
+        }
+    }
+
+    /// Drops cached promotion gate evaluations. Call after changing promotion state
+    /// outside this engine.
+    pub fn invalidate_gate_cache(&self) {
+        self.gate_cache.invalidate();
+    }
+
     pub async fn list_workflows(
         &self,
         pool: &PgPool,
@@ -103,6 +132,7 @@ impl GovernanceEngine {
         }
 
         tx.commit().await?;
+        self.gate_cache.invalidate();
 
         Ok(workflow)
     }
@@ -199,6 +229,7 @@ impl GovernanceEngine {
         }
 
         tx.commit().await?;
+        self.gate_cache.invalidate();
 
         self.fetch_run_detail(pool, run_id, owner_id).await
     }
@@ -339,6 +370,7 @@ impl GovernanceEngine {
 
         self.sync_promotion_status(pool, row.get("id"), payload.status)
             .await?;
+        self.gate_cache.invalidate();
 
         match payload.status {
             GovernanceRunStatus::Completed => {
@@ -476,6 +508,25 @@ impl GovernanceEngine {
             });
         };
 
+        if let Some(cached) = self.gate_cache.get(digest, tier_value) {
+            return Ok(cached);
+        }
+        let generation = self.gate_cache.generation();
+
+        let evaluation = Self::evaluate_promotion_gate(pool, digest, tier_value).await?;
+        if let Some(generation) = generation {
+            self.gate_cache
+                .insert(generation, digest, tier_value, &evaluation);
+        }
+        Ok(evaluation)
+    }
+
+    async fn evaluate_promotion_gate(
+        pool: &PgPool,
+        digest: &str,
+        tier_value: &str,
+    ) -> Result<GovernanceGateEvaluation, GovernanceError> {
+        let mut notes = Vec::new();
         let row = sqlx::query(
             r#"
             SELECT ap.id,
@@ -17,7 +17,7 @@ use tracing::{error, info};
 use serde_json::Value;
 
 use crate::extractor::AuthUser;
-use crate::job_queue::{enqueue_job, Job};
+use crate::job_queue::{enqueue_job_with_priority, Job, JobPriority, QueuedJob};
 use crate::servers::set_status;
 
 use super::{
@@ -95,7 +95,7 @@ async fn get_run(
 async fn update_run_status(
     Extension(pool): Extension<PgPool>,
     Extension(engine): Extension<Arc<GovernanceEngine>>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i64>,
     Json(payload): Json<RunStatusUpdateRequest>,
@@ -154,7 +154,7 @@ async fn stream_run(
 
 async fn trigger_runtime_retry(
     pool: &PgPool,
-    job_tx: &tokio::sync::mpsc::Sender<Job>,
+    job_tx: &tokio::sync::mpsc::Sender<QueuedJob>,
     decision_id: i32,
     owner_id: i32,
 ) -> Result<(), sqlx::Error> {
@@ -198,8 +198,9 @@ async fn trigger_runtime_retry(
         api_key,
         use_gpu,
     };
-    enqueue_job(pool, &job).await;
-    let _ = job_tx.send(job).await;
+    // remediation retries jump ahead of routine deploys
+    enqueue_job_with_priority(pool, &job, JobPriority::High).await;
+    let _ = job_tx.send(job.with_priority(JobPriority::High)).await;
     info!(
         server_id,
         "governance workflow completed; retriggered deployment"
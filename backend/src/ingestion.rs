@@ -1,12 +1,26 @@
+use crate::config;
 use crate::extractor::AuthUser;
+use async_trait::async_trait;
 use axum::{
     extract::{Extension, Path},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
 use sqlx::{PgPool, Row};
-use tracing::error;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time;
+use tracing::{error, info, warn};
+
+pub mod chunking;
+
+use chunking::{ChunkingConfig, DocumentChunk, DocumentChunker, Utf8StreamDecoder};
 
 #[derive(Serialize)]
 pub struct IngestionJob {
@@ -84,6 +98,39 @@ pub async fn create_job(
     }))
 }
 
+#[derive(Deserialize, Default)]
+pub struct RunJob {
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Queues a job for the next worker pass; `force` re-embeds even when content is unchanged.
+pub async fn run_job(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    payload: Option<Json<RunJob>>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let force = payload.map(|Json(body)| body.force).unwrap_or_default();
+    let res = sqlx::query(
+        "UPDATE ingestion_jobs SET last_run = NULL, force_next_run = force_next_run OR $3 \
+         WHERE id = $1 AND owner_id = $2",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(force)
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error queueing ingestion job");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Job not found".into()));
+    }
+    Ok(StatusCode::ACCEPTED)
+}
+
 pub async fn delete_job(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
@@ -104,11 +151,358 @@ pub async fn delete_job(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// key: ingestion-batching -> queued document chunk bound for a vector store
+#[derive(Debug, Clone)]
+pub struct IngestionItem {
+    pub job_id: i32,
+    pub vector_db_id: i32,
+    pub document: String,
+    /// Told whether the write landed, so a job's hash only advances once every chunk did.
+    pub receipt: Option<WriteReceipt>,
+}
+
+/// Receives `true` once an item's write was accepted and `false` if it failed.
+pub type WriteReceipt = mpsc::UnboundedSender<bool>;
+
+/// key: ingestion-dedupe -> normalized content hash
+///
+/// Hashes the document with whitespace runs collapsed so reformatting alone does not
+/// trigger a re-embed.
+pub fn normalized_content_hash(document: &str) -> String {
+    let mut hasher = NormalizedContentHasher::default();
+    hasher.update(document);
+    hasher.finish()
+}
+
+/// Incremental form of [`normalized_content_hash`] for documents read in pieces.
+#[derive(Default)]
+pub struct NormalizedContentHasher {
+    hasher: Sha256,
+    in_token: bool,
+    seen_token: bool,
+}
+
+impl NormalizedContentHasher {
+    pub fn update(&mut self, text: &str) {
+        let mut utf8 = [0u8; 4];
+        for ch in text.chars() {
+            if ch.is_whitespace() {
+                self.in_token = false;
+                continue;
+            }
+            if !self.in_token {
+                if self.seen_token {
+                    self.hasher.update(b" ");
+                }
+                self.in_token = true;
+                self.seen_token = true;
+            }
+            self.hasher.update(ch.encode_utf8(&mut utf8).as_bytes());
+        }
+    }
+
+    pub fn finish(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IngestionDecision {
+    Embed { content_hash: String },
+    SkipUnchanged { content_hash: String },
+}
+
+pub fn plan_ingestion(stored_hash: Option<&str>, document: &str, force: bool) -> IngestionDecision {
+    plan_ingestion_for_hash(stored_hash, normalized_content_hash(document), force)
+}
+
+pub fn plan_ingestion_for_hash(
+    stored_hash: Option<&str>,
+    content_hash: String,
+    force: bool,
+) -> IngestionDecision {
+    if !force && stored_hash == Some(content_hash.as_str()) {
+        IngestionDecision::SkipUnchanged { content_hash }
+    } else {
+        IngestionDecision::Embed { content_hash }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IngestionBatchConfig {
+    pub max_batch_size: usize,
+    pub flush_interval: Duration,
+    pub queue_capacity: usize,
+}
+
+impl IngestionBatchConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_batch_size: *config::INGESTION_BATCH_MAX_SIZE,
+            flush_interval: Duration::from_millis(*config::INGESTION_BATCH_FLUSH_INTERVAL_MS),
+            queue_capacity: *config::INGESTION_QUEUE_CAPACITY,
+        }
+    }
+}
+
+/// Destination for flushed ingestion batches, one call per vector database.
+#[async_trait]
+pub trait IngestionSink: Send + Sync {
+    async fn write(&self, vector_db_id: i32, items: &[IngestionItem]) -> Result<(), String>;
+}
+
+struct HttpIngestionSink {
+    client: reqwest::Client,
+    /// Opt-in `{"documents": [...]}` body per flush; otherwise `/ingest` receives its
+    /// original raw text body, one request per document.
+    json_batches: bool,
+}
+
+impl HttpIngestionSink {
+    async fn post(&self, request: reqwest::RequestBuilder) -> Result<(), String> {
+        request
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map(drop)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl IngestionSink for HttpIngestionSink {
+    async fn write(&self, vector_db_id: i32, items: &[IngestionItem]) -> Result<(), String> {
+        let target = format!("http://mcp-vectordb-{vector_db_id}:8000/ingest");
+        if self.json_batches {
+            let documents: Vec<&str> = items.iter().map(|item| item.document.as_str()).collect();
+            return self
+                .post(
+                    self.client
+                        .post(&target)
+                        .json(&json!({ "documents": documents })),
+                )
+                .await;
+        }
+        for item in items {
+            self.post(self.client.post(&target).body(item.document.clone()))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Spawns the batching stage. Senders wait once `queue_capacity` items are pending,
+/// which pushes back on producers while the vector store is slow.
+pub fn spawn_ingestion_batcher(
+    sink: Arc<dyn IngestionSink>,
+    config: IngestionBatchConfig,
+) -> (mpsc::Sender<IngestionItem>, JoinHandle<()>) {
+    let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+    let handle = tokio::spawn(run_ingestion_batcher(rx, sink, config));
+    (tx, handle)
+}
+
+async fn run_ingestion_batcher(
+    mut rx: mpsc::Receiver<IngestionItem>,
+    sink: Arc<dyn IngestionSink>,
+    config: IngestionBatchConfig,
+) {
+    let max_batch_size = config.max_batch_size.max(1);
+    while let Some(first) = rx.recv().await {
+        let mut batch = Vec::with_capacity(max_batch_size);
+        batch.push(first);
+        let deadline = time::Instant::now() + config.flush_interval;
+        while batch.len() < max_batch_size {
+            match time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(item)) => batch.push(item),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        flush_ingestion_batch(sink.as_ref(), batch).await;
+    }
+}
+
+async fn flush_ingestion_batch(sink: &dyn IngestionSink, batch: Vec<IngestionItem>) {
+    let batch_size = batch.len();
+    let started = Instant::now();
+    let mut by_vector_db: BTreeMap<i32, Vec<IngestionItem>> = BTreeMap::new();
+    for item in batch {
+        by_vector_db
+            .entry(item.vector_db_id)
+            .or_default()
+            .push(item);
+    }
+    for (vector_db_id, items) in &by_vector_db {
+        let result = sink.write(*vector_db_id, items).await;
+        if let Err(err) = &result {
+            warn!(%err, vector_db_id, documents = items.len(), "ingestion batch write failed");
+        }
+        for receipt in items.iter().filter_map(|item| item.receipt.as_ref()) {
+            let _ = receipt.send(result.is_ok());
+        }
+    }
+    info!(
+        target: "ingestion.metrics",
+        event_type = "ingestion_batch_flushed",
+        batch_size,
+        vector_dbs = by_vector_db.len(),
+        latency_ms = started.elapsed().as_millis() as u64,
+        "ingestion batch flushed"
+    );
+}
+
+struct StreamingJob {
+    job_id: i32,
+    vector_db_id: i32,
+}
+
+struct BatcherStopped;
+
+/// key: ingestion-chunking -> stream a fetched document into the batcher
+///
+/// Chunks are queued as the body arrives, so embedding starts before the download finishes
+/// and the document is never held whole. Returns the content hash once every chunk was
+/// written, or `None` when the body could not be read or a write failed.
+async fn stream_document(
+    mut resp: reqwest::Response,
+    job: &StreamingJob,
+    chunking: ChunkingConfig,
+    batch_tx: &mpsc::Sender<IngestionItem>,
+) -> Result<Option<String>, BatcherStopped> {
+    let mut chunker = match DocumentChunker::new(chunking) {
+        Ok(chunker) => chunker,
+        Err(err) => {
+            error!(%err, job_id = job.job_id, "invalid ingestion chunking config");
+            return Ok(None);
+        }
+    };
+    let mut decoder = Utf8StreamDecoder::default();
+    let mut hasher = NormalizedContentHasher::default();
+    let (receipt_tx, mut receipts) = mpsc::unbounded_channel();
+    let mut queued = 0usize;
+
+    loop {
+        match resp.chunk().await {
+            Ok(Some(bytes)) => {
+                let text = decoder.push(&bytes);
+                hasher.update(&text);
+                for chunk in chunker.push(&text) {
+                    enqueue_chunk(job, chunk, &receipt_tx, batch_tx).await?;
+                    queued += 1;
+                }
+            }
+            Ok(None) => break,
+            Err(err) => {
+                warn!(%err, job_id = job.job_id, "ingestion document read failed");
+                return Ok(None);
+            }
+        }
+    }
+    let tail = decoder.finish();
+    hasher.update(&tail);
+    let mut rest = chunker.push(&tail);
+    rest.extend(chunker.finish());
+    for chunk in rest {
+        enqueue_chunk(job, chunk, &receipt_tx, batch_tx).await?;
+        queued += 1;
+    }
+    drop(receipt_tx);
+
+    // the channel closes once the batcher has flushed (and dropped) every queued chunk
+    let mut written = 0usize;
+    while let Some(accepted) = receipts.recv().await {
+        if !accepted {
+            warn!(
+                job_id = job.job_id,
+                "ingestion chunk write failed; hash not stored"
+            );
+            return Ok(None);
+        }
+        written += 1;
+    }
+    Ok((written == queued).then(|| hasher.finish()))
+}
+
+/// Reads a document only to hash it, for deciding whether it changed before embedding.
+async fn hash_document(mut resp: reqwest::Response, job_id: i32) -> Option<String> {
+    let mut decoder = Utf8StreamDecoder::default();
+    let mut hasher = NormalizedContentHasher::default();
+    loop {
+        match resp.chunk().await {
+            Ok(Some(bytes)) => hasher.update(&decoder.push(&bytes)),
+            Ok(None) => break,
+            Err(err) => {
+                warn!(%err, job_id, "ingestion document read failed");
+                return None;
+            }
+        }
+    }
+    hasher.update(&decoder.finish());
+    Some(hasher.finish())
+}
+
+async fn enqueue_chunk(
+    job: &StreamingJob,
+    chunk: DocumentChunk,
+    receipt: &WriteReceipt,
+    batch_tx: &mpsc::Sender<IngestionItem>,
+) -> Result<(), BatcherStopped> {
+    let item = IngestionItem {
+        job_id: job.job_id,
+        vector_db_id: job.vector_db_id,
+        document: chunk.text,
+        receipt: Some(receipt.clone()),
+    };
+    batch_tx.send(item).await.map_err(|_| BatcherStopped)
+}
+
+fn log_skipped_unchanged(job: &StreamingJob, content_hash: &str) {
+    info!(
+        target: "ingestion.metrics",
+        event_type = "ingestion_skipped_unchanged",
+        job_id = job.job_id,
+        vector_db_id = job.vector_db_id,
+        content_hash = %content_hash,
+        "ingestion skipped unchanged document"
+    );
+}
+
+/// Marks a job as run, remembering the hash and ETag of what the vector store now holds.
+async fn record_ingested(pool: &PgPool, job_id: i32, content_hash: &str, etag: Option<&str>) {
+    let result = sqlx::query(
+        "UPDATE ingestion_jobs SET last_run = NOW(), force_next_run = FALSE, content_hash = $2, \
+         source_etag = $3 WHERE id = $1",
+    )
+    .bind(job_id)
+    .bind(content_hash)
+    .bind(etag)
+    .execute(pool)
+    .await;
+    if let Err(err) = result {
+        error!(?err, job_id, "failed to record ingestion run");
+    }
+}
+
+fn response_etag(resp: &reqwest::Response) -> Option<String> {
+    resp.headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
 pub fn start_ingestion_worker(pool: PgPool) {
+    let sink: Arc<dyn IngestionSink> = Arc::new(HttpIngestionSink {
+        client: reqwest::Client::new(),
+        json_batches: *config::INGESTION_JSON_BATCHES,
+    });
+    let (batch_tx, _batcher) = spawn_ingestion_batcher(sink, IngestionBatchConfig::from_env());
+    let chunking = ChunkingConfig::from_env();
+    let client = reqwest::Client::new();
     tokio::spawn(async move {
         loop {
             let rows = sqlx::query(
-                "SELECT id, vector_db_id, source_url, schedule_minutes, last_run FROM ingestion_jobs"
+                "SELECT id, vector_db_id, source_url, schedule_minutes, last_run, content_hash, \
+                 force_next_run, source_etag FROM ingestion_jobs",
             )
             .fetch_all(&pool)
             .await
@@ -120,22 +514,66 @@ pub fn start_ingestion_worker(pool: PgPool) {
                 let url: String = row.get("source_url");
                 let schedule: i32 = row.get("schedule_minutes");
                 let last_run: Option<chrono::DateTime<chrono::Utc>> = row.try_get("last_run").ok();
+                let stored_hash: Option<String> = row.try_get("content_hash").ok().flatten();
+                let stored_etag: Option<String> = row.try_get("source_etag").ok().flatten();
+                let force: bool = row.get("force_next_run");
                 let due = match last_run {
                     Some(t) => now - t > chrono::Duration::minutes(schedule as i64),
                     None => true,
                 };
-                if due {
-                    if let Ok(resp) = reqwest::get(&url).await {
-                        if let Ok(text) = resp.text().await {
-                            let target = format!("http://mcp-vectordb-{vector_db_id}:8000/ingest");
-                            let _ = reqwest::Client::new().post(&target).body(text).send().await;
-                            let _ = sqlx::query(
-                                "UPDATE ingestion_jobs SET last_run = NOW() WHERE id = $1",
-                            )
-                            .bind(id)
-                            .execute(&pool)
-                            .await;
+                if !due {
+                    continue;
+                }
+                let job = StreamingJob {
+                    job_id: id,
+                    vector_db_id,
+                };
+
+                // a matching ETag answers 304 without sending the document again
+                let mut request = client.get(&url);
+                if let (false, Some(etag)) = (force, &stored_etag) {
+                    request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                let Ok(mut resp) = request.send().await else {
+                    continue;
+                };
+                if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+                    if let Some(hash) = &stored_hash {
+                        log_skipped_unchanged(&job, hash);
+                        record_ingested(&pool, id, hash, stored_etag.as_deref()).await;
+                    }
+                    continue;
+                }
+
+                // without a usable ETag, hash the body first and fetch it again only if it
+                // changed, so neither pass holds the document in memory
+                if stored_hash.is_some() && !force {
+                    let etag = response_etag(&resp);
+                    let Some(content_hash) = hash_document(resp, id).await else {
+                        continue;
+                    };
+                    match plan_ingestion_for_hash(stored_hash.as_deref(), content_hash, force) {
+                        IngestionDecision::SkipUnchanged { content_hash } => {
+                            log_skipped_unchanged(&job, &content_hash);
+                            record_ingested(&pool, id, &content_hash, etag.as_deref()).await;
+                            continue;
                         }
+                        IngestionDecision::Embed { .. } => {}
+                    }
+                    resp = match client.get(&url).send().await {
+                        Ok(resp) => resp,
+                        Err(_) => continue,
+                    };
+                }
+                let etag = response_etag(&resp);
+                match stream_document(resp, &job, chunking, &batch_tx).await {
+                    Ok(Some(content_hash)) => {
+                        record_ingested(&pool, id, &content_hash, etag.as_deref()).await;
+                    }
+                    Ok(None) => {}
+                    Err(BatcherStopped) => {
+                        error!(job_id = id, "ingestion batcher stopped");
+                        return;
                     }
                 }
             }
@@ -143,3 +581,189 @@ pub fn start_ingestion_worker(pool: PgPool) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalized_content_hash, plan_ingestion, spawn_ingestion_batcher, stream_document,
+        ChunkingConfig, IngestionBatchConfig, IngestionDecision, IngestionItem, IngestionSink,
+        NormalizedContentHasher, StreamingJob,
+    };
+    use async_trait::async_trait;
+    use httpmock::prelude::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        writes: Mutex<Vec<usize>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl IngestionSink for RecordingSink {
+        async fn write(&self, _vector_db_id: i32, items: &[IngestionItem]) -> Result<(), String> {
+            self.writes.lock().unwrap().push(items.len());
+            if self.fail {
+                return Err("vector store unavailable".into());
+            }
+            Ok(())
+        }
+    }
+
+    fn item(job_id: i32) -> IngestionItem {
+        IngestionItem {
+            job_id,
+            vector_db_id: 1,
+            document: format!("document {job_id}"),
+            receipt: None,
+        }
+    }
+
+    /// Streams `document` from a mock source into a batcher backed by `sink`.
+    async fn stream_from_mock(document: &str, sink: Arc<RecordingSink>) -> Option<String> {
+        let source = MockServer::start_async().await;
+        source
+            .mock_async(|when, then| {
+                when.method(GET).path("/doc");
+                then.status(200).body(document);
+            })
+            .await;
+        let (tx, _handle) = spawn_ingestion_batcher(
+            sink,
+            IngestionBatchConfig {
+                max_batch_size: 4,
+                flush_interval: Duration::from_millis(20),
+                queue_capacity: 2,
+            },
+        );
+        let resp = reqwest::get(source.url("/doc")).await.unwrap();
+        let job = StreamingJob {
+            job_id: 7,
+            vector_db_id: 1,
+        };
+        let chunking = ChunkingConfig {
+            chunk_size: 40,
+            overlap: 5,
+            boundary_tolerance: 5,
+        };
+        stream_document(resp, &job, chunking, &tx)
+            .await
+            .unwrap_or_else(|_| panic!("batcher stopped"))
+    }
+
+    #[tokio::test]
+    async fn items_within_window_coalesce_into_one_flush() {
+        let sink = Arc::new(RecordingSink::default());
+        let (tx, handle) = spawn_ingestion_batcher(
+            sink.clone(),
+            IngestionBatchConfig {
+                max_batch_size: 10,
+                flush_interval: Duration::from_millis(100),
+                queue_capacity: 16,
+            },
+        );
+
+        for job_id in 0..3 {
+            tx.send(item(job_id)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(*sink.writes.lock().unwrap(), vec![3]);
+
+        drop(tx);
+        handle.await.unwrap();
+        assert_eq!(*sink.writes.lock().unwrap(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn batch_flushes_early_when_size_cap_reached() {
+        let sink = Arc::new(RecordingSink::default());
+        let (tx, handle) = spawn_ingestion_batcher(
+            sink.clone(),
+            IngestionBatchConfig {
+                max_batch_size: 2,
+                flush_interval: Duration::from_secs(30),
+                queue_capacity: 16,
+            },
+        );
+
+        for job_id in 0..5 {
+            tx.send(item(job_id)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(*sink.writes.lock().unwrap(), vec![2, 2]);
+
+        drop(tx);
+        handle.await.unwrap();
+        assert_eq!(*sink.writes.lock().unwrap(), vec![2, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn hash_is_returned_once_every_chunk_is_written() {
+        let document = "Streaming keeps memory flat. ".repeat(20);
+        let sink = Arc::new(RecordingSink::default());
+        let hash = stream_from_mock(&document, sink.clone()).await;
+        assert_eq!(hash, Some(normalized_content_hash(&document)));
+        // the document went out as several chunks, never as one buffered write
+        let writes = sink.writes.lock().unwrap();
+        assert!(writes.iter().sum::<usize>() > 4, "writes: {writes:?}");
+    }
+
+    #[tokio::test]
+    async fn failed_write_withholds_the_hash() {
+        let document = "Streaming keeps memory flat. ".repeat(20);
+        let sink = Arc::new(RecordingSink {
+            fail: true,
+            ..Default::default()
+        });
+        assert_eq!(stream_from_mock(&document, sink).await, None);
+    }
+
+    #[test]
+    fn unchanged_document_is_skipped() {
+        let stored = normalized_content_hash("Hello   vector\nworld");
+        let decision = plan_ingestion(Some(&stored), "  Hello vector world\n", false);
+        assert_eq!(
+            decision,
+            IngestionDecision::SkipUnchanged {
+                content_hash: stored
+            }
+        );
+    }
+
+    #[test]
+    fn changed_document_is_reembedded() {
+        let stored = normalized_content_hash("Hello vector world");
+        let decision = plan_ingestion(Some(&stored), "Hello vector galaxy", false);
+        assert!(matches!(
+            decision,
+            IngestionDecision::Embed { ref content_hash } if *content_hash != stored
+        ));
+        assert!(matches!(
+            plan_ingestion(None, "first ingest", false),
+            IngestionDecision::Embed { .. }
+        ));
+    }
+
+    #[test]
+    fn force_always_reembeds() {
+        let stored = normalized_content_hash("Hello vector world");
+        let decision = plan_ingestion(Some(&stored), "Hello vector world", true);
+        assert_eq!(
+            decision,
+            IngestionDecision::Embed {
+                content_hash: stored
+            }
+        );
+    }
+
+    #[test]
+    fn incremental_hash_matches_whole_document_hash() {
+        let document = "  Hello   vector\nworld,\tstreamed ";
+        let mut hasher = NormalizedContentHasher::default();
+        for piece in ["  Hel", "lo   vec", "tor\nworld,", "\tstreamed", " "] {
+            hasher.update(piece);
+        }
+        assert_eq!(hasher.finish(), normalized_content_hash(document));
+    }
+}
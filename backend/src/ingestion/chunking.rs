@@ -0,0 +1,291 @@
+use crate::config;
+
+// key: ingestion-chunking -> incremental document chunker
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkingConfig {
+    /// Target chunk length in characters.
+    pub chunk_size: usize,
+    /// Characters repeated from the end of one chunk at the start of the next.
+    pub overlap: usize,
+    /// How far from `chunk_size` a paragraph or sentence break may move the cut.
+    pub boundary_tolerance: usize,
+}
+
+impl ChunkingConfig {
+    pub fn from_env() -> Self {
+        let chunk_size = *config::INGESTION_CHUNK_SIZE;
+        let boundary_tolerance = (*config::INGESTION_CHUNK_BOUNDARY_TOLERANCE).min(chunk_size / 4);
+        Self {
+            chunk_size,
+            overlap: (*config::INGESTION_CHUNK_OVERLAP)
+                .min(chunk_size.saturating_sub(boundary_tolerance + 1)),
+            boundary_tolerance,
+        }
+    }
+
+    /// Every cut must land past the carried overlap or the chunker would stall.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.chunk_size == 0 {
+            return Err("chunk_size must be positive".into());
+        }
+        if self.overlap + self.boundary_tolerance >= self.chunk_size {
+            return Err("overlap plus boundary_tolerance must be smaller than chunk_size".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentChunk {
+    pub index: usize,
+    pub text: String,
+    /// Character offsets of the chunk within the whole document.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Splits a document fed in arbitrary pieces into overlapping chunks, emitting each
+/// chunk as soon as enough text has arrived to choose its boundary.
+#[derive(Debug)]
+pub struct DocumentChunker {
+    config: ChunkingConfig,
+    buffer: Vec<char>,
+    /// Document offset of `buffer[0]`.
+    offset: usize,
+    /// Leading characters of `buffer` already emitted as overlap.
+    carried: usize,
+    next_index: usize,
+}
+
+impl DocumentChunker {
+    pub fn new(config: ChunkingConfig) -> Result<Self, String> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            buffer: Vec::new(),
+            offset: 0,
+            carried: 0,
+            next_index: 0,
+        })
+    }
+
+    pub fn push(&mut self, text: &str) -> Vec<DocumentChunk> {
+        self.buffer.extend(text.chars());
+        let lookahead = self.config.chunk_size + self.config.boundary_tolerance;
+        let mut chunks = Vec::new();
+        while self.buffer.len() >= lookahead {
+            let cut = self.choose_cut();
+            chunks.push(self.emit(cut));
+        }
+        chunks
+    }
+
+    /// Flushes whatever text has not been emitted yet.
+    pub fn finish(mut self) -> Vec<DocumentChunk> {
+        let mut chunks = Vec::new();
+        while self.buffer.len() > self.carried {
+            let cut = if self.buffer.len() > self.config.chunk_size {
+                self.choose_cut()
+            } else {
+                self.buffer.len()
+            };
+            chunks.push(self.emit(cut));
+        }
+        chunks
+    }
+
+    fn choose_cut(&self) -> usize {
+        let target = self.config.chunk_size.min(self.buffer.len());
+        let low = target
+            .saturating_sub(self.config.boundary_tolerance)
+            .max(self.carried + 1);
+        let high = (target + self.config.boundary_tolerance).min(self.buffer.len());
+        self.closest_break(low, high, target, is_paragraph_break)
+            .or_else(|| self.closest_break(low, high, target, is_sentence_break))
+            .unwrap_or(target)
+    }
+
+    /// Finds the cut in `low..=high` nearest `target` whose preceding text ends in a break.
+    fn closest_break(
+        &self,
+        low: usize,
+        high: usize,
+        target: usize,
+        is_break: fn(&[char], usize) -> bool,
+    ) -> Option<usize> {
+        (low..=high)
+            .filter(|cut| is_break(&self.buffer, *cut))
+            .min_by_key(|cut| cut.abs_diff(target))
+    }
+
+    fn emit(&mut self, cut: usize) -> DocumentChunk {
+        let chunk = DocumentChunk {
+            index: self.next_index,
+            text: self.buffer[..cut].iter().collect(),
+            start: self.offset,
+            end: self.offset + cut,
+        };
+        self.next_index += 1;
+        let keep_from = cut.saturating_sub(self.config.overlap);
+        self.buffer.drain(..keep_from);
+        self.offset += keep_from;
+        self.carried = cut - keep_from;
+        chunk
+    }
+}
+
+/// A cut after a blank line.
+fn is_paragraph_break(text: &[char], cut: usize) -> bool {
+    cut >= 2 && text[cut - 1] == '\n' && text[cut - 2] == '\n'
+}
+
+/// A cut after sentence-ending punctuation followed by whitespace.
+fn is_sentence_break(text: &[char], cut: usize) -> bool {
+    cut >= 2 && text[cut - 1].is_whitespace() && matches!(text[cut - 2], '.' | '!' | '?')
+}
+
+/// Decodes UTF-8 arriving in arbitrary byte slices, holding back split code points.
+#[derive(Debug, Default)]
+pub struct Utf8StreamDecoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8StreamDecoder {
+    pub fn push(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let mut decoded = String::new();
+        loop {
+            match std::str::from_utf8(&self.pending) {
+                Ok(text) => {
+                    decoded.push_str(text);
+                    self.pending.clear();
+                    return decoded;
+                }
+                Err(err) => {
+                    let valid = err.valid_up_to();
+                    decoded.push_str(&String::from_utf8_lossy(&self.pending[..valid]));
+                    match err.error_len() {
+                        Some(len) => {
+                            decoded.push(char::REPLACEMENT_CHARACTER);
+                            self.pending.drain(..valid + len);
+                        }
+                        None => {
+                            self.pending.drain(..valid);
+                            return decoded;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> String {
+        String::from_utf8_lossy(&self.pending).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(chunk_size: usize, overlap: usize, boundary_tolerance: usize) -> ChunkingConfig {
+        ChunkingConfig {
+            chunk_size,
+            overlap,
+            boundary_tolerance,
+        }
+    }
+
+    fn chunk_all(config: ChunkingConfig, document: &str, piece: usize) -> Vec<DocumentChunk> {
+        let mut chunker = DocumentChunker::new(config).unwrap();
+        let chars: Vec<char> = document.chars().collect();
+        let mut chunks = Vec::new();
+        for part in chars.chunks(piece) {
+            chunks.extend(chunker.push(&part.iter().collect::<String>()));
+        }
+        chunks.extend(chunker.finish());
+        chunks
+    }
+
+    fn sample_document() -> String {
+        let sentence = "Vector stores index embeddings for retrieval. ";
+        let paragraph = sentence.repeat(4);
+        format!("{paragraph}\n\n{paragraph}\n\n{paragraph}")
+    }
+
+    #[test]
+    fn chunk_counts_and_coverage_match_configuration() {
+        let document = "abcdefghij".repeat(10);
+        let chunks = chunk_all(config(30, 5, 0), &document, 7);
+
+        // 100 chars, 25 new chars per chunk after the first: 30 + 25 + 25 + 20
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks.iter().all(|chunk| chunk.text.chars().count() <= 30));
+        assert_eq!(chunks.last().unwrap().end, 100);
+        let indexes: Vec<usize> = chunks.iter().map(|chunk| chunk.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+
+        let mut rebuilt: String = chunks[0].text.clone();
+        for chunk in &chunks[1..] {
+            rebuilt.extend(chunk.text.chars().skip(5));
+        }
+        assert_eq!(rebuilt, document);
+    }
+
+    #[test]
+    fn consecutive_chunks_share_configured_overlap() {
+        let document = sample_document();
+        let chunks = chunk_all(config(120, 20, 30), &document, 13);
+
+        assert!(chunks.len() > 2);
+        for pair in chunks.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            assert_eq!(next.start, prev.end - 20);
+            let tail: String = prev
+                .text
+                .chars()
+                .skip(prev.text.chars().count() - 20)
+                .collect();
+            let head: String = next.text.chars().take(20).collect();
+            assert_eq!(tail, head);
+        }
+        let total = document.chars().count();
+        assert_eq!(chunks.last().unwrap().end, total);
+    }
+
+    #[test]
+    fn cuts_prefer_paragraph_then_sentence_breaks_within_tolerance() {
+        let sentence = "Vector stores index embeddings for retrieval. ";
+        assert_eq!(sentence.len(), 46);
+        let document = sample_document();
+
+        // the first paragraph ends at 184 + 2 newlines = 186, within 30 of 180
+        let chunks = chunk_all(config(180, 0, 30), &document, 64);
+        assert!(chunks[0].text.ends_with("\n\n"));
+        assert_eq!(chunks[0].end, 186);
+
+        // no paragraph break near 100, but a sentence ends at 92
+        let chunks = chunk_all(config(100, 0, 10), &document, 64);
+        assert!(chunks[0].text.ends_with("retrieval. "));
+        assert_eq!(chunks[0].end, 92);
+
+        // with no break inside the tolerance the cut falls on the target size
+        let chunks = chunk_all(config(100, 0, 2), &document, 64);
+        assert_eq!(chunks[0].end, 100);
+    }
+
+    #[test]
+    fn decoder_reassembles_code_points_split_across_reads() {
+        let text = "naïve café ✓";
+        let bytes = text.as_bytes();
+        let mut decoder = Utf8StreamDecoder::default();
+        let mut decoded = String::new();
+        for byte in bytes {
+            decoded.push_str(&decoder.push(std::slice::from_ref(byte)));
+        }
+        decoded.push_str(&decoder.finish());
+        assert_eq!(decoded, text);
+        assert!(config(10, 8, 2).validate().is_err());
+    }
+}
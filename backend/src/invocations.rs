@@ -1,17 +1,89 @@
+use crate::config;
+use crate::db::mcp_servers;
 use crate::error::{AppError, AppResult};
 use crate::extractor::AuthUser;
 use axum::{
     extract::{Extension, Path},
+    http::HeaderMap,
     Json,
 };
 use serde::Serialize;
 use sqlx::{PgPool, Row};
+use std::future::Future;
+use std::time::Duration;
+
+pub mod batch;
+pub mod cache;
+pub mod stream;
+
+// key: invocation-timeout -> per-invocation deadlines and cancellation
+
+/// Request header carrying a per-invocation timeout override in milliseconds.
+/// The resolved deadline is forwarded upstream under the same name.
+pub const INVOCATION_TIMEOUT_HEADER: &str = "x-invocation-timeout-ms";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvocationStatus {
+    Succeeded,
+    Failed,
+    TimedOut,
+}
+
+impl InvocationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::TimedOut => "timed_out",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvocationTimedOut {
+    pub after: Duration,
+}
+
+pub fn default_timeout() -> Duration {
+    Duration::from_millis(*config::INVOCATION_TIMEOUT_MS)
+}
+
+/// Resolves the override header against the configured default, capping it at
+/// `INVOCATION_MAX_TIMEOUT_MS`.
+pub fn resolve_timeout(headers: &HeaderMap) -> Result<Duration, String> {
+    let Some(value) = headers.get(INVOCATION_TIMEOUT_HEADER) else {
+        return Ok(default_timeout());
+    };
+    let millis = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|millis| *millis > 0)
+        .ok_or_else(|| format!("{INVOCATION_TIMEOUT_HEADER} must be a positive integer"))?;
+    Ok(Duration::from_millis(
+        millis.min(*config::INVOCATION_MAX_TIMEOUT_MS),
+    ))
+}
+
+/// Runs `exchange` until `deadline`. On expiry the future is dropped, which
+/// aborts any in-flight upstream request it owns.
+pub async fn with_deadline<F: Future>(
+    deadline: Duration,
+    exchange: F,
+) -> Result<F::Output, InvocationTimedOut> {
+    tokio::time::timeout(deadline, exchange)
+        .await
+        .map_err(|_| InvocationTimedOut { after: deadline })
+}
 
 #[derive(Serialize)]
 pub struct InvocationTrace {
     pub id: i32,
     pub input_json: serde_json::Value,
     pub output_text: Option<String>,
+    pub status: String,
+    pub timeout_ms: Option<i64>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -20,16 +92,11 @@ pub async fn list_invocations(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> AppResult<Json<Vec<InvocationTrace>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await?;
-    if rec.is_none() {
+    if !mcp_servers::is_live_owned_by(&pool, server_id, user_id).await? {
         return Err(AppError::NotFound);
     }
     let rows = sqlx::query(
-        "SELECT id, input_json, output_text, created_at FROM invocation_traces WHERE server_id = $1 ORDER BY id DESC LIMIT 50"
+        "SELECT id, input_json, output_text, status, timeout_ms, created_at FROM invocation_traces WHERE server_id = $1 ORDER BY id DESC LIMIT 50"
     )
     .bind(server_id)
     .fetch_all(&pool)
@@ -40,6 +107,8 @@ pub async fn list_invocations(
             id: r.get("id"),
             input_json: r.get("input_json"),
             output_text: r.get("output_text"),
+            status: r.get("status"),
+            timeout_ms: r.get("timeout_ms"),
             created_at: r.get("created_at"),
         })
         .collect();
@@ -52,15 +121,83 @@ pub async fn record_invocation(
     user_id: i32,
     input_json: &serde_json::Value,
     output_text: Option<&str>,
+    status: InvocationStatus,
+    timeout: Duration,
 ) -> Result<(), sqlx::Error> {
     sqlx::query(
-        "INSERT INTO invocation_traces (server_id, user_id, input_json, output_text) VALUES ($1,$2,$3,$4)"
+        "INSERT INTO invocation_traces (server_id, user_id, input_json, output_text, status, timeout_ms) VALUES ($1,$2,$3,$4,$5,$6)"
     )
     .bind(server_id)
     .bind(user_id)
     .bind(input_json)
     .bind(output_text)
+    .bind(status.as_str())
+    .bind(timeout.as_millis() as i64)
     .execute(pool)
     .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn exchange_within_deadline_returns_output() {
+        let result = with_deadline(Duration::from_millis(500), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            "done"
+        })
+        .await;
+        assert_eq!(result, Ok("done"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(INVOCATION_TIMEOUT_HEADER, HeaderValue::from_static("250"));
+        assert_eq!(resolve_timeout(&headers), Ok(Duration::from_millis(250)));
+        headers.insert(INVOCATION_TIMEOUT_HEADER, HeaderValue::from_static("0"));
+        assert!(resolve_timeout(&headers).is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_deadline_cancels_upstream_request() {
+        // the upstream accepts the request and never answers; it observes the
+        // connection closing once the deadline drops the client future
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let closed = Arc::new(AtomicBool::new(false));
+        let upstream_closed = closed.clone();
+        let upstream = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            while socket.read(&mut buf).await.map(|n| n > 0).unwrap_or(false) {}
+            upstream_closed.store(true, Ordering::SeqCst);
+        });
+
+        let client = reqwest::Client::new();
+        let request = client.post(format!("http://{addr}/invoke")).body("{}");
+        let deadline = Duration::from_millis(100);
+        let result = with_deadline(deadline, request.send()).await;
+
+        let status = match result {
+            Ok(_) => InvocationStatus::Succeeded,
+            Err(InvocationTimedOut { after }) => {
+                assert_eq!(after, deadline);
+                InvocationStatus::TimedOut
+            }
+        };
+        assert_eq!(status, InvocationStatus::TimedOut);
+        assert_eq!(status.as_str(), "timed_out");
+
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(2), upstream)
+            .await
+            .expect("upstream saw the cancelled connection close")
+            .unwrap();
+        assert!(closed.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,250 @@
+use std::future::Future;
+use std::time::Duration;
+
+use axum::{
+    extract::Extension,
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+
+use super::resolve_timeout;
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use crate::servers::invoke_server_internal;
+
+// key: invocation-batch -> bounded fan-out with per-item results
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchInvocation {
+    pub server_id: i32,
+    pub input: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchInvocationRequest {
+    pub invocations: Vec<BatchInvocation>,
+    /// Lowered to `INVOCATION_BATCH_MAX_CONCURRENCY` when larger.
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BatchItemOutcome {
+    Succeeded {
+        output: Value,
+    },
+    Failed {
+        code: u16,
+        error: String,
+    },
+    /// Still running, or never started, when the batch deadline passed.
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub server_id: i32,
+    #[serde(flatten)]
+    pub outcome: BatchItemOutcome,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchInvocationResponse {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+    /// One entry per invocation, in request order.
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchInvocationResponse {
+    fn new(results: Vec<BatchItemResult>) -> Self {
+        let count = |wanted: fn(&BatchItemOutcome) -> bool| {
+            results
+                .iter()
+                .filter(|result| wanted(&result.outcome))
+                .count()
+        };
+        Self {
+            succeeded: count(|outcome| matches!(outcome, BatchItemOutcome::Succeeded { .. })),
+            failed: count(|outcome| matches!(outcome, BatchItemOutcome::Failed { .. })),
+            timed_out: count(|outcome| matches!(outcome, BatchItemOutcome::TimedOut)),
+            results,
+        }
+    }
+}
+
+/// Runs `invoke` for every item, at most `concurrency` at a time, until `deadline`. A failing
+/// item only fails its own entry. Items unfinished at the deadline are dropped, which cancels
+/// their upstream requests, and reported as timed out.
+pub async fn run_batch<F, Fut>(
+    items: Vec<BatchInvocation>,
+    concurrency: usize,
+    deadline: Duration,
+    invoke: F,
+) -> Vec<BatchItemResult>
+where
+    F: Fn(BatchInvocation) -> Fut,
+    Fut: Future<Output = Result<Value, (StatusCode, String)>>,
+{
+    let mut results: Vec<BatchItemResult> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| BatchItemResult {
+            index,
+            server_id: item.server_id,
+            outcome: BatchItemOutcome::TimedOut,
+        })
+        .collect();
+
+    let mut pending = stream::iter(items.into_iter().enumerate())
+        .map(|(index, item)| {
+            let call = invoke(item);
+            async move { (index, call.await) }
+        })
+        .buffer_unordered(concurrency.max(1));
+    let collect = async {
+        while let Some((index, result)) = pending.next().await {
+            results[index].outcome = match result {
+                Ok(output) => BatchItemOutcome::Succeeded { output },
+                Err((status, error)) => BatchItemOutcome::Failed {
+                    code: status.as_u16(),
+                    error,
+                },
+            };
+        }
+    };
+    let _ = tokio::time::timeout(deadline, collect).await;
+    results
+}
+
+/// POST /api/invocations/batch. Each item is invoked like a workflow step, against a server
+/// the caller owns. The `X-Invocation-Timeout-Ms` header bounds the whole batch.
+pub async fn invoke_batch(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    headers: HeaderMap,
+    Json(request): Json<BatchInvocationRequest>,
+) -> AppResult<Json<BatchInvocationResponse>> {
+    let deadline = resolve_timeout(&headers).map_err(AppError::BadRequest)?;
+    if request.invocations.is_empty() {
+        return Err(AppError::BadRequest("invocations cannot be empty".into()));
+    }
+    let max_items = config::reload::current().invocation_batch_max_items;
+    if request.invocations.len() > max_items {
+        return Err(AppError::BadRequest(format!(
+            "a batch holds at most {max_items} invocations"
+        )));
+    }
+    let concurrency = request
+        .max_concurrency
+        .unwrap_or(usize::MAX)
+        .min(*config::INVOCATION_BATCH_MAX_CONCURRENCY);
+
+    let pool = &pool;
+    let results =
+        run_batch(
+            request.invocations,
+            concurrency,
+            deadline,
+            |item| async move {
+                invoke_server_internal(pool, user_id, item.server_id, &item.input).await
+            },
+        )
+        .await;
+    Ok(Json(BatchInvocationResponse::new(results)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn items(server_ids: &[i32]) -> Vec<BatchInvocation> {
+        server_ids
+            .iter()
+            .map(|server_id| BatchInvocation {
+                server_id: *server_id,
+                input: json!({ "tool": "echo", "arguments": { "server": server_id } }),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn one_failure_leaves_the_rest_of_the_batch_intact() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let results = run_batch(items(&[1, 2, 3, 4, 5]), 2, Duration::from_secs(5), |item| {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                if item.server_id == 3 {
+                    Err((StatusCode::BAD_GATEWAY, "Container unreachable".to_string()))
+                } else {
+                    Ok(item.input["arguments"].clone())
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let response = BatchInvocationResponse::new(results);
+        assert_eq!(
+            (response.succeeded, response.failed, response.timed_out),
+            (4, 1, 0)
+        );
+        assert_eq!(
+            response.results[2].outcome,
+            BatchItemOutcome::Failed {
+                code: 502,
+                error: "Container unreachable".into()
+            }
+        );
+        assert_eq!(
+            response.results[4].outcome,
+            BatchItemOutcome::Succeeded {
+                output: json!({ "server": 5 })
+            }
+        );
+        let indexes: Vec<usize> = response.results.iter().map(|result| result.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn overall_deadline_reports_unfinished_items_as_timed_out() {
+        let results = run_batch(
+            items(&[1, 2, 3]),
+            3,
+            Duration::from_millis(100),
+            |item| async move {
+                if item.server_id == 2 {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                }
+                Ok(json!({ "server": item.server_id }))
+            },
+        )
+        .await;
+
+        let outcomes: Vec<&BatchItemOutcome> =
+            results.iter().map(|result| &result.outcome).collect();
+        assert!(matches!(outcomes[0], BatchItemOutcome::Succeeded { .. }));
+        assert_eq!(outcomes[1], &BatchItemOutcome::TimedOut);
+        assert!(matches!(outcomes[2], BatchItemOutcome::Succeeded { .. }));
+        assert_eq!(
+            serde_json::to_value(&results[1]).unwrap(),
+            json!({ "index": 1, "server_id": 2, "status": "timed_out" })
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use crate::config;
+use axum::http::{header, HeaderMap};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+
+// key: invocation-cache -> ttl result cache for deterministic tools
+
+/// Response header marking whether the body was served from the cache.
+pub const CACHED_HEADER: &str = "x-invocation-cached";
+
+pub static INVOCATION_CACHE: Lazy<InvocationCache> =
+    Lazy::new(|| InvocationCache::new(*config::INVOCATION_CACHE_MAX_ENTRIES));
+
+/// Normalized identity of an invocation: the server, the tool it calls, and its
+/// arguments with object keys sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub server_id: i32,
+    pub tool: Option<String>,
+    digest: String,
+}
+
+impl CacheKey {
+    /// Reads MCP `tools/call` requests (`params.name`/`params.arguments`) and the flat
+    /// `{tool, arguments}` shape. Anything else is keyed on the whole payload minus
+    /// its JSON-RPC `id`, which differs on every call.
+    pub fn for_payload(server_id: i32, payload: &Value) -> Self {
+        let (tool, arguments) =
+            if payload.get("method").and_then(Value::as_str) == Some("tools/call") {
+                let params = payload.get("params");
+                (
+                    params.and_then(|p| p.get("name")).and_then(Value::as_str),
+                    params.and_then(|p| p.get("arguments")).cloned(),
+                )
+            } else if let Some(tool) = payload.get("tool").and_then(Value::as_str) {
+                (
+                    Some(tool),
+                    payload
+                        .get("arguments")
+                        .or_else(|| payload.get("input"))
+                        .cloned(),
+                )
+            } else {
+                let mut body = payload.clone();
+                if let Some(object) = body.as_object_mut() {
+                    object.remove("id");
+                }
+                (None, Some(body))
+            };
+        let mut canonical = String::new();
+        write_canonical(&arguments.unwrap_or(Value::Null), &mut canonical);
+        let mut hasher = Sha256::new();
+        hasher.update(tool.unwrap_or_default().as_bytes());
+        hasher.update([0]);
+        hasher.update(canonical.as_bytes());
+        Self {
+            server_id,
+            tool: tool.map(str::to_owned),
+            digest: hex::encode(hasher.finalize()),
+        }
+    }
+}
+
+/// Writes `value` as JSON with object keys sorted, so equal documents hash equally.
+pub(crate) fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (idx, key) in keys.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (idx, item) in items.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// `Cache-Control: no-cache` (or `no-store`) skips the lookup; the fresh result is
+/// still stored for later callers.
+pub fn bypass_requested(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|directive| {
+            let directive = directive.trim();
+            directive.eq_ignore_ascii_case("no-cache") || directive.eq_ignore_ascii_case("no-store")
+        })
+}
+
+#[derive(Debug, Clone)]
+struct CachedResult {
+    body: String,
+    expires_at: Instant,
+}
+
+pub struct InvocationCache {
+    entries: DashMap<CacheKey, CachedResult>,
+    max_entries: usize,
+}
+
+impl InvocationCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            max_entries,
+        }
+    }
+
+    pub fn get(&self, key: &CacheKey, now: Instant) -> Option<String> {
+        let hit = self.entries.get(key).map(|entry| entry.clone())?;
+        if hit.expires_at <= now {
+            self.entries.remove(key);
+            return None;
+        }
+        Some(hit.body)
+    }
+
+    /// Stores a result; when the cache is full, expired entries are swept first and the
+    /// result is dropped if there is still no room.
+    pub fn insert(&self, key: CacheKey, body: String, ttl: Duration, now: Instant) {
+        if self.entries.len() >= self.max_entries && !self.entries.contains_key(&key) {
+            self.entries.retain(|_, entry| entry.expires_at > now);
+            if self.entries.len() >= self.max_entries {
+                return;
+            }
+        }
+        self.entries.insert(
+            key,
+            CachedResult {
+                body,
+                expires_at: now + ttl,
+            },
+        );
+    }
+
+    pub fn invalidate_server(&self, server_id: i32) {
+        self.entries.retain(|key, _| key.server_id != server_id);
+    }
+}
+
+/// Tools declared with `"deterministic": false` in the manifest opt out of caching.
+pub async fn tool_is_cacheable(
+    pool: &PgPool,
+    server_id: i32,
+    tool: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let Some(tool) = tool else {
+        return Ok(true);
+    };
+    let deterministic: Option<bool> = sqlx::query_scalar(
+        "SELECT deterministic FROM server_capabilities WHERE server_id = $1 AND name = $2 LIMIT 1",
+    )
+    .bind(server_id)
+    .bind(tool)
+    .fetch_optional(pool)
+    .await?;
+    Ok(deterministic.unwrap_or(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    fn call(id: i64, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": {"name": "lookup", "arguments": arguments},
+        })
+    }
+
+    #[test]
+    fn identical_calls_hit_within_ttl() {
+        let cache = InvocationCache::new(16);
+        let now = Instant::now();
+        let first = CacheKey::for_payload(7, &call(1, json!({"q": "rust", "limit": 5})));
+        cache.insert(first, "result".into(), Duration::from_secs(60), now);
+
+        // a new request id and reordered arguments normalize to the same key
+        let repeat = CacheKey::for_payload(7, &call(2, json!({"limit": 5, "q": "rust"})));
+        assert_eq!(repeat.tool.as_deref(), Some("lookup"));
+        assert_eq!(
+            cache.get(&repeat, now + Duration::from_secs(59)),
+            Some("result".into())
+        );
+
+        let other_args = CacheKey::for_payload(7, &call(3, json!({"q": "go", "limit": 5})));
+        let other_server = CacheKey::for_payload(8, &call(1, json!({"q": "rust", "limit": 5})));
+        assert_eq!(cache.get(&other_args, now), None);
+        assert_eq!(cache.get(&other_server, now), None);
+    }
+
+    #[test]
+    fn entries_expire_after_ttl() {
+        let cache = InvocationCache::new(1);
+        let now = Instant::now();
+        let key = CacheKey::for_payload(7, &json!({"tool": "clock", "arguments": {}}));
+        cache.insert(key.clone(), "noon".into(), Duration::from_secs(30), now);
+        assert_eq!(cache.get(&key, now + Duration::from_secs(30)), None);
+        assert_eq!(cache.get(&key, now), None, "expired entry was evicted");
+
+        // a full cache makes room by sweeping expired entries
+        cache.insert(key.clone(), "noon".into(), Duration::from_secs(30), now);
+        let later = now + Duration::from_secs(31);
+        let next = CacheKey::for_payload(7, &json!({"tool": "clock", "arguments": {"tz": "utc"}}));
+        cache.insert(next.clone(), "one".into(), Duration::from_secs(30), later);
+        assert_eq!(cache.get(&next, later), Some("one".into()));
+
+        cache.invalidate_server(7);
+        assert_eq!(cache.get(&next, later), None);
+    }
+
+    #[test]
+    fn no_cache_directive_requests_bypass() {
+        let mut headers = HeaderMap::new();
+        assert!(!bypass_requested(&headers));
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
+        assert!(!bypass_requested(&headers));
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static("max-age=0, No-Cache"),
+        );
+        assert!(bypass_requested(&headers));
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        assert!(bypass_requested(&headers));
+    }
+}
@@ -0,0 +1,290 @@
+use bytes::Bytes;
+use futures_util::{pin_mut, Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use super::InvocationStatus;
+use crate::proxy::collect_limited;
+use crate::proxy::transform::ProxyTransforms;
+
+// key: invocation-stream -> incremental tool output over SSE
+
+/// Framed like the remediation stream: one JSON `data:` line per event, tagged by `event`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum InvocationStreamEvent {
+    /// One chunk of incremental output, numbered from `0` in upstream order.
+    Partial { sequence: u64, output: Value },
+    /// Terminal. Non-streaming tools carry their whole output here.
+    Completed {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<Value>,
+    },
+    /// Terminal. The invocation failed or ran past its deadline.
+    Failed {
+        status: InvocationStatus,
+        message: String,
+    },
+}
+
+impl InvocationStreamEvent {
+    pub fn failed(status: InvocationStatus, message: impl Into<String>) -> Self {
+        Self::Failed {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvocationStreamMessage {
+    pub server_id: i32,
+    pub event: InvocationStreamEvent,
+}
+
+/// Whether an upstream response streams its output as server-sent events.
+pub fn is_event_stream(headers: &reqwest::header::HeaderMap) -> bool {
+    headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim_start().starts_with("text/event-stream"))
+}
+
+/// Splits an upstream SSE body into the `data` payloads of its frames. Chunks may end anywhere,
+/// even inside a UTF-8 sequence; frames without `data` lines, such as keep-alive comments,
+/// yield nothing.
+#[derive(Debug, Default)]
+pub struct SseDataDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDataDecoder {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer
+            .extend(chunk.iter().copied().filter(|byte| *byte != b'\r'));
+        let mut payloads = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|pair| pair == b"\n\n") {
+            let frame: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            payloads.extend(frame_data(&frame[..end]));
+        }
+        payloads
+    }
+
+    /// Flushes a last frame the upstream did not terminate with a blank line.
+    pub fn finish(&mut self) -> Option<String> {
+        let frame = std::mem::take(&mut self.buffer);
+        frame_data(&frame)
+    }
+}
+
+fn frame_data(frame: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(frame);
+    let lines: Vec<&str> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// JSON output stays structured; anything else is passed on as a string.
+fn parse_output(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+/// Relays an upstream body to `events` and ends with exactly one terminal event. A streaming
+/// body becomes one `partial` per SSE frame, then `completed`. Any other body is read whole,
+/// rewritten by `transforms` when given, and sent as a single `completed`. Returns the status
+/// and output text to record; a client that hangs up stops the relay early.
+pub async fn relay<S, E>(
+    body: S,
+    streaming: bool,
+    limit: usize,
+    transforms: Option<&ProxyTransforms>,
+    events: &mpsc::Sender<InvocationStreamEvent>,
+) -> (InvocationStatus, Option<String>)
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: std::fmt::Display,
+{
+    if !streaming {
+        let body = collect_limited(body, limit)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| match transforms {
+                Some(transforms) => transforms
+                    .transform_response(bytes)
+                    .map_err(|e| format!("proxy transform failed: {e}")),
+                None => Ok(bytes),
+            });
+        let (status, text, event) = match body {
+            Ok(bytes) => {
+                let text = String::from_utf8_lossy(&bytes).into_owned();
+                let output = parse_output(&text);
+                (
+                    InvocationStatus::Succeeded,
+                    Some(text),
+                    InvocationStreamEvent::Completed {
+                        output: Some(output),
+                    },
+                )
+            }
+            Err(message) => (
+                InvocationStatus::Failed,
+                None,
+                InvocationStreamEvent::failed(InvocationStatus::Failed, message),
+            ),
+        };
+        let _ = events.send(event).await;
+        return (status, text);
+    }
+
+    pin_mut!(body);
+    let mut decoder = SseDataDecoder::default();
+    let mut transcript = String::new();
+    let mut received = 0usize;
+    let mut sequence = 0u64;
+    let mut finished = false;
+    while !finished {
+        let payloads = match body.next().await {
+            Some(Ok(chunk)) => {
+                received += chunk.len();
+                if received > limit {
+                    let message = format!("upstream response exceeded {limit} bytes");
+                    let _ = events
+                        .send(InvocationStreamEvent::failed(
+                            InvocationStatus::Failed,
+                            message,
+                        ))
+                        .await;
+                    return (InvocationStatus::Failed, Some(transcript));
+                }
+                decoder.push(&chunk)
+            }
+            Some(Err(err)) => {
+                let message = format!("upstream stream failed: {err}");
+                let _ = events
+                    .send(InvocationStreamEvent::failed(
+                        InvocationStatus::Failed,
+                        message,
+                    ))
+                    .await;
+                return (InvocationStatus::Failed, Some(transcript));
+            }
+            None => {
+                finished = true;
+                decoder.finish().into_iter().collect()
+            }
+        };
+        for data in payloads {
+            let event = InvocationStreamEvent::Partial {
+                sequence,
+                output: parse_output(&data),
+            };
+            if events.send(event).await.is_err() {
+                return (InvocationStatus::Failed, Some(transcript));
+            }
+            transcript.push_str(&data);
+            transcript.push('\n');
+            sequence += 1;
+        }
+    }
+    let _ = events
+        .send(InvocationStreamEvent::Completed { output: None })
+        .await;
+    (InvocationStatus::Succeeded, Some(transcript))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use serde_json::json;
+
+    async fn relayed(
+        chunks: Vec<&'static [u8]>,
+        streaming: bool,
+    ) -> (InvocationStatus, Vec<InvocationStreamEvent>) {
+        let body = stream::iter(
+            chunks
+                .into_iter()
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::from_static(chunk))),
+        );
+        let (tx, mut rx) = mpsc::channel(16);
+        let (status, _) = relay(body, streaming, 1024, None, &tx).await;
+        drop(tx);
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        (status, events)
+    }
+
+    #[tokio::test]
+    async fn streaming_tool_yields_partials_in_order_then_completes() {
+        // frames split across chunks, including one inside a multi-byte character
+        let (status, events) = relayed(
+            vec![
+                b"data: {\"token\":\"he\"}\n\n: keep-alive\n\ndata: {\"tok",
+                b"en\":\"llo\"}\r\n\r\ndata: caf\xc3",
+                b"\xa9\n\ndata: done",
+            ],
+            true,
+        )
+        .await;
+        assert_eq!(status, InvocationStatus::Succeeded);
+        assert_eq!(
+            events,
+            vec![
+                InvocationStreamEvent::Partial {
+                    sequence: 0,
+                    output: json!({ "token": "he" })
+                },
+                InvocationStreamEvent::Partial {
+                    sequence: 1,
+                    output: json!({ "token": "llo" })
+                },
+                InvocationStreamEvent::Partial {
+                    sequence: 2,
+                    output: json!("café")
+                },
+                InvocationStreamEvent::Partial {
+                    sequence: 3,
+                    output: json!("done")
+                },
+                InvocationStreamEvent::Completed { output: None },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(InvocationStreamMessage {
+                server_id: 4,
+                event: events[0].clone(),
+            })
+            .unwrap(),
+            json!({
+                "server_id": 4,
+                "event": { "event": "partial", "sequence": 0, "output": { "token": "he" } }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn non_streaming_tool_emits_one_terminal_event() {
+        let (status, events) = relayed(vec![b"{\"result\":", b"\"ok\"}"], false).await;
+        assert_eq!(status, InvocationStatus::Succeeded);
+        assert_eq!(
+            events,
+            vec![InvocationStreamEvent::Completed {
+                output: Some(json!({ "result": "ok" }))
+            }]
+        );
+
+        let (status, events) = relayed(vec![&[b'x'; 2048]], true).await;
+        assert_eq!(status, InvocationStatus::Failed);
+        assert!(matches!(
+            events.as_slice(),
+            [InvocationStreamEvent::Failed { .. }]
+        ));
+    }
+}
@@ -1,13 +1,21 @@
 use crate::policy::trust::evaluate_placement_gate;
 use crate::runtime::ContainerRuntime;
-use crate::{evaluations, intelligence, servers};
+use crate::{config, evaluations, intelligence, servers};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{PgPool, Row};
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::time::{sleep, Duration};
 
+pub mod lanes;
+
+pub use lanes::JobPriority;
+use lanes::PriorityLanes;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Job {
     Start {
@@ -31,155 +39,757 @@ pub enum Job {
     },
 }
 
+/// A job together with the lane it is scheduled in.
+#[derive(Debug)]
+pub struct QueuedJob {
+    pub job: Job,
+    pub priority: JobPriority,
+    /// `job_queue` row holding the job's lease, for jobs claimed from the table.
+    pub queue_id: Option<i32>,
+    /// Jobs sharing a key collapse into one execution while the first is queued,
+    /// running, or completed within `JOB_DEDUPE_WINDOW_SECS`.
+    pub dedupe_key: Option<String>,
+}
+
+impl Job {
+    pub fn with_priority(self, priority: JobPriority) -> QueuedJob {
+        QueuedJob {
+            job: self,
+            priority,
+            queue_id: None,
+            dedupe_key: None,
+        }
+    }
+}
+
+impl QueuedJob {
+    pub fn dedupe_on(mut self, key: impl Into<String>) -> Self {
+        self.dedupe_key = Some(key.into());
+        self
+    }
+}
+
+impl From<Job> for QueuedJob {
+    fn from(job: Job) -> Self {
+        job.with_priority(JobPriority::Normal)
+    }
+}
+
 pub async fn enqueue_job(pool: &PgPool, job: &Job) {
+    enqueue_job_with_priority(pool, job, JobPriority::Normal).await;
+}
+
+pub async fn enqueue_job_with_priority(pool: &PgPool, job: &Job, priority: JobPriority) {
     if let Ok(payload) = serde_json::to_value(job) {
-        let _ = sqlx::query("INSERT INTO job_queue (payload) VALUES ($1)")
+        let _ = sqlx::query("INSERT INTO job_queue (payload, priority) VALUES ($1, $2)")
             .bind(payload)
+            .bind(priority.as_str())
             .execute(pool)
             .await;
     }
 }
 
 pub async fn enqueue_intelligence_refresh(pool: &PgPool, server_id: i32) {
-    let job = Job::IntelligenceRefresh { server_id };
-    enqueue_job(pool, &job).await;
+    let queued = Job::IntelligenceRefresh { server_id }
+        .with_priority(JobPriority::Normal)
+        .dedupe_on(format!("intelligence-refresh:{server_id}"));
+    if let Err(err) = enqueue_deduped(pool, &queued, *config::JOB_DEDUPE_WINDOW_SECS).await {
+        tracing::error!(?err, %server_id, "failed to enqueue intelligence refresh");
+    }
+}
+
+// key: job-queue-dedupe -> collapse jobs sharing a dedupe key
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnqueueOutcome {
+    pub id: i32,
+    /// True when an existing job with the same key absorbed this enqueue.
+    pub deduplicated: bool,
 }
 
-pub fn start_worker(pool: PgPool, runtime: Arc<dyn ContainerRuntime>) -> Sender<Job> {
-    let (tx, mut rx): (Sender<Job>, Receiver<Job>) = channel(32);
+/// Persists a job, honouring its dedupe key. A unique index over live and
+/// completed rows makes concurrent enqueues of one key race to a single insert;
+/// the losers get the winner's id. `window_secs` of zero dedupes in-flight jobs only.
+pub async fn enqueue_deduped(
+    pool: &PgPool,
+    queued: &QueuedJob,
+    window_secs: u64,
+) -> Result<EnqueueOutcome, sqlx::Error> {
+    let payload = serde_json::to_value(&queued.job)
+        .map_err(|err| sqlx::Error::Protocol(format!("unserializable job: {err}")))?;
+    let Some(key) = queued.dedupe_key.as_deref() else {
+        let id = sqlx::query_scalar(
+            "INSERT INTO job_queue (payload, priority) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(&payload)
+        .bind(queued.priority.as_str())
+        .fetch_one(pool)
+        .await?;
+        return Ok(EnqueueOutcome {
+            id,
+            deduplicated: false,
+        });
+    };
+    loop {
+        // completed rows outside the window no longer count as duplicates
+        sqlx::query(
+            "DELETE FROM job_queue WHERE dedupe_key = $1 AND status = 'completed' \
+             AND completed_at <= NOW() - make_interval(secs => $2)",
+        )
+        .bind(key)
+        .bind(window_secs as f64)
+        .execute(pool)
+        .await?;
+        let inserted: Option<i32> = sqlx::query_scalar(
+            r#"
+            INSERT INTO job_queue (payload, priority, dedupe_key)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (dedupe_key) WHERE status IN ('queued', 'processing', 'completed')
+            DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(&payload)
+        .bind(queued.priority.as_str())
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(id) = inserted {
+            return Ok(EnqueueOutcome {
+                id,
+                deduplicated: false,
+            });
+        }
+        let existing: Option<i32> = sqlx::query_scalar(
+            "SELECT id FROM job_queue WHERE dedupe_key = $1 \
+             AND status IN ('queued', 'processing', 'completed')",
+        )
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+        // the holder can finish and disappear between the two statements; try again
+        if let Some(id) = existing {
+            return Ok(EnqueueOutcome {
+                id,
+                deduplicated: true,
+            });
+        }
+    }
+}
+
+pub fn start_worker(pool: PgPool, runtime: Arc<dyn ContainerRuntime>) -> Sender<QueuedJob> {
+    let (tx, mut rx): (Sender<QueuedJob>, Receiver<QueuedJob>) = channel(32);
 
-    // Load queued jobs from the database on startup
+    // Reap expired leases and load queued jobs from the database
     let db_pool = pool.clone();
     let replay_tx = tx.clone();
     tokio::spawn(async move {
+        let lease = Duration::from_secs(*config::JOB_LEASE_SECS);
         loop {
-            let rows = sqlx::query(
-                "SELECT id, payload FROM job_queue WHERE status = 'queued' ORDER BY id",
-            )
-            .fetch_all(&db_pool)
-            .await
-            .unwrap_or_default();
-            for row in rows {
-                let id: i32 = row.get("id");
-                let payload: Value = row.get("payload");
-                if let Ok(job) = serde_json::from_value::<Job>(payload) {
-                    let _ = sqlx::query("UPDATE job_queue SET status = 'processing' WHERE id = $1")
-                        .bind(id)
-                        .execute(&db_pool)
-                        .await;
-                    let _ = replay_tx.send(job).await;
-                    let _ = sqlx::query("DELETE FROM job_queue WHERE id = $1")
-                        .bind(id)
-                        .execute(&db_pool)
-                        .await;
+            match reap_expired_leases(&db_pool, *config::JOB_MAX_REQUEUES).await {
+                Ok(report) if report.requeued + report.dead_lettered > 0 => {
+                    tracing::warn!(
+                        requeued = report.requeued,
+                        dead_lettered = report.dead_lettered,
+                        "reaped jobs with expired leases",
+                    );
                 }
+                Ok(_) => {}
+                Err(err) => tracing::error!(?err, "failed to reap expired job leases"),
+            }
+            if let Err(err) = purge_completed(&db_pool, *config::JOB_DEDUPE_WINDOW_SECS).await {
+                tracing::error!(?err, "failed to purge completed jobs");
+            }
+            let claimed = claim_jobs(&db_pool, lease, CLAIM_BATCH)
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::error!(?err, "failed to claim queued jobs");
+                    Vec::new()
+                });
+            for queued in claimed {
+                let _ = replay_tx.send(queued).await;
             }
             sleep(Duration::from_secs(5)).await;
         }
     });
 
     tokio::spawn(async move {
-        while let Some(job) = rx.recv().await {
-            match job {
-                Job::Start {
-                    server_id,
-                    server_type,
-                    config,
-                    api_key,
-                    use_gpu,
-                } => {
-                    match evaluate_placement_gate(&pool, server_id).await {
-                        Ok(Some(gate)) if gate.blocked => {
-                            let status = gate.blocked_status();
-                            if let Err(err) = servers::set_status(&pool, server_id, status).await {
-                                tracing::error!(
-                                    ?err,
-                                    %server_id,
-                                    status,
-                                    "failed to persist status after trust preemption",
-                                );
-                            }
-                            tracing::warn!(
-                                %server_id,
-                                stale = gate.stale,
-                                notes = %gate.notes.join(","),
-                                "preempting start job due to trust gate",
-                            );
-                            continue;
-                        }
-                        Ok(_) => {}
-                        Err(err) => {
+        let mut lanes = PriorityLanes::default();
+        loop {
+            // pull everything already waiting so lanes can reorder the backlog
+            while let Ok(queued) = rx.try_recv() {
+                lanes.push(queued.priority, queued);
+            }
+            let Some((_, queued)) = lanes.pop() else {
+                match rx.recv().await {
+                    Some(queued) => {
+                        lanes.push(queued.priority, queued);
+                        continue;
+                    }
+                    None => break,
+                }
+            };
+            dispatch(&pool, &runtime, queued).await;
+        }
+    });
+    tx
+}
+
+type JobHandler = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+/// Hands a job to its executor under supervision. Container jobs are awaited so
+/// the worker keeps their order; background refreshes run detached.
+async fn dispatch(pool: &PgPool, runtime: &Arc<dyn ContainerRuntime>, queued: QueuedJob) {
+    let QueuedJob { job, queue_id, .. } = queued;
+    let background = matches!(
+        job,
+        Job::IntelligenceRefresh { .. } | Job::EvaluationRefresh { .. }
+    );
+    let handler = job_handler(pool.clone(), runtime.clone(), job);
+    let supervised = supervise(pool.clone(), queue_id, handler, *config::JOB_MAX_FAILURES);
+    if background {
+        tokio::spawn(supervised);
+    } else {
+        supervised.await;
+    }
+}
+
+fn job_handler(pool: PgPool, runtime: Arc<dyn ContainerRuntime>, job: Job) -> JobHandler {
+    Box::pin(async move {
+        match job {
+            Job::Start {
+                server_id,
+                server_type,
+                config,
+                api_key,
+                use_gpu,
+            } => {
+                match evaluate_placement_gate(&pool, server_id).await {
+                    Ok(Some(gate)) if gate.blocked => {
+                        let status = gate.blocked_status();
+                        if let Err(err) = servers::set_status(&pool, server_id, status).await {
                             tracing::error!(
                                 ?err,
                                 %server_id,
-                                "failed to evaluate trust gate before dispatching job",
+                                status,
+                                "failed to persist status after trust preemption",
                             );
                         }
+                        tracing::warn!(
+                            %server_id,
+                            stale = gate.stale,
+                            notes = %gate.notes.join(","),
+                            "preempting start job due to trust gate",
+                        );
+                        return Ok(());
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::error!(
+                            ?err,
+                            %server_id,
+                            "failed to evaluate trust gate before dispatching job",
+                        );
                     }
-                    let rt = runtime.clone();
-                    rt.spawn_server_task(
-                        server_id,
-                        server_type,
-                        config,
-                        api_key,
-                        use_gpu,
-                        pool.clone(),
-                    );
-                }
-                Job::Stop { server_id } => {
-                    let rt = runtime.clone();
-                    rt.stop_server_task(server_id, pool.clone());
                 }
-                Job::Delete { server_id } => {
-                    let rt = runtime.clone();
-                    rt.delete_server_task(server_id, pool.clone());
+                runtime.spawn_server_task(
+                    server_id,
+                    server_type,
+                    config,
+                    api_key,
+                    use_gpu,
+                    pool.clone(),
+                );
+                Ok(())
+            }
+            Job::Stop { server_id } => {
+                runtime.stop_server_task(server_id, pool.clone());
+                Ok(())
+            }
+            Job::Delete { server_id } => {
+                runtime.delete_server_task(server_id, pool.clone());
+                Ok(())
+            }
+            Job::IntelligenceRefresh { server_id } => {
+                match intelligence::recompute_from_history(&pool, server_id).await {
+                    Ok(_) => {
+                        tracing::info!(
+                            %server_id,
+                            "intelligence recompute job completed",
+                        );
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("intelligence recompute failed: {err}")),
                 }
-                Job::IntelligenceRefresh { server_id } => {
-                    let db = pool.clone();
-                    tokio::spawn(async move {
-                        if let Err(err) = intelligence::recompute_from_history(&db, server_id).await
-                        {
-                            tracing::warn!(
-                                ?err,
-                                %server_id,
-                                "intelligence recompute job failed",
-                            );
-                        } else {
-                            tracing::info!(
-                                %server_id,
-                                "intelligence recompute job completed",
-                            );
-                        }
-                    });
+            }
+            Job::EvaluationRefresh { certification_id } => {
+                match evaluations::retry_certification(&pool, certification_id).await {
+                    Ok(Some(_)) => {
+                        tracing::info!(
+                            %certification_id,
+                            "evaluation certification marked for refresh",
+                        );
+                        Ok(())
+                    }
+                    Ok(None) => {
+                        tracing::warn!(
+                            %certification_id,
+                            "evaluation refresh job referenced missing certification",
+                        );
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("evaluation refresh failed: {err}")),
                 }
-                Job::EvaluationRefresh { certification_id } => {
-                    let db = pool.clone();
-                    tokio::spawn(async move {
-                        match evaluations::retry_certification(&db, certification_id).await {
-                            Ok(Some(_)) => {
-                                tracing::info!(
-                                    %certification_id,
-                                    "evaluation certification marked for refresh",
-                                );
-                            }
-                            Ok(None) => {
-                                tracing::warn!(
-                                    %certification_id,
-                                    "evaluation refresh job referenced missing certification",
-                                );
-                            }
-                            Err(err) => {
-                                tracing::warn!(
-                                    ?err,
-                                    %certification_id,
-                                    "evaluation refresh job failed",
-                                );
-                            }
-                        }
-                    });
+            }
+        }
+    })
+}
+
+// key: job-queue-poison -> isolate panicking handlers and dead-letter repeat failures
+
+/// Runs a handler on its own task so a panic unwinds that task, not the worker.
+pub async fn isolate<F>(handler: F) -> Result<(), String>
+where
+    F: Future<Output = Result<(), String>> + Send + 'static,
+{
+    match tokio::spawn(handler).await {
+        Ok(result) => result,
+        Err(err) if err.is_panic() => Err(format!(
+            "handler panicked: {}",
+            panic_message(err.into_panic().as_ref())
+        )),
+        Err(err) => Err(format!("handler aborted: {err}")),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".into())
+}
+
+/// Completes a claimed job when its handler succeeds; otherwise records the failure.
+pub async fn supervise<F>(pool: PgPool, queue_id: Option<i32>, handler: F, max_failures: i32)
+where
+    F: Future<Output = Result<(), String>> + Send + 'static,
+{
+    let Err(message) = isolate(handler).await else {
+        complete_claimed(&pool, queue_id).await;
+        return;
+    };
+    let Some(id) = queue_id else {
+        tracing::error!(%message, "unqueued job failed");
+        return;
+    };
+    match record_job_failure(&pool, id, &message, max_failures).await {
+        Ok(status) => tracing::error!(job_id = id, %message, status, "job failed"),
+        Err(err) => tracing::error!(?err, job_id = id, %message, "failed to record job failure"),
+    }
+}
+
+/// Counts a handler failure against the job. The job goes back to `queued` for another
+/// attempt until it reaches `max_failures`, then moves to `dead_letter`.
+pub async fn record_job_failure(
+    pool: &PgPool,
+    id: i32,
+    message: &str,
+    max_failures: i32,
+) -> Result<&'static str, sqlx::Error> {
+    let dead_lettered: bool = sqlx::query_scalar(
+        r#"
+        UPDATE job_queue
+        SET failure_count = failure_count + 1,
+            last_error = $2,
+            lease_expires_at = NULL,
+            status = CASE WHEN failure_count + 1 >= $3 THEN 'dead_letter' ELSE 'queued' END
+        WHERE id = $1
+        RETURNING status = 'dead_letter'
+        "#,
+    )
+    .bind(id)
+    .bind(message)
+    .bind(max_failures)
+    .fetch_one(pool)
+    .await?;
+    Ok(if dead_lettered {
+        "dead_letter"
+    } else {
+        "queued"
+    })
+}
+
+// key: job-queue-leases -> visibility timeouts and dead-lettering
+
+/// Upper bound on rows claimed per poll.
+const CLAIM_BATCH: i64 = 100;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReapReport {
+    pub requeued: u64,
+    pub dead_lettered: u64,
+}
+
+/// Atomically moves queued rows to `processing` under a lease ending `lease` from now.
+/// Rows whose payload no longer deserializes are dead-lettered instead.
+pub async fn claim_jobs(
+    pool: &PgPool,
+    lease: Duration,
+    limit: i64,
+) -> Result<Vec<QueuedJob>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        UPDATE job_queue
+        SET status = 'processing',
+            claimed_at = NOW(),
+            lease_expires_at = NOW() + make_interval(secs => $1)
+        WHERE id IN (
+            SELECT id FROM job_queue
+            WHERE status = 'queued'
+            ORDER BY CASE priority WHEN 'high' THEN 0 WHEN 'normal' THEN 1 ELSE 2 END, id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, payload, priority, dedupe_key
+        "#,
+    )
+    .bind(lease.as_secs_f64())
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    let mut claimed = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: i32 = row.get("id");
+        let priority = row
+            .try_get::<String, _>("priority")
+            .ok()
+            .and_then(|value| JobPriority::parse(&value))
+            .unwrap_or_default();
+        match serde_json::from_value::<Job>(row.get("payload")) {
+            Ok(job) => claimed.push(QueuedJob {
+                job,
+                priority,
+                queue_id: Some(id),
+                dedupe_key: row.get("dedupe_key"),
+            }),
+            Err(err) => {
+                sqlx::query(
+                    "UPDATE job_queue SET status = 'dead_letter', lease_expires_at = NULL, last_error = $2 WHERE id = $1",
+                )
+                .bind(id)
+                .bind(format!("invalid payload: {err}"))
+                .execute(pool)
+                .await?;
+            }
+        }
+    }
+    claimed.sort_by_key(|queued| (queued.priority.lane(), queued.queue_id));
+    Ok(claimed)
+}
+
+/// Returns jobs whose lease lapsed to the queue, or dead-letters them once they
+/// have already been requeued `max_requeues` times.
+pub async fn reap_expired_leases(
+    pool: &PgPool,
+    max_requeues: i32,
+) -> Result<ReapReport, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        UPDATE job_queue
+        SET status = CASE WHEN requeue_count >= $1 THEN 'dead_letter' ELSE 'queued' END,
+            requeue_count = CASE WHEN requeue_count >= $1 THEN requeue_count ELSE requeue_count + 1 END,
+            last_error = 'lease expired before completion',
+            lease_expires_at = NULL
+        WHERE status = 'processing' AND lease_expires_at <= NOW()
+        RETURNING status
+        "#,
+    )
+    .bind(max_requeues)
+    .fetch_all(pool)
+    .await?;
+    let mut report = ReapReport::default();
+    for row in rows {
+        if row.get::<String, _>("status") == "dead_letter" {
+            report.dead_lettered += 1;
+        } else {
+            report.requeued += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// Deletes a finished job, or keeps a deduped one as `completed` while the dedupe
+/// window is open so repeats keep collapsing onto it.
+pub async fn complete_job(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    let window_secs = *config::JOB_DEDUPE_WINDOW_SECS;
+    let retained = sqlx::query(
+        "UPDATE job_queue SET status = 'completed', completed_at = NOW(), lease_expires_at = NULL \
+         WHERE id = $1 AND dedupe_key IS NOT NULL AND $2",
+    )
+    .bind(id)
+    .bind(window_secs > 0)
+    .execute(pool)
+    .await?;
+    if retained.rows_affected() == 0 {
+        sqlx::query("DELETE FROM job_queue WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Drops completed rows whose dedupe window has closed.
+pub async fn purge_completed(pool: &PgPool, window_secs: u64) -> Result<u64, sqlx::Error> {
+    let purged = sqlx::query(
+        "DELETE FROM job_queue WHERE status = 'completed' \
+         AND completed_at <= NOW() - make_interval(secs => $1)",
+    )
+    .bind(window_secs as f64)
+    .execute(pool)
+    .await?;
+    Ok(purged.rows_affected())
+}
+
+async fn complete_claimed(pool: &PgPool, queue_id: Option<i32>) {
+    let Some(id) = queue_id else {
+        return;
+    };
+    if let Err(err) = complete_job(pool, id).await {
+        tracing::error!(?err, job_id = id, "failed to complete claimed job");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn queued_job(pool: &PgPool) -> i32 {
+        enqueue_job(pool, &Job::Stop { server_id: 1 }).await;
+        sqlx::query("SELECT id FROM job_queue ORDER BY id DESC LIMIT 1")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+            .get("id")
+    }
+
+    async fn job_state(pool: &PgPool, id: i32) -> (String, i32) {
+        let row = sqlx::query("SELECT status, requeue_count FROM job_queue WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        (row.get("status"), row.get("requeue_count"))
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn abandoned_lease_is_requeued_after_timeout(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let id = queued_job(&pool).await;
+
+        let claimed = claim_jobs(&pool, Duration::from_secs(60), 10)
+            .await
+            .unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].queue_id, Some(id));
+        // a live lease is left alone
+        assert_eq!(
+            reap_expired_leases(&pool, 3).await.unwrap(),
+            ReapReport::default()
+        );
+        assert_eq!(job_state(&pool, id).await, ("processing".into(), 0));
+
+        // the worker dies: simulate the lease running out
+        sqlx::query("UPDATE job_queue SET lease_expires_at = NOW() - INTERVAL '1 second'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = reap_expired_leases(&pool, 3).await.unwrap();
+        assert_eq!(report.requeued, 1);
+        assert_eq!(job_state(&pool, id).await, ("queued".into(), 1));
+
+        let reclaimed = claim_jobs(&pool, Duration::from_secs(60), 10)
+            .await
+            .unwrap();
+        assert_eq!(reclaimed[0].queue_id, Some(id));
+        complete_job(&pool, id).await.unwrap();
+        assert!(claim_jobs(&pool, Duration::from_secs(60), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn exceeding_max_requeues_dead_letters_job(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let id = queued_job(&pool).await;
+
+        for attempt in 1..=2 {
+            assert_eq!(
+                claim_jobs(&pool, Duration::ZERO, 10).await.unwrap().len(),
+                1
+            );
+            let report = reap_expired_leases(&pool, 2).await.unwrap();
+            assert_eq!(report.requeued, 1);
+            assert_eq!(job_state(&pool, id).await, ("queued".into(), attempt));
+        }
+
+        assert_eq!(
+            claim_jobs(&pool, Duration::ZERO, 10).await.unwrap().len(),
+            1
+        );
+        let report = reap_expired_leases(&pool, 2).await.unwrap();
+        assert_eq!(report.dead_lettered, 1);
+        assert_eq!(job_state(&pool, id).await, ("dead_letter".into(), 2));
+        assert!(claim_jobs(&pool, Duration::ZERO, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn panicking_handler_is_isolated_from_the_worker() {
+        let failure = isolate(async { panic!("poison payload") }).await;
+        assert_eq!(failure, Err("handler panicked: poison payload".to_string()));
+        let formatted = isolate(async {
+            let id = 42;
+            panic!("job {id} exploded")
+        })
+        .await;
+        assert_eq!(
+            formatted,
+            Err("handler panicked: job 42 exploded".to_string())
+        );
+        // the caller keeps going and later handlers still run
+        assert_eq!(isolate(async { Ok(()) }).await, Ok(()));
+        assert_eq!(
+            isolate(async { Err("deterministic".to_string()) }).await,
+            Err("deterministic".to_string())
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn poison_job_is_dead_lettered_after_threshold(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let poison = queued_job(&pool).await;
+        let healthy = queued_job(&pool).await;
+
+        for attempt in 1..=3 {
+            let claimed = claim_jobs(&pool, Duration::from_secs(60), 10)
+                .await
+                .unwrap();
+            for queued in claimed {
+                if queued.queue_id == Some(poison) {
+                    supervise(pool.clone(), queued.queue_id, async { panic!("boom") }, 3).await;
+                } else {
+                    supervise(pool.clone(), queued.queue_id, async { Ok(()) }, 3).await;
                 }
             }
+            let (status, failures, error): (String, i32, Option<String>) = sqlx::query_as(
+                "SELECT status, failure_count, last_error FROM job_queue WHERE id = $1",
+            )
+            .bind(poison)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(failures, attempt);
+            assert_eq!(error.as_deref(), Some("handler panicked: boom"));
+            let expected = if attempt < 3 { "queued" } else { "dead_letter" };
+            assert_eq!(status, expected);
         }
-    });
-    tx
+
+        let healthy_left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_queue WHERE id = $1")
+            .bind(healthy)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            healthy_left, 0,
+            "the healthy job completed alongside the poison one"
+        );
+        assert!(claim_jobs(&pool, Duration::from_secs(60), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn concurrent_enqueues_with_one_key_collapse(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let enqueues = (0..8).map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                let queued = Job::IntelligenceRefresh { server_id: 7 }
+                    .with_priority(JobPriority::Normal)
+                    .dedupe_on("intelligence-refresh:7");
+                enqueue_deduped(&pool, &queued, 60).await.unwrap()
+            })
+        });
+        let outcomes: Vec<EnqueueOutcome> = futures_util::future::join_all(enqueues)
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        let id = outcomes[0].id;
+        assert!(outcomes.iter().all(|outcome| outcome.id == id));
+        assert_eq!(outcomes.iter().filter(|o| !o.deduplicated).count(), 1);
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_queue")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+
+        // a completed job still absorbs repeats inside the window, but not after it
+        sqlx::query("UPDATE job_queue SET status = 'completed', completed_at = NOW()")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let queued = Job::IntelligenceRefresh { server_id: 7 }
+            .with_priority(JobPriority::Normal)
+            .dedupe_on("intelligence-refresh:7");
+        let repeat = enqueue_deduped(&pool, &queued, 60).await.unwrap();
+        assert_eq!(
+            repeat,
+            EnqueueOutcome {
+                id,
+                deduplicated: true
+            }
+        );
+        let in_flight_only = enqueue_deduped(&pool, &queued, 0).await.unwrap();
+        assert!(!in_flight_only.deduplicated);
+        assert_ne!(in_flight_only.id, id);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn distinct_dedupe_keys_enqueue_separate_jobs(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let mut ids = Vec::new();
+        for server_id in [1, 2] {
+            let queued = Job::IntelligenceRefresh { server_id }
+                .with_priority(JobPriority::Normal)
+                .dedupe_on(format!("intelligence-refresh:{server_id}"));
+            let outcome = enqueue_deduped(&pool, &queued, 60).await.unwrap();
+            assert!(!outcome.deduplicated);
+            ids.push(outcome.id);
+        }
+        assert_ne!(ids[0], ids[1]);
+        let claimed = claim_jobs(&pool, Duration::from_secs(60), 10)
+            .await
+            .unwrap();
+        let keys: Vec<Option<String>> = claimed.into_iter().map(|q| q.dedupe_key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                Some("intelligence-refresh:1".to_string()),
+                Some("intelligence-refresh:2".to_string())
+            ]
+        );
+    }
 }
@@ -13,6 +13,7 @@ use super::models::{
     ProviderTierRequirement,
 };
 use super::policy::ProviderKeyPolicySummary;
+use crate::audit::organization::{self, AuditCategory, AuditRecord, AuditScope};
 
 /// key: provider-keys-service
 /// Entry point for BYOK operations across registration, rotation, and runtime policy integration.
@@ -50,6 +51,8 @@ pub struct RegisterProviderKey {
     pub attestation_digest: Option<String>,
     pub attestation_signature: Option<String>,
     pub rotation_due_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Organization whose audit log records this key's lifecycle.
+    pub organization_id: Option<i32>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -134,7 +137,7 @@ impl ProviderKeyService {
 
         let mut tx = self.pool.begin().await?;
         sqlx::query(
-            "INSERT INTO provider_keys(id, provider_id, alias, state, rotation_due_at, attestation_digest, attestation_signature, attestation_verified_at, activated_at, retired_at, compromised_at, version, created_at, updated_at) VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14)"
+            "INSERT INTO provider_keys(id, provider_id, alias, state, rotation_due_at, attestation_digest, attestation_signature, attestation_verified_at, activated_at, retired_at, compromised_at, version, created_at, updated_at, organization_id) VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15)"
         )
         .bind(record.id)
         .bind(record.provider_id)
@@ -156,6 +159,7 @@ impl ProviderKeyService {
         .bind(record.version)
         .bind(record.created_at)
         .bind(record.updated_at)
+        .bind(request.organization_id)
         .execute(&mut *tx)
         .await?;
 
@@ -173,6 +177,17 @@ impl ProviderKeyService {
 
         tx.commit().await?;
 
+        organization::record(
+            &self.pool,
+            AuditRecord::new(
+                AuditScope::ProviderKey(record.id),
+                AuditCategory::Keys,
+                "key_registered",
+            )
+            .target(format!("provider_key:{}", record.id))
+            .details(json!({ "provider_id": provider_id, "alias": record.alias })),
+        );
+
         Ok(record)
     }
 
@@ -376,6 +391,18 @@ impl ProviderKeyService {
 
         tx.commit().await?;
 
+        let mut entry = AuditRecord::new(
+            AuditScope::ProviderKey(key.id),
+            AuditCategory::Keys,
+            "rotation_requested",
+        )
+        .target(format!("provider_key:{}", key.id))
+        .details(json!({ "provider_id": provider_id, "rotation_id": rotation.id }));
+        if let Some(actor) = &rotation.request_actor_ref {
+            entry = entry.actor(actor.clone());
+        }
+        organization::record(&self.pool, entry);
+
         Ok(rotation)
     }
 
@@ -538,6 +565,17 @@ impl ProviderKeyService {
 
         tx.commit().await?;
 
+        organization::record(
+            &self.pool,
+            AuditRecord::new(
+                AuditScope::ProviderKey(key.id),
+                AuditCategory::Keys,
+                "key_revoked",
+            )
+            .target(format!("provider_key:{}", key.id))
+            .details(completed.payload.clone()),
+        );
+
         self.dispatch_notification(&initiated).await?;
         self.dispatch_notification(&completed).await?;
 
@@ -922,6 +960,7 @@ mod tests {
                     attestation_digest: Some(attestation.clone()),
                     attestation_signature: Some(attestation),
                     rotation_due_at: None,
+                    organization_id: None,
                 },
             )
             .await?;
@@ -979,6 +1018,7 @@ mod tests {
                     attestation_digest: Some(attestation.clone()),
                     attestation_signature: Some(attestation.clone()),
                     rotation_due_at: None,
+                    organization_id: None,
                 },
             )
             .await?;
@@ -61,6 +61,7 @@ async fn register_key(
         attestation_digest: payload.attestation_digest,
         attestation_signature: payload.attestation_signature,
         rotation_due_at,
+        organization_id: payload.organization_id,
     };
 
     let record = service
@@ -228,6 +229,7 @@ pub struct RegisterKeyRequest {
     pub attestation_digest: Option<String>,
     pub attestation_signature: Option<String>,
     pub rotation_due_at: Option<String>,
+    pub organization_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
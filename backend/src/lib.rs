@@ -1,14 +1,18 @@
 pub mod audit;
 pub mod billing;
+pub mod cors;
 pub mod db;
 pub mod error;
 pub mod intelligence;
 pub mod keys;
 pub mod keys_api;
 pub mod lifecycle_console;
+pub mod metrics_access;
 pub mod policy;
+pub mod probes;
 pub mod remediation;
 pub mod remediation_api;
+pub mod request_limits;
 pub mod runtime;
 pub mod telemetry;
 pub mod trust;
@@ -20,11 +24,12 @@ mod capabilities;
 pub mod config;
 
 pub use config::{
-    libvirt_provisioning_config_from_env, VmProvisionerDriver, LIBVIRT_PROVISIONING_CONFIG,
-    VM_LOG_TAIL_LINES, VM_PROVISIONER_DRIVER,
+    db_pool_config_from_env, libvirt_provisioning_config_from_env, VmProvisionerDriver,
+    DB_POOL_CONFIG, LIBVIRT_PROVISIONING_CONFIG, VM_LOG_TAIL_LINES, VM_PROVISIONER_DRIVER,
 };
 
-pub use job_queue::Job;
+pub use job_queue::{Job, JobPriority, QueuedJob};
+pub use servers::{delete_server, spawn_deleted_server_sweep, sweep_deleted_servers};
 
 mod docker;
 mod domains;
@@ -38,13 +43,13 @@ mod invocations;
 pub mod job_queue;
 mod marketplace;
 pub mod organizations;
-mod promotions;
+pub mod promotions;
 pub mod proxy;
 pub mod routes;
-mod secrets;
+pub mod secrets;
 mod servers;
 mod services;
-mod vault;
+pub mod vault;
 pub mod vector_dbs;
-mod webhooks;
+pub mod webhooks;
 mod workflows;
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::config;
+
+// key: lifecycle-console -> override-actor email cache
+
+/// Most actors one stream remembers; the least recently fetched are dropped first.
+const MAX_CACHED_ACTORS: usize = 1_024;
+
+#[derive(Debug, Clone)]
+struct CachedEmail {
+    /// `None` records that the user no longer exists, so it is not looked up on every poll.
+    email: Option<String>,
+    fetched_at: Instant,
+}
+
+/// Remembers override actor emails across the polls of one SSE stream. Entries expire after
+/// `ttl`, so an email change shows up on a later poll.
+#[derive(Debug, Clone)]
+pub struct ActorEmailCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<i32, CachedEmail>,
+}
+
+impl ActorEmailCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// A cache using `LIFECYCLE_CONSOLE_ACTOR_CACHE_TTL_SECONDS`.
+    pub fn configured() -> Self {
+        Self::new(
+            Duration::from_secs(*config::LIFECYCLE_CONSOLE_ACTOR_CACHE_TTL_SECONDS),
+            MAX_CACHED_ACTORS,
+        )
+    }
+
+    /// Emails for `actor_ids`. Only ids that are missing or expired at `now` are passed to
+    /// `load`, and `load` is not called when every id is fresh.
+    pub async fn resolve<F, Fut, E>(
+        &mut self,
+        actor_ids: &HashSet<i32>,
+        now: Instant,
+        load: F,
+    ) -> Result<HashMap<i32, String>, E>
+    where
+        F: FnOnce(Vec<i32>) -> Fut,
+        Fut: Future<Output = Result<HashMap<i32, String>, E>>,
+    {
+        let mut stale: Vec<i32> = actor_ids
+            .iter()
+            .copied()
+            .filter(|id| match self.entries.get(id) {
+                Some(entry) => now.saturating_duration_since(entry.fetched_at) >= self.ttl,
+                None => true,
+            })
+            .collect();
+
+        if !stale.is_empty() {
+            stale.sort_unstable();
+            let mut loaded = load(stale.clone()).await?;
+            for id in stale {
+                self.entries.insert(
+                    id,
+                    CachedEmail {
+                        email: loaded.remove(&id),
+                        fetched_at: now,
+                    },
+                );
+            }
+        }
+
+        let resolved = actor_ids
+            .iter()
+            .filter_map(|id| {
+                let email = self.entries.get(id)?.email.clone()?;
+                Some((*id, email))
+            })
+            .collect();
+        self.evict();
+        Ok(resolved)
+    }
+
+    fn evict(&mut self) {
+        if self.entries.len() <= self.capacity {
+            return;
+        }
+        let mut by_age: Vec<(Instant, i32)> = self
+            .entries
+            .iter()
+            .map(|(id, entry)| (entry.fetched_at, *id))
+            .collect();
+        by_age.sort_unstable();
+        let excess = self.entries.len() - self.capacity;
+        for (_, id) in by_age.into_iter().take(excess) {
+            self.entries.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn emails(ids: &[i32]) -> HashMap<i32, String> {
+        ids.iter()
+            .map(|id| (*id, format!("actor-{id}@example.com")))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn repeated_polls_query_each_actor_once_until_expiry() {
+        let mut cache = ActorEmailCache::new(Duration::from_secs(60), 16);
+        let queries = Cell::new(0);
+        let actors = HashSet::from([7, 9]);
+        let start = Instant::now();
+
+        for poll in 0..5 {
+            let resolved = cache
+                .resolve(&actors, start + Duration::from_secs(poll), |ids| {
+                    queries.set(queries.get() + 1);
+                    assert_eq!(ids, vec![7, 9]);
+                    async move { Ok::<_, ()>(emails(&ids)) }
+                })
+                .await
+                .unwrap();
+            assert_eq!(resolved, emails(&[7, 9]));
+        }
+        assert_eq!(queries.get(), 1);
+
+        // an expired entry is fetched again and picks up the new email
+        let resolved = cache
+            .resolve(&actors, start + Duration::from_secs(60), |ids| {
+                queries.set(queries.get() + 1);
+                async move {
+                    Ok::<_, ()>(
+                        ids.into_iter()
+                            .map(|id| (id, format!("renamed-{id}@example.com")))
+                            .collect(),
+                    )
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(queries.get(), 2);
+        assert_eq!(resolved[&7], "renamed-7@example.com");
+    }
+
+    #[tokio::test]
+    async fn unknown_actors_are_remembered_and_the_oldest_entries_evicted() {
+        let mut cache = ActorEmailCache::new(Duration::from_secs(60), 2);
+        let queries = Cell::new(0);
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            let resolved = cache
+                .resolve(&HashSet::from([404]), start, |_| {
+                    queries.set(queries.get() + 1);
+                    async { Ok::<_, ()>(HashMap::new()) }
+                })
+                .await
+                .unwrap();
+            assert!(resolved.is_empty());
+        }
+        assert_eq!(queries.get(), 1);
+
+        let later = start + Duration::from_secs(1);
+        cache
+            .resolve(&HashSet::from([1, 2]), later, |ids| async move {
+                Ok::<_, ()>(emails(&ids))
+            })
+            .await
+            .unwrap();
+        assert_eq!(cache.entries.len(), 2);
+        assert!(!cache.entries.contains_key(&404));
+    }
+}
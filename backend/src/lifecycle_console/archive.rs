@@ -0,0 +1,94 @@
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use serde_json::to_value;
+use sqlx::PgPool;
+
+use super::fetch_workspace_snapshot;
+use super::redaction::RedactionPolicy;
+use crate::db::runtime_vm_remediation_workspaces::{
+    archive_workspace as archive_workspace_record, get_workspace_archive,
+    restore_archived_workspace, ArchiveWorkspace, RuntimeVmRemediationWorkspace,
+    RuntimeVmRemediationWorkspaceArchive,
+};
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use crate::organizations::OrgRole;
+use crate::remediation_api::ensure_workspace_role;
+
+// key: lifecycle-console -> workspace archival (cold storage)
+
+pub const ARCHIVED_LIFECYCLE_STATE: &str = "archived";
+
+/// Lifecycle states a workspace must have reached before it can be archived.
+const TERMINAL_LIFECYCLE_STATES: &[&str] = &["promoted"];
+
+/// Freezes a terminal workspace's console snapshot into the archive and hides the workspace
+/// from the default console listing.
+pub async fn archive_workspace(
+    Extension(pool): Extension<PgPool>,
+    user: AuthUser,
+    Path(workspace_id): Path<i64>,
+) -> AppResult<Json<RuntimeVmRemediationWorkspaceArchive>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
+    let snapshot = fetch_workspace_snapshot(&pool, workspace_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let state = snapshot.workspace.lifecycle_state.as_str();
+    if state == ARCHIVED_LIFECYCLE_STATE {
+        return Err(AppError::Conflict("workspace is already archived".into()));
+    }
+    if !TERMINAL_LIFECYCLE_STATES.contains(&state) {
+        return Err(AppError::Conflict(format!(
+            "only completed workspaces can be archived; workspace is `{state}`"
+        )));
+    }
+
+    let expected_workspace_version = snapshot.workspace.version;
+    let snapshot = to_value(&snapshot).map_err(|err| {
+        AppError::Message(format!("failed to serialize workspace snapshot: {err}"))
+    })?;
+    let archive = archive_workspace_record(
+        &pool,
+        ArchiveWorkspace {
+            workspace_id,
+            archived_by: user.user_id,
+            snapshot: &snapshot,
+            expected_workspace_version,
+        },
+    )
+    .await?
+    .ok_or_else(|| AppError::Conflict("workspace changed while archiving; retry".into()))?;
+    Ok(Json(archive))
+}
+
+/// Returns the snapshot frozen when the workspace was archived.
+pub async fn get_archived_workspace(
+    Extension(pool): Extension<PgPool>,
+    user: AuthUser,
+    Path(workspace_id): Path<i64>,
+) -> AppResult<Json<RuntimeVmRemediationWorkspaceArchive>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Viewer).await?;
+    let mut archive = get_workspace_archive(&pool, workspace_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if let Some(policy) = RedactionPolicy::configured().for_viewer(Some(&user)) {
+        policy.redact_stored_snapshot(&mut archive.snapshot);
+    }
+    Ok(Json(archive))
+}
+
+/// Takes a workspace out of the archive and returns it with its pre-archival lifecycle state.
+pub async fn restore_workspace(
+    Extension(pool): Extension<PgPool>,
+    user: AuthUser,
+    Path(workspace_id): Path<i64>,
+) -> AppResult<Json<RuntimeVmRemediationWorkspace>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
+    let workspace = restore_archived_workspace(&pool, workspace_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(workspace))
+}
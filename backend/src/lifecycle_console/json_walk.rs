@@ -0,0 +1,49 @@
+use crate::config;
+
+// key: lifecycle-console -> depth bound for metadata walkers
+
+/// Depth budget for one walk over a JSON document. The root sits at depth `0`; a walker asks
+/// before descending into the children of a value and skips them once the limit is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthLimit {
+    max_depth: usize,
+    reached: bool,
+}
+
+impl DepthLimit {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            reached: false,
+        }
+    }
+
+    /// The limit set by `LIFECYCLE_CONSOLE_JSON_MAX_DEPTH`.
+    pub fn configured() -> Self {
+        Self::new(*config::LIFECYCLE_CONSOLE_JSON_MAX_DEPTH)
+    }
+
+    /// Whether values at `depth` may be visited.
+    pub fn allows(&mut self, depth: usize) -> bool {
+        if depth > self.max_depth {
+            self.reached = true;
+            return false;
+        }
+        true
+    }
+
+    pub fn reached(&self) -> bool {
+        self.reached
+    }
+
+    /// Logs once per walk when anything was skipped.
+    pub fn finish(self, walker: &'static str) {
+        if self.reached {
+            tracing::warn!(
+                walker,
+                max_depth = self.max_depth,
+                "JSON walker reached its depth limit; deeper values were skipped"
+            );
+        }
+    }
+}
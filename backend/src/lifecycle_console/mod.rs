@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::Infallible;
 
 use axum::{
@@ -7,16 +7,20 @@ use axum::{
     response::sse::{Event, KeepAlive, Sse},
     Json,
 };
+use base64::engine::general_purpose::STANDARD as Base64Engine;
+use base64::Engine;
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::{to_value, Value};
 use sqlx::{query_as, PgPool, QueryBuilder};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::time::Duration;
 use tokio_stream::wrappers::ReceiverStream;
 
 use sha2::{Digest, Sha256};
 
+use crate::config;
 use crate::db::runtime_vm_remediation_runs::RuntimeVmRemediationRun;
 use crate::db::runtime_vm_remediation_workspaces::{
     RuntimeVmRemediationWorkspace, RuntimeVmRemediationWorkspaceRevision,
@@ -24,7 +28,26 @@ use crate::db::runtime_vm_remediation_workspaces::{
 };
 use crate::db::runtime_vm_trust_registry::RuntimeVmTrustRegistryState;
 use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
 use crate::keys::models::ProviderKeyDecisionPosture;
+use crate::runtime::ResourceUsage;
+
+pub mod actor_cache;
+pub mod archive;
+pub mod json_walk;
+pub mod msgpack;
+pub mod redaction;
+pub mod retry_ledger;
+pub mod verdict_selection;
+pub mod veto_reasons;
+
+use actor_cache::ActorEmailCache;
+use archive::ARCHIVED_LIFECYCLE_STATE;
+use json_walk::DepthLimit;
+use redaction::RedactionPolicy;
+use retry_ledger::{parse_retry_ledger, RetryLedgerMode, RETRY_LEDGER_MALFORMED_TOTAL};
+use verdict_selection::VerdictStrategy;
+use veto_reasons::VetoReasonNormalizer;
 
 // key: lifecycle-console -> aggregation,data-plane
 
@@ -48,6 +71,13 @@ pub struct LifecycleConsoleQuery {
     pub severity: Option<String>,
     #[serde(default)]
     pub run_limit: Option<u32>,
+    /// Only runs that started (or, for promotion runs, changed) after this instant count
+    /// toward `run_limit`; lets pollers skip runs they have already seen.
+    #[serde(default)]
+    pub runs_since: Option<DateTime<Utc>>,
+    /// Also list workspaces moved to the archive; they are skipped by default.
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 impl Default for LifecycleConsoleQuery {
@@ -62,10 +92,46 @@ impl Default for LifecycleConsoleQuery {
             promotion_lane: None,
             severity: None,
             run_limit: None,
+            runs_since: None,
+            include_archived: false,
+        }
+    }
+}
+
+/// Ceilings applied to the `limit` and `run_limit` query parameters. Requests may ask
+/// for less but never more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifecycleConsoleLimits {
+    pub max_limit: u32,
+    pub max_run_limit: u32,
+}
+
+impl Default for LifecycleConsoleLimits {
+    fn default() -> Self {
+        Self {
+            max_limit: 100,
+            max_run_limit: 10,
         }
     }
 }
 
+impl LifecycleConsoleLimits {
+    pub fn from_config() -> Self {
+        let settings = config::reload::current();
+        Self {
+            max_limit: settings.lifecycle_console_max_limit,
+            max_run_limit: settings.lifecycle_console_max_run_limit,
+        }
+    }
+
+    /// Returns the page size and per-workspace run count for `query`.
+    fn resolve(&self, query: &LifecycleConsoleQuery) -> (i64, usize) {
+        let limit = query.limit.unwrap_or(25).min(self.max_limit) as i64;
+        let run_limit = query.run_limit.unwrap_or(5).min(self.max_run_limit) as usize;
+        (limit, run_limit)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LifecycleConsolePage {
     pub workspaces: Vec<LifecycleWorkspaceSnapshot>,
@@ -102,6 +168,56 @@ pub struct LifecycleStreamQuery {
     pub query: LifecycleConsoleQuery,
     #[serde(default)]
     pub heartbeat_ms: Option<u64>,
+    #[serde(default)]
+    pub encoding: LifecycleStreamEncoding,
+}
+
+/// How each stream event's `data` field is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleStreamEncoding {
+    /// The envelope as JSON.
+    #[default]
+    Json,
+    /// A [`LifecycleEncodedEnvelope`] wrapping the envelope as base64 standard MessagePack.
+    Msgpack,
+}
+
+/// `data` of a `msgpack` stream event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEncodedEnvelope {
+    /// Always `msgpack`.
+    pub encoding: String,
+    /// Base64 of the envelope encoded by [`msgpack::encode`].
+    pub payload: String,
+}
+
+impl LifecycleStreamEncoding {
+    pub fn encode_data(
+        self,
+        envelope: &LifecycleConsoleEventEnvelope,
+    ) -> Result<String, serde_json::Error> {
+        match self {
+            Self::Json => serde_json::to_string(envelope),
+            Self::Msgpack => {
+                let bytes = msgpack::encode(&to_value(envelope)?);
+                serde_json::to_string(&LifecycleEncodedEnvelope {
+                    encoding: "msgpack".to_string(),
+                    payload: Base64Engine.encode(bytes),
+                })
+            }
+        }
+    }
+
+    fn event(
+        self,
+        name: &'static str,
+        envelope: &LifecycleConsoleEventEnvelope,
+    ) -> Result<Event, serde_json::Error> {
+        Ok(Event::default()
+            .event(name)
+            .data(self.encode_data(envelope)?))
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -159,6 +275,8 @@ pub struct LifecycleRunSnapshot {
     pub artifact_fingerprints: Vec<LifecycleRunArtifactFingerprint>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub promotion_verdict: Option<LifecycleRunPromotionVerdictRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_usage: Option<ResourceUsage>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -198,8 +316,12 @@ pub struct LifecyclePromotionPosture {
     pub track_name: String,
     pub track_tier: String,
     pub allowed: bool,
+    /// Canonical veto codes (see `PROMOTION_VETO_REASON_ALIASES`).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub veto_reasons: Vec<String>,
+    /// The reasons exactly as the verdict reported them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_veto_reasons: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub notes: Vec<String>,
     pub updated_at: DateTime<Utc>,
@@ -228,6 +350,9 @@ pub struct LifecycleRunPromotionVerdictRef {
     pub track_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub track_tier: Option<String>,
+    /// How the posture was chosen when the run did not record a verdict.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_by: Option<VerdictStrategy>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -281,8 +406,12 @@ pub struct LifecyclePromotionPostureDelta {
     pub track_name: String,
     pub track_tier: String,
     pub allowed: bool,
+    /// Canonical veto codes (see `PROMOTION_VETO_REASON_ALIASES`).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub veto_reasons: Vec<String>,
+    /// The reasons exactly as the verdict reported them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_veto_reasons: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub notes: Vec<String>,
     pub updated_at: DateTime<Utc>,
@@ -428,26 +557,65 @@ struct TrustRegistryRow {
 
 pub async fn list_snapshots(
     Extension(pool): Extension<PgPool>,
+    viewer: Option<AuthUser>,
     Query(query): Query<LifecycleConsoleQuery>,
 ) -> AppResult<Json<LifecycleConsolePage>> {
-    let page = fetch_page(&pool, &query).await?;
+    let redaction = RedactionPolicy::configured().for_viewer(viewer.as_ref());
+    let page = fetch_page(&pool, &query, redaction).await?;
     Ok(Json(page))
 }
 
+/// Parses a reconnecting client's `Last-Event-ID`. Snapshot events carry workspace ids, so a
+/// usable cursor is a non-negative integer; a cursor past the newest workspace is still valid
+/// and simply waits for new workspaces.
+pub fn parse_last_event_id(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    match text.parse::<i64>() {
+        Ok(cursor) if cursor >= 0 => Ok(cursor),
+        Ok(cursor) => Err(format!("Last-Event-ID {cursor} is negative")),
+        Err(_) => Err(format!("Last-Event-ID `{text}` is not a workspace cursor")),
+    }
+}
+
+/// Bumped whenever a promotion's posture changes outside the stream's own polling.
+static POSTURE_CHANGES: Lazy<watch::Sender<u64>> = Lazy::new(|| watch::channel(0).0);
+
+/// Makes every open lifecycle stream poll now instead of at its next tick, so a posture
+/// change written by an API call reaches consoles without waiting out the heartbeat.
+pub fn notify_posture_changed() {
+    POSTURE_CHANGES.send_modify(|generation| *generation = generation.wrapping_add(1));
+}
+
+/// A receiver that sees every later `notify_posture_changed` call.
+pub fn subscribe_posture_changes() -> watch::Receiver<u64> {
+    POSTURE_CHANGES.subscribe()
+}
+
 // key: lifecycle-console -> sse,streaming
 pub async fn stream_snapshots(
     Extension(pool): Extension<PgPool>,
+    viewer: Option<AuthUser>,
     Query(params): Query<LifecycleStreamQuery>,
     headers: HeaderMap,
 ) -> AppResult<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>> {
+    // deltas are computed from the masked pages, so a redacted value cannot leak as a change
+    let redaction = RedactionPolicy::configured().for_viewer(viewer.as_ref());
     let poll_ms = params.heartbeat_ms.unwrap_or(5_000).clamp(1_000, 60_000);
     let poll_interval = Duration::from_millis(poll_ms);
+    let encoding = params.encoding;
 
     let mut query = params.query;
+    let mut rejected_cursor = None;
     if let Some(value) = headers.get("last-event-id") {
-        if let Ok(text) = value.to_str() {
-            if let Ok(cursor) = text.parse::<i64>() {
-                query.cursor = Some(cursor);
+        match value
+            .to_str()
+            .map_err(|_| "Last-Event-ID is not valid text".to_string())
+            .and_then(parse_last_event_id)
+        {
+            Ok(cursor) => query.cursor = Some(cursor),
+            Err(reason) => {
+                query.cursor = None;
+                rejected_cursor = Some(reason);
             }
         }
     }
@@ -455,21 +623,50 @@ pub async fn stream_snapshots(
     let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(16);
     let pool_clone = pool.clone();
     tokio::spawn(async move {
+        if let Some(reason) = rejected_cursor {
+            let envelope = LifecycleConsoleEventEnvelope {
+                event_type: LifecycleConsoleEventType::Error,
+                emitted_at: Utc::now(),
+                cursor: None,
+                page: None,
+                error: Some(format!(
+                    "{reason}; cursor ignored, streaming from the start"
+                )),
+                delta: None,
+            };
+            match encoding.event("lifecycle-error", &envelope) {
+                Ok(event) => {
+                    if tx.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(?err, "failed to encode lifecycle cursor error");
+                }
+            }
+        }
+
         let mut cursor = query.cursor;
+        let mut actor_cache = ActorEmailCache::configured();
         let mut interval = tokio::time::interval(poll_interval);
+        let mut posture_changes = subscribe_posture_changes();
         let mut initial = true;
         let mut last_snapshots: HashMap<i64, LifecycleWorkspaceSnapshot> = HashMap::new();
         loop {
             if initial {
                 initial = false;
             } else {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    // the sender is static, so this never reports a closed channel
+                    _ = posture_changes.changed() => {}
+                }
             }
 
             let mut request = query.clone();
             request.cursor = cursor;
 
-            match fetch_page(&pool_clone, &request).await {
+            match fetch_page_cached(&pool_clone, &request, redaction, &mut actor_cache).await {
                 Ok(page) => {
                     if page.workspaces.is_empty() {
                         let envelope = LifecycleConsoleEventEnvelope {
@@ -480,10 +677,7 @@ pub async fn stream_snapshots(
                             error: None,
                             delta: None,
                         };
-                        match Event::default()
-                            .event("lifecycle-heartbeat")
-                            .json_data(&envelope)
-                        {
+                        match encoding.event("lifecycle-heartbeat", &envelope) {
                             Ok(event) => {
                                 if tx.send(Ok(event)).await.is_err() {
                                     break;
@@ -514,10 +708,7 @@ pub async fn stream_snapshots(
                         delta,
                     };
 
-                    match Event::default()
-                        .event("lifecycle-snapshot")
-                        .json_data(&envelope)
-                    {
+                    match encoding.event("lifecycle-snapshot", &envelope) {
                         Ok(mut event) => {
                             if let Some(id) = event_cursor {
                                 event = event.id(id.to_string());
@@ -541,10 +732,7 @@ pub async fn stream_snapshots(
                         error: Some(err.to_string()),
                         delta: None,
                     };
-                    match Event::default()
-                        .event("lifecycle-error")
-                        .json_data(&envelope)
-                    {
+                    match encoding.event("lifecycle-error", &envelope) {
                         Ok(event) => {
                             if tx.send(Ok(event)).await.is_err() {
                                 break;
@@ -567,15 +755,236 @@ pub async fn stream_snapshots(
     ))
 }
 
+// key: lifecycle-console -> override-audit
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OverrideExportQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub cursor: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManualOverrideRecord {
+    pub run_id: i64,
+    pub runtime_vm_instance_id: i64,
+    pub playbook: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_revision_id: Option<i64>,
+    /// When the override decision was recorded: the approval decision time, falling back
+    /// to the run's last update.
+    pub overridden_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub manual_override: LifecycleRunOverride,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManualOverrideExportPage {
+    pub overrides: Vec<ManualOverrideRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<i64>,
+}
+
+/// Exports every manually overridden run in the `from`..`to` window for compliance
+/// review, oldest run first. Pass `next_cursor` back as `cursor` for the next page.
+pub async fn export_manual_overrides(
+    Extension(pool): Extension<PgPool>,
+    _user: AuthUser,
+    Query(query): Query<OverrideExportQuery>,
+) -> AppResult<Json<ManualOverrideExportPage>> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::BadRequest("`from` must not be after `to`".into()));
+        }
+    }
+    let page = fetch_manual_overrides(&pool, &query).await?;
+    Ok(Json(page))
+}
+
+pub async fn fetch_manual_overrides(
+    pool: &PgPool,
+    query: &OverrideExportQuery,
+) -> Result<ManualOverrideExportPage, AppError> {
+    let max_limit = LifecycleConsoleLimits::from_config().max_limit;
+    let limit = query.limit.unwrap_or(max_limit).clamp(1, max_limit) as i64;
+
+    // The SQL predicate mirrors `compute_run_override_reason` closely enough to page in
+    // the database; `build_manual_override` has the final say on each candidate.
+    let candidates: Vec<RuntimeVmRemediationRun> = query_as(
+        r#"
+        SELECT
+            id,
+            runtime_vm_instance_id,
+            playbook,
+            playbook_id,
+            status,
+            automation_payload,
+            approval_required,
+            started_at,
+            completed_at,
+            last_error,
+            assigned_owner_id,
+            sla_deadline,
+            approval_state,
+            approval_decided_at,
+            approval_notes,
+            metadata,
+            workspace_id,
+            workspace_revision_id,
+            promotion_gate_context,
+            version,
+            updated_at,
+            cancelled_at,
+            cancellation_reason,
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
+        FROM runtime_vm_remediation_runs
+        WHERE ($1::timestamptz IS NULL OR COALESCE(approval_decided_at, updated_at) >= $1)
+          AND ($2::timestamptz IS NULL OR COALESCE(approval_decided_at, updated_at) < $2)
+          AND ($3::bigint IS NULL OR id > $3)
+          AND (
+              COALESCE(approval_notes, '') <> ''
+              OR metadata::text ~ '"(override_reason|manual_override|override)": "[^"]'
+              OR promotion_gate_context::text ~ '"override_reason": "[^"]'
+          )
+        ORDER BY id
+        LIMIT $4
+        "#,
+    )
+    .bind(query.from)
+    .bind(query.to)
+    .bind(query.cursor)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    // a short page means the window is exhausted, even if some candidates were dropped
+    let next_cursor = if candidates.len() as i64 == limit {
+        candidates.last().map(|run| run.id)
+    } else {
+        None
+    };
+
+    let actor_ids: HashSet<i32> = candidates
+        .iter()
+        .filter_map(|run| run.analytics_override_actor_id)
+        .collect();
+    let actors = load_override_actors(pool, &actor_ids, None).await?;
+
+    let workspace_ids: Vec<i64> = candidates
+        .iter()
+        .filter_map(|run| run.workspace_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let workspace_keys: HashMap<i64, String> = if workspace_ids.is_empty() {
+        HashMap::new()
+    } else {
+        query_as::<_, (i64, String)>(
+            "SELECT id, workspace_key FROM runtime_vm_remediation_workspaces WHERE id = ANY($1)",
+        )
+        .bind(&workspace_ids)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect()
+    };
+
+    let overrides = candidates
+        .into_iter()
+        .filter_map(|run| {
+            let reason = compute_run_override_reason(&run);
+            let manual_override = build_manual_override(&run, reason, &actors)?;
+            Some(ManualOverrideRecord {
+                run_id: run.id,
+                runtime_vm_instance_id: run.runtime_vm_instance_id,
+                workspace_key: run
+                    .workspace_id
+                    .and_then(|id| workspace_keys.get(&id).cloned()),
+                workspace_id: run.workspace_id,
+                workspace_revision_id: run.workspace_revision_id,
+                overridden_at: run.approval_decided_at.unwrap_or(run.updated_at),
+                playbook: run.playbook,
+                status: run.status,
+                manual_override,
+            })
+        })
+        .collect();
+
+    Ok(ManualOverrideExportPage {
+        overrides,
+        next_cursor,
+    })
+}
+
+/// Loads one page of workspace snapshots, masking values with `redaction` when given.
 pub async fn fetch_page(
     pool: &PgPool,
     query: &LifecycleConsoleQuery,
+    redaction: Option<&RedactionPolicy>,
+) -> Result<LifecycleConsolePage, AppError> {
+    fetch_page_with(pool, query, redaction, None).await
+}
+
+/// Like [`fetch_page`], but reuses override actor emails remembered by `actor_cache`, so a
+/// polling stream does not look the same users up on every poll.
+pub async fn fetch_page_cached(
+    pool: &PgPool,
+    query: &LifecycleConsoleQuery,
+    redaction: Option<&RedactionPolicy>,
+    actor_cache: &mut ActorEmailCache,
+) -> Result<LifecycleConsolePage, AppError> {
+    fetch_page_with(pool, query, redaction, Some(actor_cache)).await
+}
+
+/// Loads the snapshot of a single workspace, archived or not, with as many recent runs as the
+/// configured ceiling allows. No redaction is applied.
+pub async fn fetch_workspace_snapshot(
+    pool: &PgPool,
+    workspace_id: i64,
+) -> Result<Option<LifecycleWorkspaceSnapshot>, AppError> {
+    let query = LifecycleConsoleQuery {
+        cursor: Some(workspace_id - 1),
+        limit: Some(1),
+        run_limit: Some(LifecycleConsoleLimits::from_config().max_run_limit),
+        include_archived: true,
+        ..LifecycleConsoleQuery::default()
+    };
+    let page = fetch_page_with(pool, &query, None, None).await?;
+    Ok(page
+        .workspaces
+        .into_iter()
+        .find(|snapshot| snapshot.workspace.id == workspace_id))
+}
+
+async fn fetch_page_with(
+    pool: &PgPool,
+    query: &LifecycleConsoleQuery,
+    redaction: Option<&RedactionPolicy>,
+    actor_cache: Option<&mut ActorEmailCache>,
 ) -> Result<LifecycleConsolePage, AppError> {
-    let limit = query.limit.unwrap_or(25).min(100) as i64;
-    let run_limit = query.run_limit.unwrap_or(5).min(10) as usize;
+    let (limit, run_limit) = LifecycleConsoleLimits::from_config().resolve(query);
 
     let mut builder = QueryBuilder::new(
-        "SELECT id, workspace_key, display_name, description, owner_id, lifecycle_state, \
+        "SELECT id, workspace_key, display_name, description, owner_id, organization_id, lifecycle_state, \
              active_revision_id, metadata, lineage_tags, created_at, updated_at, version \
          FROM runtime_vm_remediation_workspaces",
     );
@@ -640,6 +1049,16 @@ pub async fn fetch_page(
         has_where = true;
     }
 
+    if !query.include_archived {
+        builder.push(if has_where {
+            " AND lifecycle_state <> "
+        } else {
+            " WHERE lifecycle_state <> "
+        });
+        builder.push_bind(ARCHIVED_LIFECYCLE_STATE);
+        has_where = true;
+    }
+
     if let Some(cursor) = query.cursor {
         builder.push(if has_where {
             " AND id > "
@@ -672,8 +1091,9 @@ pub async fn fetch_page(
 
     let revisions = load_revisions(pool, &revision_ids).await?;
     let gate_snapshots = load_gate_snapshots(pool, &revision_ids).await?;
-    let runs = load_runs(pool, &workspace_ids, run_limit).await?;
-    let promotion_runs = load_promotion_runs(pool, &workspace_ids, run_limit).await?;
+    let runs = load_runs(pool, &workspace_ids, run_limit, query.runs_since).await?;
+    let promotion_runs =
+        load_promotion_runs(pool, &workspace_ids, run_limit, query.runs_since).await?;
 
     let mut instance_ids = HashSet::new();
     let mut override_actor_ids = HashSet::new();
@@ -697,10 +1117,10 @@ pub async fn fetch_page(
     let intelligence_scores = load_intelligence_scores(pool, &server_ids).await?;
     let marketplace = load_marketplace(pool, &server_ids).await?;
     let provider_key_postures = load_provider_key_postures(pool, &server_ids).await?;
-    let override_actors = load_override_actors(pool, &override_actor_ids).await?;
+    let override_actors = load_override_actors(pool, &override_actor_ids, actor_cache).await?;
 
     let mut snapshots = Vec::with_capacity(workspaces.len());
-    let mut workspace_manifest_index: HashMap<i64, HashSet<String>> = HashMap::new();
+    let mut workspace_manifest_index: HashMap<i64, BTreeSet<String>> = HashMap::new();
 
     for workspace in workspaces {
         let revision = workspace
@@ -741,12 +1161,14 @@ pub async fn fetch_page(
             let retry_attempt = compute_run_retry_attempt(&run);
             let retry_limit = compute_run_retry_limit(&run);
             let retry_count = compute_retry_count(&run, retry_attempt);
-            let retry_ledger = build_retry_ledger(&run, retry_attempt);
+            let retry_ledger =
+                build_retry_ledger(&run, retry_attempt, RetryLedgerMode::from_config());
             let override_reason = compute_run_override_reason(&run);
             let manual_override =
                 build_manual_override(&run, override_reason.clone(), &override_actors);
             let artifacts = extract_run_artifacts(&run);
             let artifact_fingerprints = derive_artifact_fingerprints(&artifacts);
+            let resource_usage = extract_resource_usage(&run);
 
             run_snapshots.push(LifecycleRunSnapshot {
                 trust,
@@ -765,6 +1187,7 @@ pub async fn fetch_page(
                 artifacts,
                 artifact_fingerprints,
                 promotion_verdict: None,
+                resource_usage,
                 run,
             });
         }
@@ -796,7 +1219,7 @@ pub async fn fetch_page(
     }
 
     if !workspace_manifest_index.is_empty() {
-        let mut manifest_digests = HashSet::new();
+        let mut manifest_digests = BTreeSet::new();
         for digests in workspace_manifest_index.values() {
             for digest in digests {
                 manifest_digests.insert(digest.clone());
@@ -820,7 +1243,11 @@ pub async fn fetch_page(
                         promotions.extend(entries.clone());
                     }
                 }
-                promotions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+                promotions.sort_by(|a, b| {
+                    b.updated_at
+                        .cmp(&a.updated_at)
+                        .then(b.promotion_id.cmp(&a.promotion_id))
+                });
                 promotions.dedup_by(|left, right| left.promotion_id == right.promotion_id);
                 snapshot.promotion_postures = promotions;
             }
@@ -836,17 +1263,11 @@ pub async fn fetch_page(
                     });
 
                     if verdict.is_none() {
-                        for artifact in &run.artifacts {
-                            if let Some(entries) = promotion_map.get(&artifact.manifest_digest) {
-                                if let Some(posture) = entries.first() {
-                                    verdict = Some(make_promotion_verdict_ref(
-                                        posture.promotion_id,
-                                        Some(posture),
-                                    ));
-                                    break;
-                                }
-                            }
-                        }
+                        verdict = select_promotion_verdict(
+                            &run.artifacts,
+                            &promotion_map,
+                            *config::LIFECYCLE_CONSOLE_VERDICT_STRATEGY,
+                        );
                     }
 
                     run.promotion_verdict = verdict;
@@ -855,10 +1276,14 @@ pub async fn fetch_page(
         }
     }
 
-    Ok(LifecycleConsolePage {
+    let mut page = LifecycleConsolePage {
         workspaces: snapshots,
         next_cursor,
-    })
+    };
+    if let Some(policy) = redaction {
+        policy.redact_page(&mut page);
+    }
+    Ok(page)
 }
 
 async fn load_revisions(
@@ -922,6 +1347,7 @@ async fn load_runs(
     pool: &PgPool,
     workspace_ids: &[i64],
     limit: usize,
+    since: Option<DateTime<Utc>>,
 ) -> Result<HashMap<i64, Vec<RuntimeVmRemediationRun>>, AppError> {
     if workspace_ids.is_empty() {
         return Ok(HashMap::new());
@@ -953,13 +1379,24 @@ async fn load_runs(
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         FROM (
             SELECT
                 runs.*,
                 ROW_NUMBER() OVER (PARTITION BY workspace_id ORDER BY started_at DESC) AS row_number
             FROM runtime_vm_remediation_runs runs
             WHERE workspace_id = ANY($1)
+              AND ($3::timestamptz IS NULL OR started_at > $3)
         ) ranked
         WHERE ranked.row_number <= $2
         ORDER BY workspace_id, started_at DESC
@@ -967,6 +1404,7 @@ async fn load_runs(
     )
     .bind(workspace_ids)
     .bind(limit as i64)
+    .bind(since)
     .fetch_all(pool)
     .await?;
 
@@ -983,6 +1421,7 @@ async fn load_promotion_runs(
     pool: &PgPool,
     workspace_ids: &[i64],
     limit: usize,
+    since: Option<DateTime<Utc>>,
 ) -> Result<HashMap<i64, Vec<RuntimeVmRemediationRun>>, AppError> {
     if workspace_ids.is_empty() {
         return Ok(HashMap::new());
@@ -1014,7 +1453,17 @@ async fn load_promotion_runs(
             updated_at,
             cancelled_at,
             cancellation_reason,
-            failure_reason
+            failure_reason,
+            scheduled_for,
+            analytics_duration_ms,
+            analytics_execution_started_at,
+            analytics_execution_completed_at,
+            analytics_retry_count,
+            analytics_retry_ledger,
+            analytics_override_actor_id,
+            analytics_artifact_hash,
+            analytics_promotion_verdict_id,
+            organization_id
         FROM (
             SELECT
                 runs.*,
@@ -1022,6 +1471,7 @@ async fn load_promotion_runs(
             FROM runtime_vm_remediation_runs runs
             WHERE workspace_id = ANY($1)
               AND metadata ? 'promotion'
+              AND ($3::timestamptz IS NULL OR updated_at > $3)
         ) ranked
         WHERE ranked.row_number <= $2
         ORDER BY workspace_id, updated_at DESC
@@ -1029,6 +1479,7 @@ async fn load_promotion_runs(
     )
     .bind(workspace_ids)
     .bind(limit as i64)
+    .bind(since)
     .fetch_all(pool)
     .await?;
 
@@ -1067,12 +1518,30 @@ async fn load_runtime_instances(
 async fn load_override_actors(
     pool: &PgPool,
     actor_ids: &HashSet<i32>,
+    cache: Option<&mut ActorEmailCache>,
 ) -> Result<HashMap<i32, OverrideActorRecord>, AppError> {
     if actor_ids.is_empty() {
         return Ok(HashMap::new());
     }
 
-    let ids: Vec<i32> = actor_ids.iter().copied().collect();
+    let emails = match cache {
+        Some(cache) => {
+            cache
+                .resolve(actor_ids, std::time::Instant::now(), |ids| {
+                    load_actor_emails(pool, ids)
+                })
+                .await?
+        }
+        None => load_actor_emails(pool, actor_ids.iter().copied().collect()).await?,
+    };
+
+    Ok(emails
+        .into_iter()
+        .map(|(id, email)| (id, OverrideActorRecord { email }))
+        .collect())
+}
+
+async fn load_actor_emails(pool: &PgPool, ids: Vec<i32>) -> Result<HashMap<i32, String>, AppError> {
     let rows: Vec<UserRow> = query_as(
         r#"
         SELECT id, email
@@ -1084,10 +1553,7 @@ async fn load_override_actors(
     .fetch_all(pool)
     .await?;
 
-    Ok(rows
-        .into_iter()
-        .map(|row| (row.id, OverrideActorRecord { email: row.email }))
-        .collect())
+    Ok(rows.into_iter().map(|row| (row.id, row.email)).collect())
 }
 
 async fn load_trust_states(
@@ -1158,6 +1624,7 @@ struct PromotionPostureRow {
 struct PromotionVerdictSummary {
     allowed: bool,
     veto_reasons: Vec<String>,
+    raw_veto_reasons: Vec<String>,
     notes: Vec<String>,
     signals: Option<Value>,
     remediation_hooks: Vec<String>,
@@ -1191,8 +1658,8 @@ fn collect_workspace_manifest_digests(
     revision: Option<&LifecycleWorkspaceRevision>,
     runs: &[LifecycleRunSnapshot],
     promotion_runs: &[RuntimeVmRemediationRun],
-) -> HashSet<String> {
-    let mut digests = HashSet::new();
+) -> BTreeSet<String> {
+    let mut digests = BTreeSet::new();
     collect_manifest_digests_from_value(&workspace.metadata, &mut digests);
     if let Some(revision) = revision {
         collect_manifest_digests_from_value(&revision.revision.plan, &mut digests);
@@ -1215,7 +1682,21 @@ fn collect_workspace_manifest_digests(
     digests
 }
 
-fn collect_manifest_digests_from_value(value: &Value, digests: &mut HashSet<String>) {
+fn collect_manifest_digests_from_value(value: &Value, digests: &mut BTreeSet<String>) {
+    let mut limit = DepthLimit::configured();
+    collect_manifest_digests_within(value, digests, 0, &mut limit);
+    limit.finish("manifest_digest");
+}
+
+fn collect_manifest_digests_within(
+    value: &Value,
+    digests: &mut BTreeSet<String>,
+    depth: usize,
+    limit: &mut DepthLimit,
+) {
+    if !limit.allows(depth) {
+        return;
+    }
     match value {
         Value::Object(map) => {
             for (key, entry) in map {
@@ -1226,12 +1707,12 @@ fn collect_manifest_digests_from_value(value: &Value, digests: &mut HashSet<Stri
                         }
                     }
                 }
-                collect_manifest_digests_from_value(entry, digests);
+                collect_manifest_digests_within(entry, digests, depth + 1, limit);
             }
         }
         Value::Array(items) => {
             for entry in items {
-                collect_manifest_digests_from_value(entry, digests);
+                collect_manifest_digests_within(entry, digests, depth + 1, limit);
             }
         }
         _ => {}
@@ -1240,7 +1721,7 @@ fn collect_manifest_digests_from_value(value: &Value, digests: &mut HashSet<Stri
 
 async fn load_promotion_postures(
     pool: &PgPool,
-    manifest_digests: &HashSet<String>,
+    manifest_digests: &BTreeSet<String>,
 ) -> Result<HashMap<String, Vec<LifecyclePromotionPosture>>, AppError> {
     if manifest_digests.is_empty() {
         return Ok(HashMap::new());
@@ -1254,7 +1735,7 @@ async fn load_promotion_postures(
         FROM artifact_promotions ap
         JOIN promotion_tracks t ON t.id = ap.promotion_track_id
         WHERE ap.manifest_digest = ANY($1)
-        ORDER BY ap.updated_at DESC
+        ORDER BY ap.updated_at DESC, ap.id DESC
         "#,
     )
     .bind(&digests)
@@ -1263,7 +1744,10 @@ async fn load_promotion_postures(
 
     let mut grouped: HashMap<String, Vec<LifecyclePromotionPosture>> = HashMap::new();
     for row in rows {
-        let summary = summarize_promotion_verdict(row.posture_verdict.as_ref());
+        let summary = summarize_promotion_verdict(
+            row.posture_verdict.as_ref(),
+            VetoReasonNormalizer::configured(),
+        );
         let mut notes = row.notes.clone();
         notes.extend(summary.notes.iter().cloned());
         notes.sort();
@@ -1286,6 +1770,7 @@ async fn load_promotion_postures(
                 track_tier: row.tier,
                 allowed: summary.allowed,
                 veto_reasons: summary.veto_reasons,
+                raw_veto_reasons: summary.raw_veto_reasons,
                 notes,
                 updated_at: row.updated_at,
                 remediation_hooks: hooks,
@@ -1298,7 +1783,7 @@ async fn load_promotion_postures(
 
 async fn load_build_artifacts_by_digest(
     pool: &PgPool,
-    manifest_digests: &HashSet<String>,
+    manifest_digests: &BTreeSet<String>,
 ) -> Result<HashMap<String, BuildArtifactSummary>, AppError> {
     if manifest_digests.is_empty() {
         return Ok(HashMap::new());
@@ -1354,10 +1839,14 @@ async fn load_build_artifacts_by_digest(
         .collect())
 }
 
-fn summarize_promotion_verdict(value: Option<&Value>) -> PromotionVerdictSummary {
+fn summarize_promotion_verdict(
+    value: Option<&Value>,
+    normalizer: &VetoReasonNormalizer,
+) -> PromotionVerdictSummary {
     let mut summary = PromotionVerdictSummary {
         allowed: true,
         veto_reasons: Vec::new(),
+        raw_veto_reasons: Vec::new(),
         notes: Vec::new(),
         signals: None,
         remediation_hooks: Vec::new(),
@@ -1372,10 +1861,15 @@ fn summarize_promotion_verdict(value: Option<&Value>) -> PromotionVerdictSummary
     }
 
     if let Some(reasons) = verdict.get("reasons").and_then(Value::as_array) {
-        summary.veto_reasons = reasons
+        summary.raw_veto_reasons = reasons
             .iter()
             .filter_map(|entry| entry.as_str().map(|value| value.to_string()))
             .collect();
+        summary.veto_reasons = summary
+            .raw_veto_reasons
+            .iter()
+            .map(|reason| normalizer.normalize(reason))
+            .collect();
     }
 
     if let Some(notes) = verdict.get("notes").and_then(Value::as_array) {
@@ -1395,6 +1889,8 @@ fn summarize_promotion_verdict(value: Option<&Value>) -> PromotionVerdictSummary
 
     summary.veto_reasons.sort();
     summary.veto_reasons.dedup();
+    summary.raw_veto_reasons.sort();
+    summary.raw_veto_reasons.dedup();
     summary.notes.sort();
     summary.notes.dedup();
     summary.remediation_hooks.sort();
@@ -1404,6 +1900,20 @@ fn summarize_promotion_verdict(value: Option<&Value>) -> PromotionVerdictSummary
 }
 
 fn collect_remediation_hooks(value: &Value, hooks: &mut Vec<String>) {
+    let mut limit = DepthLimit::configured();
+    collect_remediation_hooks_within(value, hooks, 0, &mut limit);
+    limit.finish("remediation_hooks");
+}
+
+fn collect_remediation_hooks_within(
+    value: &Value,
+    hooks: &mut Vec<String>,
+    depth: usize,
+    limit: &mut DepthLimit,
+) {
+    if !limit.allows(depth) {
+        return;
+    }
     match value {
         Value::Object(map) => {
             for (key, entry) in map {
@@ -1422,13 +1932,13 @@ fn collect_remediation_hooks(value: &Value, hooks: &mut Vec<String>) {
                         }
                     }
                 } else {
-                    collect_remediation_hooks(entry, hooks);
+                    collect_remediation_hooks_within(entry, hooks, depth + 1, limit);
                 }
             }
         }
         Value::Array(items) => {
             for entry in items {
-                collect_remediation_hooks(entry, hooks);
+                collect_remediation_hooks_within(entry, hooks, depth + 1, limit);
             }
         }
         _ => {}
@@ -1536,6 +2046,7 @@ fn diff_promotion_postures(
             prev.status != posture.status
                 || prev.allowed != posture.allowed
                 || prev.veto_reasons != posture.veto_reasons
+                || prev.raw_veto_reasons != posture.raw_veto_reasons
                 || prev.notes != posture.notes
                 || prev.remediation_hooks != posture.remediation_hooks
                 || prev.signals != posture.signals
@@ -1552,6 +2063,7 @@ fn diff_promotion_postures(
                 track_tier: posture.track_tier.clone(),
                 allowed: posture.allowed,
                 veto_reasons: posture.veto_reasons.clone(),
+                raw_veto_reasons: posture.raw_veto_reasons.clone(),
                 notes: posture.notes.clone(),
                 updated_at: posture.updated_at,
                 remediation_hooks: posture.remediation_hooks.clone(),
@@ -2450,7 +2962,7 @@ fn format_float(value: f32) -> String {
 
 fn compute_run_duration(run: &RuntimeVmRemediationRun) -> Option<i64> {
     let end_time = run.completed_at.or(run.cancelled_at).or_else(|| {
-        if run.status == "running" || run.status == "pending" {
+        if matches!(run.status.as_str(), "running" | "pending" | "blocked") {
             Some(Utc::now())
         } else {
             None
@@ -2463,6 +2975,13 @@ fn compute_run_duration(run: &RuntimeVmRemediationRun) -> Option<i64> {
     })
 }
 
+fn extract_resource_usage(run: &RuntimeVmRemediationRun) -> Option<ResourceUsage> {
+    run.metadata
+        .get("resource_usage")
+        .and_then(|value| serde_json::from_value::<ResourceUsage>(value.clone()).ok())
+        .and_then(|usage| ResourceUsage::measured(usage.cpu_seconds, usage.peak_memory_bytes))
+}
+
 fn compute_run_duration_ms(run: &RuntimeVmRemediationRun) -> Option<i64> {
     if let Some(value) = run.analytics_duration_ms {
         return Some(value.max(0));
@@ -2556,35 +3075,22 @@ fn compute_run_override_reason(run: &RuntimeVmRemediationRun) -> Option<String>
 fn build_retry_ledger(
     run: &RuntimeVmRemediationRun,
     retry_attempt: Option<i64>,
+    mode: RetryLedgerMode,
 ) -> Vec<LifecycleRunRetryRecord> {
     if let Some(Value::Array(entries)) = run.analytics_retry_ledger.as_ref() {
-        let mut records = Vec::new();
-        for entry in entries {
-            if let Some(map) = entry.as_object() {
-                if let Some(attempt) = map.get("attempt").and_then(Value::as_i64) {
-                    let status = map
-                        .get("status")
-                        .and_then(Value::as_str)
-                        .map(|s| s.to_string());
-                    let reason = map
-                        .get("reason")
-                        .and_then(Value::as_str)
-                        .map(|s| s.to_string());
-                    let observed_at = map
-                        .get("observed_at")
-                        .and_then(Value::as_str)
-                        .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
-                        .map(|dt| dt.with_timezone(&Utc));
-                    records.push(LifecycleRunRetryRecord {
-                        attempt,
-                        status,
-                        reason,
-                        observed_at,
-                    });
-                }
+        let parsed = parse_retry_ledger(entries, mode);
+        if !parsed.malformed.is_empty() {
+            for entry in &parsed.malformed {
+                tracing::warn!(
+                    run_id = run.id,
+                    index = entry.index,
+                    problem = %entry.problem,
+                    "malformed retry ledger entry"
+                );
             }
+            metrics::counter!(RETRY_LEDGER_MALFORMED_TOTAL, parsed.malformed.len() as u64);
         }
-
+        let mut records = parsed.records;
         if !records.is_empty() {
             records.sort_by_key(|record| record.attempt);
             return records;
@@ -2675,6 +3181,7 @@ fn make_promotion_verdict_ref(
             stage: Some(posture.stage.clone()),
             track_name: Some(posture.track_name.clone()),
             track_tier: Some(posture.track_tier.clone()),
+            selected_by: None,
         }
     } else {
         LifecycleRunPromotionVerdictRef {
@@ -2684,10 +3191,27 @@ fn make_promotion_verdict_ref(
             stage: None,
             track_name: None,
             track_tier: None,
+            selected_by: None,
         }
     }
 }
 
+/// The posture `strategy` picks for the first artifact that has any. Artifacts keep their
+/// extraction order, so the same run always picks the same verdict.
+fn select_promotion_verdict(
+    artifacts: &[LifecycleRunArtifact],
+    promotion_map: &HashMap<String, Vec<LifecyclePromotionPosture>>,
+    strategy: VerdictStrategy,
+) -> Option<LifecycleRunPromotionVerdictRef> {
+    artifacts.iter().find_map(|artifact| {
+        let postures = promotion_map.get(&artifact.manifest_digest)?;
+        let posture = strategy.choose(artifact, postures)?;
+        let mut verdict = make_promotion_verdict_ref(posture.promotion_id, Some(posture));
+        verdict.selected_by = Some(strategy);
+        Some(verdict)
+    })
+}
+
 fn extract_run_artifacts(run: &RuntimeVmRemediationRun) -> Vec<LifecycleRunArtifact> {
     let promotion = run.metadata.get("promotion");
     let promotion_track = promotion.and_then(|value| value.get("track"));
@@ -2774,7 +3298,7 @@ fn extract_run_artifacts(run: &RuntimeVmRemediationRun) -> Vec<LifecycleRunArtif
     }
 
     if artifacts.is_empty() {
-        let mut digests = HashSet::new();
+        let mut digests = BTreeSet::new();
         collect_manifest_digests_from_value(&run.metadata, &mut digests);
         collect_manifest_digests_from_value(&run.promotion_gate_context, &mut digests);
         for digest in digests {
@@ -2824,6 +3348,21 @@ fn enrich_run_artifacts(
 }
 
 fn search_for_integer(value: &Value, key: &str) -> Option<i64> {
+    let mut limit = DepthLimit::configured();
+    let found = search_for_integer_within(value, key, 0, &mut limit);
+    limit.finish("search_for_integer");
+    found
+}
+
+fn search_for_integer_within(
+    value: &Value,
+    key: &str,
+    depth: usize,
+    limit: &mut DepthLimit,
+) -> Option<i64> {
+    if !limit.allows(depth) {
+        return None;
+    }
     match value {
         Value::Object(map) => {
             if let Some(entry) = map.get(key) {
@@ -2832,7 +3371,7 @@ fn search_for_integer(value: &Value, key: &str) -> Option<i64> {
                 }
             }
             for entry in map.values() {
-                if let Some(num) = search_for_integer(entry, key) {
+                if let Some(num) = search_for_integer_within(entry, key, depth + 1, limit) {
                     return Some(num);
                 }
             }
@@ -2840,7 +3379,7 @@ fn search_for_integer(value: &Value, key: &str) -> Option<i64> {
         }
         Value::Array(items) => {
             for entry in items {
-                if let Some(num) = search_for_integer(entry, key) {
+                if let Some(num) = search_for_integer_within(entry, key, depth + 1, limit) {
                     return Some(num);
                 }
             }
@@ -2857,6 +3396,225 @@ mod tests {
     use serde_json::json;
     use std::collections::HashMap;
 
+    /// `{"child": {"child": ... leaf}}` with `levels` wrappers; `shallow` sits beside the first.
+    fn deeply_nested(levels: usize, leaf: Value, shallow: Value) -> Value {
+        let mut value = leaf;
+        for _ in 0..levels {
+            value = json!({ "child": value });
+        }
+        value["shallow"] = shallow;
+        value
+    }
+
+    #[test]
+    fn walkers_stop_at_the_depth_limit_but_keep_shallow_matches() {
+        let document = deeply_nested(
+            40,
+            json!({
+                "manifest_digest": "sha256:deep",
+                "hooks": ["deep-hook"],
+                "attempt": 9,
+                "override_reason": "deep"
+            }),
+            json!({
+                "manifest_digest": "sha256:shallow",
+                "hooks": ["shallow-hook"],
+                "attempt": 2,
+                "override_reason": "shallow"
+            }),
+        );
+
+        let mut limit = DepthLimit::new(8);
+        let mut digests = BTreeSet::new();
+        collect_manifest_digests_within(&document, &mut digests, 0, &mut limit);
+        assert_eq!(digests, BTreeSet::from(["sha256:shallow".to_string()]));
+        assert!(limit.reached());
+
+        let mut limit = DepthLimit::new(8);
+        let mut hooks = Vec::new();
+        collect_remediation_hooks_within(&document, &mut hooks, 0, &mut limit);
+        assert_eq!(hooks, vec!["shallow-hook".to_string()]);
+        assert!(limit.reached());
+
+        // searches return the first match in key order, so hide the shallow one to reach down
+        let deep_only = deeply_nested(
+            40,
+            json!({ "attempt": 9, "override_reason": "deep" }),
+            json!({}),
+        );
+        let mut limit = DepthLimit::new(8);
+        assert_eq!(
+            search_for_integer_within(&deep_only, "attempt", 0, &mut limit),
+            None
+        );
+        assert!(limit.reached());
+        let mut limit = DepthLimit::new(8);
+        assert_eq!(
+            search_for_string_within(&deep_only, "override_reason", 0, &mut limit),
+            None
+        );
+        assert!(limit.reached());
+
+        // within the limit everything is found and nothing is reported
+        let mut limit = DepthLimit::new(64);
+        assert_eq!(
+            search_for_integer_within(&deep_only, "attempt", 0, &mut limit),
+            Some(9)
+        );
+        assert!(!limit.reached());
+        let mut limit = DepthLimit::new(64);
+        let mut digests = BTreeSet::new();
+        collect_manifest_digests_within(&document, &mut digests, 0, &mut limit);
+        assert_eq!(digests.len(), 2);
+        assert!(!limit.reached());
+    }
+
+    #[test]
+    fn verdict_summary_groups_synonym_vetoes_and_keeps_originals() {
+        let normalizer = VetoReasonNormalizer::new(&HashMap::from([(
+            "attestation.pending".to_string(),
+            vec![
+                "attestation pending".to_string(),
+                "pending attestation".to_string(),
+            ],
+        )]));
+        let verdict = json!({
+            "allowed": false,
+            "reasons": ["pending attestation", "attestation pending", "trust.lifecycle_state=quarantined"]
+        });
+        let summary = summarize_promotion_verdict(Some(&verdict), &normalizer);
+        assert_eq!(
+            summary.veto_reasons,
+            vec!["attestation.pending", "trust.lifecycle_state=quarantined"]
+        );
+        assert_eq!(
+            summary.raw_veto_reasons,
+            vec![
+                "attestation pending",
+                "pending attestation",
+                "trust.lifecycle_state=quarantined"
+            ]
+        );
+    }
+
+    #[test]
+    fn msgpack_envelopes_decode_to_the_json_form_and_are_smaller_before_base64() {
+        let run_deltas = (0..50)
+            .map(|run_id| LifecycleRunDelta {
+                run_id,
+                status: "running".to_string(),
+                trust_changes: vec![LifecycleFieldChange {
+                    field: "trust.lifecycle_state".to_string(),
+                    previous: Some("quarantined".to_string()),
+                    current: Some("restored".to_string()),
+                }],
+                intelligence_changes: Vec::new(),
+                marketplace_changes: Vec::new(),
+                analytics_changes: vec![LifecycleFieldChange {
+                    field: "analytics.retry_count".to_string(),
+                    previous: Some(run_id.to_string()),
+                    current: Some((run_id + 1).to_string()),
+                }],
+                artifact_changes: Vec::new(),
+                provider_key_changes: Vec::new(),
+            })
+            .collect();
+        let envelope = LifecycleConsoleEventEnvelope {
+            event_type: LifecycleConsoleEventType::Snapshot,
+            emitted_at: Utc.with_ymd_and_hms(2025, 12, 9, 12, 0, 0).unwrap(),
+            cursor: Some(42),
+            page: None,
+            error: None,
+            delta: Some(LifecycleDelta {
+                workspaces: vec![LifecycleWorkspaceDelta {
+                    workspace_id: 17,
+                    run_deltas,
+                    removed_run_ids: vec![3, 4],
+                    promotion_run_deltas: Vec::new(),
+                    removed_promotion_run_ids: Vec::new(),
+                    promotion_posture_deltas: Vec::new(),
+                    removed_promotion_ids: Vec::new(),
+                }],
+            }),
+        };
+
+        let json_data = LifecycleStreamEncoding::Json
+            .encode_data(&envelope)
+            .unwrap();
+        let msgpack_data = LifecycleStreamEncoding::Msgpack
+            .encode_data(&envelope)
+            .unwrap();
+        let wrapper: LifecycleEncodedEnvelope = serde_json::from_str(&msgpack_data).unwrap();
+        assert_eq!(wrapper.encoding, "msgpack");
+        let bytes = Base64Engine.decode(wrapper.payload).unwrap();
+        let decoded = msgpack::decode(&bytes).unwrap();
+        assert_eq!(decoded, serde_json::from_str::<Value>(&json_data).unwrap());
+        assert!(
+            bytes.len() < json_data.len(),
+            "msgpack {} bytes vs json {} bytes",
+            bytes.len(),
+            json_data.len()
+        );
+    }
+
+    #[test]
+    fn last_event_id_accepts_only_non_negative_cursors() {
+        assert_eq!(parse_last_event_id("42"), Ok(42));
+        assert_eq!(parse_last_event_id(" 0 "), Ok(0));
+        assert_eq!(
+            parse_last_event_id("9000000000000000000"),
+            Ok(9_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            parse_last_event_id("-5"),
+            Err("Last-Event-ID -5 is negative".to_string())
+        );
+        assert!(parse_last_event_id("99999999999999999999").is_err());
+        assert!(parse_last_event_id("snapshot-3").is_err());
+        assert!(parse_last_event_id("").is_err());
+    }
+
+    #[test]
+    fn default_limits_cap_run_limit_at_ten() {
+        let limits = LifecycleConsoleLimits::default();
+        let query = LifecycleConsoleQuery {
+            limit: Some(500),
+            run_limit: Some(50),
+            ..LifecycleConsoleQuery::default()
+        };
+        assert_eq!(limits.resolve(&query), (100, 10));
+        assert_eq!(limits.resolve(&LifecycleConsoleQuery::default()), (25, 5));
+    }
+
+    #[test]
+    fn configured_ceilings_replace_the_defaults() {
+        let dashboard = LifecycleConsoleLimits {
+            max_limit: 250,
+            max_run_limit: 40,
+        };
+        let query = LifecycleConsoleQuery {
+            limit: Some(200),
+            run_limit: Some(25),
+            ..LifecycleConsoleQuery::default()
+        };
+        assert_eq!(dashboard.resolve(&query), (200, 25));
+        let query = LifecycleConsoleQuery {
+            run_limit: Some(90),
+            ..query
+        };
+        assert_eq!(dashboard.resolve(&query), (200, 40));
+
+        // a constrained deployment also lowers the implicit defaults
+        let constrained = LifecycleConsoleLimits {
+            max_limit: 10,
+            max_run_limit: 2,
+        };
+        assert_eq!(
+            constrained.resolve(&LifecycleConsoleQuery::default()),
+            (10, 2)
+        );
+    }
+
     fn base_run() -> RuntimeVmRemediationRun {
         let now = Utc::now();
         RuntimeVmRemediationRun {
@@ -2884,6 +3642,7 @@ mod tests {
             cancelled_at: None,
             cancellation_reason: None,
             failure_reason: None,
+            scheduled_for: None,
             analytics_duration_ms: None,
             analytics_execution_started_at: None,
             analytics_execution_completed_at: None,
@@ -2892,9 +3651,203 @@ mod tests {
             analytics_override_actor_id: None,
             analytics_artifact_hash: None,
             analytics_promotion_verdict_id: None,
+            organization_id: None,
+        }
+    }
+
+    fn promotion_page(api_token: &str) -> LifecycleConsolePage {
+        let now = Utc::now();
+        let mut run = base_run();
+        run.automation_payload = Some(json!({ "lane": "prod", "api_token": api_token }));
+        LifecycleConsolePage {
+            workspaces: vec![LifecycleWorkspaceSnapshot {
+                workspace: RuntimeVmRemediationWorkspace {
+                    id: 7,
+                    workspace_key: "edge".to_string(),
+                    display_name: "Edge".to_string(),
+                    description: None,
+                    owner_id: 1,
+                    organization_id: None,
+                    lifecycle_state: "draft".to_string(),
+                    active_revision_id: None,
+                    metadata: json!({ "api_token": api_token }),
+                    lineage_tags: Vec::new(),
+                    created_at: now,
+                    updated_at: now,
+                    version: 1,
+                },
+                active_revision: None,
+                recent_runs: Vec::new(),
+                promotion_runs: vec![run],
+                promotion_postures: Vec::new(),
+            }],
+            next_cursor: Some(7),
+        }
+    }
+
+    #[test]
+    fn redaction_masks_the_snapshot_and_its_deltas() {
+        let policy = RedactionPolicy::new(&["api_token".to_string()], &["admin".to_string()]);
+        let operator = AuthUser {
+            user_id: 2,
+            role: "operator".to_string(),
+        };
+        let admin = AuthUser {
+            user_id: 1,
+            role: "admin".to_string(),
+        };
+        let redact = |mut page: LifecycleConsolePage, viewer: &AuthUser| {
+            if let Some(policy) = policy.for_viewer(Some(viewer)) {
+                policy.redact_page(&mut page);
+            }
+            page
+        };
+
+        let first = redact(promotion_page("tok-1"), &operator);
+        let serialized = serde_json::to_string(&first).unwrap();
+        assert!(!serialized.contains("tok-1"));
+        assert_eq!(
+            first.workspaces[0].workspace.metadata["api_token"],
+            redaction::REDACTION_MARKER
+        );
+
+        let delta = compute_delta(&HashMap::new(), &first).unwrap();
+        let delta_json = serde_json::to_string(&delta).unwrap();
+        assert!(delta_json.contains(redaction::REDACTION_MARKER));
+        assert!(!delta_json.contains("tok-1"));
+
+        // rotating the secret is not visible as a change to a masked viewer
+        let previous = HashMap::from([(7, first.workspaces[0].clone())]);
+        let rotated = redact(promotion_page("tok-2"), &operator);
+        assert!(compute_delta(&previous, &rotated).is_none());
+
+        let privileged = redact(promotion_page("tok-1"), &admin);
+        assert_eq!(
+            privileged.workspaces[0].promotion_runs[0]
+                .automation_payload
+                .as_ref()
+                .unwrap()["api_token"],
+            "tok-1"
+        );
+        let delta = compute_delta(&HashMap::new(), &privileged).unwrap();
+        assert!(serde_json::to_string(&delta).unwrap().contains("tok-1"));
+    }
+
+    fn posture(
+        promotion_id: i64,
+        digest: &str,
+        updated_at: DateTime<Utc>,
+    ) -> LifecyclePromotionPosture {
+        LifecyclePromotionPosture {
+            promotion_id,
+            manifest_digest: digest.to_string(),
+            stage: "production".to_string(),
+            status: "active".to_string(),
+            track_id: 1,
+            track_name: "release".to_string(),
+            track_tier: "gold".to_string(),
+            allowed: true,
+            veto_reasons: Vec::new(),
+            raw_veto_reasons: Vec::new(),
+            notes: Vec::new(),
+            updated_at,
+            remediation_hooks: Vec::new(),
+            signals: None,
         }
     }
 
+    #[test]
+    fn fallback_digests_and_chosen_verdict_are_deterministic() {
+        let mut run = base_run();
+        run.metadata = json!({
+            "steps": [
+                { "manifest_digest": "sha256:zeta" },
+                { "nested": { "manifest_digest": "sha256:alpha" } },
+                { "manifest_digest": "sha256:mu" }
+            ]
+        });
+        run.promotion_gate_context = json!({ "manifest_digest": "sha256:beta" });
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let promotion_map = HashMap::from([
+            ("sha256:mu".to_string(), vec![posture(30, "sha256:mu", at)]),
+            (
+                "sha256:zeta".to_string(),
+                vec![posture(40, "sha256:zeta", at)],
+            ),
+            (
+                "sha256:beta".to_string(),
+                vec![posture(20, "sha256:beta", at)],
+            ),
+        ]);
+
+        for _ in 0..16 {
+            let artifacts = extract_run_artifacts(&run);
+            let digests: Vec<&str> = artifacts
+                .iter()
+                .map(|artifact| artifact.manifest_digest.as_str())
+                .collect();
+            assert_eq!(
+                digests,
+                vec!["sha256:alpha", "sha256:beta", "sha256:mu", "sha256:zeta"]
+            );
+            let verdict =
+                select_promotion_verdict(&artifacts, &promotion_map, VerdictStrategy::MostRecent)
+                    .unwrap();
+            assert_eq!(verdict.promotion_id, Some(20));
+        }
+    }
+
+    #[test]
+    fn verdict_strategies_pick_among_postures_for_one_digest() {
+        let mut run = base_run();
+        run.metadata = json!({
+            "target": { "manifest_digest": "sha256:shared", "stage": "production" }
+        });
+        let artifacts = extract_run_artifacts(&run);
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut staging = posture(1, "sha256:shared", at + chrono::Duration::hours(2));
+        staging.stage = "staging".to_string();
+        let mut vetoed = posture(2, "sha256:shared", at);
+        vetoed.allowed = false;
+        let production = posture(3, "sha256:shared", at + chrono::Duration::hours(1));
+        let promotion_map = HashMap::from([(
+            "sha256:shared".to_string(),
+            vec![vetoed.clone(), production.clone(), staging.clone()],
+        )]);
+
+        let chosen = |strategy: VerdictStrategy, artifacts: &[LifecycleRunArtifact]| {
+            let verdict = select_promotion_verdict(artifacts, &promotion_map, strategy).unwrap();
+            assert_eq!(verdict.selected_by, Some(strategy));
+            verdict.promotion_id.unwrap()
+        };
+        assert_eq!(chosen(VerdictStrategy::MostRecent, &artifacts), 1);
+        assert_eq!(chosen(VerdictStrategy::MatchingStage, &artifacts), 3);
+        assert_eq!(chosen(VerdictStrategy::MostRestrictive, &artifacts), 2);
+
+        // without a matching stage or a veto, both fall back to the newest posture
+        let mut canary = artifacts.clone();
+        canary[0].stage = Some("canary".to_string());
+        assert_eq!(chosen(VerdictStrategy::MatchingStage, &canary), 1);
+        let all_allowed = HashMap::from([(
+            "sha256:shared".to_string(),
+            vec![production.clone(), staging.clone()],
+        )]);
+        let verdict =
+            select_promotion_verdict(&artifacts, &all_allowed, VerdictStrategy::MostRestrictive)
+                .unwrap();
+        assert_eq!(verdict.promotion_id, Some(1));
+
+        assert_eq!(
+            " Matching_Stage ".parse::<VerdictStrategy>(),
+            Ok(VerdictStrategy::MatchingStage)
+        );
+        assert!("first".parse::<VerdictStrategy>().is_err());
+        assert_eq!(
+            serde_json::to_value(VerdictStrategy::MostRestrictive).unwrap(),
+            json!(VerdictStrategy::MostRestrictive.as_str())
+        );
+    }
+
     #[test]
     fn extract_run_artifacts_prefers_target_metadata() {
         let mut run = base_run();
@@ -3022,6 +3975,21 @@ mod tests {
 }
 
 fn search_for_string(value: &Value, key: &str) -> Option<String> {
+    let mut limit = DepthLimit::configured();
+    let found = search_for_string_within(value, key, 0, &mut limit);
+    limit.finish("search_for_string");
+    found
+}
+
+fn search_for_string_within(
+    value: &Value,
+    key: &str,
+    depth: usize,
+    limit: &mut DepthLimit,
+) -> Option<String> {
+    if !limit.allows(depth) {
+        return None;
+    }
     match value {
         Value::Object(map) => {
             if let Some(entry) = map.get(key) {
@@ -3032,7 +4000,7 @@ fn search_for_string(value: &Value, key: &str) -> Option<String> {
                 }
             }
             for entry in map.values() {
-                if let Some(text) = search_for_string(entry, key) {
+                if let Some(text) = search_for_string_within(entry, key, depth + 1, limit) {
                     return Some(text);
                 }
             }
@@ -3040,7 +4008,7 @@ fn search_for_string(value: &Value, key: &str) -> Option<String> {
         }
         Value::Array(items) => {
             for entry in items {
-                if let Some(text) = search_for_string(entry, key) {
+                if let Some(text) = search_for_string_within(entry, key, depth + 1, limit) {
                     return Some(text);
                 }
             }
@@ -0,0 +1,57 @@
+use serde_json::Value;
+
+// key: lifecycle-console -> msgpack stream encoding
+
+/// Standard MessagePack for JSON values, written by `rmp-serde`. Objects become maps keyed
+/// by field name, so any MessagePack decoder reads the payload back as the JSON envelope
+/// without extension types.
+pub fn encode(value: &Value) -> Vec<u8> {
+    rmp_serde::to_vec_named(value).expect("JSON values always encode as MessagePack")
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Value, rmp_serde::decode::Error> {
+    rmp_serde::from_slice(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_every_json_shape() {
+        let value = json!({
+            "null": null,
+            "flags": [true, false],
+            "small": [0, 127, -1, -32],
+            "wide": [128, 255, 256, 65_536, u64::MAX, -33, -129, -32_769, i64::MIN],
+            "float": 1.5,
+            "short": "run",
+            "long": "x".repeat(40),
+            "longer": "y".repeat(300),
+            "many": (0..20).collect::<Vec<_>>(),
+            "nested": { "a": { "b": [] } },
+        });
+        assert_eq!(decode(&encode(&value)).unwrap(), value);
+    }
+
+    #[test]
+    fn writes_plain_messagepack() {
+        assert_eq!(encode(&json!(5)), vec![0x05]);
+        assert_eq!(encode(&json!(-3)), vec![0xfd]);
+        assert_eq!(encode(&json!("ab")), vec![0xa2, b'a', b'b']);
+        assert_eq!(encode(&json!([])), vec![0x90]);
+        assert_eq!(
+            encode(&json!({ "field": "field" })),
+            [&[0x81, 0xa5][..], b"field", &[0xa5], b"field"].concat()
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_and_malformed_payloads() {
+        let bytes = encode(&json!({ "status": "running" }));
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&[0xc1]).is_err());
+        assert!(decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+}
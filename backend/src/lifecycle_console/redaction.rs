@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+use super::{LifecycleConsolePage, LifecycleRunSnapshot};
+use crate::config;
+use crate::db::runtime_vm_remediation_runs::RuntimeVmRemediationRun;
+use crate::extractor::AuthUser;
+
+// key: lifecycle-console -> snapshot redaction
+
+/// Replaces every redacted value, whatever its JSON type.
+pub const REDACTION_MARKER: &str = "[redacted]";
+
+static CONFIGURED: Lazy<RedactionPolicy> = Lazy::new(|| {
+    RedactionPolicy::new(
+        &config::LIFECYCLE_CONSOLE_REDACTED_FIELDS,
+        &config::LIFECYCLE_CONSOLE_UNREDACTED_ROLES,
+    )
+});
+
+/// Masks sensitive values inside the JSON documents a lifecycle page carries: workspace,
+/// revision and gate snapshot metadata, run payloads and metadata, and trust provenance.
+///
+/// A rule without a dot is a key redacted at any depth. A dotted rule such as
+/// `credentials.token` is a path from the root of each document; arrays along the way are
+/// searched element by element.
+#[derive(Debug, Clone, Default)]
+pub struct RedactionPolicy {
+    keys: HashSet<String>,
+    paths: Vec<Vec<String>>,
+    unredacted_roles: HashSet<String>,
+}
+
+impl RedactionPolicy {
+    pub fn new(fields: &[String], unredacted_roles: &[String]) -> Self {
+        let mut keys = HashSet::new();
+        let mut paths = Vec::new();
+        for field in fields.iter().map(|field| field.trim()) {
+            if field.is_empty() {
+                continue;
+            }
+            if field.contains('.') {
+                paths.push(field.split('.').map(str::to_string).collect());
+            } else {
+                keys.insert(field.to_string());
+            }
+        }
+        Self {
+            keys,
+            paths,
+            unredacted_roles: unredacted_roles.iter().cloned().collect(),
+        }
+    }
+
+    /// The policy built from `LIFECYCLE_CONSOLE_REDACTED_FIELDS` and
+    /// `LIFECYCLE_CONSOLE_UNREDACTED_ROLES`.
+    pub fn configured() -> &'static Self {
+        &CONFIGURED
+    }
+
+    /// The policy to enforce for `viewer`, or `None` when nothing needs masking: no rules are
+    /// configured or the viewer holds an unredacted role. Anonymous viewers are always masked.
+    pub fn for_viewer(&self, viewer: Option<&AuthUser>) -> Option<&Self> {
+        if self.keys.is_empty() && self.paths.is_empty() {
+            return None;
+        }
+        match viewer {
+            Some(user) if self.unredacted_roles.contains(&user.role) => None,
+            _ => Some(self),
+        }
+    }
+
+    pub fn redact_value(&self, value: &mut Value) {
+        let mut path = Vec::new();
+        self.redact_at(value, &mut path);
+    }
+
+    fn redact_at<'a>(&self, value: &'a mut Value, path: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(entries) => {
+                for (key, item) in entries {
+                    path.push(key.as_str());
+                    if self.keys.contains(key) || self.matches_path(path) {
+                        *item = Value::from(REDACTION_MARKER);
+                    } else {
+                        self.redact_at(item, path);
+                    }
+                    path.pop();
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.redact_at(item, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn matches_path(&self, path: &[&str]) -> bool {
+        self.paths
+            .iter()
+            .any(|rule| rule.len() == path.len() && rule.iter().zip(path).all(|(a, b)| a == b))
+    }
+
+    pub fn redact_page(&self, page: &mut LifecycleConsolePage) {
+        for snapshot in &mut page.workspaces {
+            self.redact_value(&mut snapshot.workspace.metadata);
+            if let Some(revision) = snapshot.active_revision.as_mut() {
+                self.redact_value(&mut revision.revision.plan);
+                self.redact_value(&mut revision.revision.metadata);
+                for gate in &mut revision.gate_snapshots {
+                    self.redact_value(&mut gate.gate_context);
+                    self.redact_value(&mut gate.metadata);
+                }
+            }
+            for run in &mut snapshot.recent_runs {
+                self.redact_run_snapshot(run);
+            }
+            for run in &mut snapshot.promotion_runs {
+                self.redact_run(run);
+            }
+        }
+    }
+
+    /// Redacts a workspace snapshot stored as JSON, such as an archived one, touching the same
+    /// fields as [`Self::redact_page`].
+    pub fn redact_stored_snapshot(&self, snapshot: &mut Value) {
+        self.redact_pointers(
+            snapshot,
+            &[
+                "/workspace/metadata",
+                "/active_revision/revision/plan",
+                "/active_revision/revision/metadata",
+            ],
+        );
+        for gate in items_mut(snapshot, "/active_revision/gate_snapshots") {
+            self.redact_pointers(gate, &["/gate_context", "/metadata"]);
+        }
+        for run in items_mut(snapshot, "/recent_runs") {
+            self.redact_pointers(
+                run,
+                &[
+                    "/run/automation_payload",
+                    "/run/metadata",
+                    "/run/promotion_gate_context",
+                    "/trust/provenance",
+                ],
+            );
+        }
+        for run in items_mut(snapshot, "/promotion_runs") {
+            self.redact_pointers(
+                run,
+                &[
+                    "/automation_payload",
+                    "/metadata",
+                    "/promotion_gate_context",
+                ],
+            );
+        }
+    }
+
+    fn redact_pointers(&self, value: &mut Value, pointers: &[&str]) {
+        for pointer in pointers {
+            if let Some(target) = value.pointer_mut(pointer) {
+                self.redact_value(target);
+            }
+        }
+    }
+
+    fn redact_run_snapshot(&self, snapshot: &mut LifecycleRunSnapshot) {
+        self.redact_run(&mut snapshot.run);
+        if let Some(provenance) = snapshot
+            .trust
+            .as_mut()
+            .and_then(|trust| trust.provenance.as_mut())
+        {
+            self.redact_value(provenance);
+        }
+    }
+
+    fn redact_run(&self, run: &mut RuntimeVmRemediationRun) {
+        if let Some(payload) = run.automation_payload.as_mut() {
+            self.redact_value(payload);
+        }
+        self.redact_value(&mut run.metadata);
+        self.redact_value(&mut run.promotion_gate_context);
+    }
+}
+
+fn items_mut<'a>(value: &'a mut Value, pointer: &str) -> impl Iterator<Item = &'a mut Value> {
+    value
+        .pointer_mut(pointer)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy() -> RedactionPolicy {
+        RedactionPolicy::new(
+            &["api_token".to_string(), "credentials.password".to_string()],
+            &["admin".to_string()],
+        )
+    }
+
+    fn viewer(role: &str) -> AuthUser {
+        AuthUser {
+            user_id: 1,
+            role: role.to_string(),
+        }
+    }
+
+    #[test]
+    fn keys_match_anywhere_and_paths_from_the_root() {
+        let mut value = json!({
+            "api_token": "tok-1",
+            "steps": [{ "api_token": { "nested": true } }, { "name": "restart" }],
+            "credentials": { "password": "hunter2", "user": "ops" },
+            "other": { "credentials": { "password": "kept" } },
+        });
+        policy().redact_value(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "api_token": REDACTION_MARKER,
+                "steps": [{ "api_token": REDACTION_MARKER }, { "name": "restart" }],
+                "credentials": { "password": REDACTION_MARKER, "user": "ops" },
+                "other": { "credentials": { "password": "kept" } },
+            })
+        );
+    }
+
+    #[test]
+    fn privileged_roles_and_empty_policies_skip_redaction() {
+        let policy = policy();
+        assert!(policy.for_viewer(Some(&viewer("admin"))).is_none());
+        assert!(policy.for_viewer(Some(&viewer("operator"))).is_some());
+        assert!(policy.for_viewer(None).is_some());
+        assert!(RedactionPolicy::new(&[], &[])
+            .for_viewer(Some(&viewer("operator")))
+            .is_none());
+    }
+
+    #[test]
+    fn stored_snapshots_redact_the_same_payloads_as_pages() {
+        let mut snapshot = json!({
+            "workspace": { "id": 7, "metadata": { "api_token": "tok-1" } },
+            "active_revision": {
+                "revision": { "plan": { "credentials": { "password": "p" } }, "metadata": {} },
+                "gate_snapshots": [{ "gate_context": { "api_token": "tok-2" }, "metadata": {} }]
+            },
+            "recent_runs": [{
+                "run": { "metadata": { "api_token": "tok-3" }, "automation_payload": null },
+                "trust": { "provenance": { "api_token": "tok-4" } },
+                "duration_ms": 5
+            }],
+            "promotion_runs": [{ "promotion_gate_context": { "api_token": "tok-5" } }]
+        });
+        policy().redact_stored_snapshot(&mut snapshot);
+        let text = snapshot.to_string();
+        for secret in ["tok-1", "tok-2", "tok-3", "tok-4", "tok-5", "\"p\""] {
+            assert!(!text.contains(secret), "{secret} leaked: {text}");
+        }
+        assert_eq!(
+            snapshot["active_revision"]["revision"]["plan"]["credentials"]["password"],
+            REDACTION_MARKER
+        );
+        assert_eq!(snapshot["recent_runs"][0]["duration_ms"], 5);
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+use super::LifecycleRunRetryRecord;
+use crate::config;
+
+// key: lifecycle-retry-ledger -> strict or lenient parsing of analytics_retry_ledger
+
+pub const RETRY_LEDGER_MALFORMED_TOTAL: &str = "mcp_lifecycle_retry_ledger_malformed_entries_total";
+
+/// How `analytics_retry_ledger` entries are read. Lenient keeps any object with an integer
+/// `attempt` and ignores the rest; strict checks every field and reports what it drops.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetryLedgerMode {
+    #[default]
+    Lenient,
+    Strict,
+}
+
+impl RetryLedgerMode {
+    pub fn from_config() -> Self {
+        if *config::LIFECYCLE_CONSOLE_STRICT_RETRY_LEDGER {
+            Self::Strict
+        } else {
+            Self::Lenient
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedLedgerEntry {
+    /// Position in the stored ledger array.
+    pub index: usize,
+    pub problem: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedRetryLedger {
+    /// In ledger order.
+    pub records: Vec<LifecycleRunRetryRecord>,
+    /// Always empty in lenient mode.
+    pub malformed: Vec<MalformedLedgerEntry>,
+}
+
+pub fn parse_retry_ledger(entries: &[Value], mode: RetryLedgerMode) -> ParsedRetryLedger {
+    let mut parsed = ParsedRetryLedger::default();
+    for (index, entry) in entries.iter().enumerate() {
+        match mode {
+            RetryLedgerMode::Lenient => parsed.records.extend(lenient_record(entry)),
+            RetryLedgerMode::Strict => match strict_record(entry) {
+                Ok(record) => parsed.records.push(record),
+                Err(problem) => parsed
+                    .malformed
+                    .push(MalformedLedgerEntry { index, problem }),
+            },
+        }
+    }
+    parsed
+}
+
+fn lenient_record(entry: &Value) -> Option<LifecycleRunRetryRecord> {
+    let map = entry.as_object()?;
+    let attempt = map.get("attempt").and_then(Value::as_i64)?;
+    let text = |key: &str| map.get(key).and_then(Value::as_str).map(str::to_string);
+    Some(LifecycleRunRetryRecord {
+        attempt,
+        status: text("status"),
+        reason: text("reason"),
+        observed_at: map
+            .get("observed_at")
+            .and_then(Value::as_str)
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+    })
+}
+
+fn strict_record(entry: &Value) -> Result<LifecycleRunRetryRecord, String> {
+    let map = entry
+        .as_object()
+        .ok_or_else(|| "entry is not an object".to_string())?;
+    let attempt = match map.get("attempt") {
+        None | Some(Value::Null) => return Err("missing `attempt`".into()),
+        Some(value) => value
+            .as_i64()
+            .filter(|attempt| *attempt >= 0)
+            .ok_or_else(|| "`attempt` must be a non-negative integer".to_string())?,
+    };
+    let observed_at = match optional_string(map, "observed_at")? {
+        Some(text) => Some(
+            DateTime::parse_from_rfc3339(&text)
+                .map_err(|_| "`observed_at` must be an RFC 3339 timestamp".to_string())?
+                .with_timezone(&Utc),
+        ),
+        None => None,
+    };
+    Ok(LifecycleRunRetryRecord {
+        attempt,
+        status: optional_string(map, "status")?,
+        reason: optional_string(map, "reason")?,
+        observed_at,
+    })
+}
+
+fn optional_string(map: &Map<String, Value>, key: &str) -> Result<Option<String>, String> {
+    match map.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(text)) => Ok(Some(text.clone())),
+        Some(_) => Err(format!("`{key}` must be a string")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ledger() -> Vec<Value> {
+        json!([
+            { "attempt": 1, "status": "failed", "reason": "timeout", "observed_at": "2025-01-01T00:00:00Z" },
+            { "status": "failed", "reason": "producer forgot the attempt" },
+            { "attempt": 2, "status": "succeeded" }
+        ])
+        .as_array()
+        .cloned()
+        .unwrap()
+    }
+
+    #[test]
+    fn strict_mode_keeps_valid_entries_and_counts_malformed_ones() {
+        let parsed = parse_retry_ledger(&ledger(), RetryLedgerMode::Strict);
+        let attempts: Vec<i64> = parsed.records.iter().map(|record| record.attempt).collect();
+        assert_eq!(attempts, vec![1, 2]);
+        assert_eq!(
+            parsed.malformed,
+            vec![MalformedLedgerEntry {
+                index: 1,
+                problem: "missing `attempt`".into(),
+            }]
+        );
+
+        let parsed = parse_retry_ledger(
+            &[
+                json!("attempt 3"),
+                json!({ "attempt": 3, "observed_at": "yesterday" }),
+                json!({ "attempt": 3, "status": 500 }),
+            ],
+            RetryLedgerMode::Strict,
+        );
+        assert!(parsed.records.is_empty());
+        let problems: Vec<&str> = parsed
+            .malformed
+            .iter()
+            .map(|entry| entry.problem.as_str())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "entry is not an object",
+                "`observed_at` must be an RFC 3339 timestamp",
+                "`status` must be a string",
+            ]
+        );
+    }
+
+    #[test]
+    fn lenient_mode_skips_malformed_entries_silently() {
+        let parsed = parse_retry_ledger(&ledger(), RetryLedgerMode::Lenient);
+        let attempts: Vec<i64> = parsed.records.iter().map(|record| record.attempt).collect();
+        assert_eq!(attempts, vec![1, 2]);
+        assert!(parsed.malformed.is_empty());
+        assert_eq!(parsed.records[0].reason.as_deref(), Some("timeout"));
+        assert!(parsed.records[0].observed_at.is_some());
+
+        // fields with the wrong type are dropped rather than rejecting the entry
+        let parsed = parse_retry_ledger(
+            &[json!({ "attempt": 3, "observed_at": "yesterday", "status": 500 })],
+            RetryLedgerMode::Lenient,
+        );
+        assert_eq!(parsed.records.len(), 1);
+        assert_eq!(parsed.records[0].status, None);
+        assert_eq!(parsed.records[0].observed_at, None);
+    }
+}
@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::{LifecyclePromotionPosture, LifecycleRunArtifact};
+
+// key: lifecycle-console -> promotion verdict attribution for runs without a recorded verdict
+
+/// Which posture the console attributes to a run when the run did not record a verdict and
+/// its artifact's digest has several postures. Ties go to the newest posture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerdictStrategy {
+    /// The most recently updated posture.
+    #[default]
+    MostRecent,
+    /// The newest posture on the stage the run's artifact targets, falling back to the newest.
+    MatchingStage,
+    /// The newest vetoed posture, so a veto on any track is never hidden; falls back to the
+    /// newest.
+    MostRestrictive,
+}
+
+impl VerdictStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VerdictStrategy::MostRecent => "most_recent",
+            VerdictStrategy::MatchingStage => "matching_stage",
+            VerdictStrategy::MostRestrictive => "most_restrictive",
+        }
+    }
+
+    /// Picks one of `postures`, all for `artifact`'s digest, in any order.
+    pub fn choose<'a>(
+        self,
+        artifact: &LifecycleRunArtifact,
+        postures: &'a [LifecyclePromotionPosture],
+    ) -> Option<&'a LifecyclePromotionPosture> {
+        let preferred = match self {
+            VerdictStrategy::MostRecent => None,
+            VerdictStrategy::MatchingStage => artifact
+                .stage
+                .as_deref()
+                .and_then(|stage| newest(postures.iter().filter(|posture| posture.stage == stage))),
+            VerdictStrategy::MostRestrictive => {
+                newest(postures.iter().filter(|posture| !posture.allowed))
+            }
+        };
+        preferred.or_else(|| newest(postures.iter()))
+    }
+}
+
+fn newest<'a>(
+    candidates: impl Iterator<Item = &'a LifecyclePromotionPosture>,
+) -> Option<&'a LifecyclePromotionPosture> {
+    candidates.max_by_key(|posture| (posture.updated_at, posture.promotion_id))
+}
+
+impl FromStr for VerdictStrategy {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "most_recent" => Ok(VerdictStrategy::MostRecent),
+            "matching_stage" => Ok(VerdictStrategy::MatchingStage),
+            "most_restrictive" => Ok(VerdictStrategy::MostRestrictive),
+            other => Err(format!("unknown verdict strategy `{other}`")),
+        }
+    }
+}
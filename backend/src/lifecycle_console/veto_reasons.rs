@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::config;
+
+// key: lifecycle-console -> veto-reason canonicalization
+
+static CONFIGURED: Lazy<VetoReasonNormalizer> =
+    Lazy::new(|| VetoReasonNormalizer::new(&config::PROMOTION_VETO_REASON_ALIASES));
+
+/// Maps the different phrasings producers use for the same promotion veto onto one
+/// canonical code, so equivalent reasons group together in the console.
+#[derive(Debug, Clone, Default)]
+pub struct VetoReasonNormalizer {
+    canonical_by_alias: HashMap<String, String>,
+}
+
+impl VetoReasonNormalizer {
+    /// `aliases` maps each canonical code to the phrasings that mean the same thing.
+    /// The canonical code is always an alias of itself.
+    pub fn new(aliases: &HashMap<String, Vec<String>>) -> Self {
+        let mut canonical_by_alias = HashMap::new();
+        for (canonical, phrasings) in aliases {
+            for phrasing in phrasings.iter().chain(std::iter::once(canonical)) {
+                canonical_by_alias.insert(match_key(phrasing), canonical.clone());
+            }
+        }
+        Self { canonical_by_alias }
+    }
+
+    /// The normalizer built from `PROMOTION_VETO_REASON_ALIASES`.
+    pub fn configured() -> &'static Self {
+        &CONFIGURED
+    }
+
+    /// Returns the canonical code for `reason`, or `reason` unchanged when it is not
+    /// mapped. Matching ignores case and repeated whitespace.
+    pub fn normalize(&self, reason: &str) -> String {
+        self.canonical_by_alias
+            .get(&match_key(reason))
+            .cloned()
+            .unwrap_or_else(|| reason.to_string())
+    }
+}
+
+fn match_key(reason: &str) -> String {
+    reason
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalizer() -> VetoReasonNormalizer {
+        VetoReasonNormalizer::new(&HashMap::from([(
+            "attestation.pending".to_string(),
+            vec![
+                "attestation pending".to_string(),
+                "Pending attestation".to_string(),
+            ],
+        )]))
+    }
+
+    #[test]
+    fn synonyms_share_a_canonical_code() {
+        let normalizer = normalizer();
+        assert_eq!(
+            normalizer.normalize("attestation pending"),
+            "attestation.pending"
+        );
+        assert_eq!(
+            normalizer.normalize("pending  ATTESTATION"),
+            "attestation.pending"
+        );
+        assert_eq!(
+            normalizer.normalize("attestation.pending"),
+            "attestation.pending"
+        );
+    }
+
+    #[test]
+    fn unmapped_reasons_pass_through_unchanged() {
+        let normalizer = normalizer();
+        assert_eq!(
+            normalizer.normalize("trust.lifecycle_state=quarantined"),
+            "trust.lifecycle_state=quarantined"
+        );
+        assert_eq!(
+            VetoReasonNormalizer::default().normalize("Attestation Pending"),
+            "Attestation Pending"
+        );
+    }
+}
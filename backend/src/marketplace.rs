@@ -69,6 +69,10 @@ pub fn routes() -> Router {
             "/api/marketplace/providers/:provider_id/promotions/:promotion_id/transition",
             post(transition_promotion_gate),
         )
+        .route(
+            "/api/marketplace/servers/:server_id/publish",
+            post(publish_listing),
+        )
 }
 
 pub fn subscribe_marketplace_events() -> broadcast::Receiver<ProviderMarketplaceStreamEvent> {
@@ -1205,6 +1209,394 @@ async fn ensure_provider_marketplace_eligible(
     })
 }
 
+// key: marketplace-publish -> readiness gate (build, intelligence, trust)
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MarketplaceListing {
+    pub server_id: i32,
+    pub build_artifact_run_id: Option<i32>,
+    pub manifest_digest: Option<String>,
+    pub manifest_tag: Option<String>,
+    pub registry_image: Option<String>,
+    pub readiness: Value,
+    pub published_by: Option<i32>,
+    pub published_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListingCriterion {
+    pub criterion: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListingCapabilityScore {
+    pub capability: String,
+    pub tier: Option<String>,
+    pub score: f32,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListingReadinessSignals {
+    pub build_status: Option<String>,
+    pub capability_scores: Vec<ListingCapabilityScore>,
+    pub trust_lifecycle_state: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ListingReadinessThresholds {
+    /// Global score floor; `None` defers to `intelligence::minimum_threshold`.
+    pub min_score: Option<f32>,
+    pub min_confidence: f32,
+}
+
+impl ListingReadinessThresholds {
+    pub fn from_config() -> Self {
+        Self {
+            min_score: *crate::config::MARKETPLACE_PUBLISH_MIN_SCORE,
+            min_confidence: *crate::config::MARKETPLACE_PUBLISH_MIN_CONFIDENCE,
+        }
+    }
+
+    fn score_floor(&self, capability: &str, tier: Option<&str>) -> f32 {
+        self.min_score
+            .unwrap_or_else(|| crate::intelligence::minimum_threshold(capability, tier))
+    }
+}
+
+/// Evaluate every publish criterion so callers can report all failures at once.
+pub fn evaluate_listing_readiness(
+    signals: &ListingReadinessSignals,
+    thresholds: &ListingReadinessThresholds,
+) -> Vec<ListingCriterion> {
+    let mut criteria = Vec::new();
+
+    criteria.push(match signals.build_status.as_deref() {
+        Some(status) => ListingCriterion {
+            criterion: "build".into(),
+            passed: matches_success(status),
+            detail: format!("latest build status {status}"),
+        },
+        None => ListingCriterion {
+            criterion: "build".into(),
+            passed: false,
+            detail: "no build artifact runs recorded".into(),
+        },
+    });
+
+    if signals.capability_scores.is_empty() {
+        criteria.push(ListingCriterion {
+            criterion: "intelligence".into(),
+            passed: false,
+            detail: "no capability intelligence scores recorded".into(),
+        });
+    }
+    for score in &signals.capability_scores {
+        let floor = thresholds.score_floor(&score.capability, score.tier.as_deref());
+        let passed = score.score >= floor && score.confidence >= thresholds.min_confidence;
+        criteria.push(ListingCriterion {
+            criterion: format!("intelligence:{}", score.capability),
+            passed,
+            detail: format!(
+                "score {:.1} (min {:.1}), confidence {:.2} (min {:.2})",
+                score.score, floor, score.confidence, thresholds.min_confidence
+            ),
+        });
+    }
+
+    criteria.push(match signals.trust_lifecycle_state.as_deref() {
+        Some(state) => ListingCriterion {
+            criterion: "trust".into(),
+            passed: state == "trusted",
+            detail: format!("vm trust lifecycle state {state}"),
+        },
+        None => ListingCriterion {
+            criterion: "trust".into(),
+            passed: false,
+            detail: "no attested vm instance".into(),
+        },
+    });
+
+    criteria
+}
+
+#[derive(sqlx::FromRow)]
+struct ListingBuildRow {
+    id: i32,
+    status: String,
+    manifest_digest: Option<String>,
+    manifest_tag: Option<String>,
+    registry_image: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ListingScoreRow {
+    capability: String,
+    tier: Option<String>,
+    score: f32,
+    confidence: f32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PublishListingRequest {
+    /// Permit publishing a manifest tag whose semver is not newer than the listing's.
+    #[serde(default)]
+    pub allow_downgrade: bool,
+}
+
+/// Semantic version parsed from a manifest tag (`v` prefix and build metadata tolerated).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListingVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre_release: Vec<String>,
+}
+
+impl ListingVersion {
+    pub fn parse(tag: &str) -> Option<Self> {
+        let trimmed = tag.trim();
+        let trimmed = trimmed.strip_prefix('v').unwrap_or(trimmed);
+        let without_build = trimmed.split('+').next()?;
+        let (core, pre) = match without_build.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (without_build, None),
+        };
+        let mut parts = core.split('.');
+        let major = parse_version_number(parts.next()?)?;
+        let minor = parse_version_number(parts.next()?)?;
+        let patch = parse_version_number(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+        let pre_release = match pre {
+            Some(pre) => {
+                let identifiers: Vec<String> = pre.split('.').map(str::to_string).collect();
+                if identifiers.iter().any(|id| {
+                    id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                }) {
+                    return None;
+                }
+                identifiers
+            }
+            None => Vec::new(),
+        };
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre_release,
+        })
+    }
+}
+
+fn parse_version_number(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if value.len() > 1 && value.starts_with('0') {
+        return None;
+    }
+    value.parse().ok()
+}
+
+impl Ord for ListingVersion {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| {
+                // A release outranks any of its pre-releases.
+                match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => {
+                        for (left, right) in self.pre_release.iter().zip(&other.pre_release) {
+                            let ordering = match (left.parse::<u64>(), right.parse::<u64>()) {
+                                (Ok(l), Ok(r)) => l.cmp(&r),
+                                (Ok(_), Err(_)) => Ordering::Less,
+                                (Err(_), Ok(_)) => Ordering::Greater,
+                                (Err(_), Err(_)) => left.cmp(right),
+                            };
+                            if ordering != Ordering::Equal {
+                                return ordering;
+                            }
+                        }
+                        self.pre_release.len().cmp(&other.pre_release.len())
+                    }
+                }
+            })
+    }
+}
+
+impl PartialOrd for ListingVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reject a listing update whose semver tag does not move past the published one.
+/// Tags that do not parse as semver on either side skip the check.
+pub fn check_listing_version(
+    published_tag: Option<&str>,
+    next_tag: Option<&str>,
+    allow_downgrade: bool,
+) -> Result<(), String> {
+    if allow_downgrade {
+        return Ok(());
+    }
+    let (Some(published_tag), Some(next_tag)) = (published_tag, next_tag) else {
+        return Ok(());
+    };
+    let (Some(published), Some(next)) = (
+        ListingVersion::parse(published_tag),
+        ListingVersion::parse(next_tag),
+    ) else {
+        return Ok(());
+    };
+    if next > published {
+        Ok(())
+    } else {
+        Err(format!(
+            "manifest_tag {next_tag} is not newer than published version {published_tag}; set allow_downgrade to override"
+        ))
+    }
+}
+
+async fn publish_listing(
+    Extension(pool): Extension<PgPool>,
+    Path(server_id): Path<i32>,
+    user: AuthUser,
+    request: Option<Json<PublishListingRequest>>,
+) -> AppResult<Json<MarketplaceListing>> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let owner_id: Option<i32> =
+        sqlx::query_scalar("SELECT owner_id FROM mcp_servers WHERE id = $1")
+            .bind(server_id)
+            .fetch_optional(&pool)
+            .await?;
+    let Some(owner_id) = owner_id else {
+        return Err(AppError::NotFound);
+    };
+    if owner_id != user.user_id && user.role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+
+    let build = sqlx::query_as::<_, ListingBuildRow>(
+        r#"
+        SELECT id, status, manifest_digest, manifest_tag, registry_image
+        FROM build_artifact_runs
+        WHERE server_id = $1
+        ORDER BY completed_at DESC NULLS LAST, id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(server_id)
+    .fetch_optional(&pool)
+    .await?;
+
+    let scores = sqlx::query_as::<_, ListingScoreRow>(
+        r#"
+        SELECT capability, tier, score::float4 AS score, confidence::float4 AS confidence
+        FROM capability_intelligence_scores
+        WHERE server_id = $1
+        ORDER BY capability ASC
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let trust_lifecycle_state: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT registry.lifecycle_state
+        FROM runtime_vm_instances instances
+        JOIN runtime_vm_trust_registry registry
+            ON registry.runtime_vm_instance_id = instances.id
+        WHERE instances.server_id = $1
+        ORDER BY registry.updated_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(server_id)
+    .fetch_optional(&pool)
+    .await?;
+
+    let signals = ListingReadinessSignals {
+        build_status: build.as_ref().map(|row| row.status.clone()),
+        capability_scores: scores
+            .into_iter()
+            .map(|row| ListingCapabilityScore {
+                capability: row.capability,
+                tier: row.tier,
+                score: row.score,
+                confidence: row.confidence,
+            })
+            .collect(),
+        trust_lifecycle_state,
+    };
+    let criteria = evaluate_listing_readiness(&signals, &ListingReadinessThresholds::from_config());
+    let failed: Vec<&ListingCriterion> = criteria.iter().filter(|c| !c.passed).collect();
+    if !failed.is_empty() {
+        return Err(AppError::JsonBadRequest(json!({
+            "error": "marketplace readiness gate failed",
+            "failed": failed,
+            "criteria": criteria,
+        })));
+    }
+    // The build criterion passed, so a run is present.
+    let Some(build) = build else {
+        return Err(AppError::NotFound);
+    };
+
+    let published_tag: Option<Option<String>> =
+        sqlx::query_scalar("SELECT manifest_tag FROM marketplace_listings WHERE server_id = $1")
+            .bind(server_id)
+            .fetch_optional(&pool)
+            .await?;
+    check_listing_version(
+        published_tag.flatten().as_deref(),
+        build.manifest_tag.as_deref(),
+        request.allow_downgrade,
+    )
+    .map_err(AppError::Conflict)?;
+
+    let listing = sqlx::query_as::<_, MarketplaceListing>(
+        r#"
+        INSERT INTO marketplace_listings (
+            server_id, build_artifact_run_id, manifest_digest, manifest_tag,
+            registry_image, readiness, published_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (server_id) DO UPDATE SET
+            build_artifact_run_id = EXCLUDED.build_artifact_run_id,
+            manifest_digest = EXCLUDED.manifest_digest,
+            manifest_tag = EXCLUDED.manifest_tag,
+            registry_image = EXCLUDED.registry_image,
+            readiness = EXCLUDED.readiness,
+            published_by = EXCLUDED.published_by,
+            updated_at = NOW()
+        RETURNING server_id, build_artifact_run_id, manifest_digest, manifest_tag,
+                  registry_image, readiness, published_by, published_at, updated_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(build.id)
+    .bind(build.manifest_digest)
+    .bind(build.manifest_tag)
+    .bind(build.registry_image)
+    .bind(json!(criteria))
+    .bind(user.user_id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(listing))
+}
+
 pub(crate) fn derive_health(
     status: &str,
     run_health: &str,
@@ -1334,6 +1726,80 @@ mod tests {
         assert!(tier.starts_with("gold:"));
     }
 
+    fn ready_signals() -> ListingReadinessSignals {
+        ListingReadinessSignals {
+            build_status: Some("succeeded".into()),
+            capability_scores: vec![
+                ListingCapabilityScore {
+                    capability: "runtime".into(),
+                    tier: None,
+                    score: 82.0,
+                    confidence: 0.9,
+                },
+                ListingCapabilityScore {
+                    capability: "image-build".into(),
+                    tier: None,
+                    score: 74.0,
+                    confidence: 0.8,
+                },
+            ],
+            trust_lifecycle_state: Some("trusted".into()),
+        }
+    }
+
+    #[test]
+    fn listing_version_allows_forward_bump() {
+        assert!(check_listing_version(Some("v1.2.3"), Some("v1.3.0"), false).is_ok());
+        assert!(check_listing_version(Some("1.3.0-rc.1"), Some("1.3.0"), false).is_ok());
+        assert!(check_listing_version(None, Some("0.1.0"), false).is_ok());
+    }
+
+    #[test]
+    fn listing_version_rejects_downgrade() {
+        let error = check_listing_version(Some("2.0.0"), Some("1.9.9"), false).unwrap_err();
+        assert!(error.contains("not newer than published version 2.0.0"));
+        assert!(check_listing_version(Some("1.0.0"), Some("1.0.0"), false).is_err());
+        assert!(check_listing_version(Some("1.0.0"), Some("1.0.0-beta.2"), false).is_err());
+    }
+
+    #[test]
+    fn listing_version_downgrade_allowed_with_override() {
+        assert!(check_listing_version(Some("2.0.0"), Some("1.9.9"), true).is_ok());
+    }
+
+    #[test]
+    fn listing_version_skips_non_semver_tags() {
+        assert!(check_listing_version(Some("latest"), Some("1.0.0"), false).is_ok());
+        assert!(check_listing_version(Some("2.0.0"), Some("nightly-2024"), false).is_ok());
+        assert_eq!(ListingVersion::parse("1.02.0"), None);
+    }
+
+    #[test]
+    fn listing_readiness_passes_all_gates() {
+        let thresholds = ListingReadinessThresholds {
+            min_score: None,
+            min_confidence: 0.5,
+        };
+        let criteria = evaluate_listing_readiness(&ready_signals(), &thresholds);
+        assert_eq!(criteria.len(), 4);
+        assert!(criteria.iter().all(|criterion| criterion.passed));
+    }
+
+    #[test]
+    fn listing_readiness_reports_intelligence_threshold_failure() {
+        let thresholds = ListingReadinessThresholds {
+            min_score: Some(80.0),
+            min_confidence: 0.5,
+        };
+        let criteria = evaluate_listing_readiness(&ready_signals(), &thresholds);
+        let failed: Vec<&str> = criteria
+            .iter()
+            .filter(|criterion| !criterion.passed)
+            .map(|criterion| criterion.criterion.as_str())
+            .collect();
+        assert_eq!(failed, vec!["intelligence:image-build"]);
+    }
+
     #[tokio::test]
     async fn submission_flow_persists_events() -> Result<()> {
         let database_url = match std::env::var("DATABASE_URL") {
@@ -1383,6 +1849,7 @@ mod tests {
                     attestation_digest: Some(attestation.clone()),
                     attestation_signature: Some(attestation),
                     rotation_due_at: None,
+                    organization_id: None,
                 },
             )
             .await?;
@@ -0,0 +1,267 @@
+use crate::config;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+// key: metrics-access -> optional bearer token and source allowlist for /metrics
+
+/// An allowed source: a single address, or a network when written as `addr/prefix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllowedSource {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl AllowedSource {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value, None),
+        };
+        let network: IpAddr = address
+            .parse()
+            .map_err(|_| format!("invalid address `{value}`"))?;
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| format!("invalid prefix length in `{value}`"))?,
+            None => max_prefix,
+        };
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, address: IpAddr) -> bool {
+        // an IPv4 client reaching a dual-stack listener shows up as ::ffff:a.b.c.d
+        let address = match address {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(address),
+            v4 => v4,
+        };
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Who may scrape `/metrics`. With neither a token nor an allowlist the endpoint is open.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsAccess {
+    bearer_token: Option<Arc<str>>,
+    allowed_sources: Arc<[AllowedSource]>,
+}
+
+impl MetricsAccess {
+    pub fn new(bearer_token: Option<String>, allowed_sources: Vec<AllowedSource>) -> Self {
+        Self {
+            bearer_token: bearer_token.map(Arc::from),
+            allowed_sources: allowed_sources.into(),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(
+            config::METRICS_BEARER_TOKEN.clone(),
+            config::METRICS_ALLOWED_SOURCES.clone(),
+        )
+    }
+
+    fn source_allowed(&self, peer: Option<IpAddr>) -> bool {
+        if self.allowed_sources.is_empty() {
+            return true;
+        }
+        peer.is_some_and(|peer| {
+            self.allowed_sources
+                .iter()
+                .any(|source| source.contains(peer))
+        })
+    }
+
+    fn token_accepted<B>(&self, request: &Request<B>) -> bool {
+        let Some(expected) = self.bearer_token.as_deref() else {
+            return true;
+        };
+        request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|presented| constant_time_eq(presented.as_bytes(), expected.as_bytes()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Answers 403 when the peer is outside the allowlist and 401 when the bearer token is
+/// missing or wrong. The peer address comes from `ConnectInfo`; without it an allowlist
+/// rejects the request.
+pub async fn guard_metrics<B>(
+    State(access): State<MetricsAccess>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if !access.source_allowed(peer) {
+        tracing::warn!(?peer, "rejected /metrics scrape from outside the allowlist");
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if !access.token_accepted(&request) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(access: MetricsAccess) -> Router {
+        Router::new()
+            .route("/metrics", get(|| async { "mcp_up 1" }))
+            .route_layer(middleware::from_fn_with_state(access, guard_metrics))
+    }
+
+    fn scrape(peer: Option<&str>, token: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/metrics");
+        if let Some(peer) = peer {
+            let addr: SocketAddr = peer.parse().unwrap();
+            builder = builder.extension(ConnectInfo(addr));
+        }
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn status(access: &MetricsAccess, request: Request<Body>) -> StatusCode {
+        app(access.clone()).oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn open_by_default() {
+        let access = MetricsAccess::default();
+        assert_eq!(status(&access, scrape(None, None)).await, StatusCode::OK);
+        assert_eq!(
+            status(&access, scrape(Some("203.0.113.9:5000"), None)).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn bearer_token_gates_access() {
+        let access = MetricsAccess::new(Some("scrape-secret".into()), Vec::new());
+        assert_eq!(
+            status(&access, scrape(None, None)).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&access, scrape(None, Some("wrong"))).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&access, scrape(None, Some("scrape-secret"))).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn allowlist_rejects_other_sources() {
+        let access = MetricsAccess::new(
+            None,
+            vec![
+                AllowedSource::parse("10.0.0.0/8").unwrap(),
+                AllowedSource::parse("192.0.2.7").unwrap(),
+            ],
+        );
+        assert_eq!(
+            status(&access, scrape(Some("10.20.30.40:9000"), None)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&access, scrape(Some("192.0.2.7:9000"), None)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&access, scrape(Some("[::ffff:10.1.1.1]:9000"), None)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&access, scrape(Some("192.0.2.8:9000"), None)).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&access, scrape(None, None)).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn allowlist_is_checked_before_the_token() {
+        let access = MetricsAccess::new(
+            Some("scrape-secret".into()),
+            vec![AllowedSource::parse("10.0.0.0/8").unwrap()],
+        );
+        assert_eq!(
+            status(
+                &access,
+                scrape(Some("172.16.0.1:9000"), Some("scrape-secret"))
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&access, scrape(Some("10.0.0.1:9000"), None)).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(
+                &access,
+                scrape(Some("10.0.0.1:9000"), Some("scrape-secret"))
+            )
+            .await,
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn parses_addresses_and_networks() {
+        assert!(AllowedSource::parse("0.0.0.0/0")
+            .unwrap()
+            .contains("8.8.8.8".parse().unwrap()));
+        assert!(AllowedSource::parse("fd00::/8")
+            .unwrap()
+            .contains("fd12::1".parse().unwrap()));
+        assert!(AllowedSource::parse("10.0.0.0/33").is_err());
+        assert!(AllowedSource::parse("metrics.internal").is_err());
+    }
+}
@@ -1,13 +1,17 @@
 // key: organizations-api -> self-service-onboarding
 use crate::error::{AppError, AppResult};
 use crate::extractor::AuthUser;
-use axum::extract::Path;
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Path};
+use axum::http::request::Parts;
 use axum::{
     routing::{get, post},
     Extension, Json, Router,
 };
 use chrono::{DateTime, Utc};
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::marker::PhantomData;
 use uuid::Uuid;
 
 #[derive(serde::Deserialize)]
@@ -100,6 +104,9 @@ pub async fn create_org(
 #[derive(serde::Deserialize)]
 pub struct AddMemberPayload {
     pub user_id: i32,
+    /// Optional role to grant; existing members keep their role when omitted.
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 pub async fn add_member(
@@ -109,11 +116,20 @@ pub async fn add_member(
     Json(payload): Json<AddMemberPayload>,
 ) -> AppResult<()> {
     ensure_owner(&pool, id, user_id).await?;
+    let role = match payload.role.as_deref() {
+        Some(value) => Some(
+            OrgRole::parse(value)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown role {value}")))?,
+        ),
+        None => None,
+    };
     sqlx::query(
-        "INSERT INTO organization_members (organization_id, user_id) VALUES ($1,$2) ON CONFLICT DO NOTHING"
+        "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, COALESCE($3, 'member')) \
+         ON CONFLICT (organization_id, user_id) DO UPDATE SET role = COALESCE($3, organization_members.role)"
     )
     .bind(id)
     .bind(payload.user_id)
+    .bind(role.map(|r| r.as_str()))
     .execute(&pool)
     .await
     .map_err(|e| { tracing::error!(?e, "DB error adding member"); AppError::Db(e) })?;
@@ -279,8 +295,65 @@ pub async fn accept_invitation(
     Ok(Json(invite))
 }
 
+pub(crate) async fn ensure_member(
+    pool: &PgPool,
+    organization_id: i32,
+    user_id: i32,
+) -> AppResult<()> {
+    require_org_role(pool, organization_id, user_id, OrgRole::Viewer).await?;
+    Ok(())
+}
+
 async fn ensure_owner(pool: &PgPool, organization_id: i32, user_id: i32) -> AppResult<()> {
-    let rec = sqlx::query(
+    require_org_role(pool, organization_id, user_id, OrgRole::Owner).await?;
+    Ok(())
+}
+
+/// key: organizations-roles -> membership role ladder
+///
+/// Roles are ordered so a guard can ask for a minimum: every role satisfies
+/// the checks of the roles below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    Viewer,
+    Member,
+    Admin,
+    Owner,
+}
+
+impl OrgRole {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Some(Self::Viewer),
+            "member" => Some(Self::Member),
+            "admin" => Some(Self::Admin),
+            "owner" => Some(Self::Owner),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Member => "member",
+            Self::Admin => "admin",
+            Self::Owner => "owner",
+        }
+    }
+
+    pub fn satisfies(&self, minimum: OrgRole) -> bool {
+        *self >= minimum
+    }
+}
+
+/// Resolve the caller's role within an organization, `None` when not a member.
+pub async fn member_role(
+    pool: &PgPool,
+    organization_id: i32,
+    user_id: i32,
+) -> AppResult<Option<OrgRole>> {
+    let role: Option<String> = sqlx::query_scalar(
         "SELECT role FROM organization_members WHERE organization_id=$1 AND user_id=$2",
     )
     .bind(organization_id)
@@ -288,15 +361,114 @@ async fn ensure_owner(pool: &PgPool, organization_id: i32, user_id: i32) -> AppR
     .fetch_optional(pool)
     .await
     .map_err(|e| {
-        tracing::error!(?e, "DB error verifying organization owner");
+        tracing::error!(?e, "DB error verifying organization membership");
         AppError::Db(e)
     })?;
-    let Some(row) = rec else {
-        return Err(AppError::Forbidden);
-    };
-    let role: String = row.get("role");
-    if role != "owner" {
-        return Err(AppError::Forbidden);
+    Ok(role.as_deref().and_then(OrgRole::parse))
+}
+
+/// Fail with `Forbidden` unless the caller holds at least `minimum` in the organization.
+pub async fn require_org_role(
+    pool: &PgPool,
+    organization_id: i32,
+    user_id: i32,
+    minimum: OrgRole,
+) -> AppResult<OrgRole> {
+    match member_role(pool, organization_id, user_id).await? {
+        Some(role) if role.satisfies(minimum) => Ok(role),
+        _ => Err(AppError::Forbidden),
+    }
+}
+
+/// Marker selecting the minimum role an [`OrgAccess`] guard enforces.
+pub trait MinimumOrgRole: Send + Sync + 'static {
+    const ROLE: OrgRole;
+}
+
+pub struct ViewerRole;
+pub struct MemberRole;
+pub struct AdminRole;
+pub struct OwnerRole;
+
+impl MinimumOrgRole for ViewerRole {
+    const ROLE: OrgRole = OrgRole::Viewer;
+}
+impl MinimumOrgRole for MemberRole {
+    const ROLE: OrgRole = OrgRole::Member;
+}
+impl MinimumOrgRole for AdminRole {
+    const ROLE: OrgRole = OrgRole::Admin;
+}
+impl MinimumOrgRole for OwnerRole {
+    const ROLE: OrgRole = OrgRole::Owner;
+}
+
+/// Route guard resolving the organization from the `:organization_id` (or `:id`)
+/// path segment and rejecting callers below the role selected by `R`.
+pub struct OrgAccess<R: MinimumOrgRole> {
+    pub user_id: i32,
+    pub organization_id: i32,
+    pub role: OrgRole,
+    _minimum: PhantomData<fn() -> R>,
+}
+
+#[async_trait]
+impl<S, R> FromRequestParts<S> for OrgAccess<R>
+where
+    S: Send + Sync,
+    R: MinimumOrgRole,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::Unauthorized)?;
+        let Extension(pool) = Extension::<PgPool>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::Message("database pool unavailable".into()))?;
+        let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::BadRequest("organization id required".into()))?;
+        let organization_id = params
+            .get("organization_id")
+            .or_else(|| params.get("id"))
+            .and_then(|value| value.parse::<i32>().ok())
+            .ok_or_else(|| AppError::BadRequest("organization id required".into()))?;
+        let role = require_org_role(&pool, organization_id, user.user_id, R::ROLE).await?;
+        Ok(Self {
+            user_id: user.user_id,
+            organization_id,
+            role,
+            _minimum: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrgRole;
+
+    #[test]
+    fn roles_satisfy_lower_minimums() {
+        assert!(OrgRole::Owner.satisfies(OrgRole::Admin));
+        assert!(OrgRole::Admin.satisfies(OrgRole::Viewer));
+        assert!(OrgRole::Member.satisfies(OrgRole::Member));
+        assert!(!OrgRole::Viewer.satisfies(OrgRole::Member));
+        assert!(!OrgRole::Member.satisfies(OrgRole::Admin));
+    }
+
+    #[test]
+    fn parse_round_trips_known_roles() {
+        for role in [
+            OrgRole::Viewer,
+            OrgRole::Member,
+            OrgRole::Admin,
+            OrgRole::Owner,
+        ] {
+            assert_eq!(OrgRole::parse(role.as_str()), Some(role));
+        }
+        assert_eq!(OrgRole::parse(" Admin "), Some(OrgRole::Admin));
+        assert_eq!(OrgRole::parse("operator"), None);
     }
-    Ok(())
 }
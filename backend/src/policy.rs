@@ -7,8 +7,9 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use axum::{
-    extract::Query,
+    extract::{Extension, Query},
     response::sse::{Event, Sse},
+    Json,
 };
 use chrono::{DateTime, Duration, Utc};
 use futures_util::StreamExt;
@@ -22,9 +23,15 @@ use tokio_stream::wrappers::BroadcastStream;
 
 use crate::billing::BillingService;
 use crate::config;
+use crate::db::mcp_servers;
+use crate::db::runtime_policy_audit::{
+    insert_audit_entry, list_for_server as list_audit_entries, NewRuntimePolicyAudit,
+    RuntimePolicyAuditRecord,
+};
 use crate::db::runtime_vm_trust_history::{
     latest_for_instance as latest_trust_event, RuntimeVmTrustEvent,
 };
+use crate::error::{AppError, AppResult};
 use crate::evaluations::{self, CertificationStatus};
 use crate::extractor::AuthUser;
 use crate::governance::GovernanceEngine;
@@ -130,6 +137,34 @@ pub async fn stream_policy_events(
     Sse::new(stream)
 }
 
+const POLICY_AUDIT_DEFAULT_LIMIT: i64 = 50;
+const POLICY_AUDIT_MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct PolicyAuditParams {
+    pub server_id: i32,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+/// Audit trail of placement decisions for one of the caller's servers, newest first.
+pub async fn list_policy_decisions(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Query(params): Query<PolicyAuditParams>,
+) -> AppResult<Json<Vec<RuntimePolicyAuditRecord>>> {
+    if !mcp_servers::is_live_owned_by(&pool, params.server_id, user_id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    let limit = params
+        .limit
+        .unwrap_or(POLICY_AUDIT_DEFAULT_LIMIT)
+        .clamp(1, POLICY_AUDIT_MAX_LIMIT);
+    let entries = list_audit_entries(&pool, params.server_id, limit).await?;
+    Ok(Json(entries))
+}
+
 impl PolicyEvent {
     pub fn decision(
         owner_id: i32,
@@ -307,6 +342,56 @@ pub struct PolicyDecision {
     pub provider_key_posture: Option<ProviderKeyDecisionPosture>,
 }
 
+impl PolicyDecision {
+    /// Reasons this decision blocks a launch, in the order the runtime checks them. Empty
+    /// when the policy allows the placement.
+    pub fn veto_reasons(&self) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if let Some(posture) = self
+            .provider_key_posture
+            .as_ref()
+            .filter(|posture| posture.vetoed)
+        {
+            if posture.notes.is_empty() {
+                reasons.push("provider-key:veto".to_string());
+            }
+            reasons.extend(
+                posture
+                    .notes
+                    .iter()
+                    .map(|note| format!("provider-key:{note}")),
+            );
+        }
+        if self.governance_required {
+            reasons.push("governance:required".to_string());
+            reasons.extend(self.promotion_notes.iter().cloned());
+        }
+        if !self.capabilities_satisfied {
+            reasons.push(format!(
+                "capabilities:unsatisfied:{}",
+                self.capability_requirements
+                    .iter()
+                    .map(RuntimeCapability::as_str)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+        reasons
+    }
+
+    /// `required` when governance blocks the launch, `approved` when a governance run
+    /// cleared it, otherwise `not-required`.
+    pub fn governance_verdict(&self) -> &'static str {
+        if self.governance_required {
+            "required"
+        } else if self.governance_run_id.is_some() {
+            "approved"
+        } else {
+            "not-required"
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum PolicyError {
     #[error("database error: {0}")]
@@ -343,6 +428,19 @@ impl RuntimePolicyEngine {
         executors.insert(descriptor.backend, descriptor);
     }
 
+    /// Backends with a registered executor, sorted by name.
+    pub async fn registered_backends(&self) -> Vec<&'static str> {
+        let mut backends: Vec<&'static str> = self
+            .executors
+            .read()
+            .await
+            .keys()
+            .map(|backend| backend.as_str())
+            .collect();
+        backends.sort();
+        backends
+    }
+
     pub async fn executor_descriptor(
         &self,
         backend: RuntimeBackend,
@@ -383,6 +481,8 @@ impl RuntimePolicyEngine {
             )
             .await?;
         let decision_id = self.record_decision(pool, server_id, &decision).await?;
+        self.record_audit(pool, server_id, decision_id, &decision)
+            .await;
 
         job_queue::enqueue_intelligence_refresh(pool, server_id).await;
 
@@ -412,6 +512,38 @@ impl RuntimePolicyEngine {
         Ok(decision)
     }
 
+    /// Evaluates the policy for a prospective launch without recording the decision,
+    /// publishing events, or queueing follow-up work.
+    pub async fn preview(
+        &self,
+        pool: &PgPool,
+        server_id: i32,
+        server_type: &str,
+        config: Option<&Value>,
+        use_gpu: bool,
+    ) -> Result<PolicyDecision, PolicyError> {
+        let organization_id: Option<Option<i32>> =
+            sqlx::query_scalar("SELECT organization_id FROM mcp_servers WHERE id = $1")
+                .bind(server_id)
+                .fetch_optional(pool)
+                .await?;
+        let Some(organization_id) = organization_id else {
+            return Err(PolicyError::Database(sqlx::Error::RowNotFound));
+        };
+
+        let (decision, _) = self
+            .evaluate(
+                pool,
+                server_id,
+                organization_id,
+                server_type,
+                config,
+                use_gpu,
+            )
+            .await?;
+        Ok(decision)
+    }
+
     async fn evaluate(
         &self,
         pool: &PgPool,
@@ -1065,6 +1197,48 @@ impl RuntimePolicyEngine {
         Ok(row.get("id"))
     }
 
+    /// Best-effort: a failed audit write is logged and never blocks the placement.
+    async fn record_audit(
+        &self,
+        pool: &PgPool,
+        server_id: i32,
+        decision_id: i32,
+        decision: &PolicyDecision,
+    ) {
+        let executors_considered: Vec<String> = self
+            .registered_backends()
+            .await
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let veto_reasons = decision.veto_reasons();
+
+        if let Err(err) = insert_audit_entry(
+            pool,
+            NewRuntimePolicyAudit {
+                server_id,
+                decision_id: Some(decision_id),
+                candidate_backend: decision.candidate_backend.as_str(),
+                backend: decision.backend.as_str(),
+                executor_name: decision.executor_name.as_deref(),
+                executors_considered: &executors_considered,
+                governance_verdict: decision.governance_verdict(),
+                allowed: veto_reasons.is_empty(),
+                veto_reasons: &veto_reasons,
+                policy_version: &decision.policy_version,
+            },
+        )
+        .await
+        {
+            tracing::warn!(
+                ?err,
+                %server_id,
+                decision_id,
+                "failed to record runtime policy audit entry",
+            );
+        }
+    }
+
     async fn select_backend(
         &self,
         candidate: RuntimeBackend,
@@ -1845,6 +2019,7 @@ mod tests {
                     attestation_digest: Some(attestation.clone()),
                     attestation_signature: Some(attestation.clone()),
                     rotation_due_at: Some(Utc::now() + Duration::days(30)),
+                    organization_id: None,
                 },
             )
             .await?;
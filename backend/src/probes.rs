@@ -0,0 +1,186 @@
+use crate::config;
+use crate::policy::RuntimePolicyEngine;
+use axum::{extract::Extension, http::StatusCode, routing::get, Json, Router};
+use migrations::{MigrationReport, MigrationStatus};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+// key: probes -> kubernetes liveness and readiness endpoints
+
+pub mod migrations;
+pub mod startup;
+
+/// Migrations bundled into the binary; applied at startup.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
+
+/// Liveness: answering at all means the server loop is running.
+pub async fn healthz() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    Ok,
+    /// Serving, but an operator should look; does not fail readiness.
+    Degraded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessState {
+    Ready,
+    Degraded,
+    NotReady,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckOutcome {
+    pub name: &'static str,
+    pub status: CheckState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub status: ReadinessState,
+    /// Names of the checks that did not pass.
+    pub failing: Vec<&'static str>,
+    /// Names of the checks that passed with a warning.
+    pub degraded: Vec<&'static str>,
+    pub checks: Vec<CheckOutcome>,
+}
+
+/// Readiness: the database answers, the configured runtime backend has an executor,
+/// and every bundled migration has been applied. Returns 503 naming failed checks.
+/// A migration failure tolerated at startup reports `degraded` but stays ready.
+pub async fn readyz(
+    Extension(pool): Extension<PgPool>,
+    Extension(policy_engine): Extension<Arc<RuntimePolicyEngine>>,
+    Extension(migration_status): Extension<MigrationStatus>,
+) -> (StatusCode, Json<Readiness>) {
+    let timeout = Duration::from_millis(*config::READINESS_CHECK_TIMEOUT_MS);
+    let database = bounded(timeout, check_database(&pool)).await;
+    let database_ok = database.is_ok();
+    let database = CheckOutcome::from_result("database", database);
+    let runtime = CheckOutcome::from_result("runtime", check_runtime(&policy_engine).await);
+    let migrations = if !migration_status.fully_applied {
+        CheckOutcome {
+            name: "migrations",
+            status: CheckState::Degraded,
+            error: Some(tolerated_failure(&migration_status)),
+        }
+    } else if database_ok {
+        CheckOutcome::from_result(
+            "migrations",
+            bounded(timeout, check_migrations(&pool)).await,
+        )
+    } else {
+        // without a connection the migration check can only repeat the database error
+        CheckOutcome::from_result("migrations", Err("skipped: database unreachable".into()))
+    };
+
+    let checks = vec![database, runtime, migrations];
+    let names = |state: CheckState| -> Vec<&'static str> {
+        checks
+            .iter()
+            .filter(|check| check.status == state)
+            .map(|check| check.name)
+            .collect()
+    };
+    let failing = names(CheckState::Failed);
+    let degraded = names(CheckState::Degraded);
+    let (code, status) = match (failing.is_empty(), degraded.is_empty()) {
+        (false, _) => (StatusCode::SERVICE_UNAVAILABLE, ReadinessState::NotReady),
+        (true, false) => (StatusCode::OK, ReadinessState::Degraded),
+        (true, true) => (StatusCode::OK, ReadinessState::Ready),
+    };
+    (
+        code,
+        Json(Readiness {
+            ready: failing.is_empty(),
+            status,
+            failing,
+            degraded,
+            checks,
+        }),
+    )
+}
+
+impl CheckOutcome {
+    fn from_result(name: &'static str, result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self {
+                name,
+                status: CheckState::Ok,
+                error: None,
+            },
+            Err(error) => Self {
+                name,
+                status: CheckState::Failed,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+fn tolerated_failure(report: &MigrationReport) -> String {
+    let error = report.error.as_deref().unwrap_or("unknown error");
+    match &report.failed {
+        Some(failed) => format!("migration {} failed at startup: {error}", failed.version),
+        None => format!("migrations failed at startup: {error}"),
+    }
+}
+
+async fn bounded<F>(timeout: Duration, check: F) -> Result<(), String>
+where
+    F: Future<Output = Result<(), String>>,
+{
+    tokio::time::timeout(timeout, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {}ms", timeout.as_millis())))
+}
+
+async fn check_database(pool: &PgPool) -> Result<(), String> {
+    sqlx::query("SELECT 1")
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+async fn check_migrations(pool: &PgPool) -> Result<(), String> {
+    let applied = migrations::applied_versions(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let pending = migrations::pending_versions(&MIGRATOR, &applied);
+    match pending.as_slice() {
+        [] => Ok(()),
+        [first, ..] => Err(format!(
+            "{} migration(s) pending, starting at {first}",
+            pending.len()
+        )),
+    }
+}
+
+async fn check_runtime(policy_engine: &RuntimePolicyEngine) -> Result<(), String> {
+    let backend = policy_engine.default_backend();
+    match policy_engine.executor_descriptor(backend).await {
+        Some(_) => Ok(()),
+        None => Err(format!("no executor registered for the {backend} backend")),
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use axum::{extract::Extension, Json};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// key: probes-migrations -> startup migration outcome for operators
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FailedMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+/// What happened when the server applied its migrations at startup. Only a tolerated
+/// failure (`ALLOW_MIGRATION_FAILURE`) leaves a running server with `fully_applied: false`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub fully_applied: bool,
+    pub failed: Option<FailedMigration>,
+    /// Bundled migration versions not recorded as applied.
+    pub pending: Vec<i64>,
+    pub error: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Shared with handlers through an `Extension`.
+pub type MigrationStatus = Arc<MigrationReport>;
+
+impl MigrationReport {
+    pub fn applied() -> Self {
+        Self {
+            fully_applied: true,
+            failed: None,
+            pending: Vec::new(),
+            error: None,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Builds the report for a failed run. Migrations apply in version order, so unless
+    /// the error names a version, the first one still pending is the one that failed.
+    pub async fn failed(pool: &PgPool, migrator: &Migrator, error: &MigrateError) -> Self {
+        let applied = applied_versions(pool).await.unwrap_or_else(|e| {
+            tracing::warn!(?e, "could not read applied migrations after failure");
+            Vec::new()
+        });
+        let pending = pending_versions(migrator, &applied);
+        let named = match error {
+            MigrateError::VersionMissing(version)
+            | MigrateError::VersionMismatch(version)
+            | MigrateError::Dirty(version) => Some(*version),
+            _ => pending.first().copied(),
+        };
+        let failed = named.map(|version| FailedMigration {
+            version,
+            description: migrator
+                .iter()
+                .find(|migration| migration.version == version)
+                .map(|migration| migration.description.to_string())
+                .unwrap_or_default(),
+        });
+        Self {
+            fully_applied: false,
+            failed,
+            pending,
+            error: Some(error.to_string()),
+            recorded_at: Utc::now(),
+        }
+    }
+}
+
+pub(crate) async fn applied_versions(pool: &PgPool) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+        .fetch_all(pool)
+        .await
+}
+
+pub(crate) fn pending_versions(migrator: &Migrator, applied: &[i64]) -> Vec<i64> {
+    migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .filter(|version| !applied.contains(version))
+        .collect()
+}
+
+/// GET /api/admin/migrations/status
+pub async fn migration_status(
+    Extension(status): Extension<MigrationStatus>,
+    AuthUser { role, .. }: AuthUser,
+) -> AppResult<Json<MigrationReport>> {
+    if role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+    Ok(Json(status.as_ref().clone()))
+}
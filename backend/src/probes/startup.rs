@@ -0,0 +1,301 @@
+use crate::config::{self, VmProvisionerDriver};
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use crate::policy::RuntimePolicyEngine;
+use axum::{extract::Extension, Json};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// key: probes-startup -> optional integration self-check, logged once at boot
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationState {
+    Configured,
+    /// Optional and not set up; the server runs without it.
+    Missing,
+    /// Set up but only partly usable.
+    Degraded,
+    /// Not relevant to the active runtime.
+    Disabled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationCheck {
+    pub name: &'static str,
+    pub status: IntegrationState,
+    pub detail: String,
+}
+
+/// The runtime the server ended up with, which can differ from the configured one when a
+/// backend fails to initialise.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeSetup {
+    pub configured: String,
+    pub active_backends: Vec<&'static str>,
+    pub fallback_reason: Option<String>,
+}
+
+impl RuntimeSetup {
+    pub async fn from_engine(
+        configured: &str,
+        engine: &RuntimePolicyEngine,
+        fallback_reason: Option<String>,
+    ) -> Self {
+        Self {
+            configured: configured.to_string(),
+            active_backends: engine.registered_backends().await,
+            fallback_reason,
+        }
+    }
+
+    fn vm_active(&self) -> bool {
+        self.active_backends.contains(&"virtual-machine")
+    }
+}
+
+/// Outcome of decoding `VM_ATTESTATION_TRUST_ROOTS`.
+#[derive(Debug, Clone, Default)]
+pub struct TrustRootSummary {
+    pub accepted: usize,
+    pub rejected: Vec<String>,
+}
+
+/// Registered vector databases; there is no process-wide vector store, so this is the
+/// closest signal of whether the integration is in use.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VectorDbInventory {
+    pub registered: i64,
+    pub with_endpoint: i64,
+}
+
+impl VectorDbInventory {
+    pub async fn load(pool: &PgPool) -> Result<Self, sqlx::Error> {
+        let (registered, with_endpoint): (i64, i64) =
+            sqlx::query_as("SELECT COUNT(*), COUNT(url) FROM vector_dbs")
+                .fetch_one(pool)
+                .await?;
+        Ok(Self {
+            registered,
+            with_endpoint,
+        })
+    }
+}
+
+/// What the server found at startup. Built once, logged, and served to admins unchanged.
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupReport {
+    pub generated_at: DateTime<Utc>,
+    pub runtime: RuntimeSetup,
+    pub integrations: Vec<IntegrationCheck>,
+}
+
+/// Shared with handlers through an `Extension`.
+pub type StartupStatus = Arc<StartupReport>;
+
+impl StartupReport {
+    /// `vector_dbs` is `None` when the inventory query failed.
+    pub fn collect(
+        runtime: RuntimeSetup,
+        trust_roots: &TrustRootSummary,
+        vector_dbs: Option<VectorDbInventory>,
+    ) -> Self {
+        let integrations = vec![
+            attestation_roots(&runtime, trust_roots),
+            registry_auth(),
+            libvirt(&runtime),
+            billing_adapter(),
+            vector_db(vector_dbs),
+        ];
+        Self {
+            generated_at: Utc::now(),
+            runtime,
+            integrations,
+        }
+    }
+
+    pub fn integration(&self, name: &str) -> Option<&IntegrationCheck> {
+        self.integrations.iter().find(|check| check.name == name)
+    }
+
+    /// One event for the runtime, then one per integration; anything not configured or
+    /// disabled logs at warn.
+    pub fn log(&self) {
+        tracing::info!(
+            configured = %self.runtime.configured,
+            active_backends = ?self.runtime.active_backends,
+            fallback_reason = self.runtime.fallback_reason.as_deref(),
+            "startup self-check: runtime"
+        );
+        for check in &self.integrations {
+            match check.status {
+                IntegrationState::Configured | IntegrationState::Disabled => tracing::info!(
+                    integration = check.name,
+                    status = ?check.status,
+                    detail = %check.detail,
+                    "startup self-check"
+                ),
+                IntegrationState::Missing | IntegrationState::Degraded => tracing::warn!(
+                    integration = check.name,
+                    status = ?check.status,
+                    detail = %check.detail,
+                    "startup self-check"
+                ),
+            }
+        }
+    }
+}
+
+fn check(
+    name: &'static str,
+    status: IntegrationState,
+    detail: impl Into<String>,
+) -> IntegrationCheck {
+    IntegrationCheck {
+        name,
+        status,
+        detail: detail.into(),
+    }
+}
+
+fn attestation_roots(runtime: &RuntimeSetup, roots: &TrustRootSummary) -> IntegrationCheck {
+    const NAME: &str = "attestation_roots";
+    let mut detail = match (roots.accepted, roots.rejected.len()) {
+        (0, 0) => "no trust roots configured; relying on evidence-provided keys".to_string(),
+        (0, rejected) => format!("all {rejected} configured trust roots were rejected"),
+        (accepted, 0) => format!("{accepted} trust roots loaded"),
+        (accepted, rejected) => format!("{accepted} trust roots loaded, {rejected} rejected"),
+    };
+    if !roots.rejected.is_empty() {
+        detail.push_str(&format!(" ({})", roots.rejected.join("; ")));
+    }
+    if !runtime.vm_active() {
+        detail.push_str("; virtual-machine runtime not active");
+    }
+    let status = match (roots.accepted, roots.rejected.len()) {
+        (0, _) => IntegrationState::Missing,
+        (_, 0) => IntegrationState::Configured,
+        _ => IntegrationState::Degraded,
+    };
+    check(NAME, status, detail)
+}
+
+fn registry_auth() -> IntegrationCheck {
+    const NAME: &str = "registry_auth";
+    let registry = std::env::var("REGISTRY")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let Some(registry) = registry else {
+        return check(
+            NAME,
+            IntegrationState::Disabled,
+            "REGISTRY not set; built images stay local",
+        );
+    };
+    let pull_secret = config::K8S_REGISTRY_SECRET_NAME
+        .as_deref()
+        .map(|name| format!("; pull secret {name}"))
+        .unwrap_or_default();
+    match config::REGISTRY_AUTH_DOCKERCONFIG.as_deref() {
+        Some(path) if std::path::Path::new(path).is_file() => check(
+            NAME,
+            IntegrationState::Configured,
+            format!("pushing to {registry} with credentials from {path}{pull_secret}"),
+        ),
+        Some(path) => check(
+            NAME,
+            IntegrationState::Degraded,
+            format!("REGISTRY_AUTH_DOCKERCONFIG points at {path}, which is not a readable file"),
+        ),
+        None => check(
+            NAME,
+            IntegrationState::Missing,
+            format!("pushing to {registry} without REGISTRY_AUTH_DOCKERCONFIG; relying on ambient docker credentials{pull_secret}"),
+        ),
+    }
+}
+
+fn libvirt(runtime: &RuntimeSetup) -> IntegrationCheck {
+    const NAME: &str = "libvirt";
+    if !runtime.vm_active() {
+        return check(
+            NAME,
+            IntegrationState::Disabled,
+            "virtual-machine runtime not active",
+        );
+    }
+    match *config::VM_PROVISIONER_DRIVER {
+        VmProvisionerDriver::Http => check(
+            NAME,
+            IntegrationState::Disabled,
+            "VM_PROVISIONER_DRIVER=http; provisioning through the hypervisor endpoint",
+        ),
+        VmProvisionerDriver::Libvirt if cfg!(feature = "libvirt-executor") => check(
+            NAME,
+            IntegrationState::Configured,
+            format!(
+                "connected to {}",
+                config::LIBVIRT_PROVISIONING_CONFIG.connection_uri
+            ),
+        ),
+        VmProvisionerDriver::Libvirt => check(
+            NAME,
+            IntegrationState::Missing,
+            "backend compiled without the libvirt-executor feature",
+        ),
+    }
+}
+
+fn billing_adapter() -> IntegrationCheck {
+    check(
+        "billing_adapter",
+        IntegrationState::Missing,
+        "reconciliation uses the stripe-like stub adapter; no billing provider is connected",
+    )
+}
+
+fn vector_db(inventory: Option<VectorDbInventory>) -> IntegrationCheck {
+    const NAME: &str = "vector_db";
+    match inventory {
+        None => check(
+            NAME,
+            IntegrationState::Degraded,
+            "could not read the vector database inventory",
+        ),
+        Some(VectorDbInventory { registered: 0, .. }) => check(
+            NAME,
+            IntegrationState::Missing,
+            "no vector databases registered",
+        ),
+        Some(VectorDbInventory {
+            registered,
+            with_endpoint,
+        }) if with_endpoint < registered => check(
+            NAME,
+            IntegrationState::Degraded,
+            format!(
+                "{registered} vector databases registered, {} without an endpoint",
+                registered - with_endpoint
+            ),
+        ),
+        Some(VectorDbInventory { registered, .. }) => check(
+            NAME,
+            IntegrationState::Configured,
+            format!("{registered} vector databases registered"),
+        ),
+    }
+}
+
+/// GET /api/admin/startup-report
+pub async fn startup_report(
+    Extension(report): Extension<StartupStatus>,
+    AuthUser { role, .. }: AuthUser,
+) -> AppResult<Json<StartupReport>> {
+    if role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+    Ok(Json(report.as_ref().clone()))
+}
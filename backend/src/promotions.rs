@@ -11,10 +11,15 @@ use serde_json::{json, Map, Value};
 use sqlx::{query_as, FromRow, PgPool, Postgres, QueryBuilder, Transaction};
 use tracing::error;
 
+use crate::artifacts::attestation::{load_attestation, trusted_keys, verify_envelope};
 use crate::error::{AppError, AppResult};
 use crate::extractor::AuthUser;
 use crate::governance::{GovernanceEngine, StartWorkflowRunRequest};
 
+pub mod auto_advance;
+pub mod rollback;
+pub mod tracks;
+
 // key: release-train -> promotion-tracks,governance-binding
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -26,6 +31,8 @@ pub struct PromotionTrack {
     pub stages: Vec<String>,
     pub description: Option<String>,
     pub workflow_id: Option<i32>,
+    /// Veto promotions of digests without a verified provenance attestation.
+    pub require_attestation: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -74,6 +81,37 @@ pub struct PromotionHistoryQuery {
     pub track_id: Option<i32>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromotionStageDwell {
+    pub stage: String,
+    pub reached: bool,
+    pub entered_at: Option<DateTime<Utc>>,
+    /// Seconds until the next reached stage; absent for skipped stages and the current stage.
+    pub dwell_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromotionDwellReport {
+    pub promotion_track_id: i32,
+    pub track_name: String,
+    pub manifest_digest: String,
+    pub built_at: Option<DateTime<Utc>>,
+    pub stages: Vec<PromotionStageDwell>,
+    /// Seconds from build (or first stage entry) to the final track stage, once reached.
+    pub lead_time_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct PromotionDwellRow {
+    promotion_track_id: i32,
+    track_name: String,
+    track_stages: Vec<String>,
+    manifest_digest: String,
+    stage: String,
+    updated_at: DateTime<Utc>,
+    built_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone)]
 struct ReleaseTrain {
     stages: Vec<String>,
@@ -84,6 +122,9 @@ struct ReleaseTrain {
 struct PromotionPostureSignals {
     artifact_status: Option<String>,
     credential_health_status: Option<String>,
+    /// `verified` or `invalid`; `None` when the digest has no attestation.
+    attestation_status: Option<String>,
+    attestation_error: Option<String>,
     trust_lifecycle_state: Option<String>,
     trust_attestation_status: Option<String>,
     trust_remediation_state: Option<String>,
@@ -129,6 +170,11 @@ impl ReleaseTrain {
         self.stages.iter().any(|item| item == &stage)
     }
 
+    fn position(&self, stage: &str) -> Option<usize> {
+        let stage = stage.to_lowercase();
+        self.stages.iter().position(|candidate| candidate == &stage)
+    }
+
     fn previous_stage(&self, stage: &str) -> Option<String> {
         let stage = stage.to_lowercase();
         self.stages
@@ -141,10 +187,31 @@ impl ReleaseTrain {
 
 pub fn routes() -> Router {
     Router::new()
-        .route("/api/promotions/tracks", get(list_tracks))
+        .route(
+            "/api/promotions/tracks",
+            get(list_tracks).post(tracks::create_track),
+        )
+        .route(
+            "/api/promotions/tracks/:id",
+            get(tracks::get_track)
+                .patch(tracks::update_track)
+                .delete(tracks::delete_track),
+        )
+        .route(
+            "/api/promotions/tracks/:id/auto-advance",
+            get(auto_advance::get_rule).put(auto_advance::put_rule),
+        )
         .route("/api/promotions/schedule", post(schedule_promotion))
         .route("/api/promotions/:id/approve", post(approve_promotion))
+        .route(
+            "/api/promotions/:id/rollback",
+            post(rollback::rollback_promotion),
+        )
         .route("/api/promotions/history", get(history))
+        .route(
+            "/api/promotions/analytics/time-in-stage",
+            get(time_in_stage),
+        )
 }
 
 async fn list_tracks(
@@ -153,7 +220,8 @@ async fn list_tracks(
 ) -> AppResult<Json<Vec<PromotionTrack>>> {
     let tracks = sqlx::query_as::<_, PromotionTrack>(
         r#"
-        SELECT id, owner_id, name, tier, stages, description, workflow_id, created_at, updated_at
+        SELECT id, owner_id, name, tier, stages, description, workflow_id, require_attestation,
+               created_at, updated_at
         FROM promotion_tracks
         WHERE owner_id = $1
         ORDER BY name
@@ -176,7 +244,8 @@ async fn schedule_promotion(
 
     let track = sqlx::query_as::<_, PromotionTrack>(
         r#"
-        SELECT id, owner_id, name, tier, stages, description, workflow_id, created_at, updated_at
+        SELECT id, owner_id, name, tier, stages, description, workflow_id, require_attestation,
+               created_at, updated_at
         FROM promotion_tracks
         WHERE id = $1 AND owner_id = $2
         "#,
@@ -190,6 +259,31 @@ async fn schedule_promotion(
         return Err(AppError::NotFound);
     };
 
+    match schedule_on_track(&pool, &engine, tx, &track, payload, Some(user_id)).await? {
+        ScheduleOutcome::Scheduled(record) => Ok(Json(record)),
+        ScheduleOutcome::Vetoed(payload) => Err(AppError::JsonBadRequest(payload)),
+    }
+}
+
+enum ScheduleOutcome {
+    Scheduled(PromotionRecord),
+    /// The posture verdict vetoed the promotion; carries the verdict payload.
+    Vetoed(Value),
+}
+
+/// Schedules `payload` on `track`, which the caller loaded inside `tx`. Checks the stage
+/// order, the posture verdict, and the attestation requirement, then starts the track's
+/// governance workflow. `scheduled_by` is `None` for automated promotions, whose workflow
+/// runs on behalf of the track owner.
+async fn schedule_on_track(
+    pool: &PgPool,
+    engine: &GovernanceEngine,
+    mut tx: Transaction<'_, Postgres>,
+    track: &PromotionTrack,
+    payload: SchedulePromotionRequest,
+    scheduled_by: Option<i32>,
+) -> AppResult<ScheduleOutcome> {
+    let initiator = scheduled_by.unwrap_or(track.owner_id);
     let SchedulePromotionRequest {
         track_id: _,
         manifest_digest,
@@ -256,15 +350,15 @@ async fn schedule_promotion(
     }
 
     let signals = collect_promotion_signals(&mut tx, artifact_run_id, &manifest_digest).await?;
-    let verdict = evaluate_promotion_posture(&track, &signals);
-    let verdict_payload = build_verdict_payload(&track, &stage, &verdict);
+    let verdict = evaluate_promotion_posture(track, &signals);
+    let verdict_payload = build_verdict_payload(track, &stage, &verdict);
 
     if !verdict.allowed {
         let mut payload = verdict_payload.clone();
         if let Some(object) = payload.as_object_mut() {
             object.insert("error".to_string(), json!("promotion_veto"));
         }
-        return Err(AppError::JsonBadRequest(payload));
+        return Ok(ScheduleOutcome::Vetoed(payload));
     }
 
     notes.extend(verdict.posture_notes);
@@ -288,15 +382,16 @@ async fn schedule_promotion(
     .bind(&manifest_digest)
     .bind(artifact_run_id)
     .bind(&stage)
-    .bind(user_id)
+    .bind(scheduled_by)
     .bind(&notes)
     .bind(&verdict_payload)
     .fetch_one(&mut *tx)
     .await?;
 
     tx.commit().await?;
+    engine.invalidate_gate_cache();
 
-    let mut record = load_promotion(&pool, record_id).await?;
+    let mut record = load_promotion(pool, record_id).await?;
 
     if let Some(workflow_id) = track.workflow_id {
         let mut workflow_notes = notes.clone();
@@ -310,7 +405,7 @@ async fn schedule_promotion(
         };
 
         match engine
-            .start_workflow_run(&pool, workflow_id, user_id, workflow_request)
+            .start_workflow_run(pool, workflow_id, initiator, workflow_request)
             .await
         {
             Ok(run) => {
@@ -327,9 +422,10 @@ async fn schedule_promotion(
                 .bind(run.id)
                 .bind(format!("governance:run-started:{}", run.id))
                 .bind(record.id)
-                .execute(&pool)
+                .execute(pool)
                 .await?;
-                record = load_promotion(&pool, record.id).await?;
+                engine.invalidate_gate_cache();
+                record = load_promotion(pool, record.id).await?;
             }
             Err(err) => {
                 error!(?err, "failed to start governance workflow for promotion");
@@ -343,7 +439,7 @@ async fn schedule_promotion(
                 )
                 .bind(format!("governance:error:{}", err))
                 .bind(record.id)
-                .execute(&pool)
+                .execute(pool)
                 .await?;
                 return Err(AppError::Message(
                     "failed to start governance workflow".into(),
@@ -352,11 +448,12 @@ async fn schedule_promotion(
         }
     }
 
-    Ok(Json(record))
+    Ok(ScheduleOutcome::Scheduled(record))
 }
 
 async fn approve_promotion(
     Extension(pool): Extension<PgPool>,
+    Extension(engine): Extension<Arc<GovernanceEngine>>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i64>,
     Json(payload): Json<ApprovePromotionRequest>,
@@ -387,6 +484,7 @@ async fn approve_promotion(
     if rows.rows_affected() == 0 {
         return Err(AppError::NotFound);
     }
+    engine.invalidate_gate_cache();
 
     let record = load_promotion(&pool, id).await?;
     Ok(Json(record))
@@ -424,6 +522,130 @@ async fn history(
     Ok(Json(records))
 }
 
+// key: release-train -> time-in-stage-analytics
+async fn time_in_stage(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Query(params): Query<PromotionHistoryQuery>,
+) -> AppResult<Json<Vec<PromotionDwellReport>>> {
+    if params.track_id.is_none() && params.manifest_digest.is_none() {
+        return Err(AppError::BadRequest(
+            "manifest_digest or track_id is required".into(),
+        ));
+    }
+
+    let mut builder = QueryBuilder::new(
+        "SELECT ap.promotion_track_id, t.name as track_name, t.stages as track_stages, \
+         ap.manifest_digest, ap.stage, ap.updated_at, bar.completed_at as built_at \
+         FROM artifact_promotions ap \
+         JOIN promotion_tracks t ON t.id = ap.promotion_track_id \
+         LEFT JOIN build_artifact_runs bar ON bar.id = ap.artifact_run_id \
+         WHERE t.owner_id = ",
+    );
+    builder.push_bind(user_id);
+
+    if let Some(track_id) = params.track_id {
+        builder.push(" AND ap.promotion_track_id = ");
+        builder.push_bind(track_id);
+    }
+
+    if let Some(manifest_digest) = params.manifest_digest.as_ref() {
+        builder.push(" AND ap.manifest_digest = ");
+        builder.push_bind(manifest_digest);
+    }
+
+    builder.push(" ORDER BY ap.promotion_track_id, ap.manifest_digest, ap.updated_at, ap.id");
+
+    let rows = builder
+        .build_query_as::<PromotionDwellRow>()
+        .fetch_all(&pool)
+        .await?;
+
+    let mut grouped: Vec<Vec<PromotionDwellRow>> = Vec::new();
+    for row in rows {
+        match grouped.last_mut() {
+            Some(group)
+                if group[0].promotion_track_id == row.promotion_track_id
+                    && group[0].manifest_digest == row.manifest_digest =>
+            {
+                group.push(row)
+            }
+            _ => grouped.push(vec![row]),
+        }
+    }
+
+    let reports = grouped
+        .into_iter()
+        .map(|group| {
+            let first = &group[0];
+            let train = ReleaseTrain::new(first.track_stages.clone());
+            let built_at = group.iter().filter_map(|row| row.built_at).min();
+            let transitions: Vec<(String, DateTime<Utc>)> = group
+                .iter()
+                .map(|row| (row.stage.clone(), row.updated_at))
+                .collect();
+            let (stages, lead_time_seconds) = compute_stage_dwell(&train, built_at, &transitions);
+            PromotionDwellReport {
+                promotion_track_id: first.promotion_track_id,
+                track_name: first.track_name.clone(),
+                manifest_digest: first.manifest_digest.clone(),
+                built_at,
+                stages,
+                lead_time_seconds,
+            }
+        })
+        .collect();
+
+    Ok(Json(reports))
+}
+
+/// Derives per-stage dwell times from stage transitions ordered by the release train.
+fn compute_stage_dwell(
+    train: &ReleaseTrain,
+    built_at: Option<DateTime<Utc>>,
+    transitions: &[(String, DateTime<Utc>)],
+) -> (Vec<PromotionStageDwell>, Option<i64>) {
+    let entered: Vec<Option<DateTime<Utc>>> = train
+        .stages
+        .iter()
+        .map(|stage| {
+            transitions
+                .iter()
+                .filter(|(candidate, _)| candidate.to_lowercase() == *stage)
+                .map(|(_, at)| *at)
+                .max()
+        })
+        .collect();
+
+    let stages = train
+        .stages
+        .iter()
+        .enumerate()
+        .map(|(idx, stage)| {
+            let entered_at = entered[idx];
+            let next_entry = entered[idx + 1..].iter().flatten().next();
+            let dwell_seconds = match (entered_at, next_entry) {
+                (Some(start), Some(end)) => Some((*end - start).num_seconds().max(0)),
+                _ => None,
+            };
+            PromotionStageDwell {
+                stage: stage.clone(),
+                reached: entered_at.is_some(),
+                entered_at,
+                dwell_seconds,
+            }
+        })
+        .collect();
+
+    let start = built_at.or_else(|| entered.iter().flatten().min().copied());
+    let lead_time_seconds = match (start, entered.last().copied().flatten()) {
+        (Some(start), Some(end)) => Some((end - start).num_seconds().max(0)),
+        _ => None,
+    };
+
+    (stages, lead_time_seconds)
+}
+
 async fn load_promotion(pool: &PgPool, id: i64) -> AppResult<PromotionRecord> {
     let record = sqlx::query_as::<_, PromotionRecord>(
         r#"
@@ -499,6 +721,8 @@ async fn collect_promotion_signals(
     let mut signals = PromotionPostureSignals {
         artifact_status: None,
         credential_health_status: None,
+        attestation_status: None,
+        attestation_error: None,
         trust_lifecycle_state: None,
         trust_attestation_status: None,
         trust_remediation_state: None,
@@ -508,6 +732,16 @@ async fn collect_promotion_signals(
         intelligence: Vec::new(),
     };
 
+    if let Some(stored) = load_attestation(&mut *tx, manifest_digest).await? {
+        match verify_envelope(&stored.envelope, &trusted_keys(), manifest_digest) {
+            Ok(_) => signals.attestation_status = Some("verified".to_string()),
+            Err(err) => {
+                signals.attestation_status = Some("invalid".to_string());
+                signals.attestation_error = Some(err.to_string());
+            }
+        }
+    }
+
     let artifact_row = if let Some(id) = artifact_run_id {
         query_as::<_, ArtifactRunRow>(
             r#"
@@ -640,6 +874,17 @@ fn evaluate_promotion_posture(
         }
     }
 
+    let attestation = signals.attestation_status.as_deref().unwrap_or("missing");
+    artifact_map.insert("attestation".to_string(), json!(attestation));
+    if let Some(error) = signals.attestation_error.as_ref() {
+        artifact_map.insert("attestation_error".to_string(), json!(error));
+    }
+    posture_notes.push(format!("posture:artifact.attestation:{attestation}"));
+    if track.require_attestation && attestation != "verified" {
+        allowed = false;
+        veto_reasons.push(format!("artifact.attestation={attestation}"));
+    }
+
     if let Some(lifecycle) = signals.trust_lifecycle_state.as_ref() {
         trust_map.insert("lifecycle_state".to_string(), json!(lifecycle));
         posture_notes.push(format!("posture:trust.lifecycle_state:{lifecycle}"));
@@ -754,9 +999,10 @@ fn evaluate_promotion_posture(
 #[cfg(test)]
 mod tests {
     use super::{
-        build_verdict_payload, evaluate_promotion_posture, IntelligenceSignal,
+        build_verdict_payload, compute_stage_dwell, evaluate_promotion_posture, IntelligenceSignal,
         PromotionPostureSignals, PromotionTrack, ReleaseTrain,
     };
+    use chrono::{Duration, TimeZone, Utc};
 
     #[test]
     fn release_train_defaults_when_missing() {
@@ -783,12 +1029,15 @@ mod tests {
             stages: vec!["candidate".into(), "prod".into()],
             description: None,
             workflow_id: None,
+            require_attestation: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
         let signals = PromotionPostureSignals {
             artifact_status: Some("completed".to_string()),
             credential_health_status: Some("healthy".to_string()),
+            attestation_status: None,
+            attestation_error: None,
             trust_lifecycle_state: Some("trusted".to_string()),
             trust_attestation_status: Some("trusted".to_string()),
             trust_remediation_state: Some("remediation:none".to_string()),
@@ -823,12 +1072,15 @@ mod tests {
             stages: vec!["preprod".into(), "prod".into()],
             description: None,
             workflow_id: None,
+            require_attestation: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
         let signals = PromotionPostureSignals {
             artifact_status: Some("completed".to_string()),
             credential_health_status: Some("healthy".to_string()),
+            attestation_status: None,
+            attestation_error: None,
             trust_lifecycle_state: Some("trusted".to_string()),
             trust_attestation_status: Some("trusted".to_string()),
             trust_remediation_state: Some("remediation:none".to_string()),
@@ -861,12 +1113,15 @@ mod tests {
             stages: vec!["candidate".into(), "production".into()],
             description: None,
             workflow_id: None,
+            require_attestation: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
         let signals = PromotionPostureSignals {
             artifact_status: Some("completed".into()),
             credential_health_status: Some("degraded".into()),
+            attestation_status: None,
+            attestation_error: None,
             trust_lifecycle_state: Some("quarantined".into()),
             trust_attestation_status: Some("critical".into()),
             trust_remediation_state: Some("remediation:pending".into()),
@@ -899,4 +1154,87 @@ mod tests {
             .map(|entries| !entries.is_empty())
             .unwrap_or(false));
     }
+
+    #[test]
+    fn required_attestation_must_verify() {
+        let track = PromotionTrack {
+            id: 4,
+            owner_id: 2,
+            name: "Signed".to_string(),
+            tier: "stable".to_string(),
+            stages: vec!["candidate".into(), "production".into()],
+            description: None,
+            workflow_id: None,
+            require_attestation: true,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let signals = |status: Option<&str>| PromotionPostureSignals {
+            artifact_status: Some("succeeded".to_string()),
+            credential_health_status: Some("healthy".to_string()),
+            attestation_status: status.map(str::to_string),
+            attestation_error: None,
+            trust_lifecycle_state: None,
+            trust_attestation_status: None,
+            trust_remediation_state: None,
+            trust_remediation_attempts: None,
+            remediation_status: None,
+            remediation_failure_reason: None,
+            intelligence: vec![],
+        };
+
+        assert!(evaluate_promotion_posture(&track, &signals(Some("verified"))).allowed);
+        for (status, reason) in [
+            (None, "artifact.attestation=missing"),
+            (Some("invalid"), "artifact.attestation=invalid"),
+        ] {
+            let verdict = evaluate_promotion_posture(&track, &signals(status));
+            assert!(!verdict.allowed);
+            assert_eq!(verdict.veto_reasons, vec![reason.to_string()]);
+        }
+
+        let optional = PromotionTrack {
+            require_attestation: false,
+            ..track
+        };
+        assert!(evaluate_promotion_posture(&optional, &signals(None)).allowed);
+    }
+
+    #[test]
+    fn stage_dwell_tracks_three_stage_progression() {
+        let train = ReleaseTrain::new(vec![]);
+        let built = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let transitions = vec![
+            ("candidate".to_string(), built + Duration::minutes(10)),
+            ("staging".to_string(), built + Duration::hours(2)),
+            ("production".to_string(), built + Duration::hours(26)),
+        ];
+
+        let (stages, lead_time) = compute_stage_dwell(&train, Some(built), &transitions);
+
+        assert_eq!(stages.len(), 3);
+        assert_eq!(stages[0].dwell_seconds, Some(110 * 60));
+        assert_eq!(stages[1].dwell_seconds, Some(24 * 3600));
+        assert!(stages[2].reached);
+        assert_eq!(stages[2].dwell_seconds, None);
+        assert_eq!(lead_time, Some(26 * 3600));
+    }
+
+    #[test]
+    fn stage_dwell_reports_skipped_stage_as_absent() {
+        let train = ReleaseTrain::new(vec![]);
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let transitions = vec![
+            ("candidate".to_string(), start),
+            ("Production".to_string(), start + Duration::hours(5)),
+        ];
+
+        let (stages, lead_time) = compute_stage_dwell(&train, None, &transitions);
+
+        assert_eq!(stages[0].dwell_seconds, Some(5 * 3600));
+        assert!(!stages[1].reached);
+        assert_eq!(stages[1].entered_at, None);
+        assert_eq!(stages[1].dwell_seconds, None);
+        assert_eq!(lead_time, Some(5 * 3600));
+    }
 }
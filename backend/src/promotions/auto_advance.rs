@@ -0,0 +1,359 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+
+use super::{schedule_on_track, PromotionTrack, ScheduleOutcome, SchedulePromotionRequest};
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use crate::governance::GovernanceEngine;
+
+// key: release-train -> promotion-auto-advance
+
+/// Most promotions one sweep considers; the longest-dwelling go first.
+const SWEEP_BATCH: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AutoAdvanceRule {
+    pub promotion_track_id: i32,
+    pub enabled: bool,
+    /// How long a manifest must stay `active` in a stage before it moves on.
+    pub min_dwell_seconds: i64,
+    pub updated_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutoAdvanceRuleRequest {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub min_dwell_seconds: i64,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct AutoAdvanceCandidate {
+    promotion_id: i64,
+    promotion_track_id: i32,
+    manifest_digest: String,
+    artifact_run_id: Option<i32>,
+    stage: String,
+    next_stage: String,
+    dwell_seconds: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AutoAdvanceStatus {
+    /// The next stage was scheduled through the regular promotion path.
+    Advanced { promotion_id: i64 },
+    /// The posture verdict for the next stage vetoed it; the manifest stays put.
+    Vetoed { reasons: Vec<String> },
+    /// Scheduling was refused for another reason, e.g. the track changed mid-sweep.
+    Skipped { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoAdvanceOutcome {
+    pub source_promotion_id: i64,
+    pub promotion_track_id: i32,
+    pub manifest_digest: String,
+    pub from_stage: String,
+    pub to_stage: String,
+    #[serde(flatten)]
+    pub status: AutoAdvanceStatus,
+}
+
+async fn owned_track_exists(pool: &PgPool, track_id: i32, owner_id: i32) -> AppResult<()> {
+    let found = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM promotion_tracks WHERE id = $1 AND owner_id = $2",
+    )
+    .bind(track_id)
+    .bind(owner_id)
+    .fetch_optional(pool)
+    .await?;
+    found.map(|_| ()).ok_or(AppError::NotFound)
+}
+
+pub async fn get_rule(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(track_id): Path<i32>,
+) -> AppResult<Json<AutoAdvanceRule>> {
+    owned_track_exists(&pool, track_id, user_id).await?;
+    let rule = sqlx::query_as::<_, AutoAdvanceRule>(
+        r#"
+        SELECT promotion_track_id, enabled, min_dwell_seconds, updated_by, created_at, updated_at
+        FROM promotion_auto_advance_rules
+        WHERE promotion_track_id = $1
+        "#,
+    )
+    .bind(track_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    Ok(Json(rule))
+}
+
+/// Creates or replaces the track's rule. `enabled: false` keeps the dwell time but stops the
+/// sweep from advancing the track.
+pub async fn put_rule(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(track_id): Path<i32>,
+    Json(request): Json<AutoAdvanceRuleRequest>,
+) -> AppResult<Json<AutoAdvanceRule>> {
+    if request.min_dwell_seconds < 0 {
+        return Err(AppError::BadRequest(
+            "min_dwell_seconds cannot be negative".into(),
+        ));
+    }
+    owned_track_exists(&pool, track_id, user_id).await?;
+    let rule = sqlx::query_as::<_, AutoAdvanceRule>(
+        r#"
+        INSERT INTO promotion_auto_advance_rules (
+            promotion_track_id, enabled, min_dwell_seconds, updated_by
+        )
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (promotion_track_id) DO UPDATE
+        SET enabled = EXCLUDED.enabled,
+            min_dwell_seconds = EXCLUDED.min_dwell_seconds,
+            updated_by = EXCLUDED.updated_by,
+            updated_at = NOW()
+        RETURNING promotion_track_id, enabled, min_dwell_seconds, updated_by, created_at, updated_at
+        "#,
+    )
+    .bind(track_id)
+    .bind(request.enabled)
+    .bind(request.min_dwell_seconds)
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await?;
+    Ok(Json(rule))
+}
+
+/// Active promotions on tracks with an enabled rule that have dwelt long enough and whose
+/// digest has not reached the next stage yet. The final stage has no next stage, so it never
+/// qualifies.
+async fn due_candidates(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<AutoAdvanceCandidate>, sqlx::Error> {
+    sqlx::query_as::<_, AutoAdvanceCandidate>(
+        r#"
+        SELECT
+            ap.id AS promotion_id,
+            ap.promotion_track_id,
+            ap.manifest_digest,
+            ap.artifact_run_id,
+            ap.stage,
+            next.stage AS next_stage,
+            EXTRACT(EPOCH FROM ($1 - COALESCE(ap.activated_at, ap.updated_at)))::BIGINT
+                AS dwell_seconds
+        FROM artifact_promotions ap
+        JOIN promotion_tracks t ON t.id = ap.promotion_track_id
+        JOIN promotion_auto_advance_rules rules
+            ON rules.promotion_track_id = ap.promotion_track_id
+        CROSS JOIN LATERAL (
+            SELECT lower(later.stage) AS stage
+            FROM unnest(t.stages) WITH ORDINALITY AS later(stage, position)
+            WHERE later.position > (
+                SELECT here.position
+                FROM unnest(t.stages) WITH ORDINALITY AS here(stage, position)
+                WHERE lower(here.stage) = ap.stage
+                LIMIT 1
+            )
+            ORDER BY later.position
+            LIMIT 1
+        ) next
+        WHERE rules.enabled
+          AND ap.status = 'active'
+          AND COALESCE(ap.activated_at, ap.updated_at)
+              <= $1 - make_interval(secs => rules.min_dwell_seconds)
+          AND NOT EXISTS (
+              SELECT 1
+              FROM artifact_promotions reached
+              WHERE reached.promotion_track_id = ap.promotion_track_id
+                AND reached.manifest_digest = ap.manifest_digest
+                AND reached.stage = next.stage
+          )
+        ORDER BY COALESCE(ap.activated_at, ap.updated_at), ap.id
+        LIMIT $2
+        "#,
+    )
+    .bind(now)
+    .bind(SWEEP_BATCH)
+    .fetch_all(pool)
+    .await
+}
+
+/// Notes the veto on the source promotion, once per distinct set of reasons. `updated_at` is
+/// left alone so the dwell clock keeps running.
+async fn record_veto(
+    pool: &PgPool,
+    promotion_id: i64,
+    to_stage: &str,
+    reasons: &[String],
+) -> Result<(), sqlx::Error> {
+    let note = format!(
+        "promotion:auto-advance:vetoed:{to_stage}:{}",
+        reasons.join(",")
+    );
+    sqlx::query(
+        r#"
+        UPDATE artifact_promotions
+        SET notes = array_append(notes, $2)
+        WHERE id = $1
+          AND NOT ($2 = ANY(notes))
+        "#,
+    )
+    .bind(promotion_id)
+    .bind(note)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn advance(
+    pool: &PgPool,
+    engine: &GovernanceEngine,
+    candidate: &AutoAdvanceCandidate,
+) -> AppResult<AutoAdvanceStatus> {
+    let mut tx = pool.begin().await?;
+    let track = sqlx::query_as::<_, PromotionTrack>(
+        r#"
+        SELECT id, owner_id, name, tier, stages, description, workflow_id, require_attestation,
+               created_at, updated_at
+        FROM promotion_tracks
+        WHERE id = $1
+        "#,
+    )
+    .bind(candidate.promotion_track_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let request = SchedulePromotionRequest {
+        track_id: track.id,
+        manifest_digest: candidate.manifest_digest.clone(),
+        artifact_run_id: candidate.artifact_run_id,
+        stage: candidate.next_stage.clone(),
+        notes: vec![format!(
+            "promotion:auto-advanced:{}:{}:dwell:{}s",
+            candidate.stage, candidate.next_stage, candidate.dwell_seconds
+        )],
+    };
+    let status = match schedule_on_track(pool, engine, tx, &track, request, None).await {
+        Ok(ScheduleOutcome::Scheduled(record)) => {
+            sqlx::query(
+                r#"
+                UPDATE artifact_promotions
+                SET notes = array_append(notes, $2)
+                WHERE id = $1
+                "#,
+            )
+            .bind(candidate.promotion_id)
+            .bind(format!(
+                "promotion:auto-advance:{}:{}",
+                candidate.next_stage, record.id
+            ))
+            .execute(pool)
+            .await?;
+            AutoAdvanceStatus::Advanced {
+                promotion_id: record.id,
+            }
+        }
+        Ok(ScheduleOutcome::Vetoed(payload)) => {
+            let reasons: Vec<String> = payload
+                .get("reasons")
+                .and_then(Value::as_array)
+                .map(|reasons| {
+                    reasons
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            record_veto(
+                pool,
+                candidate.promotion_id,
+                &candidate.next_stage,
+                &reasons,
+            )
+            .await?;
+            AutoAdvanceStatus::Vetoed { reasons }
+        }
+        Err(AppError::BadRequest(reason)) => AutoAdvanceStatus::Skipped { reason },
+        Err(err) => return Err(err),
+    };
+    Ok(status)
+}
+
+/// Advances every due promotion by one stage, as of `now`. Each one goes through the same
+/// scheduling path as `POST /api/promotions/schedule`, so stage order, posture vetoes, the
+/// attestation requirement, and governance workflows all apply. A promotion that fails is
+/// logged and retried on the next sweep.
+pub async fn advance_due(
+    pool: &PgPool,
+    engine: &GovernanceEngine,
+    now: DateTime<Utc>,
+) -> AppResult<Vec<AutoAdvanceOutcome>> {
+    let mut outcomes = Vec::new();
+    for candidate in due_candidates(pool, now).await? {
+        let status = match advance(pool, engine, &candidate).await {
+            Ok(status) => status,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    source_promotion_id = candidate.promotion_id,
+                    "failed to auto-advance promotion"
+                );
+                continue;
+            }
+        };
+        if let AutoAdvanceStatus::Advanced { promotion_id } = &status {
+            info!(
+                source_promotion_id = candidate.promotion_id,
+                promotion_id,
+                stage = %candidate.next_stage,
+                "auto-advanced promotion"
+            );
+        }
+        outcomes.push(AutoAdvanceOutcome {
+            source_promotion_id: candidate.promotion_id,
+            promotion_track_id: candidate.promotion_track_id,
+            manifest_digest: candidate.manifest_digest,
+            from_stage: candidate.stage,
+            to_stage: candidate.next_stage,
+            status,
+        });
+    }
+    Ok(outcomes)
+}
+
+pub fn spawn(pool: PgPool, engine: Arc<GovernanceEngine>) {
+    let interval = Duration::from_secs(*config::PROMOTION_AUTO_ADVANCE_INTERVAL_SECS);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = advance_due(&pool, &engine, Utc::now()).await {
+                warn!(?err, "promotion auto-advance sweep failed");
+            }
+        }
+    });
+}
@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+
+use super::{load_promotion, PromotionRecord, ReleaseTrain};
+use crate::audit::organization::{self, AuditCategory, AuditRecord, AuditScope};
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use crate::governance::GovernanceEngine;
+use crate::lifecycle_console::notify_posture_changed;
+
+// key: release-train -> promotion-rollback
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RollbackPromotionRequest {
+    pub reason: String,
+    /// An earlier stage of the track to reinstate. Without it the promotion is only marked
+    /// `rolled_back`.
+    #[serde(default)]
+    pub to_stage: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PromotionRollback {
+    pub id: i64,
+    pub promotion_id: i64,
+    pub from_stage: String,
+    pub to_stage: Option<String>,
+    pub restored_promotion_id: Option<i64>,
+    pub reason: String,
+    pub rolled_back_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromotionRollbackResponse {
+    pub rollback: PromotionRollback,
+    pub promotion: PromotionRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored: Option<PromotionRecord>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct RollbackTarget {
+    promotion_track_id: i32,
+    manifest_digest: String,
+    artifact_run_id: Option<i32>,
+    stage: String,
+    status: String,
+    track_stages: Vec<String>,
+}
+
+/// Admin-only: takes a promotion out of service. With `to_stage`, every live promotion of the
+/// digest in a stage after `to_stage` is rolled back too, and the `to_stage` promotion is made
+/// `active` again, or created if the digest skipped it. Open lifecycle streams are woken so the
+/// console shows the new posture right away.
+pub async fn rollback_promotion(
+    Extension(pool): Extension<PgPool>,
+    Extension(engine): Extension<Arc<GovernanceEngine>>,
+    AuthUser { user_id, role }: AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<RollbackPromotionRequest>,
+) -> AppResult<Json<PromotionRollbackResponse>> {
+    if role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+    let reason = request.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::BadRequest("reason is required".into()));
+    }
+
+    let mut tx = pool.begin().await?;
+    let target = sqlx::query_as::<_, RollbackTarget>(
+        r#"
+        SELECT ap.promotion_track_id, ap.manifest_digest, ap.artifact_run_id, ap.stage,
+               ap.status::TEXT AS status, t.stages AS track_stages
+        FROM artifact_promotions ap
+        JOIN promotion_tracks t ON t.id = ap.promotion_track_id
+        WHERE ap.id = $1
+        FOR UPDATE OF ap
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if target.status == "rolled_back" {
+        return Err(AppError::Conflict(
+            "promotion is already rolled back".into(),
+        ));
+    }
+
+    let train = ReleaseTrain::new(target.track_stages.clone());
+    let from_position = train.position(&target.stage);
+    let to_stage = match request.to_stage.as_deref() {
+        Some(stage) => {
+            let stage = stage.trim().to_lowercase();
+            match (train.position(&stage), from_position) {
+                (Some(to), Some(from)) if to < from => Some((stage, to, from)),
+                (Some(_), _) => {
+                    return Err(AppError::BadRequest(format!(
+                        "stage `{stage}` does not come before `{}`",
+                        target.stage
+                    )))
+                }
+                (None, _) => {
+                    return Err(AppError::BadRequest(format!(
+                        "stage `{stage}` is not part of the release train"
+                    )))
+                }
+            }
+        }
+        None => None,
+    };
+
+    let note = format!("promotion:rolled-back:user:{user_id}:{reason}");
+    // the rolled-back promotion itself, plus any later stages the digest reached since
+    let withdrawn: Vec<String> = match &to_stage {
+        Some((_, to, from)) => train.stages[to + 1..=*from].to_vec(),
+        None => Vec::new(),
+    };
+    sqlx::query(
+        r#"
+        UPDATE artifact_promotions
+        SET status = 'rolled_back',
+            updated_at = NOW(),
+            notes = array_append(notes, $2)
+        WHERE id = $1
+           OR (promotion_track_id = $3
+               AND manifest_digest = $4
+               AND lower(stage) = ANY($5)
+               AND status <> 'rolled_back')
+        "#,
+    )
+    .bind(id)
+    .bind(&note)
+    .bind(target.promotion_track_id)
+    .bind(&target.manifest_digest)
+    .bind(&withdrawn)
+    .execute(&mut *tx)
+    .await?;
+
+    let restored_id = match &to_stage {
+        Some((stage, _, _)) => {
+            let restored: i64 = sqlx::query_scalar(
+                r#"
+                INSERT INTO artifact_promotions (
+                    promotion_track_id, manifest_digest, artifact_run_id, stage, status,
+                    scheduled_by, approved_by, approved_at, activated_at, notes
+                )
+                VALUES ($1, $2, $3, $4, 'active', $5, $5, NOW(), NOW(), ARRAY[$6])
+                ON CONFLICT (promotion_track_id, stage, manifest_digest) DO UPDATE
+                SET status = 'active',
+                    activated_at = NOW(),
+                    updated_at = NOW(),
+                    notes = array_append(artifact_promotions.notes, $6)
+                RETURNING id
+                "#,
+            )
+            .bind(target.promotion_track_id)
+            .bind(&target.manifest_digest)
+            .bind(target.artifact_run_id)
+            .bind(stage)
+            .bind(user_id)
+            .bind(format!(
+                "promotion:restored:from:{}:user:{user_id}",
+                target.stage
+            ))
+            .fetch_one(&mut *tx)
+            .await?;
+            Some(restored)
+        }
+        None => None,
+    };
+
+    let rollback = sqlx::query_as::<_, PromotionRollback>(
+        r#"
+        INSERT INTO promotion_rollbacks (
+            promotion_id, from_stage, to_stage, restored_promotion_id, reason, rolled_back_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, promotion_id, from_stage, to_stage, restored_promotion_id, reason,
+                  rolled_back_by, created_at
+        "#,
+    )
+    .bind(id)
+    .bind(&target.stage)
+    .bind(to_stage.as_ref().map(|(stage, _, _)| stage.as_str()))
+    .bind(restored_id)
+    .bind(reason)
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    engine.invalidate_gate_cache();
+    notify_posture_changed();
+    if let Some(run_id) = target.artifact_run_id {
+        organization::record(
+            &pool,
+            AuditRecord::new(
+                AuditScope::ArtifactRun(run_id),
+                AuditCategory::Promotions,
+                "promotion_rolled_back",
+            )
+            .user(user_id)
+            .target(format!("promotion:{id}"))
+            .details(json!({
+                "from_stage": rollback.from_stage,
+                "to_stage": rollback.to_stage,
+                "reason": rollback.reason,
+            })),
+        );
+    }
+
+    let promotion = load_promotion(&pool, id).await?;
+    let restored = match restored_id {
+        Some(restored_id) => Some(load_promotion(&pool, restored_id).await?),
+        None => None,
+    };
+    Ok(Json(PromotionRollbackResponse {
+        rollback,
+        promotion,
+        restored,
+    }))
+}
@@ -0,0 +1,393 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use super::PromotionTrack;
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+
+// key: release-train -> promotion-track-management
+
+/// Promotion statuses that keep a track in use; only rolled-back promotions let it go.
+const LIVE_PROMOTION_STATUSES: &[&str] = &["scheduled", "in_progress", "approved", "active"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePromotionTrackRequest {
+    pub name: String,
+    pub tier: String,
+    /// Defaults to `candidate`, `staging`, `production`.
+    #[serde(default)]
+    pub stages: Option<Vec<String>>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub workflow_id: Option<i32>,
+    #[serde(default)]
+    pub require_attestation: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdatePromotionTrackRequest {
+    pub name: Option<String>,
+    pub tier: Option<String>,
+    pub stages: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub workflow_id: Option<i32>,
+    pub require_attestation: Option<bool>,
+}
+
+/// The ordered tiers from `PROMOTION_TRACK_TIERS`, lowest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromotionTiers {
+    tiers: Vec<String>,
+}
+
+impl PromotionTiers {
+    pub fn new(tiers: &[String]) -> Self {
+        Self {
+            tiers: tiers
+                .iter()
+                .map(|tier| tier.trim().to_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn configured() -> Self {
+        Self::new(&config::PROMOTION_TRACK_TIERS)
+    }
+
+    pub fn rank(&self, tier: &str) -> Option<usize> {
+        self.tiers.iter().position(|candidate| candidate == tier)
+    }
+
+    fn describe(&self) -> String {
+        self.tiers.join(" < ")
+    }
+
+    /// Normalizes a track's tier and stages and checks them against the tier order. The tier
+    /// must be a configured one. Stages must be unique, and stages named after tiers must
+    /// follow the tier order, so a track never promotes from a higher tier to a lower one.
+    pub fn validate(&self, tier: &str, stages: &[String]) -> Result<(String, Vec<String>), String> {
+        let tier = tier.trim().to_lowercase();
+        if self.rank(&tier).is_none() {
+            return Err(format!(
+                "tier `{tier}` is not one of {}",
+                self.tiers.join(", ")
+            ));
+        }
+
+        let stages: Vec<String> = stages
+            .iter()
+            .map(|stage| stage.trim().to_lowercase())
+            .collect();
+        if stages.is_empty() {
+            return Err("a track needs at least one stage".to_string());
+        }
+        let mut seen = HashSet::new();
+        let mut previous: Option<(&str, usize)> = None;
+        for stage in &stages {
+            if stage.is_empty() {
+                return Err("stage names cannot be blank".to_string());
+            }
+            if !seen.insert(stage.as_str()) {
+                return Err(format!("stage `{stage}` is listed twice"));
+            }
+            let Some(rank) = self.rank(stage) else {
+                continue;
+            };
+            if let Some((earlier, earlier_rank)) = previous {
+                if rank < earlier_rank {
+                    return Err(format!(
+                        "stage `{stage}` cannot follow `{earlier}`; tiers are ordered {}",
+                        self.describe()
+                    ));
+                }
+            }
+            previous = Some((stage, rank));
+        }
+        Ok((tier, stages))
+    }
+}
+
+fn default_stages() -> Vec<String> {
+    vec![
+        "candidate".to_string(),
+        "staging".to_string(),
+        "production".to_string(),
+    ]
+}
+
+fn map_write_error(err: sqlx::Error) -> AppError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+            AppError::Conflict("a promotion track with this name already exists".into())
+        }
+        _ => AppError::Db(err),
+    }
+}
+
+/// Loads the caller's track and locks its row until the transaction ends.
+async fn lock_track(
+    tx: &mut Transaction<'_, Postgres>,
+    track_id: i32,
+    owner_id: i32,
+) -> AppResult<PromotionTrack> {
+    sqlx::query_as::<_, PromotionTrack>(
+        r#"
+        SELECT id, owner_id, name, tier, stages, description, workflow_id, require_attestation,
+               created_at, updated_at
+        FROM promotion_tracks
+        WHERE id = $1 AND owner_id = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(track_id)
+    .bind(owner_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+async fn ensure_workflow_owned(
+    tx: &mut Transaction<'_, Postgres>,
+    workflow_id: i32,
+    owner_id: i32,
+) -> AppResult<()> {
+    let owned = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM governance_workflows WHERE id = $1 AND owner_id = $2",
+    )
+    .bind(workflow_id)
+    .bind(owner_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if owned.is_none() {
+        return Err(AppError::BadRequest(format!(
+            "governance workflow {workflow_id} not found"
+        )));
+    }
+    Ok(())
+}
+
+fn normalized_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("name is required".into()));
+    }
+    Ok(name.to_string())
+}
+
+pub async fn create_track(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Json(request): Json<CreatePromotionTrackRequest>,
+) -> AppResult<Json<PromotionTrack>> {
+    let name = normalized_name(&request.name)?;
+    let (tier, stages) = PromotionTiers::configured()
+        .validate(
+            &request.tier,
+            &request.stages.unwrap_or_else(default_stages),
+        )
+        .map_err(AppError::BadRequest)?;
+
+    let mut tx = pool.begin().await?;
+    if let Some(workflow_id) = request.workflow_id {
+        ensure_workflow_owned(&mut tx, workflow_id, user_id).await?;
+    }
+    let track = sqlx::query_as::<_, PromotionTrack>(
+        r#"
+        INSERT INTO promotion_tracks (
+            owner_id, name, tier, stages, description, workflow_id, require_attestation
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, owner_id, name, tier, stages, description, workflow_id,
+                  require_attestation, created_at, updated_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(&name)
+    .bind(&tier)
+    .bind(&stages)
+    .bind(request.description.as_deref())
+    .bind(request.workflow_id)
+    .bind(request.require_attestation)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_write_error)?;
+    tx.commit().await?;
+
+    Ok(Json(track))
+}
+
+pub async fn get_track(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(track_id): Path<i32>,
+) -> AppResult<Json<PromotionTrack>> {
+    let track = sqlx::query_as::<_, PromotionTrack>(
+        r#"
+        SELECT id, owner_id, name, tier, stages, description, workflow_id, require_attestation,
+               created_at, updated_at
+        FROM promotion_tracks
+        WHERE id = $1 AND owner_id = $2
+        "#,
+    )
+    .bind(track_id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    Ok(Json(track))
+}
+
+/// Applies the given fields; the merged tier and stages are validated together.
+pub async fn update_track(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(track_id): Path<i32>,
+    Json(request): Json<UpdatePromotionTrackRequest>,
+) -> AppResult<Json<PromotionTrack>> {
+    let mut tx = pool.begin().await?;
+    let current = lock_track(&mut tx, track_id, user_id).await?;
+
+    let name = match request.name.as_deref() {
+        Some(name) => normalized_name(name)?,
+        None => current.name,
+    };
+    let (tier, stages) = PromotionTiers::configured()
+        .validate(
+            request.tier.as_deref().unwrap_or(&current.tier),
+            request.stages.as_deref().unwrap_or(&current.stages),
+        )
+        .map_err(AppError::BadRequest)?;
+    let workflow_id = request.workflow_id.or(current.workflow_id);
+    if let Some(workflow_id) = request.workflow_id {
+        ensure_workflow_owned(&mut tx, workflow_id, user_id).await?;
+    }
+
+    let track = sqlx::query_as::<_, PromotionTrack>(
+        r#"
+        UPDATE promotion_tracks
+        SET name = $2,
+            tier = $3,
+            stages = $4,
+            description = $5,
+            workflow_id = $6,
+            require_attestation = $7,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, owner_id, name, tier, stages, description, workflow_id,
+                  require_attestation, created_at, updated_at
+        "#,
+    )
+    .bind(track_id)
+    .bind(&name)
+    .bind(&tier)
+    .bind(&stages)
+    .bind(request.description.or(current.description))
+    .bind(workflow_id)
+    .bind(
+        request
+            .require_attestation
+            .unwrap_or(current.require_attestation),
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_write_error)?;
+    tx.commit().await?;
+
+    Ok(Json(track))
+}
+
+/// Deletes a track that has no live promotions. Deleting cascades to its promotion history.
+pub async fn delete_track(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(track_id): Path<i32>,
+) -> AppResult<Json<Value>> {
+    let mut tx = pool.begin().await?;
+    // the row lock keeps a concurrent schedule from landing between the check and the delete
+    lock_track(&mut tx, track_id, user_id).await?;
+
+    let live: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM artifact_promotions
+        WHERE promotion_track_id = $1
+          AND status::TEXT = ANY($2)
+        "#,
+    )
+    .bind(track_id)
+    .bind(LIVE_PROMOTION_STATUSES)
+    .fetch_one(&mut *tx)
+    .await?;
+    if live > 0 {
+        return Err(AppError::Conflict(format!(
+            "track has {live} live promotion(s); roll them back before deleting it"
+        )));
+    }
+
+    sqlx::query("DELETE FROM promotion_tracks WHERE id = $1")
+        .bind(track_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({ "deleted": true })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiers() -> PromotionTiers {
+        PromotionTiers::new(&[
+            "pilot".to_string(),
+            "staging".to_string(),
+            "production".to_string(),
+        ])
+    }
+
+    fn stages(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn stages_named_after_tiers_follow_the_tier_order() {
+        let (tier, normalized) = tiers()
+            .validate(" Staging ", &stages(&["candidate", "Pilot", "production"]))
+            .unwrap();
+        assert_eq!(tier, "staging");
+        assert_eq!(normalized, stages(&["candidate", "pilot", "production"]));
+
+        let err = tiers()
+            .validate(
+                "production",
+                &stages(&["candidate", "production", "staging"]),
+            )
+            .unwrap_err();
+        assert!(
+            err.contains("`staging` cannot follow `production`"),
+            "{err}"
+        );
+        assert!(err.contains("pilot < staging < production"), "{err}");
+    }
+
+    #[test]
+    fn unknown_tiers_and_malformed_stages_are_rejected() {
+        assert!(tiers().validate("gold", &default_stages()).is_err());
+        assert!(tiers().validate("pilot", &[]).is_err());
+        assert!(tiers()
+            .validate("pilot", &stages(&["canary", "Canary"]))
+            .is_err());
+        assert!(tiers()
+            .validate("pilot", &stages(&["canary", " "]))
+            .is_err());
+    }
+}
@@ -1,9 +1,21 @@
+use crate::config;
 use acme2::{gen_rsa_private_key, AccountBuilder, Csr, DirectoryBuilder, OrderBuilder};
+use bytes::{Bytes, BytesMut};
+use dashmap::DashMap;
+use futures_util::{pin_mut, Stream, StreamExt};
 use nix::sys::signal::{kill, Signal};
 use nix::unistd::Pid;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::json;
 use sqlx::{PgPool, Row};
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+pub mod pool;
+pub mod routing;
+pub mod transform;
 
 pub fn conf_dir() -> PathBuf {
     std::env::var("PROXY_CONF_DIR")
@@ -132,3 +144,475 @@ pub async fn rebuild_for_server(pool: &PgPool, server_id: i32) {
         Err(e) => tracing::error!(?e, "proxy DB error"),
     }
 }
+
+/// key: proxy-limits -> streaming body caps for invoke forwarding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyBodyLimits {
+    pub max_request_bytes: usize,
+    pub max_response_bytes: usize,
+}
+
+impl ProxyBodyLimits {
+    /// Applies per-server overrides on top of the environment defaults.
+    pub fn for_server(request_override: Option<i64>, response_override: Option<i64>) -> Self {
+        let pick = |value: Option<i64>, default: usize| {
+            value
+                .filter(|value| *value > 0)
+                .and_then(|value| usize::try_from(value).ok())
+                .unwrap_or(default)
+        };
+        Self {
+            max_request_bytes: pick(request_override, *config::PROXY_MAX_REQUEST_BYTES),
+            max_response_bytes: pick(response_override, *config::PROXY_MAX_RESPONSE_BYTES),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProxyBodyError {
+    #[error("body exceeded {limit} bytes")]
+    TooLarge { limit: usize },
+    #[error("body stream failed: {0}")]
+    Stream(String),
+}
+
+/// Collects a body stream, aborting as soon as the running total crosses `limit`
+/// so oversized payloads are never fully buffered.
+pub async fn collect_limited<S, E>(stream: S, limit: usize) -> Result<Bytes, ProxyBodyError>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: std::fmt::Display,
+{
+    pin_mut!(stream);
+    let mut buffer = BytesMut::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| ProxyBodyError::Stream(e.to_string()))?;
+        if buffer.len() + chunk.len() > limit {
+            return Err(ProxyBodyError::TooLarge { limit });
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(buffer.freeze())
+}
+
+/// Reads an upstream response under `limit`, rejecting early when `Content-Length`
+/// already announces an oversized body.
+pub async fn read_response_limited(
+    response: reqwest::Response,
+    limit: usize,
+) -> Result<Bytes, ProxyBodyError> {
+    if let Some(length) = response.content_length() {
+        if length > limit as u64 {
+            return Err(ProxyBodyError::TooLarge { limit });
+        }
+    }
+    collect_limited(response.bytes_stream(), limit).await
+}
+
+/// key: proxy-circuit-breaker -> per-server upstream failure memory
+pub static PROXY_BREAKERS: Lazy<CircuitBreakerRegistry> = Lazy::new(|| {
+    CircuitBreakerRegistry::new(
+        *config::PROXY_BREAKER_FAILURE_THRESHOLD,
+        Duration::from_secs(*config::PROXY_BREAKER_COOLDOWN_SECS),
+    )
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerTransition {
+    pub from: BreakerState,
+    pub to: BreakerState,
+    pub consecutive_failures: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("circuit open; retry in {retry_after:?}")]
+pub struct BreakerOpen {
+    pub retry_after: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerSnapshot {
+    pub server_id: i32,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub failure_threshold: u32,
+    pub retry_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BreakerEntry {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the current half-open probe was admitted. A probe whose request was dropped
+    /// never reports back, so it only blocks other callers for one cooldown.
+    probe_started_at: Option<Instant>,
+}
+
+impl Default for BreakerEntry {
+    fn default() -> Self {
+        Self {
+            state: BreakerState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            probe_started_at: None,
+        }
+    }
+}
+
+pub struct CircuitBreakerRegistry {
+    failure_threshold: u32,
+    cooldown: Duration,
+    entries: DashMap<i32, BreakerEntry>,
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// Admits a request unless the breaker is open. Once the cooldown elapses a single
+    /// probe is let through in the half-open state; concurrent callers keep fast-failing
+    /// until it reports back or, if it never does, for one more cooldown.
+    pub fn acquire(
+        &self,
+        server_id: i32,
+        now: Instant,
+    ) -> Result<Option<BreakerTransition>, BreakerOpen> {
+        let mut entry = self.entries.entry(server_id).or_default();
+        match entry.state {
+            BreakerState::Closed => Ok(None),
+            BreakerState::Open => {
+                let opened_at = entry.opened_at.unwrap_or(now);
+                let elapsed = now.saturating_duration_since(opened_at);
+                if elapsed < self.cooldown {
+                    return Err(BreakerOpen {
+                        retry_after: self.cooldown - elapsed,
+                    });
+                }
+                entry.state = BreakerState::HalfOpen;
+                entry.probe_started_at = Some(now);
+                Ok(Some(BreakerTransition {
+                    from: BreakerState::Open,
+                    to: BreakerState::HalfOpen,
+                    consecutive_failures: entry.consecutive_failures,
+                }))
+            }
+            BreakerState::HalfOpen => match self.probe_remaining(&entry, now) {
+                Some(retry_after) => Err(BreakerOpen { retry_after }),
+                None => {
+                    entry.probe_started_at = Some(now);
+                    Ok(None)
+                }
+            },
+        }
+    }
+
+    /// Whether `acquire` would fast-fail at `now`. Unlike `acquire`, it never changes state.
+    pub fn is_open(&self, server_id: i32, now: Instant) -> bool {
+        let Some(entry) = self.entries.get(&server_id).map(|entry| *entry) else {
+            return false;
+        };
+        match entry.state {
+            BreakerState::Closed => false,
+            BreakerState::Open => {
+                let opened_at = entry.opened_at.unwrap_or(now);
+                now.saturating_duration_since(opened_at) < self.cooldown
+            }
+            BreakerState::HalfOpen => self.probe_remaining(&entry, now).is_some(),
+        }
+    }
+
+    /// How much longer the half-open probe in flight holds the slot, if one does.
+    fn probe_remaining(&self, entry: &BreakerEntry, now: Instant) -> Option<Duration> {
+        let started_at = entry.probe_started_at?;
+        let elapsed = now.saturating_duration_since(started_at);
+        (elapsed < self.cooldown).then(|| self.cooldown - elapsed)
+    }
+
+    pub fn record_success(&self, server_id: i32) -> Option<BreakerTransition> {
+        let mut entry = self.entries.entry(server_id).or_default();
+        let previous = entry.state;
+        *entry = BreakerEntry::default();
+        (previous != BreakerState::Closed).then_some(BreakerTransition {
+            from: previous,
+            to: BreakerState::Closed,
+            consecutive_failures: 0,
+        })
+    }
+
+    pub fn record_failure(&self, server_id: i32, now: Instant) -> Option<BreakerTransition> {
+        let mut entry = self.entries.entry(server_id).or_default();
+        entry.consecutive_failures = entry.consecutive_failures.saturating_add(1);
+        entry.probe_started_at = None;
+        let previous = entry.state;
+        let should_open = match previous {
+            BreakerState::Closed => entry.consecutive_failures >= self.failure_threshold,
+            BreakerState::HalfOpen => true,
+            BreakerState::Open => false,
+        };
+        if !should_open {
+            return None;
+        }
+        entry.state = BreakerState::Open;
+        entry.opened_at = Some(now);
+        Some(BreakerTransition {
+            from: previous,
+            to: BreakerState::Open,
+            consecutive_failures: entry.consecutive_failures,
+        })
+    }
+
+    pub fn snapshot(&self, server_id: i32, now: Instant) -> BreakerSnapshot {
+        let entry = self
+            .entries
+            .get(&server_id)
+            .map(|entry| *entry)
+            .unwrap_or_default();
+        let retry_after_secs = match (entry.state, entry.opened_at) {
+            (BreakerState::Open, Some(opened_at)) => Some(
+                self.cooldown
+                    .saturating_sub(now.saturating_duration_since(opened_at))
+                    .as_secs(),
+            ),
+            _ => None,
+        };
+        BreakerSnapshot {
+            server_id,
+            state: entry.state,
+            consecutive_failures: entry.consecutive_failures,
+            failure_threshold: self.failure_threshold,
+            retry_after_secs,
+        }
+    }
+}
+
+/// Persists a breaker state change as a `proxy_breaker_<state>` usage metric.
+pub async fn record_breaker_transition(
+    pool: &PgPool,
+    server_id: i32,
+    transition: Option<BreakerTransition>,
+) {
+    let Some(transition) = transition else {
+        return;
+    };
+    tracing::info!(
+        server_id,
+        from = transition.from.as_str(),
+        to = transition.to.as_str(),
+        consecutive_failures = transition.consecutive_failures,
+        "proxy circuit breaker transition"
+    );
+    let details = json!({
+        "from": transition.from.as_str(),
+        "to": transition.to.as_str(),
+        "consecutive_failures": transition.consecutive_failures,
+    });
+    let event_type = format!("proxy_breaker_{}", transition.to.as_str());
+    if let Err(e) = crate::servers::add_metric(pool, server_id, &event_type, Some(&details)).await {
+        tracing::warn!(?e, server_id, "failed to record proxy breaker metric");
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpstreamSendError {
+    #[error(transparent)]
+    CircuitOpen(#[from] BreakerOpen),
+    #[error("upstream unreachable: {0}")]
+    Unreachable(#[from] reqwest::Error),
+}
+
+/// Sends an upstream request through the server's circuit breaker. Connection errors and
+/// 5xx responses count as failures; anything else closes the breaker. The request
+/// carries the current trace context when span export is enabled.
+#[tracing::instrument(name = "proxy.upstream", skip(pool, request))]
+pub async fn send_guarded(
+    pool: &PgPool,
+    server_id: i32,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, UpstreamSendError> {
+    let transition = PROXY_BREAKERS.acquire(server_id, Instant::now())?;
+    record_breaker_transition(pool, server_id, transition).await;
+    match crate::telemetry::otel::propagate(request).send().await {
+        Ok(resp) => {
+            let transition = if resp.status().is_server_error() {
+                PROXY_BREAKERS.record_failure(server_id, Instant::now())
+            } else {
+                PROXY_BREAKERS.record_success(server_id)
+            };
+            record_breaker_transition(pool, server_id, transition).await;
+            Ok(resp)
+        }
+        Err(e) => {
+            pool::record_send_error(&e);
+            let transition = PROXY_BREAKERS.record_failure(server_id, Instant::now());
+            record_breaker_transition(pool, server_id, transition).await;
+            Err(UpstreamSendError::Unreachable(e))
+        }
+    }
+}
+
+/// Records a request that was dropped at its deadline. Counting it as a failure
+/// also releases a half-open probe that would otherwise stay marked in flight.
+pub async fn record_cancelled(pool: &PgPool, server_id: i32) {
+    let transition = PROXY_BREAKERS.record_failure(server_id, Instant::now());
+    record_breaker_transition(pool, server_id, transition).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        collect_limited, read_response_limited, BreakerState, CircuitBreakerRegistry,
+        ProxyBodyError,
+    };
+    use bytes::Bytes;
+    use futures_util::{stream, StreamExt};
+    use httpmock::prelude::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn oversized_stream_aborts_before_buffering_remaining_chunks() {
+        let pulled = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = pulled.clone();
+        let chunks = stream::iter(0..10).map(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok::<_, std::io::Error>(Bytes::from(vec![b'x'; 400]))
+        });
+
+        let err = collect_limited(chunks, 1000).await.unwrap_err();
+        assert!(matches!(err, ProxyBodyError::TooLarge { limit: 1000 }));
+        assert_eq!(pulled.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn upstream_response_over_cap_is_rejected() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/invoke");
+            then.status(200).body("y".repeat(4096));
+        });
+
+        let response = reqwest::Client::new()
+            .post(server.url("/invoke"))
+            .send()
+            .await
+            .unwrap();
+        let err = read_response_limited(response, 1024).await.unwrap_err();
+        assert!(matches!(err, ProxyBodyError::TooLarge { limit: 1024 }));
+    }
+
+    #[tokio::test]
+    async fn upstream_response_under_cap_passes_through_intact() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/invoke");
+            then.status(200).body(r#"{"result":"ok"}"#);
+        });
+
+        let response = reqwest::Client::new()
+            .post(server.url("/invoke"))
+            .send()
+            .await
+            .unwrap();
+        let body = read_response_limited(response, 1024).await.unwrap();
+        assert_eq!(&body[..], br#"{"result":"ok"}"#);
+    }
+
+    #[test]
+    fn breaker_opens_after_consecutive_failures_and_fast_fails() {
+        let breakers = CircuitBreakerRegistry::new(3, Duration::from_secs(30));
+        let now = Instant::now();
+
+        assert_eq!(breakers.acquire(7, now), Ok(None));
+        assert!(breakers.record_failure(7, now).is_none());
+        assert!(breakers.record_failure(7, now).is_none());
+        let opened = breakers
+            .record_failure(7, now)
+            .expect("third failure opens");
+        assert_eq!(opened.from, BreakerState::Closed);
+        assert_eq!(opened.to, BreakerState::Open);
+
+        let err = breakers
+            .acquire(7, now + Duration::from_secs(10))
+            .unwrap_err();
+        assert_eq!(err.retry_after, Duration::from_secs(20));
+        assert_eq!(breakers.snapshot(7, now).state, BreakerState::Open);
+        assert_eq!(breakers.acquire(8, now), Ok(None));
+    }
+
+    #[test]
+    fn breaker_half_opens_after_cooldown_and_recovers_on_probe_success() {
+        let breakers = CircuitBreakerRegistry::new(1, Duration::from_secs(5));
+        let now = Instant::now();
+        breakers.record_failure(3, now);
+
+        let later = now + Duration::from_secs(6);
+        let probe = breakers.acquire(3, later).unwrap().expect("half-open");
+        assert_eq!(probe.to, BreakerState::HalfOpen);
+        assert!(breakers.acquire(3, later).is_err());
+
+        let closed = breakers.record_success(3).expect("probe closes breaker");
+        assert_eq!(closed.from, BreakerState::HalfOpen);
+        assert_eq!(closed.to, BreakerState::Closed);
+        assert_eq!(breakers.acquire(3, later), Ok(None));
+        assert_eq!(breakers.snapshot(3, later).consecutive_failures, 0);
+    }
+
+    #[test]
+    fn failed_probe_reopens_breaker() {
+        let breakers = CircuitBreakerRegistry::new(1, Duration::from_secs(5));
+        let now = Instant::now();
+        breakers.record_failure(4, now);
+        let later = now + Duration::from_secs(5);
+        breakers.acquire(4, later).unwrap();
+
+        let reopened = breakers.record_failure(4, later).expect("probe failure");
+        assert_eq!(reopened.from, BreakerState::HalfOpen);
+        assert_eq!(reopened.to, BreakerState::Open);
+        assert!(breakers.acquire(4, later + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn abandoned_probe_expires_after_a_cooldown() {
+        let breakers = CircuitBreakerRegistry::new(1, Duration::from_secs(5));
+        let now = Instant::now();
+        breakers.record_failure(5, now);
+        let probing = now + Duration::from_secs(5);
+        breakers.acquire(5, probing).unwrap().expect("half-open");
+
+        // the probe's request is dropped and never records an outcome
+        let err = breakers
+            .acquire(5, probing + Duration::from_secs(2))
+            .unwrap_err();
+        assert_eq!(err.retry_after, Duration::from_secs(3));
+        assert!(breakers.is_open(5, probing + Duration::from_secs(2)));
+
+        let expired = probing + Duration::from_secs(5);
+        assert!(!breakers.is_open(5, expired));
+        assert_eq!(breakers.acquire(5, expired), Ok(None));
+        assert!(breakers.acquire(5, expired).is_err());
+        assert!(breakers.record_success(5).is_some());
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use crate::config;
+
+/// key: proxy-pool -> shared keep-alive clients per upstream server
+pub static UPSTREAM_CLIENTS: Lazy<UpstreamClientPool> =
+    Lazy::new(|| UpstreamClientPool::new(PoolSettings::configured()));
+
+pub const POOL_CLIENTS: &str = "mcp_proxy_pool_clients";
+pub const POOL_REQUESTS_TOTAL: &str = "mcp_proxy_pool_requests_total";
+pub const POOL_CONNECT_FAILURES_TOTAL: &str = "mcp_proxy_pool_connect_failures_total";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolSettings {
+    pub max_idle_per_host: usize,
+    /// How long an unused connection stays open for reuse.
+    pub idle_timeout: Duration,
+    /// TCP keep-alive probe interval; `None` leaves it off.
+    pub tcp_keepalive: Option<Duration>,
+    pub connect_timeout: Duration,
+}
+
+impl PoolSettings {
+    pub fn configured() -> Self {
+        Self {
+            max_idle_per_host: *config::PROXY_POOL_MAX_IDLE_PER_HOST,
+            idle_timeout: Duration::from_secs(*config::PROXY_POOL_IDLE_TIMEOUT_SECS),
+            tcp_keepalive: Some(*config::PROXY_TCP_KEEPALIVE_SECS)
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            connect_timeout: Duration::from_millis(*config::PROXY_CONNECT_TIMEOUT_MS),
+        }
+    }
+}
+
+/// One `reqwest::Client`, and so one connection pool, per upstream server. Sequential
+/// invocations of a server reuse its idle connections instead of dialing each time.
+pub struct UpstreamClientPool {
+    settings: PoolSettings,
+    clients: DashMap<i32, reqwest::Client>,
+}
+
+impl UpstreamClientPool {
+    pub fn new(settings: PoolSettings) -> Self {
+        Self {
+            settings,
+            clients: DashMap::new(),
+        }
+    }
+
+    /// The server's shared client, built on first use. Clones share the pool.
+    pub fn client(&self, server_id: i32) -> reqwest::Client {
+        metrics::increment_counter!(POOL_REQUESTS_TOTAL);
+        if let Some(client) = self.clients.get(&server_id) {
+            return client.clone();
+        }
+        let client = self
+            .clients
+            .entry(server_id)
+            .or_insert_with(|| self.build())
+            .clone();
+        metrics::gauge!(POOL_CLIENTS, self.clients.len() as f64);
+        client
+    }
+
+    /// Drops the server's client along with its idle connections.
+    pub fn forget(&self, server_id: i32) {
+        if self.clients.remove(&server_id).is_some() {
+            metrics::gauge!(POOL_CLIENTS, self.clients.len() as f64);
+        }
+    }
+
+    fn build(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .pool_max_idle_per_host(self.settings.max_idle_per_host)
+            .pool_idle_timeout(self.settings.idle_timeout)
+            .tcp_keepalive(self.settings.tcp_keepalive)
+            .connect_timeout(self.settings.connect_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!(?e, "failed to build pooled upstream client; using defaults");
+                reqwest::Client::new()
+            })
+    }
+}
+
+/// Counts upstream connection failures, including connect timeouts.
+pub fn record_send_error(err: &reqwest::Error) {
+    if err.is_connect() {
+        metrics::increment_counter!(POOL_CONNECT_FAILURES_TOTAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::server::conn::AddrStream;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Response, Server};
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn settings(connect_timeout: Duration) -> PoolSettings {
+        PoolSettings {
+            max_idle_per_host: 4,
+            idle_timeout: Duration::from_secs(30),
+            tcp_keepalive: Some(Duration::from_secs(30)),
+            connect_timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn sequential_requests_reuse_one_connection() {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        let make_service = make_service_fn(move |_: &AddrStream| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async {
+                Ok::<_, Infallible>(service_fn(|_| async {
+                    Ok::<_, Infallible>(Response::new(Body::from(r#"{"ok":true}"#)))
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let pool = UpstreamClientPool::new(settings(Duration::from_secs(2)));
+        for _ in 0..5 {
+            let body = pool
+                .client(7)
+                .post(format!("http://{addr}/invoke"))
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            assert_eq!(body, r#"{"ok":true}"#);
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // a forgotten server starts over with a fresh pool
+        pool.forget(7);
+        pool.client(7)
+            .post(format!("http://{addr}/invoke"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn connect_timeout_is_honored() {
+        let pool = UpstreamClientPool::new(settings(Duration::from_millis(200)));
+        let started = Instant::now();
+        // TEST-NET-1 is never routed, so the connect either times out or fails outright
+        let err = pool
+            .client(9)
+            .post("http://192.0.2.1:8080/invoke")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_connect(), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,346 @@
+use std::str::FromStr;
+use std::time::Instant;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    Json,
+};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{error, warn};
+
+use super::PROXY_BREAKERS;
+use crate::config;
+use crate::extractor::AuthUser;
+use crate::servers::invoke_server_internal;
+
+/// key: proxy-routing -> capability-aware server selection
+pub static CAPABILITY_ROUTER: Lazy<CapabilityRouter> = Lazy::new(CapabilityRouter::new);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingStrategy {
+    /// Always the highest intelligence score.
+    BestScore,
+    /// Takes turns across eligible servers, in server id order.
+    RoundRobin,
+    /// Takes turns in proportion to each server's score.
+    Weighted,
+}
+
+impl RoutingStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoutingStrategy::BestScore => "best_score",
+            RoutingStrategy::RoundRobin => "round_robin",
+            RoutingStrategy::Weighted => "weighted",
+        }
+    }
+}
+
+impl FromStr for RoutingStrategy {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "best_score" => Ok(RoutingStrategy::BestScore),
+            "round_robin" => Ok(RoutingStrategy::RoundRobin),
+            "weighted" => Ok(RoutingStrategy::Weighted),
+            other => Err(format!("unknown routing strategy `{other}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct RouteCandidate {
+    pub server_id: i32,
+    /// Latest intelligence score for the capability; `0` when it was never observed.
+    pub score: f64,
+}
+
+/// Per-capability turn counters for the rotating strategies.
+pub struct CapabilityRouter {
+    turns: DashMap<String, u64>,
+}
+
+impl Default for CapabilityRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CapabilityRouter {
+    pub fn new() -> Self {
+        Self {
+            turns: DashMap::new(),
+        }
+    }
+
+    fn next_turn(&self, capability: &str) -> u64 {
+        let mut turn = self.turns.entry(capability.to_string()).or_insert(0);
+        let current = *turn;
+        *turn = current.wrapping_add(1);
+        current
+    }
+
+    /// The order in which to try servers for `capability`: the strategy's pick first, then
+    /// the rest by score as fallbacks. Servers for which `is_open` holds are left out.
+    pub fn route(
+        &self,
+        capability: &str,
+        strategy: RoutingStrategy,
+        candidates: &[RouteCandidate],
+        is_open: impl Fn(i32) -> bool,
+    ) -> Vec<i32> {
+        let mut eligible: Vec<&RouteCandidate> = candidates
+            .iter()
+            .filter(|candidate| !is_open(candidate.server_id))
+            .collect();
+        if eligible.is_empty() {
+            return Vec::new();
+        }
+        eligible.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.server_id.cmp(&b.server_id))
+        });
+
+        let mut order: Vec<i32> = eligible
+            .iter()
+            .map(|candidate| candidate.server_id)
+            .collect();
+        match strategy {
+            RoutingStrategy::BestScore => {}
+            RoutingStrategy::RoundRobin => {
+                order.sort_unstable();
+                let turn = self.next_turn(capability) % order.len() as u64;
+                order.rotate_left(turn as usize);
+            }
+            RoutingStrategy::Weighted => {
+                let pick = weighted_pick(&eligible, self.next_turn(capability));
+                let chosen = order.remove(pick);
+                order.insert(0, chosen);
+            }
+        }
+        order
+    }
+}
+
+/// Maps a turn onto the candidates' cumulative weights. Weights are scores rounded to whole
+/// points, at least `1`, so an unscored server still gets an occasional turn.
+fn weighted_pick(candidates: &[&RouteCandidate], turn: u64) -> usize {
+    let weights: Vec<u64> = candidates
+        .iter()
+        .map(|candidate| (candidate.score.max(0.0).round() as u64).max(1))
+        .collect();
+    let mut point = turn % weights.iter().sum::<u64>();
+    for (index, weight) in weights.iter().enumerate() {
+        if point < *weight {
+            return index;
+        }
+        point -= weight;
+    }
+    0
+}
+
+/// The caller's running servers that declare `capability`, with their latest score for it.
+pub async fn load_candidates(
+    pool: &PgPool,
+    owner_id: i32,
+    capability: &str,
+) -> Result<Vec<RouteCandidate>, sqlx::Error> {
+    sqlx::query_as::<_, RouteCandidate>(
+        r#"
+        SELECT s.id AS server_id, COALESCE(latest.score, 0)::FLOAT8 AS score
+        FROM mcp_servers s
+        LEFT JOIN LATERAL (
+            SELECT scores.score
+            FROM capability_intelligence_scores scores
+            WHERE scores.server_id = s.id AND scores.capability = $2
+            ORDER BY scores.last_observed_at DESC
+            LIMIT 1
+        ) latest ON TRUE
+        WHERE s.owner_id = $1
+          AND s.status = 'running'
+          AND s.deleted_at IS NULL
+          AND EXISTS (
+              SELECT 1
+              FROM server_capabilities caps
+              WHERE caps.server_id = s.id AND caps.name = $2
+          )
+        "#,
+    )
+    .bind(owner_id)
+    .bind(capability)
+    .fetch_all(pool)
+    .await
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CapabilityInvokeQuery {
+    /// Overrides `PROXY_ROUTING_STRATEGY` for this call.
+    #[serde(default)]
+    pub strategy: Option<RoutingStrategy>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutedInvocation {
+    pub capability: String,
+    pub strategy: RoutingStrategy,
+    pub server_id: i32,
+    /// Servers whose circuit opened between routing and sending, tried before `server_id`.
+    pub skipped: Vec<i32>,
+    pub output: Value,
+}
+
+/// Forwards the JSON body to one of the caller's servers offering `capability`. A server whose
+/// breaker turns out to be open is skipped in favour of the next one. Other upstream errors are
+/// returned as they are, since the request may already have reached the server.
+pub async fn invoke_capability(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(capability): Path<String>,
+    Query(query): Query<CapabilityInvokeQuery>,
+    Json(payload): Json<Value>,
+) -> Result<Json<RoutedInvocation>, (StatusCode, String)> {
+    let candidates = load_candidates(&pool, user_id, &capability)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error loading routing candidates");
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })?;
+    if candidates.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("no running server offers `{capability}`"),
+        ));
+    }
+
+    let strategy = query.strategy.unwrap_or(*config::PROXY_ROUTING_STRATEGY);
+    let order = CAPABILITY_ROUTER.route(&capability, strategy, &candidates, |server_id| {
+        PROXY_BREAKERS.is_open(server_id, Instant::now())
+    });
+    let mut skipped = Vec::new();
+    let mut last_error = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        format!("every server offering `{capability}` has an open circuit"),
+    );
+    for server_id in order {
+        match invoke_server_internal(&pool, user_id, server_id, &payload).await {
+            Ok(output) => {
+                return Ok(Json(RoutedInvocation {
+                    capability,
+                    strategy,
+                    server_id,
+                    skipped,
+                    output,
+                }))
+            }
+            Err((status, message)) if status == StatusCode::SERVICE_UNAVAILABLE => {
+                warn!(server_id, %capability, %message, "routed invocation skipped server");
+                skipped.push(server_id);
+                last_error = (status, message);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(scores: &[(i32, f64)]) -> Vec<RouteCandidate> {
+        scores
+            .iter()
+            .map(|(server_id, score)| RouteCandidate {
+                server_id: *server_id,
+                score: *score,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn best_score_prefers_the_higher_scored_server() {
+        let router = CapabilityRouter::new();
+        let servers = candidates(&[(1, 62.5), (2, 91.0), (3, 78.0)]);
+        for _ in 0..3 {
+            assert_eq!(
+                router.route("search", RoutingStrategy::BestScore, &servers, |_| false),
+                vec![2, 3, 1]
+            );
+        }
+    }
+
+    #[test]
+    fn circuit_open_server_is_skipped_for_the_next_best() {
+        let router = CapabilityRouter::new();
+        let servers = candidates(&[(1, 62.5), (2, 91.0), (3, 78.0)]);
+        assert_eq!(
+            router.route("search", RoutingStrategy::BestScore, &servers, |id| id == 2),
+            vec![3, 1]
+        );
+        assert!(router
+            .route("search", RoutingStrategy::Weighted, &servers, |_| true)
+            .is_empty());
+
+        // a real breaker: open after one failure, eligible again once the cooldown passes
+        let breakers =
+            crate::proxy::CircuitBreakerRegistry::new(1, std::time::Duration::from_secs(30));
+        let now = Instant::now();
+        breakers.record_failure(2, now);
+        let order = router.route("search", RoutingStrategy::BestScore, &servers, |id| {
+            breakers.is_open(id, now)
+        });
+        assert_eq!(order[0], 3);
+        let later = now + std::time::Duration::from_secs(31);
+        let order = router.route("search", RoutingStrategy::BestScore, &servers, |id| {
+            breakers.is_open(id, later)
+        });
+        assert_eq!(order[0], 2);
+    }
+
+    #[test]
+    fn round_robin_rotates_per_capability() {
+        let router = CapabilityRouter::new();
+        let servers = candidates(&[(3, 10.0), (1, 90.0), (2, 50.0)]);
+        let picks: Vec<i32> = (0..4)
+            .map(|_| router.route("search", RoutingStrategy::RoundRobin, &servers, |_| false)[0])
+            .collect();
+        assert_eq!(picks, vec![1, 2, 3, 1]);
+        // another capability keeps its own turn
+        assert_eq!(
+            router.route("summarize", RoutingStrategy::RoundRobin, &servers, |_| {
+                false
+            }),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn weighted_turns_follow_the_scores() {
+        let router = CapabilityRouter::new();
+        let servers = candidates(&[(1, 75.0), (2, 25.0)]);
+        let mut counts = [0usize; 2];
+        for _ in 0..100 {
+            let order = router.route("search", RoutingStrategy::Weighted, &servers, |_| false);
+            assert_eq!(order.len(), 2);
+            counts[(order[0] - 1) as usize] += 1;
+        }
+        assert_eq!(counts, [75, 25]);
+    }
+
+    #[test]
+    fn strategies_parse_from_config_values() {
+        assert_eq!(
+            " Round_Robin ".parse::<RoutingStrategy>(),
+            Ok(RoutingStrategy::RoundRobin)
+        );
+        assert!("random".parse::<RoutingStrategy>().is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// key: proxy-transforms -> per-server request/response JSON rewrites
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TransformOp {
+    /// Moves the value at `from` to `to`.
+    Rename { from: String, to: String },
+    /// Sets `path` to `value`, creating missing parent objects.
+    Inject { path: String, value: Value },
+    /// Removes `path`; a missing field is left alone.
+    Strip { path: String },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformFailureMode {
+    /// A failing transform fails the invocation.
+    #[default]
+    FailClosed,
+    /// A failing transform is skipped and the untouched body is forwarded.
+    PassThrough,
+}
+
+/// A server's transforms. Paths are JSON pointers such as `/params/arguments/query`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyTransforms {
+    #[serde(default)]
+    pub request: Vec<TransformOp>,
+    #[serde(default)]
+    pub response: Vec<TransformOp>,
+    #[serde(default)]
+    pub on_error: TransformFailureMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TransformError {
+    #[error("invalid transform config: {0}")]
+    Invalid(String),
+    #[error("`{0}` is missing")]
+    Missing(String),
+    #[error("`{0}` is not inside an object")]
+    NotAnObject(String),
+    #[error("body is not JSON: {0}")]
+    NotJson(String),
+}
+
+impl ProxyTransforms {
+    /// Parses and validates a stored or submitted config.
+    pub fn from_value(value: Value) -> Result<Self, TransformError> {
+        let transforms: Self =
+            serde_json::from_value(value).map_err(|e| TransformError::Invalid(e.to_string()))?;
+        transforms.validate()?;
+        Ok(transforms)
+    }
+
+    /// Every path must be a JSON pointer below the root, and a rename cannot move a field
+    /// into itself.
+    pub fn validate(&self) -> Result<(), TransformError> {
+        for op in self.request.iter().chain(&self.response) {
+            match op {
+                TransformOp::Rename { from, to } => {
+                    split_pointer(from)?;
+                    split_pointer(to)?;
+                    if to == from || to.starts_with(&format!("{from}/")) {
+                        return Err(TransformError::Invalid(format!(
+                            "cannot rename `{from}` to `{to}`"
+                        )));
+                    }
+                }
+                TransformOp::Inject { path, .. } | TransformOp::Strip { path } => {
+                    split_pointer(path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.request.is_empty() && self.response.is_empty()
+    }
+
+    /// Rewrites the payload sent upstream.
+    pub fn transform_request(&self, payload: &Value) -> Result<Value, TransformError> {
+        let mut transformed = payload.clone();
+        match apply(&self.request, &mut transformed) {
+            Ok(()) => Ok(transformed),
+            Err(err) => self.recover(err).map(|()| payload.clone()),
+        }
+    }
+
+    /// Rewrites an upstream response body. Bodies pass untouched when there are no response
+    /// transforms.
+    pub fn transform_response(&self, body: Bytes) -> Result<Bytes, TransformError> {
+        if self.response.is_empty() {
+            return Ok(body);
+        }
+        let rewritten = serde_json::from_slice::<Value>(&body)
+            .map_err(|e| TransformError::NotJson(e.to_string()))
+            .and_then(|mut value| {
+                apply(&self.response, &mut value)?;
+                serde_json::to_vec(&value)
+                    .map(Bytes::from)
+                    .map_err(|e| TransformError::NotJson(e.to_string()))
+            });
+        match rewritten {
+            Ok(rewritten) => Ok(rewritten),
+            Err(err) => self.recover(err).map(|()| body),
+        }
+    }
+
+    fn recover(&self, err: TransformError) -> Result<(), TransformError> {
+        match self.on_error {
+            TransformFailureMode::FailClosed => Err(err),
+            TransformFailureMode::PassThrough => {
+                tracing::warn!(%err, "proxy transform failed; passing the body through");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Splits a JSON pointer into its parent pointer and unescaped last token.
+fn split_pointer(path: &str) -> Result<(&str, String), TransformError> {
+    let Some((parent, last)) = path.rsplit_once('/') else {
+        return Err(TransformError::Invalid(format!(
+            "`{path}` is not a JSON pointer"
+        )));
+    };
+    if !path.starts_with('/') || last.is_empty() {
+        return Err(TransformError::Invalid(format!(
+            "`{path}` must name a field below the root"
+        )));
+    }
+    Ok((parent, last.replace("~1", "/").replace("~0", "~")))
+}
+
+fn apply(ops: &[TransformOp], value: &mut Value) -> Result<(), TransformError> {
+    for op in ops {
+        match op {
+            TransformOp::Rename { from, to } => {
+                let moved =
+                    remove(value, from)?.ok_or_else(|| TransformError::Missing(from.clone()))?;
+                insert(value, to, moved)?;
+            }
+            TransformOp::Inject {
+                path,
+                value: injected,
+            } => {
+                insert(value, path, injected.clone())?;
+            }
+            TransformOp::Strip { path } => {
+                remove(value, path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn remove(value: &mut Value, path: &str) -> Result<Option<Value>, TransformError> {
+    let (parent, key) = split_pointer(path)?;
+    Ok(value
+        .pointer_mut(parent)
+        .and_then(Value::as_object_mut)
+        .and_then(|object| object.remove(&key)))
+}
+
+fn insert(value: &mut Value, path: &str, inserted: Value) -> Result<(), TransformError> {
+    let (parent, key) = split_pointer(path)?;
+    let mut target = value;
+    for token in parent.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let object = target
+            .as_object_mut()
+            .ok_or_else(|| TransformError::NotAnObject(path.to_string()))?;
+        target = object
+            .entry(token)
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    target
+        .as_object_mut()
+        .ok_or_else(|| TransformError::NotAnObject(path.to_string()))?
+        .insert(key, inserted);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transforms(config: Value) -> ProxyTransforms {
+        ProxyTransforms::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn request_fields_are_injected_and_renamed() {
+        let transforms = transforms(json!({
+            "request": [
+                { "op": "inject", "path": "/params/meta/api_version", "value": "2024-06" },
+                { "op": "rename", "from": "/params/q", "to": "/params/query" }
+            ]
+        }));
+        let payload = json!({ "method": "tools/call", "params": { "q": "rust" } });
+        assert_eq!(
+            transforms.transform_request(&payload).unwrap(),
+            json!({
+                "method": "tools/call",
+                "params": { "query": "rust", "meta": { "api_version": "2024-06" } }
+            })
+        );
+    }
+
+    #[test]
+    fn response_fields_are_stripped() {
+        let transforms = transforms(json!({
+            "response": [
+                { "op": "strip", "path": "/result/debug" },
+                { "op": "strip", "path": "/absent" }
+            ]
+        }));
+        let body = Bytes::from_static(br#"{"result":{"text":"ok","debug":{"trace":[1,2]}}}"#);
+        let rewritten = transforms.transform_response(body).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&rewritten).unwrap(),
+            json!({ "result": { "text": "ok" } })
+        );
+    }
+
+    #[test]
+    fn failing_transforms_fail_closed_unless_configured_to_pass_through() {
+        let config = json!({
+            "request": [{ "op": "rename", "from": "/params/q", "to": "/params/query" }],
+            "response": [{ "op": "strip", "path": "/result/debug" }]
+        });
+        let payload = json!({ "params": { "query": "already renamed" } });
+        let closed = transforms(config.clone());
+        assert_eq!(
+            closed.transform_request(&payload),
+            Err(TransformError::Missing("/params/q".into()))
+        );
+        assert!(matches!(
+            closed.transform_response(Bytes::from_static(b"plain text")),
+            Err(TransformError::NotJson(_))
+        ));
+
+        let mut config = config;
+        config["on_error"] = json!("pass_through");
+        let open = transforms(config);
+        assert_eq!(open.transform_request(&payload).unwrap(), payload);
+        assert_eq!(
+            open.transform_response(Bytes::from_static(b"plain text"))
+                .unwrap(),
+            Bytes::from_static(b"plain text")
+        );
+    }
+
+    #[test]
+    fn malformed_configs_are_rejected_at_load() {
+        for config in [
+            json!({ "request": [{ "op": "strip", "path": "params" }] }),
+            json!({ "request": [{ "op": "strip", "path": "/" }] }),
+            json!({ "request": [{ "op": "rename", "from": "/a", "to": "/a/b" }] }),
+            json!({ "request": [{ "op": "uppercase", "path": "/a" }] }),
+            json!({ "requests": [] }),
+        ] {
+            assert!(
+                matches!(
+                    ProxyTransforms::from_value(config.clone()),
+                    Err(TransformError::Invalid(_))
+                ),
+                "{config}"
+            );
+        }
+    }
+}
@@ -1,3 +1,14 @@
+pub mod artifact_content;
+pub mod artifact_retention;
+pub mod gate_context;
+pub mod impact;
+pub mod lineage;
+pub mod maintenance;
+pub mod note_templates;
+pub mod payload_schema;
+pub mod playbook_validation;
+pub mod poll;
+
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -14,22 +25,31 @@ use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
-use crate::db::runtime_vm_remediation_artifacts::insert_artifact;
 use crate::db::runtime_vm_remediation_playbooks::{
     get_by_id as get_playbook_by_id, get_by_key as get_playbook_by_key,
     RuntimeVmRemediationPlaybook,
 };
 use crate::db::runtime_vm_remediation_runs::{
     ensure_remediation_run, get_active_run_for_instance, mark_run_completed, mark_run_failed,
-    try_acquire_next_run, EnsureRemediationRunRequest, RuntimeVmRemediationRun,
+    record_run_resource_usage, release_due_scheduled_runs, try_acquire_next_run,
+    EnsureRemediationRunRequest, RuntimeVmRemediationRun,
+};
+use crate::db::runtime_vm_remediation_workspaces::{
+    prune_validation_snapshots, RuntimeVmRemediationWorkspaceRevision,
 };
 use crate::db::runtime_vm_trust_registry::{
     get_state as get_registry_state, upsert_state as upsert_registry_state,
     UpsertRuntimeVmTrustRegistryState,
 };
+use crate::remediation::artifact_content::record_artifact;
+use crate::remediation::poll::{notify_work_available, wait_for_work, PollBackoff};
+use crate::runtime::{ResourceUsage, RuntimeExecutor, VirtualMachineExecutor};
+use crate::telemetry::tenant::TENANT_METRICS;
 use crate::trust::{subscribe_registry_events, TrustRegistryEvent};
 
 const DEFAULT_PLAYBOOK: &str = "default-vm-remediation";
+/// Playbook metadata flag opting runs into a VM snapshot with rollback on failure.
+const VM_SNAPSHOT_FLAG: &str = "vm_snapshot";
 const REMEDIATION_STREAM_BUFFER: usize = 64;
 const REMEDIATION_BROADCAST_BUFFER: usize = 128;
 
@@ -44,9 +64,108 @@ pub fn subscribe_remediation_events() -> broadcast::Receiver<RemediationStreamMe
     REMEDIATION_EVENT_CHANNEL.subscribe()
 }
 
+// key: remediation-orchestrator -> gate-ordering
+pub static WORKSPACE_GATE_GRAPH: Lazy<WorkspaceGateGraph> =
+    Lazy::new(|| WorkspaceGateGraph::new(crate::config::REMEDIATION_GATE_DEPENDENCIES.clone()));
+
+const PASSING_GATE_STATES: &[&str] = &["passed", "succeeded", "approved", "completed"];
+
+/// Gates a workspace revision tracks; see [`workspace_gate_status`].
+pub const WORKSPACE_GATES: &[&str] = &["schema", "policy", "simulation", "promotion"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnmetWorkspaceGate {
+    pub gate: String,
+    pub status: String,
+}
+
+/// Dependency graph describing which workspace gates must pass before another gate applies.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceGateGraph {
+    dependencies: HashMap<String, Vec<String>>,
+}
+
+impl WorkspaceGateGraph {
+    pub fn new(dependencies: HashMap<String, Vec<String>>) -> Self {
+        Self { dependencies }
+    }
+
+    /// Parses a `REMEDIATION_GATE_DEPENDENCIES` object, rejecting gate names a revision does not
+    /// track so a typo cannot leave a prerequisite permanently unmet.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let dependencies: HashMap<String, Vec<String>> = serde_json::from_value(value.clone())
+            .map_err(|err| format!("unexpected JSON shape: {err}"))?;
+        for (gate, prerequisites) in &dependencies {
+            for name in std::iter::once(gate).chain(prerequisites) {
+                if !WORKSPACE_GATES.contains(&name.as_str()) {
+                    return Err(format!(
+                        "unknown gate `{name}`; expected one of {}",
+                        WORKSPACE_GATES.join(", ")
+                    ));
+                }
+            }
+        }
+        Ok(Self::new(dependencies))
+    }
+
+    /// Returns every transitive prerequisite of `gate`, nearest first, without duplicates.
+    pub fn prerequisites(&self, gate: &str) -> Vec<String> {
+        let mut ordered = Vec::new();
+        let mut pending: Vec<&str> = vec![gate];
+        while let Some(current) = pending.pop() {
+            let Some(direct) = self.dependencies.get(current) else {
+                continue;
+            };
+            for prerequisite in direct {
+                if prerequisite != gate && !ordered.contains(prerequisite) {
+                    ordered.push(prerequisite.clone());
+                    pending.push(prerequisite.as_str());
+                }
+            }
+        }
+        ordered
+    }
+
+    pub fn unmet_prerequisites(
+        &self,
+        gate: &str,
+        revision: &RuntimeVmRemediationWorkspaceRevision,
+    ) -> Vec<UnmetWorkspaceGate> {
+        self.prerequisites(gate)
+            .into_iter()
+            .filter_map(|prerequisite| {
+                let status = workspace_gate_status(revision, &prerequisite)
+                    .unwrap_or("unknown")
+                    .to_string();
+                if PASSING_GATE_STATES.contains(&status.as_str()) {
+                    None
+                } else {
+                    Some(UnmetWorkspaceGate {
+                        gate: prerequisite,
+                        status,
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn workspace_gate_status<'a>(
+    revision: &'a RuntimeVmRemediationWorkspaceRevision,
+    gate: &str,
+) -> Option<&'a str> {
+    match gate {
+        "schema" => Some(revision.schema_status.as_str()),
+        "policy" => Some(revision.policy_status.as_str()),
+        "simulation" => Some(revision.simulation_status.as_str()),
+        "promotion" => Some(revision.promotion_status.as_str()),
+        _ => None,
+    }
+}
+
 // key: remediation-orchestrator -> execution-engine
-pub fn spawn(pool: PgPool) {
-    let registry = Arc::new(RemediationExecutorRegistry::bootstrap());
+pub fn spawn(pool: PgPool, vm_executor: Option<Arc<VirtualMachineExecutor>>) {
+    let registry = Arc::new(RemediationExecutorRegistry::bootstrap(vm_executor));
     let pool_clone = pool.clone();
     let registry_clone = registry.clone();
 
@@ -59,6 +178,24 @@ pub fn spawn(pool: PgPool) {
     });
 }
 
+// key: remediation-orchestrator -> snapshot-retention
+pub fn spawn_snapshot_retention(pool: PgPool) {
+    let interval = Duration::from_secs(*crate::config::REMEDIATION_SNAPSHOT_PRUNE_INTERVAL_SECS);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let keep_latest = crate::config::reload::current().remediation_snapshot_retention;
+            match prune_validation_snapshots(&pool, keep_latest).await {
+                Ok(0) => {}
+                Ok(pruned) => info!(pruned, keep_latest, "pruned workspace validation snapshots"),
+                Err(err) => warn!(?err, "workspace validation snapshot retention sweep failed"),
+            }
+        }
+    });
+}
+
 async fn remediation_event_listener(pool: PgPool, registry: Arc<RemediationExecutorRegistry>) {
     let mut receiver = subscribe_registry_events();
     while let Ok(event) = receiver.recv().await {
@@ -76,17 +213,27 @@ async fn remediation_event_listener(pool: PgPool, registry: Arc<RemediationExecu
 }
 
 async fn remediation_worker(pool: PgPool, registry: Arc<RemediationExecutorRegistry>) {
+    let mut backoff = PollBackoff::configured();
     loop {
+        backoff.refresh_configured();
+        match release_due_scheduled_runs(&pool).await {
+            Ok(0) => {}
+            Ok(released) => info!(released, "queued scheduled remediation runs"),
+            Err(err) => error!(?err, "remediation worker failed to queue scheduled runs"),
+        }
         match dispatch_next_run(&pool, &registry).await {
             Ok(Some(_)) => {
+                backoff.record_work();
                 continue;
             }
             Ok(None) => {
-                sleep(Duration::from_secs(1)).await;
+                if wait_for_work(backoff.idle_delay()).await {
+                    debug!("remediation worker woken by enqueue");
+                }
             }
             Err(err) => {
                 error!(?err, "remediation worker failed to dispatch next run");
-                sleep(Duration::from_secs(2)).await;
+                sleep(backoff.idle_delay()).await;
             }
         }
     }
@@ -152,6 +299,11 @@ async fn resolve_playbook(
     }
 }
 
+#[tracing::instrument(
+    name = "remediation.run",
+    skip_all,
+    fields(run_id = run.id, vm_instance_id = run.runtime_vm_instance_id)
+)]
 async fn execute_run(
     pool: PgPool,
     run: RuntimeVmRemediationRun,
@@ -176,6 +328,7 @@ async fn execute_run(
             failure_reason,
             "executor not registered".to_string(),
             None,
+            None,
         )
         .await
         {
@@ -184,106 +337,218 @@ async fn execute_run(
         return;
     };
 
+    let snapshot_requested = playbook.as_ref().is_some_and(requests_vm_snapshot);
     let metadata = merge_metadata(&run, playbook.as_ref());
     let context = RemediationExecutionRequest {
         run: run.clone(),
         playbook,
         metadata,
     };
+    let work = collect_outcome(executor, context, &run);
 
-    match executor.execute(context).await {
-        Ok(handle) => {
-            let (mut log_rx, completion, _cancel) = handle.into_parts();
-            let mut collected_logs = Vec::new();
-            while let Some(event) = log_rx.recv().await {
-                collected_logs.push(event.clone());
-                broadcast_log(&run, &event);
-            }
-
-            match completion.await {
-                Ok(Ok(status)) if status.code == 0 => {
-                    let message = status.message.clone();
-                    if let Err(err) =
-                        finalize_success(&pool, run.clone(), collected_logs, status).await
-                    {
-                        error!(?err, "failed to persist remediation success");
-                    } else {
-                        broadcast_status(&run, "completed", None, message);
-                    }
-                }
-                Ok(Ok(status)) => {
-                    let reason = status
-                        .failure_reason
-                        .unwrap_or(RemediationFailureReason::ExecutionFailure);
-                    let message = status
-                        .message
-                        .clone()
-                        .unwrap_or_else(|| "remediation executor returned non-zero exit".into());
-                    if let Err(err) = finalize_failure(
-                        &pool,
-                        run.clone(),
-                        reason,
-                        message.clone(),
-                        Some(collected_logs),
-                    )
+    let (outcome, vm_snapshot) = if snapshot_requested {
+        match snapshot_target(&pool, &registry, &run).await {
+            Ok((vm_executor, instance_id)) => {
+                match vm_executor
+                    .run_with_snapshot(&instance_id, work, RunOutcome::is_failure)
                     .await
-                    {
-                        error!(?err, "failed to persist remediation failure");
-                    } else {
-                        broadcast_status(&run, "failed", Some(reason), Some(message));
-                    }
-                }
-                Ok(Err(err)) => {
-                    let failure_reason = err.failure_reason();
-                    let message = err.to_string();
-                    if let Err(inner) = finalize_failure(
-                        &pool,
-                        run.clone(),
-                        failure_reason,
-                        message.clone(),
-                        Some(collected_logs),
-                    )
-                    .await
-                    {
-                        error!(?inner, "failed to persist remediation error outcome");
-                    } else {
-                        broadcast_status(&run, "failed", Some(failure_reason), Some(message));
-                    }
-                }
-                Err(join_err) => {
-                    let message = join_err.to_string();
-                    if let Err(inner) = finalize_failure(
-                        &pool,
-                        run.clone(),
-                        RemediationFailureReason::TransientInfrastructure,
-                        message.clone(),
-                        Some(collected_logs),
-                    )
-                    .await
-                    {
-                        error!(?inner, "failed to persist remediation join failure");
-                    } else {
-                        broadcast_status(
-                            &run,
-                            "failed",
-                            Some(RemediationFailureReason::TransientInfrastructure),
-                            Some(message),
-                        );
+                {
+                    Ok(guarded) => {
+                        let snapshot = guarded.metadata();
+                        (guarded.output, Some(snapshot))
                     }
+                    Err(err) => (
+                        RunOutcome::Failed {
+                            reason: RemediationFailureReason::TransientInfrastructure,
+                            message: format!("{err:#}"),
+                            logs: None,
+                        },
+                        None,
+                    ),
                 }
             }
+            Err(outcome) => (outcome, None),
         }
-        Err(err) => {
-            let failure_reason = err.failure_reason();
-            let message = err.to_string();
-            if let Err(inner) =
-                finalize_failure(&pool, run.clone(), failure_reason, message.clone(), None).await
+    } else {
+        (work.await, None)
+    };
+
+    match outcome {
+        RunOutcome::Completed { logs, status } => {
+            let message = status.message.clone();
+            if let Err(err) =
+                finalize_success(&pool, run.clone(), logs, status, vm_snapshot.as_ref()).await
             {
-                error!(?inner, "failed to persist remediation spawn error");
+                error!(?err, "failed to persist remediation success");
             } else {
-                broadcast_status(&run, "failed", Some(failure_reason), Some(message));
+                broadcast_status(&run, "completed", None, message);
             }
         }
+        RunOutcome::Failed {
+            reason,
+            message,
+            logs,
+        } => {
+            if let Err(err) = finalize_failure(
+                &pool,
+                run.clone(),
+                reason,
+                message.clone(),
+                logs,
+                vm_snapshot.as_ref(),
+            )
+            .await
+            {
+                error!(?err, "failed to persist remediation failure");
+            } else {
+                broadcast_status(&run, "failed", Some(reason), Some(message));
+            }
+        }
+    }
+
+    if let Some(vm_executor) = registry.vm_executor.as_deref() {
+        if let Err(err) = record_resource_usage(&pool, &run, vm_executor).await {
+            warn!(
+                ?err,
+                run_id = run.id,
+                "failed to record remediation resource usage"
+            );
+        }
+    }
+}
+
+/// Asks `executor` for the resource usage of the server behind the run's VM instance and
+/// stores it in the run metadata. Nothing is written when the executor cannot measure it.
+pub async fn record_resource_usage(
+    pool: &PgPool,
+    run: &RuntimeVmRemediationRun,
+    executor: &dyn RuntimeExecutor,
+) -> Result<Option<ResourceUsage>, RemediationError> {
+    let server_id: Option<i32> =
+        sqlx::query_scalar("SELECT server_id FROM runtime_vm_instances WHERE id = $1")
+            .bind(run.runtime_vm_instance_id)
+            .fetch_optional(pool)
+            .await?;
+    let Some(server_id) = server_id else {
+        return Ok(None);
+    };
+    let Some(usage) = executor.resource_usage(server_id).await else {
+        return Ok(None);
+    };
+    record_run_resource_usage(pool, run.id, &json!(usage)).await?;
+    Ok(Some(usage))
+}
+
+enum RunOutcome {
+    Completed {
+        logs: Vec<RemediationLogEvent>,
+        status: RemediationExitStatus,
+    },
+    Failed {
+        reason: RemediationFailureReason,
+        message: String,
+        logs: Option<Vec<RemediationLogEvent>>,
+    },
+}
+
+impl RunOutcome {
+    fn is_failure(&self) -> bool {
+        matches!(self, RunOutcome::Failed { .. })
+    }
+}
+
+fn requests_vm_snapshot(playbook: &RuntimeVmRemediationPlaybook) -> bool {
+    playbook
+        .metadata
+        .get(VM_SNAPSHOT_FLAG)
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Resolves the VM executor and hypervisor instance a snapshot-guarded run operates on,
+/// or the failure to record when the run cannot be protected.
+async fn snapshot_target(
+    pool: &PgPool,
+    registry: &RemediationExecutorRegistry,
+    run: &RuntimeVmRemediationRun,
+) -> Result<(Arc<VirtualMachineExecutor>, String), RunOutcome> {
+    let Some(vm_executor) = registry.vm_executor.clone() else {
+        return Err(RunOutcome::Failed {
+            reason: RemediationFailureReason::ExecutorUnavailable,
+            message: "playbook requests a vm snapshot but no vm executor is configured".into(),
+            logs: None,
+        });
+    };
+    let instance_id: Option<String> =
+        sqlx::query_scalar("SELECT instance_id FROM runtime_vm_instances WHERE id = $1")
+            .bind(run.runtime_vm_instance_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|err| RunOutcome::Failed {
+                reason: RemediationFailureReason::TransientInfrastructure,
+                message: err.to_string(),
+                logs: None,
+            })?;
+    match instance_id {
+        Some(instance_id) => Ok((vm_executor, instance_id)),
+        None => Err(RunOutcome::Failed {
+            reason: RemediationFailureReason::DependencyUnavailable,
+            message: format!(
+                "vm instance {} not found for snapshot",
+                run.runtime_vm_instance_id
+            ),
+            logs: None,
+        }),
+    }
+}
+
+async fn collect_outcome(
+    executor: Arc<dyn RemediationExecutor>,
+    context: RemediationExecutionRequest,
+    run: &RuntimeVmRemediationRun,
+) -> RunOutcome {
+    let handle = match executor.execute(context).await {
+        Ok(handle) => handle,
+        Err(err) => {
+            return RunOutcome::Failed {
+                reason: err.failure_reason(),
+                message: err.to_string(),
+                logs: None,
+            }
+        }
+    };
+
+    let (mut log_rx, completion, _cancel) = handle.into_parts();
+    let mut collected_logs = Vec::new();
+    while let Some(event) = log_rx.recv().await {
+        collected_logs.push(event.clone());
+        broadcast_log(run, &event);
+    }
+
+    match completion.await {
+        Ok(Ok(status)) if status.code == 0 => RunOutcome::Completed {
+            logs: collected_logs,
+            status,
+        },
+        Ok(Ok(status)) => RunOutcome::Failed {
+            reason: status
+                .failure_reason
+                .unwrap_or(RemediationFailureReason::ExecutionFailure),
+            message: status
+                .message
+                .unwrap_or_else(|| "remediation executor returned non-zero exit".into()),
+            logs: Some(collected_logs),
+        },
+        Ok(Err(err)) => RunOutcome::Failed {
+            reason: err.failure_reason(),
+            message: err.to_string(),
+            logs: Some(collected_logs),
+        },
+        Err(join_err) => RunOutcome::Failed {
+            reason: RemediationFailureReason::TransientInfrastructure,
+            message: join_err.to_string(),
+            logs: Some(collected_logs),
+        },
     }
 }
 
@@ -323,12 +588,13 @@ async fn handle_quarantine_event(
         workspace_id: None,
         workspace_revision_id: None,
         promotion_gate_context: None,
+        scheduled_for: None,
     };
 
-    if ensure_remediation_run(&mut *tx, request).await?.is_none() {
+    let Some(run) = ensure_remediation_run(&mut *tx, request).await? else {
         tx.commit().await?;
         return Ok(());
-    }
+    };
 
     let attempts = current_state
         .as_ref()
@@ -364,6 +630,8 @@ async fn handle_quarantine_event(
     .await?;
 
     tx.commit().await?;
+    notify_work_available();
+    TENANT_METRICS.run_enqueued(run.organization_id);
     Ok(())
 }
 
@@ -428,29 +696,34 @@ async fn finalize_success(
     run: RuntimeVmRemediationRun,
     logs: Vec<RemediationLogEvent>,
     status: RemediationExitStatus,
+    vm_snapshot: Option<&Value>,
 ) -> Result<(), RemediationError> {
     let payload = json!({
         "exit_code": status.code,
         "message": status.message,
     });
 
-    let metadata = json!({
+    let mut metadata = json!({
         "logs": logs.clone(),
     });
+    if let Some(snapshot) = vm_snapshot {
+        metadata[VM_SNAPSHOT_FLAG] = snapshot.clone();
+    }
 
     let mut tx = pool.begin().await?;
-    if let Some(record) =
-        mark_run_completed(&mut *tx, run.id, Some(&metadata), Some(&payload)).await?
-    {
+    let completed = mark_run_completed(&mut *tx, run.id, Some(&metadata), Some(&payload)).await?;
+    let finalized = completed.is_some();
+    if let Some(record) = completed {
         let log_metadata = json!({
             "lines": logs,
             "summary": "remediation completed",
         });
-        let _ = insert_artifact(
+        let _ = record_artifact(
             &mut *tx,
             record.id,
             "execution-log",
             None,
+            None,
             &log_metadata,
             None,
         )
@@ -459,6 +732,9 @@ async fn finalize_success(
         update_registry_after_completion(&mut tx, &run, "remediation:automation-complete").await?;
     }
     tx.commit().await?;
+    if finalized {
+        TENANT_METRICS.run_completed(run.organization_id);
+    }
     info!(run_id = run.id, "remediation automation completed");
     Ok(())
 }
@@ -469,28 +745,34 @@ async fn finalize_failure(
     failure_reason: RemediationFailureReason,
     message: String,
     logs: Option<Vec<RemediationLogEvent>>,
+    vm_snapshot: Option<&Value>,
 ) -> Result<(), RemediationError> {
-    let metadata = logs.as_ref().map(|entries| json!({ "logs": entries }));
+    let mut metadata = logs.as_ref().map(|entries| json!({ "logs": entries }));
+    if let Some(snapshot) = vm_snapshot {
+        metadata.get_or_insert_with(|| json!({}))[VM_SNAPSHOT_FLAG] = snapshot.clone();
+    }
     let mut tx = pool.begin().await?;
-    if let Some(record) = mark_run_failed(
+    let failed = mark_run_failed(
         &mut *tx,
         run.id,
         failure_reason.as_str(),
         &message,
         metadata.as_ref(),
     )
-    .await?
-    {
+    .await?;
+    let finalized = failed.is_some();
+    if let Some(record) = failed {
         if let Some(entries) = logs {
             let artifact_metadata = json!({
                 "lines": entries,
                 "summary": message,
             });
-            let _ = insert_artifact(
+            let _ = record_artifact(
                 &mut *tx,
                 record.id,
                 "execution-log",
                 None,
+                None,
                 &artifact_metadata,
                 None,
             )
@@ -514,6 +796,9 @@ async fn finalize_failure(
         .await?;
     }
     tx.commit().await?;
+    if finalized {
+        TENANT_METRICS.run_failed(run.organization_id);
+    }
     warn!(run_id = run.id, reason = %failure_reason.as_str(), message = %message, "remediation automation failed");
     Ok(())
 }
@@ -1059,10 +1344,11 @@ impl FromStr for RemediationExecutorKind {
 
 struct RemediationExecutorRegistry {
     executors: HashMap<RemediationExecutorKind, Arc<dyn RemediationExecutor>>,
+    vm_executor: Option<Arc<VirtualMachineExecutor>>,
 }
 
 impl RemediationExecutorRegistry {
-    fn bootstrap() -> Self {
+    fn bootstrap(vm_executor: Option<Arc<VirtualMachineExecutor>>) -> Self {
         let mut executors: HashMap<RemediationExecutorKind, Arc<dyn RemediationExecutor>> =
             HashMap::new();
         executors.insert(
@@ -1077,7 +1363,10 @@ impl RemediationExecutorRegistry {
             RemediationExecutorKind::CloudApi,
             Arc::new(CloudApiRemediationExecutor),
         );
-        Self { executors }
+        Self {
+            executors,
+            vm_executor,
+        }
     }
 
     fn get<T: AsRef<str>>(&self, kind: T) -> Option<Arc<dyn RemediationExecutor>> {
@@ -1215,3 +1504,54 @@ async fn execute_simulated(
         cancel: Some(cancel_tx),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(entries: &[(&str, &[&str])]) -> WorkspaceGateGraph {
+        WorkspaceGateGraph::new(
+            entries
+                .iter()
+                .map(|(gate, deps)| {
+                    (
+                        gate.to_string(),
+                        deps.iter().map(|dep| dep.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn gate_graph_expands_transitive_prerequisites() {
+        let graph = graph(&[
+            ("policy", &["schema"]),
+            ("simulation", &["policy"]),
+            ("promotion", &["simulation"]),
+        ]);
+        assert_eq!(
+            graph.prerequisites("promotion"),
+            vec!["simulation", "policy", "schema"]
+        );
+        assert!(graph.prerequisites("schema").is_empty());
+    }
+
+    #[test]
+    fn gate_graph_tolerates_cycles() {
+        let graph = graph(&[("policy", &["schema"]), ("schema", &["policy"])]);
+        assert_eq!(graph.prerequisites("policy"), vec!["schema"]);
+    }
+
+    #[test]
+    fn gate_graph_rejects_unknown_gates() {
+        let graph = WorkspaceGateGraph::from_json(&json!({"promotion": ["policy"]})).unwrap();
+        assert_eq!(graph.prerequisites("promotion"), vec!["policy"]);
+
+        let err = WorkspaceGateGraph::from_json(&json!({"promotion": ["simulaton"]})).unwrap_err();
+        assert!(err.contains("unknown gate `simulaton`"), "{err}");
+        let err = WorkspaceGateGraph::from_json(&json!({"deploy": []})).unwrap_err();
+        assert!(err.contains("unknown gate `deploy`"), "{err}");
+        assert!(WorkspaceGateGraph::from_json(&json!(["schema"])).is_err());
+    }
+}
@@ -0,0 +1,421 @@
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{Executor, Postgres};
+
+use crate::db::runtime_vm_remediation_artifacts::{insert_artifact, InsertRemediationArtifact};
+use crate::invocations::cache::write_canonical;
+
+// key: remediation-artifact-content -> content type and rendering hint per artifact
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const TEXT_CONTENT_TYPE: &str = "text/plain";
+const MARKDOWN_CONTENT_TYPE: &str = "text/markdown";
+const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// How the lifecycle console should present an artifact's stored value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactRenderHint {
+    Text,
+    Json,
+    Markdown,
+    /// The value names a file held elsewhere; the console links to it instead of inlining.
+    BinaryPointer,
+}
+
+impl ArtifactRenderHint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactRenderHint::Text => "text",
+            ArtifactRenderHint::Json => "json",
+            ArtifactRenderHint::Markdown => "markdown",
+            ArtifactRenderHint::BinaryPointer => "binary-pointer",
+        }
+    }
+
+    fn for_content_type(content_type: &str) -> Self {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if essence == JSON_CONTENT_TYPE || essence.ends_with("+json") {
+            ArtifactRenderHint::Json
+        } else if essence == MARKDOWN_CONTENT_TYPE {
+            ArtifactRenderHint::Markdown
+        } else if essence.starts_with("text/") {
+            ArtifactRenderHint::Text
+        } else {
+            ArtifactRenderHint::BinaryPointer
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactContent {
+    pub content_type: String,
+    pub render_hint: ArtifactRenderHint,
+}
+
+impl ArtifactContent {
+    /// A declared content type wins; otherwise the type is inferred from the artifact's `uri`
+    /// and stored value. An object carrying a `uri` or `path` string counts as a file pointer.
+    pub fn detect(declared: Option<&str>, uri: Option<&str>, value: &Value) -> Self {
+        if let Some(declared) = declared.map(str::trim).filter(|value| !value.is_empty()) {
+            return Self::new(declared, ArtifactRenderHint::for_content_type(declared));
+        }
+        if let Some(location) = uri.or_else(|| file_pointer(value)) {
+            return Self::new(
+                content_type_for_path(location),
+                ArtifactRenderHint::BinaryPointer,
+            );
+        }
+        match value {
+            Value::String(text) if parses_as_json_document(text) => {
+                Self::new(JSON_CONTENT_TYPE, ArtifactRenderHint::Json)
+            }
+            Value::String(text) if looks_like_markdown(text) => {
+                Self::new(MARKDOWN_CONTENT_TYPE, ArtifactRenderHint::Markdown)
+            }
+            Value::String(_) => Self::new(TEXT_CONTENT_TYPE, ArtifactRenderHint::Text),
+            _ => Self::new(JSON_CONTENT_TYPE, ArtifactRenderHint::Json),
+        }
+    }
+
+    fn new(content_type: &str, render_hint: ArtifactRenderHint) -> Self {
+        Self {
+            content_type: content_type.to_string(),
+            render_hint,
+        }
+    }
+}
+
+fn file_pointer(value: &Value) -> Option<&str> {
+    let object = value.as_object()?;
+    ["uri", "path"]
+        .iter()
+        .find_map(|key| object.get(*key).and_then(Value::as_str))
+}
+
+fn content_type_for_path(location: &str) -> &'static str {
+    let path = location.split(['?', '#']).next().unwrap_or_default();
+    let extension = path
+        .rsplit_once('.')
+        .filter(|(_, extension)| !extension.contains('/'))
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("json") => JSON_CONTENT_TYPE,
+        Some("md") => MARKDOWN_CONTENT_TYPE,
+        Some("txt" | "log") => TEXT_CONTENT_TYPE,
+        _ => BINARY_CONTENT_TYPE,
+    }
+}
+
+fn parses_as_json_document(text: &str) -> bool {
+    let trimmed = text.trim_start();
+    (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<Value>(text).is_ok()
+}
+
+fn looks_like_markdown(text: &str) -> bool {
+    text.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("```") || line.starts_with("# ") || line.starts_with("## ")
+    })
+}
+
+// key: remediation-artifact-content -> fingerprint dedupe
+
+/// Hex SHA-256 of the artifact type, `uri`, and stored value. Object keys are sorted first,
+/// so key order does not change the fingerprint.
+pub fn artifact_fingerprint(artifact_type: &str, uri: Option<&str>, metadata: &Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(metadata, &mut canonical);
+    let mut hasher = Sha256::new();
+    hasher.update(artifact_type.as_bytes());
+    hasher.update([0]);
+    hasher.update(uri.unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(canonical.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Records an artifact with its content type and rendering hint, detecting both from the
+/// value when `declared_content_type` is `None`.
+///
+/// A retried producer cannot duplicate an artifact. If the run already holds one with the same
+/// type and content, the existing id is returned and nothing is written. Use
+/// [`record_repeated_artifact`] when identical artifacts are meant to repeat.
+pub async fn record_artifact<'c, E>(
+    executor: E,
+    remediation_run_id: i64,
+    artifact_type: &str,
+    uri: Option<&str>,
+    declared_content_type: Option<&str>,
+    metadata: &Value,
+    recorded_by: Option<i32>,
+) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let fingerprint = artifact_fingerprint(artifact_type, uri, metadata);
+    let artifact = PendingArtifact {
+        remediation_run_id,
+        artifact_type,
+        uri,
+        declared_content_type,
+        metadata,
+        recorded_by,
+    };
+    artifact.insert(executor, Some(&fingerprint)).await
+}
+
+/// Like [`record_artifact`], but always inserts a new row, even when an identical artifact
+/// exists. Meant for repeated status logs and similar artifacts.
+pub async fn record_repeated_artifact<'c, E>(
+    executor: E,
+    remediation_run_id: i64,
+    artifact_type: &str,
+    uri: Option<&str>,
+    declared_content_type: Option<&str>,
+    metadata: &Value,
+    recorded_by: Option<i32>,
+) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let artifact = PendingArtifact {
+        remediation_run_id,
+        artifact_type,
+        uri,
+        declared_content_type,
+        metadata,
+        recorded_by,
+    };
+    artifact.insert(executor, None).await
+}
+
+struct PendingArtifact<'a> {
+    remediation_run_id: i64,
+    artifact_type: &'a str,
+    uri: Option<&'a str>,
+    declared_content_type: Option<&'a str>,
+    metadata: &'a Value,
+    recorded_by: Option<i32>,
+}
+
+impl PendingArtifact<'_> {
+    async fn insert<'c, E>(self, executor: E, fingerprint: Option<&str>) -> Result<i64, sqlx::Error>
+    where
+        E: Executor<'c, Database = Postgres>,
+    {
+        let content = ArtifactContent::detect(self.declared_content_type, self.uri, self.metadata);
+        insert_artifact(
+            executor,
+            InsertRemediationArtifact {
+                remediation_run_id: self.remediation_run_id,
+                artifact_type: self.artifact_type,
+                uri: self.uri,
+                metadata: self.metadata,
+                content_type: &content.content_type,
+                render_hint: content.render_hint.as_str(),
+                recorded_by: self.recorded_by,
+                content_fingerprint: fingerprint,
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::runtime_vm_remediation_artifacts::list_artifacts;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn hint(value: &Value) -> ArtifactRenderHint {
+        ArtifactContent::detect(None, None, value).render_hint
+    }
+
+    #[test]
+    fn json_values_are_hinted_json() {
+        assert_eq!(
+            hint(&json!({ "lines": ["started"], "summary": "ok" })),
+            ArtifactRenderHint::Json
+        );
+        assert_eq!(hint(&json!([1, 2, 3])), ArtifactRenderHint::Json);
+        let encoded = ArtifactContent::detect(None, None, &json!("{\"exit_code\": 0}"));
+        assert_eq!(encoded.render_hint, ArtifactRenderHint::Json);
+        assert_eq!(encoded.content_type, "application/json");
+    }
+
+    #[test]
+    fn plain_strings_are_hinted_text() {
+        let content = ArtifactContent::detect(None, None, &json!("unit restarted\nexit 0"));
+        assert_eq!(content.render_hint, ArtifactRenderHint::Text);
+        assert_eq!(content.content_type, "text/plain");
+        assert_eq!(
+            hint(&json!("# Summary\n\nRestarted the unit.")),
+            ArtifactRenderHint::Markdown
+        );
+    }
+
+    #[test]
+    fn file_pointers_are_hinted_binary_pointer() {
+        let pointer = ArtifactContent::detect(
+            None,
+            None,
+            &json!({ "path": "s3://artifacts/run-7/console.png", "size_bytes": 2048 }),
+        );
+        assert_eq!(pointer.render_hint, ArtifactRenderHint::BinaryPointer);
+        assert_eq!(pointer.content_type, "image/png");
+
+        let by_uri = ArtifactContent::detect(None, Some("file:///var/dumps/core"), &json!({}));
+        assert_eq!(by_uri.render_hint, ArtifactRenderHint::BinaryPointer);
+        assert_eq!(by_uri.content_type, "application/octet-stream");
+    }
+
+    #[test]
+    fn declared_content_type_wins() {
+        let content = ArtifactContent::detect(
+            Some("text/markdown; charset=utf-8"),
+            None,
+            &json!({ "body": "ignored" }),
+        );
+        assert_eq!(content.render_hint, ArtifactRenderHint::Markdown);
+        assert_eq!(content.content_type, "text/markdown; charset=utf-8");
+        assert_eq!(
+            ArtifactContent::detect(Some("application/vnd.run+json"), None, &json!("x"))
+                .render_hint,
+            ArtifactRenderHint::Json
+        );
+        assert_eq!(
+            ArtifactContent::detect(Some("image/png"), None, &json!({})).render_hint,
+            ArtifactRenderHint::BinaryPointer
+        );
+    }
+
+    #[test]
+    fn fingerprint_covers_type_and_content_but_not_key_order() {
+        let first = artifact_fingerprint("log", None, &json!({ "a": 1, "b": [true, null] }));
+        let reordered = artifact_fingerprint("log", None, &json!({ "b": [true, null], "a": 1 }));
+        assert_eq!(first, reordered);
+        assert_eq!(first.len(), 64);
+        assert_ne!(
+            first,
+            artifact_fingerprint("status", None, &json!({ "a": 1, "b": [true, null] }))
+        );
+        assert_ne!(
+            first,
+            artifact_fingerprint("log", None, &json!({ "a": 2, "b": [true, null] }))
+        );
+        assert_ne!(
+            first,
+            artifact_fingerprint(
+                "log",
+                Some("s3://logs/1"),
+                &json!({ "a": 1, "b": [true, null] })
+            )
+        );
+    }
+
+    async fn seed_run(pool: &PgPool) -> i64 {
+        let owner_id: i32 = sqlx::query_scalar(
+            "INSERT INTO users (email, password_hash) VALUES ('artifacts@example.com', 'hashed') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let server_id: i32 = sqlx::query_scalar(
+            "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, 'artifacts', 'virtual-machine', '{}'::jsonb, 'active', 'artifacts-key') RETURNING id",
+        )
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let vm_instance_id: i64 = sqlx::query_scalar(
+            "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, 'artifacts-vm') RETURNING id",
+        )
+        .bind(server_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO runtime_vm_remediation_runs (runtime_vm_instance_id, playbook, status) VALUES ($1, 'vm.restart.service', 'running') RETURNING id",
+        )
+        .bind(vm_instance_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn retried_artifact_is_deduplicated(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let run_id = seed_run(&pool).await;
+        let log = json!({ "lines": ["restarting unit"], "summary": "ok" });
+        let retried = json!({ "summary": "ok", "lines": ["restarting unit"] });
+
+        let first = record_artifact(&pool, run_id, "execution-log", None, None, &log, None)
+            .await
+            .unwrap();
+        let second = record_artifact(&pool, run_id, "execution-log", None, None, &retried, None)
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+
+        // same content under another type is a different artifact
+        let status = record_artifact(&pool, run_id, "final-status", None, None, &log, None)
+            .await
+            .unwrap();
+        assert_ne!(status, first);
+
+        let artifacts = list_artifacts(&pool, run_id).await.unwrap();
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(
+            artifacts[0].content_fingerprint.as_deref(),
+            Some(artifact_fingerprint("execution-log", None, &log).as_str())
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn repeated_artifacts_opt_out_of_dedupe(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let run_id = seed_run(&pool).await;
+        let status = json!({ "status": "running" });
+
+        let deduped = record_artifact(&pool, run_id, "status-log", None, None, &status, None)
+            .await
+            .unwrap();
+        let mut repeated = Vec::new();
+        for _ in 0..2 {
+            repeated.push(
+                record_repeated_artifact(&pool, run_id, "status-log", None, None, &status, None)
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert_ne!(repeated[0], repeated[1]);
+        assert!(!repeated.contains(&deduped));
+
+        let artifacts = list_artifacts(&pool, run_id).await.unwrap();
+        assert_eq!(artifacts.len(), 3);
+        assert_eq!(
+            artifacts
+                .iter()
+                .filter(|artifact| artifact.content_fingerprint.is_none())
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::config::{self, ArtifactRetentionMode};
+use crate::db::runtime_vm_remediation_artifacts::{
+    list_archivable, mark_archived, prune_aged, RuntimeVmRemediationArtifact,
+};
+use crate::file_store::BlobStore;
+
+// key: remediation-artifact-retention -> age out run artifacts
+
+/// Artifact types that record how a run ended; the sweep never touches them.
+pub const ESSENTIAL_ARTIFACT_TYPES: &[&str] =
+    &["final-status", "attestation", "attestation-evidence"];
+
+const ARCHIVE_BATCH_SIZE: i64 = 100;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RetentionError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("blob store error: {0}")]
+    Storage(String),
+    #[error("artifact metadata encoding error: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepOutcome {
+    pub archived: u64,
+    pub pruned: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArtifactRetention {
+    store: BlobStore,
+    mode: ArtifactRetentionMode,
+    max_age: chrono::Duration,
+}
+
+impl ArtifactRetention {
+    pub fn new(store: BlobStore, mode: ArtifactRetentionMode, max_age: chrono::Duration) -> Self {
+        Self {
+            store,
+            mode,
+            max_age,
+        }
+    }
+
+    /// `None` when `REMEDIATION_ARTIFACT_RETENTION_DAYS` is `0`.
+    pub fn from_config() -> Option<Self> {
+        let days = *config::REMEDIATION_ARTIFACT_RETENTION_DAYS;
+        (days > 0).then(|| {
+            Self::new(
+                BlobStore::default(),
+                *config::REMEDIATION_ARTIFACT_RETENTION_MODE,
+                chrono::Duration::days(days),
+            )
+        })
+    }
+
+    pub async fn sweep(&self, pool: &PgPool) -> Result<SweepOutcome, RetentionError> {
+        let cutoff = Utc::now() - self.max_age;
+        match self.mode {
+            ArtifactRetentionMode::Prune => Ok(SweepOutcome {
+                pruned: prune_aged(pool, cutoff, ESSENTIAL_ARTIFACT_TYPES).await?,
+                ..SweepOutcome::default()
+            }),
+            ArtifactRetentionMode::Archive => {
+                let mut outcome = SweepOutcome::default();
+                loop {
+                    let batch =
+                        list_archivable(pool, cutoff, ESSENTIAL_ARTIFACT_TYPES, ARCHIVE_BATCH_SIZE)
+                            .await?;
+                    for artifact in &batch {
+                        if self.archive(pool, artifact).await? {
+                            outcome.archived += 1;
+                        }
+                    }
+                    if (batch.len() as i64) < ARCHIVE_BATCH_SIZE {
+                        return Ok(outcome);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves the metadata into the blob store and leaves a stub naming the blob, so the
+    /// row keeps its place in the run's artifact ledger.
+    async fn archive(
+        &self,
+        pool: &PgPool,
+        artifact: &RuntimeVmRemediationArtifact,
+    ) -> Result<bool, RetentionError> {
+        let payload = serde_json::to_vec(&artifact.metadata)?;
+        let mut tx = pool.begin().await?;
+        let digest = self
+            .store
+            .put_blob(&mut tx, &payload)
+            .await
+            .map_err(|(_, message)| RetentionError::Storage(message))?;
+        let stub = json!({
+            "archived": {
+                "blob_digest": digest,
+                "size_bytes": payload.len(),
+            }
+        });
+        if !mark_archived(&mut *tx, artifact.id, &digest, &stub).await? {
+            return Ok(false);
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// The artifact's original metadata, read back from the blob store when archived.
+    pub async fn restore_metadata(
+        &self,
+        artifact: &RuntimeVmRemediationArtifact,
+    ) -> Result<Value, RetentionError> {
+        let Some(digest) = artifact.archived_blob_digest.as_deref() else {
+            return Ok(artifact.metadata.clone());
+        };
+        let payload = self
+            .store
+            .read_blob(digest)
+            .await
+            .map_err(|err| RetentionError::Storage(err.to_string()))?;
+        Ok(serde_json::from_slice(&payload)?)
+    }
+}
+
+pub fn spawn(pool: PgPool) {
+    let Some(retention) = ArtifactRetention::from_config() else {
+        info!("remediation artifact retention disabled");
+        return;
+    };
+    let interval = Duration::from_secs(*config::REMEDIATION_ARTIFACT_SWEEP_INTERVAL_SECS);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match retention.sweep(&pool).await {
+                Ok(SweepOutcome {
+                    archived: 0,
+                    pruned: 0,
+                }) => {}
+                Ok(SweepOutcome { archived, pruned }) => info!(
+                    archived,
+                    pruned,
+                    mode = retention.mode.as_str(),
+                    "aged out remediation artifacts"
+                ),
+                Err(err) => warn!(?err, "remediation artifact retention sweep failed"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::runtime_vm_remediation_artifacts::list_artifacts;
+    use crate::remediation::artifact_content::record_artifact;
+
+    async fn seed_run(pool: &PgPool) -> i64 {
+        let owner_id: i32 = sqlx::query_scalar(
+            "INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id",
+        )
+        .bind("retention@example.com")
+        .bind("hashed")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let server_id: i32 = sqlx::query_scalar(
+            "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, 'retention', 'virtual-machine', '{}'::jsonb, 'active', 'retention-key') RETURNING id",
+        )
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let vm_instance_id: i64 = sqlx::query_scalar(
+            "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, 'retention-vm') RETURNING id",
+        )
+        .bind(server_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO runtime_vm_remediation_runs (runtime_vm_instance_id, playbook, status) VALUES ($1, 'vm.restart.service', 'completed') RETURNING id",
+        )
+        .bind(vm_instance_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn aged_log_is_archived_and_essential_artifact_stays_inline(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let run_id = seed_run(&pool).await;
+        let log_metadata = json!({ "lines": ["restarting unit", "unit active"] });
+        let status_metadata = json!({ "status": "completed" });
+        let log_id = record_artifact(
+            &pool,
+            run_id,
+            "execution-log",
+            None,
+            None,
+            &log_metadata,
+            None,
+        )
+        .await
+        .unwrap();
+        let status_id = record_artifact(
+            &pool,
+            run_id,
+            "final-status",
+            None,
+            None,
+            &status_metadata,
+            None,
+        )
+        .await
+        .unwrap();
+        sqlx::query(
+            "UPDATE runtime_vm_remediation_artifacts SET created_at = NOW() - INTERVAL '40 days'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let root = std::env::temp_dir().join(format!("blobs-{}", uuid::Uuid::new_v4()));
+        let retention = ArtifactRetention::new(
+            BlobStore::new(&root),
+            ArtifactRetentionMode::Archive,
+            chrono::Duration::days(30),
+        );
+        let outcome = retention.sweep(&pool).await.unwrap();
+        assert_eq!(
+            outcome,
+            SweepOutcome {
+                archived: 1,
+                pruned: 0
+            }
+        );
+
+        let artifacts = list_artifacts(&pool, run_id).await.unwrap();
+        let log = artifacts.iter().find(|a| a.id == log_id).unwrap();
+        let digest = log.archived_blob_digest.as_deref().expect("log archived");
+        assert!(log.archived_at.is_some());
+        assert_eq!(log.metadata["archived"]["blob_digest"], digest);
+        assert_eq!(retention.restore_metadata(log).await.unwrap(), log_metadata);
+        let ref_count: i32 =
+            sqlx::query_scalar("SELECT ref_count FROM file_blobs WHERE digest = $1")
+                .bind(digest)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(ref_count, 1);
+
+        let status = artifacts.iter().find(|a| a.id == status_id).unwrap();
+        assert!(status.archived_blob_digest.is_none());
+        assert_eq!(status.metadata, status_metadata);
+
+        // a second sweep finds nothing left to archive
+        assert_eq!(
+            retention.sweep(&pool).await.unwrap(),
+            SweepOutcome::default()
+        );
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
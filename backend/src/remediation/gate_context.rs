@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+// key: remediation-workspace -> promotion gate context merge
+
+/// Target entry fields that pick and drive the run rather than describe its context.
+const TARGET_RUN_FIELDS: &[&str] = &[
+    "runtime_vm_instance_id",
+    "instance_id",
+    "playbook",
+    "automation_payload",
+    "payload",
+    "source",
+];
+
+/// How a promotion request's gate context is combined with the context each plan target
+/// carries (its own fields plus the `lane`/`stage` style values inherited from its group).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GateContextMerge {
+    /// Runs get the request's gate context unchanged; target context is ignored.
+    #[default]
+    RequestWins,
+    /// Target context is layered over the request's, so a target keeps its own lane or stage.
+    TargetWins,
+    /// Both are merged recursively: keys from either side are kept and the request's values
+    /// win where both set the same non-object key.
+    DeepMerge,
+}
+
+impl GateContextMerge {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GateContextMerge::RequestWins => "request_wins",
+            GateContextMerge::TargetWins => "target_wins",
+            GateContextMerge::DeepMerge => "deep_merge",
+        }
+    }
+
+    /// Gate context for the run staged for `target_snapshot`.
+    pub fn resolve(self, target_snapshot: &Value, request: &Value) -> Value {
+        match self {
+            GateContextMerge::RequestWins => request.clone(),
+            GateContextMerge::TargetWins => {
+                let mut merged = request.clone();
+                overlay(&mut merged, &target_context(target_snapshot));
+                merged
+            }
+            GateContextMerge::DeepMerge => {
+                let mut merged = target_context(target_snapshot);
+                overlay(&mut merged, request);
+                merged
+            }
+        }
+    }
+}
+
+impl FromStr for GateContextMerge {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "request_wins" => Ok(GateContextMerge::RequestWins),
+            "target_wins" => Ok(GateContextMerge::TargetWins),
+            "deep_merge" => Ok(GateContextMerge::DeepMerge),
+            other => Err(format!("unknown gate context merge strategy `{other}`")),
+        }
+    }
+}
+
+fn target_context(target_snapshot: &Value) -> Value {
+    let mut context = Map::new();
+    if let Some(fields) = target_snapshot.as_object() {
+        for (key, value) in fields {
+            if !TARGET_RUN_FIELDS.contains(&key.as_str()) {
+                context.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(context)
+}
+
+/// Writes `top` into `base`, recursing where both hold objects; `top` wins elsewhere.
+fn overlay(base: &mut Value, top: &Value) {
+    match (base, top) {
+        (Value::Object(base), Value::Object(top)) => {
+            for (key, value) in top {
+                match base.get_mut(key) {
+                    Some(existing) => overlay(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, top) => *base = top.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strategies_parse_from_config_spellings() {
+        for strategy in [
+            GateContextMerge::RequestWins,
+            GateContextMerge::TargetWins,
+            GateContextMerge::DeepMerge,
+        ] {
+            assert_eq!(strategy.as_str().parse(), Ok(strategy));
+        }
+        assert_eq!("Target-Wins".parse(), Ok(GateContextMerge::TargetWins));
+        assert!("newest".parse::<GateContextMerge>().is_err());
+    }
+
+    #[test]
+    fn request_context_overwrites_non_objects_when_layered() {
+        let mut base = json!({"lane": "blue", "window": {"start": "01:00"}, "tags": ["a"]});
+        overlay(
+            &mut base,
+            &json!({"lane": "green", "window": {"end": "02:00"}, "tags": "b"}),
+        );
+        assert_eq!(
+            base,
+            json!({"lane": "green", "window": {"start": "01:00", "end": "02:00"}, "tags": "b"})
+        );
+
+        let mut base = json!(null);
+        overlay(&mut base, &json!({"lane": "green"}));
+        assert_eq!(base, json!({"lane": "green"}));
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+
+use crate::db::runtime_vm_remediation_playbooks::RuntimeVmRemediationPlaybook;
+use crate::db::runtime_vm_remediation_runs::RuntimeVmRemediationRun;
+
+// key: remediation-impact-preview -> blast radius of a run before approval
+
+/// Run metadata key listing instances the run touches beyond its own.
+pub const TARGET_INSTANCES_KEY: &str = "target_instance_ids";
+/// Playbook metadata key; `"server"` widens the impact to every live instance of each
+/// targeted server.
+pub const IMPACT_SCOPE_KEY: &str = "impact_scope";
+
+/// Lifecycle states the placement gate refuses to route to.
+const WITHDRAWN_LIFECYCLE_STATES: &[&str] = &["quarantined", "remediating"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImpactScope {
+    Instances,
+    Server,
+}
+
+impl ImpactScope {
+    pub fn for_playbook(playbook: Option<&RuntimeVmRemediationPlaybook>) -> Self {
+        let scope = playbook
+            .and_then(|playbook| playbook.metadata.get(IMPACT_SCOPE_KEY))
+            .and_then(Value::as_str);
+        match scope {
+            Some("server") => ImpactScope::Server,
+            _ => ImpactScope::Instances,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ImpactedInstance {
+    pub runtime_vm_instance_id: i64,
+    pub instance_id: String,
+    pub server_id: i32,
+    pub server_name: String,
+    pub server_status: String,
+    /// The registry's attestation status, or the instance's own while it has no registry row.
+    pub attestation_status: String,
+    /// `None` while the instance has no registry row.
+    pub lifecycle_state: Option<String>,
+    pub remediation_state: Option<String>,
+    pub serving_traffic: bool,
+    /// Whether this is the run's own instance rather than one pulled in by metadata or scope.
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunImpactPreview {
+    pub run_id: i64,
+    pub playbook: String,
+    pub scope: ImpactScope,
+    pub instances: Vec<ImpactedInstance>,
+    pub serving_instances: usize,
+    /// Ids named in the run metadata that match no instance.
+    pub unresolved_instance_ids: Vec<i64>,
+}
+
+/// The run's own instance followed by any listed under [`TARGET_INSTANCES_KEY`], deduplicated.
+pub fn target_instance_ids(run: &RuntimeVmRemediationRun) -> Vec<i64> {
+    let mut seen = BTreeSet::from([run.runtime_vm_instance_id]);
+    let mut targets = vec![run.runtime_vm_instance_id];
+    let listed = run
+        .metadata
+        .get(TARGET_INSTANCES_KEY)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_i64);
+    for id in listed {
+        if seen.insert(id) {
+            targets.push(id);
+        }
+    }
+    targets
+}
+
+/// An instance serves traffic when its server is running, it is the server's newest live
+/// instance (the one the runtime routes to), and trust has not withdrawn it.
+fn serving_traffic(
+    server_status: &str,
+    routed: bool,
+    terminated: bool,
+    lifecycle_state: Option<&str>,
+) -> bool {
+    server_status == "running"
+        && routed
+        && !terminated
+        && !lifecycle_state.is_some_and(|state| WITHDRAWN_LIFECYCLE_STATES.contains(&state))
+}
+
+pub async fn preview_run_impact(
+    pool: &PgPool,
+    run: &RuntimeVmRemediationRun,
+    playbook: Option<&RuntimeVmRemediationPlaybook>,
+) -> Result<RunImpactPreview, sqlx::Error> {
+    let scope = ImpactScope::for_playbook(playbook);
+    let targets = target_instance_ids(run);
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            instances.id::BIGINT AS runtime_vm_instance_id,
+            instances.instance_id,
+            instances.terminated_at IS NOT NULL AS terminated,
+            instances.id = (
+                SELECT latest.id
+                FROM runtime_vm_instances latest
+                WHERE latest.server_id = instances.server_id
+                    AND latest.terminated_at IS NULL
+                ORDER BY latest.created_at DESC
+                LIMIT 1
+            ) AS routed,
+            servers.id AS server_id,
+            servers.name AS server_name,
+            servers.status AS server_status,
+            COALESCE(registry.attestation_status, instances.attestation_status)
+                AS attestation_status,
+            registry.lifecycle_state,
+            registry.remediation_state
+        FROM runtime_vm_instances instances
+        JOIN mcp_servers servers ON servers.id = instances.server_id
+        LEFT JOIN runtime_vm_trust_registry registry
+            ON registry.runtime_vm_instance_id = instances.id
+        WHERE instances.id = ANY($1)
+            OR (
+                $2
+                AND instances.terminated_at IS NULL
+                AND instances.server_id IN (
+                    SELECT server_id FROM runtime_vm_instances WHERE id = ANY($1)
+                )
+            )
+        ORDER BY servers.id, instances.id
+        "#,
+    )
+    .bind(&targets)
+    .bind(scope == ImpactScope::Server)
+    .fetch_all(pool)
+    .await?;
+
+    let instances: Vec<ImpactedInstance> = rows
+        .iter()
+        .map(|row| {
+            let runtime_vm_instance_id: i64 = row.get("runtime_vm_instance_id");
+            let server_status: String = row.get("server_status");
+            let lifecycle_state: Option<String> = row.get("lifecycle_state");
+            let serving = serving_traffic(
+                &server_status,
+                row.get::<Option<bool>, _>("routed").unwrap_or(false),
+                row.get("terminated"),
+                lifecycle_state.as_deref(),
+            );
+            ImpactedInstance {
+                runtime_vm_instance_id,
+                instance_id: row.get("instance_id"),
+                server_id: row.get("server_id"),
+                server_name: row.get("server_name"),
+                server_status,
+                attestation_status: row.get("attestation_status"),
+                lifecycle_state,
+                remediation_state: row.get("remediation_state"),
+                serving_traffic: serving,
+                primary: runtime_vm_instance_id == run.runtime_vm_instance_id,
+            }
+        })
+        .collect();
+
+    let unresolved_instance_ids = targets
+        .iter()
+        .copied()
+        .filter(|id| {
+            !instances
+                .iter()
+                .any(|instance| instance.runtime_vm_instance_id == *id)
+        })
+        .collect();
+
+    Ok(RunImpactPreview {
+        run_id: run.id,
+        playbook: run.playbook.clone(),
+        scope,
+        serving_instances: instances
+            .iter()
+            .filter(|instance| instance.serving_traffic)
+            .count(),
+        instances,
+        unresolved_instance_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn withdrawn_or_superseded_instances_do_not_serve() {
+        assert!(serving_traffic("running", true, false, Some("restored")));
+        assert!(serving_traffic("running", true, false, None));
+        assert!(!serving_traffic(
+            "running",
+            true,
+            false,
+            Some("quarantined")
+        ));
+        assert!(!serving_traffic("running", false, false, Some("trusted")));
+        assert!(!serving_traffic("stopped", true, false, None));
+        assert!(!serving_traffic("running", true, true, None));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::db::runtime_vm_remediation_workspaces::{
+    RuntimeVmRemediationWorkspace, RuntimeVmRemediationWorkspaceRevision,
+};
+
+// key: remediation-lineage -> revision chain and branch points of a workspace
+
+/// Promotion status a revision carries once it has been promoted.
+const PROMOTED_STATUS: &str = "completed";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceLineage {
+    pub workspace_id: i64,
+    pub active_revision_id: Option<i64>,
+    /// The most recently promoted revision, if any.
+    pub promoted_revision_id: Option<i64>,
+    /// Every revision, oldest first.
+    pub revisions: Vec<LineageRevision>,
+    /// Revisions that more than one later revision was based on.
+    pub branch_points: Vec<LineageBranchPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageRevision {
+    pub revision_id: i64,
+    pub revision_number: i64,
+    pub previous_revision_id: Option<i64>,
+    /// Revisions based on this one, oldest first.
+    pub children: Vec<i64>,
+    pub created_by: i32,
+    pub created_at: DateTime<Utc>,
+    pub lineage_labels: Vec<String>,
+    pub schema_status: String,
+    pub policy_status: String,
+    pub simulation_status: String,
+    pub promotion_status: String,
+    pub promoted: bool,
+    pub promoted_at: Option<DateTime<Utc>>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineageBranchPoint {
+    pub revision_id: i64,
+    pub children: Vec<i64>,
+}
+
+/// Builds the lineage of `workspace` from its revisions, given in any order. A
+/// `previous_revision_id` outside the workspace is kept but starts a new root.
+pub fn build_lineage(
+    workspace: &RuntimeVmRemediationWorkspace,
+    revisions: &[RuntimeVmRemediationWorkspaceRevision],
+) -> WorkspaceLineage {
+    let mut ordered: Vec<&RuntimeVmRemediationWorkspaceRevision> = revisions.iter().collect();
+    ordered.sort_by_key(|revision| (revision.revision_number, revision.id));
+
+    let mut children: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for revision in &ordered {
+        if let Some(previous) = revision.previous_revision_id {
+            children.entry(previous).or_default().push(revision.id);
+        }
+    }
+
+    let promoted_revision_id = ordered
+        .iter()
+        .filter(|revision| revision.promotion_status == PROMOTED_STATUS)
+        .max_by_key(|revision| (revision.promoted_at, revision.revision_number))
+        .map(|revision| revision.id);
+
+    let revisions: Vec<LineageRevision> = ordered
+        .iter()
+        .map(|revision| LineageRevision {
+            revision_id: revision.id,
+            revision_number: revision.revision_number,
+            previous_revision_id: revision.previous_revision_id,
+            children: children.get(&revision.id).cloned().unwrap_or_default(),
+            created_by: revision.created_by,
+            created_at: revision.created_at,
+            lineage_labels: revision.lineage_labels.clone(),
+            schema_status: revision.schema_status.clone(),
+            policy_status: revision.policy_status.clone(),
+            simulation_status: revision.simulation_status.clone(),
+            promotion_status: revision.promotion_status.clone(),
+            promoted: revision.promotion_status == PROMOTED_STATUS,
+            promoted_at: revision.promoted_at,
+            active: workspace.active_revision_id == Some(revision.id),
+        })
+        .collect();
+
+    let branch_points = revisions
+        .iter()
+        .filter(|revision| revision.children.len() > 1)
+        .map(|revision| LineageBranchPoint {
+            revision_id: revision.revision_id,
+            children: revision.children.clone(),
+        })
+        .collect();
+
+    WorkspaceLineage {
+        workspace_id: workspace.id,
+        active_revision_id: workspace.active_revision_id,
+        promoted_revision_id,
+        revisions,
+        branch_points,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn workspace(active_revision_id: Option<i64>) -> RuntimeVmRemediationWorkspace {
+        RuntimeVmRemediationWorkspace {
+            id: 1,
+            workspace_key: "lineage".into(),
+            display_name: "Lineage".into(),
+            description: None,
+            owner_id: 7,
+            organization_id: None,
+            lifecycle_state: "promoted".into(),
+            active_revision_id,
+            metadata: json!({}),
+            lineage_tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        }
+    }
+
+    fn revision(
+        id: i64,
+        revision_number: i64,
+        previous_revision_id: Option<i64>,
+    ) -> RuntimeVmRemediationWorkspaceRevision {
+        RuntimeVmRemediationWorkspaceRevision {
+            id,
+            workspace_id: 1,
+            revision_number,
+            previous_revision_id,
+            created_by: 7,
+            plan: json!({}),
+            schema_status: "pending".into(),
+            schema_errors: Vec::new(),
+            policy_status: "pending".into(),
+            policy_veto_reasons: Vec::new(),
+            simulation_status: "not_requested".into(),
+            promotion_status: "not_requested".into(),
+            metadata: json!({}),
+            lineage_labels: Vec::new(),
+            schema_validated_at: None,
+            policy_evaluated_at: None,
+            simulated_at: None,
+            promoted_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn branches_are_reported_where_revisions_share_a_parent() {
+        let mut promoted = revision(12, 2, Some(11));
+        promoted.promotion_status = PROMOTED_STATUS.into();
+        promoted.promoted_at = Some(Utc::now());
+        // given out of order, with revisions 2 and 3 both based on revision 1
+        let revisions = vec![
+            revision(14, 4, Some(12)),
+            revision(13, 3, Some(11)),
+            promoted,
+            revision(11, 1, None),
+        ];
+
+        let lineage = build_lineage(&workspace(Some(12)), &revisions);
+        let order: Vec<i64> = lineage.revisions.iter().map(|r| r.revision_id).collect();
+        assert_eq!(order, vec![11, 12, 13, 14]);
+        assert_eq!(lineage.revisions[0].children, vec![12, 13]);
+        assert_eq!(
+            lineage.branch_points,
+            vec![LineageBranchPoint {
+                revision_id: 11,
+                children: vec![12, 13],
+            }]
+        );
+        assert_eq!(lineage.promoted_revision_id, Some(12));
+        let flagged: Vec<(i64, bool, bool)> = lineage
+            .revisions
+            .iter()
+            .map(|r| (r.revision_id, r.promoted, r.active))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![
+                (11, false, false),
+                (12, true, true),
+                (13, false, false),
+                (14, false, false),
+            ]
+        );
+    }
+}
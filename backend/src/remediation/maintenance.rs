@@ -0,0 +1,259 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::db::remediation_maintenance_windows::{list_windows, RemediationMaintenanceWindow};
+use crate::db::runtime_vm_remediation_playbooks::RuntimeVmRemediationPlaybook;
+use crate::evaluations::cron::{parse_utc_offset, CronError, CronSchedule};
+
+// key: remediation-maintenance-windows -> disruptive playbooks wait for an org window
+
+/// Playbook metadata flag marking a playbook that may only run inside a maintenance window.
+pub const DISRUPTIVE_FLAG: &str = "disruptive";
+const MAX_WINDOW_MINUTES: i32 = 7 * 24 * 60;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MaintenanceWindowError {
+    #[error("invalid recurrence: {0}")]
+    Recurrence(#[from] CronError),
+    #[error("invalid timezone `{0}`; expected UTC or an offset such as +02:00")]
+    Timezone(String),
+    #[error("duration_minutes must be between 1 and {MAX_WINDOW_MINUTES}")]
+    Duration,
+}
+
+/// A parsed window: a cron recurrence for each opening, read in a fixed-offset wall clock,
+/// and how long each opening lasts.
+#[derive(Debug, Clone)]
+pub struct WindowSchedule {
+    recurrence: CronSchedule,
+    offset: FixedOffset,
+    duration: Duration,
+}
+
+impl WindowSchedule {
+    pub fn parse(
+        recurrence: &str,
+        duration_minutes: i32,
+        timezone: &str,
+    ) -> Result<Self, MaintenanceWindowError> {
+        if !(1..=MAX_WINDOW_MINUTES).contains(&duration_minutes) {
+            return Err(MaintenanceWindowError::Duration);
+        }
+        let offset = parse_utc_offset(timezone)
+            .ok_or_else(|| MaintenanceWindowError::Timezone(timezone.to_string()))?;
+        Ok(Self {
+            recurrence: CronSchedule::parse(recurrence)?,
+            offset,
+            duration: Duration::minutes(duration_minutes.into()),
+        })
+    }
+
+    pub fn for_window(
+        window: &RemediationMaintenanceWindow,
+    ) -> Result<Self, MaintenanceWindowError> {
+        Self::parse(
+            &window.recurrence,
+            window.duration_minutes,
+            &window.timezone,
+        )
+    }
+
+    /// The opening covering `at` as `(opens_at, closes_at)`; each opening is half-open.
+    pub fn occurrence_at(&self, at: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let opens_at = self
+            .recurrence
+            .next_after(at - self.duration, self.offset)?;
+        (opens_at <= at).then(|| (opens_at, opens_at + self.duration))
+    }
+
+    pub fn next_opening_after(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.recurrence.next_after(at, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum MaintenanceGate {
+    Open {
+        window_id: i64,
+        window_name: String,
+        closes_at: DateTime<Utc>,
+    },
+    Closed {
+        /// `None` when the organization has no window that opens again.
+        next_opens_at: Option<DateTime<Utc>>,
+    },
+}
+
+pub fn is_disruptive(playbook: &RuntimeVmRemediationPlaybook) -> bool {
+    playbook
+        .metadata
+        .get(DISRUPTIVE_FLAG)
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Whether any of `windows` is open at `at`; when several are, the one closing last wins.
+pub fn evaluate_windows(
+    windows: &[RemediationMaintenanceWindow],
+    at: DateTime<Utc>,
+) -> MaintenanceGate {
+    let mut open: Option<(&RemediationMaintenanceWindow, DateTime<Utc>)> = None;
+    let mut next_opens_at: Option<DateTime<Utc>> = None;
+    for window in windows.iter().filter(|window| window.enabled) {
+        let schedule = match WindowSchedule::for_window(window) {
+            Ok(schedule) => schedule,
+            Err(err) => {
+                warn!(window_id = window.id, %err, "skipping unparseable maintenance window");
+                continue;
+            }
+        };
+        if let Some((_, closes_at)) = schedule.occurrence_at(at) {
+            let closes_later = match open {
+                Some((_, latest)) => closes_at > latest,
+                None => true,
+            };
+            if closes_later {
+                open = Some((window, closes_at));
+            }
+        }
+        if let Some(opens_at) = schedule.next_opening_after(at) {
+            next_opens_at = Some(next_opens_at.map_or(opens_at, |next| next.min(opens_at)));
+        }
+    }
+    match open {
+        Some((window, closes_at)) => MaintenanceGate::Open {
+            window_id: window.id,
+            window_name: window.name.clone(),
+            closes_at,
+        },
+        None => MaintenanceGate::Closed { next_opens_at },
+    }
+}
+
+pub async fn maintenance_gate(
+    pool: &PgPool,
+    organization_id: i32,
+    at: DateTime<Utc>,
+) -> Result<MaintenanceGate, sqlx::Error> {
+    let windows = list_windows(pool, organization_id, true).await?;
+    Ok(evaluate_windows(&windows, at))
+}
+
+/// The organization owning the instance's server, if the server belongs to one.
+pub async fn instance_organization(
+    pool: &PgPool,
+    runtime_vm_instance_id: i64,
+) -> Result<Option<i32>, sqlx::Error> {
+    let organization_id: Option<Option<i32>> = sqlx::query_scalar(
+        r#"
+        SELECT servers.organization_id
+        FROM runtime_vm_instances instances
+        JOIN mcp_servers servers ON servers.id = instances.server_id
+        WHERE instances.id = $1
+        "#,
+    )
+    .bind(runtime_vm_instance_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(organization_id.flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn window(
+        id: i64,
+        recurrence: &str,
+        duration_minutes: i32,
+        timezone: &str,
+    ) -> RemediationMaintenanceWindow {
+        RemediationMaintenanceWindow {
+            id,
+            organization_id: 1,
+            name: format!("window-{id}"),
+            recurrence: recurrence.to_string(),
+            duration_minutes,
+            timezone: timezone.to_string(),
+            enabled: true,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        // 2024-03-04 is a Monday
+        Utc.with_ymd_and_hms(2024, 3, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn recurrence_is_read_in_the_window_timezone() {
+        // Saturdays 02:00-04:00 at +02:00, i.e. 00:00-02:00 UTC
+        let schedule = WindowSchedule::parse("0 2 * * SAT", 120, "+02:00").unwrap();
+        assert_eq!(
+            schedule.occurrence_at(utc(9, 1, 30)),
+            Some((utc(9, 0, 0), utc(9, 2, 0)))
+        );
+        assert_eq!(schedule.occurrence_at(utc(9, 2, 0)), None);
+        assert_eq!(schedule.occurrence_at(utc(8, 23, 59)), None);
+        assert_eq!(
+            schedule.next_opening_after(utc(4, 12, 0)),
+            Some(utc(9, 0, 0))
+        );
+    }
+
+    #[test]
+    fn gate_reports_the_open_window_or_the_next_opening() {
+        let windows = vec![
+            window(1, "0 22 * * *", 60, "UTC"),
+            window(2, "30 21 * * *", 180, "UTC"),
+            RemediationMaintenanceWindow {
+                enabled: false,
+                ..window(3, "* * * * *", 60, "UTC")
+            },
+        ];
+        assert_eq!(
+            evaluate_windows(&windows, utc(4, 22, 15)),
+            MaintenanceGate::Open {
+                window_id: 2,
+                window_name: "window-2".into(),
+                closes_at: utc(5, 0, 30),
+            }
+        );
+        assert_eq!(
+            evaluate_windows(&windows, utc(4, 12, 0)),
+            MaintenanceGate::Closed {
+                next_opens_at: Some(utc(4, 21, 30))
+            }
+        );
+        assert_eq!(
+            evaluate_windows(&[], utc(4, 12, 0)),
+            MaintenanceGate::Closed {
+                next_opens_at: None
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_windows_are_rejected() {
+        assert!(matches!(
+            WindowSchedule::parse("0 2 * *", 60, "UTC"),
+            Err(MaintenanceWindowError::Recurrence(_))
+        ));
+        assert_eq!(
+            WindowSchedule::parse("0 2 * * *", 60, "Europe/Berlin").unwrap_err(),
+            MaintenanceWindowError::Timezone("Europe/Berlin".into())
+        );
+        assert_eq!(
+            WindowSchedule::parse("0 2 * * *", 0, "UTC").unwrap_err(),
+            MaintenanceWindowError::Duration
+        );
+    }
+}
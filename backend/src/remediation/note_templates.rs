@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+// key: remediation-workspace -> promotion note templates
+
+/// Variables a promotion note template may reference as `{name}`.
+pub const NOTE_VARIABLES: &[&str] = &[
+    "requester",
+    "timestamp",
+    "workspace",
+    "revision",
+    "promotion_status",
+    "schema_status",
+    "policy_status",
+    "simulation_status",
+    "gate_override",
+];
+
+/// Rendered in place of a variable with no value for this promotion.
+pub const MISSING_VALUE: &str = "unknown";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Variable(&'static str),
+}
+
+/// A parsed template. `{{` and `}}` render literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteTemplate {
+    segments: Vec<Segment>,
+}
+
+impl NoteTemplate {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => name.push(ch),
+                            None => return Err(format!("unclosed `{{{name}`")),
+                        }
+                    }
+                    let name = name.trim();
+                    let Some(variable) =
+                        NOTE_VARIABLES.iter().copied().find(|known| *known == name)
+                    else {
+                        return Err(format!(
+                            "unknown variable `{name}`; expected one of {}",
+                            NOTE_VARIABLES.join(", ")
+                        ));
+                    };
+                    if !literal.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Variable(variable));
+                }
+                '}' => return Err("unmatched `}`; write `}}` for a literal brace".into()),
+                ch => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Text(literal));
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn render(&self, variables: &NoteVariables) -> String {
+        let mut note = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => note.push_str(text),
+                Segment::Variable(name) => note.push_str(
+                    variables
+                        .values
+                        .get(name)
+                        .map(String::as_str)
+                        .unwrap_or(MISSING_VALUE),
+                ),
+            }
+        }
+        note
+    }
+}
+
+/// Values known for one promotion; variables never set render as [`MISSING_VALUE`].
+#[derive(Debug, Clone, Default)]
+pub struct NoteVariables {
+    values: BTreeMap<&'static str, String>,
+}
+
+impl NoteVariables {
+    /// Sets `name`, which must be one of [`NOTE_VARIABLES`].
+    pub fn set(&mut self, name: &'static str, value: impl ToString) {
+        debug_assert!(
+            NOTE_VARIABLES.contains(&name),
+            "unknown note variable {name}"
+        );
+        self.values.insert(name, value.to_string());
+    }
+}
+
+/// Named templates that promotion requests select through `note_templates`.
+#[derive(Debug, Clone, Default)]
+pub struct NoteTemplates {
+    templates: BTreeMap<String, NoteTemplate>,
+}
+
+impl NoteTemplates {
+    /// Parses a JSON object of template names to template strings, rejecting the whole set if
+    /// any template is malformed.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let entries = value
+            .as_object()
+            .ok_or_else(|| "expected a JSON object of template names to templates".to_string())?;
+        let mut templates = BTreeMap::new();
+        for (name, text) in entries {
+            let text = text
+                .as_str()
+                .ok_or_else(|| format!("template `{name}` must be a string"))?;
+            let template =
+                NoteTemplate::parse(text).map_err(|err| format!("template `{name}`: {err}"))?;
+            templates.insert(name.clone(), template);
+        }
+        Ok(Self { templates })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NoteTemplate> {
+        self.templates.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn all_variables() -> NoteVariables {
+        let mut variables = NoteVariables::default();
+        variables.set("requester", 42);
+        variables.set("timestamp", "2025-03-01T12:00:00Z");
+        variables.set("workspace", "db-failover");
+        variables.set("revision", 3);
+        variables.set("promotion_status", "completed");
+        variables.set("schema_status", "succeeded");
+        variables.set("policy_status", "approved");
+        variables.set("simulation_status", "succeeded");
+        variables.set("gate_override", "hotfix");
+        variables
+    }
+
+    #[test]
+    fn renders_every_variable() {
+        let templates = NoteTemplates::from_json(&json!({
+            "audit": "requested_by={requester} at {timestamp}: {workspace} r{revision} -> {promotion_status}",
+            "gates": "{{gates}} schema={schema_status} policy={ policy_status } simulation={simulation_status} override={gate_override}"
+        }))
+        .unwrap();
+        let variables = all_variables();
+        assert_eq!(
+            templates.get("audit").unwrap().render(&variables),
+            "requested_by=42 at 2025-03-01T12:00:00Z: db-failover r3 -> completed"
+        );
+        assert_eq!(
+            templates.get("gates").unwrap().render(&variables),
+            "{gates} schema=succeeded policy=approved simulation=succeeded override=hotfix"
+        );
+        assert!(templates.get("missing").is_none());
+    }
+
+    #[test]
+    fn unset_variables_render_as_unknown() {
+        let template = NoteTemplate::parse("override={gate_override} by {requester}").unwrap();
+        let mut variables = NoteVariables::default();
+        variables.set("requester", 7);
+        assert_eq!(template.render(&variables), "override=unknown by 7");
+    }
+
+    #[test]
+    fn malformed_templates_are_rejected() {
+        for (template, problem) in [
+            (
+                json!({"a": "by {requester"}),
+                "template `a`: unclosed `{requester`",
+            ),
+            (json!({"a": "done }"}), "template `a`: unmatched `}`"),
+            (
+                json!({"a": "by {approver}"}),
+                "template `a`: unknown variable `approver`",
+            ),
+            (json!({"a": 3}), "template `a` must be a string"),
+            (json!(["by {requester}"]), "expected a JSON object"),
+        ] {
+            let err = NoteTemplates::from_json(&template).unwrap_err();
+            assert!(err.starts_with(problem), "{err}");
+        }
+    }
+}
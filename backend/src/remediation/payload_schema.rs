@@ -0,0 +1,366 @@
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// key: remediation-payload-schema -> JSON Schema subset for playbook automation payloads
+
+/// Keywords the validator enforces. Anything else that would constrain a payload is refused
+/// when the schema is saved, so a playbook never carries a rule that silently passes.
+const ENFORCED_KEYWORDS: &[&str] = &[
+    "type",
+    "enum",
+    "const",
+    "required",
+    "properties",
+    "additionalProperties",
+    "items",
+    "minItems",
+    "maxItems",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+];
+/// Keywords that only describe the payload.
+const ANNOTATION_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "format",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+const TYPE_NAMES: &[&str] = &[
+    "null", "boolean", "object", "array", "number", "integer", "string",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value; empty for the payload itself.
+    pub path: String,
+    pub message: String,
+}
+
+/// Checks that `schema` only uses keywords the validator enforces, with well-formed values.
+pub fn check_schema(schema: &Value) -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    check_node(schema, "", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+fn check_node(schema: &Value, at: &str, problems: &mut Vec<String>) {
+    let object = match schema {
+        Value::Bool(_) => return,
+        Value::Object(object) => object,
+        _ => {
+            problems.push(format!(
+                "{}: a schema must be an object or boolean",
+                label(at)
+            ));
+            return;
+        }
+    };
+    for (keyword, value) in object {
+        let here = format!("{at}/{keyword}");
+        match keyword.as_str() {
+            "type" => {
+                let names: Vec<&Value> = match value {
+                    Value::Array(names) => names.iter().collect(),
+                    other => vec![other],
+                };
+                if !names
+                    .iter()
+                    .all(|name| name.as_str().is_some_and(|name| TYPE_NAMES.contains(&name)))
+                {
+                    problems.push(format!("{here}: unknown type"));
+                }
+            }
+            "enum" if !value.is_array() => problems.push(format!("{here}: must be an array")),
+            "required"
+                if !value
+                    .as_array()
+                    .is_some_and(|names| names.iter().all(Value::is_string)) =>
+            {
+                problems.push(format!("{here}: must be an array of property names"))
+            }
+            "properties" => match value.as_object() {
+                Some(properties) => {
+                    for (name, property) in properties {
+                        check_node(property, &format!("{here}/{name}"), problems);
+                    }
+                }
+                None => problems.push(format!("{here}: must be an object")),
+            },
+            "additionalProperties" | "items" => check_node(value, &here, problems),
+            "minItems" | "maxItems" | "minLength" | "maxLength" if value.as_u64().is_none() => {
+                problems.push(format!("{here}: must be a non-negative integer"))
+            }
+            "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum"
+                if !value.is_number() =>
+            {
+                problems.push(format!("{here}: must be a number"))
+            }
+            "pattern" => match value.as_str().map(Regex::new) {
+                Some(Ok(_)) => {}
+                _ => problems.push(format!("{here}: must be a valid regular expression")),
+            },
+            keyword
+                if ENFORCED_KEYWORDS.contains(&keyword)
+                    || ANNOTATION_KEYWORDS.contains(&keyword) => {}
+            keyword => problems.push(format!("{here}: keyword `{keyword}` is not supported")),
+        }
+    }
+}
+
+fn label(at: &str) -> &str {
+    if at.is_empty() {
+        "schema"
+    } else {
+        at
+    }
+}
+
+/// Every way `instance` breaks `schema`. Assumes `schema` passed [`check_schema`].
+pub fn validate(schema: &Value, instance: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_node(schema, instance, "", &mut violations);
+    violations
+}
+
+fn validate_node(schema: &Value, instance: &Value, at: &str, out: &mut Vec<SchemaViolation>) {
+    let rules = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => return violation(out, at, "no value is allowed here".into()),
+        Value::Object(rules) => rules,
+        _ => return,
+    };
+
+    if let Some(expected) = rules.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        if !names.iter().any(|name| matches_type(name, instance)) {
+            return violation(
+                out,
+                at,
+                format!(
+                    "expected {}, found {}",
+                    names.join(" or "),
+                    type_of(instance)
+                ),
+            );
+        }
+    }
+    if let Some(allowed) = rules.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(instance) {
+            violation(
+                out,
+                at,
+                format!("must be one of {}", Value::from(allowed.clone())),
+            );
+        }
+    }
+    if let Some(expected) = rules.get("const") {
+        if expected != instance {
+            violation(out, at, format!("must equal {expected}"));
+        }
+    }
+
+    match instance {
+        Value::Object(object) => validate_object(rules, object, at, out),
+        Value::Array(items) => {
+            if let Some(min) = rules.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    violation(out, at, format!("must have at least {min} items"));
+                }
+            }
+            if let Some(max) = rules.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    violation(out, at, format!("must have at most {max} items"));
+                }
+            }
+            if let Some(item_schema) = rules.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_node(item_schema, item, &format!("{at}/{index}"), out);
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = rules.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    violation(out, at, format!("must be at least {min} characters"));
+                }
+            }
+            if let Some(max) = rules.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    violation(out, at, format!("must be at most {max} characters"));
+                }
+            }
+            if let Some(pattern) = rules.get("pattern").and_then(Value::as_str) {
+                if let Ok(regex) = Regex::new(pattern) {
+                    if !regex.is_match(text) {
+                        violation(out, at, format!("must match pattern {pattern}"));
+                    }
+                }
+            }
+        }
+        Value::Number(number) => {
+            let Some(value) = number.as_f64() else {
+                return;
+            };
+            let bound = |keyword: &str| rules.get(keyword).and_then(Value::as_f64);
+            if let Some(min) = bound("minimum").filter(|min| value < *min) {
+                violation(out, at, format!("must be at least {min}"));
+            }
+            if let Some(max) = bound("maximum").filter(|max| value > *max) {
+                violation(out, at, format!("must be at most {max}"));
+            }
+            if let Some(min) = bound("exclusiveMinimum").filter(|min| value <= *min) {
+                violation(out, at, format!("must be greater than {min}"));
+            }
+            if let Some(max) = bound("exclusiveMaximum").filter(|max| value >= *max) {
+                violation(out, at, format!("must be less than {max}"));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    rules: &Map<String, Value>,
+    object: &Map<String, Value>,
+    at: &str,
+    out: &mut Vec<SchemaViolation>,
+) {
+    if let Some(required) = rules.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                violation(out, at, format!("missing required property `{name}`"));
+            }
+        }
+    }
+    let properties = rules.get("properties").and_then(Value::as_object);
+    for (name, value) in object {
+        let path = format!("{at}/{name}");
+        match properties.and_then(|properties| properties.get(name)) {
+            Some(property_schema) => validate_node(property_schema, value, &path, out),
+            None => match rules.get("additionalProperties") {
+                Some(Value::Bool(false)) => violation(out, &path, "property is not allowed".into()),
+                Some(extra_schema) => validate_node(extra_schema, value, &path, out),
+                None => {}
+            },
+        }
+    }
+}
+
+fn matches_type(name: &str, instance: &Value) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|value| value.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+fn type_of(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+    }
+}
+
+fn violation(out: &mut Vec<SchemaViolation>, at: &str, message: String) {
+    out.push(SchemaViolation {
+        path: at.to_string(),
+        message,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn restart_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["service"],
+            "properties": {
+                "service": { "type": "string", "pattern": "^[a-z0-9-]+$" },
+                "timeout_seconds": { "type": "integer", "minimum": 1, "maximum": 600 },
+                "signals": { "type": "array", "items": { "enum": ["TERM", "KILL"] } }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn matching_payload_passes() {
+        let schema = restart_schema();
+        assert_eq!(check_schema(&schema), Ok(()));
+        let payload = json!({ "service": "nginx", "timeout_seconds": 30, "signals": ["TERM"] });
+        assert!(validate(&schema, &payload).is_empty());
+    }
+
+    #[test]
+    fn violations_are_listed_with_paths() {
+        let payload = json!({
+            "timeout_seconds": 0,
+            "signals": ["TERM", "HUP"],
+            "force": true
+        });
+        let violations = validate(&restart_schema(), &payload);
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["", "/force", "/signals/1", "/timeout_seconds"]);
+        assert_eq!(violations[0].message, "missing required property `service`");
+
+        let violations = validate(&restart_schema(), &Value::Null);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "expected object, found null");
+    }
+
+    #[test]
+    fn unsupported_keywords_are_rejected() {
+        let problems = check_schema(&json!({
+            "type": "object",
+            "properties": { "mode": { "oneOf": [{ "const": "a" }] } },
+            "minProperties": 1
+        }))
+        .unwrap_err();
+        assert_eq!(
+            problems,
+            vec![
+                "/minProperties: keyword `minProperties` is not supported",
+                "/properties/mode/oneOf: keyword `oneOf` is not supported",
+            ]
+        );
+        assert!(check_schema(&json!({ "pattern": "(" })).is_err());
+        assert!(check_schema(&json!("object")).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::maintenance::DISRUPTIVE_FLAG;
+use super::payload_schema::check_schema;
+use super::{RemediationExecutorKind, VM_SNAPSHOT_FLAG};
+
+// key: remediation-playbook-validation -> pre-flight checks shared by create and validate
+
+const SUPPORTED_EXECUTORS: &[RemediationExecutorKind] = &[
+    RemediationExecutorKind::Shell,
+    RemediationExecutorKind::Ansible,
+    RemediationExecutorKind::CloudApi,
+];
+/// SLAs beyond a week are allowed but almost always a units mistake.
+const LONG_SLA_SECONDS: i32 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybookIssueSeverity {
+    /// Blocks creation.
+    Error,
+    /// Reported but does not block creation.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlaybookIssue {
+    pub field: String,
+    pub code: &'static str,
+    pub severity: PlaybookIssueSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlaybookValidation {
+    pub valid: bool,
+    pub errors: Vec<PlaybookIssue>,
+    pub warnings: Vec<PlaybookIssue>,
+}
+
+/// The fields of a playbook the checks look at.
+#[derive(Debug, Clone, Copy)]
+pub struct PlaybookDraft<'a> {
+    pub playbook_key: &'a str,
+    pub display_name: &'a str,
+    pub executor_type: &'a str,
+    pub sla_duration_seconds: Option<i32>,
+    pub max_concurrent_runs: Option<i32>,
+    pub metadata: &'a Value,
+    pub automation_payload_schema: Option<&'a Value>,
+}
+
+impl PlaybookValidation {
+    fn error(&mut self, field: impl Into<String>, code: &'static str, message: impl Into<String>) {
+        self.errors.push(PlaybookIssue {
+            field: field.into(),
+            code,
+            severity: PlaybookIssueSeverity::Error,
+            message: message.into(),
+        });
+    }
+
+    fn warning(
+        &mut self,
+        field: impl Into<String>,
+        code: &'static str,
+        message: impl Into<String>,
+    ) {
+        self.warnings.push(PlaybookIssue {
+            field: field.into(),
+            code,
+            severity: PlaybookIssueSeverity::Warning,
+            message: message.into(),
+        });
+    }
+}
+
+pub fn validate_playbook(draft: &PlaybookDraft<'_>) -> PlaybookValidation {
+    let mut report = PlaybookValidation::default();
+
+    if draft.playbook_key.trim().is_empty() {
+        report.error("playbook_key", "missing", "playbook_key is required");
+    } else if draft.playbook_key.chars().any(char::is_whitespace) {
+        report.error(
+            "playbook_key",
+            "malformed",
+            "playbook_key must not contain whitespace",
+        );
+    }
+    if draft.display_name.trim().is_empty() {
+        report.error("display_name", "missing", "display_name is required");
+    }
+
+    if RemediationExecutorKind::from_str(draft.executor_type).is_err() {
+        let supported = SUPPORTED_EXECUTORS
+            .iter()
+            .map(RemediationExecutorKind::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        report.error(
+            "executor_type",
+            "unsupported_executor",
+            format!(
+                "executor type `{}` is not supported; expected one of {supported}",
+                draft.executor_type
+            ),
+        );
+    }
+
+    match draft.sla_duration_seconds {
+        Some(seconds) if seconds < 0 => report.error(
+            "sla_duration_seconds",
+            "negative_sla",
+            "sla_duration_seconds must not be negative",
+        ),
+        Some(0) => report.warning(
+            "sla_duration_seconds",
+            "zero_sla",
+            "an SLA of 0 seconds is breached as soon as a run starts",
+        ),
+        Some(seconds) if seconds > LONG_SLA_SECONDS => report.warning(
+            "sla_duration_seconds",
+            "long_sla",
+            format!("an SLA of {seconds} seconds is longer than a week"),
+        ),
+        _ => {}
+    }
+
+    if draft.max_concurrent_runs.is_some_and(|limit| limit < 1) {
+        report.error(
+            "max_concurrent_runs",
+            "invalid_limit",
+            "max_concurrent_runs must be at least 1; omit it for no limit",
+        );
+    }
+
+    validate_metadata(draft.metadata, &mut report);
+
+    if let Some(Err(problems)) = draft.automation_payload_schema.map(check_schema) {
+        for problem in problems {
+            report.error("automation_payload_schema", "invalid_schema", problem);
+        }
+    }
+
+    report.valid = report.errors.is_empty();
+    report
+}
+
+fn validate_metadata(metadata: &Value, report: &mut PlaybookValidation) {
+    let object = match metadata {
+        Value::Null => return,
+        Value::Object(object) => object,
+        _ => {
+            report.error(
+                "metadata",
+                "not_an_object",
+                "metadata must be a JSON object",
+            );
+            return;
+        }
+    };
+
+    for key in object.keys() {
+        if !well_formed_key(key) {
+            report.warning(
+                format!("metadata.{key}"),
+                "malformed_key",
+                "metadata keys should use lowercase letters, digits, `_`, `-`, or `.`",
+            );
+        }
+    }
+
+    for flag in [VM_SNAPSHOT_FLAG, DISRUPTIVE_FLAG] {
+        if object.get(flag).is_some_and(|value| !value.is_boolean()) {
+            report.error(
+                format!("metadata.{flag}"),
+                "invalid_type",
+                format!("{flag} must be true or false"),
+            );
+        }
+    }
+    if let Some(gate) = object.get("policy_gate") {
+        match gate.as_object() {
+            None => report.error(
+                "metadata.policy_gate",
+                "invalid_type",
+                "policy_gate must be an object",
+            ),
+            Some(gate) => {
+                let hooks_valid = match gate.get("remediation_hooks") {
+                    Some(hooks) => hooks
+                        .as_array()
+                        .is_some_and(|entries| entries.iter().all(Value::is_string)),
+                    None => true,
+                };
+                if !hooks_valid {
+                    report.error(
+                        "metadata.policy_gate.remediation_hooks",
+                        "invalid_type",
+                        "remediation_hooks must be an array of strings",
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn well_formed_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.chars().all(|ch| {
+            ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, '_' | '-' | '.')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn draft<'a>(
+        executor_type: &'a str,
+        sla_duration_seconds: Option<i32>,
+        metadata: &'a Value,
+    ) -> PlaybookDraft<'a> {
+        PlaybookDraft {
+            playbook_key: "vm.restart",
+            display_name: "Restart VM",
+            executor_type,
+            sla_duration_seconds,
+            max_concurrent_runs: None,
+            metadata,
+            automation_payload_schema: None,
+        }
+    }
+
+    #[test]
+    fn valid_playbook_has_no_errors() {
+        let metadata = json!({ "vm_snapshot": true, "tier": "gold" });
+        let report = validate_playbook(&draft("shell", Some(900), &metadata));
+        assert!(report.valid);
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn unsupported_executor_type_is_an_error() {
+        let report = validate_playbook(&draft("terraform", Some(900), &Value::Null));
+        assert!(!report.valid);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].field, "executor_type");
+        assert_eq!(report.errors[0].code, "unsupported_executor");
+        assert_eq!(report.errors[0].severity, PlaybookIssueSeverity::Error);
+    }
+
+    #[test]
+    fn zero_sla_is_a_warning() {
+        let report = validate_playbook(&draft("ansible", Some(0), &Value::Null));
+        assert!(report.valid);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].field, "sla_duration_seconds");
+        assert_eq!(report.warnings[0].code, "zero_sla");
+        assert_eq!(report.warnings[0].severity, PlaybookIssueSeverity::Warning);
+    }
+
+    #[test]
+    fn metadata_keys_and_known_values_are_checked() {
+        let metadata = json!({
+            "vm_snapshot": "yes",
+            "policy_gate": { "remediation_hooks": ["notify", 3] },
+            "Owner Team": "sre",
+        });
+        let report = validate_playbook(&draft("cloud_api", Some(-5), &metadata));
+        let error_fields: Vec<&str> = report.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            error_fields,
+            vec![
+                "sla_duration_seconds",
+                "metadata.vm_snapshot",
+                "metadata.policy_gate.remediation_hooks"
+            ]
+        );
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].field, "metadata.Owner Team");
+    }
+
+    #[test]
+    fn payload_schema_is_checked() {
+        let schema = json!({ "type": "object", "anyOf": [] });
+        let report = validate_playbook(&PlaybookDraft {
+            automation_payload_schema: Some(&schema),
+            ..draft("shell", Some(900), &Value::Null)
+        });
+        assert!(!report.valid);
+        assert_eq!(report.errors[0].field, "automation_payload_schema");
+        assert_eq!(report.errors[0].code, "invalid_schema");
+    }
+
+    #[test]
+    fn concurrency_limit_must_be_positive() {
+        let report = validate_playbook(&PlaybookDraft {
+            max_concurrent_runs: Some(0),
+            ..draft("shell", Some(900), &Value::Null)
+        });
+        assert!(!report.valid);
+        assert_eq!(report.errors[0].field, "max_concurrent_runs");
+        assert_eq!(report.errors[0].code, "invalid_limit");
+    }
+}
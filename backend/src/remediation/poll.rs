@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+
+use crate::config::reload;
+
+// key: remediation-orchestrator -> adaptive poll loop
+
+static WORK_SIGNAL: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// Wakes the remediation worker now instead of at its next poll. Call it after committing a
+/// run the worker can pick up. A signal sent while the worker is busy is kept, so the worker's
+/// next wait returns at once.
+pub fn notify_work_available() {
+    WORK_SIGNAL.notify_one();
+}
+
+/// How long the worker waits between polls. Each idle poll doubles the wait, up to `max`.
+/// Finding work resets it to `min`.
+#[derive(Debug, Clone)]
+pub struct PollBackoff {
+    min: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl PollBackoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        let min = min.max(Duration::from_millis(1));
+        Self {
+            min,
+            max: max.max(min),
+            next: min,
+        }
+    }
+
+    /// Bounds from `REMEDIATION_POLL_MIN_INTERVAL_MS` and `REMEDIATION_POLL_MAX_INTERVAL_MS`.
+    pub fn configured() -> Self {
+        let (min, max) = configured_bounds();
+        Self::new(min, max)
+    }
+
+    /// Picks up bounds changed by a config reload. The current wait is kept if it still
+    /// falls within them.
+    pub fn refresh_configured(&mut self) {
+        let (min, max) = configured_bounds();
+        let min = min.max(Duration::from_millis(1));
+        self.min = min;
+        self.max = max.max(min);
+        self.next = self.next.clamp(self.min, self.max);
+    }
+
+    pub fn record_work(&mut self) {
+        self.next = self.min;
+    }
+
+    /// The wait before the next poll. Each call doubles the wait after it.
+    pub fn idle_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = delay.saturating_mul(2).min(self.max);
+        delay
+    }
+}
+
+fn configured_bounds() -> (Duration, Duration) {
+    let settings = reload::current();
+    (
+        Duration::from_millis(settings.remediation_poll_min_interval_ms),
+        Duration::from_millis(settings.remediation_poll_max_interval_ms),
+    )
+}
+
+/// Sleeps for `delay`, or less if `notify_work_available` is called first. Returns `true`
+/// if the signal cut the sleep short.
+pub async fn wait_for_work(delay: Duration) -> bool {
+    tokio::select! {
+        _ = WORK_SIGNAL.notified() => true,
+        _ = sleep(delay) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn idle_polls_back_off_to_the_cap_and_work_resets() {
+        let mut backoff = PollBackoff::new(Duration::from_millis(250), Duration::from_secs(2));
+        let delays: Vec<u128> = (0..6).map(|_| backoff.idle_delay().as_millis()).collect();
+        assert_eq!(delays, vec![250, 500, 1000, 2000, 2000, 2000]);
+
+        backoff.record_work();
+        assert_eq!(backoff.idle_delay(), Duration::from_millis(250));
+
+        // a cap below the floor is raised to the floor
+        let mut inverted = PollBackoff::new(Duration::from_secs(1), Duration::from_millis(10));
+        assert_eq!(inverted.idle_delay(), Duration::from_secs(1));
+        assert_eq!(inverted.idle_delay(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn enqueue_signal_wakes_an_idle_wait() {
+        let started = Instant::now();
+        let waiter = tokio::spawn(wait_for_work(Duration::from_secs(3600)));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        notify_work_available();
+        let woken = tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("wait should end promptly after the signal")
+            .unwrap();
+        assert!(woken);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // a signal sent while nobody waits is kept for the next wait
+        notify_work_available();
+        assert!(wait_for_work(Duration::from_secs(3600)).await);
+    }
+}
@@ -2,17 +2,28 @@ use std::collections::HashSet;
 use std::convert::Infallible;
 
 use axum::{
+    body::{Bytes, StreamBody},
     extract::{Extension, Path, Query},
+    http::{header, HeaderMap},
     response::sse::{Event, Sse},
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 use sqlx::PgPool;
-use tokio_stream::wrappers::BroadcastStream;
-
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
+
+use crate::audit::organization::{self, AuditCategory, AuditRecord, AuditScope};
+use crate::config;
+use crate::db::remediation_maintenance_windows::{
+    create_window, delete_window, insert_override, list_overrides, list_windows,
+    CreateMaintenanceWindow, NewMaintenanceOverride, RemediationMaintenanceOverride,
+    RemediationMaintenanceWindow,
+};
 use crate::db::runtime_vm_accelerator_posture::{replace_instance_posture, NewAcceleratorPosture};
 use crate::db::runtime_vm_remediation_artifacts::{
     list_artifacts as list_run_artifacts, RuntimeVmRemediationArtifact,
@@ -24,25 +35,42 @@ use crate::db::runtime_vm_remediation_playbooks::{
     UpdateRuntimeVmRemediationPlaybook,
 };
 use crate::db::runtime_vm_remediation_runs::{
-    ensure_remediation_run, get_active_run_for_instance, get_run_by_id, list_runs,
-    update_approval_state, update_run_workspace_linkage, EnsureRemediationRunRequest,
-    ListRuntimeVmRemediationRuns, RuntimeVmRemediationRun, UpdateApprovalState,
+    cancel_unstarted_run, ensure_remediation_run, get_active_run_for_instance, get_run_by_id,
+    list_runs, list_runs_query, update_approval_state, update_run_workspace_linkage,
+    EnsureRemediationRunRequest, ListRuntimeVmRemediationRuns, RuntimeVmRemediationRun,
+    UpdateApprovalState,
 };
 use crate::db::runtime_vm_remediation_workspaces::{
     apply_policy_feedback, apply_promotion, apply_sandbox_simulation, apply_schema_validation,
     create_revision as create_workspace_revision, create_workspace as create_workspace_record,
-    get_workspace, list_workspace_details, CreateWorkspace, CreateWorkspaceRevision,
-    PolicyFeedbackUpdate, PromotionUpdate, RuntimeVmRemediationWorkspace,
+    get_workspace, get_workspace_by_key, list_workspace_details, CreateWorkspace,
+    CreateWorkspaceRevision, PolicyFeedbackUpdate, PromotionUpdate, RuntimeVmRemediationWorkspace,
     RuntimeVmRemediationWorkspaceRevision, RuntimeVmRemediationWorkspaceSandboxExecution,
     RuntimeVmRemediationWorkspaceValidationSnapshot, SandboxSimulationUpdate,
     SchemaValidationUpdate, WorkspaceDetails,
 };
 use crate::error::{AppError, AppResult};
-use crate::extractor::AuthUser;
+use crate::extractor::{AuthUser, Principal};
+use crate::organizations::{require_org_role, AdminRole, OrgAccess, OrgRole, ViewerRole};
+use crate::remediation::gate_context::GateContextMerge;
+use crate::remediation::impact::{preview_run_impact, RunImpactPreview};
+use crate::remediation::lineage::{build_lineage, WorkspaceLineage};
+use crate::remediation::maintenance::{
+    instance_organization, is_disruptive, maintenance_gate, MaintenanceGate, WindowSchedule,
+};
+use crate::remediation::note_templates::NoteVariables;
+use crate::remediation::payload_schema::{self, check_schema};
+use crate::remediation::playbook_validation::{
+    validate_playbook, PlaybookDraft, PlaybookValidation,
+};
+use crate::remediation::poll::notify_work_available;
 use crate::remediation::{
     broadcast_promotion_refresh, subscribe_remediation_events, PromotionAutomationRefresh,
+    WORKSPACE_GATE_GRAPH,
 };
-use tracing::{trace, warn};
+use crate::telemetry::tenant::TENANT_METRICS;
+use tracing::{error, trace, warn};
+use uuid::Uuid;
 
 // key: remediation_surface -> http-handlers
 #[derive(Debug, Deserialize)]
@@ -58,13 +86,31 @@ pub struct PlaybookCreateRequest {
     #[serde(default)]
     pub sla_duration_seconds: Option<i32>,
     #[serde(default)]
+    pub max_concurrent_runs: Option<i32>,
+    #[serde(default)]
     pub metadata: Value,
+    #[serde(default)]
+    pub automation_payload_schema: Option<Value>,
 }
 
 fn default_executor_type() -> String {
     "shell".to_string()
 }
 
+impl PlaybookCreateRequest {
+    fn validate(&self) -> PlaybookValidation {
+        validate_playbook(&PlaybookDraft {
+            playbook_key: &self.playbook_key,
+            display_name: &self.display_name,
+            executor_type: &self.executor_type,
+            sla_duration_seconds: self.sla_duration_seconds,
+            max_concurrent_runs: self.max_concurrent_runs,
+            metadata: &self.metadata,
+            automation_payload_schema: self.automation_payload_schema.as_ref(),
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PlaybookUpdateRequest {
     #[serde(default)]
@@ -78,7 +124,11 @@ pub struct PlaybookUpdateRequest {
     #[serde(default)]
     pub sla_duration_seconds: Option<Option<i32>>,
     #[serde(default)]
+    pub max_concurrent_runs: Option<Option<i32>>,
+    #[serde(default)]
     pub metadata: Option<Value>,
+    #[serde(default)]
+    pub automation_payload_schema: Option<Option<Value>>,
     pub expected_version: i64,
 }
 
@@ -92,6 +142,42 @@ pub struct RunCreateRequest {
     pub automation_payload: Option<Value>,
     #[serde(default)]
     pub assigned_owner_id: Option<i32>,
+    /// Defers execution until this time; the run waits as `scheduled` until then.
+    #[serde(default)]
+    pub scheduled_for: Option<DateTime<Utc>>,
+    /// Admits a disruptive playbook outside the organization's maintenance windows.
+    #[serde(default)]
+    pub emergency_override: Option<EmergencyOverrideRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmergencyOverrideRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceWindowCreateRequest {
+    pub name: String,
+    pub recurrence: String,
+    pub duration_minutes: i32,
+    #[serde(default = "default_window_timezone")]
+    pub timezone: String,
+    #[serde(default = "default_window_enabled")]
+    pub enabled: bool,
+}
+
+fn default_window_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_window_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RunCancelRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -112,6 +198,23 @@ pub struct RunsQuery {
     pub workspace_id: Option<i64>,
     #[serde(default)]
     pub workspace_revision_id: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+impl RunsQuery {
+    fn filter(&self) -> ListRuntimeVmRemediationRuns<'_> {
+        ListRuntimeVmRemediationRuns {
+            runtime_vm_instance_id: self.runtime_vm_instance_id,
+            status: self.status.as_deref(),
+            workspace_id: self.workspace_id,
+            workspace_revision_id: self.workspace_revision_id,
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -193,6 +296,8 @@ impl From<WorkspaceDetails> for WorkspaceEnvelope {
 #[derive(Debug, Deserialize)]
 pub struct WorkspaceCreateRequest {
     pub workspace_key: String,
+    #[serde(default)]
+    pub organization_id: Option<i32>,
     pub display_name: String,
     #[serde(default)]
     pub description: Option<String>,
@@ -205,6 +310,122 @@ pub struct WorkspaceCreateRequest {
     pub lineage_labels: Vec<String>,
 }
 
+/// Lists the request fields that differ from an existing workspace with the same key.
+/// Plan and lineage labels are compared against the initial revision so retries still match
+/// after later revisions were appended.
+fn workspace_create_conflicts(
+    existing: &WorkspaceDetails,
+    request: &WorkspaceCreateRequest,
+) -> Vec<&'static str> {
+    let workspace = &existing.workspace;
+    let initial_revision = existing
+        .revisions
+        .iter()
+        .find(|entry| entry.revision.revision_number == 1)
+        .map(|entry| &entry.revision);
+
+    let mut conflicts = Vec::new();
+    if workspace.display_name != request.display_name {
+        conflicts.push("display_name");
+    }
+    if workspace.description != request.description {
+        conflicts.push("description");
+    }
+    if workspace.metadata != request.metadata {
+        conflicts.push("metadata");
+    }
+    if workspace.lineage_tags != request.lineage_tags {
+        conflicts.push("lineage_tags");
+    }
+    match initial_revision {
+        Some(revision) => {
+            if revision.plan != request.plan {
+                conflicts.push("plan");
+            }
+            if revision.lineage_labels != request.lineage_labels {
+                conflicts.push("lineage_labels");
+            }
+        }
+        None => conflicts.push("plan"),
+    }
+    conflicts
+}
+
+fn resolve_existing_workspace(
+    existing: WorkspaceDetails,
+    request: &WorkspaceCreateRequest,
+) -> AppResult<Json<WorkspaceEnvelope>> {
+    let conflicts = workspace_create_conflicts(&existing, request);
+    if !conflicts.is_empty() {
+        return Err(AppError::Conflict(format!(
+            "workspace_key {} already exists with different fields: {}",
+            request.workspace_key,
+            conflicts.join(", ")
+        )));
+    }
+    trace!(
+        workspace_id = existing.workspace.id,
+        workspace_key = %request.workspace_key,
+        "workspace create replayed against existing workspace"
+    );
+    Ok(Json(WorkspaceEnvelope::from(existing)))
+}
+
+/// Enforce `minimum` against the organization owning a workspace. Workspaces
+/// without an organization are private to their owner.
+pub(crate) async fn ensure_workspace_role(
+    pool: &PgPool,
+    workspace_id: i64,
+    user_id: i32,
+    minimum: OrgRole,
+) -> AppResult<()> {
+    let scope: Option<(Option<i32>, i32)> = sqlx::query_as(
+        "SELECT organization_id, owner_id FROM runtime_vm_remediation_workspaces WHERE id = $1",
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((organization_id, owner_id)) = scope else {
+        return Err(AppError::NotFound);
+    };
+    require_workspace_scope(pool, organization_id, owner_id, user_id, minimum).await
+}
+
+/// [`ensure_workspace_role`] for a workspace that is already loaded.
+async fn require_workspace_role(
+    pool: &PgPool,
+    workspace: &RuntimeVmRemediationWorkspace,
+    user_id: i32,
+    minimum: OrgRole,
+) -> AppResult<()> {
+    require_workspace_scope(
+        pool,
+        workspace.organization_id,
+        workspace.owner_id,
+        user_id,
+        minimum,
+    )
+    .await
+}
+
+async fn require_workspace_scope(
+    pool: &PgPool,
+    organization_id: Option<i32>,
+    owner_id: i32,
+    user_id: i32,
+    minimum: OrgRole,
+) -> AppResult<()> {
+    match organization_id {
+        Some(organization_id) => {
+            require_org_role(pool, organization_id, user_id, minimum).await?;
+            Ok(())
+        }
+        // unscoped workspaces are private to their owner
+        None if owner_id != user_id => Err(AppError::NotFound),
+        None => Ok(()),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WorkspaceRevisionCreateRequest {
     pub plan: Value,
@@ -263,8 +484,51 @@ pub struct WorkspacePromotionRequest {
     pub gate_context: Value,
     pub expected_workspace_version: i64,
     pub expected_revision_version: i64,
+    #[serde(default)]
+    pub gate_override: Option<WorkspaceGateOverride>,
+    /// Names of `PROMOTION_NOTE_TEMPLATES` entries rendered and appended to `notes`.
+    #[serde(default)]
+    pub note_templates: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceGateOverride {
+    pub reason: String,
+}
+
+fn promotion_stages_runs(promotion_status: &str) -> bool {
+    matches!(promotion_status, "approved" | "completed")
+}
+
+/// Every status that moves a revision toward promotion must clear its prerequisite gates, not just
+/// the ones that stage runs.
+fn promotion_requires_gates(promotion_status: &str) -> bool {
+    !matches!(promotion_status, "not_requested" | "rejected")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspacePromotionPreviewRequest {
+    #[serde(default = "default_gate_context")]
+    pub gate_context: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromotionRunPreview {
+    pub runtime_vm_instance_id: i64,
+    pub playbook: String,
+    pub automation_payload: Value,
+    pub promotion_gate_context: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspacePromotionPreview {
+    pub workspace_id: i64,
+    pub revision_id: i64,
+    pub runs: Vec<PromotionRunPreview>,
+}
+
+const DEFAULT_PROMOTION_PLAYBOOK: &str = "default-vm-remediation";
+
 #[derive(Debug, Clone)]
 struct PromotionAutomationTarget {
     instance_id: i64,
@@ -273,6 +537,36 @@ struct PromotionAutomationTarget {
     automation_payload: Option<Value>,
 }
 
+impl PromotionAutomationTarget {
+    fn resolved_playbook(&self) -> String {
+        self.playbook_key
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PROMOTION_PLAYBOOK.to_string())
+    }
+
+    fn resolved_automation_payload(&self) -> Value {
+        self.automation_payload.clone().unwrap_or(Value::Null)
+    }
+}
+
+// key: remediation-workspace -> promotion-preview
+fn preview_promotion_runs(
+    workspace: &RuntimeVmRemediationWorkspace,
+    revision: &RuntimeVmRemediationWorkspaceRevision,
+    gate_context: &Value,
+    merge: GateContextMerge,
+) -> Vec<PromotionRunPreview> {
+    extract_promotion_targets(workspace, revision)
+        .into_iter()
+        .map(|target| PromotionRunPreview {
+            runtime_vm_instance_id: target.instance_id,
+            playbook: target.resolved_playbook(),
+            automation_payload: target.resolved_automation_payload(),
+            promotion_gate_context: merge.resolve(&target.target_snapshot, gate_context),
+        })
+        .collect()
+}
+
 fn parse_instance_id(value: &Value) -> Option<i64> {
     match value {
         Value::Number(number) => number.as_i64(),
@@ -497,6 +791,7 @@ fn build_promotion_metadata(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::runtime_vm_remediation_workspaces::WorkspaceRevisionDetails;
     use chrono::{TimeZone, Utc};
 
     fn sample_workspace(metadata_targets: Value) -> RuntimeVmRemediationWorkspace {
@@ -506,6 +801,7 @@ mod tests {
             display_name: "Workspace Test".to_string(),
             description: None,
             owner_id: 42,
+            organization_id: None,
             lifecycle_state: "draft".to_string(),
             active_revision_id: Some(88),
             metadata: json!({"targets": metadata_targets}),
@@ -642,32 +938,161 @@ mod tests {
         assert_eq!(targets.len(), 1);
         assert_eq!(targets[0].instance_id, 808);
     }
+
+    fn sample_create_request() -> WorkspaceCreateRequest {
+        WorkspaceCreateRequest {
+            workspace_key: "workspace.test".to_string(),
+            organization_id: None,
+            display_name: "Workspace Test".to_string(),
+            description: None,
+            plan: json!({"playbooks": ["vm.restart"], "targets": []}),
+            metadata: json!({"targets": []}),
+            lineage_tags: vec!["test".to_string()],
+            lineage_labels: vec!["alpha".to_string()],
+        }
+    }
+
+    fn sample_details() -> WorkspaceDetails {
+        let mut revision = sample_revision(json!([]));
+        revision.revision_number = 1;
+        WorkspaceDetails {
+            workspace: sample_workspace(json!([])),
+            revisions: vec![WorkspaceRevisionDetails {
+                revision,
+                sandbox_executions: Vec::new(),
+                validation_snapshots: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn workspace_create_conflicts_accepts_identical_replay() {
+        let conflicts = workspace_create_conflicts(&sample_details(), &sample_create_request());
+        assert!(conflicts.is_empty(), "unexpected conflicts: {conflicts:?}");
+    }
+
+    #[test]
+    fn workspace_create_conflicts_reports_changed_fields() {
+        let mut request = sample_create_request();
+        request.display_name = "Renamed".to_string();
+        request.plan = json!({"playbooks": ["vm.redeploy"]});
+
+        let conflicts = workspace_create_conflicts(&sample_details(), &request);
+        assert_eq!(conflicts, vec!["display_name", "plan"]);
+    }
+
+    #[test]
+    fn gate_context_merge_strategies_resolve_a_conflicting_target_lane() {
+        let workspace = sample_workspace(Value::Null);
+        let mut revision = sample_revision(json!({
+            "lanes": [{
+                "lane": "blue",
+                "targets": [{"instance_id": 101, "stage": "canary", "window": {"start": "01:00"}}]
+            }]
+        }));
+        revision.metadata = json!({});
+        let request = json!({"lane": "green", "window": {"end": "02:00"}, "approver": "ops"});
+
+        let resolved = |merge| {
+            let previews = preview_promotion_runs(&workspace, &revision, &request, merge);
+            assert_eq!(previews.len(), 1);
+            previews[0].promotion_gate_context.clone()
+        };
+
+        assert_eq!(resolved(GateContextMerge::RequestWins), request);
+        assert_eq!(
+            resolved(GateContextMerge::TargetWins),
+            json!({
+                "lane": "blue",
+                "stage": "canary",
+                "window": {"start": "01:00", "end": "02:00"},
+                "approver": "ops"
+            })
+        );
+        assert_eq!(
+            resolved(GateContextMerge::DeepMerge),
+            json!({
+                "lane": "green",
+                "stage": "canary",
+                "window": {"start": "01:00", "end": "02:00"},
+                "approver": "ops"
+            })
+        );
+    }
+
+    #[test]
+    fn preview_promotion_runs_resolves_playbooks_payloads_and_gate_context() {
+        let plan_targets = json!({
+            "lanes": [
+                {
+                    "lane": "blue",
+                    "targets": [{"instance_id": 101}]
+                }
+            ],
+            "direct": [
+                {
+                    "runtime_vm_instance_id": 303,
+                    "playbook": "vm.redeploy",
+                    "payload": {"path": "direct"}
+                }
+            ]
+        });
+        let workspace = sample_workspace(Value::Null);
+        let mut revision = sample_revision(plan_targets);
+        revision.metadata = json!({});
+        revision.plan = json!({"targets": revision.plan["targets"].clone()});
+        let gate_context = json!({"lane": "blue", "stage": "canary"});
+
+        let mut previews = preview_promotion_runs(
+            &workspace,
+            &revision,
+            &gate_context,
+            GateContextMerge::RequestWins,
+        );
+        previews.sort_by_key(|preview| preview.runtime_vm_instance_id);
+
+        assert_eq!(
+            previews,
+            vec![
+                PromotionRunPreview {
+                    runtime_vm_instance_id: 101,
+                    playbook: DEFAULT_PROMOTION_PLAYBOOK.to_string(),
+                    automation_payload: Value::Null,
+                    promotion_gate_context: gate_context.clone(),
+                },
+                PromotionRunPreview {
+                    runtime_vm_instance_id: 303,
+                    playbook: "vm.redeploy".to_string(),
+                    automation_payload: json!({"path": "direct"}),
+                    promotion_gate_context: gate_context.clone(),
+                },
+            ]
+        );
+    }
 }
 
 async fn stage_workspace_promotion_runs(
     pool: &PgPool,
     workspace: &RuntimeVmRemediationWorkspace,
     revision: &RuntimeVmRemediationWorkspaceRevision,
-    gate_context: &Value,
+    request_gate_context: &Value,
     notes: &[String],
     requested_by: i32,
 ) -> Result<Vec<RuntimeVmRemediationRun>, AppError> {
-    const DEFAULT_PLAYBOOK: &str = "default-vm-remediation";
-
     let targets = extract_promotion_targets(workspace, revision);
     if targets.is_empty() {
         return Ok(Vec::new());
     }
 
+    let merge = *config::PROMOTION_GATE_CONTEXT_MERGE;
     let mut staged = Vec::new();
     for target in targets {
-        let playbook_key = target
-            .playbook_key
-            .clone()
-            .unwrap_or_else(|| DEFAULT_PLAYBOOK.to_string());
+        let target_gate_context = merge.resolve(&target.target_snapshot, request_gate_context);
+        let gate_context = &target_gate_context;
+        let playbook_key = target.resolved_playbook();
         let playbook = get_playbook_by_key(pool, &playbook_key).await?;
 
-        let automation_payload_value = target.automation_payload.clone().unwrap_or(Value::Null);
+        let automation_payload_value = target.resolved_automation_payload();
         let automation_payload_for_insert =
             if automation_payload_value.is_null() && target.automation_payload.is_none() {
                 None
@@ -705,10 +1130,13 @@ async fn stage_workspace_promotion_runs(
             workspace_id: Some(workspace.id),
             workspace_revision_id: Some(revision.id),
             promotion_gate_context: Some(gate_context),
+            scheduled_for: None,
         };
 
         match ensure_remediation_run(pool, request).await? {
             Some(run) => {
+                notify_work_available();
+                TENANT_METRICS.run_enqueued(run.organization_id);
                 let updated = update_run_workspace_linkage(
                     pool,
                     run.id,
@@ -782,9 +1210,9 @@ pub async fn list_all_playbooks(
 
 pub async fn list_workspaces_handler(
     Extension(pool): Extension<PgPool>,
-    _user: AuthUser,
+    AuthUser { user_id, .. }: AuthUser,
 ) -> AppResult<Json<Vec<WorkspaceEnvelope>>> {
-    let records = list_workspace_details(&pool).await?;
+    let records = list_workspace_details(&pool, user_id).await?;
     let payload = records.into_iter().map(WorkspaceEnvelope::from).collect();
     Ok(Json(payload))
 }
@@ -794,44 +1222,189 @@ pub async fn create_workspace_handler(
     user: AuthUser,
     Json(request): Json<WorkspaceCreateRequest>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
+    if let Some(organization_id) = request.organization_id {
+        require_org_role(&pool, organization_id, user.user_id, OrgRole::Admin).await?;
+    }
+
+    if let Some(existing) =
+        get_workspace_by_key(&pool, request.organization_id, &request.workspace_key).await?
+    {
+        return resolve_existing_workspace(existing, &request);
+    }
+
     let lineage_tags: Vec<&str> = request.lineage_tags.iter().map(String::as_str).collect();
     let lineage_labels: Vec<&str> = request.lineage_labels.iter().map(String::as_str).collect();
 
-    let details = create_workspace_record(
+    let result = create_workspace_record(
         &pool,
         CreateWorkspace {
             workspace_key: &request.workspace_key,
             display_name: &request.display_name,
             description: request.description.as_deref(),
             owner_id: user.user_id,
+            organization_id: request.organization_id,
             plan: &request.plan,
             metadata: Some(&request.metadata),
             lineage_tags: &lineage_tags,
             lineage_labels: &lineage_labels,
         },
     )
-    .await?;
+    .await;
+
+    match result {
+        Ok(details) => Ok(Json(WorkspaceEnvelope::from(details))),
+        Err(sqlx::Error::Database(db_err)) if db_err.code().as_deref() == Some("23505") => {
+            // A concurrent request won the insert race; replay against the stored workspace.
+            let Some(existing) =
+                get_workspace_by_key(&pool, request.organization_id, &request.workspace_key)
+                    .await?
+            else {
+                return Err(AppError::Conflict(format!(
+                    "workspace_key {} already exists",
+                    request.workspace_key
+                )));
+            };
+            resolve_existing_workspace(existing, &request)
+        }
+        Err(err) => Err(AppError::Db(err)),
+    }
+}
 
-    Ok(Json(WorkspaceEnvelope::from(details)))
+#[derive(Debug, Default, Deserialize)]
+pub struct WorkspaceCloneRequest {
+    /// Key for the clone; generated from the source key when omitted.
+    #[serde(default)]
+    pub workspace_key: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Creates a workspace for the caller seeded from another workspace's active revision. The
+/// clone starts over at revision 1 with every gate pending, and inherits no runs.
+pub async fn clone_workspace_handler(
+    Extension(pool): Extension<PgPool>,
+    user: AuthUser,
+    Path(workspace_id): Path<i64>,
+    Json(request): Json<WorkspaceCloneRequest>,
+) -> AppResult<Json<WorkspaceEnvelope>> {
+    let Some(source) = get_workspace(&pool, workspace_id).await? else {
+        return Err(AppError::NotFound);
+    };
+    require_workspace_role(&pool, &source.workspace, user.user_id, OrgRole::Admin).await?;
+    let Some(active_revision) = source.workspace.active_revision_id.and_then(|revision_id| {
+        source
+            .revisions
+            .iter()
+            .find(|entry| entry.revision.id == revision_id)
+    }) else {
+        return Err(AppError::Conflict(
+            "workspace has no active revision to clone".into(),
+        ));
+    };
+
+    let workspace_key = match request.workspace_key {
+        Some(key) if key.trim().is_empty() => {
+            return Err(AppError::BadRequest(
+                "workspace_key must not be empty".into(),
+            ))
+        }
+        Some(key) => key,
+        None => format!(
+            "{}-clone-{}",
+            source.workspace.workspace_key,
+            &Uuid::new_v4().simple().to_string()[..8]
+        ),
+    };
+    let display_name = request
+        .display_name
+        .unwrap_or_else(|| format!("{} (clone)", source.workspace.display_name));
+    let description = request
+        .description
+        .or_else(|| source.workspace.description.clone());
+    let lineage_tags: Vec<&str> = source
+        .workspace
+        .lineage_tags
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let lineage_labels: Vec<&str> = active_revision
+        .revision
+        .lineage_labels
+        .iter()
+        .map(String::as_str)
+        .collect();
+
+    let result = create_workspace_record(
+        &pool,
+        CreateWorkspace {
+            workspace_key: &workspace_key,
+            display_name: &display_name,
+            description: description.as_deref(),
+            owner_id: user.user_id,
+            organization_id: source.workspace.organization_id,
+            plan: &active_revision.revision.plan,
+            metadata: Some(&source.workspace.metadata),
+            lineage_tags: &lineage_tags,
+            lineage_labels: &lineage_labels,
+        },
+    )
+    .await;
+
+    match result {
+        Ok(details) => {
+            trace!(
+                source_workspace_id = workspace_id,
+                workspace_id = details.workspace.id,
+                "workspace cloned"
+            );
+            Ok(Json(WorkspaceEnvelope::from(details)))
+        }
+        Err(sqlx::Error::Database(db_err)) if db_err.code().as_deref() == Some("23505") => Err(
+            AppError::Conflict(format!("workspace_key {workspace_key} already exists")),
+        ),
+        Err(err) => Err(AppError::Db(err)),
+    }
 }
 
 pub async fn get_workspace_handler(
     Extension(pool): Extension<PgPool>,
-    _user: AuthUser,
+    user: AuthUser,
     Path(workspace_id): Path<i64>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
     let Some(details) = get_workspace(&pool, workspace_id).await? else {
         return Err(AppError::NotFound);
     };
+    require_workspace_role(&pool, &details.workspace, user.user_id, OrgRole::Viewer).await?;
     Ok(Json(WorkspaceEnvelope::from(details)))
 }
 
+/// Revision chain of a workspace with gate statuses, the promoted revision, and branch points.
+pub async fn get_workspace_lineage_handler(
+    Extension(pool): Extension<PgPool>,
+    user: AuthUser,
+    Path(workspace_id): Path<i64>,
+) -> AppResult<Json<WorkspaceLineage>> {
+    let Some(details) = get_workspace(&pool, workspace_id).await? else {
+        return Err(AppError::NotFound);
+    };
+    require_workspace_role(&pool, &details.workspace, user.user_id, OrgRole::Viewer).await?;
+    let revisions: Vec<RuntimeVmRemediationWorkspaceRevision> = details
+        .revisions
+        .into_iter()
+        .map(|entry| entry.revision)
+        .collect();
+    Ok(Json(build_lineage(&details.workspace, &revisions)))
+}
+
 pub async fn create_workspace_revision_handler(
     Extension(pool): Extension<PgPool>,
     user: AuthUser,
     Path(workspace_id): Path<i64>,
     Json(request): Json<WorkspaceRevisionCreateRequest>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
     let lineage_labels: Vec<&str> = request.lineage_labels.iter().map(String::as_str).collect();
 
     let result = create_workspace_revision(
@@ -858,6 +1431,7 @@ pub async fn apply_workspace_schema_validation_handler(
     Path((workspace_id, revision_id)): Path<(i64, i64)>,
     Json(request): Json<WorkspaceSchemaValidationRequest>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
     let errors: Vec<&str> = request.errors.iter().map(String::as_str).collect();
 
     let result = apply_schema_validation(
@@ -887,6 +1461,7 @@ pub async fn apply_workspace_policy_feedback_handler(
     Path((workspace_id, revision_id)): Path<(i64, i64)>,
     Json(request): Json<WorkspacePolicyFeedbackRequest>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
     let veto_reasons: Vec<&str> = request.veto_reasons.iter().map(String::as_str).collect();
 
     let result = apply_policy_feedback(
@@ -916,6 +1491,7 @@ pub async fn apply_workspace_simulation_handler(
     Path((workspace_id, revision_id)): Path<(i64, i64)>,
     Json(request): Json<WorkspaceSimulationRequest>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
     let result = apply_sandbox_simulation(
         &pool,
         SandboxSimulationUpdate {
@@ -944,7 +1520,83 @@ pub async fn apply_workspace_promotion_handler(
     Path((workspace_id, revision_id)): Path<(i64, i64)>,
     Json(request): Json<WorkspacePromotionRequest>,
 ) -> AppResult<Json<WorkspaceEnvelope>> {
-    let notes: Vec<&str> = request.notes.iter().map(String::as_str).collect();
+    ensure_workspace_role(&pool, workspace_id, user.user_id, OrgRole::Admin).await?;
+    let mut promotion_notes = request.notes.clone();
+
+    if let Some(gate_override) = request.gate_override.as_ref() {
+        if gate_override.reason.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "gate_override requires a non-empty reason".into(),
+            ));
+        }
+    }
+
+    let note_templates = request
+        .note_templates
+        .iter()
+        .map(|name| {
+            config::PROMOTION_NOTE_TEMPLATES
+                .get(name)
+                .ok_or_else(|| AppError::BadRequest(format!("unknown note template `{name}`")))
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let requires_gates = promotion_requires_gates(&request.promotion_status);
+    let current = if requires_gates || !note_templates.is_empty() {
+        let Some(details) = get_workspace(&pool, workspace_id).await? else {
+            return Err(AppError::NotFound);
+        };
+        let Some(revision_details) = details
+            .revisions
+            .into_iter()
+            .find(|entry| entry.revision.id == revision_id)
+        else {
+            return Err(AppError::NotFound);
+        };
+        Some((details.workspace, revision_details.revision))
+    } else {
+        None
+    };
+
+    if let Some((_, revision)) = current.as_ref().filter(|_| requires_gates) {
+        let unmet = WORKSPACE_GATE_GRAPH.unmet_prerequisites("promotion", revision);
+        if !unmet.is_empty() {
+            let summary = unmet
+                .iter()
+                .map(|entry| format!("{}={}", entry.gate, entry.status))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let Some(gate_override) = request.gate_override.as_ref() else {
+                return Err(AppError::Conflict(format!(
+                    "promotion requires passing prerequisite gates ({summary}); supply gate_override with a reason to bypass"
+                )));
+            };
+            warn!(
+                workspace_id,
+                revision_id,
+                user_id = user.user_id,
+                unmet = %summary,
+                "workspace promotion bypassing unmet gates via override"
+            );
+            promotion_notes.push(format!("gate_override_by={}", user.user_id));
+            promotion_notes.push(format!("gate_override_bypassed={summary}"));
+            promotion_notes.push(format!(
+                "gate_override_reason={}",
+                gate_override.reason.trim()
+            ));
+        }
+    }
+
+    if let Some((workspace, revision)) = current.as_ref() {
+        let variables = promotion_note_variables(user.user_id, workspace, revision, &request);
+        promotion_notes.extend(
+            note_templates
+                .iter()
+                .map(|template| template.render(&variables)),
+        );
+    }
+
+    let notes: Vec<&str> = promotion_notes.iter().map(String::as_str).collect();
 
     let result = apply_promotion(
         &pool,
@@ -963,7 +1615,7 @@ pub async fn apply_workspace_promotion_handler(
     let mut envelope =
         map_workspace_update_result(&pool, workspace_id, Some(revision_id), result).await?;
 
-    if matches!(request.promotion_status.as_str(), "approved" | "completed") {
+    if promotion_stages_runs(&request.promotion_status) {
         if let Some(revision_envelope) = envelope
             .revisions
             .iter()
@@ -974,7 +1626,7 @@ pub async fn apply_workspace_promotion_handler(
                 &envelope.workspace,
                 &revision_envelope.revision,
                 &request.gate_context,
-                &request.notes,
+                &promotion_notes,
                 user.user_id,
             )
             .await?;
@@ -1004,11 +1656,81 @@ pub async fn apply_workspace_promotion_handler(
     Ok(Json(envelope))
 }
 
+fn promotion_note_variables(
+    requester: i32,
+    workspace: &RuntimeVmRemediationWorkspace,
+    revision: &RuntimeVmRemediationWorkspaceRevision,
+    request: &WorkspacePromotionRequest,
+) -> NoteVariables {
+    let mut variables = NoteVariables::default();
+    variables.set("requester", requester);
+    variables.set(
+        "timestamp",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+    );
+    variables.set("workspace", &workspace.workspace_key);
+    variables.set("revision", revision.revision_number);
+    variables.set("promotion_status", &request.promotion_status);
+    variables.set("schema_status", &revision.schema_status);
+    variables.set("policy_status", &revision.policy_status);
+    variables.set("simulation_status", &revision.simulation_status);
+    if let Some(gate_override) = request.gate_override.as_ref() {
+        variables.set("gate_override", gate_override.reason.trim());
+    }
+    variables
+}
+
+pub async fn preview_workspace_promotion_handler(
+    Extension(pool): Extension<PgPool>,
+    user: AuthUser,
+    Path((workspace_id, revision_id)): Path<(i64, i64)>,
+    Json(request): Json<WorkspacePromotionPreviewRequest>,
+) -> AppResult<Json<WorkspacePromotionPreview>> {
+    let Some(details) = get_workspace(&pool, workspace_id).await? else {
+        return Err(AppError::NotFound);
+    };
+    require_workspace_role(&pool, &details.workspace, user.user_id, OrgRole::Viewer).await?;
+    let Some(revision_details) = details
+        .revisions
+        .iter()
+        .find(|entry| entry.revision.id == revision_id)
+    else {
+        return Err(AppError::NotFound);
+    };
+
+    let runs = preview_promotion_runs(
+        &details.workspace,
+        &revision_details.revision,
+        &request.gate_context,
+        *config::PROMOTION_GATE_CONTEXT_MERGE,
+    );
+    trace!(
+        workspace_id,
+        revision_id,
+        run_count = runs.len(),
+        "computed workspace promotion preview"
+    );
+
+    Ok(Json(WorkspacePromotionPreview {
+        workspace_id,
+        revision_id,
+        runs,
+    }))
+}
+
 pub async fn create_playbook_handler(
     Extension(pool): Extension<PgPool>,
     user: AuthUser,
     Json(request): Json<PlaybookCreateRequest>,
 ) -> AppResult<Json<RuntimeVmRemediationPlaybook>> {
+    let validation = request.validate();
+    if !validation.valid {
+        return Err(AppError::JsonBadRequest(json!({
+            "error": "invalid playbook",
+            "errors": validation.errors,
+            "warnings": validation.warnings,
+        })));
+    }
     let record = create_playbook(
         &pool,
         CreateRuntimeVmRemediationPlaybook {
@@ -1019,13 +1741,26 @@ pub async fn create_playbook_handler(
             owner_id: user.user_id,
             approval_required: request.approval_required,
             sla_duration_seconds: request.sla_duration_seconds,
+            max_concurrent_runs: request.max_concurrent_runs,
             metadata: &request.metadata,
+            automation_payload_schema: request.automation_payload_schema.as_ref(),
         },
     )
     .await?;
     Ok(Json(record))
 }
 
+/// POST /api/trust/remediation/playbooks/validate
+///
+/// Runs the checks `create_playbook_handler` applies and reports every finding without
+/// persisting anything.
+pub async fn validate_playbook_handler(
+    _user: AuthUser,
+    Json(request): Json<PlaybookCreateRequest>,
+) -> AppResult<Json<PlaybookValidation>> {
+    Ok(Json(request.validate()))
+}
+
 pub async fn get_playbook_handler(
     Extension(pool): Extension<PgPool>,
     _user: AuthUser,
@@ -1043,6 +1778,19 @@ pub async fn update_playbook_handler(
     Path(playbook_id): Path<i64>,
     Json(request): Json<PlaybookUpdateRequest>,
 ) -> AppResult<Json<RuntimeVmRemediationPlaybook>> {
+    if let Some(Some(schema)) = &request.automation_payload_schema {
+        if let Err(problems) = check_schema(schema) {
+            return Err(AppError::JsonBadRequest(json!({
+                "error": "invalid automation_payload_schema",
+                "problems": problems,
+            })));
+        }
+    }
+    if matches!(request.max_concurrent_runs, Some(Some(limit)) if limit < 1) {
+        return Err(AppError::BadRequest(
+            "max_concurrent_runs must be at least 1; send null for no limit".to_string(),
+        ));
+    }
     let update = UpdateRuntimeVmRemediationPlaybook {
         display_name: request.display_name.as_deref(),
         description: request.description.as_deref(),
@@ -1050,7 +1798,12 @@ pub async fn update_playbook_handler(
         owner_id: None,
         approval_required: request.approval_required,
         sla_duration_seconds: request.sla_duration_seconds,
+        max_concurrent_runs: request.max_concurrent_runs,
         metadata: request.metadata.as_ref(),
+        automation_payload_schema: request
+            .automation_payload_schema
+            .as_ref()
+            .map(Option::as_ref),
         expected_version: request.expected_version,
     };
 
@@ -1072,40 +1825,108 @@ pub async fn delete_playbook_handler(
     Ok(Json(json!({ "deleted": true })))
 }
 
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+/// Rows serialized ahead of a slow reader before the cursor stops fetching.
+const NDJSON_BUFFERED_ROWS: usize = 32;
+
+fn accepts_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains(NDJSON_CONTENT_TYPE))
+}
+
+/// Returns the runs as a JSON array, or with `Accept: application/x-ndjson` as one
+/// JSON object per line streamed from a server-side cursor.
 pub async fn list_runs_handler(
     Extension(pool): Extension<PgPool>,
     _user: AuthUser,
+    headers: HeaderMap,
     Query(query): Query<RunsQuery>,
-) -> AppResult<Json<Vec<RuntimeVmRemediationRun>>> {
-    let records = list_runs(
-        &pool,
-        ListRuntimeVmRemediationRuns {
-            runtime_vm_instance_id: query.runtime_vm_instance_id,
-            status: query.status.as_deref(),
-            workspace_id: query.workspace_id,
-            workspace_revision_id: query.workspace_revision_id,
-        },
+) -> AppResult<Response> {
+    if accepts_ndjson(&headers) {
+        return Ok(stream_runs_ndjson(pool, query));
+    }
+    let records = list_runs(&pool, query.filter()).await?;
+    Ok(Json(records).into_response())
+}
+
+/// Rows are forwarded through a bounded channel as the cursor yields them, so memory
+/// stays flat however many runs match and a client that stops reading also stops the
+/// query. A database error mid-stream ends the body early; the status is already sent.
+fn stream_runs_ndjson(pool: PgPool, query: RunsQuery) -> Response {
+    let (tx, rx) = mpsc::channel::<Result<Bytes, sqlx::Error>>(NDJSON_BUFFERED_ROWS);
+    tokio::spawn(async move {
+        let mut builder = list_runs_query(&query.filter());
+        let mut rows = builder
+            .build_query_as::<RuntimeVmRemediationRun>()
+            .fetch(&pool);
+        while let Some(row) = rows.next().await {
+            let line = match row {
+                Ok(run) => {
+                    let mut line = match serde_json::to_vec(&run) {
+                        Ok(line) => line,
+                        Err(err) => {
+                            error!(?err, run_id = run.id, "failed to encode remediation run");
+                            break;
+                        }
+                    };
+                    line.push(b'\n');
+                    Ok(Bytes::from(line))
+                }
+                Err(err) => {
+                    error!(?err, "remediation run stream aborted");
+                    Err(err)
+                }
+            };
+            let failed = line.is_err();
+            if tx.send(line).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        StreamBody::new(ReceiverStream::new(rx)),
     )
-    .await?;
-    Ok(Json(records))
+        .into_response()
 }
 
 pub async fn get_run_handler(
     Extension(pool): Extension<PgPool>,
-    _user: AuthUser,
+    principal: Principal,
     Path(run_id): Path<i64>,
 ) -> AppResult<Json<RuntimeVmRemediationRun>> {
     let Some(record) = get_run_by_id(&pool, run_id).await? else {
         return Err(AppError::NotFound);
     };
+    if let Principal::Service(service) = &principal {
+        if !service
+            .owns_vm_instance(&pool, record.runtime_vm_instance_id)
+            .await?
+        {
+            return Err(AppError::NotFound);
+        }
+    }
     Ok(Json(record))
 }
 
+/// Users may enqueue for any instance; a service principal only for its own server's.
 pub async fn enqueue_run_handler(
     Extension(pool): Extension<PgPool>,
-    user: AuthUser,
+    principal: Principal,
     Json(request): Json<RunCreateRequest>,
 ) -> AppResult<Json<RunEnqueueResponse>> {
+    if let Principal::Service(service) = &principal {
+        if !service
+            .owns_vm_instance(&pool, request.runtime_vm_instance_id)
+            .await?
+        {
+            return Err(AppError::Forbidden);
+        }
+    }
     let playbook = match get_playbook_by_key(&pool, &request.playbook).await? {
         Some(record) => record,
         None => {
@@ -1115,9 +1936,56 @@ pub async fn enqueue_run_handler(
             )))
         }
     };
+    if let Some(schema) = &playbook.automation_payload_schema {
+        let payload = request.automation_payload.clone().unwrap_or(Value::Null);
+        let violations = payload_schema::validate(schema, &payload);
+        if !violations.is_empty() {
+            return Err(AppError::JsonBadRequest(json!({
+                "error": "automation_payload does not match the playbook schema",
+                "playbook": playbook.playbook_key,
+                "violations": violations,
+            })));
+        }
+    }
 
+    let now = Utc::now();
+    let effective_at = request.scheduled_for.filter(|at| *at > now).unwrap_or(now);
+    let mut admitted_override = None;
+    if is_disruptive(&playbook) {
+        if let Some(organization_id) =
+            instance_organization(&pool, request.runtime_vm_instance_id).await?
+        {
+            if let MaintenanceGate::Closed { next_opens_at } =
+                maintenance_gate(&pool, organization_id, effective_at).await?
+            {
+                let Some(emergency) = request.emergency_override.as_ref() else {
+                    let next = next_opens_at
+                        .map(|at| format!("; the next window opens at {}", at.to_rfc3339()))
+                        .unwrap_or_default();
+                    return Err(AppError::Conflict(format!(
+                        "playbook {} is disruptive and organization {organization_id} has no open maintenance window{next}",
+                        playbook.playbook_key
+                    )));
+                };
+                let reason = emergency.reason.trim();
+                if reason.is_empty() {
+                    return Err(AppError::BadRequest(
+                        "emergency_override requires a reason".into(),
+                    ));
+                }
+                let Principal::User(user) = &principal else {
+                    return Err(AppError::Forbidden);
+                };
+                let role =
+                    require_org_role(&pool, organization_id, user.user_id, OrgRole::Admin).await?;
+                admitted_override = Some((organization_id, user.user_id, role, reason));
+            }
+        }
+    }
+
+    let mut tx = pool.begin().await?;
     let created = ensure_remediation_run(
-        &pool,
+        &mut *tx,
         EnsureRemediationRunRequest {
             runtime_vm_instance_id: request.runtime_vm_instance_id,
             playbook_key: &playbook.playbook_key,
@@ -1125,24 +1993,64 @@ pub async fn enqueue_run_handler(
             metadata: Some(&request.metadata),
             automation_payload: request.automation_payload.as_ref(),
             approval_required: playbook.approval_required,
-            assigned_owner_id: request.assigned_owner_id.or(Some(user.user_id)),
+            assigned_owner_id: request
+                .assigned_owner_id
+                .or(Some(principal.acting_user_id())),
             sla_duration_seconds: playbook.sla_duration_seconds,
             workspace_id: None,
             workspace_revision_id: None,
             promotion_gate_context: None,
+            scheduled_for: request.scheduled_for,
         },
     )
     .await?;
 
-    if created.is_none() {
+    let Some(run) = created.as_ref() else {
         return Err(AppError::Conflict(
             "remediation run already active for instance".into(),
         ));
+    };
+    let mut override_audit = None;
+    if let Some((organization_id, actor_id, role, reason)) = admitted_override {
+        let record = insert_override(
+            &mut *tx,
+            NewMaintenanceOverride {
+                organization_id,
+                remediation_run_id: run.id,
+                playbook_id: Some(playbook.id),
+                actor_id,
+                actor_role: role.as_str(),
+                reason,
+                effective_at,
+            },
+        )
+        .await?;
+        warn!(
+            run_id = run.id,
+            organization_id,
+            actor_id,
+            override_id = record.id,
+            "disruptive remediation admitted outside maintenance windows"
+        );
+        override_audit = Some(
+            AuditRecord::new(
+                AuditScope::Organization(organization_id),
+                AuditCategory::Remediation,
+                "maintenance_override_admitted",
+            )
+            .user(actor_id)
+            .target(format!("remediation_run:{}", run.id))
+            .details(json!({ "override_id": record.id, "reason": reason })),
+        );
     }
-
-    if let Some(run) = created.as_ref() {
-        ingest_accelerator_posture(&pool, run.runtime_vm_instance_id, &request.metadata).await?;
+    tx.commit().await?;
+    if let Some(entry) = override_audit {
+        organization::record(&pool, entry);
     }
+    notify_work_available();
+
+    TENANT_METRICS.run_enqueued(run.organization_id);
+    ingest_accelerator_posture(&pool, run.runtime_vm_instance_id, &request.metadata).await?;
 
     Ok(Json(RunEnqueueResponse {
         created: true,
@@ -1266,10 +2174,135 @@ pub async fn update_approval_handler(
     else {
         return Err(AppError::Conflict("approval version mismatch".into()));
     };
+    if record.approval_state == "approved" {
+        notify_work_available();
+    }
 
     Ok(Json(record))
 }
 
+pub async fn list_maintenance_windows_handler(
+    Extension(pool): Extension<PgPool>,
+    access: OrgAccess<ViewerRole>,
+) -> AppResult<Json<Vec<RemediationMaintenanceWindow>>> {
+    let windows = list_windows(&pool, access.organization_id, false).await?;
+    Ok(Json(windows))
+}
+
+pub async fn create_maintenance_window_handler(
+    Extension(pool): Extension<PgPool>,
+    access: OrgAccess<AdminRole>,
+    Json(request): Json<MaintenanceWindowCreateRequest>,
+) -> AppResult<Json<RemediationMaintenanceWindow>> {
+    if request.name.trim().is_empty() {
+        return Err(AppError::BadRequest("name is required".into()));
+    }
+    WindowSchedule::parse(
+        &request.recurrence,
+        request.duration_minutes,
+        &request.timezone,
+    )
+    .map_err(|err| AppError::BadRequest(err.to_string()))?;
+    let window = create_window(
+        &pool,
+        CreateMaintenanceWindow {
+            organization_id: access.organization_id,
+            name: request.name.trim(),
+            recurrence: request.recurrence.trim(),
+            duration_minutes: request.duration_minutes,
+            timezone: request.timezone.trim(),
+            enabled: request.enabled,
+            created_by: Some(access.user_id),
+        },
+    )
+    .await?;
+    organization::record(
+        &pool,
+        AuditRecord::new(
+            AuditScope::Organization(access.organization_id),
+            AuditCategory::Remediation,
+            "maintenance_window_created",
+        )
+        .user(access.user_id)
+        .target(format!("maintenance_window:{}", window.id))
+        .details(json!({ "name": window.name, "recurrence": window.recurrence })),
+    );
+    Ok(Json(window))
+}
+
+pub async fn delete_maintenance_window_handler(
+    Extension(pool): Extension<PgPool>,
+    access: OrgAccess<AdminRole>,
+    Path((_, window_id)): Path<(i32, i64)>,
+) -> AppResult<Json<Value>> {
+    if !delete_window(&pool, access.organization_id, window_id).await? {
+        return Err(AppError::NotFound);
+    }
+    organization::record(
+        &pool,
+        AuditRecord::new(
+            AuditScope::Organization(access.organization_id),
+            AuditCategory::Remediation,
+            "maintenance_window_deleted",
+        )
+        .user(access.user_id)
+        .target(format!("maintenance_window:{window_id}")),
+    );
+    Ok(Json(json!({ "deleted": true })))
+}
+
+pub async fn list_maintenance_overrides_handler(
+    Extension(pool): Extension<PgPool>,
+    access: OrgAccess<ViewerRole>,
+) -> AppResult<Json<Vec<RemediationMaintenanceOverride>>> {
+    let overrides = list_overrides(&pool, access.organization_id).await?;
+    Ok(Json(overrides))
+}
+
+/// Cancels a run before it starts executing, including a scheduled run that has not fired.
+pub async fn cancel_run_handler(
+    Extension(pool): Extension<PgPool>,
+    principal: Principal,
+    Path(run_id): Path<i64>,
+    Json(request): Json<RunCancelRequest>,
+) -> AppResult<Json<RuntimeVmRemediationRun>> {
+    let Some(record) = get_run_by_id(&pool, run_id).await? else {
+        return Err(AppError::NotFound);
+    };
+    if let Principal::Service(service) = &principal {
+        if !service
+            .owns_vm_instance(&pool, record.runtime_vm_instance_id)
+            .await?
+        {
+            return Err(AppError::NotFound);
+        }
+    }
+    let Some(cancelled) = cancel_unstarted_run(&pool, run_id, request.reason.as_deref()).await?
+    else {
+        return Err(AppError::Conflict(format!(
+            "remediation run {run_id} has already started or finished"
+        )));
+    };
+    Ok(Json(cancelled))
+}
+
+/// Lists the instances a run would touch so an approver can judge its blast radius.
+pub async fn run_impact_handler(
+    Extension(pool): Extension<PgPool>,
+    _user: AuthUser,
+    Path(run_id): Path<i64>,
+) -> AppResult<Json<RunImpactPreview>> {
+    let Some(run) = get_run_by_id(&pool, run_id).await? else {
+        return Err(AppError::NotFound);
+    };
+    let playbook = match run.playbook_id {
+        Some(playbook_id) => get_playbook_by_id(&pool, playbook_id).await?,
+        None => get_playbook_by_key(&pool, &run.playbook).await?,
+    };
+    let preview = preview_run_impact(&pool, &run, playbook.as_ref()).await?;
+    Ok(Json(preview))
+}
+
 pub async fn list_artifacts_handler(
     Extension(pool): Extension<PgPool>,
     _user: AuthUser,
@@ -0,0 +1,206 @@
+use crate::config;
+use crate::error::AppError;
+use crate::invocations::{self, INVOCATION_TIMEOUT_HEADER};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+// key: request-limits -> global request timeout and load shedding
+
+/// Paths that bypass both limits so probes and scraping keep working under load.
+const UNLIMITED_PATHS: [&str; 3] = ["/healthz", "/readyz", "/metrics"];
+
+/// Headroom over an invocation's own deadline, so the handler reports and records
+/// the timeout before the global layer cuts the request.
+const INVOCATION_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+pub struct RequestLimits {
+    timeout: Duration,
+    in_flight: Arc<Semaphore>,
+}
+
+impl RequestLimits {
+    pub fn new(timeout: Duration, max_in_flight: usize) -> Self {
+        Self {
+            timeout,
+            in_flight: Arc::new(Semaphore::new(max_in_flight)),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(
+            Duration::from_secs(*config::REQUEST_TIMEOUT_SECS),
+            *config::MAX_IN_FLIGHT_REQUESTS,
+        )
+    }
+
+    /// Invocations may ask for a longer deadline than the global timeout.
+    fn deadline(&self, headers: &HeaderMap) -> Duration {
+        if !headers.contains_key(INVOCATION_TIMEOUT_HEADER) {
+            return self.timeout;
+        }
+        invocations::resolve_timeout(headers)
+            .map(|timeout| self.timeout.max(timeout + INVOCATION_GRACE))
+            .unwrap_or(self.timeout)
+    }
+}
+
+/// Streams (`/stream` routes or `Accept: text/event-stream`) stay open for as long as
+/// the client listens, so they are never timed out.
+fn is_streaming<B>(request: &Request<B>) -> bool {
+    request.uri().path().ends_with("/stream")
+        || request
+            .headers()
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .any(|value| value.contains("text/event-stream"))
+}
+
+/// Sheds requests with 503 once `max_in_flight` are being handled and answers 504
+/// when a handler has not produced a response within the timeout. The permit is held
+/// until the response head is ready; streamed bodies do not count against the limit.
+pub async fn enforce_limits<B>(
+    State(limits): State<RequestLimits>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if UNLIMITED_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    let Ok(_permit) = limits.in_flight.clone().try_acquire_owned() else {
+        let mut response =
+            AppError::ServiceUnavailable("server is at capacity; retry shortly".into())
+                .into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+        return response;
+    };
+    if is_streaming(&request) {
+        return next.run(request).await;
+    }
+    let deadline = limits.deadline(request.headers());
+    match tokio::time::timeout(deadline, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => AppError::GatewayTimeout(format!(
+            "request did not complete within {}ms",
+            deadline.as_millis()
+        ))
+        .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::StatusCode,
+        middleware,
+        response::sse::{Event, Sse},
+        routing::get,
+        Router,
+    };
+    use std::convert::Infallible;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        "done"
+    }
+
+    async fn slow_stream() -> Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>> {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        Sse::new(futures_util::stream::iter([Ok(
+            Event::default().data("tick")
+        )]))
+    }
+
+    fn app(limits: RequestLimits, router: Router) -> Router {
+        router.layer(middleware::from_fn_with_state(limits, enforce_limits))
+    }
+
+    fn get_request(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn slow_handler_times_out_but_streams_do_not() {
+        let limits = RequestLimits::new(Duration::from_millis(50), 8);
+        let router = Router::new()
+            .route("/slow", get(slow))
+            .route("/api/events/stream", get(slow_stream))
+            .route("/events", get(slow_stream));
+
+        let response = app(limits.clone(), router.clone())
+            .oneshot(get_request("/slow"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let response = app(limits.clone(), router.clone())
+            .oneshot(get_request("/api/events/stream"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+
+        let request = Request::builder()
+            .uri("/events")
+            .header(header::ACCEPT, "text/event-stream")
+            .body(Body::empty())
+            .unwrap();
+        let response = app(limits, router).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn saturation_sheds_load() {
+        let release = Arc::new(Notify::new());
+        let entered = Arc::new(Notify::new());
+        let router = Router::new()
+            .route(
+                "/work",
+                get({
+                    let (release, entered) = (release.clone(), entered.clone());
+                    move || async move {
+                        entered.notify_one();
+                        release.notified().await;
+                        "worked"
+                    }
+                }),
+            )
+            .route("/healthz", get(|| async { "ok" }))
+            .route("/readyz", get(|| async { "ready" }));
+        let app = app(RequestLimits::new(Duration::from_secs(5), 1), router);
+
+        let busy = tokio::spawn(app.clone().oneshot(get_request("/work")));
+        entered.notified().await;
+
+        let shed = app.clone().oneshot(get_request("/work")).await.unwrap();
+        assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(shed.headers()[header::RETRY_AFTER], "1");
+        let live = app.clone().oneshot(get_request("/healthz")).await.unwrap();
+        assert_eq!(live.status(), StatusCode::OK);
+        let ready = app.clone().oneshot(get_request("/readyz")).await.unwrap();
+        assert_eq!(ready.status(), StatusCode::OK);
+
+        release.notify_one();
+        assert_eq!(busy.await.unwrap().unwrap().status(), StatusCode::OK);
+        let after = app.oneshot(get_request("/work"));
+        release.notify_one();
+        assert_eq!(after.await.unwrap().status(), StatusCode::OK);
+    }
+}
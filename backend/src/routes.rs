@@ -1,13 +1,13 @@
 use axum::{
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 
 use crate::{
-    auth, billing, capabilities, domains, evaluation, file_store, governance, ingestion,
-    intelligence, invocations, keys_api, lifecycle_console, marketplace, organizations, policy,
-    promotions, remediation_api, secrets, servers, services, trust, vector_dbs, webhooks,
-    workflows,
+    artifacts, audit, auth, billing, build, capabilities, config, domains, evaluation, file_store,
+    governance, ingestion, intelligence, invocations, keys_api, lifecycle_console, marketplace,
+    organizations, policy, probes, promotions, proxy, remediation_api, secrets, servers, services,
+    trust, vector_dbs, webhooks, workflows,
 };
 
 pub fn api_routes() -> Router {
@@ -21,11 +21,45 @@ pub fn api_routes() -> Router {
             "/api/console/lifecycle/stream",
             get(lifecycle_console::stream_snapshots),
         )
+        .route(
+            "/api/console/lifecycle/workspaces/:workspace_id/archive",
+            post(lifecycle_console::archive::archive_workspace),
+        )
+        .route(
+            "/api/console/lifecycle/archive/:workspace_id",
+            get(lifecycle_console::archive::get_archived_workspace)
+                .delete(lifecycle_console::archive::restore_workspace),
+        )
         .route("/api/register", post(auth::register_user))
         .route("/api/login", post(auth::login_user))
         .route("/api/logout", post(auth::logout_user))
         .route("/api/me", get(auth::current_user))
+        .route(
+            "/api/admin/migrations/status",
+            get(probes::migrations::migration_status),
+        )
+        .route(
+            "/api/admin/startup-report",
+            get(probes::startup::startup_report),
+        )
+        .route("/api/admin/registry/gc", post(build::gc::run_registry_gc))
+        .route(
+            "/api/admin/config/effective",
+            get(config::reload::effective_config),
+        )
         .route("/api/webhooks/billing", post(webhooks::billing_webhook))
+        .route(
+            "/api/webhooks/endpoints",
+            get(webhooks::dispatch::list_endpoints).post(webhooks::dispatch::create_endpoint),
+        )
+        .route(
+            "/api/webhooks/endpoints/:id",
+            delete(webhooks::dispatch::delete_endpoint),
+        )
+        .route(
+            "/api/webhooks/endpoints/:id/deliveries",
+            get(webhooks::dispatch::list_deliveries),
+        )
         .route(
             "/api/billing/catalog",
             get(billing::billing_list_plan_catalog),
@@ -39,6 +73,14 @@ pub fn api_routes() -> Router {
             "/api/billing/organizations/:organization_id/quotas/check",
             post(billing::billing_check_quota),
         )
+        .route(
+            "/api/organizations/:id/usage/export",
+            get(billing::billing_export_usage),
+        )
+        .route(
+            "/api/organizations/:id/audit",
+            get(audit::organization::list_organization_audit),
+        )
         .route(
             "/api/servers",
             get(servers::list_servers).post(servers::create_server),
@@ -48,7 +90,26 @@ pub fn api_routes() -> Router {
         .route("/api/servers/:id/redeploy", post(servers::redeploy_server))
         .route("/api/servers/:id/webhook", post(servers::webhook_redeploy))
         .route("/api/servers/:id/github", post(servers::github_webhook))
+        .route("/api/servers/:id/restore", post(servers::restore_server))
+        .route("/api/servers/:id/config", put(servers::update_server_config))
         .route("/api/servers/:id/invoke", post(servers::invoke_server))
+        .route(
+            "/api/servers/:id/invoke/stream",
+            post(servers::invoke_server_stream),
+        )
+        .route("/api/servers/:id/health", get(servers::server_health))
+        .route(
+            "/api/servers/:id/proxy-limits",
+            put(servers::update_proxy_limits),
+        )
+        .route(
+            "/api/servers/:id/proxy-transforms",
+            put(servers::update_proxy_transforms),
+        )
+        .route(
+            "/api/servers/:id/invocation-cache",
+            put(servers::update_invocation_cache),
+        )
         .route("/api/servers/:id/manifest", get(servers::get_manifest))
         .route("/api/servers/:id/vm", get(servers::vm_runtime_details))
         .route(
@@ -59,8 +120,20 @@ pub fn api_routes() -> Router {
             "/api/servers/:id/capabilities",
             get(capabilities::list_capabilities),
         )
+        .route(
+            "/api/servers/:id/capabilities/reconciled",
+            get(capabilities::reconciled_capabilities),
+        )
+        .route(
+            "/api/capabilities/:capability/invoke",
+            post(proxy::routing::invoke_capability),
+        )
         .route("/api/servers/:id", delete(servers::delete_server))
         .route("/api/servers/:id/logs", get(servers::server_logs))
+        .route(
+            "/api/runtime/placement/preview",
+            post(servers::preview_placement),
+        )
         .route("/api/servers/:id/logs/history", get(servers::stored_logs))
         .route("/api/servers/:id/logs/stream", get(servers::stream_logs))
         .route(
@@ -80,6 +153,15 @@ pub fn api_routes() -> Router {
             "/api/servers/:id/services/:service_id",
             patch(services::update_service).delete(services::delete_service),
         )
+        .route(
+            "/api/servers/:id/services/:service_id/health",
+            put(services::report_service_health),
+        )
+        .route(
+            "/api/servers/:id/services/:service_id/dependencies",
+            put(services::set_service_dependencies),
+        )
+        .route("/api/services/health", get(services::services_health))
         .route(
             "/api/servers/:id/secrets",
             get(secrets::list_secrets).post(secrets::create_secret),
@@ -90,6 +172,10 @@ pub fn api_routes() -> Router {
                 .patch(secrets::update_secret)
                 .delete(secrets::delete_secret),
         )
+        .route(
+            "/api/secrets/:name/versions",
+            get(secrets::list_secret_versions),
+        )
         .route(
             "/api/servers/:id/domains",
             get(domains::list_domains).post(domains::create_domain),
@@ -98,6 +184,14 @@ pub fn api_routes() -> Router {
             "/api/servers/:id/domains/:domain_id",
             delete(domains::delete_domain),
         )
+        .route(
+            "/api/domains/:id/verify/initiate",
+            post(domains::initiate_verification),
+        )
+        .route(
+            "/api/domains/:id/verify/check",
+            post(domains::check_verification),
+        )
         .route(
             "/api/servers/:id/files",
             get(file_store::list_files).post(file_store::upload_file),
@@ -111,6 +205,10 @@ pub fn api_routes() -> Router {
             get(vector_dbs::list_vector_dbs).post(vector_dbs::create_vector_db),
         )
         .route("/api/vector-dbs/:id", delete(vector_dbs::delete_vector_db))
+        .route(
+            "/api/vector-dbs/:id/search",
+            post(vector_dbs::search_vector_db),
+        )
         .route(
             "/api/vector-dbs/:id/residency-policies",
             get(vector_dbs::list_vector_db_residency_policies)
@@ -138,10 +236,15 @@ pub fn api_routes() -> Router {
             get(ingestion::list_jobs).post(ingestion::create_job),
         )
         .route("/api/ingestion-jobs/:id", delete(ingestion::delete_job))
+        .route("/api/ingestion-jobs/:id/run", post(ingestion::run_job))
         .route(
             "/api/servers/:id/invocations",
             get(invocations::list_invocations),
         )
+        .route(
+            "/api/invocations/batch",
+            post(invocations::batch::invoke_batch),
+        )
         .route(
             "/api/servers/:id/eval/tests",
             get(evaluation::list_tests).post(evaluation::create_test),
@@ -159,6 +262,10 @@ pub fn api_routes() -> Router {
             "/api/artifacts/:id/evaluations",
             get(evaluation::list_certifications).post(evaluation::submit_certification),
         )
+        .route(
+            "/api/artifacts/:id/attestation",
+            get(artifacts::attestation::get_attestation),
+        )
         .route("/api/evaluations", get(evaluation::list_all_results))
         .route(
             "/api/evaluations/:id/retry",
@@ -172,12 +279,25 @@ pub fn api_routes() -> Router {
             "/api/evaluations/:id/lineage",
             get(evaluation::certification_lineage),
         )
+        .route(
+            "/api/evaluations/:id/schedule",
+            put(evaluation::set_certification_schedule),
+        )
         .route("/api/evaluations/summary", get(evaluation::scores_summary))
+        .route(
+            "/api/evaluations/runs/compare",
+            get(evaluation::compare_runs),
+        )
         .route("/api/trust/registry", get(trust::list_registry_states))
+        .route("/api/trust/replay", post(trust::replay::replay_trust_events))
         .route(
             "/api/trust/registry/stream",
             get(trust::stream_trust_events),
         )
+        .route(
+            "/api/trust/registry/by-server/:server_id",
+            get(trust::get_registry_states_by_server),
+        )
         .route(
             "/api/trust/registry/:instance_id",
             get(trust::get_registry_state),
@@ -194,6 +314,10 @@ pub fn api_routes() -> Router {
             "/api/trust/remediation/playbooks",
             get(remediation_api::list_all_playbooks).post(remediation_api::create_playbook_handler),
         )
+        .route(
+            "/api/trust/remediation/playbooks/validate",
+            post(remediation_api::validate_playbook_handler),
+        )
         .route(
             "/api/trust/remediation/playbooks/:playbook_id",
             get(remediation_api::get_playbook_handler)
@@ -209,6 +333,14 @@ pub fn api_routes() -> Router {
             "/api/trust/remediation/workspaces/:workspace_id",
             get(remediation_api::get_workspace_handler),
         )
+        .route(
+            "/api/trust/remediation/workspaces/:workspace_id/clone",
+            post(remediation_api::clone_workspace_handler),
+        )
+        .route(
+            "/api/trust/remediation/workspaces/:workspace_id/lineage",
+            get(remediation_api::get_workspace_lineage_handler),
+        )
         .route(
             "/api/trust/remediation/workspaces/:workspace_id/revisions",
             post(remediation_api::create_workspace_revision_handler),
@@ -229,6 +361,10 @@ pub fn api_routes() -> Router {
             "/api/trust/remediation/workspaces/:workspace_id/revisions/:revision_id/promotion",
             post(remediation_api::apply_workspace_promotion_handler),
         )
+        .route(
+            "/api/trust/remediation/workspaces/:workspace_id/revisions/:revision_id/promotion/preview",
+            post(remediation_api::preview_workspace_promotion_handler),
+        )
         .route(
             "/api/trust/remediation/runs",
             get(remediation_api::list_runs_handler).post(remediation_api::enqueue_run_handler),
@@ -241,15 +377,44 @@ pub fn api_routes() -> Router {
             "/api/trust/remediation/runs/:run_id/approval",
             post(remediation_api::update_approval_handler),
         )
+        .route(
+            "/api/trust/remediation/organizations/:organization_id/maintenance-windows",
+            get(remediation_api::list_maintenance_windows_handler)
+                .post(remediation_api::create_maintenance_window_handler),
+        )
+        .route(
+            "/api/trust/remediation/organizations/:organization_id/maintenance-windows/:window_id",
+            delete(remediation_api::delete_maintenance_window_handler),
+        )
+        .route(
+            "/api/trust/remediation/organizations/:organization_id/maintenance-overrides",
+            get(remediation_api::list_maintenance_overrides_handler),
+        )
+        .route(
+            "/api/trust/remediation/runs/:run_id/cancel",
+            post(remediation_api::cancel_run_handler),
+        )
+        .route(
+            "/api/trust/remediation/runs/:run_id/impact",
+            get(remediation_api::run_impact_handler),
+        )
         .route(
             "/api/trust/remediation/runs/:run_id/artifacts",
             get(remediation_api::list_artifacts_handler),
         )
+        .route(
+            "/api/trust/remediation/overrides",
+            get(lifecycle_console::export_manual_overrides),
+        )
         .route(
             "/api/trust/remediation/stream",
             get(remediation_api::stream_remediation_events),
         )
         .route("/api/policy/stream", get(policy::stream_policy_events))
+        .route(
+            "/api/runtime/policy/decisions",
+            get(policy::list_policy_decisions),
+        )
         .merge(keys_api::routes())
         .merge(governance::routes())
         .merge(promotions::routes())
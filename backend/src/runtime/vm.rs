@@ -1,3 +1,4 @@
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -13,12 +14,16 @@ use tokio::sync::mpsc::Receiver;
 
 use crate::policy::trust::{persist_vm_attestation_outcome, remediation_notes_for_status};
 use crate::policy::{publish_policy_event, PolicyDecision, PolicyEvent, RuntimeBackend};
+use crate::runtime::ResourceUsage;
 use crate::servers::{add_metric, set_status};
 
 pub mod attestation;
 pub mod libvirt;
 
-pub use attestation::{AttestationStatus, AttestationVerifier, TpmAttestationVerifier};
+pub use attestation::{
+    decode_trust_roots, AttestationKind, AttestationStatus, AttestationVerifier,
+    AttestationVerifierRegistry, SevSnpAttestationVerifier, TpmAttestationVerifier,
+};
 
 // key: runtime-vm-executor -> attestation,policy-hooks
 
@@ -101,6 +106,44 @@ pub trait VmProvisioner: Send + Sync {
     async fn fetch_logs(&self, instance_id: &str) -> Result<String>;
 
     async fn stream_logs(&self, instance_id: &str) -> Result<Option<Receiver<String>>>;
+
+    /// Resource usage of the instance so far; `Ok(None)` when the hypervisor cannot report it.
+    async fn resource_usage(&self, _instance_id: &str) -> Result<Option<ResourceUsage>> {
+        Ok(None)
+    }
+
+    /// Captures a point-in-time snapshot of the instance and returns its identifier.
+    async fn snapshot(&self, instance_id: &str) -> Result<String> {
+        Err(anyhow::anyhow!(
+            "vm provisioner does not support snapshots (instance {instance_id})"
+        ))
+    }
+
+    /// Rolls the instance back to a snapshot previously returned by [`VmProvisioner::snapshot`].
+    async fn restore(&self, instance_id: &str, snapshot_id: &str) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "vm provisioner does not support restoring snapshot {snapshot_id} (instance {instance_id})"
+        ))
+    }
+}
+
+/// Result of work run under [`VirtualMachineExecutor::run_with_snapshot`].
+#[derive(Debug)]
+pub struct VmSnapshotGuarded<T> {
+    pub output: T,
+    pub snapshot_id: String,
+    /// `None` when the work succeeded and no rollback was attempted.
+    pub restore: Option<Result<(), String>>,
+}
+
+impl<T> VmSnapshotGuarded<T> {
+    pub fn metadata(&self) -> Value {
+        json!({
+            "snapshot_id": self.snapshot_id,
+            "restored": matches!(self.restore, Some(Ok(()))),
+            "restore_error": self.restore.as_ref().and_then(|outcome| outcome.as_ref().err()),
+        })
+    }
 }
 
 pub struct VirtualMachineExecutor {
@@ -122,6 +165,46 @@ impl VirtualMachineExecutor {
         }
     }
 
+    /// Snapshots `instance_id`, awaits `work`, and restores the snapshot when `failed` reports
+    /// the output as a failure. Errors only when the snapshot itself cannot be taken, in which
+    /// case `work` is never started.
+    pub async fn run_with_snapshot<T, Fut>(
+        &self,
+        instance_id: &str,
+        work: Fut,
+        failed: impl FnOnce(&T) -> bool,
+    ) -> Result<VmSnapshotGuarded<T>>
+    where
+        Fut: Future<Output = T>,
+    {
+        let snapshot_id = self
+            .provisioner
+            .snapshot(instance_id)
+            .await
+            .with_context(|| format!("failed to snapshot vm instance {instance_id}"))?;
+
+        let output = work.await;
+        let restore = if failed(&output) {
+            let outcome = self
+                .provisioner
+                .restore(instance_id, &snapshot_id)
+                .await
+                .map_err(|err| format!("{err:#}"));
+            if let Err(err) = &outcome {
+                tracing::error!(%instance_id, %snapshot_id, error = %err, "failed to restore vm snapshot");
+            }
+            Some(outcome)
+        } else {
+            None
+        };
+
+        Ok(VmSnapshotGuarded {
+            output,
+            snapshot_id,
+            restore,
+        })
+    }
+
     pub fn descriptor() -> crate::policy::RuntimeExecutorDescriptor {
         crate::policy::RuntimeExecutorDescriptor::new(
             RuntimeBackend::VirtualMachine,
@@ -655,6 +738,24 @@ impl crate::runtime::RuntimeExecutor for VirtualMachineExecutor {
         });
         Some(rx)
     }
+
+    async fn resource_usage(&self, server_id: i32) -> Option<ResourceUsage> {
+        let instance = match self.active_instance_for(server_id).await {
+            Ok(Some(instance)) => instance,
+            Ok(None) => return None,
+            Err(err) => {
+                tracing::warn!(?err, %server_id, "failed to locate vm instance for resource usage");
+                return None;
+            }
+        };
+        match self.provisioner.resource_usage(&instance.instance_id).await {
+            Ok(usage) => usage,
+            Err(err) => {
+                tracing::warn!(?err, %server_id, "failed to read vm resource usage");
+                None
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
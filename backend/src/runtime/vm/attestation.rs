@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
@@ -12,6 +13,33 @@ use serde_json::{json, Map, Value};
 
 use crate::policy::PolicyDecision;
 
+/// Decodes base64 Ed25519 trust roots. Entries that are not valid keys come back as
+/// rejection messages rather than failing the whole set.
+pub fn decode_trust_roots<'a>(
+    encoded: impl IntoIterator<Item = &'a String>,
+) -> (Vec<PublicKey>, Vec<String>) {
+    let mut keys = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, value) in encoded.into_iter().enumerate() {
+        match Base64Engine.decode(value) {
+            Ok(bytes) if bytes.len() == 32 => {
+                let mut key_bytes = [0u8; 32];
+                key_bytes.copy_from_slice(&bytes);
+                match PublicKey::from_bytes(&key_bytes) {
+                    Ok(key) => keys.push(key),
+                    Err(err) => rejected.push(format!("trust root {index}: {err}")),
+                }
+            }
+            Ok(bytes) => rejected.push(format!(
+                "trust root {index}: expected 32 bytes, got {}",
+                bytes.len()
+            )),
+            Err(err) => rejected.push(format!("trust root {index}: {err}")),
+        }
+    }
+    (keys, rejected)
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AttestationStatus {
     Trusted,
@@ -75,7 +103,7 @@ impl AttestationOutcome {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum AttestationKind {
     Tpm,
     AmdSevSnp,
@@ -92,6 +120,32 @@ impl AttestationKind {
             AttestationKind::Unknown => "unknown",
         }
     }
+
+    /// Parses an evidence `format` discriminator (the `as_str` spelling).
+    pub fn from_format(format: &str) -> Option<Self> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "tpm" => Some(AttestationKind::Tpm),
+            "amd-sev-snp" => Some(AttestationKind::AmdSevSnp),
+            "intel-tdx" => Some(AttestationKind::IntelTdx),
+            _ => None,
+        }
+    }
+}
+
+/// Evidence field naming its format explicitly, e.g. `{"format": "amd-sev-snp", ...}`.
+pub const EVIDENCE_FORMAT_FIELD: &str = "format";
+
+/// Resolves the evidence format from the explicit `format` discriminator, falling back
+/// to [`detect_kind`] for producers that predate it. An unrecognised discriminator is
+/// returned as `Err` with the offending value.
+pub fn evidence_format(evidence: &Value) -> std::result::Result<AttestationKind, String> {
+    match evidence.get(EVIDENCE_FORMAT_FIELD) {
+        Some(Value::String(format)) => {
+            AttestationKind::from_format(format).ok_or_else(|| format.clone())
+        }
+        Some(other) => Err(other.to_string()),
+        None => Ok(detect_kind(evidence)),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -254,6 +308,31 @@ fn normalize_tdx(evidence: &Value) -> Result<NormalizedAttestation> {
     })
 }
 
+/// Default allowance for clock drift between hosts and the control plane.
+pub const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// Checks an evidence timestamp against the freshness window, widened on both ends by
+/// `clock_skew`: evidence up to `clock_skew` in the future is accepted, as is evidence up
+/// to `max_age + clock_skew` old. Returns the rejection note otherwise.
+pub fn check_evidence_timestamp(
+    timestamp: DateTime<Utc>,
+    now: DateTime<Utc>,
+    max_age: ChronoDuration,
+    clock_skew: ChronoDuration,
+) -> Option<&'static str> {
+    if timestamp > now + clock_skew {
+        Some("attestation:not-yet-valid")
+    } else if now - timestamp > max_age + clock_skew {
+        Some("attestation:stale")
+    } else {
+        None
+    }
+}
+
+fn chrono_clock_skew(clock_skew: Duration) -> ChronoDuration {
+    ChronoDuration::from_std(clock_skew).unwrap_or_else(|_| ChronoDuration::seconds(30))
+}
+
 #[async_trait]
 pub trait AttestationVerifier: Send + Sync {
     async fn verify(
@@ -269,6 +348,7 @@ pub struct TpmAttestationVerifier {
     trusted_measurements: HashSet<String>,
     trust_roots: Vec<PublicKey>,
     max_age: Duration,
+    clock_skew: Duration,
 }
 
 impl TpmAttestationVerifier {
@@ -281,9 +361,15 @@ impl TpmAttestationVerifier {
             trusted_measurements,
             trust_roots,
             max_age,
+            clock_skew: DEFAULT_CLOCK_SKEW,
         }
     }
 
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+
     fn parse_signature(value: &Value) -> Result<Signature> {
         let signature_b64 = value
             .as_str()
@@ -371,11 +457,16 @@ impl AttestationVerifier for TpmAttestationVerifier {
                     .timestamp
                     .context("missing attestation timestamp")?;
 
-                if Utc::now() - timestamp > max_age {
+                if let Some(note) = check_evidence_timestamp(
+                    timestamp,
+                    Utc::now(),
+                    max_age,
+                    chrono_clock_skew(self.clock_skew),
+                ) {
                     return Ok(AttestationOutcome::untrusted(
                         AttestationKind::Tpm,
                         Some(evidence.clone()),
-                        vec!["attestation:stale".to_string()],
+                        vec![note.to_string()],
                     ));
                 }
 
@@ -442,6 +533,7 @@ impl AttestationVerifier for TpmAttestationVerifier {
                     normalized,
                     &self.trusted_measurements,
                     max_age,
+                    chrono_clock_skew(self.clock_skew),
                 ))
             }
             AttestationKind::Unknown => Ok(unsupported_attestation(Some(evidence.clone()))),
@@ -449,6 +541,126 @@ impl AttestationVerifier for TpmAttestationVerifier {
     }
 }
 
+/// Verifies AMD SEV-SNP attestation reports against the measurement allowlist and
+/// freshness window. The report's VCEK signature chain is not checked yet.
+pub struct SevSnpAttestationVerifier {
+    trusted_measurements: HashSet<String>,
+    max_age: Duration,
+    clock_skew: Duration,
+}
+
+impl SevSnpAttestationVerifier {
+    pub fn new(trusted_measurements: HashSet<String>, max_age: Duration) -> Self {
+        Self {
+            trusted_measurements,
+            max_age,
+            clock_skew: DEFAULT_CLOCK_SKEW,
+        }
+    }
+
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+}
+
+#[async_trait]
+impl AttestationVerifier for SevSnpAttestationVerifier {
+    async fn verify(
+        &self,
+        _server_id: i32,
+        decision: &PolicyDecision,
+        provisioning: &crate::runtime::vm::VmProvisioningResult,
+        _config: Option<&Value>,
+    ) -> Result<AttestationOutcome> {
+        let Some(evidence) = provisioning.attestation_evidence.as_ref() else {
+            return Ok(AttestationOutcome::untrusted(
+                AttestationKind::AmdSevSnp,
+                None,
+                vec!["attestation:missing-evidence".to_string()],
+            ));
+        };
+        let normalized = normalize_sev(evidence)?;
+        let max_age =
+            ChronoDuration::from_std(self.max_age).unwrap_or_else(|_| ChronoDuration::minutes(5));
+        Ok(sev_outcome_from_normalized(
+            decision,
+            normalized,
+            &self.trusted_measurements,
+            max_age,
+            chrono_clock_skew(self.clock_skew),
+        ))
+    }
+}
+
+/// Routes evidence to the verifier registered for its format, so hosts with different
+/// attestation hardware can share one executor. Evidence in a format with no registered
+/// verifier is rejected as untrusted.
+#[derive(Default, Clone)]
+pub struct AttestationVerifierRegistry {
+    verifiers: HashMap<AttestationKind, Arc<dyn AttestationVerifier>>,
+}
+
+impl AttestationVerifierRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        mut self,
+        kind: AttestationKind,
+        verifier: Arc<dyn AttestationVerifier>,
+    ) -> Self {
+        self.verifiers.insert(kind, verifier);
+        self
+    }
+
+    pub fn formats(&self) -> Vec<AttestationKind> {
+        self.verifiers.keys().copied().collect()
+    }
+}
+
+#[async_trait]
+impl AttestationVerifier for AttestationVerifierRegistry {
+    async fn verify(
+        &self,
+        server_id: i32,
+        decision: &PolicyDecision,
+        provisioning: &crate::runtime::vm::VmProvisioningResult,
+        config: Option<&Value>,
+    ) -> Result<AttestationOutcome> {
+        let Some(evidence) = provisioning.attestation_evidence.as_ref() else {
+            return Ok(AttestationOutcome::untrusted(
+                AttestationKind::Unknown,
+                None,
+                vec!["attestation:missing-evidence".to_string()],
+            ));
+        };
+        let kind = match evidence_format(evidence) {
+            Ok(kind) => kind,
+            Err(format) => {
+                return Ok(AttestationOutcome::untrusted(
+                    AttestationKind::Unknown,
+                    Some(evidence.clone()),
+                    vec![format!("attestation:unsupported-format:{format}")],
+                ))
+            }
+        };
+        match self.verifiers.get(&kind) {
+            Some(verifier) => {
+                verifier
+                    .verify(server_id, decision, provisioning, config)
+                    .await
+            }
+            None => Ok(AttestationOutcome::untrusted(
+                kind,
+                Some(evidence.clone()),
+                vec![format!("attestation:unsupported-format:{}", kind.as_str())],
+            )),
+        }
+    }
+}
+
 pub fn unsupported_attestation(evidence: Option<Value>) -> AttestationOutcome {
     let kind = evidence
         .as_ref()
@@ -465,6 +677,7 @@ pub fn sev_outcome_from_normalized(
     normalized: NormalizedAttestation,
     trusted_measurements: &HashSet<String>,
     max_age: ChronoDuration,
+    clock_skew: ChronoDuration,
 ) -> AttestationOutcome {
     let measurement = match normalized.measurement.as_ref() {
         Some(measurement) => measurement.clone(),
@@ -486,11 +699,11 @@ pub fn sev_outcome_from_normalized(
     }
 
     if let Some(timestamp) = normalized.timestamp {
-        if Utc::now() - timestamp > max_age {
+        if let Some(note) = check_evidence_timestamp(timestamp, Utc::now(), max_age, clock_skew) {
             return AttestationOutcome::untrusted(
                 normalized.kind,
                 Some(evidence_payload(&normalized)),
-                vec!["attestation:stale".to_string()],
+                vec![note.to_string()],
             );
         }
     }
@@ -534,4 +747,66 @@ mod tests {
         assert_eq!(AttestationStatus::Untrusted.as_str(), "untrusted");
         assert_eq!(AttestationStatus::Unknown.as_str(), "unknown");
     }
+
+    #[test]
+    fn clock_skew_widens_both_ends_of_the_freshness_window() {
+        let now = Utc::now();
+        let max_age = ChronoDuration::minutes(5);
+        let skew = ChronoDuration::seconds(30);
+
+        let slightly_ahead = now + ChronoDuration::seconds(10);
+        assert_eq!(
+            check_evidence_timestamp(slightly_ahead, now, max_age, skew),
+            None
+        );
+        let far_ahead = now + ChronoDuration::seconds(45);
+        assert_eq!(
+            check_evidence_timestamp(far_ahead, now, max_age, skew),
+            Some("attestation:not-yet-valid")
+        );
+        // without tolerance even a small lead is rejected
+        assert_eq!(
+            check_evidence_timestamp(slightly_ahead, now, max_age, ChronoDuration::zero()),
+            Some("attestation:not-yet-valid")
+        );
+
+        let just_expired = now - max_age - ChronoDuration::seconds(10);
+        assert_eq!(
+            check_evidence_timestamp(just_expired, now, max_age, skew),
+            None
+        );
+        let expired = now - max_age - ChronoDuration::seconds(45);
+        assert_eq!(
+            check_evidence_timestamp(expired, now, max_age, skew),
+            Some("attestation:stale")
+        );
+    }
+
+    #[test]
+    fn trust_roots_decode_independently() {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let valid = Base64Engine.encode(PublicKey::from(&secret).as_bytes());
+        let short = Base64Engine.encode([1u8; 16]);
+        let entries = vec![valid, short, "not base64!".to_string()];
+
+        let (keys, rejected) = decode_trust_roots(&entries);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(rejected.len(), 2);
+        assert_eq!(rejected[0], "trust root 1: expected 32 bytes, got 16");
+        assert!(rejected[1].starts_with("trust root 2:"));
+    }
+
+    #[test]
+    fn explicit_format_overrides_detection() {
+        let evidence = json!({"format": "amd-sev-snp", "quote": {}});
+        assert_eq!(evidence_format(&evidence), Ok(AttestationKind::AmdSevSnp));
+        assert_eq!(
+            evidence_format(&json!({"quote": {}})),
+            Ok(AttestationKind::Tpm)
+        );
+        assert_eq!(
+            evidence_format(&json!({"format": "arm-cca"})),
+            Err("arm-cca".to_string())
+        );
+    }
 }
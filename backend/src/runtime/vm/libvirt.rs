@@ -15,6 +15,7 @@ use tokio::task::spawn_blocking;
 
 use super::{HypervisorSnapshot, VmProvisioner, VmProvisioningResult};
 use crate::policy::PolicyDecision;
+use crate::runtime::ResourceUsage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LibvirtAuthConfig {
@@ -32,6 +33,13 @@ impl LibvirtAuthConfig {
     }
 }
 
+/// Named vCPU/memory sizing applied to a domain, e.g. `small`, `medium`, `large`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibvirtResourceProfile {
+    pub vcpu_count: u32,
+    pub memory_mib: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct LibvirtProvisioningConfig {
     pub connection_uri: String,
@@ -39,6 +47,8 @@ pub struct LibvirtProvisioningConfig {
     pub default_isolation_tier: Option<String>,
     pub default_memory_mib: u64,
     pub default_vcpu_count: u32,
+    pub resource_profiles: HashMap<String, LibvirtResourceProfile>,
+    pub default_resource_profile: String,
     pub log_tail: usize,
     pub network_template: Value,
     pub volume_template: Value,
@@ -50,12 +60,36 @@ impl LibvirtProvisioningConfig {
     pub fn sanitized_snapshot(&self) -> Option<Value> {
         self.auth.as_ref().map(|auth| auth.snapshot())
     }
+
+    /// Resolves `requested` (or the default profile when `None`) against the configured
+    /// profiles, failing with the list of known names when it is not defined.
+    pub fn resolve_resource_profile(
+        &self,
+        requested: Option<&str>,
+    ) -> Result<(String, LibvirtResourceProfile)> {
+        let name = requested
+            .map(str::trim)
+            .unwrap_or(self.default_resource_profile.as_str());
+        match self.resource_profiles.get(name) {
+            Some(profile) => Ok((name.to_string(), *profile)),
+            None => {
+                let mut known: Vec<&str> =
+                    self.resource_profiles.keys().map(String::as_str).collect();
+                known.sort_unstable();
+                Err(anyhow!(
+                    "unknown libvirt resource profile '{name}' (configured: {})",
+                    known.join(", ")
+                ))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct LibvirtProvisionSpec {
     pub domain_name: String,
     pub image: String,
+    pub resource_profile: String,
     pub memory_mib: u64,
     pub vcpu_count: u32,
     pub network_template: Value,
@@ -69,15 +103,7 @@ pub struct LibvirtProvisionSpec {
 impl LibvirtProvisionSpec {
     #[cfg(not(feature = "libvirt-executor"))]
     fn mark_unused_fields(&self) {
-        let _ = (
-            self.memory_mib,
-            self.vcpu_count,
-            &self.network_template,
-            &self.volume_template,
-            &self.gpu_policy,
-            &self.user_config,
-            &self.attestation_hint,
-        );
+        let _ = (&self.user_config, &self.attestation_hint);
     }
 }
 
@@ -104,6 +130,15 @@ pub trait LibvirtDriver: Send + Sync {
     async fn fetch_console(&self, name: &str, tail: usize) -> Result<String>;
 
     async fn stream_console(&self, name: &str) -> Result<Option<Receiver<String>>>;
+
+    /// Creates a snapshot of the domain and returns the snapshot name.
+    async fn snapshot_domain(&self, name: &str) -> Result<String>;
+
+    async fn revert_domain(&self, name: &str, snapshot: &str) -> Result<()>;
+
+    async fn domain_usage(&self, _name: &str) -> Result<Option<ResourceUsage>> {
+        Ok(None)
+    }
 }
 
 pub struct LibvirtVmProvisioner {
@@ -122,15 +157,19 @@ impl LibvirtVmProvisioner {
         server_id: i32,
         decision: &PolicyDecision,
         config: Option<&Value>,
-    ) -> (LibvirtProvisionSpec, HypervisorSnapshot) {
+    ) -> Result<(LibvirtProvisionSpec, HypervisorSnapshot)> {
         let vm_overrides = config.and_then(|cfg| cfg.get("vm"));
+        let (resource_profile, profile) = self.config.resolve_resource_profile(
+            vm_overrides.and_then(|vm| vm.get("resource_profile").and_then(|value| value.as_str())),
+        )?;
+        // explicit sizing still wins over the profile it was requested alongside
         let memory_mib = vm_overrides
             .and_then(|vm| vm.get("memory_mib").and_then(|value| value.as_u64()))
-            .unwrap_or(self.config.default_memory_mib);
+            .unwrap_or(profile.memory_mib);
         let vcpu_count = vm_overrides
             .and_then(|vm| vm.get("vcpu_count").and_then(|value| value.as_u64()))
             .map(|value| value as u32)
-            .unwrap_or(self.config.default_vcpu_count);
+            .unwrap_or(profile.vcpu_count);
 
         let image = vm_overrides
             .and_then(|vm| vm.get("image").and_then(|value| value.as_str()))
@@ -158,6 +197,7 @@ impl LibvirtVmProvisioner {
         let spec = LibvirtProvisionSpec {
             domain_name,
             image,
+            resource_profile,
             memory_mib,
             vcpu_count,
             network_template: self.config.network_template.clone(),
@@ -171,10 +211,21 @@ impl LibvirtVmProvisioner {
         #[cfg(not(feature = "libvirt-executor"))]
         spec.mark_unused_fields();
 
-        #[cfg(not(feature = "libvirt-executor"))]
-        let _ = &self.config.console_source;
+        Ok((spec, snapshot))
+    }
 
-        (spec, snapshot)
+    /// Domain definition the provisioner would submit for `server_id`, without creating it.
+    pub fn render_domain_xml(
+        &self,
+        server_id: i32,
+        decision: &PolicyDecision,
+        config: Option<&Value>,
+    ) -> Result<String> {
+        let (spec, _) = self.plan_spec(server_id, decision, config)?;
+        Ok(build_domain_xml(
+            &spec,
+            self.config.console_source.as_deref(),
+        ))
     }
 }
 
@@ -186,7 +237,7 @@ impl VmProvisioner for LibvirtVmProvisioner {
         decision: &PolicyDecision,
         config: Option<&Value>,
     ) -> Result<VmProvisioningResult> {
-        let (spec, snapshot) = self.plan_spec(server_id, decision, config);
+        let (spec, snapshot) = self.plan_spec(server_id, decision, config)?;
         let provisioned = self.driver.provision_domain(&spec).await?;
 
         let isolation_tier = provisioned
@@ -231,6 +282,105 @@ impl VmProvisioner for LibvirtVmProvisioner {
     async fn stream_logs(&self, instance_id: &str) -> Result<Option<Receiver<String>>> {
         self.driver.stream_console(instance_id).await
     }
+
+    async fn snapshot(&self, instance_id: &str) -> Result<String> {
+        self.driver.snapshot_domain(instance_id).await
+    }
+
+    async fn restore(&self, instance_id: &str, snapshot_id: &str) -> Result<()> {
+        self.driver.revert_domain(instance_id, snapshot_id).await
+    }
+
+    async fn resource_usage(&self, instance_id: &str) -> Result<Option<ResourceUsage>> {
+        self.driver.domain_usage(instance_id).await
+    }
+}
+
+/// Renders the libvirt domain definition for `spec`; the console defaults to `pty`.
+pub fn build_domain_xml(spec: &LibvirtProvisionSpec, console_source: Option<&str>) -> String {
+    let disk_path = spec
+        .volume_template
+        .get("path")
+        .and_then(|value| value.as_str())
+        .unwrap_or("/var/lib/libvirt/images/mcp.qcow2");
+    let disk_driver = spec
+        .volume_template
+        .get("driver")
+        .and_then(|value| value.as_str())
+        .unwrap_or("qcow2");
+    let target_dev = spec
+        .volume_template
+        .get("target_dev")
+        .and_then(|value| value.as_str())
+        .unwrap_or("vda");
+    let target_bus = spec
+        .volume_template
+        .get("target_bus")
+        .and_then(|value| value.as_str())
+        .unwrap_or("virtio");
+    let network_name = spec
+        .network_template
+        .get("name")
+        .and_then(|value| value.as_str())
+        .unwrap_or("default");
+    let network_model = spec
+        .network_template
+        .get("model")
+        .and_then(|value| value.as_str())
+        .unwrap_or("virtio");
+
+    let mut gpu_devices = String::new();
+    if spec
+        .gpu_policy
+        .get("enabled")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+    {
+        if let Some(devices) = spec
+            .gpu_policy
+            .get("devices")
+            .and_then(|value| value.as_array())
+        {
+            for device in devices {
+                let domain = device
+                    .get("domain")
+                    .and_then(|value| value.as_str())
+                    .unwrap_or("0x0000");
+                let bus = device
+                    .get("bus")
+                    .and_then(|value| value.as_str())
+                    .unwrap_or("0x00");
+                let slot = device
+                    .get("slot")
+                    .and_then(|value| value.as_str())
+                    .unwrap_or("0x00");
+                let function = device
+                    .get("function")
+                    .and_then(|value| value.as_str())
+                    .unwrap_or("0x0");
+                gpu_devices.push_str(&format!(
+                    "            <hostdev mode='subsystem' type='pci' managed='yes'>\n                <source>\n                    <address domain='{domain}' bus='{bus}' slot='{slot}' function='{function}'/>\n                </source>\n            </hostdev>\n"
+                ));
+            }
+        }
+    }
+
+    let console_type = console_source.unwrap_or("pty");
+
+    format!(
+        "<domain type='kvm'>\n    <name>{name}</name>\n    <memory unit='MiB'>{memory}</memory>\n    <vcpu>{vcpu}</vcpu>\n    <os>\n        <type arch='x86_64' machine='pc-q35-6.2'>hvm</type>\n    </os>\n    <devices>\n        <disk type='file' device='disk'>\n            <driver name='qemu' type='{driver}'/>\n            <source file='{path}'/>\n            <target dev='{target_dev}' bus='{target_bus}'/>\n        </disk>\n        <interface type='network'>\n            <source network='{network}'/>\n            <model type='{network_model}'/>\n        </interface>\n        <serial type='{console_type}'>\n            <target port='0'/>\n        </serial>\n        <console type='{console_type}'>\n            <target type='serial' port='0'/>\n        </console>\n{gpu_devices}    </devices>\n</domain>",
+        name = spec.domain_name,
+        memory = spec.memory_mib,
+        vcpu = spec.vcpu_count,
+        driver = disk_driver,
+        path = disk_path,
+        target_dev = target_dev,
+        target_bus = target_bus,
+        network = network_name,
+        network_model = network_model,
+        console_type = console_type,
+        gpu_devices = gpu_devices
+    )
 }
 
 #[cfg(feature = "libvirt-executor")]
@@ -290,92 +440,6 @@ impl RealLibvirtDriver {
         }
     }
 
-    fn build_domain_xml(&self, spec: &LibvirtProvisionSpec) -> String {
-        let disk_path = spec
-            .volume_template
-            .get("path")
-            .and_then(|value| value.as_str())
-            .unwrap_or("/var/lib/libvirt/images/mcp.qcow2");
-        let disk_driver = spec
-            .volume_template
-            .get("driver")
-            .and_then(|value| value.as_str())
-            .unwrap_or("qcow2");
-        let target_dev = spec
-            .volume_template
-            .get("target_dev")
-            .and_then(|value| value.as_str())
-            .unwrap_or("vda");
-        let target_bus = spec
-            .volume_template
-            .get("target_bus")
-            .and_then(|value| value.as_str())
-            .unwrap_or("virtio");
-        let network_name = spec
-            .network_template
-            .get("name")
-            .and_then(|value| value.as_str())
-            .unwrap_or("default");
-        let network_model = spec
-            .network_template
-            .get("model")
-            .and_then(|value| value.as_str())
-            .unwrap_or("virtio");
-
-        let mut gpu_devices = String::new();
-        if spec
-            .gpu_policy
-            .get("enabled")
-            .and_then(|value| value.as_bool())
-            .unwrap_or(false)
-        {
-            if let Some(devices) = spec
-                .gpu_policy
-                .get("devices")
-                .and_then(|value| value.as_array())
-            {
-                for device in devices {
-                    let domain = device
-                        .get("domain")
-                        .and_then(|value| value.as_str())
-                        .unwrap_or("0x0000");
-                    let bus = device
-                        .get("bus")
-                        .and_then(|value| value.as_str())
-                        .unwrap_or("0x00");
-                    let slot = device
-                        .get("slot")
-                        .and_then(|value| value.as_str())
-                        .unwrap_or("0x00");
-                    let function = device
-                        .get("function")
-                        .and_then(|value| value.as_str())
-                        .unwrap_or("0x0");
-                    gpu_devices.push_str(&format!(
-                        "            <hostdev mode='subsystem' type='pci' managed='yes'>\n                <source>\n                    <address domain='{domain}' bus='{bus}' slot='{slot}' function='{function}'/>\n                </source>\n            </hostdev>\n"
-                    ));
-                }
-            }
-        }
-
-        let console_type = self.console_source.as_deref().unwrap_or("pty");
-
-        format!(
-            "<domain type='kvm'>\n    <name>{name}</name>\n    <memory unit='MiB'>{memory}</memory>\n    <vcpu>{vcpu}</vcpu>\n    <os>\n        <type arch='x86_64' machine='pc-q35-6.2'>hvm</type>\n    </os>\n    <devices>\n        <disk type='file' device='disk'>\n            <driver name='qemu' type='{driver}'/>\n            <source file='{path}'/>\n            <target dev='{target_dev}' bus='{target_bus}'/>\n        </disk>\n        <interface type='network'>\n            <source network='{network}'/>\n            <model type='{network_model}'/>\n        </interface>\n        <serial type='{console_type}'>\n            <target port='0'/>\n        </serial>\n        <console type='{console_type}'>\n            <target type='serial' port='0'/>\n        </console>\n{gpu_devices}    </devices>\n</domain>",
-            name = spec.domain_name,
-            memory = spec.memory_mib,
-            vcpu = spec.vcpu_count,
-            driver = disk_driver,
-            path = disk_path,
-            target_dev = target_dev,
-            target_bus = target_bus,
-            network = network_name,
-            network_model = network_model,
-            console_type = console_type,
-            gpu_devices = gpu_devices
-        )
-    }
-
     fn read_console_once(&self, name: &str) -> Result<String> {
         let mut conn = self.connect().context("failed to connect to libvirt")?;
         let domain = virt::domain::Domain::lookup_by_name(&conn, name)
@@ -450,7 +514,7 @@ impl LibvirtDriver for RealLibvirtDriver {
         let driver = self.clone();
         let spec = spec.clone();
         spawn_blocking(move || {
-            let xml = driver.build_domain_xml(&spec);
+            let xml = build_domain_xml(&spec, driver.console_source.as_deref());
             let mut conn = driver.connect().context("failed to connect to libvirt")?;
             virt::domain::Domain::define_xml(&conn, &xml)
                 .map_err(|err| anyhow!(err.to_string()))?;
@@ -507,6 +571,60 @@ impl LibvirtDriver for RealLibvirtDriver {
         .context("failed to join libvirt destroy task")??
     }
 
+    async fn snapshot_domain(&self, name: &str) -> Result<String> {
+        let driver = self.clone();
+        let name = name.to_string();
+        spawn_blocking(move || {
+            let mut conn = driver.connect().context("failed to connect to libvirt")?;
+            let domain = virt::domain::Domain::lookup_by_name(&conn, &name)
+                .map_err(|err| anyhow!(err.to_string()))?;
+            let snapshot_name = format!("{name}-snap-{}", Utc::now().format("%Y%m%d%H%M%S%3f"));
+            let xml = format!("<domainsnapshot><name>{snapshot_name}</name></domainsnapshot>");
+            virt::domain_snapshot::DomainSnapshot::create_xml(&domain, &xml, 0)
+                .map_err(|err| anyhow!(err.to_string()))?;
+            Ok::<_, anyhow::Error>(snapshot_name)
+        })
+        .await
+        .context("failed to join libvirt snapshot task")??
+    }
+
+    async fn revert_domain(&self, name: &str, snapshot: &str) -> Result<()> {
+        let driver = self.clone();
+        let name = name.to_string();
+        let snapshot = snapshot.to_string();
+        spawn_blocking(move || {
+            let mut conn = driver.connect().context("failed to connect to libvirt")?;
+            let domain = virt::domain::Domain::lookup_by_name(&conn, &name)
+                .map_err(|err| anyhow!(err.to_string()))?;
+            let snapshot =
+                virt::domain_snapshot::DomainSnapshot::lookup_by_name(&domain, &snapshot, 0)
+                    .map_err(|err| anyhow!(err.to_string()))?;
+            snapshot.revert(0).map_err(|err| anyhow!(err.to_string()))?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .await
+        .context("failed to join libvirt snapshot revert task")??
+    }
+
+    async fn domain_usage(&self, name: &str) -> Result<Option<ResourceUsage>> {
+        let driver = self.clone();
+        let name = name.to_string();
+        spawn_blocking(move || {
+            let mut conn = driver.connect().context("failed to connect to libvirt")?;
+            let domain = virt::domain::Domain::lookup_by_name(&conn, &name)
+                .map_err(|err| anyhow!(err.to_string()))?;
+            let info = domain.get_info().map_err(|err| anyhow!(err.to_string()))?;
+            // libvirt reports current balloon size, not a high-water mark, so peak memory is
+            // left unmeasured rather than approximated
+            Ok::<_, anyhow::Error>(ResourceUsage::measured(
+                Some(info.cpu_time as f64 / 1_000_000_000.0),
+                None,
+            ))
+        })
+        .await
+        .context("failed to join libvirt usage task")??
+    }
+
     async fn fetch_console(&self, name: &str, tail: usize) -> Result<String> {
         let driver = self.clone();
         let name = name.to_string();
@@ -546,6 +664,7 @@ pub mod testing {
         attestation: Option<Value>,
         running: bool,
         log: Vec<String>,
+        snapshots: HashMap<String, bool>,
     }
 
     #[derive(Default)]
@@ -553,6 +672,12 @@ pub mod testing {
         domains: Mutex<HashMap<String, DomainState>>,
     }
 
+    impl InMemoryLibvirtDriver {
+        pub async fn domain_count(&self) -> usize {
+            self.domains.lock().await.len()
+        }
+    }
+
     #[async_trait]
     impl LibvirtDriver for InMemoryLibvirtDriver {
         async fn provision_domain(
@@ -567,6 +692,7 @@ pub mod testing {
                     attestation: spec.attestation_hint.clone(),
                     running: false,
                     log: Vec::new(),
+                    snapshots: HashMap::new(),
                 },
             );
             Ok(LibvirtProvisionedDomain {
@@ -632,5 +758,29 @@ pub mod testing {
             });
             Ok(Some(rx))
         }
+
+        async fn snapshot_domain(&self, name: &str) -> Result<String> {
+            let mut guard = self.domains.lock().await;
+            let entry = guard
+                .get_mut(name)
+                .ok_or_else(|| anyhow!("domain not found: {name}"))?;
+            let snapshot = format!("{name}-snap-{}", entry.snapshots.len() + 1);
+            entry.snapshots.insert(snapshot.clone(), entry.running);
+            Ok(snapshot)
+        }
+
+        async fn revert_domain(&self, name: &str, snapshot: &str) -> Result<()> {
+            let mut guard = self.domains.lock().await;
+            let entry = guard
+                .get_mut(name)
+                .ok_or_else(|| anyhow!("domain not found: {name}"))?;
+            let running = *entry
+                .snapshots
+                .get(snapshot)
+                .ok_or_else(|| anyhow!("snapshot not found: {snapshot}"))?;
+            entry.running = running;
+            entry.log.push(format!("vm-restored:{snapshot}"));
+            Ok(())
+        }
     }
 }
@@ -1,7 +1,9 @@
+use crate::config;
+use crate::db::mcp_servers;
 use crate::extractor::AuthUser;
-use crate::vault::VaultClient;
+use crate::vault::{open_secret, seal_secret, SealedSecret, VaultClient, ENVELOPE_WRAPPER};
 use axum::{
-    extract::{Extension, Path},
+    extract::{Extension, Path, Query},
     http::StatusCode,
     Json,
 };
@@ -27,25 +29,260 @@ pub struct UpdateSecret {
     pub value: String,
 }
 
+#[derive(Serialize)]
+pub struct SecretVersionInfo {
+    pub version: i32,
+    pub current: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct SecretVersionsQuery {
+    pub server_id: i32,
+}
+
+/// key: secrets-versioning -> `secret://name@version` references
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretVersionSelector {
+    Latest,
+    Exact(i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReference {
+    pub name: String,
+    pub version: SecretVersionSelector,
+}
+
+impl SecretReference {
+    /// Parses `secret://name` or `secret://name@version`, where version is `latest` or a
+    /// positive integer.
+    pub fn parse(reference: &str) -> Option<Self> {
+        let body = reference.trim().strip_prefix("secret://")?;
+        let (name, version) = match body.rsplit_once('@') {
+            Some((name, "latest")) => (name, SecretVersionSelector::Latest),
+            Some((name, version)) => {
+                let version = version.parse::<i32>().ok().filter(|v| *v > 0)?;
+                (name, SecretVersionSelector::Exact(version))
+            }
+            None => (body, SecretVersionSelector::Latest),
+        };
+        if name.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
 pub fn encryption_key() -> String {
     std::env::var("SECRET_KEY").unwrap_or_else(|_| "secret".into())
 }
 
+async fn ensure_server_owner(
+    pool: &PgPool,
+    server_id: i32,
+    user_id: i32,
+) -> Result<(), (StatusCode, String)> {
+    let owned = mcp_servers::is_live_owned_by(pool, server_id, user_id)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error while verifying server owner");
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })?;
+    if !owned {
+        return Err((StatusCode::NOT_FOUND, "Server not found".into()));
+    }
+    Ok(())
+}
+
+const VAULT_VALUE_PREFIX: &[u8] = b"vault:";
+
+fn vault_path(value: &[u8]) -> Option<&str> {
+    value
+        .strip_prefix(VAULT_VALUE_PREFIX)
+        .and_then(|path| std::str::from_utf8(path).ok())
+}
+
+const ENVELOPE_VALUE_PREFIX: &[u8] = b"envelope:";
+
+/// Envelope-seals a value when `VAULT_MASTER_KEYS` is configured, returning the stored
+/// bytes and the wrapping master key id.
+async fn envelope_seal(value: &str) -> Result<Option<(Vec<u8>, String)>, (StatusCode, String)> {
+    let Some(wrapper) = ENVELOPE_WRAPPER.as_deref() else {
+        return Ok(None);
+    };
+    let sealed = seal_secret(wrapper, value.as_bytes()).await.map_err(|e| {
+        error!(?e, "Envelope error sealing secret");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Envelope error".into())
+    })?;
+    let mut stored = ENVELOPE_VALUE_PREFIX.to_vec();
+    stored.extend_from_slice(&sealed.to_bytes());
+    Ok(Some((stored, sealed.master_key_id)))
+}
+
+/// Seals a value for storage outside the secrets tables: envelope-sealed when
+/// `VAULT_MASTER_KEYS` is configured, pgcrypto-encrypted with `SECRET_KEY` otherwise.
+pub(crate) async fn seal_stored_value(
+    pool: &PgPool,
+    value: &str,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    if let Some((sealed, _)) = envelope_seal(value).await? {
+        return Ok(sealed);
+    }
+    sqlx::query_scalar("SELECT pgp_sym_encrypt($1, $2)")
+        .bind(value)
+        .bind(encryption_key())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error encrypting secret");
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })
+}
+
+/// Decrypts a stored secret value, following `vault:` pointers when present.
+pub(crate) async fn reveal_stored_value(
+    pool: &PgPool,
+    value: Vec<u8>,
+) -> Result<String, (StatusCode, String)> {
+    if let Some(path) = vault_path(&value) {
+        let Some(vault) = VaultClient::from_env() else {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Vault not configured".into(),
+            ));
+        };
+        return vault.read_secret(path).await.map_err(|e| {
+            error!(?e, "Vault error reading secret");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Vault error".into())
+        });
+    }
+    if let Some(sealed) = value.strip_prefix(ENVELOPE_VALUE_PREFIX) {
+        let Some(wrapper) = ENVELOPE_WRAPPER.as_deref() else {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Envelope master keys not configured".into(),
+            ));
+        };
+        let plaintext = match SealedSecret::from_bytes(sealed) {
+            Ok(sealed) => open_secret(wrapper, &sealed).await,
+            Err(e) => Err(e),
+        }
+        .map_err(|e| {
+            error!(?e, "Envelope error opening secret");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Envelope error".into())
+        })?;
+        return String::from_utf8(plaintext).map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Secret is not valid UTF-8".into(),
+            )
+        });
+    }
+    let key = encryption_key();
+    let row = sqlx::query("SELECT pgp_sym_decrypt($1::bytea, $2) as value")
+        .bind(&value)
+        .bind(&key)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error decrypting secret");
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })?;
+    Ok(row.get("value"))
+}
+
+/// Resolves a `secret://name[@version]` reference for a server, defaulting to the
+/// latest version so pinned consumers are unaffected by rotations.
+pub async fn resolve_secret_reference(
+    pool: &PgPool,
+    server_id: i32,
+    reference: &str,
+) -> Result<String, (StatusCode, String)> {
+    let Some(reference) = SecretReference::parse(reference) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid secret reference: {reference}"),
+        ));
+    };
+    let sql = match reference.version {
+        SecretVersionSelector::Latest => {
+            "SELECT v.value FROM server_secret_versions v \
+             JOIN server_secrets s ON s.id = v.secret_id AND v.version = s.current_version \
+             WHERE s.server_id = $1 AND s.name = $2"
+        }
+        SecretVersionSelector::Exact(_) => {
+            "SELECT v.value FROM server_secret_versions v \
+             JOIN server_secrets s ON s.id = v.secret_id \
+             WHERE s.server_id = $1 AND s.name = $2 AND v.version = $3"
+        }
+    };
+    // bind in placeholder order: $1, $2, then the pinned version as $3
+    let mut query = sqlx::query(sql).bind(server_id).bind(&reference.name);
+    if let SecretVersionSelector::Exact(version) = reference.version {
+        query = query.bind(version);
+    }
+    let row = query.fetch_optional(pool).await.map_err(|e| {
+        error!(?e, "DB error resolving secret reference");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    let Some(row) = row else {
+        return Err((StatusCode::NOT_FOUND, "Secret version not found".into()));
+    };
+    reveal_stored_value(pool, row.get("value")).await
+}
+
+pub async fn list_secret_versions(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(name): Path<String>,
+    Query(params): Query<SecretVersionsQuery>,
+) -> Result<Json<Vec<SecretVersionInfo>>, (StatusCode, String)> {
+    ensure_server_owner(&pool, params.server_id, user_id).await?;
+    let rows = sqlx::query(
+        "SELECT v.version, v.version = s.current_version AS current, v.created_at \
+         FROM server_secret_versions v \
+         JOIN server_secrets s ON s.id = v.secret_id \
+         WHERE s.server_id = $1 AND s.name = $2 \
+         ORDER BY v.version DESC",
+    )
+    .bind(params.server_id)
+    .bind(&name)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error listing secret versions");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    if rows.is_empty() {
+        return Err((StatusCode::NOT_FOUND, "Secret not found".into()));
+    }
+    let versions = rows
+        .into_iter()
+        .map(|r| SecretVersionInfo {
+            version: r.get("version"),
+            current: r.get("current"),
+            created_at: r.get("created_at"),
+        })
+        .collect();
+    Ok(Json(versions))
+}
+
 pub async fn list_secrets(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> Result<Json<Vec<SecretInfo>>, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     let rows = sqlx::query(
@@ -78,73 +315,115 @@ pub async fn create_secret(
     if payload.name.trim().is_empty() {
         return Err((StatusCode::BAD_REQUEST, "Name required".into()));
     }
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
-    if let Some(vault) = VaultClient::from_env() {
-        let path = format!("servers/{}/{}", server_id, payload.name);
-        vault
-            .store_secret(&path, &payload.value)
-            .await
-            .map_err(|e| {
-                error!(?e, "Vault error storing secret");
-                (StatusCode::INTERNAL_SERVER_ERROR, "Vault error".into())
-            })?;
-        sqlx::query("INSERT INTO server_secrets (server_id, name, value) VALUES ($1, $2, $3)")
-            .bind(server_id)
-            .bind(&payload.name)
-            .bind(format!("vault:{}", path))
-            .execute(&pool)
-            .await
-            .map_err(|e| {
-                error!(?e, "DB error inserting secret path");
-                (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-            })?;
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!(?e, "DB error starting secret insert");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    let vault = VaultClient::from_env();
+    let path = format!("servers/{}/{}", server_id, payload.name);
+    let inserted = if vault.is_some() {
+        sqlx::query_scalar(
+            "INSERT INTO server_secrets (server_id, name, value) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(server_id)
+        .bind(&payload.name)
+        .bind(format!("vault:{}", path).into_bytes())
+        .fetch_one(&mut *tx)
+        .await
+    } else if let Some((sealed, master_key_id)) = envelope_seal(&payload.value).await? {
+        sqlx::query_scalar(
+            "INSERT INTO server_secrets (server_id, name, value, master_key_id) VALUES ($1, $2, $3, $4) RETURNING id",
+        )
+        .bind(server_id)
+        .bind(&payload.name)
+        .bind(sealed)
+        .bind(master_key_id)
+        .fetch_one(&mut *tx)
+        .await
     } else {
         let key = encryption_key();
-        sqlx::query(
-            "INSERT INTO server_secrets (server_id, name, value) VALUES ($1, $2, pgp_sym_encrypt($3, $4))",
+        sqlx::query_scalar(
+            "INSERT INTO server_secrets (server_id, name, value) VALUES ($1, $2, pgp_sym_encrypt($3, $4)) RETURNING id",
         )
         .bind(server_id)
         .bind(&payload.name)
         .bind(&payload.value)
         .bind(&key)
-        .execute(&pool)
+        .fetch_one(&mut *tx)
         .await
-        .map_err(|e| {
-            error!(?e, "DB error inserting secret");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
-    }
+    };
+    let secret_id: i32 = inserted.map_err(|e| {
+        error!(?e, "DB error inserting secret");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    sqlx::query(
+        "INSERT INTO server_secret_versions (secret_id, version, value, master_key_id) \
+         SELECT id, current_version, value, master_key_id FROM server_secrets WHERE id = $1",
+    )
+    .bind(secret_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error recording initial secret version");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    let vault_written = match &vault {
+        Some(vault) => {
+            store_in_vault(vault, &path, &payload.value).await?;
+            Some((vault, path.as_str()))
+        }
+        None => None,
+    };
+    commit_secret_write(tx, vault_written).await?;
     Ok(StatusCode::CREATED)
 }
 
+/// Writes a value to Vault once the rows pointing at it are in place but not committed. A
+/// failed write drops the transaction, so nothing refers to a value Vault never stored.
+async fn store_in_vault(
+    vault: &VaultClient,
+    path: &str,
+    value: &str,
+) -> Result<(), (StatusCode, String)> {
+    vault.store_secret(path, value).await.map_err(|e| {
+        error!(?e, "Vault error storing secret");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Vault error".into())
+    })
+}
+
+/// Commits a secret write. If the commit fails after a Vault write, the Vault path is
+/// deleted so it does not outlive the rows that would have referenced it.
+async fn commit_secret_write(
+    tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    vault_written: Option<(&VaultClient, &str)>,
+) -> Result<(), (StatusCode, String)> {
+    let Err(e) = tx.commit().await else {
+        return Ok(());
+    };
+    error!(?e, "DB error committing secret");
+    if let Some((vault, path)) = vault_written {
+        if let Err(e) = vault.delete_secret(path).await {
+            error!(?e, "Vault error deleting uncommitted secret");
+        }
+    }
+    Err((StatusCode::INTERNAL_SERVER_ERROR, "DB error".into()))
+}
+
 pub async fn get_secret(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path((server_id, secret_id)): Path<(i32, i32)>,
 ) -> Result<Json<CreateSecret>, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error while verifying server owner");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
-    if rec.is_none() {
-        return Err((StatusCode::NOT_FOUND, "Server not found".into()));
-    }
+    ensure_server_owner(&pool, server_id, user_id).await?;
     let row =
         sqlx::query("SELECT name, value FROM server_secrets WHERE id = $1 AND server_id = $2")
             .bind(secret_id)
@@ -155,126 +434,161 @@ pub async fn get_secret(
                 error!(?e, "DB error fetching secret");
                 (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
             })?;
-    if let Some(r) = row {
-        let name: String = r.get("name");
-        let value: String = r.get("value");
-        if let Some(path) = value.strip_prefix("vault:") {
-            if let Some(vault) = VaultClient::from_env() {
-                let val = vault.read_secret(path).await.map_err(|e| {
-                    error!(?e, "Vault error reading secret");
-                    (StatusCode::INTERNAL_SERVER_ERROR, "Vault error".into())
-                })?;
-                Ok(Json(CreateSecret { name, value: val }))
-            } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Vault not configured".into(),
-                ))
-            }
-        } else {
-            let key = encryption_key();
-            let row = sqlx::query("SELECT pgp_sym_decrypt($1::bytea, $2) as value")
-                .bind(value)
-                .bind(&key)
-                .fetch_one(&pool)
-                .await
-                .map_err(|e| {
-                    error!(?e, "DB error decrypting secret");
-                    (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-                })?;
-            let val: String = row.get("value");
-            Ok(Json(CreateSecret { name, value: val }))
-        }
-    } else {
-        Err((StatusCode::NOT_FOUND, "Secret not found".into()))
-    }
+    let Some(r) = row else {
+        return Err((StatusCode::NOT_FOUND, "Secret not found".into()));
+    };
+    let name: String = r.get("name");
+    let value = reveal_stored_value(&pool, r.get("value")).await?;
+    Ok(Json(CreateSecret { name, value }))
 }
 
+/// Rotates a secret by recording a new version; older versions stay resolvable until
+/// they fall outside `SECRET_VERSION_RETENTION`.
 pub async fn update_secret(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path((server_id, secret_id)): Path<(i32, i32)>,
     Json(payload): Json<UpdateSecret>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error while verifying server owner");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
-    if rec.is_none() {
-        return Err((StatusCode::NOT_FOUND, "Server not found".into()));
-    }
-    let row = sqlx::query("SELECT value FROM server_secrets WHERE id = $1 AND server_id = $2")
-        .bind(secret_id)
-        .bind(server_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error fetching secret");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
+    ensure_server_owner(&pool, server_id, user_id).await?;
+    let row = sqlx::query(
+        "SELECT name, value, current_version FROM server_secrets WHERE id = $1 AND server_id = $2",
+    )
+    .bind(secret_id)
+    .bind(server_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error fetching secret");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
     let Some(r) = row else {
         return Err((StatusCode::NOT_FOUND, "Secret not found".into()));
     };
-    let stored: String = r.get("value");
-    if let Some(path) = stored.strip_prefix("vault:") {
-        if let Some(vault) = VaultClient::from_env() {
-            vault
-                .store_secret(path, &payload.value)
-                .await
-                .map_err(|e| {
-                    error!(?e, "Vault error updating secret");
-                    (StatusCode::INTERNAL_SERVER_ERROR, "Vault error".into())
-                })?;
-        } else {
+    let name: String = r.get("name");
+    let stored: Vec<u8> = r.get("value");
+    let current_version: i32 = r.get("current_version");
+    let next_version = current_version + 1;
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!(?e, "DB error starting secret rotation");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    let vault = if vault_path(&stored).is_some() {
+        let Some(vault) = VaultClient::from_env() else {
             return Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Vault not configured".into(),
             ));
-        }
+        };
+        Some(vault)
     } else {
-        let key = encryption_key();
-        let result = sqlx::query(
-            "UPDATE server_secrets SET value = pgp_sym_encrypt($1, $2) WHERE id = $3 AND server_id = $4",
+        None
+    };
+    let path = format!("servers/{server_id}/{name}/versions/{next_version}");
+    // the version row is reserved first, so a concurrent rotation to the same version fails
+    // here instead of overwriting this one's Vault value
+    let inserted = if vault.is_some() {
+        sqlx::query(
+            "INSERT INTO server_secret_versions (secret_id, version, value) VALUES ($1, $2, $3)",
         )
-        .bind(&payload.value)
-        .bind(&key)
         .bind(secret_id)
-        .bind(server_id)
-        .execute(&pool)
+        .bind(next_version)
+        .bind(format!("vault:{path}").into_bytes())
+        .execute(&mut *tx)
         .await
-        .map_err(|e| {
-            error!(?e, "DB error updating secret");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
-        if result.rows_affected() == 0 {
-            return Err((StatusCode::NOT_FOUND, "Secret not found".into()));
+    } else if let Some((sealed, master_key_id)) = envelope_seal(&payload.value).await? {
+        sqlx::query(
+            "INSERT INTO server_secret_versions (secret_id, version, value, master_key_id) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(secret_id)
+        .bind(next_version)
+        .bind(sealed)
+        .bind(master_key_id)
+        .execute(&mut *tx)
+        .await
+    } else {
+        sqlx::query(
+            "INSERT INTO server_secret_versions (secret_id, version, value) \
+             VALUES ($1, $2, pgp_sym_encrypt($3, $4))",
+        )
+        .bind(secret_id)
+        .bind(next_version)
+        .bind(&payload.value)
+        .bind(encryption_key())
+        .execute(&mut *tx)
+        .await
+    };
+    inserted.map_err(|e| {
+        error!(?e, "DB error recording secret version");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+
+    sqlx::query(
+        "UPDATE server_secrets s \
+         SET value = v.value, current_version = v.version, master_key_id = v.master_key_id \
+         FROM server_secret_versions v \
+         WHERE v.secret_id = s.id AND v.version = $2 AND s.id = $1",
+    )
+    .bind(secret_id)
+    .bind(next_version)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error updating secret");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+
+    let pruned: Vec<Vec<u8>> = sqlx::query_scalar(
+        "DELETE FROM server_secret_versions WHERE secret_id = $1 AND version <= $2 RETURNING value",
+    )
+    .bind(secret_id)
+    .bind(next_version - *config::SECRET_VERSION_RETENTION)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error pruning secret versions");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+
+    let vault_written = match &vault {
+        Some(vault) => {
+            store_in_vault(vault, &path, &payload.value).await?;
+            Some((vault, path.as_str()))
         }
-    }
+        None => None,
+    };
+    commit_secret_write(tx, vault_written).await?;
+
+    delete_vault_values(pruned).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn delete_vault_values(values: Vec<Vec<u8>>) {
+    let paths: Vec<String> = values
+        .iter()
+        .filter_map(|value| vault_path(value).map(str::to_string))
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+    let Some(vault) = VaultClient::from_env() else {
+        return;
+    };
+    for path in paths {
+        if let Err(e) = vault.delete_secret(&path).await {
+            error!(?e, "Vault error deleting secret version");
+        }
+    }
+}
+
 pub async fn delete_secret(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path((server_id, secret_id)): Path<(i32, i32)>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error while verifying server owner");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
-    if rec.is_none() {
-        return Err((StatusCode::NOT_FOUND, "Server not found".into()));
-    }
+    ensure_server_owner(&pool, server_id, user_id).await?;
     let row = sqlx::query("SELECT value FROM server_secrets WHERE id = $1 AND server_id = $2")
         .bind(secret_id)
         .bind(server_id)
@@ -287,20 +601,22 @@ pub async fn delete_secret(
     let Some(r) = row else {
         return Err((StatusCode::NOT_FOUND, "Secret not found".into()));
     };
-    let stored: String = r.get("value");
-    if let Some(path) = stored.strip_prefix("vault:") {
-        if let Some(vault) = VaultClient::from_env() {
-            vault.delete_secret(path).await.map_err(|e| {
-                error!(?e, "Vault error deleting secret");
-                (StatusCode::INTERNAL_SERVER_ERROR, "Vault error".into())
-            })?;
-        } else {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Vault not configured".into(),
-            ));
-        }
+    let stored: Vec<u8> = r.get("value");
+    if vault_path(&stored).is_some() && VaultClient::from_env().is_none() {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Vault not configured".into(),
+        ));
     }
+    let versions: Vec<Vec<u8>> =
+        sqlx::query_scalar("SELECT value FROM server_secret_versions WHERE secret_id = $1")
+            .bind(secret_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| {
+                error!(?e, "DB error fetching secret versions");
+                (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+            })?;
     let result = sqlx::query("DELETE FROM server_secrets WHERE id = $1 AND server_id = $2")
         .bind(secret_id)
         .bind(server_id)
@@ -313,5 +629,34 @@ pub async fn delete_secret(
     if result.rows_affected() == 0 {
         return Err((StatusCode::NOT_FOUND, "Secret not found".into()));
     }
+    delete_vault_values(versions).await;
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SecretReference, SecretVersionSelector};
+
+    #[test]
+    fn reference_defaults_to_latest() {
+        let reference = SecretReference::parse("secret://db-password").unwrap();
+        assert_eq!(reference.name, "db-password");
+        assert_eq!(reference.version, SecretVersionSelector::Latest);
+        assert_eq!(
+            SecretReference::parse("secret://db-password@latest")
+                .unwrap()
+                .version,
+            SecretVersionSelector::Latest
+        );
+    }
+
+    #[test]
+    fn reference_parses_pinned_version() {
+        let reference = SecretReference::parse("secret://api@key@3").unwrap();
+        assert_eq!(reference.name, "api@key");
+        assert_eq!(reference.version, SecretVersionSelector::Exact(3));
+        assert!(SecretReference::parse("secret://api@0").is_none());
+        assert!(SecretReference::parse("secret://@2").is_none());
+        assert!(SecretReference::parse("vault://api").is_none());
+    }
+}
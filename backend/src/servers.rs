@@ -1,12 +1,23 @@
+use crate::db::mcp_servers::{self, RestoreOutcome, ServerSummary};
 use crate::error::{AppError, AppResult};
 use crate::extractor::AuthUser;
-use crate::invocations::record_invocation;
+use crate::invocations::cache::{self, CacheKey, INVOCATION_CACHE};
+use crate::invocations::stream::{InvocationStreamEvent, InvocationStreamMessage};
+use crate::invocations::{
+    self, record_invocation, with_deadline, InvocationStatus, InvocationTimedOut,
+    INVOCATION_TIMEOUT_HEADER,
+};
 use crate::policy::trust::{evaluate_placement_gate, TrustPlacementGate};
-use crate::runtime::ContainerRuntime;
+use crate::policy::PolicyError;
+use crate::proxy::pool::UPSTREAM_CLIENTS;
+use crate::proxy::transform::{ProxyTransforms, TransformError};
+use crate::proxy::{self, BreakerSnapshot, ProxyBodyError, ProxyBodyLimits, UpstreamSendError};
+use crate::remediation::payload_schema::SchemaViolation;
+use crate::runtime::{ContainerRuntime, PlacementPreview};
 use crate::telemetry::{validate_metric_details, Metric, MetricError};
 use axum::{
-    extract::{Extension, Path},
-    http::StatusCode,
+    extract::{BodyStream, Extension, Path},
+    http::{HeaderMap, HeaderValue, StatusCode},
     response::sse::{Event, Sse},
     Json,
 };
@@ -24,15 +35,9 @@ use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
 use tracing::{error, warn};
 use uuid::Uuid;
 
-#[derive(Serialize)]
-pub struct Server {
-    pub id: i32,
-    pub name: String,
-    pub server_type: String,
-    pub status: String,
-    pub use_gpu: bool,
-    pub organization_id: Option<i32>,
-}
+mod config_schema;
+
+use config_schema::{validate_config, ConfigValidation};
 
 #[derive(Deserialize)]
 pub struct CreateServer {
@@ -55,6 +60,9 @@ pub struct ServerInfo {
     pub webhook_secret: String,
     pub manifest: Option<serde_json::Value>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Config keys outside the server type's schema; they were saved anyway.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub config_warnings: Vec<SchemaViolation>,
 }
 
 #[derive(Serialize)]
@@ -422,55 +430,41 @@ mod tests {
 pub async fn list_servers(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, role }: AuthUser,
-) -> AppResult<Json<Vec<Server>>> {
-    let query = if role == "admin" {
-        sqlx::query(
-            "SELECT id, name, server_type, status, use_gpu, organization_id FROM mcp_servers",
-        )
-        .fetch_all(&pool)
-    } else {
-        sqlx::query("SELECT id, name, server_type, status, use_gpu, organization_id FROM mcp_servers WHERE owner_id = $1")
-            .bind(user_id)
-            .fetch_all(&pool)
-    };
-    let rows = query.await.map_err(|e| {
+) -> AppResult<Json<Vec<ServerSummary>>> {
+    let owner = (role != "admin").then_some(user_id);
+    let servers = mcp_servers::list_servers(&pool, owner).await.map_err(|e| {
         error!(?e, "DB error listing servers");
         AppError::Db(e)
     })?;
-    let servers = rows
-        .into_iter()
-        .map(|r| Server {
-            id: r.get("id"),
-            name: r.get("name"),
-            server_type: r.get("server_type"),
-            status: r.get("status"),
-            use_gpu: r.get("use_gpu"),
-            organization_id: r.try_get("organization_id").ok(),
-        })
-        .collect();
     Ok(Json(servers))
 }
 
 pub async fn create_server(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     AuthUser { user_id, role }: AuthUser,
     Json(payload): Json<CreateServer>,
 ) -> AppResult<Json<ServerInfo>> {
     if payload.name.trim().is_empty() {
         return Err(AppError::BadRequest("Name is required".into()));
     }
+    let validation = validate_config(&payload.server_type, payload.config.as_ref());
+    if !validation.is_valid() {
+        return Err(invalid_config(&payload.server_type, validation));
+    }
 
     // enforce quota for non-admin users
     if role != "admin" {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM mcp_servers WHERE owner_id = $1")
-            .bind(user_id)
-            .fetch_one(&pool)
-            .await
-            .map_err(|e| {
-                error!(?e, "DB error counting servers");
-                AppError::Db(e)
-            })?;
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM mcp_servers WHERE owner_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error counting servers");
+            AppError::Db(e)
+        })?;
         let quota: i32 = sqlx::query_scalar("SELECT server_quota FROM users WHERE id = $1")
             .bind(user_id)
             .fetch_one(&pool)
@@ -521,6 +515,7 @@ pub async fn create_server(
         webhook_secret: webhook_secret.clone(),
         manifest: None,
         created_at,
+        config_warnings: validation.warnings,
     };
 
     let gate = trust_gate_for(&pool, id).await?;
@@ -549,22 +544,82 @@ pub async fn create_server(
             use_gpu: payload.use_gpu.unwrap_or(false),
         };
         enqueue_job(&pool, &job).await;
-        let _ = job_tx.send(job).await;
+        let _ = job_tx.send(job.into()).await;
     }
 
     Ok(Json(info))
 }
 
-use crate::job_queue::{enqueue_job, Job};
+fn invalid_config(server_type: &str, validation: ConfigValidation) -> AppError {
+    AppError::JsonBadRequest(serde_json::json!({
+        "error": "invalid server config",
+        "server_type": server_type,
+        "errors": validation.errors,
+        "warnings": validation.warnings,
+    }))
+}
+
+#[derive(Serialize)]
+pub struct ServerConfigUpdate {
+    pub config: serde_json::Value,
+    pub warnings: Vec<SchemaViolation>,
+}
+
+/// Replace the server's config after checking it against its type's schema. The new config
+/// applies on the next start or redeploy.
+pub async fn update_server_config(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    Json(config): Json<serde_json::Value>,
+) -> AppResult<Json<ServerConfigUpdate>> {
+    let server_type: Option<String> = sqlx::query_scalar(
+        "SELECT server_type FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error loading server type");
+        AppError::Db(e)
+    })?;
+    let server_type = server_type.ok_or(AppError::NotFound)?;
+    let validation = validate_config(&server_type, Some(&config));
+    if !validation.is_valid() {
+        return Err(invalid_config(&server_type, validation));
+    }
+    let updated = sqlx::query(
+        "UPDATE mcp_servers SET config = $3 WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(&config)
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error updating server config");
+        AppError::Db(e)
+    })?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(Json(ServerConfigUpdate {
+        config,
+        warnings: validation.warnings,
+    }))
+}
+
+use crate::job_queue::{enqueue_job, Job, QueuedJob};
 
 pub async fn start_server(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<StatusCode> {
     let rec = sqlx::query(
-            "SELECT server_type, config, api_key, status, use_gpu FROM mcp_servers WHERE id = $1 AND owner_id = $2"
+            "SELECT server_type, config, api_key, status, use_gpu FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL"
         )
         .bind(id)
         .bind(user_id)
@@ -612,26 +667,28 @@ pub async fn start_server(
         use_gpu,
     };
     enqueue_job(&pool, &job).await;
-    let _ = job_tx.send(job).await;
+    let _ = job_tx.send(job.into()).await;
 
     Ok(StatusCode::ACCEPTED)
 }
 
 pub async fn stop_server(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<StatusCode> {
-    let rec = sqlx::query("SELECT status FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error fetching server");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT status FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error fetching server");
+        AppError::Db(e)
+    })?;
     let Some(row) = rec else {
         return Err(AppError::NotFound);
     };
@@ -644,44 +701,129 @@ pub async fn stop_server(
 
     let job = Job::Stop { server_id: id };
     enqueue_job(&pool, &job).await;
-    let _ = job_tx.send(job).await;
+    let _ = job_tx.send(job.into()).await;
 
     Ok(StatusCode::ACCEPTED)
 }
 
+/// Soft-deletes the server: it drops out of listings and its runtime is stopped, but the row
+/// stays so history that points at it still resolves. `restore_server` undoes this until the
+/// retention sweep removes the server for good.
 pub async fn delete_server(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<StatusCode> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let deleted = mcp_servers::soft_delete(&pool, id, user_id)
         .await
         .map_err(|e| {
-            error!(?e, "DB error fetching server status");
+            error!(?e, "DB error soft-deleting server");
             AppError::Db(e)
         })?;
-    let Some(_) = rec else {
+    if deleted.is_none() {
         return Err(AppError::NotFound);
-    };
+    }
 
-    let job = Job::Delete { server_id: id };
+    let job = Job::Stop { server_id: id };
+    UPSTREAM_CLIENTS.forget(id);
+    INVOCATION_CACHE.invalidate_server(id);
     enqueue_job(&pool, &job).await;
-    let _ = job_tx.send(job).await;
+    let _ = job_tx.send(job.into()).await;
+    let _ = add_metric(&pool, id, "soft_delete", None).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Undeletes a server within `SERVER_DELETE_RETENTION_HOURS`. It comes back stopped.
+pub async fn restore_server(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+) -> AppResult<Json<ServerSummary>> {
+    let cutoff = chrono::Utc::now()
+        - chrono::Duration::hours(crate::config::reload::current().server_delete_retention_hours);
+    let outcome = mcp_servers::restore(&pool, id, user_id, cutoff)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error restoring server");
+            AppError::Db(e)
+        })?;
+    match outcome {
+        RestoreOutcome::Restored(server) => {
+            let _ = add_metric(&pool, id, "restore", None).await;
+            Ok(Json(server))
+        }
+        RestoreOutcome::RetentionExpired { deleted_at } => Err(AppError::Conflict(format!(
+            "server {id} was deleted at {} and is past its restore window",
+            deleted_at.to_rfc3339()
+        ))),
+        RestoreOutcome::NotFound => Err(AppError::NotFound),
+    }
+}
+
+/// key: server-soft-delete -> retention sweep
+///
+/// Hard-deletes servers whose restore window has passed, then queues a runtime delete for each
+/// to clear its container and storage.
+pub fn spawn_deleted_server_sweep(pool: PgPool, job_tx: tokio::sync::mpsc::Sender<QueuedJob>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(
+            *crate::config::SERVER_DELETE_SWEEP_INTERVAL_SECS,
+        ));
+        loop {
+            ticker.tick().await;
+            let cutoff = chrono::Utc::now()
+                - chrono::Duration::hours(
+                    crate::config::reload::current().server_delete_retention_hours,
+                );
+            sweep_deleted_servers(&pool, &job_tx, cutoff).await;
+        }
+    });
+}
+
+/// Hard-deletes servers deleted at or before `cutoff` one at a time, so a server that cannot
+/// be removed is logged and retried next tick without holding up the rest. Returns the ids
+/// that were removed.
+pub async fn sweep_deleted_servers(
+    pool: &PgPool,
+    job_tx: &tokio::sync::mpsc::Sender<QueuedJob>,
+    cutoff: chrono::DateTime<chrono::Utc>,
+) -> Vec<i32> {
+    let expired = match mcp_servers::expired_deletions(pool, cutoff).await {
+        Ok(expired) => expired,
+        Err(err) => {
+            warn!(?err, "deleted server sweep failed to list expired servers");
+            return Vec::new();
+        }
+    };
+    let mut purged = Vec::with_capacity(expired.len());
+    for server_id in expired {
+        match mcp_servers::purge(pool, server_id, cutoff).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                warn!(?err, %server_id, "deleted server sweep failed to remove server");
+                continue;
+            }
+        }
+        tracing::info!(%server_id, "deleted server past retention removed");
+        UPSTREAM_CLIENTS.forget(server_id);
+        let job = Job::Delete { server_id };
+        enqueue_job(pool, &job).await;
+        let _ = job_tx.send(job.into()).await;
+        purged.push(server_id);
+    }
+    purged
+}
+
 pub async fn redeploy_server(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<StatusCode> {
     let rec = sqlx::query(
-            "SELECT server_type, config, api_key, use_gpu FROM mcp_servers WHERE id = $1 AND owner_id = $2",
+            "SELECT server_type, config, api_key, use_gpu FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
         )
         .bind(id)
         .bind(user_id)
@@ -723,18 +865,18 @@ pub async fn redeploy_server(
         use_gpu,
     };
     enqueue_job(&pool, &job).await;
-    let _ = job_tx.send(job).await;
+    let _ = job_tx.send(job.into()).await;
     Ok(StatusCode::ACCEPTED)
 }
 
 pub async fn webhook_redeploy(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     Path(id): Path<i32>,
     headers: axum::http::HeaderMap,
 ) -> AppResult<StatusCode> {
     let rec = sqlx::query(
-        "SELECT webhook_secret, server_type, config, api_key, use_gpu FROM mcp_servers WHERE id = $1",
+        "SELECT webhook_secret, server_type, config, api_key, use_gpu FROM mcp_servers WHERE id = $1 AND deleted_at IS NULL",
     )
     .bind(id)
     .fetch_optional(&pool)
@@ -784,14 +926,14 @@ pub async fn webhook_redeploy(
         use_gpu,
     };
     enqueue_job(&pool, &job).await;
-    let _ = job_tx.send(job).await;
+    let _ = job_tx.send(job.into()).await;
     Ok(StatusCode::ACCEPTED)
 }
 
 /// Handle GitHub push webhooks using the stored secret for HMAC verification.
 pub async fn github_webhook(
     Extension(pool): Extension<PgPool>,
-    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<Job>>,
+    Extension(job_tx): Extension<tokio::sync::mpsc::Sender<QueuedJob>>,
     Path(id): Path<i32>,
     headers: axum::http::HeaderMap,
     body: axum::body::Bytes,
@@ -800,7 +942,7 @@ pub async fn github_webhook(
     use sha2::Sha256;
 
     let rec = sqlx::query(
-        "SELECT webhook_secret, server_type, config, api_key, use_gpu FROM mcp_servers WHERE id = $1",
+        "SELECT webhook_secret, server_type, config, api_key, use_gpu FROM mcp_servers WHERE id = $1 AND deleted_at IS NULL",
     )
     .bind(id)
     .fetch_optional(&pool)
@@ -866,7 +1008,7 @@ pub async fn github_webhook(
         use_gpu,
     };
     enqueue_job(&pool, &job).await;
-    let _ = job_tx.send(job).await;
+    let _ = job_tx.send(job.into()).await;
     Ok(StatusCode::ACCEPTED)
 }
 
@@ -876,15 +1018,17 @@ pub async fn server_logs(
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<String> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(_) = rec else {
         return Err(AppError::NotFound);
     };
@@ -902,20 +1046,79 @@ pub async fn server_logs(
     }
 }
 
+/// Prospective launch for [`preview_placement`]. Omitted fields fall back to the server's
+/// stored settings.
+#[derive(Deserialize)]
+pub struct PlacementPreviewRequest {
+    pub server_id: i32,
+    pub server_type: Option<String>,
+    pub config: Option<serde_json::Value>,
+    pub use_gpu: Option<bool>,
+}
+
+pub async fn preview_placement(
+    Extension(pool): Extension<PgPool>,
+    Extension(runtime): Extension<std::sync::Arc<dyn ContainerRuntime>>,
+    AuthUser { user_id, .. }: AuthUser,
+    Json(request): Json<PlacementPreviewRequest>,
+) -> AppResult<Json<PlacementPreview>> {
+    let rec = sqlx::query(
+        "SELECT server_type, config, use_gpu FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(request.server_id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
+    let Some(rec) = rec else {
+        return Err(AppError::NotFound);
+    };
+
+    let server_type = request
+        .server_type
+        .unwrap_or_else(|| rec.get("server_type"));
+    let config = request
+        .config
+        .or_else(|| rec.get::<Option<serde_json::Value>, _>("config"));
+    let use_gpu = request.use_gpu.unwrap_or_else(|| rec.get("use_gpu"));
+
+    let preview = runtime
+        .preview_placement(
+            &pool,
+            request.server_id,
+            &server_type,
+            config.as_ref(),
+            use_gpu,
+        )
+        .await
+        .map_err(|err| match err {
+            PolicyError::Database(e) => {
+                error!(?e, "DB error evaluating placement preview");
+                AppError::Db(e)
+            }
+        })?;
+    Ok(Json(preview))
+}
+
 pub async fn stored_logs(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<Json<Vec<LogEntry>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(_) = rec else {
         return Err(AppError::NotFound);
     };
@@ -945,15 +1148,17 @@ pub async fn stream_logs(
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(_) = rec else {
         return Err(AppError::NotFound);
     };
@@ -970,15 +1175,17 @@ pub async fn vm_runtime_details(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> AppResult<Json<VmRuntimeSummary>> {
-    let server = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|error| {
-            error!(?error, %server_id, "failed to verify VM server ownership");
-            AppError::Db(error)
-        })?;
+    let server = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|error| {
+        error!(?error, %server_id, "failed to verify VM server ownership");
+        AppError::Db(error)
+    })?;
 
     if server.is_none() {
         return Err(AppError::NotFound);
@@ -1134,15 +1341,17 @@ pub async fn get_metrics(
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<Json<Vec<Metric>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(_) = rec else {
         return Err(AppError::NotFound);
     };
@@ -1172,15 +1381,17 @@ pub async fn post_metric(
     Path(id): Path<i32>,
     Json(payload): Json<MetricInput>,
 ) -> AppResult<StatusCode> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(_) = rec else {
         return Err(AppError::NotFound);
     };
@@ -1203,15 +1414,17 @@ pub async fn stream_metrics(
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(_) = rec else {
         return Err(AppError::NotFound);
     };
@@ -1246,51 +1459,513 @@ pub async fn stream_status(
     Sse::new(stream)
 }
 
+/// A stored config that no longer validates fails closed like a failing transform.
+fn proxy_transforms_from_row(
+    row: &sqlx::postgres::PgRow,
+) -> Result<ProxyTransforms, TransformError> {
+    match row
+        .try_get::<Option<serde_json::Value>, _>("proxy_transforms")
+        .ok()
+        .flatten()
+    {
+        Some(value) => ProxyTransforms::from_value(value),
+        None => Ok(ProxyTransforms::default()),
+    }
+}
+
+fn transform_failed(err: TransformError) -> AppError {
+    AppError::BadGateway(format!("proxy transform failed: {err}"))
+}
+
+fn proxy_limits_from_row(row: &sqlx::postgres::PgRow) -> ProxyBodyLimits {
+    ProxyBodyLimits::for_server(
+        row.try_get("proxy_max_request_bytes").ok().flatten(),
+        row.try_get("proxy_max_response_bytes").ok().flatten(),
+    )
+}
+
 /// Proxy a request to the running MCP server and return its response.
 pub async fn invoke_server(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
-    Json(payload): Json<serde_json::Value>,
-) -> AppResult<String> {
-    let rec = sqlx::query("SELECT api_key FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    headers: HeaderMap,
+    body: BodyStream,
+) -> AppResult<(HeaderMap, String)> {
+    let timeout = invocations::resolve_timeout(&headers).map_err(AppError::BadRequest)?;
+    let rec = sqlx::query(
+        "SELECT api_key, proxy_max_request_bytes, proxy_max_response_bytes, invocation_cache_ttl_secs, \
+         proxy_transforms FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(rec) = rec else {
         return Err(AppError::NotFound);
     };
     let api_key: String = rec.get("api_key");
+    let limits = proxy_limits_from_row(&rec);
+    let transforms = proxy_transforms_from_row(&rec).map_err(transform_failed)?;
+
+    let raw = match proxy::collect_limited(body, limits.max_request_bytes).await {
+        Ok(raw) => raw,
+        Err(ProxyBodyError::TooLarge { limit }) => {
+            return Err(AppError::PayloadTooLarge(format!(
+                "request body exceeded {limit} bytes"
+            )))
+        }
+        Err(e) => return Err(AppError::BadRequest(e.to_string())),
+    };
+    let payload: serde_json::Value = serde_json::from_slice(&raw)
+        .map_err(|e| AppError::BadRequest(format!("invalid JSON body: {e}")))?;
+
+    let cache_ttl = rec
+        .try_get::<Option<i32>, _>("invocation_cache_ttl_secs")
+        .ok()
+        .flatten()
+        .map(|secs| std::time::Duration::from_secs(secs.max(1) as u64));
+    let mut cache_key = None;
+    if cache_ttl.is_some() {
+        let key = CacheKey::for_payload(id, &payload);
+        if cache::tool_is_cacheable(&pool, id, key.tool.as_deref()).await? {
+            cache_key = Some(key);
+        }
+    }
+    if let Some(key) = cache_key
+        .as_ref()
+        .filter(|_| !cache::bypass_requested(&headers))
+    {
+        if let Some(body) = INVOCATION_CACHE.get(key, std::time::Instant::now()) {
+            return Ok((cache_marker(true), body));
+        }
+    }
 
-    let client = reqwest::Client::new();
-    match client
+    // the cache key and the trace keep the caller's payload; only the upstream sees the rewrite
+    let upstream_payload = transforms
+        .transform_request(&payload)
+        .map_err(transform_failed)?;
+    let request = UPSTREAM_CLIENTS
+        .client(id)
         .post(format!("http://mcp-server-{id}:8080/invoke"))
         .header("Authorization", format!("Bearer {}", api_key))
-        .json(&payload)
-        .send()
-        .await
-    {
-        Ok(resp) => match resp.text().await {
-            Ok(text) => {
-                if let Err(e) = record_invocation(&pool, id, user_id, &payload, Some(&text)).await {
-                    error!(?e, "failed to record invocation");
-                }
-                Ok(text)
-            }
-            Err(_) => Err(AppError::Message("Failed to read response".into())),
-        },
-        Err(_) => {
-            if let Err(e) = record_invocation(&pool, id, user_id, &payload, None).await {
+        .header(INVOCATION_TIMEOUT_HEADER, timeout.as_millis().to_string())
+        .json(&upstream_payload);
+    let exchange = async {
+        let resp = proxy::send_guarded(&pool, id, request).await?;
+        let succeeded = resp.status().is_success();
+        Ok::<_, UpstreamSendError>((
+            succeeded,
+            proxy::read_response_limited(resp, limits.max_response_bytes).await,
+        ))
+    };
+    let record = |output: Option<String>, status: InvocationStatus| {
+        let (pool, payload) = (&pool, &payload);
+        async move {
+            if let Err(e) = record_invocation(
+                pool,
+                id,
+                user_id,
+                payload,
+                output.as_deref(),
+                status,
+                timeout,
+            )
+            .await
+            {
                 error!(?e, "failed to record invocation");
             }
+        }
+    };
+    match with_deadline(timeout, exchange).await {
+        Ok(Ok((succeeded, Ok(bytes)))) => {
+            let bytes = if succeeded {
+                match transforms.transform_response(bytes) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        record(None, InvocationStatus::Failed).await;
+                        return Err(transform_failed(err));
+                    }
+                }
+            } else {
+                bytes
+            };
+            let text = String::from_utf8_lossy(&bytes).into_owned();
+            // an upstream error status still returns its body, but the trace records a failure
+            let status = if succeeded {
+                InvocationStatus::Succeeded
+            } else {
+                InvocationStatus::Failed
+            };
+            record(Some(text.clone()), status).await;
+            if let (true, Some(key), Some(ttl)) = (succeeded, cache_key, cache_ttl) {
+                INVOCATION_CACHE.insert(key, text.clone(), ttl, std::time::Instant::now());
+            }
+            Ok((cache_marker(false), text))
+        }
+        Ok(Ok((_, Err(ProxyBodyError::TooLarge { limit })))) => {
+            record(None, InvocationStatus::Failed).await;
+            Err(AppError::PayloadTooLarge(format!(
+                "upstream response exceeded {limit} bytes"
+            )))
+        }
+        Ok(Ok((_, Err(_)))) => {
+            record(None, InvocationStatus::Failed).await;
+            Err(AppError::Message("Failed to read response".into()))
+        }
+        Ok(Err(UpstreamSendError::CircuitOpen(open))) => {
+            Err(AppError::ServiceUnavailable(format!(
+                "upstream circuit open; retry in {}s",
+                open.retry_after.as_secs()
+            )))
+        }
+        Ok(Err(UpstreamSendError::Unreachable(_))) => {
+            record(None, InvocationStatus::Failed).await;
             Err(AppError::BadGateway("Container unreachable".into()))
         }
+        Err(InvocationTimedOut { after }) => {
+            proxy::record_cancelled(&pool, id).await;
+            record(None, InvocationStatus::TimedOut).await;
+            Err(AppError::GatewayTimeout(format!(
+                "invocation timed out after {}ms",
+                after.as_millis()
+            )))
+        }
+    }
+}
+
+fn cache_marker(cached: bool) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        cache::CACHED_HEADER,
+        HeaderValue::from_static(if cached { "true" } else { "false" }),
+    );
+    headers
+}
+
+/// Proxy a request like `invoke_server`, relaying the output over SSE as it arrives. Each event
+/// is an `InvocationStreamMessage`; the last one is always `completed` or `failed`. The result is
+/// never cached.
+pub async fn invoke_server_stream(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+    body: BodyStream,
+) -> AppResult<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>> {
+    let timeout = invocations::resolve_timeout(&headers).map_err(AppError::BadRequest)?;
+    let rec = sqlx::query(
+        "SELECT api_key, proxy_max_request_bytes, proxy_max_response_bytes, proxy_transforms \
+         FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
+    let Some(rec) = rec else {
+        return Err(AppError::NotFound);
+    };
+    let api_key: String = rec.get("api_key");
+    let limits = proxy_limits_from_row(&rec);
+    let transforms = proxy_transforms_from_row(&rec).map_err(transform_failed)?;
+
+    let raw = match proxy::collect_limited(body, limits.max_request_bytes).await {
+        Ok(raw) => raw,
+        Err(ProxyBodyError::TooLarge { limit }) => {
+            return Err(AppError::PayloadTooLarge(format!(
+                "request body exceeded {limit} bytes"
+            )))
+        }
+        Err(e) => return Err(AppError::BadRequest(e.to_string())),
+    };
+    let payload: serde_json::Value = serde_json::from_slice(&raw)
+        .map_err(|e| AppError::BadRequest(format!("invalid JSON body: {e}")))?;
+    let upstream_payload = transforms
+        .transform_request(&payload)
+        .map_err(transform_failed)?;
+    let request = UPSTREAM_CLIENTS
+        .client(id)
+        .post(format!("http://mcp-server-{id}:8080/invoke"))
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header(
+            reqwest::header::ACCEPT,
+            "text/event-stream, application/json",
+        )
+        .header(INVOCATION_TIMEOUT_HEADER, timeout.as_millis().to_string())
+        .json(&upstream_payload);
+
+    let (tx, rx) = tokio::sync::mpsc::channel(32);
+    tokio::spawn(async move {
+        let exchange = async {
+            match proxy::send_guarded(&pool, id, request).await {
+                Ok(resp) if resp.status().is_success() => {
+                    let streaming = invocations::stream::is_event_stream(resp.headers());
+                    let transforms = (!streaming).then_some(&transforms);
+                    invocations::stream::relay(
+                        resp.bytes_stream(),
+                        streaming,
+                        limits.max_response_bytes,
+                        transforms,
+                        &tx,
+                    )
+                    .await
+                }
+                Ok(resp) => {
+                    let status = resp.status();
+                    let body = proxy::read_response_limited(resp, limits.max_response_bytes)
+                        .await
+                        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                        .unwrap_or_default();
+                    let message = format!("upstream returned {status}");
+                    let _ = tx
+                        .send(InvocationStreamEvent::failed(
+                            InvocationStatus::Failed,
+                            message,
+                        ))
+                        .await;
+                    (InvocationStatus::Failed, Some(body))
+                }
+                Err(UpstreamSendError::CircuitOpen(open)) => {
+                    let message = format!(
+                        "upstream circuit open; retry in {}s",
+                        open.retry_after.as_secs()
+                    );
+                    let _ = tx
+                        .send(InvocationStreamEvent::failed(
+                            InvocationStatus::Failed,
+                            message,
+                        ))
+                        .await;
+                    (InvocationStatus::Failed, None)
+                }
+                Err(UpstreamSendError::Unreachable(_)) => {
+                    let _ = tx
+                        .send(InvocationStreamEvent::failed(
+                            InvocationStatus::Failed,
+                            "Container unreachable",
+                        ))
+                        .await;
+                    (InvocationStatus::Failed, None)
+                }
+            }
+        };
+        let (status, output) = match with_deadline(timeout, exchange).await {
+            Ok(outcome) => outcome,
+            Err(InvocationTimedOut { after }) => {
+                proxy::record_cancelled(&pool, id).await;
+                let message = format!("invocation timed out after {}ms", after.as_millis());
+                let _ = tx
+                    .send(InvocationStreamEvent::failed(
+                        InvocationStatus::TimedOut,
+                        message,
+                    ))
+                    .await;
+                (InvocationStatus::TimedOut, None)
+            }
+        };
+        if let Err(e) = record_invocation(
+            &pool,
+            id,
+            user_id,
+            &payload,
+            output.as_deref(),
+            status,
+            timeout,
+        )
+        .await
+        {
+            error!(?e, "failed to record invocation");
+        }
+    });
+
+    let stream = ReceiverStream::new(rx).filter_map(move |event| async move {
+        let message = InvocationStreamMessage {
+            server_id: id,
+            event,
+        };
+        match Event::default().json_data(&message) {
+            Ok(event) => Some(Ok(event)),
+            Err(err) => {
+                tracing::error!(?err, "failed to serialize invocation event");
+                None
+            }
+        }
+    });
+    Ok(Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default()))
+}
+
+#[derive(Deserialize)]
+pub struct InvocationCacheUpdate {
+    /// `null` turns the cache off for this server.
+    pub ttl_secs: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct InvocationCacheView {
+    pub enabled: bool,
+    pub ttl_secs: Option<i32>,
+}
+
+/// Opt a server into invocation result caching, or out again with `null`.
+pub async fn update_invocation_cache(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<InvocationCacheUpdate>,
+) -> AppResult<Json<InvocationCacheView>> {
+    if payload.ttl_secs.is_some_and(|ttl| ttl <= 0) {
+        return Err(AppError::BadRequest(
+            "ttl_secs must be a positive number of seconds".into(),
+        ));
     }
+    let updated = sqlx::query(
+        "UPDATE mcp_servers SET invocation_cache_ttl_secs = $3 WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(payload.ttl_secs)
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error updating invocation cache");
+        AppError::Db(e)
+    })?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    INVOCATION_CACHE.invalidate_server(id);
+    Ok(Json(InvocationCacheView {
+        enabled: payload.ttl_secs.is_some(),
+        ttl_secs: payload.ttl_secs,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ProxyLimitsUpdate {
+    #[serde(default)]
+    pub max_request_bytes: Option<i64>,
+    #[serde(default)]
+    pub max_response_bytes: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ProxyLimitsView {
+    pub max_request_bytes: usize,
+    pub max_response_bytes: usize,
+    pub request_override: Option<i64>,
+    pub response_override: Option<i64>,
+}
+
+/// Set or clear (with `null`) the per-server proxy body limits.
+pub async fn update_proxy_limits(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<ProxyLimitsUpdate>,
+) -> AppResult<Json<ProxyLimitsView>> {
+    if [payload.max_request_bytes, payload.max_response_bytes]
+        .iter()
+        .flatten()
+        .any(|value| *value <= 0)
+    {
+        return Err(AppError::BadRequest(
+            "proxy limits must be positive byte counts".into(),
+        ));
+    }
+    let rec = sqlx::query(
+        "UPDATE mcp_servers SET proxy_max_request_bytes = $3, proxy_max_response_bytes = $4 \
+         WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL \
+         RETURNING proxy_max_request_bytes, proxy_max_response_bytes",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(payload.max_request_bytes)
+    .bind(payload.max_response_bytes)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error updating proxy limits");
+        AppError::Db(e)
+    })?;
+    let Some(rec) = rec else {
+        return Err(AppError::NotFound);
+    };
+    let limits = proxy_limits_from_row(&rec);
+    Ok(Json(ProxyLimitsView {
+        max_request_bytes: limits.max_request_bytes,
+        max_response_bytes: limits.max_response_bytes,
+        request_override: rec.try_get("proxy_max_request_bytes").ok().flatten(),
+        response_override: rec.try_get("proxy_max_response_bytes").ok().flatten(),
+    }))
+}
+
+/// Set or clear (with `null`) the server's request/response transforms. The config is
+/// validated before it is stored, and the server's cached results are dropped.
+pub async fn update_proxy_transforms(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<Option<serde_json::Value>>,
+) -> AppResult<Json<Option<ProxyTransforms>>> {
+    let transforms = payload
+        .map(ProxyTransforms::from_value)
+        .transpose()
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+        .filter(|transforms| !transforms.is_empty());
+    let stored = transforms
+        .as_ref()
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| AppError::Message(e.to_string()))?;
+    let updated =
+        sqlx::query("UPDATE mcp_servers SET proxy_transforms = $3 WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL")
+            .bind(id)
+            .bind(user_id)
+            .bind(stored)
+            .execute(&pool)
+            .await
+            .map_err(|e| {
+                error!(?e, "DB error updating proxy transforms");
+                AppError::Db(e)
+            })?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    INVOCATION_CACHE.invalidate_server(id);
+    Ok(Json(transforms))
+}
+
+/// Report the proxy circuit breaker state for a server.
+pub async fn server_health(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+) -> AppResult<Json<BreakerSnapshot>> {
+    let owned: Option<i32> = sqlx::query_scalar(
+        "SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
+    if owned.is_none() {
+        return Err(AppError::NotFound);
+    }
+    Ok(Json(
+        proxy::PROXY_BREAKERS.snapshot(id, std::time::Instant::now()),
+    ))
 }
 
 /// Return the stored MCP manifest for a server if available.
@@ -1299,15 +1974,17 @@ pub async fn get_manifest(
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
 ) -> AppResult<Json<serde_json::Value>> {
-    let rec = sqlx::query("SELECT manifest FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            AppError::Db(e)
-        })?;
+    let rec = sqlx::query(
+        "SELECT manifest FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        AppError::Db(e)
+    })?;
     let Some(rec) = rec else {
         return Err(AppError::NotFound);
     };
@@ -1325,7 +2002,7 @@ pub async fn client_config(
     Path(id): Path<i32>,
 ) -> AppResult<Json<serde_json::Value>> {
     let row =
-        sqlx::query("SELECT api_key, manifest FROM mcp_servers WHERE id = $1 AND owner_id = $2")
+        sqlx::query("SELECT api_key, manifest FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL")
             .bind(id)
             .bind(user_id)
             .fetch_optional(&pool)
@@ -1372,31 +2049,89 @@ pub async fn invoke_server_internal(
     id: i32,
     payload: &serde_json::Value,
 ) -> Result<serde_json::Value, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT api_key FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| {
-            error!(?e, "DB error verifying server ownership");
-            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-        })?;
+    let rec = sqlx::query(
+        "SELECT api_key, proxy_max_request_bytes, proxy_max_response_bytes, proxy_transforms \
+         FROM mcp_servers WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying server ownership");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
     let Some(rec) = rec else {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     };
     let api_key: String = rec.get("api_key");
-    let client = reqwest::Client::new();
-    match client
+    let limits = proxy_limits_from_row(&rec);
+    let transform_error = |err: TransformError| {
+        (
+            StatusCode::BAD_GATEWAY,
+            format!("proxy transform failed: {err}"),
+        )
+    };
+    let transforms = proxy_transforms_from_row(&rec).map_err(transform_error)?;
+    let request_size = serde_json::to_vec(payload).map(|v| v.len()).unwrap_or(0);
+    if request_size > limits.max_request_bytes {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("request body exceeded {} bytes", limits.max_request_bytes),
+        ));
+    }
+    let timeout = invocations::default_timeout();
+    let request = UPSTREAM_CLIENTS
+        .client(id)
         .post(format!("http://mcp-server-{id}:8080/invoke"))
         .header("Authorization", format!("Bearer {}", api_key))
-        .json(payload)
-        .send()
-        .await
-    {
-        Ok(resp) => match resp.json::<serde_json::Value>().await {
-            Ok(json) => Ok(json),
-            Err(_) => Err((StatusCode::BAD_GATEWAY, "Invalid response".into())),
-        },
-        Err(_) => Err((StatusCode::BAD_GATEWAY, "Container unreachable".into())),
+        .header(INVOCATION_TIMEOUT_HEADER, timeout.as_millis().to_string())
+        .json(
+            &transforms
+                .transform_request(payload)
+                .map_err(transform_error)?,
+        );
+    let exchange = async {
+        let resp = proxy::send_guarded(pool, id, request).await?;
+        let succeeded = resp.status().is_success();
+        Ok::<_, UpstreamSendError>((
+            succeeded,
+            proxy::read_response_limited(resp, limits.max_response_bytes).await,
+        ))
+    };
+    match with_deadline(timeout, exchange).await {
+        Ok(Ok((succeeded, Ok(bytes)))) => {
+            let bytes = if succeeded {
+                transforms
+                    .transform_response(bytes)
+                    .map_err(transform_error)?
+            } else {
+                bytes
+            };
+            serde_json::from_slice(&bytes)
+                .map_err(|_| (StatusCode::BAD_GATEWAY, "Invalid response".into()))
+        }
+        Ok(Ok((_, Err(ProxyBodyError::TooLarge { limit })))) => Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("upstream response exceeded {limit} bytes"),
+        )),
+        Ok(Ok((_, Err(_)))) => Err((StatusCode::BAD_GATEWAY, "Invalid response".into())),
+        Ok(Err(UpstreamSendError::CircuitOpen(open))) => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "upstream circuit open; retry in {}s",
+                open.retry_after.as_secs()
+            ),
+        )),
+        Ok(Err(UpstreamSendError::Unreachable(_))) => {
+            Err((StatusCode::BAD_GATEWAY, "Container unreachable".into()))
+        }
+        Err(InvocationTimedOut { after }) => {
+            proxy::record_cancelled(pool, id).await;
+            Err((
+                StatusCode::GATEWAY_TIMEOUT,
+                format!("invocation timed out after {}ms", after.as_millis()),
+            ))
+        }
     }
 }
@@ -0,0 +1,231 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::remediation::payload_schema::{self, SchemaViolation};
+
+// key: server-config-schema -> per-server_type validation of mcp_servers.config
+
+/// Server types launched from a marketplace image; their settings reach the container as
+/// `CFG_*` environment variables, so only the shared runtime keys are checked.
+const IMAGE_SERVER_TYPES: &[&str] = &["PostgreSQL", "Slack", "PDF Parser", "Notion", "Router"];
+
+/// Outcome of checking a config against its server type. Errors block the save; warnings are
+/// returned alongside the saved server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConfigValidation {
+    pub errors: Vec<SchemaViolation>,
+    pub warnings: Vec<SchemaViolation>,
+}
+
+impl ConfigValidation {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Keys every runtime reads, whatever the server type.
+fn shared_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "image": { "type": "string", "minLength": 1 },
+            "repo_url": { "type": "string", "pattern": "^(https?|git|ssh)://|^git@" },
+            "branch": { "type": "string", "minLength": 1 },
+            "runtime": { "enum": ["docker", "kubernetes", "virtual-machine"] },
+            "cpu_limit": { "type": "number", "exclusiveMinimum": 0 },
+            "memory_limit": { "type": "integer", "minimum": 1 },
+            "attestation": { "type": "object" }
+        }
+    })
+}
+
+/// Schema for `server_type`, or `None` when the type has none of its own.
+fn schema_for(server_type: &str) -> Option<Value> {
+    let mut schema = shared_schema();
+    match server_type {
+        "virtual-machine" => {
+            // the policy default is a container image, so a VM must name the image it boots
+            schema["required"] = json!(["vm"]);
+            schema["properties"]["vm"] = json!({
+                "type": "object",
+                "required": ["image"],
+                "properties": {
+                    "image": { "type": "string", "minLength": 1 },
+                    "resource_profile": { "type": "string", "minLength": 1 },
+                    "memory_mib": { "type": "integer", "minimum": 1 },
+                    "vcpu_count": { "type": "integer", "minimum": 1 },
+                    "isolation_tier": { "type": "string", "minLength": 1 }
+                }
+            });
+        }
+        "Custom" => {}
+        other if IMAGE_SERVER_TYPES.contains(&other) => {}
+        _ => return None,
+    }
+    Some(schema)
+}
+
+/// Checks `config` against the schema for `server_type`. A missing config is checked as `{}`.
+/// Keys outside the schema are warnings rather than errors, since runtimes pass them through
+/// to the server as `CFG_*` variables.
+pub fn validate_config(server_type: &str, config: Option<&Value>) -> ConfigValidation {
+    let empty = Value::Object(Map::new());
+    let config = config.unwrap_or(&empty);
+    let mut validation = ConfigValidation::default();
+    let schema = match schema_for(server_type) {
+        Some(schema) => schema,
+        None => {
+            validation.warnings.push(SchemaViolation {
+                path: String::new(),
+                message: format!(
+                    "server type `{server_type}` has no config schema; only shared keys were checked"
+                ),
+            });
+            shared_schema()
+        }
+    };
+    validation.errors = payload_schema::validate(&schema, config);
+    if server_type == "Custom"
+        && config.is_object()
+        && config.get("image").is_none()
+        && config.get("repo_url").is_none()
+    {
+        validation.errors.push(SchemaViolation {
+            path: String::new(),
+            message: "a `Custom` server needs an `image` or a `repo_url`".into(),
+        });
+    }
+    collect_unknown(&schema, config, "", &mut validation.warnings);
+    validation
+}
+
+fn collect_unknown(schema: &Value, config: &Value, at: &str, out: &mut Vec<SchemaViolation>) {
+    let (Some(properties), Some(object)) = (
+        schema.get("properties").and_then(Value::as_object),
+        config.as_object(),
+    ) else {
+        return;
+    };
+    for (name, value) in object {
+        let path = format!("{at}/{name}");
+        match properties.get(name) {
+            Some(property) => collect_unknown(property, value, &path, out),
+            None if at.is_empty() => out.push(SchemaViolation {
+                path,
+                message: format!(
+                    "unknown key; passed to the server as `CFG_{}`",
+                    name.to_uppercase()
+                ),
+            }),
+            None => out.push(SchemaViolation {
+                path,
+                message: "unknown key; ignored".into(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_config_passes_for_every_type() {
+        let cases = [
+            (
+                "virtual-machine",
+                json!({ "vm": { "image": "ubuntu-22.04.qcow2", "vcpu_count": 2, "memory_mib": 2048 } }),
+            ),
+            (
+                "Custom",
+                json!({ "repo_url": "https://github.com/acme/tool.git", "branch": "main" }),
+            ),
+            (
+                "Custom",
+                json!({ "image": "ghcr.io/acme/tool:1", "cpu_limit": 0.5 }),
+            ),
+            (
+                "PostgreSQL",
+                json!({ "runtime": "kubernetes", "memory_limit": 512 }),
+            ),
+            ("Slack", json!({})),
+            ("PDF Parser", json!({})),
+            ("Notion", json!({})),
+            ("Router", json!({})),
+        ];
+        for (server_type, config) in cases {
+            let validation = validate_config(server_type, Some(&config));
+            assert_eq!(
+                validation,
+                ConfigValidation::default(),
+                "{server_type}: {config}"
+            );
+        }
+        assert!(validate_config("Slack", None).is_valid());
+    }
+
+    #[test]
+    fn missing_required_fields_are_rejected() {
+        let validation = validate_config("virtual-machine", None);
+        assert!(!validation.is_valid());
+        assert_eq!(
+            validation.errors,
+            vec![SchemaViolation {
+                path: String::new(),
+                message: "missing required property `vm`".into(),
+            }]
+        );
+
+        let validation = validate_config(
+            "virtual-machine",
+            Some(&json!({ "vm": { "vcpu_count": 0 } })),
+        );
+        let messages: Vec<(&str, &str)> = validation
+            .errors
+            .iter()
+            .map(|error| (error.path.as_str(), error.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("/vm", "missing required property `image`"),
+                ("/vm/vcpu_count", "must be at least 1"),
+            ]
+        );
+
+        assert!(!validate_config("Custom", Some(&json!({ "branch": "main" }))).is_valid());
+        let validation = validate_config("Router", Some(&json!({ "repo_url": "not a url" })));
+        assert_eq!(validation.errors.len(), 1);
+        assert_eq!(validation.errors[0].path, "/repo_url");
+        assert!(!validate_config("Router", Some(&json!(["image"]))).is_valid());
+    }
+
+    #[test]
+    fn unknown_fields_only_warn() {
+        let validation = validate_config(
+            "virtual-machine",
+            Some(&json!({ "vm": { "image": "base.qcow2", "disks": 2 }, "api_token": "x" })),
+        );
+        assert!(validation.is_valid());
+        let warnings: Vec<(&str, &str)> = validation
+            .warnings
+            .iter()
+            .map(|warning| (warning.path.as_str(), warning.message.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (
+                    "/api_token",
+                    "unknown key; passed to the server as `CFG_API_TOKEN`"
+                ),
+                ("/vm/disks", "unknown key; ignored"),
+            ]
+        );
+
+        let validation = validate_config("homegrown", Some(&json!({ "cpu_limit": "lots" })));
+        assert_eq!(validation.errors.len(), 1);
+        assert_eq!(validation.warnings.len(), 1);
+        assert_eq!(validation.warnings[0].path, "");
+    }
+}
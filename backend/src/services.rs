@@ -1,3 +1,4 @@
+use crate::db::mcp_servers;
 use crate::extractor::AuthUser;
 use axum::{
     extract::{Extension, Path},
@@ -8,11 +9,16 @@ use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use tracing::error;
 
+pub mod health;
+
+use health::{aggregate_health, group_dependencies, HealthRollup, ServiceHealth, ServiceNode};
+
 #[derive(Serialize)]
 pub struct Service {
     pub id: i32,
     pub service_type: String,
     pub config: Option<serde_json::Value>,
+    pub health_status: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -32,20 +38,17 @@ pub async fn list_services(
     AuthUser { user_id, .. }: AuthUser,
     Path(server_id): Path<i32>,
 ) -> Result<Json<Vec<Service>>, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     let rows = sqlx::query(
-        "SELECT id, service_type, config, created_at FROM service_integrations WHERE server_id = $1 ORDER BY id",
+        "SELECT id, service_type, config, health_status, created_at FROM service_integrations WHERE server_id = $1 ORDER BY id",
     )
     .bind(server_id)
     .fetch_all(&pool)
@@ -60,6 +63,7 @@ pub async fn list_services(
             id: r.get("id"),
             service_type: r.get("service_type"),
             config: r.try_get("config").ok(),
+            health_status: r.get("health_status"),
             created_at: r.get("created_at"),
         })
         .collect();
@@ -72,16 +76,13 @@ pub async fn create_service(
     Path(server_id): Path<i32>,
     Json(payload): Json<CreateService>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     sqlx::query(
@@ -105,16 +106,13 @@ pub async fn update_service(
     Path((server_id, service_id)): Path<(i32, i32)>,
     Json(payload): Json<UpdateService>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     let result =
@@ -139,16 +137,13 @@ pub async fn delete_service(
     AuthUser { user_id, .. }: AuthUser,
     Path((server_id, service_id)): Path<(i32, i32)>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let rec = sqlx::query("SELECT id FROM mcp_servers WHERE id = $1 AND owner_id = $2")
-        .bind(server_id)
-        .bind(user_id)
-        .fetch_optional(&pool)
+    let owned = mcp_servers::is_live_owned_by(&pool, server_id, user_id)
         .await
         .map_err(|e| {
             error!(?e, "DB error while verifying server owner");
             (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
         })?;
-    if rec.is_none() {
+    if !owned {
         return Err((StatusCode::NOT_FOUND, "Server not found".into()));
     }
     let result = sqlx::query("DELETE FROM service_integrations WHERE id = $1 AND server_id = $2")
@@ -165,3 +160,247 @@ pub async fn delete_service(
     }
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[derive(Deserialize)]
+pub struct ReportServiceHealth {
+    pub status: ServiceHealth,
+}
+
+#[derive(Deserialize)]
+pub struct SetServiceDependencies {
+    pub depends_on: Vec<i32>,
+}
+
+async fn ensure_owned_service(
+    pool: &PgPool,
+    user_id: i32,
+    server_id: i32,
+    service_id: i32,
+) -> Result<(), (StatusCode, String)> {
+    let rec = sqlx::query(
+        r#"
+        SELECT s.id
+        FROM service_integrations s
+        JOIN mcp_servers m ON m.id = s.server_id
+        WHERE s.id = $1 AND s.server_id = $2 AND m.owner_id = $3 AND m.deleted_at IS NULL
+        "#,
+    )
+    .bind(service_id)
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error while verifying service owner");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    if rec.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Service not found".into()));
+    }
+    Ok(())
+}
+
+pub async fn report_service_health(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path((server_id, service_id)): Path<(i32, i32)>,
+    Json(payload): Json<ReportServiceHealth>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    ensure_owned_service(&pool, user_id, server_id, service_id).await?;
+    sqlx::query(
+        "UPDATE service_integrations SET health_status = $1, health_checked_at = NOW() WHERE id = $2",
+    )
+    .bind(payload.status.as_str())
+    .bind(service_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error recording service health");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Replaces the dependency set; dependencies may live on any server the caller owns.
+pub async fn set_service_dependencies(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path((server_id, service_id)): Path<(i32, i32)>,
+    Json(mut payload): Json<SetServiceDependencies>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    ensure_owned_service(&pool, user_id, server_id, service_id).await?;
+    payload.depends_on.sort_unstable();
+    payload.depends_on.dedup();
+    if payload.depends_on.contains(&service_id) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "A service cannot depend on itself".into(),
+        ));
+    }
+    let owned: i64 = sqlx::query(
+        r#"
+        SELECT COUNT(*) AS owned
+        FROM service_integrations s
+        JOIN mcp_servers m ON m.id = s.server_id
+        WHERE s.id = ANY($1) AND m.owner_id = $2
+        "#,
+    )
+    .bind(&payload.depends_on)
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error verifying service dependencies");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?
+    .get("owned");
+    if owned as usize != payload.depends_on.len() {
+        return Err((StatusCode::BAD_REQUEST, "Unknown dependency service".into()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!(?e, "DB error starting dependency update");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    sqlx::query("DELETE FROM service_dependencies WHERE service_id = $1")
+        .bind(service_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!(?e, "DB error clearing service dependencies");
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })?;
+    sqlx::query(
+        "INSERT INTO service_dependencies (service_id, depends_on_id) SELECT $1, UNNEST($2::INT[])",
+    )
+    .bind(service_id)
+    .bind(&payload.depends_on)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error inserting service dependencies");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    tx.commit().await.map_err(|e| {
+        error!(?e, "DB error committing service dependencies");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn load_health_rollup(pool: &PgPool, user_id: i32) -> Result<HealthRollup, sqlx::Error> {
+    let services = sqlx::query(
+        r#"
+        SELECT s.id, s.server_id, s.service_type, s.health_status
+        FROM service_integrations s
+        JOIN mcp_servers m ON m.id = s.server_id
+        WHERE m.owner_id = $1
+        ORDER BY s.id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    let edges = sqlx::query(
+        r#"
+        SELECT d.service_id, d.depends_on_id
+        FROM service_dependencies d
+        JOIN service_integrations s ON s.id = d.service_id
+        JOIN mcp_servers m ON m.id = s.server_id
+        WHERE m.owner_id = $1
+        ORDER BY d.service_id, d.depends_on_id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    let mut dependencies = group_dependencies(
+        edges
+            .into_iter()
+            .map(|r| (r.get("service_id"), r.get("depends_on_id"))),
+    );
+    let nodes = services
+        .into_iter()
+        .map(|r| {
+            let id: i32 = r.get("id");
+            ServiceNode {
+                id,
+                server_id: r.get("server_id"),
+                service_type: r.get("service_type"),
+                raw: ServiceHealth::parse(r.get("health_status")).unwrap_or(ServiceHealth::Unknown),
+                depends_on: dependencies.remove(&id).unwrap_or_default(),
+            }
+        })
+        .collect();
+    Ok(aggregate_health(nodes))
+}
+
+pub async fn services_health(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+) -> Result<Json<HealthRollup>, (StatusCode, String)> {
+    load_health_rollup(&pool, user_id)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!(?e, "DB error aggregating service health");
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn rollup_propagates_across_servers(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let user_id: i32 = sqlx::query(
+            "INSERT INTO users (email, password_hash) VALUES ('svc@example.com', 'x') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("id");
+        let mut service_ids = Vec::new();
+        for (name, status) in [("api", "healthy"), ("db", "down")] {
+            let server_id: i32 = sqlx::query(
+                "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, $2, 'generic', '{}', 'running', $2) RETURNING id",
+            )
+            .bind(user_id)
+            .bind(name)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get("id");
+            let service_id: i32 = sqlx::query(
+                "INSERT INTO service_integrations (server_id, service_type, health_status) VALUES ($1, $2, $3) RETURNING id",
+            )
+            .bind(server_id)
+            .bind(name)
+            .bind(status)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get("id");
+            service_ids.push(service_id);
+        }
+        sqlx::query("INSERT INTO service_dependencies (service_id, depends_on_id) VALUES ($1, $2)")
+            .bind(service_ids[0])
+            .bind(service_ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let rollup = load_health_rollup(&pool, user_id).await.unwrap();
+        assert_eq!(rollup.overall, ServiceHealth::Down);
+        let api = &rollup.services[0];
+        assert_eq!(api.raw_status, ServiceHealth::Healthy);
+        assert_eq!(api.effective_status, ServiceHealth::Degraded);
+        assert_eq!(api.impaired_dependencies, vec![service_ids[1]]);
+
+        let other = load_health_rollup(&pool, user_id + 1).await.unwrap();
+        assert!(other.services.is_empty());
+    }
+}
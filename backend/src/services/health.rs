@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// key: services-health -> dependency-aware health rollup
+
+/// Ordered from best to worst so the rollup can take the maximum.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceHealth {
+    Healthy,
+    Unknown,
+    Degraded,
+    Down,
+}
+
+impl ServiceHealth {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "healthy" => Some(Self::Healthy),
+            "unknown" => Some(Self::Unknown),
+            "degraded" => Some(Self::Degraded),
+            "down" => Some(Self::Down),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Unknown => "unknown",
+            Self::Degraded => "degraded",
+            Self::Down => "down",
+        }
+    }
+
+    fn is_impaired(self) -> bool {
+        self >= Self::Degraded
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceNode {
+    pub id: i32,
+    pub server_id: i32,
+    pub service_type: String,
+    pub raw: ServiceHealth,
+    pub depends_on: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceHealthEntry {
+    pub id: i32,
+    pub server_id: i32,
+    pub service_type: String,
+    pub raw_status: ServiceHealth,
+    pub effective_status: ServiceHealth,
+    pub depends_on: Vec<i32>,
+    /// Direct dependencies whose effective status is degraded or down.
+    pub impaired_dependencies: Vec<i32>,
+    pub in_cycle: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthRollup {
+    pub overall: ServiceHealth,
+    pub services: Vec<ServiceHealthEntry>,
+    /// Each dependency cycle as its sorted member ids.
+    pub cycles: Vec<Vec<i32>>,
+}
+
+/// Computes effective health: a service with an impaired dependency is at least
+/// degraded. Propagation runs to a fixed point, so cycles terminate and every
+/// member of an impaired cycle ends up degraded.
+pub fn aggregate_health(nodes: Vec<ServiceNode>) -> HealthRollup {
+    let index: HashMap<i32, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| (node.id, idx))
+        .collect();
+    let edges: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|node| {
+            node.depends_on
+                .iter()
+                .filter_map(|dep| index.get(dep).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut effective: Vec<ServiceHealth> = nodes.iter().map(|node| node.raw).collect();
+    loop {
+        let mut changed = false;
+        for (idx, deps) in edges.iter().enumerate() {
+            if effective[idx] < ServiceHealth::Degraded
+                && deps.iter().any(|dep| effective[*dep].is_impaired())
+            {
+                effective[idx] = ServiceHealth::Degraded;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let cycles = find_cycles(&edges);
+    let mut cycle_members = vec![false; nodes.len()];
+    let mut reported: Vec<Vec<i32>> = Vec::new();
+    for cycle in cycles {
+        for member in &cycle {
+            cycle_members[*member] = true;
+        }
+        let mut ids: Vec<i32> = cycle.iter().map(|member| nodes[*member].id).collect();
+        ids.sort_unstable();
+        reported.push(ids);
+    }
+    reported.sort();
+
+    let overall = effective
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(ServiceHealth::Healthy);
+    let services = nodes
+        .into_iter()
+        .enumerate()
+        .map(|(idx, node)| ServiceHealthEntry {
+            impaired_dependencies: node
+                .depends_on
+                .iter()
+                .copied()
+                .filter(|dep| {
+                    index
+                        .get(dep)
+                        .is_some_and(|pos| effective[*pos].is_impaired())
+                })
+                .collect(),
+            id: node.id,
+            server_id: node.server_id,
+            service_type: node.service_type,
+            raw_status: node.raw,
+            effective_status: effective[idx],
+            depends_on: node.depends_on,
+            in_cycle: cycle_members[idx],
+        })
+        .collect();
+
+    HealthRollup {
+        overall,
+        services,
+        cycles: reported,
+    }
+}
+
+/// Strongly connected components that form a cycle (more than one member, or a self-edge).
+fn find_cycles(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct Tarjan<'a> {
+        edges: &'a [Vec<usize>],
+        next_index: usize,
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        components: Vec<Vec<usize>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: usize) {
+            self.index[node] = Some(self.next_index);
+            self.lowlink[node] = self.next_index;
+            self.next_index += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+            for &next in &self.edges[node] {
+                match self.index[next] {
+                    None => {
+                        self.visit(next);
+                        self.lowlink[node] = self.lowlink[node].min(self.lowlink[next]);
+                    }
+                    Some(next_index) if self.on_stack[next] => {
+                        self.lowlink[node] = self.lowlink[node].min(next_index);
+                    }
+                    Some(_) => {}
+                }
+            }
+            if Some(self.lowlink[node]) == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 || self.edges[node].contains(&node) {
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges,
+        next_index: 0,
+        index: vec![None; edges.len()],
+        lowlink: vec![0; edges.len()],
+        on_stack: vec![false; edges.len()],
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+    for node in 0..edges.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components
+}
+
+/// Groups `(service_id, depends_on_id)` rows by service.
+pub fn group_dependencies(rows: impl IntoIterator<Item = (i32, i32)>) -> BTreeMap<i32, Vec<i32>> {
+    let mut grouped: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for (service_id, depends_on) in rows {
+        grouped.entry(service_id).or_default().push(depends_on);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: i32, raw: ServiceHealth, depends_on: &[i32]) -> ServiceNode {
+        ServiceNode {
+            id,
+            server_id: 1,
+            service_type: format!("svc-{id}"),
+            raw,
+            depends_on: depends_on.to_vec(),
+        }
+    }
+
+    fn effective(rollup: &HealthRollup, id: i32) -> ServiceHealth {
+        rollup
+            .services
+            .iter()
+            .find(|entry| entry.id == id)
+            .unwrap()
+            .effective_status
+    }
+
+    #[test]
+    fn healthy_chain_stays_healthy() {
+        let rollup = aggregate_health(vec![
+            node(1, ServiceHealth::Healthy, &[2]),
+            node(2, ServiceHealth::Healthy, &[3]),
+            node(3, ServiceHealth::Healthy, &[]),
+        ]);
+        assert_eq!(rollup.overall, ServiceHealth::Healthy);
+        assert!(rollup.cycles.is_empty());
+        assert!(rollup
+            .services
+            .iter()
+            .all(|entry| entry.effective_status == ServiceHealth::Healthy
+                && entry.impaired_dependencies.is_empty()));
+    }
+
+    #[test]
+    fn down_dependency_degrades_everything_upstream() {
+        let rollup = aggregate_health(vec![
+            node(1, ServiceHealth::Healthy, &[2]),
+            node(2, ServiceHealth::Healthy, &[3]),
+            node(3, ServiceHealth::Down, &[]),
+            node(4, ServiceHealth::Unknown, &[]),
+        ]);
+        assert_eq!(effective(&rollup, 3), ServiceHealth::Down);
+        assert_eq!(effective(&rollup, 2), ServiceHealth::Degraded);
+        assert_eq!(effective(&rollup, 1), ServiceHealth::Degraded);
+        assert_eq!(effective(&rollup, 4), ServiceHealth::Unknown);
+        let top = rollup.services.iter().find(|entry| entry.id == 1).unwrap();
+        assert_eq!(top.raw_status, ServiceHealth::Healthy);
+        assert_eq!(top.impaired_dependencies, vec![2]);
+        assert_eq!(rollup.overall, ServiceHealth::Down);
+    }
+
+    #[test]
+    fn cycles_are_reported_and_propagation_terminates() {
+        let rollup = aggregate_health(vec![
+            node(1, ServiceHealth::Healthy, &[2]),
+            node(2, ServiceHealth::Healthy, &[3]),
+            node(3, ServiceHealth::Degraded, &[1]),
+            node(4, ServiceHealth::Healthy, &[1]),
+            node(5, ServiceHealth::Healthy, &[]),
+        ]);
+        assert_eq!(rollup.cycles, vec![vec![1, 2, 3]]);
+        for id in [1, 2, 3, 4] {
+            assert_eq!(effective(&rollup, id), ServiceHealth::Degraded);
+        }
+        assert_eq!(effective(&rollup, 5), ServiceHealth::Healthy);
+        let in_cycle: Vec<i32> = rollup
+            .services
+            .iter()
+            .filter(|entry| entry.in_cycle)
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(in_cycle, vec![1, 2, 3]);
+    }
+}
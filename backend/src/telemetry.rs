@@ -2,6 +2,9 @@ use serde::Serialize;
 use serde_json::Value;
 use thiserror::Error;
 
+pub mod otel;
+pub mod tenant;
+
 #[derive(Debug, Serialize, Clone)]
 pub struct Metric {
     pub id: i32,
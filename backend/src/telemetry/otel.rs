@@ -0,0 +1,244 @@
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::{global, Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{self as sdktrace, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::{Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+// key: telemetry-otel -> otlp span export and w3c trace context propagation
+
+const INSTRUMENTATION_NAME: &str = "mcp-host";
+
+/// Keeps the OTLP pipeline alive; dropping it flushes buffered spans.
+#[derive(Default)]
+pub struct TracingGuard {
+    provider: Option<TracerProvider>,
+}
+
+impl TracingGuard {
+    pub fn exporting(&self) -> bool {
+        self.provider.is_some()
+    }
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            for result in provider.force_flush() {
+                if let Err(error) = result {
+                    eprintln!("failed to flush OTLP spans: {error}");
+                }
+            }
+        }
+    }
+}
+
+/// Batches spans to `{endpoint}/v1/traces` over OTLP/HTTP. Must be called inside a
+/// Tokio runtime, which drives the batch exporter.
+pub fn tracer_provider(endpoint: &str, service_name: &str) -> Result<TracerProvider, TraceError> {
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(endpoint.trim_end_matches('/'))
+        .build_span_exporter()?;
+    Ok(TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_config(
+            sdktrace::config().with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )])),
+        )
+        .build())
+}
+
+/// Installs the global subscriber: JSON logs as before, plus an OpenTelemetry layer
+/// and the W3C `traceparent` propagator when `endpoint` is set.
+pub fn init_subscriber(endpoint: Option<&str>, service_name: &str) -> TracingGuard {
+    let provider = endpoint.map(|endpoint| tracer_provider(endpoint, service_name));
+    let (provider, setup_error) = match provider {
+        Some(Ok(provider)) => (Some(provider), None),
+        Some(Err(error)) => (None, Some(error)),
+        None => (None, None),
+    };
+    let otel_layer = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(INSTRUMENTATION_NAME))
+    });
+    tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(fmt::layer().json())
+        .with(otel_layer)
+        .init();
+
+    if let Some(error) = setup_error {
+        tracing::warn!(%error, "OTLP exporter setup failed; continuing without trace export");
+    }
+    if provider.is_some() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+    }
+    TracingGuard { provider }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Remote parent carried by incoming `traceparent`/`tracestate` headers, if any.
+pub fn extract_context(headers: &HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// Headers continuing the current span's trace; empty when export is disabled.
+pub fn current_trace_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
+    });
+    headers
+}
+
+/// Attaches the current trace context to an outgoing request.
+pub fn propagate(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    request.headers(current_trace_headers())
+}
+
+/// Middleware opening a span per request that continues the caller's trace.
+pub async fn trace_requests<B>(request: Request<B>, next: Next<B>) -> Response {
+    let span = tracing::info_span!(
+        "http.request",
+        http.method = %request.method(),
+        http.target = %request.uri().path(),
+    );
+    span.set_parent(extract_context(request.headers()));
+    next.run(request).instrument(span).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use axum::routing::post;
+    use axum::{Extension, Router};
+    use opentelemetry::propagation::TextMapPropagator;
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::{Arc, Mutex};
+
+    type Received = Arc<Mutex<Vec<Bytes>>>;
+
+    async fn collect(Extension(received): Extension<Received>, body: Bytes) {
+        received.lock().unwrap().push(body);
+    }
+
+    fn stub_collector() -> (String, Received) {
+        let received = Received::default();
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/v1/traces", post(collect))
+            .layer(Extension(received.clone()));
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        (endpoint, received)
+    }
+
+    fn incoming(traceparent: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", HeaderValue::from_str(traceparent).unwrap());
+        headers
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn enabled_export_continues_incoming_trace() {
+        let (endpoint, received) = stub_collector();
+        let provider = tracer_provider(&endpoint, "mcp-host-test").unwrap();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_opentelemetry::layer().with_tracer(provider.tracer(INSTRUMENTATION_NAME)),
+        );
+        let propagator = TraceContextPropagator::new();
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let headers = incoming(&format!("00-{trace_id}-00f067aa0ba902b7-01"));
+
+        let outgoing = tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("proxy.upstream");
+            span.set_parent(propagator.extract(&HeaderExtractor(&headers)));
+            let _entered = span.enter();
+            let mut outgoing = HeaderMap::new();
+            propagator.inject_context(
+                &Span::current().context(),
+                &mut HeaderInjector(&mut outgoing),
+            );
+            outgoing
+        });
+        let continued = outgoing["traceparent"].to_str().unwrap();
+        assert!(continued.starts_with(&format!("00-{trace_id}-")));
+        assert!(
+            !continued.contains("00f067aa0ba902b7"),
+            "child span gets its own id"
+        );
+
+        tokio::task::spawn_blocking(move || provider.force_flush())
+            .await
+            .unwrap()
+            .into_iter()
+            .for_each(|result| result.unwrap());
+        let batches = received.lock().unwrap().clone();
+        assert_eq!(batches.len(), 1);
+        let raw_trace_id: Vec<u8> = (0..16)
+            .map(|i| u8::from_str_radix(&trace_id[i * 2..i * 2 + 2], 16).unwrap())
+            .collect();
+        assert!(batches[0]
+            .windows(16)
+            .any(|window| window == raw_trace_id.as_slice()));
+        assert!(batches[0]
+            .windows(14)
+            .any(|window| window == b"proxy.upstream"));
+    }
+
+    #[tokio::test]
+    async fn disabled_export_adds_no_headers() {
+        let (_endpoint, received) = stub_collector();
+        let headers = incoming("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01");
+        let subscriber = tracing_subscriber::registry().with(fmt::layer().json());
+        let outgoing = tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("proxy.upstream");
+            span.set_parent(extract_context(&headers));
+            let _entered = span.enter();
+            current_trace_headers()
+        });
+        assert!(outgoing.is_empty());
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
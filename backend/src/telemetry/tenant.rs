@@ -0,0 +1,124 @@
+// key: telemetry-tenant-metrics -> org_id labelled prometheus series
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use sqlx::PgPool;
+
+use crate::config;
+
+pub const RUNS_ENQUEUED_TOTAL: &str = "mcp_remediation_runs_enqueued_total";
+pub const RUNS_COMPLETED_TOTAL: &str = "mcp_remediation_runs_completed_total";
+pub const RUNS_FAILED_TOTAL: &str = "mcp_remediation_runs_failed_total";
+pub const BUILD_DURATION_SECONDS: &str = "mcp_build_duration_seconds";
+pub const QUOTA_DENIALS_TOTAL: &str = "mcp_quota_denials_total";
+
+pub const ORG_LABEL: &str = "org_id";
+/// Label for orgs without a series of their own.
+pub const OTHER_ORG: &str = "other";
+/// Label for work that belongs to no organization.
+pub const NO_ORG: &str = "none";
+
+/// Decides which `org_id` label a sample carries. An org gets its own label once it has
+/// produced `min_events` samples, as long as fewer than `max_orgs` orgs already have one.
+/// Until then, and for every org past the cap, samples are labelled `other`. Admission is
+/// permanent so an org's series never moves between labels.
+#[derive(Debug)]
+pub struct OrgLabeler {
+    max_orgs: usize,
+    min_events: u64,
+    state: Mutex<LabelerState>,
+}
+
+#[derive(Debug, Default)]
+struct LabelerState {
+    admitted: HashSet<i32>,
+    pending: HashMap<i32, u64>,
+}
+
+impl OrgLabeler {
+    pub fn new(max_orgs: usize, min_events: u64) -> Self {
+        Self {
+            max_orgs,
+            min_events: min_events.max(1),
+            state: Mutex::new(LabelerState::default()),
+        }
+    }
+
+    pub fn label(&self, org_id: Option<i32>) -> String {
+        let Some(org_id) = org_id else {
+            return NO_ORG.to_string();
+        };
+        let mut state = self.state.lock().expect("org labeler mutex poisoned");
+        if state.admitted.contains(&org_id) {
+            return org_id.to_string();
+        }
+        if state.admitted.len() >= self.max_orgs {
+            state.pending.remove(&org_id);
+            return OTHER_ORG.to_string();
+        }
+        let seen = state.pending.entry(org_id).or_insert(0);
+        *seen += 1;
+        if *seen >= self.min_events {
+            state.pending.remove(&org_id);
+            state.admitted.insert(org_id);
+            org_id.to_string()
+        } else {
+            OTHER_ORG.to_string()
+        }
+    }
+}
+
+/// Emits the tenant-labelled series through the process-wide `metrics` recorder, which
+/// `/metrics` renders.
+#[derive(Debug)]
+pub struct TenantMetrics {
+    labeler: OrgLabeler,
+}
+
+impl TenantMetrics {
+    pub fn new(labeler: OrgLabeler) -> Self {
+        Self { labeler }
+    }
+
+    pub fn run_enqueued(&self, org_id: Option<i32>) {
+        metrics::increment_counter!(RUNS_ENQUEUED_TOTAL, ORG_LABEL => self.labeler.label(org_id));
+    }
+
+    pub fn run_completed(&self, org_id: Option<i32>) {
+        metrics::increment_counter!(RUNS_COMPLETED_TOTAL, ORG_LABEL => self.labeler.label(org_id));
+    }
+
+    pub fn run_failed(&self, org_id: Option<i32>) {
+        metrics::increment_counter!(RUNS_FAILED_TOTAL, ORG_LABEL => self.labeler.label(org_id));
+    }
+
+    pub fn build_duration(&self, org_id: Option<i32>, seconds: f64) {
+        metrics::histogram!(BUILD_DURATION_SECONDS, seconds, ORG_LABEL => self.labeler.label(org_id));
+    }
+
+    pub fn quota_denied(&self, org_id: i32) {
+        metrics::increment_counter!(QUOTA_DENIALS_TOTAL, ORG_LABEL => self.labeler.label(Some(org_id)));
+    }
+}
+
+pub static TENANT_METRICS: Lazy<TenantMetrics> = Lazy::new(|| {
+    TenantMetrics::new(OrgLabeler::new(
+        *config::TENANT_METRICS_MAX_ORGS,
+        *config::TENANT_METRICS_MIN_EVENTS,
+    ))
+});
+
+/// Organization owning a server. Lookup failures are logged and treated as no org so a
+/// metric is never lost to them.
+pub async fn server_org(pool: &PgPool, server_id: i32) -> Option<i32> {
+    sqlx::query_scalar::<_, Option<i32>>("SELECT organization_id FROM mcp_servers WHERE id = $1")
+        .bind(server_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!(?err, %server_id, "failed to resolve server org for metrics");
+            None
+        })
+        .flatten()
+}
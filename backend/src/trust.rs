@@ -17,13 +17,17 @@ use tracing::{debug, error, warn};
 
 use crate::{
     db::runtime_vm_trust_history::{history_for_instance as history_for_vm, RuntimeVmTrustEvent},
-    db::runtime_vm_trust_registry::{apply_transition, ApplyRuntimeVmTrustTransition},
+    db::runtime_vm_trust_registry::{
+        apply_transition, list_states_for_server, ApplyRuntimeVmTrustTransition, InstanceTrustState,
+    },
     error::{AppError, AppResult},
     evaluations::scheduler::{self, TrustTransitionSignal},
     extractor::AuthUser,
-    job_queue::{self, Job},
+    job_queue::{self, QueuedJob},
 };
 
+pub mod replay;
+
 const TRUST_CHANNEL: &str = "runtime_vm_trust_transition";
 
 // key: trust-control -> event-channel
@@ -92,6 +96,13 @@ pub struct TrustHistoryResponse {
     pub events: Vec<RuntimeVmTrustEvent>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ServerTrustStatesResponse {
+    pub server_id: i32,
+    pub server_name: String,
+    pub instances: Vec<InstanceTrustState>,
+}
+
 #[derive(Debug, Default, Deserialize)]
 pub struct TrustRegistryQuery {
     #[serde(default)]
@@ -387,7 +398,7 @@ async fn fetch_registry_view_for_vm(
     row.map(TrustRegistryView::from).ok_or(AppError::NotFound)
 }
 
-pub fn spawn_trust_listener(pool: PgPool, job_tx: Sender<Job>) {
+pub fn spawn_trust_listener(pool: PgPool, job_tx: Sender<QueuedJob>) {
     tokio::spawn(async move {
         if let Err(err) = listen(pool, job_tx).await {
             error!(?err, "trust transition listener terminated");
@@ -395,7 +406,7 @@ pub fn spawn_trust_listener(pool: PgPool, job_tx: Sender<Job>) {
     });
 }
 
-async fn listen(pool: PgPool, job_tx: Sender<Job>) -> Result<(), sqlx::Error> {
+async fn listen(pool: PgPool, job_tx: Sender<QueuedJob>) -> Result<(), sqlx::Error> {
     let mut listener = PgListener::connect_with(&pool).await?;
     listener.listen(TRUST_CHANNEL).await?;
 
@@ -405,92 +416,102 @@ async fn listen(pool: PgPool, job_tx: Sender<Job>) -> Result<(), sqlx::Error> {
         match serde_json::from_str::<TrustNotification>(payload) {
             Ok(message) => {
                 debug!(?message, "received trust transition notification");
-                let instance_row = sqlx::query(
-                    r#"
-                    SELECT
-                        instances.server_id,
-                        instances.instance_id,
-                        servers.owner_id,
-                        servers.name AS server_name
-                    FROM runtime_vm_instances instances
-                    JOIN mcp_servers servers ON servers.id = instances.server_id
-                    WHERE instances.id = $1
-                    "#,
-                )
-                .bind(message.runtime_vm_instance_id)
-                .fetch_optional(&pool)
-                .await?;
-
-                let Some(instance_row) = instance_row else {
-                    warn!(
-                        vm_instance_id = message.runtime_vm_instance_id,
-                        "ignoring trust notification for missing runtime VM instance"
-                    );
-                    continue;
-                };
-
-                let server_id: i32 = instance_row.get("server_id");
-                let owner_id: i32 = instance_row.get("owner_id");
-                let server_name: String = instance_row.get("server_name");
-                let instance_id: String = instance_row.get("instance_id");
-                let stale = compute_stale(message.freshness_deadline);
-                publish_trust_event(TrustRegistryEvent {
-                    owner_id,
-                    server_id,
-                    server_name: Some(server_name),
-                    vm_instance_id: message.runtime_vm_instance_id,
-                    instance_id,
-                    attestation_status: message.current_status.clone(),
-                    lifecycle_state: message.current_lifecycle_state.clone(),
-                    previous_attestation_status: message.previous_status.clone(),
-                    previous_lifecycle_state: message.previous_lifecycle_state.clone(),
-                    remediation_state: message.remediation_state.clone(),
-                    remediation_attempts: message.remediation_attempts.unwrap_or_default(),
-                    freshness_deadline: message.freshness_deadline,
-                    provenance_ref: message.provenance_ref.clone(),
-                    provenance: message.provenance.clone(),
-                    transition_reason: message.transition_reason.clone(),
-                    triggered_at: message.triggered_at,
-                    stale,
-                });
-                let signal = TrustTransitionSignal {
-                    server_id,
-                    vm_instance_id: message.runtime_vm_instance_id,
-                    current_status: message.current_status.clone(),
-                    previous_status: message.previous_status.clone(),
-                    lifecycle_state: message.current_lifecycle_state.clone(),
-                    previous_lifecycle_state: message.previous_lifecycle_state.clone(),
-                    transition_reason: message.transition_reason.clone(),
-                    remediation_state: message.remediation_state.clone(),
-                    triggered_at: message.triggered_at,
-                    freshness_expires_at: message.freshness_deadline,
-                    remediation_attempts: message.remediation_attempts.unwrap_or_default(),
-                    provenance_ref: message.provenance_ref.clone(),
-                    provenance: message.provenance.clone(),
-                    posture_changed: message
-                        .previous_status
-                        .as_deref()
-                        .map(|status| status != message.current_status)
-                        .unwrap_or(true),
-                };
-
-                if let Err(err) = scheduler::handle_trust_transition(&pool, &job_tx, &signal).await
-                {
-                    warn!(
-                        ?err,
-                        server_id = signal.server_id,
-                        vm_instance_id = signal.vm_instance_id,
-                        "failed to apply trust transition"
-                    );
-                }
-
-                job_queue::enqueue_intelligence_refresh(&pool, signal.server_id).await;
+                dispatch_transition(&pool, &job_tx, &message).await?;
             }
             Err(err) => warn!(?err, payload, "failed to parse trust notification payload"),
         }
     }
 }
 
+/// Runs the handler for one transition: publishes it to watchers, lets the evaluation
+/// scheduler react, and queues an intelligence refresh for the server.
+async fn dispatch_transition(
+    pool: &PgPool,
+    job_tx: &Sender<QueuedJob>,
+    message: &TrustNotification,
+) -> Result<(), sqlx::Error> {
+    let instance_row = sqlx::query(
+        r#"
+        SELECT
+            instances.server_id,
+            instances.instance_id,
+            servers.owner_id,
+            servers.name AS server_name
+        FROM runtime_vm_instances instances
+        JOIN mcp_servers servers ON servers.id = instances.server_id
+        WHERE instances.id = $1
+        "#,
+    )
+    .bind(message.runtime_vm_instance_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(instance_row) = instance_row else {
+        warn!(
+            vm_instance_id = message.runtime_vm_instance_id,
+            "ignoring trust notification for missing runtime VM instance"
+        );
+        return Ok(());
+    };
+
+    let server_id: i32 = instance_row.get("server_id");
+    let owner_id: i32 = instance_row.get("owner_id");
+    let server_name: String = instance_row.get("server_name");
+    let instance_id: String = instance_row.get("instance_id");
+    let stale = compute_stale(message.freshness_deadline);
+    publish_trust_event(TrustRegistryEvent {
+        owner_id,
+        server_id,
+        server_name: Some(server_name),
+        vm_instance_id: message.runtime_vm_instance_id,
+        instance_id,
+        attestation_status: message.current_status.clone(),
+        lifecycle_state: message.current_lifecycle_state.clone(),
+        previous_attestation_status: message.previous_status.clone(),
+        previous_lifecycle_state: message.previous_lifecycle_state.clone(),
+        remediation_state: message.remediation_state.clone(),
+        remediation_attempts: message.remediation_attempts.unwrap_or_default(),
+        freshness_deadline: message.freshness_deadline,
+        provenance_ref: message.provenance_ref.clone(),
+        provenance: message.provenance.clone(),
+        transition_reason: message.transition_reason.clone(),
+        triggered_at: message.triggered_at,
+        stale,
+    });
+    let signal = TrustTransitionSignal {
+        server_id,
+        vm_instance_id: message.runtime_vm_instance_id,
+        current_status: message.current_status.clone(),
+        previous_status: message.previous_status.clone(),
+        lifecycle_state: message.current_lifecycle_state.clone(),
+        previous_lifecycle_state: message.previous_lifecycle_state.clone(),
+        transition_reason: message.transition_reason.clone(),
+        remediation_state: message.remediation_state.clone(),
+        triggered_at: message.triggered_at,
+        freshness_expires_at: message.freshness_deadline,
+        remediation_attempts: message.remediation_attempts.unwrap_or_default(),
+        provenance_ref: message.provenance_ref.clone(),
+        provenance: message.provenance.clone(),
+        posture_changed: message
+            .previous_status
+            .as_deref()
+            .map(|status| status != message.current_status)
+            .unwrap_or(true),
+    };
+
+    if let Err(err) = scheduler::handle_trust_transition(pool, job_tx, &signal).await {
+        warn!(
+            ?err,
+            server_id = signal.server_id,
+            vm_instance_id = signal.vm_instance_id,
+            "failed to apply trust transition"
+        );
+    }
+
+    job_queue::enqueue_intelligence_refresh(pool, signal.server_id).await;
+    Ok(())
+}
+
 // key: trust-control -> rest-endpoints
 pub async fn list_registry_states(
     AuthUser { user_id, .. }: AuthUser,
@@ -563,6 +584,33 @@ pub async fn get_registry_state(
     Ok(Json(view))
 }
 
+/// Trust state of every instance of a server, with `state: null` for instances the
+/// registry has no row for yet.
+pub async fn get_registry_states_by_server(
+    AuthUser { user_id, .. }: AuthUser,
+    Path(server_id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+) -> AppResult<Json<ServerTrustStatesResponse>> {
+    let server: Option<(i32, String)> =
+        sqlx::query_as("SELECT owner_id, name FROM mcp_servers WHERE id = $1")
+            .bind(server_id)
+            .fetch_optional(&pool)
+            .await?;
+    let Some((owner_id, server_name)) = server else {
+        return Err(AppError::NotFound);
+    };
+    if owner_id != user_id {
+        return Err(AppError::Forbidden);
+    }
+
+    let instances = list_states_for_server(&pool, server_id).await?;
+    Ok(Json(ServerTrustStatesResponse {
+        server_id,
+        server_name,
+        instances,
+    }))
+}
+
 pub async fn get_registry_history(
     AuthUser { user_id, .. }: AuthUser,
     Path(vm_instance_id): Path<i64>,
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+
+use axum::{extract::Extension, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::sync::mpsc::Sender;
+use tracing::warn;
+
+use super::{dispatch_transition, TrustNotification};
+use crate::{
+    db::runtime_vm_trust_history::{
+        events_between, record_replay_mark, replay_mark, RuntimeVmTrustEvent,
+    },
+    db::runtime_vm_trust_registry::{get_state, upsert_state, UpsertRuntimeVmTrustRegistryState},
+    error::{AppError, AppResult},
+    extractor::AuthUser,
+    job_queue::QueuedJob,
+};
+
+// key: trust-control -> event-replay
+
+#[derive(Debug, Deserialize)]
+pub struct TrustReplayRequest {
+    /// Inclusive start of the `triggered_at` range.
+    pub from: DateTime<Utc>,
+    /// Exclusive end of the `triggered_at` range.
+    pub to: DateTime<Utc>,
+    #[serde(default)]
+    pub vm_instance_id: Option<i64>,
+}
+
+/// An instance's trust state as its last event in the range recorded it. Events carry the
+/// absolute `remediation_attempts`, so replaying never adds to the count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReplayedTrustState {
+    pub vm_instance_id: i64,
+    pub last_event_id: i64,
+    pub events: usize,
+    pub attestation_status: String,
+    pub lifecycle_state: String,
+    pub remediation_state: Option<String>,
+    pub remediation_attempts: i32,
+    pub freshness_deadline: Option<DateTime<Utc>>,
+    pub provenance_ref: Option<String>,
+    pub provenance: Option<Value>,
+    pub transition_reason: Option<String>,
+    pub triggered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustReplayStatus {
+    /// The registry was rewritten and the handler ran for the final transition.
+    Applied,
+    /// This range was already replayed and the registry has not changed since.
+    AlreadyReplayed,
+    /// A live transition changed the registry mid-replay; it was left alone.
+    Conflict,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrustReplayOutcome {
+    pub status: TrustReplayStatus,
+    pub state: ReplayedTrustState,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrustReplayReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub events_replayed: usize,
+    pub instances: Vec<TrustReplayOutcome>,
+}
+
+/// Folds events into each instance's final state, keyed by instance id. Events are ordered by
+/// `triggered_at` then id, and an event listed twice counts once.
+pub fn fold_events(events: &[RuntimeVmTrustEvent]) -> BTreeMap<i64, ReplayedTrustState> {
+    let mut ordered: Vec<&RuntimeVmTrustEvent> = events.iter().collect();
+    ordered.sort_by_key(|event| (event.triggered_at, event.id));
+    ordered.dedup_by_key(|event| event.id);
+
+    let mut states: BTreeMap<i64, ReplayedTrustState> = BTreeMap::new();
+    for event in ordered {
+        let events = states
+            .get(&event.runtime_vm_instance_id)
+            .map_or(0, |state| state.events);
+        states.insert(
+            event.runtime_vm_instance_id,
+            ReplayedTrustState {
+                vm_instance_id: event.runtime_vm_instance_id,
+                last_event_id: event.id,
+                events: events + 1,
+                attestation_status: event.current_status.clone(),
+                lifecycle_state: event.current_lifecycle_state.clone(),
+                remediation_state: event.remediation_state.clone(),
+                remediation_attempts: event.remediation_attempts,
+                freshness_deadline: event.freshness_deadline,
+                provenance_ref: event.provenance_ref.clone(),
+                provenance: event.provenance.clone(),
+                transition_reason: event.transition_reason.clone(),
+                triggered_at: event.triggered_at,
+            },
+        );
+    }
+    states
+}
+
+/// Replays persisted trust events in a range through the current handler. For each instance
+/// the registry is reset to the state its last event recorded, then the handler runs once
+/// for that final transition. A ledger remembers the last replay per instance, so replaying
+/// the same range again does nothing until a newer event or registry change arrives.
+pub async fn reprocess_trust_events(
+    pool: &PgPool,
+    job_tx: &Sender<QueuedJob>,
+    request: &TrustReplayRequest,
+    replayed_by: Option<i32>,
+) -> AppResult<TrustReplayReport> {
+    if request.from >= request.to {
+        return Err(AppError::BadRequest(
+            "`from` must be earlier than `to`".to_string(),
+        ));
+    }
+
+    let events = events_between(pool, request.from, request.to, request.vm_instance_id).await?;
+    let mut instances = Vec::new();
+    for (vm_instance_id, state) in fold_events(&events) {
+        let current = get_state(pool, vm_instance_id).await?;
+        if let (Some(current), Some(mark)) =
+            (current.as_ref(), replay_mark(pool, vm_instance_id).await?)
+        {
+            if mark == (state.last_event_id, current.version) {
+                instances.push(TrustReplayOutcome {
+                    status: TrustReplayStatus::AlreadyReplayed,
+                    state,
+                });
+                continue;
+            }
+        }
+
+        let upserted = upsert_state(
+            pool,
+            UpsertRuntimeVmTrustRegistryState {
+                runtime_vm_instance_id: vm_instance_id,
+                attestation_status: &state.attestation_status,
+                lifecycle_state: &state.lifecycle_state,
+                remediation_state: state.remediation_state.as_deref(),
+                remediation_attempts: state.remediation_attempts,
+                freshness_deadline: state.freshness_deadline,
+                provenance_ref: state.provenance_ref.as_deref(),
+                provenance: state.provenance.as_ref(),
+                expected_version: current.as_ref().map(|current| current.version),
+            },
+        )
+        .await;
+        match upserted {
+            Ok(_) => {}
+            Err(sqlx::Error::RowNotFound) => {
+                warn!(vm_instance_id, "trust registry changed during replay");
+                instances.push(TrustReplayOutcome {
+                    status: TrustReplayStatus::Conflict,
+                    state,
+                });
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        }
+
+        let message = TrustNotification {
+            runtime_vm_instance_id: vm_instance_id,
+            attestation_id: None,
+            previous_status: current
+                .as_ref()
+                .map(|current| current.attestation_status.clone()),
+            current_status: state.attestation_status.clone(),
+            previous_lifecycle_state: current
+                .as_ref()
+                .map(|current| current.lifecycle_state.clone()),
+            current_lifecycle_state: state.lifecycle_state.clone(),
+            transition_reason: state.transition_reason.clone(),
+            remediation_state: state.remediation_state.clone(),
+            remediation_attempts: Some(state.remediation_attempts),
+            freshness_deadline: state.freshness_deadline,
+            provenance_ref: state.provenance_ref.clone(),
+            provenance: state.provenance.clone(),
+            triggered_at: state.triggered_at,
+        };
+        dispatch_transition(pool, job_tx, &message).await?;
+
+        // the handler may move the registry on (e.g. into remediation); remember where it left it
+        if let Some(settled) = get_state(pool, vm_instance_id).await? {
+            record_replay_mark(
+                pool,
+                vm_instance_id,
+                state.last_event_id,
+                settled.version,
+                replayed_by,
+            )
+            .await?;
+        }
+        instances.push(TrustReplayOutcome {
+            status: TrustReplayStatus::Applied,
+            state,
+        });
+    }
+
+    Ok(TrustReplayReport {
+        from: request.from,
+        to: request.to,
+        events_replayed: events.len(),
+        instances,
+    })
+}
+
+/// Admin-only: replays trust events in a range, e.g. after a handler fix.
+pub async fn replay_trust_events(
+    user: AuthUser,
+    Extension(pool): Extension<PgPool>,
+    Extension(job_tx): Extension<Sender<QueuedJob>>,
+    Json(request): Json<TrustReplayRequest>,
+) -> AppResult<Json<TrustReplayReport>> {
+    if user.role != "admin" {
+        return Err(AppError::Forbidden);
+    }
+    let report = reprocess_trust_events(&pool, &job_tx, &request, Some(user.user_id)).await?;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn event(
+        id: i64,
+        instance: i64,
+        minute: i64,
+        lifecycle: &str,
+        attempts: i32,
+    ) -> RuntimeVmTrustEvent {
+        let triggered_at =
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::minutes(minute);
+        RuntimeVmTrustEvent {
+            id,
+            runtime_vm_instance_id: instance,
+            attestation_id: None,
+            previous_status: None,
+            current_status: "untrusted".to_string(),
+            previous_lifecycle_state: None,
+            current_lifecycle_state: lifecycle.to_string(),
+            transition_reason: None,
+            remediation_state: None,
+            remediation_attempts: attempts,
+            freshness_deadline: None,
+            provenance_ref: None,
+            provenance: None,
+            triggered_at,
+            metadata: None,
+            created_at: triggered_at,
+        }
+    }
+
+    #[test]
+    fn final_state_comes_from_the_last_event_without_summing_attempts() {
+        let events = vec![
+            event(3, 7, 2, "remediating", 2),
+            event(1, 7, 0, "suspect", 0),
+            event(2, 7, 1, "quarantined", 1),
+            event(2, 7, 1, "quarantined", 1),
+            event(4, 9, 1, "restored", 0),
+        ];
+        let states = fold_events(&events);
+        assert_eq!(states.len(), 2);
+
+        let state = &states[&7];
+        assert_eq!(state.last_event_id, 3);
+        assert_eq!(state.events, 3);
+        assert_eq!(state.lifecycle_state, "remediating");
+        assert_eq!(state.remediation_attempts, 2);
+        assert_eq!(states[&9].lifecycle_state, "restored");
+    }
+
+    #[test]
+    fn ties_on_triggered_at_are_broken_by_event_id() {
+        let states = fold_events(&[
+            event(6, 7, 0, "restored", 3),
+            event(5, 7, 0, "quarantined", 2),
+        ]);
+        assert_eq!(states[&7].last_event_id, 6);
+        assert_eq!(states[&7].lifecycle_state, "restored");
+    }
+}
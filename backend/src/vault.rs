@@ -1,6 +1,16 @@
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use once_cell::sync::Lazy;
 use reqwest::Client;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
+use thiserror::Error;
 
 pub struct VaultClient {
     base: String,
@@ -79,3 +89,328 @@ trait Clear {
 impl Clear for Value {
     fn clear(self) {}
 }
+
+// key: vault-envelope -> data keys wrapped by a master key
+
+const DATA_KEY_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum EnvelopeError {
+    #[error("unknown master key id `{0}`")]
+    UnknownMasterKey(String),
+    #[error("invalid master key configuration: {0}")]
+    InvalidConfig(String),
+    #[error("envelope encryption failed")]
+    Crypto,
+    #[error("malformed sealed secret: {0}")]
+    Malformed(String),
+    #[error("kms error: {0}")]
+    Kms(String),
+}
+
+/// Data encryption key ciphertext plus the id of the master key that wrapped it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub master_key_id: String,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Wraps and unwraps per-secret data keys under a master key.
+#[async_trait]
+pub trait KeyWrapper: Send + Sync {
+    /// Master key id used for new wraps.
+    fn active_key_id(&self) -> &str;
+    async fn wrap(&self, data_key: &[u8]) -> Result<WrappedKey, EnvelopeError>;
+    async fn unwrap(&self, wrapped: &WrappedKey) -> Result<Vec<u8>, EnvelopeError>;
+}
+
+/// In-process key wrapper holding AES-256-GCM master keys. Retired keys stay loaded so
+/// secrets wrapped before a master-key rotation remain readable.
+pub struct LocalKeyWrapper {
+    active_key_id: String,
+    master_keys: HashMap<String, [u8; DATA_KEY_LEN]>,
+}
+
+impl LocalKeyWrapper {
+    pub fn new(key_id: impl Into<String>, key: [u8; DATA_KEY_LEN]) -> Self {
+        let key_id = key_id.into();
+        let mut master_keys = HashMap::new();
+        master_keys.insert(key_id.clone(), key);
+        Self {
+            active_key_id: key_id,
+            master_keys,
+        }
+    }
+
+    /// Introduces a new master key and makes it active for subsequent wraps.
+    pub fn rotate_to(mut self, key_id: impl Into<String>, key: [u8; DATA_KEY_LEN]) -> Self {
+        let key_id = key_id.into();
+        self.master_keys.insert(key_id.clone(), key);
+        self.active_key_id = key_id;
+        self
+    }
+
+    /// Reads `VAULT_MASTER_KEYS` (`id:base64,...`, oldest first) and the optional
+    /// `VAULT_ACTIVE_MASTER_KEY_ID`, defaulting to the last listed key.
+    pub fn from_env() -> Result<Option<Self>, EnvelopeError> {
+        let read = |var| {
+            std::env::var(var)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        };
+        let Some(raw) = read("VAULT_MASTER_KEYS") else {
+            return Ok(None);
+        };
+        let active = read("VAULT_ACTIVE_MASTER_KEY_ID");
+        Self::from_spec(&raw, active.as_deref().map(str::trim)).map(Some)
+    }
+
+    /// Parses a comma-separated `id:base64` key list; the last key is active unless `active`
+    /// names another. Startup validation runs this before anything seals a secret.
+    pub(crate) fn from_spec(raw: &str, active: Option<&str>) -> Result<Self, EnvelopeError> {
+        let mut wrapper: Option<Self> = None;
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (key_id, encoded) = entry.split_once(':').ok_or_else(|| {
+                EnvelopeError::InvalidConfig(format!("expected id:base64, got `{entry}`"))
+            })?;
+            let bytes = STANDARD
+                .decode(encoded.trim())
+                .map_err(|e| EnvelopeError::InvalidConfig(format!("{key_id}: {e}")))?;
+            let key: [u8; DATA_KEY_LEN] = bytes.try_into().map_err(|_| {
+                EnvelopeError::InvalidConfig(format!("{key_id}: master keys must be 32 bytes"))
+            })?;
+            wrapper = Some(match wrapper {
+                Some(existing) => existing.rotate_to(key_id.trim(), key),
+                None => Self::new(key_id.trim(), key),
+            });
+        }
+        let mut wrapper = wrapper
+            .ok_or_else(|| EnvelopeError::InvalidConfig("no master keys configured".into()))?;
+        if let Some(active) = active {
+            if !wrapper.master_keys.contains_key(active) {
+                return Err(EnvelopeError::UnknownMasterKey(active.to_string()));
+            }
+            wrapper.active_key_id = active.to_string();
+        }
+        Ok(wrapper)
+    }
+
+    fn master_key(&self, key_id: &str) -> Result<&[u8; DATA_KEY_LEN], EnvelopeError> {
+        self.master_keys
+            .get(key_id)
+            .ok_or_else(|| EnvelopeError::UnknownMasterKey(key_id.to_string()))
+    }
+}
+
+#[async_trait]
+impl KeyWrapper for LocalKeyWrapper {
+    fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    async fn wrap(&self, data_key: &[u8]) -> Result<WrappedKey, EnvelopeError> {
+        let key = self.master_key(&self.active_key_id)?;
+        Ok(WrappedKey {
+            master_key_id: self.active_key_id.clone(),
+            ciphertext: aead_seal(key, self.active_key_id.as_bytes(), data_key)?,
+        })
+    }
+
+    async fn unwrap(&self, wrapped: &WrappedKey) -> Result<Vec<u8>, EnvelopeError> {
+        let key = self.master_key(&wrapped.master_key_id)?;
+        aead_open(key, wrapped.master_key_id.as_bytes(), &wrapped.ciphertext)
+    }
+}
+
+/// Minimal surface a cloud KMS client must provide to back [`KmsKeyWrapper`].
+#[async_trait]
+pub trait KmsClient: Send + Sync {
+    async fn encrypt(&self, key_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, String>;
+    async fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// Delegates data-key wrapping to an external KMS so master keys never leave it.
+pub struct KmsKeyWrapper<C> {
+    client: C,
+    key_id: String,
+}
+
+impl<C: KmsClient> KmsKeyWrapper<C> {
+    pub fn new(client: C, key_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            key_id: key_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: KmsClient> KeyWrapper for KmsKeyWrapper<C> {
+    fn active_key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    async fn wrap(&self, data_key: &[u8]) -> Result<WrappedKey, EnvelopeError> {
+        let ciphertext = self
+            .client
+            .encrypt(&self.key_id, data_key)
+            .await
+            .map_err(EnvelopeError::Kms)?;
+        Ok(WrappedKey {
+            master_key_id: self.key_id.clone(),
+            ciphertext,
+        })
+    }
+
+    async fn unwrap(&self, wrapped: &WrappedKey) -> Result<Vec<u8>, EnvelopeError> {
+        self.client
+            .decrypt(&wrapped.master_key_id, &wrapped.ciphertext)
+            .await
+            .map_err(EnvelopeError::Kms)
+    }
+}
+
+/// Secret ciphertext under a fresh data key, alongside the wrapped data key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedSecret {
+    pub master_key_id: String,
+    pub wrapped_data_key: String,
+    pub ciphertext: String,
+}
+
+impl SealedSecret {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("sealed secret serializes")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        serde_json::from_slice(bytes).map_err(|e| EnvelopeError::Malformed(e.to_string()))
+    }
+}
+
+/// Process-wide wrapper configured through `VAULT_MASTER_KEYS`; `None` keeps the legacy
+/// pgcrypto storage path.
+pub static ENVELOPE_WRAPPER: Lazy<Option<Arc<dyn KeyWrapper>>> =
+    Lazy::new(|| match LocalKeyWrapper::from_env() {
+        Ok(Some(wrapper)) => Some(Arc::new(wrapper) as Arc<dyn KeyWrapper>),
+        Ok(None) => None,
+        Err(err) => panic!("invalid VAULT_MASTER_KEYS: {err}"),
+    });
+
+pub async fn seal_secret(
+    wrapper: &dyn KeyWrapper,
+    plaintext: &[u8],
+) -> Result<SealedSecret, EnvelopeError> {
+    let mut data_key = [0u8; DATA_KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut data_key)
+        .map_err(|_| EnvelopeError::Crypto)?;
+    let ciphertext = aead_seal(&data_key, b"secret", plaintext)?;
+    let wrapped = wrapper.wrap(&data_key).await?;
+    Ok(SealedSecret {
+        master_key_id: wrapped.master_key_id,
+        wrapped_data_key: STANDARD.encode(wrapped.ciphertext),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+pub async fn open_secret(
+    wrapper: &dyn KeyWrapper,
+    sealed: &SealedSecret,
+) -> Result<Vec<u8>, EnvelopeError> {
+    let decode = |value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|e| EnvelopeError::Malformed(e.to_string()))
+    };
+    let data_key = wrapper
+        .unwrap(&WrappedKey {
+            master_key_id: sealed.master_key_id.clone(),
+            ciphertext: decode(&sealed.wrapped_data_key)?,
+        })
+        .await?;
+    aead_open(&data_key, b"secret", &decode(&sealed.ciphertext)?)
+}
+
+/// AES-256-GCM with a random nonce prefixed to the ciphertext.
+fn aead_seal(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, EnvelopeError> {
+    let key =
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| EnvelopeError::Crypto)?);
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| EnvelopeError::Crypto)?;
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(aad),
+        &mut in_out,
+    )
+    .map_err(|_| EnvelopeError::Crypto)?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&in_out);
+    Ok(out)
+}
+
+fn aead_open(key: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, EnvelopeError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(EnvelopeError::Malformed(
+            "ciphertext shorter than nonce".into(),
+        ));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let key =
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| EnvelopeError::Crypto)?);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| EnvelopeError::Crypto)?;
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| EnvelopeError::Crypto)?;
+    Ok(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open_secret, seal_secret, KeyWrapper, LocalKeyWrapper, SealedSecret};
+
+    #[tokio::test]
+    async fn secret_round_trips_through_envelope() {
+        let wrapper = LocalKeyWrapper::new("mk-1", [7u8; 32]);
+        let sealed = seal_secret(&wrapper, b"database-password").await.unwrap();
+        assert_eq!(sealed.master_key_id, "mk-1");
+
+        let restored = SealedSecret::from_bytes(&sealed.to_bytes()).unwrap();
+        let plaintext = open_secret(&wrapper, &restored).await.unwrap();
+        assert_eq!(plaintext, b"database-password");
+    }
+
+    #[tokio::test]
+    async fn secret_wrapped_under_old_master_key_survives_rotation() {
+        let original = LocalKeyWrapper::new("mk-1", [1u8; 32]);
+        let sealed = seal_secret(&original, b"rotate-me").await.unwrap();
+
+        let rotated = LocalKeyWrapper::new("mk-1", [1u8; 32]).rotate_to("mk-2", [2u8; 32]);
+        assert_eq!(rotated.active_key_id(), "mk-2");
+        assert_eq!(open_secret(&rotated, &sealed).await.unwrap(), b"rotate-me");
+
+        let resealed = seal_secret(&rotated, b"rotate-me").await.unwrap();
+        assert_eq!(resealed.master_key_id, "mk-2");
+
+        let without_old = LocalKeyWrapper::new("mk-2", [2u8; 32]);
+        assert!(open_secret(&without_old, &sealed).await.is_err());
+    }
+
+    #[test]
+    fn master_keys_parse_from_spec() {
+        let spec = format!(
+            "mk-1:{},mk-2:{}",
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, [1u8; 32]),
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, [2u8; 32]),
+        );
+        let wrapper = LocalKeyWrapper::from_spec(&spec, None).unwrap();
+        assert_eq!(wrapper.active_key_id(), "mk-2");
+        let pinned = LocalKeyWrapper::from_spec(&spec, Some("mk-1")).unwrap();
+        assert_eq!(pinned.active_key_id(), "mk-1");
+        assert!(LocalKeyWrapper::from_spec("mk-1:c2hvcnQ=", None).is_err());
+    }
+}
@@ -18,6 +18,7 @@ pub struct VectorDb {
     pub name: String,
     pub db_type: String,
     pub url: Option<String>,
+    pub dimension: Option<i32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -136,12 +137,130 @@ pub struct CreateVectorDb {
     pub name: String,
     #[serde(default = "default_db_type")]
     pub db_type: String,
+    #[serde(default)]
+    pub dimension: Option<i32>,
 }
 
 fn default_db_type() -> String {
     "chroma".into()
 }
 
+/// key: vector-dbs-search
+/// Distance metric applied when ranking similarity search hits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorSearchMetric {
+    Cosine,
+    L2,
+    Dot,
+}
+
+impl VectorSearchMetric {
+    /// Cosine and dot scores are similarities; L2 scores are distances.
+    fn higher_is_better(self) -> bool {
+        !matches!(self, VectorSearchMetric::L2)
+    }
+
+    /// Chroma `hnsw:space` a collection must use to be searched with this metric.
+    fn chroma_space(self) -> &'static str {
+        match self {
+            VectorSearchMetric::Cosine => "cosine",
+            VectorSearchMetric::L2 => "l2",
+            VectorSearchMetric::Dot => "ip",
+        }
+    }
+
+    /// Chroma always returns distances; cosine and inner-product distances are
+    /// `1 - similarity`, so they are turned back into similarities.
+    fn score_from_chroma_distance(self, distance: f64) -> f64 {
+        match self {
+            VectorSearchMetric::L2 => distance,
+            VectorSearchMetric::Cosine | VectorSearchMetric::Dot => 1.0 - distance,
+        }
+    }
+}
+
+fn default_search_metric() -> VectorSearchMetric {
+    VectorSearchMetric::Cosine
+}
+
+fn default_top_k() -> usize {
+    10
+}
+
+const MAX_SEARCH_TOP_K: usize = 1000;
+
+#[derive(Deserialize)]
+pub struct VectorSearchRequest {
+    pub embedding: Vec<f32>,
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    #[serde(default = "default_search_metric")]
+    pub metric: VectorSearchMetric,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorSearchHit {
+    pub id: String,
+    pub score: f64,
+    #[serde(default)]
+    pub metadata: Value,
+}
+
+#[derive(Serialize)]
+pub struct VectorSearchResponse {
+    pub vector_db_id: i32,
+    pub metric: VectorSearchMetric,
+    pub results: Vec<VectorSearchHit>,
+}
+
+/// Collections of a provisioned Chroma live in its default tenant and database.
+const CHROMA_COLLECTIONS_PATH: [&str; 7] = [
+    "api",
+    "v2",
+    "tenants",
+    "default_tenant",
+    "databases",
+    "default_database",
+    "collections",
+];
+
+#[derive(Deserialize)]
+struct ChromaCollection {
+    id: String,
+    #[serde(default)]
+    dimension: Option<i32>,
+    #[serde(default)]
+    metadata: Option<Value>,
+    #[serde(default)]
+    configuration_json: Option<Value>,
+}
+
+impl ChromaCollection {
+    /// Distance space the collection was created with; Chroma defaults to `l2`.
+    fn space(&self) -> &str {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("hnsw:space"))
+            .or_else(|| {
+                self.configuration_json
+                    .as_ref()
+                    .and_then(|configuration| configuration.pointer("/hnsw/space"))
+            })
+            .and_then(Value::as_str)
+            .unwrap_or("l2")
+    }
+}
+
+#[derive(Deserialize)]
+struct ChromaQueryResponse {
+    ids: Vec<Vec<String>>,
+    #[serde(default)]
+    distances: Option<Vec<Vec<Option<f64>>>>,
+    #[serde(default)]
+    metadatas: Option<Vec<Vec<Option<Value>>>>,
+}
+
 async fn ensure_vector_db_owner(
     pool: &PgPool,
     vector_db_id: i32,
@@ -168,7 +287,7 @@ pub async fn list_vector_dbs(
     AuthUser { user_id, .. }: AuthUser,
 ) -> Result<Json<Vec<VectorDb>>, (StatusCode, String)> {
     let rows = sqlx::query(
-        "SELECT id, name, db_type, url, created_at FROM vector_dbs WHERE owner_id = $1 ORDER BY id",
+        "SELECT id, name, db_type, url, dimension, created_at FROM vector_dbs WHERE owner_id = $1 ORDER BY id",
     )
     .bind(user_id)
     .fetch_all(&pool)
@@ -184,6 +303,7 @@ pub async fn list_vector_dbs(
             name: r.get("name"),
             db_type: r.get("db_type"),
             url: r.try_get("url").ok(),
+            dimension: r.try_get("dimension").ok().flatten(),
             created_at: r.get("created_at"),
         })
         .collect();
@@ -195,12 +315,19 @@ pub async fn create_vector_db(
     AuthUser { user_id, .. }: AuthUser,
     Json(payload): Json<CreateVectorDb>,
 ) -> Result<Json<VectorDb>, (StatusCode, String)> {
+    if matches!(payload.dimension, Some(dimension) if dimension <= 0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Vector dimension must be positive".into(),
+        ));
+    }
     let rec = sqlx::query(
-        "INSERT INTO vector_dbs (owner_id, name, db_type) VALUES ($1,$2,$3) RETURNING id, created_at"
+        "INSERT INTO vector_dbs (owner_id, name, db_type, dimension) VALUES ($1,$2,$3,$4) RETURNING id, created_at"
     )
     .bind(user_id)
     .bind(&payload.name)
     .bind(&payload.db_type)
+    .bind(payload.dimension)
     .fetch_one(&pool)
     .await
     .map_err(|e| {
@@ -215,6 +342,7 @@ pub async fn create_vector_db(
         name: payload.name,
         db_type: payload.db_type,
         url: None,
+        dimension: payload.dimension,
         created_at,
     }))
 }
@@ -229,6 +357,202 @@ pub async fn delete_vector_db(
     Ok(StatusCode::NO_CONTENT)
 }
 
+fn validate_search_request(
+    request: &VectorSearchRequest,
+    dimension: Option<i32>,
+) -> Result<(), (StatusCode, String)> {
+    if request.embedding.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Query embedding is empty".into()));
+    }
+    if request.top_k == 0 || request.top_k > MAX_SEARCH_TOP_K {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("top_k must be between 1 and {MAX_SEARCH_TOP_K}"),
+        ));
+    }
+    check_dimension(request, dimension)
+}
+
+fn check_dimension(
+    request: &VectorSearchRequest,
+    dimension: Option<i32>,
+) -> Result<(), (StatusCode, String)> {
+    match dimension {
+        Some(dimension) if request.embedding.len() != dimension as usize => Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Query embedding has {} dimensions but the collection expects {dimension}",
+                request.embedding.len()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn chroma_url(base: &str, segments: &[&str]) -> Result<reqwest::Url, (StatusCode, String)> {
+    let invalid = || (StatusCode::CONFLICT, "Vector DB url is invalid".to_string());
+    let mut url = reqwest::Url::parse(base).map_err(|_| invalid())?;
+    url.path_segments_mut()
+        .map_err(|_| invalid())?
+        .pop_if_empty()
+        .extend(CHROMA_COLLECTIONS_PATH.iter().chain(segments));
+    Ok(url)
+}
+
+fn chroma_failed(vector_db_id: i32, e: reqwest::Error) -> (StatusCode, String) {
+    error!(?e, vector_db_id, "chroma search request failed");
+    (StatusCode::BAD_GATEWAY, "Vector DB search failed".into())
+}
+
+/// Queries the Chroma collection named after the vector DB. The collection must use the
+/// distance space of the requested metric, since Chroma cannot rescore at query time.
+/// Without a stored `dimension`, the dimension Chroma reports for the collection is checked.
+async fn search_chroma(
+    vector_db_id: i32,
+    base_url: &str,
+    collection: &str,
+    request: &VectorSearchRequest,
+    stored_dimension: Option<i32>,
+) -> Result<Vec<VectorSearchHit>, (StatusCode, String)> {
+    let client = reqwest::Client::new();
+    let response = client
+        .get(chroma_url(base_url, &[collection])?)
+        .send()
+        .await
+        .map_err(|e| chroma_failed(vector_db_id, e))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Chroma collection `{collection}` does not exist"),
+        ));
+    }
+    let info: ChromaCollection = response
+        .error_for_status()
+        .map_err(|e| chroma_failed(vector_db_id, e))?
+        .json()
+        .await
+        .map_err(|e| chroma_failed(vector_db_id, e))?;
+    if info.space() != request.metric.chroma_space() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Chroma collection `{collection}` uses `{}` distance; search it with the matching metric",
+                info.space()
+            ),
+        ));
+    }
+    if stored_dimension.is_none() {
+        check_dimension(request, info.dimension)?;
+    }
+
+    let query: ChromaQueryResponse = client
+        .post(chroma_url(base_url, &[info.id.as_str(), "query"])?)
+        .json(&serde_json::json!({
+            "query_embeddings": [request.embedding],
+            "n_results": request.top_k,
+            "include": ["metadatas", "distances"],
+        }))
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| chroma_failed(vector_db_id, e))?
+        .json()
+        .await
+        .map_err(|e| chroma_failed(vector_db_id, e))?;
+
+    let ids = query.ids.into_iter().next().unwrap_or_default();
+    let distances = query
+        .distances
+        .and_then(|rows| rows.into_iter().next())
+        .unwrap_or_default();
+    let mut metadatas = query
+        .metadatas
+        .and_then(|rows| rows.into_iter().next())
+        .unwrap_or_default()
+        .into_iter();
+    Ok(ids
+        .into_iter()
+        .zip(distances)
+        .map(|(id, distance)| VectorSearchHit {
+            id,
+            score: distance
+                .map(|distance| request.metric.score_from_chroma_distance(distance))
+                .unwrap_or(f64::NAN),
+            metadata: metadatas.next().flatten().unwrap_or(Value::Null),
+        })
+        .collect())
+}
+
+/// Orders backend hits best-first for the metric and truncates to `top_k`.
+fn rank_search_hits(
+    mut hits: Vec<VectorSearchHit>,
+    metric: VectorSearchMetric,
+    top_k: usize,
+) -> Vec<VectorSearchHit> {
+    hits.retain(|hit| hit.score.is_finite());
+    hits.sort_by(|a, b| {
+        let ordering = a.score.total_cmp(&b.score);
+        if metric.higher_is_better() {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    hits.truncate(top_k);
+    hits
+}
+
+pub async fn search_vector_db(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<VectorSearchRequest>,
+) -> Result<Json<VectorSearchResponse>, (StatusCode, String)> {
+    ensure_vector_db_owner(&pool, id, user_id).await?;
+
+    let row = sqlx::query("SELECT name, db_type, url, dimension FROM vector_dbs WHERE id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| {
+            error!(
+                ?e,
+                vector_db_id = id,
+                "DB error loading vector db for search"
+            );
+            (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+        })?;
+    let name: String = row.get("name");
+    let db_type: String = row.get("db_type");
+    let url: Option<String> = row.try_get("url").ok().flatten();
+    let dimension: Option<i32> = row.try_get("dimension").ok().flatten();
+
+    validate_search_request(&payload, dimension)?;
+
+    let Some(url) = url else {
+        return Err((
+            StatusCode::CONFLICT,
+            "Vector DB is not provisioned yet".into(),
+        ));
+    };
+
+    let hits = match db_type.as_str() {
+        "chroma" => search_chroma(id, &url, &name, &payload, dimension).await?,
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Search is not supported for vector db type {other}"),
+            ))
+        }
+    };
+
+    Ok(Json(VectorSearchResponse {
+        vector_db_id: id,
+        metric: payload.metric,
+        results: rank_search_hits(hits, payload.metric, payload.top_k),
+    }))
+}
+
 pub async fn upsert_vector_db_residency_policy(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
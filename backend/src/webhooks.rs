@@ -4,6 +4,9 @@ use serde_json::Value;
 
 use crate::billing::{ReconciliationHandle, ReconciliationJob};
 
+pub mod dispatch;
+pub mod target;
+
 /// key: webhooks-billing -> adapter entrypoint
 #[derive(Debug, Deserialize)]
 pub struct BillingWebhookRequest {
@@ -0,0 +1,916 @@
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::extractor::AuthUser;
+use crate::remediation::{subscribe_remediation_events, RemediationStreamEvent};
+use crate::secrets::{reveal_stored_value, seal_stored_value};
+use crate::trust::subscribe_registry_events;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::{FromRow, PgPool};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, warn};
+use url::Host;
+use uuid::Uuid;
+
+use super::target;
+
+// key: webhooks-dispatch -> signed at-least-once lifecycle event delivery
+
+pub const EVENT_ID_HEADER: &str = "x-mcp-event-id";
+pub const EVENT_TYPE_HEADER: &str = "x-mcp-event-type";
+pub const TIMESTAMP_HEADER: &str = "x-mcp-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-mcp-signature-256";
+
+pub const REMEDIATION_STATUS_CHANGED: &str = "remediation.status_changed";
+pub const TRUST_TRANSITION: &str = "trust.transition";
+pub const EVENT_TYPES: [&str; 2] = [REMEDIATION_STATUS_CHANGED, TRUST_TRANSITION];
+
+/// `sha256=<hex>` HMAC of `"{timestamp}.{body}"`. Binding the timestamp lets receivers
+/// reject stale replays; the event id in the body lets them drop duplicates.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can use any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+pub fn verify_signature(secret: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(digest) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex_digest| hex::decode(hex_digest).ok())
+    else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can use any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&digest).is_ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts before a delivery is dead-lettered.
+    pub max_attempts: i32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config() -> Self {
+        Self {
+            max_attempts: *config::WEBHOOK_MAX_ATTEMPTS,
+            base_backoff: Duration::from_secs(*config::WEBHOOK_RETRY_BASE_SECS),
+            max_backoff: Duration::from_secs(*config::WEBHOOK_RETRY_MAX_SECS),
+        }
+    }
+
+    /// Doubles from `base_backoff` after each failed attempt, capped at `max_backoff`.
+    pub fn backoff(&self, failed_attempts: i32) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).clamp(0, 16) as u32;
+        self.base_backoff
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_backoff)
+    }
+}
+
+/// Records an event and queues one delivery per active endpoint of `owner_id` that
+/// subscribes to `event_type`. Events without an owner are kept but not delivered.
+pub async fn publish_event(
+    pool: &PgPool,
+    event_type: &str,
+    owner_id: Option<i32>,
+    payload: &Value,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let event_id: i64 = sqlx::query_scalar(
+        "INSERT INTO webhook_events (event_type, owner_id, payload) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(event_type)
+    .bind(owner_id)
+    .bind(payload)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO webhook_deliveries (event_id, endpoint_id) \
+         SELECT $1, id FROM webhook_endpoints \
+         WHERE active AND owner_id = $2 \
+           AND (cardinality(event_types) = 0 OR $3 = ANY(event_types))",
+    )
+    .bind(event_id)
+    .bind(owner_id)
+    .bind(event_type)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(event_id)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct DispatchReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub dead_lettered: usize,
+}
+
+#[derive(Debug, FromRow)]
+struct DueDelivery {
+    id: i64,
+    attempts: i32,
+    event_id: i64,
+    event_type: String,
+    payload: Value,
+    created_at: DateTime<Utc>,
+    url: String,
+    secret: Option<String>,
+    sealed_secret: Option<Vec<u8>>,
+}
+
+struct AttemptOutcome {
+    status_code: Option<i32>,
+    error: Option<String>,
+    elapsed: Duration,
+}
+
+impl AttemptOutcome {
+    /// An attempt that failed before any request was sent.
+    fn refused(error: String) -> Self {
+        Self {
+            status_code: None,
+            error: Some(error),
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    request_timeout: Duration,
+    batch_size: i64,
+    allow_private_targets: bool,
+}
+
+impl WebhookDispatcher {
+    pub fn new(retry: RetryPolicy, request_timeout: Duration) -> Self {
+        Self {
+            client: no_redirects()
+                .build()
+                .expect("static webhook client config"),
+            retry,
+            request_timeout,
+            batch_size: 50,
+            allow_private_targets: false,
+        }
+    }
+
+    pub fn from_config() -> Self {
+        Self {
+            allow_private_targets: *config::WEBHOOK_ALLOW_PRIVATE_TARGETS,
+            ..Self::new(
+                RetryPolicy::from_config(),
+                Duration::from_secs(*config::WEBHOOK_REQUEST_TIMEOUT_SECS),
+            )
+        }
+    }
+
+    /// Sends every due delivery once, up to the batch size. Each delivery is claimed just
+    /// before it is sent and pushed past the request timeout, so its lease covers only its
+    /// own attempt. A crashed dispatcher's delivery becomes due again and is resent, and
+    /// another replica never picks up a delivery still waiting its turn here.
+    pub async fn dispatch_due(&self, pool: &PgPool) -> Result<DispatchReport, sqlx::Error> {
+        let mut report = DispatchReport::default();
+        for _ in 0..self.batch_size {
+            let Some(delivery) = self.claim_next(pool).await? else {
+                break;
+            };
+            let outcome = match signing_secret(pool, &delivery).await {
+                Ok(secret) => self.send(&delivery, &secret).await,
+                Err(err) => AttemptOutcome::refused(err),
+            };
+            match self.record(pool, &delivery, &outcome).await? {
+                "delivered" => report.delivered += 1,
+                "dead_letter" => report.dead_lettered += 1,
+                _ => report.retrying += 1,
+            }
+        }
+        Ok(report)
+    }
+
+    async fn claim_next(&self, pool: &PgPool) -> Result<Option<DueDelivery>, sqlx::Error> {
+        let lease = self.request_timeout + Duration::from_secs(30);
+        sqlx::query_as(
+            r#"
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $1)
+            FROM webhook_events e, webhook_endpoints w
+            WHERE d.id = (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY event_id, id
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+              AND e.id = d.event_id
+              AND w.id = d.endpoint_id
+            RETURNING d.id, d.attempts, e.id AS event_id, e.event_type, e.payload,
+                      e.created_at, w.url, w.secret, w.sealed_secret
+            "#,
+        )
+        .bind(lease.as_secs_f64())
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The client for one delivery. Unless private targets are allowed, the host is
+    /// resolved here, refused if any address is internal, and the request is pinned to the
+    /// checked addresses so a later DNS answer cannot point it elsewhere.
+    async fn client_for(&self, url: &str) -> Result<reqwest::Client, String> {
+        if self.allow_private_targets {
+            return Ok(self.client.clone());
+        }
+        let url = reqwest::Url::parse(url).map_err(|e| format!("invalid webhook url: {e}"))?;
+        let addrs = target::resolve_public(&url).await?;
+        let mut builder = no_redirects();
+        if let Some(Host::Domain(domain)) = url.host() {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    async fn send(&self, delivery: &DueDelivery, secret: &str) -> AttemptOutcome {
+        let client = match self.client_for(&delivery.url).await {
+            Ok(client) => client,
+            Err(err) => return AttemptOutcome::refused(err),
+        };
+        let body = json!({
+            "id": delivery.event_id,
+            "type": delivery.event_type,
+            "created_at": delivery.created_at,
+            "data": delivery.payload,
+        })
+        .to_string();
+        let timestamp = Utc::now().timestamp();
+        let started = Instant::now();
+        let result = client
+            .post(&delivery.url)
+            .timeout(self.request_timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, delivery.event_id.to_string())
+            .header(EVENT_TYPE_HEADER, &delivery.event_type)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign_payload(secret, timestamp, body.as_bytes()),
+            )
+            .body(body)
+            .send()
+            .await;
+        let elapsed = started.elapsed();
+        match result {
+            Ok(response) if response.status().is_success() => AttemptOutcome {
+                status_code: Some(i32::from(response.status().as_u16())),
+                error: None,
+                elapsed,
+            },
+            Ok(response) => AttemptOutcome {
+                status_code: Some(i32::from(response.status().as_u16())),
+                error: Some(format!("endpoint responded {}", response.status())),
+                elapsed,
+            },
+            Err(err) => AttemptOutcome {
+                status_code: None,
+                error: Some(err.to_string()),
+                elapsed,
+            },
+        }
+    }
+
+    async fn record(
+        &self,
+        pool: &PgPool,
+        delivery: &DueDelivery,
+        outcome: &AttemptOutcome,
+    ) -> Result<&'static str, sqlx::Error> {
+        let attempt = delivery.attempts + 1;
+        let status = match outcome.error {
+            None => "delivered",
+            Some(_) if attempt >= self.retry.max_attempts => "dead_letter",
+            Some(_) => "pending",
+        };
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO webhook_delivery_attempts (delivery_id, attempt, status_code, error, duration_ms) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(delivery.id)
+        .bind(attempt)
+        .bind(outcome.status_code)
+        .bind(&outcome.error)
+        .bind(i32::try_from(outcome.elapsed.as_millis()).unwrap_or(i32::MAX))
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE webhook_deliveries \
+             SET status = $2, attempts = $3, last_status_code = $4, last_error = $5, \
+                 next_attempt_at = NOW() + make_interval(secs => $6), \
+                 delivered_at = CASE WHEN $2 = 'delivered' THEN NOW() END \
+             WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(status)
+        .bind(attempt)
+        .bind(outcome.status_code)
+        .bind(&outcome.error)
+        .bind(self.retry.backoff(attempt).as_secs_f64())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        if status == "dead_letter" {
+            warn!(
+                delivery_id = delivery.id,
+                event_id = delivery.event_id,
+                attempts = attempt,
+                error = outcome.error.as_deref().unwrap_or_default(),
+                "webhook delivery dead-lettered"
+            );
+        }
+        Ok(status)
+    }
+}
+
+/// Redirects are not followed: the target was only checked for the registered host.
+fn no_redirects() -> reqwest::ClientBuilder {
+    reqwest::Client::builder().redirect(reqwest::redirect::Policy::none())
+}
+
+async fn signing_secret(pool: &PgPool, delivery: &DueDelivery) -> Result<String, String> {
+    match (&delivery.sealed_secret, &delivery.secret) {
+        (Some(sealed), _) => reveal_stored_value(pool, sealed.clone())
+            .await
+            .map_err(|(_, msg)| format!("cannot unseal signing secret: {msg}")),
+        (None, Some(secret)) => Ok(secret.clone()),
+        (None, None) => Err("endpoint has no signing secret".into()),
+    }
+}
+
+/// Seals signing secrets of endpoints registered before secrets were sealed and clears
+/// their plaintext column.
+pub async fn seal_legacy_secrets(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let legacy: Vec<(i32, String)> = sqlx::query_as(
+        "SELECT id, secret FROM webhook_endpoints \
+         WHERE sealed_secret IS NULL AND secret IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+    let mut sealed_count = 0;
+    for (endpoint_id, secret) in legacy {
+        let sealed = match seal_stored_value(pool, &secret).await {
+            Ok(sealed) => sealed,
+            Err((_, msg)) => {
+                error!(endpoint_id, %msg, "failed to seal webhook signing secret");
+                continue;
+            }
+        };
+        sqlx::query(
+            "UPDATE webhook_endpoints SET sealed_secret = $2, secret = NULL \
+             WHERE id = $1 AND sealed_secret IS NULL",
+        )
+        .bind(endpoint_id)
+        .bind(sealed)
+        .execute(pool)
+        .await?;
+        sealed_count += 1;
+    }
+    Ok(sealed_count)
+}
+
+pub fn spawn_dispatcher(pool: PgPool) {
+    let dispatcher = WebhookDispatcher::from_config();
+    let interval = Duration::from_millis(*config::WEBHOOK_POLL_INTERVAL_MS);
+    tokio::spawn(async move {
+        if let Err(err) = seal_legacy_secrets(&pool).await {
+            error!(?err, "failed to seal legacy webhook secrets");
+        }
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = dispatcher.dispatch_due(&pool).await {
+                error!(?err, "webhook dispatch pass failed");
+            }
+        }
+    });
+}
+
+/// Publishes trust transitions and remediation status changes from their broadcast
+/// channels. Events dropped while a receiver lagged are logged, not recovered.
+pub fn spawn_event_bridge(pool: PgPool) {
+    let trust_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut receiver = subscribe_registry_events();
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "webhook bridge lagged behind trust events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let payload = serde_json::to_value(&event).unwrap_or(Value::Null);
+            if let Err(err) = publish_event(
+                &trust_pool,
+                TRUST_TRANSITION,
+                Some(event.owner_id),
+                &payload,
+            )
+            .await
+            {
+                error!(
+                    ?err,
+                    vm_instance_id = event.vm_instance_id,
+                    "failed to publish trust webhook event"
+                );
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut receiver = subscribe_remediation_events();
+        loop {
+            let message = match receiver.recv().await {
+                Ok(message) => message,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "webhook bridge lagged behind remediation events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            // log lines stay on the SSE stream; only state changes are pushed
+            if !matches!(message.event, RemediationStreamEvent::Status { .. }) {
+                continue;
+            }
+            let owner_id: Option<i32> = match sqlx::query_scalar(
+                "SELECT s.owner_id FROM runtime_vm_instances i \
+                 JOIN mcp_servers s ON s.id = i.server_id WHERE i.id = $1",
+            )
+            .bind(message.instance_id)
+            .fetch_optional(&pool)
+            .await
+            {
+                Ok(owner_id) => owner_id,
+                Err(err) => {
+                    error!(
+                        ?err,
+                        run_id = message.run_id,
+                        "failed to resolve remediation webhook owner"
+                    );
+                    continue;
+                }
+            };
+            let payload = serde_json::to_value(&message).unwrap_or(Value::Null);
+            if let Err(err) =
+                publish_event(&pool, REMEDIATION_STATUS_CHANGED, owner_id, &payload).await
+            {
+                error!(
+                    ?err,
+                    run_id = message.run_id,
+                    "failed to publish remediation webhook event"
+                );
+            }
+        }
+    });
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookEndpoint {
+    pub url: String,
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct WebhookEndpoint {
+    pub id: i32,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhookEndpoint {
+    #[serde(flatten)]
+    pub endpoint: WebhookEndpoint,
+    /// Returned only once, at registration.
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub event_id: i64,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// POST /api/webhooks/endpoints
+pub async fn create_endpoint(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Json(payload): Json<CreateWebhookEndpoint>,
+) -> AppResult<(StatusCode, Json<CreatedWebhookEndpoint>)> {
+    let url = reqwest::Url::parse(payload.url.trim())
+        .map_err(|e| AppError::BadRequest(format!("invalid webhook url: {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::BadRequest(
+            "webhook url must use http or https".into(),
+        ));
+    }
+    if let Some(unknown) = payload
+        .event_types
+        .iter()
+        .find(|event_type| !EVENT_TYPES.contains(&event_type.as_str()))
+    {
+        return Err(AppError::BadRequest(format!(
+            "unknown event type `{unknown}`"
+        )));
+    }
+    if !*config::WEBHOOK_ALLOW_PRIVATE_TARGETS {
+        target::resolve_public(&url)
+            .await
+            .map_err(AppError::BadRequest)?;
+    }
+    let secret = Uuid::new_v4().simple().to_string();
+    let sealed = seal_stored_value(&pool, &secret)
+        .await
+        .map_err(|(_, msg)| AppError::Message(msg))?;
+    let endpoint: WebhookEndpoint = sqlx::query_as(
+        "INSERT INTO webhook_endpoints (owner_id, url, sealed_secret, event_types) \
+         VALUES ($1, $2, $3, $4) RETURNING id, url, event_types, active, created_at",
+    )
+    .bind(user_id)
+    .bind(url.as_str())
+    .bind(&sealed)
+    .bind(&payload.event_types)
+    .fetch_one(&pool)
+    .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhookEndpoint { endpoint, secret }),
+    ))
+}
+
+/// GET /api/webhooks/endpoints
+pub async fn list_endpoints(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+) -> AppResult<Json<Vec<WebhookEndpoint>>> {
+    let endpoints = sqlx::query_as(
+        "SELECT id, url, event_types, active, created_at FROM webhook_endpoints \
+         WHERE owner_id = $1 ORDER BY id",
+    )
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(endpoints))
+}
+
+/// DELETE /api/webhooks/endpoints/:id
+pub async fn delete_endpoint(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(endpoint_id): Path<i32>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1 AND owner_id = $2")
+        .bind(endpoint_id)
+        .bind(user_id)
+        .execute(&pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/webhooks/endpoints/:id/deliveries
+pub async fn list_deliveries(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(endpoint_id): Path<i32>,
+) -> AppResult<Json<Vec<WebhookDelivery>>> {
+    let owned: Option<i32> =
+        sqlx::query_scalar("SELECT id FROM webhook_endpoints WHERE id = $1 AND owner_id = $2")
+            .bind(endpoint_id)
+            .bind(user_id)
+            .fetch_optional(&pool)
+            .await?;
+    if owned.is_none() {
+        return Err(AppError::NotFound);
+    }
+    let deliveries = sqlx::query_as(
+        "SELECT d.id, d.event_id, e.event_type, d.status, d.attempts, d.last_status_code, \
+                d.last_error, d.next_attempt_at, d.delivered_at \
+         FROM webhook_deliveries d JOIN webhook_events e ON e.id = d.event_id \
+         WHERE d.endpoint_id = $1 ORDER BY d.event_id DESC LIMIT 100",
+    )
+    .bind(endpoint_id)
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(deliveries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::Router;
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::{Arc, Mutex};
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, Bytes)>>>;
+
+    fn stub_receiver(status: StatusCode) -> (String, Received) {
+        let received = Received::default();
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let sink = received.clone();
+        let app = Router::new().route(
+            "/hooks",
+            post(move |headers: HeaderMap, body: Bytes| async move {
+                sink.lock().unwrap().push((headers, body));
+                status
+            }),
+        );
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        (url, received)
+    }
+
+    async fn register(pool: &PgPool, url: &str) -> (i32, i32) {
+        let user_id: i32 = sqlx::query_scalar(
+            "INSERT INTO users (email, password_hash) VALUES ('hooks@example.com', 'x') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let endpoint_id: i32 = sqlx::query_scalar(
+            "INSERT INTO webhook_endpoints (owner_id, url, secret) VALUES ($1, $2, 'shh') RETURNING id",
+        )
+        .bind(user_id)
+        .bind(url)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        (user_id, endpoint_id)
+    }
+
+    /// Stub receivers listen on loopback, so the target guard is relaxed here.
+    fn immediate_retries(max_attempts: i32) -> WebhookDispatcher {
+        WebhookDispatcher {
+            allow_private_targets: true,
+            ..WebhookDispatcher::new(
+                RetryPolicy {
+                    max_attempts,
+                    base_backoff: Duration::ZERO,
+                    max_backoff: Duration::ZERO,
+                },
+                Duration::from_secs(5),
+            )
+        }
+    }
+
+    #[test]
+    fn signatures_bind_timestamp_and_body() {
+        let signature = sign_payload("shh", 1_700_000_000, br#"{"id":1}"#);
+        assert!(signature.starts_with("sha256="));
+        assert!(verify_signature(
+            "shh",
+            1_700_000_000,
+            br#"{"id":1}"#,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "shh",
+            1_700_000_001,
+            br#"{"id":1}"#,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "shh",
+            1_700_000_000,
+            br#"{"id":2}"#,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "other",
+            1_700_000_000,
+            br#"{"id":1}"#,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "shh",
+            1_700_000_000,
+            br#"{"id":1}"#,
+            "sha256=zz"
+        ));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn internal_targets_are_refused_at_delivery(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let (url, received) = stub_receiver(StatusCode::NO_CONTENT);
+        let (user_id, _) = register(&pool, &url).await;
+        let event_id = publish_event(&pool, TRUST_TRANSITION, Some(user_id), &json!({}))
+            .await
+            .unwrap();
+
+        let dispatcher = WebhookDispatcher::new(
+            RetryPolicy {
+                max_attempts: 3,
+                base_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+            },
+            Duration::from_secs(5),
+        );
+        let report = dispatcher.dispatch_due(&pool).await.unwrap();
+        assert_eq!(report.retrying, 1);
+        assert!(received.lock().unwrap().is_empty());
+        let last_error: Option<String> =
+            sqlx::query_scalar("SELECT last_error FROM webhook_deliveries WHERE event_id = $1")
+                .bind(event_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(last_error.unwrap().contains("not a public address"));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 8,
+            base_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(60),
+        };
+        let waits: Vec<u64> = (1..=6).map(|n| policy.backoff(n).as_secs()).collect();
+        assert_eq!(waits, vec![5, 10, 20, 40, 60, 60]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn delivers_signed_event_once(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let (url, received) = stub_receiver(StatusCode::NO_CONTENT);
+        let (user_id, endpoint_id) = register(&pool, &url).await;
+        assert_eq!(seal_legacy_secrets(&pool).await.unwrap(), 1);
+        let (plaintext, sealed): (Option<String>, Option<Vec<u8>>) =
+            sqlx::query_as("SELECT secret, sealed_secret FROM webhook_endpoints WHERE id = $1")
+                .bind(endpoint_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(plaintext.is_none());
+        assert!(sealed.is_some());
+
+        let first = publish_event(&pool, TRUST_TRANSITION, Some(user_id), &json!({"n": 1}))
+            .await
+            .unwrap();
+        let second = publish_event(&pool, TRUST_TRANSITION, Some(user_id), &json!({"n": 2}))
+            .await
+            .unwrap();
+        assert!(second > first, "event ids increase");
+
+        let report = immediate_retries(3).dispatch_due(&pool).await.unwrap();
+        assert_eq!(report.delivered, 2);
+        assert_eq!(
+            immediate_retries(3).dispatch_due(&pool).await.unwrap(),
+            DispatchReport::default()
+        );
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 2);
+        let (headers, body) = &received[0];
+        let header = |name: &str| headers[name].to_str().unwrap().to_string();
+        assert_eq!(header(EVENT_ID_HEADER), first.to_string());
+        assert_eq!(header(EVENT_TYPE_HEADER), TRUST_TRANSITION);
+        let timestamp: i64 = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert!(verify_signature(
+            "shh",
+            timestamp,
+            body,
+            &header(SIGNATURE_HEADER)
+        ));
+        let envelope: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(envelope["id"], first);
+        assert_eq!(envelope["data"], json!({"n": 1}));
+
+        let (status, attempts, code): (String, i32, Option<i32>) = sqlx::query_as(
+            "SELECT status, attempts, last_status_code FROM webhook_deliveries \
+             WHERE endpoint_id = $1 AND event_id = $2",
+        )
+        .bind(endpoint_id)
+        .bind(first)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            (status.as_str(), attempts, code),
+            ("delivered", 1, Some(204))
+        );
+
+        // events for other owners never reach this endpoint
+        publish_event(&pool, TRUST_TRANSITION, None, &json!({}))
+            .await
+            .unwrap();
+        assert_eq!(
+            immediate_retries(3).dispatch_due(&pool).await.unwrap(),
+            DispatchReport::default()
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires DATABASE_URL with Postgres server"]
+    async fn failing_endpoint_is_retried_then_dead_lettered(pool: PgPool) {
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let (url, received) = stub_receiver(StatusCode::INTERNAL_SERVER_ERROR);
+        let (user_id, _) = register(&pool, &url).await;
+        let event_id = publish_event(
+            &pool,
+            REMEDIATION_STATUS_CHANGED,
+            Some(user_id),
+            &json!({"status": "failed"}),
+        )
+        .await
+        .unwrap();
+
+        let dispatcher = immediate_retries(3);
+        let mut reports = Vec::new();
+        for _ in 0..4 {
+            reports.push(dispatcher.dispatch_due(&pool).await.unwrap());
+        }
+        let retrying = DispatchReport {
+            retrying: 1,
+            ..DispatchReport::default()
+        };
+        let dead = DispatchReport {
+            dead_lettered: 1,
+            ..DispatchReport::default()
+        };
+        assert_eq!(
+            reports,
+            vec![retrying.clone(), retrying, dead, DispatchReport::default()]
+        );
+
+        // every retry resends the same event id so the receiver can dedupe
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 3);
+        assert!(received
+            .iter()
+            .all(|(headers, _)| headers[EVENT_ID_HEADER] == event_id.to_string().as_str()));
+
+        let (status, last_error): (String, Option<String>) =
+            sqlx::query_as("SELECT status, last_error FROM webhook_deliveries WHERE event_id = $1")
+                .bind(event_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "dead_letter");
+        assert!(last_error.unwrap().contains("500"));
+        let attempts: Vec<(i32, Option<i32>)> = sqlx::query_as(
+            "SELECT a.attempt, a.status_code FROM webhook_delivery_attempts a \
+             JOIN webhook_deliveries d ON d.id = a.delivery_id \
+             WHERE d.event_id = $1 ORDER BY a.attempt",
+        )
+        .bind(event_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            attempts,
+            vec![(1, Some(500)), (2, Some(500)), (3, Some(500))]
+        );
+    }
+}
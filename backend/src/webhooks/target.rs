@@ -0,0 +1,111 @@
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use url::Host;
+
+// key: webhooks-target-guard -> keep webhook deliveries off internal networks
+
+/// Whether `ip` is routable on the public internet. Loopback, RFC 1918, carrier-grade NAT,
+/// link-local (including the `169.254.169.254` metadata service), unique-local, multicast and
+/// unspecified addresses are not.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_v4(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || first == 0
+        || (first == 100 && (64..128).contains(&second)))
+}
+
+/// Resolves the host of `url` and returns its addresses, refusing the target if any of them
+/// is not public. Delivery connects to exactly these addresses, so a DNS answer that changes
+/// after the check cannot redirect it.
+pub async fn resolve_public(url: &Url) -> Result<Vec<SocketAddr>, String> {
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| "webhook url has no port".to_string())?;
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| format!("cannot resolve `{domain}`: {e}"))?
+            .collect(),
+        None => return Err("webhook url has no host".into()),
+    };
+    if addrs.is_empty() {
+        return Err("webhook host resolved to no addresses".into());
+    }
+    if let Some(blocked) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(format!(
+            "webhook target {} is not a public address",
+            blocked.ip()
+        ));
+    }
+    Ok(addrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for blocked in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public(blocked.parse().unwrap()), "{blocked}");
+        }
+        for allowed in ["93.184.216.34", "172.32.0.1", "2606:4700::1111"] {
+            assert!(is_public(allowed.parse().unwrap()), "{allowed}");
+        }
+    }
+
+    #[tokio::test]
+    async fn literal_targets_are_checked_without_dns() {
+        let metadata = Url::parse("http://169.254.169.254/latest/meta-data").unwrap();
+        assert!(resolve_public(&metadata)
+            .await
+            .unwrap_err()
+            .contains("169.254.169.254"));
+        let loopback = Url::parse("https://[::1]:8443/hooks").unwrap();
+        assert!(resolve_public(&loopback).await.is_err());
+
+        let public = Url::parse("https://93.184.216.34/hooks").unwrap();
+        assert_eq!(
+            resolve_public(&public).await.unwrap(),
+            vec!["93.184.216.34:443".parse().unwrap()]
+        );
+    }
+}
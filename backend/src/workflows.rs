@@ -1,5 +1,6 @@
 use crate::extractor::AuthUser;
 use crate::servers::invoke_server_internal; // internal helper
+use async_trait::async_trait;
 use axum::{
     extract::{Extension, Path},
     http::StatusCode,
@@ -10,6 +11,14 @@ use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use tracing::error;
 
+pub mod branching;
+pub mod engine;
+pub mod retry;
+
+use branching::{validate_routes, StepBranch, StepRouting, StepTarget};
+use engine::{run_steps, StepExecutor, StepRecord, StepResult, WorkflowStep};
+use retry::StepRetryPolicy;
+
 #[derive(Serialize)]
 pub struct Workflow {
     pub id: i32,
@@ -20,7 +29,48 @@ pub struct Workflow {
 #[derive(Deserialize)]
 pub struct CreateWorkflow {
     pub name: String,
-    pub steps: Vec<i32>, // server ids
+    pub steps: Vec<StepSpec>,
+}
+
+/// A step is either a bare server id or a server id with a retry policy and routing.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum StepSpec {
+    Server(i32),
+    Configured {
+        server_id: i32,
+        #[serde(default)]
+        retry: Option<StepRetryPolicy>,
+        #[serde(default)]
+        next: Option<StepTarget>,
+        #[serde(default)]
+        branch: Option<StepBranch>,
+    },
+}
+
+impl StepSpec {
+    fn server_id(&self) -> i32 {
+        match self {
+            StepSpec::Server(id) | StepSpec::Configured { server_id: id, .. } => *id,
+        }
+    }
+
+    fn retry(&self) -> Option<&StepRetryPolicy> {
+        match self {
+            StepSpec::Server(_) => None,
+            StepSpec::Configured { retry, .. } => retry.as_ref(),
+        }
+    }
+
+    fn routing(&self) -> StepRouting {
+        match self {
+            StepSpec::Server(_) => StepRouting::default(),
+            StepSpec::Configured { next, branch, .. } => StepRouting {
+                next: *next,
+                branch: branch.clone(),
+            },
+        }
+    }
 }
 
 pub async fn list_workflows(
@@ -52,6 +102,15 @@ pub async fn create_workflow(
     AuthUser { user_id, .. }: AuthUser,
     Json(payload): Json<CreateWorkflow>,
 ) -> Result<Json<Workflow>, (StatusCode, String)> {
+    for (pos, step) in payload.steps.iter().enumerate() {
+        if let Some(policy) = step.retry() {
+            policy
+                .validate()
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("step {}: {e}", pos + 1)))?;
+        }
+    }
+    let routes: Vec<StepRouting> = payload.steps.iter().map(StepSpec::routing).collect();
+    validate_routes(&routes).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
     let rec = sqlx::query(
         "INSERT INTO workflows (owner_id, name) VALUES ($1,$2) RETURNING id, created_at",
     )
@@ -64,13 +123,20 @@ pub async fn create_workflow(
         (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
     })?;
     let wf_id: i32 = rec.get("id");
-    for (pos, sid) in payload.steps.iter().enumerate() {
+    for (pos, (step, routing)) in payload.steps.iter().zip(&routes).enumerate() {
+        let retry_policy = step
+            .retry()
+            .map(|policy| serde_json::to_value(policy).unwrap_or_default());
+        let routing =
+            (!routing.is_linear()).then(|| serde_json::to_value(routing).unwrap_or_default());
         if let Err(e) = sqlx::query(
-            "INSERT INTO workflow_steps (workflow_id, position, server_id) VALUES ($1,$2,$3)",
+            "INSERT INTO workflow_steps (workflow_id, position, server_id, retry_policy, routing) VALUES ($1,$2,$3,$4,$5)",
         )
         .bind(wf_id)
         .bind((pos + 1) as i32)
-        .bind(*sid)
+        .bind(step.server_id())
+        .bind(retry_policy)
+        .bind(routing)
         .execute(&pool)
         .await
         {
@@ -110,34 +176,160 @@ pub struct InvokeInput {
     pub input: serde_json::Value,
 }
 
+/// Invokes MCP servers on behalf of the workflow caller.
+struct ServerStepExecutor<'a> {
+    pool: &'a PgPool,
+    user_id: i32,
+}
+
+#[async_trait]
+impl StepExecutor for ServerStepExecutor<'_> {
+    async fn execute(&self, server_id: i32, input: &serde_json::Value) -> StepResult {
+        // assumes same user ownership enforced in invoke_server_internal
+        invoke_server_internal(self.pool, self.user_id, server_id, input).await
+    }
+}
+
 pub async fn invoke_workflow(
     Extension(pool): Extension<PgPool>,
     AuthUser { user_id, .. }: AuthUser,
     Path(id): Path<i32>,
     Json(payload): Json<InvokeInput>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let steps =
-        sqlx::query("SELECT server_id FROM workflow_steps WHERE workflow_id=$1 ORDER BY position")
-            .bind(id)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| {
-                error!(?e, "DB error fetching steps");
-                (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
-            })?;
-    if steps.is_empty() {
+    let rows = sqlx::query(
+        "SELECT position, server_id, retry_policy, routing FROM workflow_steps WHERE workflow_id=$1 ORDER BY position",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error fetching steps");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    if rows.is_empty() {
         return Err((StatusCode::BAD_REQUEST, "Workflow has no steps".into()));
     }
-    let mut data = payload.input;
-    for row in steps {
-        let sid: i32 = row.get("server_id");
-        // call server internally; assumes same user ownership enforced in invoke_server_internal
-        match invoke_server_internal(&pool, user_id, sid, &data).await {
-            Ok(out) => data = out,
-            Err(e) => return Err(e),
-        }
+    let steps: Vec<WorkflowStep> = rows
+        .into_iter()
+        .map(|row| {
+            let retry = row
+                .get::<Option<serde_json::Value>, _>("retry_policy")
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default();
+            let routing = row
+                .get::<Option<serde_json::Value>, _>("routing")
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default();
+            WorkflowStep {
+                position: row.get("position"),
+                server_id: row.get("server_id"),
+                retry,
+                routing,
+            }
+        })
+        .collect();
+
+    let run_id: i64 = sqlx::query(
+        "INSERT INTO workflow_runs (workflow_id, triggered_by, input) VALUES ($1,$2,$3) RETURNING id",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(&payload.input)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error recording workflow run");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?
+    .get("id");
+
+    let executor = ServerStepExecutor {
+        pool: &pool,
+        user_id,
+    };
+    let outcome = run_steps(&executor, &steps, payload.input).await;
+    let step_attempts = serde_json::to_value(&outcome.steps).unwrap_or_default();
+    let (status, output, failure) = match &outcome.result {
+        Ok(output) => ("succeeded", Some(output), None),
+        Err(failure) => ("failed", None, Some(failure)),
+    };
+    if let Err(e) = sqlx::query(
+        r#"
+        UPDATE workflow_runs
+        SET status = $2,
+            output = $3,
+            error = $4,
+            failed_step_position = $5,
+            step_attempts = $6,
+            finished_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(run_id)
+    .bind(status)
+    .bind(output.cloned())
+    .bind(failure.map(|f| f.message.clone()))
+    .bind(failure.map(|f| f.position))
+    .bind(step_attempts)
+    .execute(&pool)
+    .await
+    {
+        error!(?e, run_id, "DB error finalizing workflow run");
     }
-    Ok(Json(data))
+
+    outcome
+        .result
+        .map(Json)
+        .map_err(|failure| (failure.status, failure.message))
+}
+
+#[derive(Serialize)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub status: String,
+    pub error: Option<String>,
+    pub failed_step_position: Option<i32>,
+    pub steps: Vec<StepRecord>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub async fn list_workflow_runs(
+    Extension(pool): Extension<PgPool>,
+    AuthUser { user_id, .. }: AuthUser,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<WorkflowRun>>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.id, r.status, r.error, r.failed_step_position, r.step_attempts, r.started_at, r.finished_at
+        FROM workflow_runs r
+        JOIN workflows w ON w.id = r.workflow_id
+        WHERE r.workflow_id = $1 AND w.owner_id = $2
+        ORDER BY r.started_at DESC
+        LIMIT 50
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e, "DB error listing workflow runs");
+        (StatusCode::INTERNAL_SERVER_ERROR, "DB error".into())
+    })?;
+    let runs = rows
+        .into_iter()
+        .map(|r| WorkflowRun {
+            id: r.get("id"),
+            status: r.get("status"),
+            error: r.get("error"),
+            failed_step_position: r.get("failed_step_position"),
+            steps: serde_json::from_value(r.get("step_attempts")).unwrap_or_default(),
+            started_at: r.get("started_at"),
+            finished_at: r.get("finished_at"),
+        })
+        .collect();
+    Ok(Json(runs))
 }
 
 pub fn routes() -> Router {
@@ -145,4 +337,5 @@ pub fn routes() -> Router {
         .route("/api/workflows", get(list_workflows).post(create_workflow))
         .route("/api/workflows/:id", delete(delete_workflow))
         .route("/api/workflows/:id/invoke", post(invoke_workflow))
+        .route("/api/workflows/:id/runs", get(list_workflow_runs))
 }
@@ -0,0 +1,428 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+// key: workflow-branching -> conditional edges between steps
+
+/// Where control goes after a step: another step (by 1-based position) or the end of the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepTarget {
+    Step(i32),
+    End,
+}
+
+impl fmt::Display for StepTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepTarget::Step(position) => write!(f, "step {position}"),
+            StepTarget::End => f.write_str("end"),
+        }
+    }
+}
+
+impl Serialize for StepTarget {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            StepTarget::Step(position) => serializer.serialize_i32(*position),
+            StepTarget::End => serializer.serialize_str("end"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StepTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Step(i32),
+            Named(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Step(position) => Ok(StepTarget::Step(position)),
+            Raw::Named(name) if name == "end" => Ok(StepTarget::End),
+            Raw::Named(name) => Err(de::Error::custom(format!(
+                "unknown step target `{name}`; expected a step position or \"end\""
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionOp {
+    Exists,
+    #[default]
+    Truthy,
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// Predicate over the accumulated run context `{"input": ..., "steps": {"<position>": output}}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Condition {
+    pub path: String,
+    #[serde(default)]
+    pub op: ConditionOp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+impl Condition {
+    pub fn validate(&self) -> Result<(), String> {
+        JsonPath::parse(&self.path)?;
+        match self.op {
+            ConditionOp::Exists | ConditionOp::Truthy => Ok(()),
+            ConditionOp::Eq | ConditionOp::Ne if self.value.is_some() => Ok(()),
+            ConditionOp::Gt | ConditionOp::Gte | ConditionOp::Lt | ConditionOp::Lte
+                if self.value.as_ref().is_some_and(Value::is_number) =>
+            {
+                Ok(())
+            }
+            _ => Err(format!(
+                "operator {:?} requires a{} `value`",
+                self.op,
+                if matches!(self.op, ConditionOp::Eq | ConditionOp::Ne) {
+                    ""
+                } else {
+                    " numeric"
+                }
+            )),
+        }
+    }
+
+    /// Evaluates the predicate; a path that does not resolve only satisfies `ne`.
+    pub fn evaluate(&self, context: &Value) -> bool {
+        let Ok(path) = JsonPath::parse(&self.path) else {
+            return false;
+        };
+        let actual = path.resolve(context);
+        let expected = self.value.as_ref();
+        match self.op {
+            ConditionOp::Exists => actual.is_some(),
+            ConditionOp::Truthy => actual.is_some_and(is_truthy),
+            ConditionOp::Eq => actual.is_some() && actual == expected,
+            ConditionOp::Ne => actual != expected,
+            ConditionOp::Gt => compare(actual, expected).is_some_and(|o| o.is_gt()),
+            ConditionOp::Gte => compare(actual, expected).is_some_and(|o| o.is_ge()),
+            ConditionOp::Lt => compare(actual, expected).is_some_and(|o| o.is_lt()),
+            ConditionOp::Lte => compare(actual, expected).is_some_and(|o| o.is_le()),
+        }
+    }
+}
+
+fn compare(actual: Option<&Value>, expected: Option<&Value>) -> Option<std::cmp::Ordering> {
+    actual?.as_f64()?.partial_cmp(&expected?.as_f64()?)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(flag) => *flag,
+        Value::Number(number) => number.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(fields) => !fields.is_empty(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Dotted JSON-path subset: `$`, `.key`, `[index]`, and `['key']`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath(Vec<PathSegment>);
+
+impl JsonPath {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid JSON path `{expression}`");
+        let rest = expression.trim().strip_prefix('$').ok_or_else(invalid)?;
+        let chars: Vec<char> = rest.chars().collect();
+        let mut segments = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    let start = i + 1;
+                    let mut end = start;
+                    while end < chars.len()
+                        && (chars[end].is_alphanumeric() || matches!(chars[end], '_' | '-'))
+                    {
+                        end += 1;
+                    }
+                    if end == start {
+                        return Err(invalid());
+                    }
+                    segments.push(PathSegment::Key(chars[start..end].iter().collect()));
+                    i = end;
+                }
+                '[' => {
+                    let close = chars[i..]
+                        .iter()
+                        .position(|c| *c == ']')
+                        .map(|offset| i + offset)
+                        .ok_or_else(invalid)?;
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    let quoted = inner
+                        .strip_prefix('\'')
+                        .and_then(|s| s.strip_suffix('\''))
+                        .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                    let segment = match quoted {
+                        Some(key) => PathSegment::Key(key.to_string()),
+                        None => PathSegment::Index(inner.parse().map_err(|_| invalid())?),
+                    };
+                    segments.push(segment);
+                    i = close + 1;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(Self(segments))
+    }
+
+    pub fn resolve<'a>(&self, root: &'a Value) -> Option<&'a Value> {
+        self.0
+            .iter()
+            .try_fold(root, |current, segment| match segment {
+                PathSegment::Key(key) => match current {
+                    Value::Object(fields) => fields.get(key),
+                    Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+                    _ => None,
+                },
+                PathSegment::Index(index) => current.as_array()?.get(*index),
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepBranch {
+    #[serde(rename = "if")]
+    pub when: Condition,
+    pub then: StepTarget,
+    #[serde(rename = "else")]
+    pub otherwise: StepTarget,
+}
+
+/// Outgoing edges of a step. Without either field the run falls through to the next position.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StepRouting {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<StepTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<StepBranch>,
+}
+
+impl StepRouting {
+    pub fn is_linear(&self) -> bool {
+        self.next.is_none() && self.branch.is_none()
+    }
+
+    /// Picks the following step, returning whether a branch condition matched.
+    pub fn resolve(
+        &self,
+        position: i32,
+        step_count: usize,
+        context: &Value,
+    ) -> (StepTarget, Option<bool>) {
+        if let Some(branch) = &self.branch {
+            let matched = branch.when.evaluate(context);
+            let target = if matched {
+                branch.then
+            } else {
+                branch.otherwise
+            };
+            return (target, Some(matched));
+        }
+        let target = self
+            .next
+            .unwrap_or_else(|| fall_through(position, step_count));
+        (target, None)
+    }
+
+    fn targets(&self, position: i32, step_count: usize) -> Vec<StepTarget> {
+        match (&self.branch, self.next) {
+            (Some(branch), _) => vec![branch.then, branch.otherwise],
+            (None, Some(next)) => vec![next],
+            (None, None) => vec![fall_through(position, step_count)],
+        }
+    }
+}
+
+fn fall_through(position: i32, step_count: usize) -> StepTarget {
+    if (position as usize) < step_count {
+        StepTarget::Step(position + 1)
+    } else {
+        StepTarget::End
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DefinitionError {
+    #[error("step {step} sets both `next` and `branch`")]
+    ConflictingRoutes { step: i32 },
+    #[error("step {step} routes to {target}, which does not exist")]
+    DanglingTarget { step: i32, target: i32 },
+    #[error("step {step} routes back to step {target}; branches may only jump forward")]
+    BackwardTarget { step: i32, target: i32 },
+    #[error("step {step} has an invalid branch condition: {reason}")]
+    InvalidCondition { step: i32, reason: String },
+    #[error("steps {steps:?} are unreachable from step 1")]
+    Unreachable { steps: Vec<i32> },
+}
+
+/// Validates routing for steps at positions `1..=routes.len()`.
+///
+/// Targets must exist and point forward, which keeps every run finite, and every
+/// step must be reachable from the first one.
+pub fn validate_routes(routes: &[StepRouting]) -> Result<(), DefinitionError> {
+    let step_count = routes.len();
+    for (index, routing) in routes.iter().enumerate() {
+        let step = index as i32 + 1;
+        if routing.next.is_some() && routing.branch.is_some() {
+            return Err(DefinitionError::ConflictingRoutes { step });
+        }
+        if let Some(branch) = &routing.branch {
+            branch
+                .when
+                .validate()
+                .map_err(|reason| DefinitionError::InvalidCondition { step, reason })?;
+        }
+        for target in routing.targets(step, step_count) {
+            if let StepTarget::Step(target) = target {
+                if target < 1 || target as usize > step_count {
+                    return Err(DefinitionError::DanglingTarget { step, target });
+                }
+                if target <= step {
+                    return Err(DefinitionError::BackwardTarget { step, target });
+                }
+            }
+        }
+    }
+
+    let mut reachable = BTreeSet::new();
+    let mut pending = vec![1];
+    while let Some(step) = pending.pop() {
+        if step as usize > step_count || !reachable.insert(step) {
+            continue;
+        }
+        let routing = &routes[step as usize - 1];
+        for target in routing.targets(step, step_count) {
+            if let StepTarget::Step(target) = target {
+                pending.push(target);
+            }
+        }
+    }
+    let unreachable: Vec<i32> = (1..=step_count as i32)
+        .filter(|step| !reachable.contains(step))
+        .collect();
+    if unreachable.is_empty() {
+        Ok(())
+    } else {
+        Err(DefinitionError::Unreachable { steps: unreachable })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn branch(path: &str, then: StepTarget, otherwise: StepTarget) -> StepRouting {
+        StepRouting {
+            next: None,
+            branch: Some(StepBranch {
+                when: Condition {
+                    path: path.into(),
+                    op: ConditionOp::Truthy,
+                    value: None,
+                },
+                then,
+                otherwise,
+            }),
+        }
+    }
+
+    fn end() -> StepRouting {
+        StepRouting {
+            next: Some(StepTarget::End),
+            branch: None,
+        }
+    }
+
+    #[test]
+    fn dangling_branch_target_is_rejected() {
+        let routes = vec![
+            branch("$.steps.1.ok", StepTarget::Step(2), StepTarget::Step(5)),
+            StepRouting::default(),
+        ];
+        assert_eq!(
+            validate_routes(&routes),
+            Err(DefinitionError::DanglingTarget { step: 1, target: 5 })
+        );
+    }
+
+    #[test]
+    fn unreachable_and_backward_steps_are_rejected() {
+        let routes = vec![end(), StepRouting::default()];
+        assert_eq!(
+            validate_routes(&routes),
+            Err(DefinitionError::Unreachable { steps: vec![2] })
+        );
+
+        let routes = vec![
+            StepRouting::default(),
+            branch("$.input", StepTarget::Step(1), StepTarget::End),
+        ];
+        assert_eq!(
+            validate_routes(&routes),
+            Err(DefinitionError::BackwardTarget { step: 2, target: 1 })
+        );
+
+        let routes = vec![
+            branch("$.steps.1.ok", StepTarget::Step(2), StepTarget::Step(3)),
+            end(),
+            StepRouting::default(),
+        ];
+        assert_eq!(validate_routes(&routes), Ok(()));
+    }
+
+    #[test]
+    fn json_path_resolves_keys_indexes_and_quoted_keys() {
+        let context = json!({
+            "input": {"tags": ["a", "b"]},
+            "steps": {"1": {"score": 0.9, "odd key": true}}
+        });
+        let resolve = |path: &str| JsonPath::parse(path).unwrap().resolve(&context).cloned();
+        assert_eq!(resolve("$.input.tags[1]"), Some(json!("b")));
+        assert_eq!(resolve("$.steps.1.score"), Some(json!(0.9)));
+        assert_eq!(resolve("$.steps['1']['odd key']"), Some(json!(true)));
+        assert_eq!(resolve("$.steps.2"), None);
+        assert!(JsonPath::parse("steps.1").is_err());
+        assert!(JsonPath::parse("$.steps[").is_err());
+
+        let condition = Condition {
+            path: "$.steps.1.score".into(),
+            op: ConditionOp::Gte,
+            value: Some(json!(0.5)),
+        };
+        assert!(condition.evaluate(&context));
+        assert!(Condition {
+            op: ConditionOp::Gt,
+            value: None,
+            ..condition
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,366 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::branching::{StepRouting, StepTarget};
+use super::retry::StepRetryPolicy;
+
+// key: workflow-engine -> step execution, routing, and attempt history
+
+#[derive(Debug, Clone)]
+pub struct WorkflowStep {
+    pub position: i32,
+    pub server_id: i32,
+    pub retry: StepRetryPolicy,
+    pub routing: StepRouting,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepAttempt {
+    pub attempt: u32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub status: StepStatus,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+    pub retryable: bool,
+    /// Delay applied before the next attempt, when one was scheduled.
+    pub backoff_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepRecord {
+    pub position: i32,
+    pub server_id: i32,
+    pub status: StepStatus,
+    pub attempts: Vec<StepAttempt>,
+    /// Outcome of the step's branch condition, when it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_taken: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepFailure {
+    pub position: i32,
+    pub status: StatusCode,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub struct WorkflowOutcome {
+    pub result: Result<Value, StepFailure>,
+    pub steps: Vec<StepRecord>,
+}
+
+pub type StepResult = Result<Value, (StatusCode, String)>;
+
+#[async_trait]
+pub trait StepExecutor: Send + Sync {
+    async fn execute(&self, server_id: i32, input: &Value) -> StepResult;
+}
+
+/// Runs a workflow from step 1, feeding each output into the next step.
+///
+/// Transient failures are retried according to the step's policy, and branch
+/// conditions are evaluated against the accumulated run context
+/// `{"input": ..., "steps": {"<position>": output}}`.
+pub async fn run_steps<E: StepExecutor + ?Sized>(
+    executor: &E,
+    steps: &[WorkflowStep],
+    input: Value,
+) -> WorkflowOutcome {
+    let mut context = json!({ "input": input.clone(), "steps": Map::new() });
+    let mut data = input;
+    let mut records = Vec::with_capacity(steps.len());
+    let mut target = StepTarget::Step(1);
+
+    while let StepTarget::Step(position) = target {
+        let Some(step) = steps.iter().find(|step| step.position == position) else {
+            return WorkflowOutcome {
+                result: Err(StepFailure {
+                    position,
+                    status: StatusCode::INTERNAL_SERVER_ERROR,
+                    message: format!("workflow step {position} does not exist"),
+                }),
+                steps: records,
+            };
+        };
+        // validated definitions only route forward; guard against stale rows anyway
+        if records.len() >= steps.len() {
+            break;
+        }
+
+        let (result, attempts) = execute_with_retry(executor, step, &data).await;
+        match result {
+            Ok(output) => {
+                context["steps"][position.to_string()] = output.clone();
+                let (next, branch_taken) = step.routing.resolve(position, steps.len(), &context);
+                records.push(StepRecord {
+                    position,
+                    server_id: step.server_id,
+                    status: StepStatus::Succeeded,
+                    attempts,
+                    branch_taken,
+                });
+                data = output;
+                target = next;
+            }
+            Err((status, message)) => {
+                records.push(StepRecord {
+                    position,
+                    server_id: step.server_id,
+                    status: StepStatus::Failed,
+                    attempts,
+                    branch_taken: None,
+                });
+                return WorkflowOutcome {
+                    result: Err(StepFailure {
+                        position,
+                        status,
+                        message,
+                    }),
+                    steps: records,
+                };
+            }
+        }
+    }
+
+    WorkflowOutcome {
+        result: Ok(data),
+        steps: records,
+    }
+}
+
+async fn execute_with_retry<E: StepExecutor + ?Sized>(
+    executor: &E,
+    step: &WorkflowStep,
+    input: &Value,
+) -> (StepResult, Vec<StepAttempt>) {
+    let mut attempts = Vec::new();
+    let mut attempt = 1;
+    loop {
+        let started_at = Utc::now();
+        match executor.execute(step.server_id, input).await {
+            Ok(output) => {
+                attempts.push(StepAttempt {
+                    attempt,
+                    started_at,
+                    finished_at: Utc::now(),
+                    status: StepStatus::Succeeded,
+                    http_status: None,
+                    error: None,
+                    retryable: false,
+                    backoff_ms: None,
+                });
+                return (Ok(output), attempts);
+            }
+            Err((status, message)) => {
+                let retryable = step.retry.is_retryable(status);
+                let delay = (retryable && attempt < step.retry.max_attempts)
+                    .then(|| step.retry.delay_after(attempt));
+                attempts.push(StepAttempt {
+                    attempt,
+                    started_at,
+                    finished_at: Utc::now(),
+                    status: StepStatus::Failed,
+                    http_status: Some(status.as_u16()),
+                    error: Some(message.clone()),
+                    retryable,
+                    backoff_ms: delay.map(|d| d.as_millis() as u64),
+                });
+                match delay {
+                    Some(delay) => {
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return (Err((status, message)), attempts),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflows::branching::{Condition, ConditionOp, StepBranch};
+    use crate::workflows::retry::BackoffStrategy;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Replays scripted results per server, then echoes the input once exhausted.
+    struct ScriptedExecutor {
+        script: Mutex<HashMap<i32, Vec<StepResult>>>,
+        calls: Mutex<Vec<i32>>,
+    }
+
+    impl ScriptedExecutor {
+        fn new(script: Vec<(i32, Vec<StepResult>)>) -> Self {
+            Self {
+                script: Mutex::new(
+                    script
+                        .into_iter()
+                        .map(|(id, mut results)| {
+                            results.reverse();
+                            (id, results)
+                        })
+                        .collect(),
+                ),
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StepExecutor for ScriptedExecutor {
+        async fn execute(&self, server_id: i32, input: &Value) -> StepResult {
+            self.calls.lock().unwrap().push(server_id);
+            self.script
+                .lock()
+                .unwrap()
+                .get_mut(&server_id)
+                .and_then(Vec::pop)
+                .unwrap_or_else(|| Ok(input.clone()))
+        }
+    }
+
+    fn policy(max_attempts: u32) -> StepRetryPolicy {
+        StepRetryPolicy {
+            max_attempts,
+            backoff: BackoffStrategy::Fixed,
+            base_delay_ms: 1,
+            max_delay_ms: 1,
+            retryable_statuses: None,
+        }
+    }
+
+    fn step(position: i32, server_id: i32, retry: StepRetryPolicy) -> WorkflowStep {
+        WorkflowStep {
+            position,
+            server_id,
+            retry,
+            routing: StepRouting::default(),
+        }
+    }
+
+    fn approval_workflow() -> Vec<WorkflowStep> {
+        let mut review = step(1, 10, policy(1));
+        review.routing.branch = Some(StepBranch {
+            when: Condition {
+                path: "$.steps.1.approved".into(),
+                op: ConditionOp::Eq,
+                value: Some(json!(true)),
+            },
+            then: StepTarget::Step(2),
+            otherwise: StepTarget::Step(3),
+        });
+        let mut publish = step(2, 20, policy(1));
+        publish.routing.next = Some(StepTarget::End);
+        vec![review, publish, step(3, 30, policy(1))]
+    }
+
+    #[tokio::test]
+    async fn step_succeeds_on_second_attempt() {
+        let executor = ScriptedExecutor::new(vec![(
+            7,
+            vec![
+                Err((StatusCode::SERVICE_UNAVAILABLE, "warming up".into())),
+                Ok(json!({"answer": 42})),
+            ],
+        )]);
+        let steps = vec![step(1, 7, policy(3)), step(2, 8, policy(1))];
+
+        let outcome = run_steps(&executor, &steps, json!({"q": "life"})).await;
+
+        assert_eq!(outcome.result.unwrap(), json!({"answer": 42}));
+        assert_eq!(*executor.calls.lock().unwrap(), vec![7, 7, 8]);
+        let first = &outcome.steps[0];
+        assert_eq!(first.status, StepStatus::Succeeded);
+        assert_eq!(first.attempts.len(), 2);
+        assert_eq!(first.attempts[0].status, StepStatus::Failed);
+        assert_eq!(first.attempts[0].http_status, Some(503));
+        assert!(first.attempts[0].retryable);
+        assert_eq!(first.attempts[0].backoff_ms, Some(1));
+        assert_eq!(first.attempts[1].status, StepStatus::Succeeded);
+        assert_eq!(outcome.steps[1].attempts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_fail_the_workflow() {
+        let executor = ScriptedExecutor::new(vec![(
+            7,
+            vec![
+                Err((StatusCode::BAD_GATEWAY, "upstream down".into())),
+                Err((StatusCode::BAD_GATEWAY, "upstream down".into())),
+                Err((StatusCode::BAD_GATEWAY, "still down".into())),
+            ],
+        )]);
+        let steps = vec![step(1, 7, policy(3)), step(2, 8, policy(1))];
+
+        let outcome = run_steps(&executor, &steps, json!({})).await;
+
+        let failure = outcome.result.unwrap_err();
+        assert_eq!(failure.position, 1);
+        assert_eq!(failure.status, StatusCode::BAD_GATEWAY);
+        assert_eq!(failure.message, "still down");
+        assert_eq!(*executor.calls.lock().unwrap(), vec![7, 7, 7]);
+        assert_eq!(outcome.steps.len(), 1);
+        let attempts = &outcome.steps[0].attempts;
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[2].backoff_ms, None);
+        assert_eq!(outcome.steps[0].status, StepStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn non_retryable_failure_short_circuits() {
+        let executor = ScriptedExecutor::new(vec![(
+            7,
+            vec![Err((StatusCode::NOT_FOUND, "Server not found".into()))],
+        )]);
+        let steps = vec![step(1, 7, policy(5))];
+
+        let outcome = run_steps(&executor, &steps, json!({})).await;
+
+        assert_eq!(outcome.result.unwrap_err().status, StatusCode::NOT_FOUND);
+        assert_eq!(outcome.steps[0].attempts.len(), 1);
+        assert!(!outcome.steps[0].attempts[0].retryable);
+    }
+
+    #[tokio::test]
+    async fn branch_routes_to_then_step_when_condition_holds() {
+        let executor =
+            ScriptedExecutor::new(vec![(10, vec![Ok(json!({"approved": true, "id": 4}))])]);
+
+        let outcome = run_steps(&executor, &approval_workflow(), json!({"id": 4})).await;
+
+        assert_eq!(outcome.result.unwrap(), json!({"approved": true, "id": 4}));
+        assert_eq!(*executor.calls.lock().unwrap(), vec![10, 20]);
+        assert_eq!(outcome.steps[0].branch_taken, Some(true));
+        assert_eq!(outcome.steps[1].branch_taken, None);
+    }
+
+    #[tokio::test]
+    async fn branch_routes_to_else_step_when_condition_fails() {
+        let executor = ScriptedExecutor::new(vec![
+            (10, vec![Ok(json!({"approved": false}))]),
+            (30, vec![Ok(json!({"notified": true}))]),
+        ]);
+
+        let outcome = run_steps(&executor, &approval_workflow(), json!({})).await;
+
+        assert_eq!(outcome.result.unwrap(), json!({"notified": true}));
+        assert_eq!(*executor.calls.lock().unwrap(), vec![10, 30]);
+        assert_eq!(outcome.steps[0].branch_taken, Some(false));
+        let positions: Vec<i32> = outcome.steps.iter().map(|s| s.position).collect();
+        assert_eq!(positions, vec![1, 3]);
+    }
+}
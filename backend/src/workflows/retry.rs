@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+// key: workflow-retry -> per-step retry policy and backoff
+
+/// Upper bound on attempts so a misconfigured step cannot pin a request open.
+pub const MAX_STEP_ATTEMPTS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    Fixed,
+    Linear,
+    #[default]
+    Exponential,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct StepRetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: BackoffStrategy,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Overrides the default classifier; failures with any other status short-circuit.
+    pub retryable_statuses: Option<Vec<u16>>,
+}
+
+impl Default for StepRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: BackoffStrategy::Exponential,
+            base_delay_ms: 200,
+            max_delay_ms: 5_000,
+            retryable_statuses: None,
+        }
+    }
+}
+
+impl StepRetryPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 || self.max_attempts > MAX_STEP_ATTEMPTS {
+            return Err(format!(
+                "max_attempts must be between 1 and {MAX_STEP_ATTEMPTS}"
+            ));
+        }
+        if self.base_delay_ms > self.max_delay_ms {
+            return Err("base_delay_ms must not exceed max_delay_ms".into());
+        }
+        if let Some(statuses) = &self.retryable_statuses {
+            if let Some(invalid) = statuses
+                .iter()
+                .find(|code| StatusCode::from_u16(**code).is_err())
+            {
+                return Err(format!("{invalid} is not a valid HTTP status"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Delay before the attempt following `attempt` (1-based), capped at `max_delay_ms`.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let base = self.base_delay_ms;
+        let millis = match self.backoff {
+            BackoffStrategy::Fixed => base,
+            BackoffStrategy::Linear => base.saturating_mul(u64::from(attempt)),
+            BackoffStrategy::Exponential => {
+                let factor = 1u64
+                    .checked_shl(attempt.saturating_sub(1))
+                    .unwrap_or(u64::MAX);
+                base.saturating_mul(factor)
+            }
+        };
+        Duration::from_millis(millis.min(self.max_delay_ms))
+    }
+
+    pub fn is_retryable(&self, status: StatusCode) -> bool {
+        match &self.retryable_statuses {
+            Some(statuses) => statuses.contains(&status.as_u16()),
+            None => is_transient_status(status),
+        }
+    }
+}
+
+/// Default classifier: timeouts, throttling, and upstream/server faults are transient.
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS
+    ) || (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_strategies_scale_and_cap() {
+        let mut retry = StepRetryPolicy {
+            max_attempts: 5,
+            backoff: BackoffStrategy::Exponential,
+            base_delay_ms: 100,
+            max_delay_ms: 1_000,
+            retryable_statuses: Some(vec![409]),
+        };
+        let delays: Vec<u64> = (1..=5)
+            .map(|n| retry.delay_after(n).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_000]);
+        retry.backoff = BackoffStrategy::Linear;
+        assert_eq!(retry.delay_after(3), Duration::from_millis(300));
+        retry.backoff = BackoffStrategy::Fixed;
+        assert_eq!(retry.delay_after(4), Duration::from_millis(100));
+
+        assert!(retry.is_retryable(StatusCode::CONFLICT));
+        assert!(!retry.is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(StepRetryPolicy {
+            max_attempts: 0,
+            ..StepRetryPolicy::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::seed_vm_server;
+
+// key: api-key-auth-tests -> service principal scoping
+
+fn app(pool: PgPool) -> Router {
+    Router::new()
+        .route(
+            "/api/trust/remediation/runs",
+            post(backend::remediation_api::enqueue_run_handler),
+        )
+        .route(
+            "/api/trust/remediation/runs/:run_id",
+            get(backend::remediation_api::get_run_handler),
+        )
+        .layer(Extension(pool))
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn enqueue(api_key: &str, vm_instance_id: i64) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/api/trust/remediation/runs")
+        .header("content-type", "application/json")
+        .header("X-API-Key", api_key)
+        .body(Body::from(
+            json!({
+                "runtime_vm_instance_id": vm_instance_id,
+                "playbook": "vm.restart.service"
+            })
+            .to_string(),
+        ))
+        .unwrap()
+}
+
+fn fetch_run(api_key: &str, run_id: i64) -> Request<Body> {
+    Request::builder()
+        .uri(format!("/api/trust/remediation/runs/{run_id}"))
+        .header("X-API-Key", api_key)
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn api_key_is_scoped_to_its_own_server(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("automation@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    sqlx::query(
+        "INSERT INTO runtime_vm_remediation_playbooks (playbook_key, display_name, executor_type, owner_id) VALUES ('vm.restart.service', 'Restart VM', 'shell', $1)",
+    )
+    .bind(owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let own_vm = seed_vm_server(&pool, owner_id, "key-a").await;
+    let other_vm = seed_vm_server(&pool, owner_id, "key-b").await;
+    let app = app(pool.clone());
+
+    let (status, body) = send(&app, enqueue("key-a", own_vm)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["runtime_vm_instance_id"], own_vm);
+    assert_eq!(body["run"]["assigned_owner_id"], owner_id);
+    let own_run = body["run"]["id"].as_i64().unwrap();
+
+    let (status, _) = send(&app, fetch_run("key-a", own_run)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // another server's instance is off limits, even with the same owner
+    let (status, _) = send(&app, enqueue("key-a", other_vm)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, fetch_run("key-b", own_run)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, enqueue("not-a-key", own_vm)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -1,4 +1,5 @@
-use backend::billing::BillingService;
+use backend::billing::{render_usage_csv, BillingService};
+use chrono::{TimeZone, Utc};
 use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -154,3 +155,97 @@ async fn billing_missing_subscription_surfaces_veto(pool: PgPool) {
         .notes
         .contains(&"billing:subscription-missing".to_string()));
 }
+
+// key: billing-tests -> usage-export
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn billing_usage_export_renders_csv_and_json(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let user_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("finance@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let organization_id: i32 = sqlx::query_scalar(
+        "INSERT INTO organizations (name, owner_id) VALUES ($1, $2) RETURNING id",
+    )
+    .bind("Export Org")
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let plan_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO billing_plans (id, code, name, amount_cents) VALUES ($1, $2, $3, $4)")
+        .bind(plan_id)
+        .bind("export-plan")
+        .bind("Export Plan")
+        .bind(1000_i32)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let subscription_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO organization_subscriptions (id, organization_id, plan_id, status) VALUES ($1, $2, $3, 'active')",
+    )
+    .bind(subscription_id)
+    .bind(organization_id)
+    .bind(plan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let service = BillingService::new(pool.clone());
+    let jan = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let feb = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+    let mar = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+    service
+        .record_usage(subscription_id, "runtime.hours", jan, feb, 40)
+        .await
+        .unwrap();
+    service
+        .record_usage(subscription_id, "runtime.hours", feb, mar, 2)
+        .await
+        .unwrap();
+    service
+        .record_usage(subscription_id, "marketplace.catalog.listings", jan, feb, 3)
+        .await
+        .unwrap();
+
+    let lines = service
+        .usage_export(organization_id, jan, mar)
+        .await
+        .unwrap();
+    let json_report = serde_json::to_value(&lines).unwrap();
+    let items = json_report.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["entitlement_key"], "marketplace.catalog.listings");
+    assert_eq!(items[0]["total_quantity"], 3);
+    assert_eq!(items[1]["entitlement_key"], "runtime.hours");
+    assert_eq!(items[1]["window_count"], 2);
+    assert_eq!(items[1]["total_quantity"], 42);
+
+    let csv = render_usage_csv(&lines);
+    let rows: Vec<&str> = csv.trim_end().split("\r\n").collect();
+    assert_eq!(
+        rows[0],
+        "entitlement_key,window_count,total_quantity,first_window_start,last_window_end"
+    );
+    assert_eq!(rows.len(), 3);
+    assert!(rows[2].starts_with("runtime.hours,2,42,2024-01-01T00:00:00+00:00"));
+
+    let quiet_start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let empty = service
+        .usage_export(organization_id, quiet_start, jan)
+        .await
+        .unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(serde_json::to_value(&empty).unwrap(), json!([]));
+    assert_eq!(
+        render_usage_csv(&empty),
+        "entitlement_key,window_count,total_quantity,first_window_start,last_window_end\r\n"
+    );
+}
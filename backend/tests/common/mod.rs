@@ -0,0 +1,81 @@
+// key: integration-test-fixtures -> seed rows and bearer tokens shared by the test binaries
+// every test binary compiles its own copy and uses only part of it
+#![allow(dead_code)]
+
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use sqlx::PgPool;
+
+pub const JWT_SECRET: &str = "integration-test-secret";
+
+/// Points the backend at [`JWT_SECRET`]. Call it before the first request so the lazily read
+/// secret matches the tokens below.
+pub fn use_test_jwt_secret() {
+    std::env::set_var("JWT_SECRET", JWT_SECRET);
+}
+
+/// A bearer token for `user_id` with `role`, valid for an hour.
+pub fn token(user_id: i32, role: &str) -> String {
+    let exp = (Utc::now() + Duration::hours(1)).timestamp();
+    encode(
+        &Header::default(),
+        &json!({ "sub": user_id, "role": role, "exp": exp }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+pub async fn seed_user(pool: &PgPool, email: &str) -> i32 {
+    sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash) VALUES ($1, 'hashed') RETURNING id",
+    )
+    .bind(email)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// A running docker server whose name is also its API key.
+pub async fn seed_server(pool: &PgPool, owner_id: i32, name: &str) -> i32 {
+    sqlx::query_scalar(
+        "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, $2, 'docker', '{}'::jsonb, 'running', $2) RETURNING id",
+    )
+    .bind(owner_id)
+    .bind(name)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// An active virtual-machine server whose name is also its API key, with one VM instance.
+/// Returns the instance id.
+pub async fn seed_vm_server(pool: &PgPool, owner_id: i32, name: &str) -> i64 {
+    seed_org_vm_server(pool, owner_id, None, name).await
+}
+
+/// [`seed_vm_server`] with the server assigned to `organization_id`.
+pub async fn seed_org_vm_server(
+    pool: &PgPool,
+    owner_id: i32,
+    organization_id: Option<i32>,
+    name: &str,
+) -> i64 {
+    let server_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mcp_servers (owner_id, organization_id, name, server_type, config, status, api_key) VALUES ($1, $2, $3, 'virtual-machine', '{}'::jsonb, 'active', $3) RETURNING id",
+    )
+    .bind(owner_id)
+    .bind(organization_id)
+    .bind(name)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    sqlx::query_scalar(
+        "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, $2) RETURNING id::BIGINT",
+    )
+    .bind(server_id)
+    .bind(format!("{name}-vm"))
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
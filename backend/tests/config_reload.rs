@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::{routing::get, Router};
+use backend::config::reload::{self, effective_config, ReloadableSettings};
+use backend::remediation::poll::PollBackoff;
+use chrono::{Duration as ChronoDuration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use nix::sys::signal::{raise, Signal};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+// key: config-reload-tests -> SIGHUP reload,effective endpoint
+
+const POLL_MIN: &str = "REMEDIATION_POLL_MIN_INTERVAL_MS";
+const POLL_MAX: &str = "REMEDIATION_POLL_MAX_INTERVAL_MS";
+
+fn token(role: &str) -> String {
+    let exp = (Utc::now() + ChronoDuration::hours(1)).timestamp();
+    encode(
+        &Header::default(),
+        &json!({"sub": 1, "role": role, "exp": exp}),
+        &EncodingKey::from_secret(b"integration-secret"),
+    )
+    .unwrap()
+}
+
+async fn effective(role: &str) -> (StatusCode, Value) {
+    let app = Router::new().route("/api/admin/config/effective", get(effective_config));
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/config/effective")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token(role)))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn delays(backoff: &mut PollBackoff, count: usize) -> Vec<u128> {
+    (0..count)
+        .map(|_| backoff.idle_delay().as_millis())
+        .collect()
+}
+
+#[tokio::test]
+async fn sighup_applies_reloadable_settings_without_a_restart() {
+    std::env::set_var("JWT_SECRET", "integration-secret");
+    std::env::set_var(POLL_MIN, "100");
+    std::env::set_var(POLL_MAX, "400");
+    reload::reload().expect("initial values are valid");
+
+    let mut backoff = PollBackoff::configured();
+    assert_eq!(delays(&mut backoff, 4), vec![100, 200, 400, 400]);
+
+    // lower the cap and deliver a real SIGHUP to this process
+    reload::spawn_sighup_reload().expect("SIGHUP handler installed");
+    let reloads_before = reload::effective().reloads;
+    std::env::set_var(POLL_MAX, "200");
+    raise(Signal::SIGHUP).expect("SIGHUP raised");
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while reload::effective().reloads == reloads_before {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("SIGHUP should trigger a reload");
+
+    // the running backoff picks the new cap up on its next loop
+    backoff.refresh_configured();
+    assert_eq!(delays(&mut backoff, 2), vec![200, 200]);
+    backoff.record_work();
+    assert_eq!(delays(&mut backoff, 3), vec![100, 200, 200]);
+
+    let (status, body) = effective("admin").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["settings"][POLL_MIN], 100);
+    assert_eq!(body["settings"][POLL_MAX], 200);
+    assert_eq!(body["settings"]["LIFECYCLE_CONSOLE_MAX_LIMIT"], 100);
+    assert_eq!(body["last_reload_error"], Value::Null);
+    let (status, _) = effective("operator").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // an invalid value is rejected as a whole and the previous values stay in effect
+    std::env::set_var(POLL_MIN, "soon");
+    std::env::set_var(POLL_MAX, "50");
+    let err = reload::reload().unwrap_err();
+    let vars: Vec<&str> = err.issues.iter().map(|issue| issue.var).collect();
+    assert_eq!(vars, vec![POLL_MIN]);
+    assert_eq!(reload::current().remediation_poll_max_interval_ms, 200);
+    let (_, body) = effective("admin").await;
+    assert_eq!(body["settings"][POLL_MAX], 200);
+    assert!(body["last_reload_error"]
+        .as_str()
+        .unwrap()
+        .contains(POLL_MIN));
+
+    std::env::remove_var(POLL_MIN);
+    std::env::remove_var(POLL_MAX);
+    assert_eq!(reload::reload().unwrap(), vec![POLL_MAX, POLL_MIN]);
+    assert_eq!(reload::current().remediation_poll_min_interval_ms, 250);
+}
+
+#[test]
+fn settings_are_read_from_the_given_lookup() {
+    let values = [
+        ("REMEDIATION_SNAPSHOT_RETENTION", " 5 "),
+        ("INVOCATION_BATCH_MAX_ITEMS", ""),
+    ];
+    let lookup = |var: &str| {
+        values
+            .iter()
+            .find(|(name, _)| *name == var)
+            .map(|(_, value)| value.to_string())
+    };
+    let settings = ReloadableSettings::from_lookup(lookup).unwrap();
+    assert_eq!(settings.remediation_snapshot_retention, 5);
+    assert_eq!(settings.invocation_batch_max_items, 50);
+
+    let err = ReloadableSettings::from_lookup(|var| {
+        (var == "SERVER_DELETE_RETENTION_HOURS").then(|| i64::MAX.to_string())
+    })
+    .unwrap_err();
+    assert_eq!(err.issues[0].var, "SERVER_DELETE_RETENTION_HOURS");
+}
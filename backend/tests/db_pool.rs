@@ -0,0 +1,118 @@
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use backend::db::pool::{sample_pool, DbPoolConfig};
+use once_cell::sync::Lazy;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+// key: db-pool-tests -> config overrides,utilization stats
+
+static ENV_GUARD: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+const POOL_VARS: [&str; 4] = [
+    "DB_POOL_MAX_CONNECTIONS",
+    "DB_POOL_MIN_CONNECTIONS",
+    "DB_POOL_ACQUIRE_TIMEOUT_SECS",
+    "DB_POOL_IDLE_TIMEOUT_SECS",
+];
+
+fn load_pool_config(vars: &[(&str, &str)]) -> DbPoolConfig {
+    let guard = ENV_GUARD.lock().expect("env guard poisoned");
+    let previous: Vec<(&str, Option<String>)> = POOL_VARS
+        .iter()
+        .map(|key| (*key, std::env::var(key).ok()))
+        .collect();
+    for key in POOL_VARS {
+        std::env::remove_var(key);
+    }
+    for (key, value) in vars {
+        std::env::set_var(key, value);
+    }
+
+    let result = catch_unwind(backend::db_pool_config_from_env);
+
+    for (key, old) in previous {
+        match old {
+            Some(value) => std::env::set_var(key, value),
+            None => std::env::remove_var(key),
+        }
+    }
+    drop(guard);
+
+    match result {
+        Ok(config) => config,
+        Err(panic) => resume_unwind(panic),
+    }
+}
+
+#[test]
+fn pool_config_defaults_without_overrides() {
+    assert_eq!(load_pool_config(&[]), DbPoolConfig::default());
+}
+
+#[test]
+fn pool_config_honors_overrides() {
+    let config = load_pool_config(&[
+        ("DB_POOL_MAX_CONNECTIONS", "40"),
+        ("DB_POOL_MIN_CONNECTIONS", "4"),
+        ("DB_POOL_ACQUIRE_TIMEOUT_SECS", "5"),
+        ("DB_POOL_IDLE_TIMEOUT_SECS", "0"),
+    ]);
+    assert_eq!(
+        config,
+        DbPoolConfig {
+            max_connections: 40,
+            min_connections: 4,
+            acquire_timeout: Duration::from_secs(5),
+            idle_timeout: None,
+        }
+    );
+
+    // invalid values fall back, and min never exceeds max
+    let config = load_pool_config(&[
+        ("DB_POOL_MAX_CONNECTIONS", "3"),
+        ("DB_POOL_MIN_CONNECTIONS", "8"),
+        ("DB_POOL_ACQUIRE_TIMEOUT_SECS", "soon"),
+    ]);
+    assert_eq!(config.max_connections, 3);
+    assert_eq!(config.min_connections, 3);
+    assert_eq!(
+        config.acquire_timeout,
+        DbPoolConfig::default().acquire_timeout
+    );
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn pool_stats_reflect_active_checkouts(_options: PgPoolOptions, connect: PgConnectOptions) {
+    let config = DbPoolConfig {
+        max_connections: 3,
+        acquire_timeout: Duration::from_millis(300),
+        ..DbPoolConfig::default()
+    };
+    let pool = config.options().connect_with(connect).await.unwrap();
+
+    let idle = sample_pool(&pool, config.max_connections).await;
+    assert_eq!(idle.in_use, 0);
+    assert!(idle.acquire_wait_ms.is_some());
+
+    let first = pool.acquire().await.unwrap();
+    let second = pool.acquire().await.unwrap();
+    let busy = sample_pool(&pool, config.max_connections).await;
+    assert_eq!(busy.in_use, 2);
+    assert!(busy.size >= 2);
+    assert!(!busy.saturated());
+
+    // with every connection checked out the probe waits out the acquire timeout
+    let third = pool.acquire().await.unwrap();
+    let exhausted = sample_pool(&pool, config.max_connections).await;
+    assert_eq!(exhausted.in_use, 3);
+    assert!(exhausted.saturated());
+    assert_eq!(exhausted.acquire_wait_ms, None);
+
+    drop((first, second, third));
+    let released = sample_pool(&pool, config.max_connections).await;
+    assert_eq!(released.in_use, 0);
+    assert_eq!(released.idle, released.size);
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use std::time::Duration;
+
+use backend::governance::{
+    GovernanceEngine, GovernanceRunStatus, RunStatusUpdateRequest, StartWorkflowRunRequest,
+};
+use sqlx::PgPool;
+
+use common::seed_user;
+
+const DIGEST: &str = "sha256:governance-cache";
+const TIER: &str = "gold";
+
+/// Seeds a promotion track with a governance workflow and an in-progress promotion whose
+/// run is started through `engine`. Returns the owner and run ids.
+async fn seed_promotion(pool: &PgPool, engine: &GovernanceEngine) -> (i32, i64) {
+    let user_id = seed_user(pool, "governor@example.com").await;
+
+    let workflow_id: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO governance_workflows (owner_id, name, workflow_type, tier)
+        VALUES ($1, 'gold promotion', 'promotion', $2)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(TIER)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    let track_id: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO promotion_tracks (owner_id, name, tier, stages, workflow_id)
+        VALUES ($1, 'release', $2, ARRAY['gold']::TEXT[], $3)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(TIER)
+    .bind(workflow_id)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    let run = engine
+        .start_workflow_run(
+            pool,
+            workflow_id,
+            user_id,
+            StartWorkflowRunRequest {
+                target_manifest_digest: Some(DIGEST.to_string()),
+                target_artifact_run_id: None,
+                notes: None,
+                promotion_track_id: Some(track_id),
+                promotion_stage: Some(TIER.to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO artifact_promotions (
+            promotion_track_id, manifest_digest, stage, status, workflow_run_id
+        ) VALUES ($1, $2, $3, 'in_progress', $4)
+        "#,
+    )
+    .bind(track_id)
+    .bind(DIGEST)
+    .bind(TIER)
+    .bind(run.id)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    (user_id, run.id)
+}
+
+async fn activate_out_of_band(pool: &PgPool) {
+    sqlx::query("UPDATE artifact_promotions SET status = 'active' WHERE manifest_digest = $1")
+        .bind(DIGEST)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn gate_evaluation_is_cached_until_a_run_status_change(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let engine = GovernanceEngine::with_gate_cache_ttl(Some(Duration::from_secs(300)));
+    let (user_id, run_id) = seed_promotion(&pool, &engine).await;
+
+    let first = engine
+        .ensure_promotion_ready(&pool, Some(DIGEST), Some(TIER))
+        .await
+        .unwrap();
+    assert!(!first.satisfied);
+    assert_eq!(first.promotion_status.as_deref(), Some("in_progress"));
+
+    // a write that bypasses the engine is not seen while the entry is fresh
+    activate_out_of_band(&pool).await;
+    let second = engine
+        .ensure_promotion_ready(&pool, Some(DIGEST), Some(TIER))
+        .await
+        .unwrap();
+    assert!(!second.satisfied);
+    assert_eq!(second.promotion_status.as_deref(), Some("in_progress"));
+
+    engine
+        .update_run_status(
+            &pool,
+            run_id,
+            user_id,
+            RunStatusUpdateRequest {
+                status: GovernanceRunStatus::Completed,
+                note: None,
+            },
+        )
+        .await
+        .unwrap()
+        .expect("run should belong to the owner");
+
+    let third = engine
+        .ensure_promotion_ready(&pool, Some(DIGEST), Some(TIER))
+        .await
+        .unwrap();
+    assert!(third.satisfied);
+    assert_eq!(third.run_id, Some(run_id));
+    assert_eq!(third.promotion_status.as_deref(), Some("active"));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn gate_cache_can_be_disabled(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let engine = GovernanceEngine::with_gate_cache_ttl(None);
+    seed_promotion(&pool, &engine).await;
+
+    let first = engine
+        .ensure_promotion_ready(&pool, Some(DIGEST), Some(TIER))
+        .await
+        .unwrap();
+    assert!(!first.satisfied);
+
+    activate_out_of_band(&pool).await;
+    let second = engine
+        .ensure_promotion_ready(&pool, Some(DIGEST), Some(TIER))
+        .await
+        .unwrap();
+    assert!(second.satisfied);
+}
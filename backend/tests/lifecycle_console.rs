@@ -1,13 +1,16 @@
 use axum::{routing::get, Extension, Router};
 use backend::db::runtime_vm_remediation_runs::{
-    ensure_remediation_run, EnsureRemediationRunRequest,
+    ensure_remediation_run, get_run_by_id, EnsureRemediationRunRequest,
 };
 use backend::db::runtime_vm_remediation_workspaces::{
     create_workspace, CreateWorkspace, WorkspaceDetails,
 };
 use backend::db::runtime_vm_trust_registry::{upsert_state, UpsertRuntimeVmTrustRegistryState};
+use backend::policy::{PolicyDecision, RuntimeBackend};
+use backend::runtime::{ResourceUsage, RuntimeExecutor};
 use chrono::{Duration, Utc};
 use hyper::{body::HttpBody, Body, Request, StatusCode};
+use jsonwebtoken::{encode, EncodingKey, Header};
 use serde_json::json;
 use sqlx::PgPool;
 use tower::ServiceExt;
@@ -111,6 +114,7 @@ async fn seed_lifecycle_fixture(pool: &PgPool) -> LifecycleFixture {
             workspace_id: Some(workspace.workspace.id),
             workspace_revision_id: Some(revision.id),
             promotion_gate_context: None,
+            scheduled_for: None,
         },
     )
     .await
@@ -476,3 +480,367 @@ async fn lifecycle_console_stream_emits_snapshot_event(pool: PgPool) {
         "expected promotion run deltas in snapshot"
     );
 }
+
+async fn stream_events(app: &Router, last_event_id: &str, count: usize) -> Vec<(String, String)> {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/console/lifecycle/stream")
+                .header("last-event-id", last_event_id)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body();
+    let mut payload = String::new();
+    while payload.matches("\n\n").count() < count {
+        let chunk = body.data().await.expect("stream ended early").unwrap();
+        payload.push_str(std::str::from_utf8(&chunk).expect("utf8"));
+    }
+    payload
+        .split("\n\n")
+        .filter(|frame| frame.contains("event: "))
+        .take(count)
+        .map(|frame| {
+            let field = |name: &str| {
+                frame
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            (field("event: "), field("data: "))
+        })
+        .collect()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn lifecycle_console_stream_validates_last_event_id(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    let fixture = seed_lifecycle_fixture(&pool).await;
+    let workspace_id = fixture.workspace.workspace.id;
+
+    let app = Router::new()
+        .route(
+            "/api/console/lifecycle/stream",
+            get(backend::lifecycle_console::stream_snapshots),
+        )
+        .layer(Extension(pool.clone()));
+
+    // a negative cursor is reported and the stream restarts from the beginning
+    let events = stream_events(&app, "-7", 2).await;
+    assert_eq!(events[0].0, "lifecycle-error");
+    let error: serde_json::Value = serde_json::from_str(&events[0].1).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("cursor ignored"));
+    assert_eq!(events[1].0, "lifecycle-snapshot");
+    assert!(events[1].1.contains(&format!("\"cursor\":{workspace_id}")));
+
+    // a cursor past every workspace keeps the stream alive with heartbeats
+    let events = stream_events(&app, "9000000000000000000", 1).await;
+    assert_eq!(events[0].0, "lifecycle-heartbeat");
+
+    // a valid cursor resumes after it
+    let events = stream_events(&app, &(workspace_id - 1).to_string(), 1).await;
+    assert_eq!(events[0].0, "lifecycle-snapshot");
+    assert!(events[0].1.contains(&format!("\"cursor\":{workspace_id}")));
+    let events = stream_events(&app, &workspace_id.to_string(), 1).await;
+    assert_eq!(events[0].0, "lifecycle-heartbeat");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn lifecycle_console_runs_since_skips_older_runs(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    let fixture = seed_lifecycle_fixture(&pool).await;
+
+    let stale_at = Utc::now() - Duration::days(2);
+    let stale_run_id: i64 = sqlx::query_scalar(
+        r#"
+        INSERT INTO runtime_vm_remediation_runs (
+            runtime_vm_instance_id,
+            playbook,
+            status,
+            metadata,
+            workspace_id,
+            started_at,
+            updated_at
+        )
+        SELECT runtime_vm_instance_id, 'shell:stale', 'completed', metadata, workspace_id, $2, $2
+        FROM runtime_vm_remediation_runs
+        WHERE id = $1
+        RETURNING id
+        "#,
+    )
+    .bind(fixture.run_id)
+    .bind(stale_at)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/console/lifecycle",
+            get(backend::lifecycle_console::list_snapshots),
+        )
+        .layer(Extension(pool.clone()));
+
+    let run_ids = |payload: &serde_json::Value, field: &str| -> Vec<i64> {
+        payload["workspaces"][0][field]
+            .as_array()
+            .unwrap_or_else(|| panic!("{field} array"))
+            .iter()
+            .filter_map(|entry| entry["run"]["id"].as_i64().or(entry["id"].as_i64()))
+            .collect()
+    };
+
+    let fetch = |uri: String| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        }
+    };
+
+    let everything = fetch("/api/console/lifecycle".to_string()).await;
+    let recent = run_ids(&everything, "recent_runs");
+    assert!(recent.contains(&fixture.run_id));
+    assert!(recent.contains(&stale_run_id));
+
+    let since = (Utc::now() - Duration::days(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let polled = fetch(format!("/api/console/lifecycle?runs_since={since}")).await;
+    assert_eq!(run_ids(&polled, "recent_runs"), vec![fixture.run_id]);
+    let promotion = run_ids(&polled, "promotion_runs");
+    assert!(promotion.contains(&fixture.run_id));
+    assert!(!promotion.contains(&stale_run_id));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn manual_override_export_lists_only_overridden_runs(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    std::env::set_var("JWT_SECRET", "integration-secret");
+    let fixture = seed_lifecycle_fixture(&pool).await;
+
+    let auditor_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("auditor@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    // window well before the fixture's own run
+    let window_start = Utc::now() - Duration::days(4);
+    let decided_at = Utc::now() - Duration::days(3);
+    let mut seeded = Vec::new();
+    for (playbook, notes, metadata, actor, decided) in [
+        (
+            "shell:break-glass",
+            Some("break-glass rollout"),
+            json!({}),
+            Some(fixture.owner_id),
+            Some(decided_at),
+        ),
+        (
+            "shell:pager",
+            None,
+            json!({"escalation": {"override_reason": "pager escalation"}}),
+            Some(auditor_id),
+            None,
+        ),
+        ("shell:routine", None, json!({}), None, None),
+    ] {
+        let run_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO runtime_vm_remediation_runs (
+                runtime_vm_instance_id,
+                playbook,
+                status,
+                metadata,
+                workspace_id,
+                approval_notes,
+                approval_decided_at,
+                analytics_override_actor_id,
+                started_at,
+                updated_at
+            )
+            SELECT runtime_vm_instance_id, $2, 'completed', $3, workspace_id, $4, $5, $6, $7, $7
+            FROM runtime_vm_remediation_runs
+            WHERE id = $1
+            RETURNING id
+            "#,
+        )
+        .bind(fixture.run_id)
+        .bind(playbook)
+        .bind(metadata)
+        .bind(notes)
+        .bind(decided)
+        .bind(actor)
+        .bind(decided_at + Duration::minutes(seeded.len() as i64))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        seeded.push(run_id);
+    }
+
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/overrides",
+            get(backend::lifecycle_console::export_manual_overrides),
+        )
+        .layer(Extension(pool.clone()));
+    let exp = (Utc::now() + Duration::hours(1)).timestamp();
+    let token = encode(
+        &Header::default(),
+        &json!({"sub": fixture.owner_id, "role": "operator", "exp": exp}),
+        &EncodingKey::from_secret(b"integration-secret"),
+    )
+    .unwrap();
+
+    let fetch = |query: String| {
+        let app = app.clone();
+        let token = token.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/trust/remediation/overrides?{query}"))
+                        .header("Authorization", format!("Bearer {token}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        }
+    };
+
+    let from = window_start.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let to = (Utc::now() - Duration::days(2)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let page = fetch(format!("from={from}&to={to}")).await;
+    let overrides = page["overrides"].as_array().expect("overrides array");
+    assert_eq!(overrides.len(), 2);
+    assert!(page.get("next_cursor").is_none());
+
+    assert_eq!(overrides[0]["run_id"].as_i64(), Some(seeded[0]));
+    assert_eq!(overrides[0]["reason"], "break-glass rollout");
+    assert_eq!(overrides[0]["actor_email"], "console@example.com");
+    assert_eq!(
+        overrides[0]["workspace_id"].as_i64(),
+        Some(fixture.workspace.workspace.id)
+    );
+    assert_eq!(overrides[0]["workspace_key"], "console-workspace");
+    assert!(overrides[0]["overridden_at"].is_string());
+
+    assert_eq!(overrides[1]["run_id"].as_i64(), Some(seeded[1]));
+    assert_eq!(overrides[1]["reason"], "pager escalation");
+    assert_eq!(overrides[1]["actor_email"], "auditor@example.com");
+
+    // one record per page walks the same two overrides
+    let first = fetch(format!("from={from}&to={to}&limit=1")).await;
+    assert_eq!(first["overrides"][0]["run_id"].as_i64(), Some(seeded[0]));
+    let cursor = first["next_cursor"].as_i64().expect("next cursor");
+    let second = fetch(format!("from={from}&to={to}&limit=1&cursor={cursor}")).await;
+    assert_eq!(second["overrides"][0]["run_id"].as_i64(), Some(seeded[1]));
+}
+
+struct MeteredExecutor;
+
+#[async_trait::async_trait]
+impl RuntimeExecutor for MeteredExecutor {
+    fn backend(&self) -> RuntimeBackend {
+        RuntimeBackend::VirtualMachine
+    }
+
+    fn spawn_server_task(
+        &self,
+        _decision: PolicyDecision,
+        _server_id: i32,
+        _server_type: String,
+        _config: Option<serde_json::Value>,
+        _api_key: String,
+        _use_gpu: bool,
+        _pool: PgPool,
+    ) {
+    }
+
+    fn stop_server_task(&self, _server_id: i32, _pool: PgPool) {}
+
+    fn delete_server_task(&self, _server_id: i32, _pool: PgPool) {}
+
+    async fn fetch_logs(&self, _server_id: i32) -> Result<String, bollard::errors::Error> {
+        Ok(String::new())
+    }
+
+    fn stream_logs_task(
+        &self,
+        _server_id: i32,
+        _pool: PgPool,
+    ) -> Option<tokio::sync::mpsc::Receiver<String>> {
+        None
+    }
+
+    async fn resource_usage(&self, _server_id: i32) -> Option<ResourceUsage> {
+        ResourceUsage::measured(Some(12.5), Some(256 * 1024 * 1024))
+    }
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn lifecycle_console_surfaces_executor_resource_usage(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    let fixture = seed_lifecycle_fixture(&pool).await;
+
+    let run = get_run_by_id(&pool, fixture.run_id)
+        .await
+        .unwrap()
+        .expect("fixture run");
+    let usage = backend::remediation::record_resource_usage(&pool, &run, &MeteredExecutor)
+        .await
+        .unwrap();
+    assert_eq!(
+        usage,
+        ResourceUsage::measured(Some(12.5), Some(256 * 1024 * 1024))
+    );
+
+    let app = Router::new()
+        .route(
+            "/api/console/lifecycle",
+            get(backend::lifecycle_console::list_snapshots),
+        )
+        .layer(Extension(pool.clone()));
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/console/lifecycle")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let payload: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+    let snapshot = payload["workspaces"][0]["recent_runs"]
+        .as_array()
+        .expect("recent runs")
+        .iter()
+        .find(|entry| entry["run"]["id"].as_i64() == Some(fixture.run_id))
+        .expect("fixture run snapshot");
+    assert_eq!(
+        snapshot["resource_usage"],
+        json!({ "cpu_seconds": 12.5, "peak_memory_bytes": 268435456u64 })
+    );
+}
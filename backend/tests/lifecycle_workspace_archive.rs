@@ -0,0 +1,163 @@
+mod common;
+
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
+use backend::db::runtime_vm_remediation_workspaces::{create_workspace, CreateWorkspace};
+use backend::lifecycle_console::{archive, list_snapshots};
+use hyper::{Body, Method, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{seed_user, token, use_test_jwt_secret};
+
+// key: lifecycle-console -> workspace archival listing, detail, restore
+
+async fn seed_workspace(pool: &PgPool, owner_id: i32, key: &str, lifecycle_state: &str) -> i64 {
+    let plan = json!({"steps": []});
+    let details = create_workspace(
+        pool,
+        CreateWorkspace {
+            workspace_key: key,
+            display_name: key,
+            description: None,
+            owner_id,
+            organization_id: None,
+            plan: &plan,
+            metadata: None,
+            lineage_tags: &[],
+            lineage_labels: &[],
+        },
+    )
+    .await
+    .unwrap();
+    sqlx::query("UPDATE runtime_vm_remediation_workspaces SET lifecycle_state = $1 WHERE id = $2")
+        .bind(lifecycle_state)
+        .bind(details.workspace.id)
+        .execute(pool)
+        .await
+        .unwrap();
+    details.workspace.id
+}
+
+async fn send(app: &Router, method: Method, uri: &str, token: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn listed_ids(page: &Value) -> Vec<i64> {
+    page["workspaces"]
+        .as_array()
+        .expect("workspaces array")
+        .iter()
+        .map(|snapshot| snapshot["workspace"]["id"].as_i64().unwrap())
+        .collect()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn archived_workspaces_leave_the_listing_until_restored(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let owner_id = seed_user(&pool, "archive@example.com").await;
+    let live = seed_workspace(&pool, owner_id, "live-workspace", "draft").await;
+    let completed = seed_workspace(&pool, owner_id, "completed-workspace", "promoted").await;
+
+    let app = Router::new()
+        .route("/api/console/lifecycle", get(list_snapshots))
+        .route(
+            "/api/console/lifecycle/workspaces/:workspace_id/archive",
+            post(archive::archive_workspace),
+        )
+        .route(
+            "/api/console/lifecycle/archive/:workspace_id",
+            get(archive::get_archived_workspace).delete(archive::restore_workspace),
+        )
+        .layer(Extension(pool.clone()));
+    let token = token(owner_id, "operator");
+
+    // only terminal workspaces can be archived
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        &format!("/api/console/lifecycle/workspaces/{live}/archive"),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, archived) = send(
+        &app,
+        Method::POST,
+        &format!("/api/console/lifecycle/workspaces/{completed}/archive"),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(archived["previous_lifecycle_state"], "promoted");
+
+    let (_, page) = send(&app, Method::GET, "/api/console/lifecycle", &token).await;
+    assert_eq!(listed_ids(&page), vec![live]);
+    let (_, page) = send(
+        &app,
+        Method::GET,
+        "/api/console/lifecycle?include_archived=true",
+        &token,
+    )
+    .await;
+    assert_eq!(listed_ids(&page), vec![live, completed]);
+    assert_eq!(
+        page["workspaces"][1]["workspace"]["lifecycle_state"],
+        "archived"
+    );
+
+    let detail_uri = format!("/api/console/lifecycle/archive/{completed}");
+    let (status, detail) = send(&app, Method::GET, &detail_uri, &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(detail["workspace_id"].as_i64(), Some(completed));
+    assert_eq!(detail["archived_by"].as_i64(), Some(owner_id as i64));
+    assert_eq!(
+        detail["snapshot"]["workspace"]["workspace_key"],
+        "completed-workspace"
+    );
+    assert_eq!(
+        detail["snapshot"]["workspace"]["lifecycle_state"],
+        "promoted"
+    );
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        &format!("/api/console/lifecycle/archive/{live}"),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // restoring puts the workspace back with its previous state
+    let (status, restored) = send(&app, Method::DELETE, &detail_uri, &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(restored["lifecycle_state"], "promoted");
+    let (_, page) = send(&app, Method::GET, "/api/console/lifecycle", &token).await;
+    assert_eq!(listed_ids(&page), vec![live, completed]);
+    let (status, _) = send(&app, Method::GET, &detail_uri, &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
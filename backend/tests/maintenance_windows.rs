@@ -0,0 +1,247 @@
+mod common;
+
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
+use chrono::{Duration, Timelike, Utc};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{seed_org_vm_server, seed_user, token, use_test_jwt_secret};
+
+// key: remediation-maintenance-window-tests -> disruptive runs gated by org windows
+
+enum Caller<'a> {
+    Service(&'a str),
+    User(i32),
+}
+
+async fn seed_member(pool: &PgPool, organization_id: i32, user_id: i32, role: &str) {
+    sqlx::query(
+        "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3)",
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .bind(role)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn send(
+    app: &Router,
+    caller: Caller<'_>,
+    method: &str,
+    uri: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    let builder = match caller {
+        Caller::Service(api_key) => builder.header("X-API-Key", api_key),
+        Caller::User(user_id) => builder.header(
+            "Authorization",
+            format!("Bearer {}", token(user_id, "operator")),
+        ),
+    };
+    let body = if body.is_null() {
+        Body::empty()
+    } else {
+        Body::from(body.to_string())
+    };
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn enqueue_body(
+    vm_instance_id: i64,
+    scheduled_for: Option<Value>,
+    override_reason: Option<&str>,
+) -> Value {
+    let mut body = json!({
+        "runtime_vm_instance_id": vm_instance_id,
+        "playbook": "vm.reimage",
+    });
+    if let Some(at) = scheduled_for {
+        body["scheduled_for"] = at;
+    }
+    if let Some(reason) = override_reason {
+        body["emergency_override"] = json!({ "reason": reason });
+    }
+    body
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn disruptive_runs_respect_maintenance_windows(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let admin_id = seed_user(&pool, "sre-lead@example.com").await;
+    let member_id = seed_user(&pool, "sre@example.com").await;
+    let organization_id: i32 = sqlx::query_scalar(
+        "INSERT INTO organizations (name, owner_id) VALUES ('Acme', $1) RETURNING id",
+    )
+    .bind(admin_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    seed_member(&pool, organization_id, admin_id, "admin").await;
+    seed_member(&pool, organization_id, member_id, "member").await;
+    sqlx::query(
+        "INSERT INTO runtime_vm_remediation_playbooks (playbook_key, display_name, executor_type, owner_id, metadata) VALUES ('vm.reimage', 'Reimage VM', 'shell', $1, '{\"disruptive\": true}'::jsonb)",
+    )
+    .bind(admin_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let inside_vm = seed_org_vm_server(&pool, admin_id, Some(organization_id), "edge-inside").await;
+    let override_vm =
+        seed_org_vm_server(&pool, admin_id, Some(organization_id), "edge-override").await;
+
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/runs",
+            post(backend::remediation_api::enqueue_run_handler),
+        )
+        .route(
+            "/api/trust/remediation/organizations/:organization_id/maintenance-windows",
+            get(backend::remediation_api::list_maintenance_windows_handler)
+                .post(backend::remediation_api::create_maintenance_window_handler),
+        )
+        .route(
+            "/api/trust/remediation/organizations/:organization_id/maintenance-overrides",
+            get(backend::remediation_api::list_maintenance_overrides_handler),
+        )
+        .layer(Extension(pool.clone()));
+    let windows_uri =
+        format!("/api/trust/remediation/organizations/{organization_id}/maintenance-windows");
+
+    // a one-hour daily window covering the current hour, written in a +02:00 wall clock
+    let local_hour = (Utc::now().hour() + 2) % 24;
+    let window = json!({
+        "name": "nightly",
+        "recurrence": format!("0 {local_hour} * * *"),
+        "duration_minutes": 60,
+        "timezone": "+02:00",
+    });
+    let (status, _) = send(
+        &app,
+        Caller::User(member_id),
+        "POST",
+        &windows_uri,
+        window.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, created) = send(&app, Caller::User(admin_id), "POST", &windows_uri, window).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["timezone"], "+02:00");
+
+    let outside = Some(json!(Utc::now() + Duration::hours(3)));
+
+    // blocked outside the window
+    let (status, _) = send(
+        &app,
+        Caller::Service("edge-inside"),
+        "POST",
+        "/api/trust/remediation/runs",
+        enqueue_body(inside_vm, outside.clone(), None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // allowed inside it
+    let (status, body) = send(
+        &app,
+        Caller::Service("edge-inside"),
+        "POST",
+        "/api/trust/remediation/runs",
+        enqueue_body(inside_vm, None, None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["status"], "pending");
+
+    // an emergency override needs an org admin
+    let (status, _) = send(
+        &app,
+        Caller::Service("edge-override"),
+        "POST",
+        "/api/trust/remediation/runs",
+        enqueue_body(override_vm, outside.clone(), Some("kernel CVE")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(
+        &app,
+        Caller::User(member_id),
+        "POST",
+        "/api/trust/remediation/runs",
+        enqueue_body(override_vm, outside.clone(), Some("kernel CVE")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(
+        &app,
+        Caller::User(admin_id),
+        "POST",
+        "/api/trust/remediation/runs",
+        enqueue_body(override_vm, outside.clone(), Some("  ")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        Caller::User(admin_id),
+        "POST",
+        "/api/trust/remediation/runs",
+        enqueue_body(
+            override_vm,
+            outside,
+            Some("kernel CVE under active exploitation"),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let run_id = body["run"]["id"].as_i64().unwrap();
+    // the run carries its server's org so tenant metrics need no lookup
+    let run_org: Option<i32> =
+        sqlx::query_scalar("SELECT organization_id FROM runtime_vm_remediation_runs WHERE id = $1")
+            .bind(run_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(run_org, Some(organization_id));
+
+    let (status, overrides) = send(
+        &app,
+        Caller::User(member_id),
+        "GET",
+        &format!("/api/trust/remediation/organizations/{organization_id}/maintenance-overrides"),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let overrides = overrides.as_array().unwrap();
+    assert_eq!(overrides.len(), 1);
+    assert_eq!(overrides[0]["remediation_run_id"], run_id);
+    assert_eq!(overrides[0]["actor_id"], admin_id);
+    assert_eq!(overrides[0]["actor_role"], "admin");
+    assert_eq!(
+        overrides[0]["reason"],
+        "kernel CVE under active exploitation"
+    );
+}
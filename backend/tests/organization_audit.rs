@@ -0,0 +1,219 @@
+mod common;
+
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use chrono::{Duration, SecondsFormat, Utc};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use common::{seed_user, token, use_test_jwt_secret};
+
+// key: organization-audit-log -> key rotations and subscriptions land in the org trail
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    user_id: i32,
+    body: Value,
+) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", token(user_id, "operator")),
+                )
+                .body(if body.is_null() {
+                    Body::empty()
+                } else {
+                    Body::from(body.to_string())
+                })
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Audit writes land in the background, so poll until `expected` entries show up.
+async fn audit_entries(app: &Router, uri: &str, user_id: i32, expected: usize) -> Vec<Value> {
+    for _ in 0..50 {
+        let (status, body) = send(app, "GET", uri, user_id, Value::Null).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let entries = body["entries"].as_array().cloned().unwrap_or_default();
+        if entries.len() >= expected {
+            return entries;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("audit log never reached {expected} entries for {uri}");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn key_rotation_and_subscription_upsert_are_audited(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let admin_id = seed_user(&pool, "finops@example.com").await;
+    let viewer_id = seed_user(&pool, "auditor@example.com").await;
+    let organization_id: i32 = sqlx::query_scalar(
+        "INSERT INTO organizations (name, owner_id) VALUES ('Audited Org', $1) RETURNING id",
+    )
+    .bind(admin_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    for (user_id, role) in [(admin_id, "admin"), (viewer_id, "viewer")] {
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3)",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let plan_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO billing_plans (id, code, name, description, amount_cents) VALUES ($1, 'team', 'Team', 'Team plan', 4900)",
+    )
+    .bind(plan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let app = Router::new()
+        .merge(backend::keys_api::routes())
+        .route(
+            "/api/billing/organizations/:organization_id/subscription",
+            post(backend::billing::billing_upsert_subscription),
+        )
+        .route(
+            "/api/organizations/:id/audit",
+            get(backend::audit::organization::list_organization_audit),
+        )
+        .layer(Extension(pool.clone()));
+
+    let provider_id = Uuid::new_v4();
+    let (status, key) = send(
+        &app,
+        "POST",
+        &format!("/api/providers/{provider_id}/keys"),
+        admin_id,
+        json!({
+            "alias": "primary",
+            "attestation_digest": STANDARD.encode(b"digest"),
+            "attestation_signature": STANDARD.encode(b"signature"),
+            "organization_id": organization_id,
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{key}");
+    let key_id = key["id"].as_str().unwrap().to_string();
+    let (status, rotation) = send(
+        &app,
+        "POST",
+        &format!("/api/providers/{provider_id}/keys/{key_id}/rotations"),
+        admin_id,
+        json!({
+            "attestation_digest": STANDARD.encode(b"next-digest"),
+            "attestation_signature": STANDARD.encode(b"next-signature"),
+            "request_actor_ref": "oncall:alice",
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{rotation}");
+
+    let (status, subscription) = send(
+        &app,
+        "POST",
+        &format!("/api/billing/organizations/{organization_id}/subscription"),
+        admin_id,
+        json!({ "plan_id": plan_id }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{subscription}");
+
+    let audit_uri = format!("/api/organizations/{organization_id}/audit");
+    let entries = audit_entries(&app, &audit_uri, admin_id, 3).await;
+    let find = |action: &str| {
+        entries
+            .iter()
+            .find(|entry| entry["action"] == action)
+            .unwrap_or_else(|| panic!("no `{action}` entry in {entries:?}"))
+            .clone()
+    };
+    let rotated = find("rotation_requested");
+    assert_eq!(rotated["category"], "keys");
+    assert_eq!(rotated["actor"], "oncall:alice");
+    assert_eq!(rotated["target"], format!("provider_key:{key_id}"));
+    assert!(rotated["occurred_at"].is_string());
+    let upserted = find("subscription_upserted");
+    assert_eq!(upserted["category"], "billing");
+    assert_eq!(upserted["actor"], format!("user:{admin_id}"));
+    assert_eq!(upserted["details"]["plan_id"], json!(plan_id));
+
+    // filtering by category and paging
+    let (_, billing_only) = send(
+        &app,
+        "GET",
+        &format!("{audit_uri}?category=billing"),
+        admin_id,
+        Value::Null,
+    )
+    .await;
+    let billing_entries = billing_only["entries"].as_array().unwrap();
+    assert_eq!(billing_entries.len(), 1);
+    assert_eq!(billing_entries[0]["action"], "subscription_upserted");
+
+    let (_, first_page) = send(
+        &app,
+        "GET",
+        &format!("{audit_uri}?limit=2"),
+        admin_id,
+        Value::Null,
+    )
+    .await;
+    assert_eq!(first_page["entries"].as_array().unwrap().len(), 2);
+    let cursor = first_page["next_cursor"].as_i64().unwrap();
+    let (_, second_page) = send(
+        &app,
+        "GET",
+        &format!("{audit_uri}?limit=2&cursor={cursor}"),
+        admin_id,
+        Value::Null,
+    )
+    .await;
+    assert_eq!(second_page["entries"].as_array().unwrap().len(), 1);
+    assert!(second_page["next_cursor"].is_null());
+
+    let future = (Utc::now() + Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let (_, empty) = send(
+        &app,
+        "GET",
+        &format!("{audit_uri}?from={future}"),
+        admin_id,
+        Value::Null,
+    )
+    .await;
+    assert!(empty["entries"].as_array().unwrap().is_empty());
+
+    let (status, _) = send(&app, "GET", &audit_uri, viewer_id, Value::Null).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
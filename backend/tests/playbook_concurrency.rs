@@ -0,0 +1,103 @@
+mod common;
+
+use axum::{routing::post, Extension, Router};
+use backend::db::runtime_vm_remediation_runs::{mark_run_completed, try_acquire_next_run};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::seed_vm_server;
+
+// key: playbook-concurrency-tests -> runs past max_concurrent_runs wait as blocked
+
+async fn enqueue(app: &Router, api_key: &str, vm_instance_id: i64) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/trust/remediation/runs")
+        .header("content-type", "application/json")
+        .header("X-API-Key", api_key)
+        .body(Body::from(
+            json!({
+                "runtime_vm_instance_id": vm_instance_id,
+                "playbook": "vm.restart.service",
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn run_status(pool: &PgPool, run_id: i64) -> String {
+    sqlx::query_scalar("SELECT status FROM runtime_vm_remediation_runs WHERE id = $1")
+        .bind(run_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn runs_past_the_limit_are_blocked_then_released(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("concurrency@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    sqlx::query(
+        "INSERT INTO runtime_vm_remediation_playbooks (playbook_key, display_name, executor_type, owner_id, approval_required, max_concurrent_runs) VALUES ('vm.restart.service', 'Restart service', 'shell', $1, FALSE, 1)",
+    )
+    .bind(owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let first_vm = seed_vm_server(&pool, owner_id, "edge-first").await;
+    let second_vm = seed_vm_server(&pool, owner_id, "edge-second").await;
+    let third_vm = seed_vm_server(&pool, owner_id, "edge-third").await;
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/runs",
+            post(backend::remediation_api::enqueue_run_handler),
+        )
+        .layer(Extension(pool.clone()));
+
+    let (status, body) = enqueue(&app, "edge-first", first_vm).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["status"], "pending");
+    let first_id = body["run"]["id"].as_i64().unwrap();
+
+    // over the limit: queued, not refused
+    let (status, body) = enqueue(&app, "edge-second", second_vm).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["status"], "blocked");
+    let second_id = body["run"]["id"].as_i64().unwrap();
+    let (status, body) = enqueue(&app, "edge-third", third_vm).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["status"], "blocked");
+    let third_id = body["run"]["id"].as_i64().unwrap();
+
+    // a blocked run is still the instance's active run
+    let (status, _) = enqueue(&app, "edge-second", second_vm).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let acquired = try_acquire_next_run(&pool).await.unwrap().unwrap();
+    assert_eq!(acquired.id, first_id);
+    assert!(try_acquire_next_run(&pool).await.unwrap().is_none());
+
+    mark_run_completed(&pool, first_id, None, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run_status(&pool, second_id).await, "pending");
+    assert_eq!(run_status(&pool, third_id).await, "blocked");
+
+    let acquired = try_acquire_next_run(&pool).await.unwrap().unwrap();
+    assert_eq!(acquired.id, second_id);
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use axum::{routing::post, Extension, Router};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::seed_vm_server;
+
+// key: playbook-payload-schema-tests -> automation_payload checked at enqueue
+
+async fn seed_playbook(pool: &PgPool, owner_id: i32, key: &str, schema: Option<Value>) {
+    sqlx::query(
+        "INSERT INTO runtime_vm_remediation_playbooks (playbook_key, display_name, executor_type, owner_id, automation_payload_schema) VALUES ($1, $1, 'shell', $2, $3)",
+    )
+    .bind(key)
+    .bind(owner_id)
+    .bind(schema)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn enqueue(
+    app: &Router,
+    api_key: &str,
+    vm_instance_id: i64,
+    playbook: &str,
+    payload: Value,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/trust/remediation/runs")
+        .header("content-type", "application/json")
+        .header("X-API-Key", api_key)
+        .body(Body::from(
+            json!({
+                "runtime_vm_instance_id": vm_instance_id,
+                "playbook": playbook,
+                "automation_payload": payload,
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn run_count(pool: &PgPool, vm_instance_id: i64) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM runtime_vm_remediation_runs WHERE runtime_vm_instance_id = $1",
+    )
+    .bind(vm_instance_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn enqueue_checks_payload_against_playbook_schema(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("schema@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    seed_playbook(
+        &pool,
+        owner_id,
+        "vm.restart.service",
+        Some(json!({
+            "type": "object",
+            "required": ["service"],
+            "properties": {
+                "service": { "type": "string", "minLength": 1 },
+                "timeout_seconds": { "type": "integer", "minimum": 1 }
+            },
+            "additionalProperties": false
+        })),
+    )
+    .await;
+    seed_playbook(&pool, owner_id, "vm.collect.diagnostics", None).await;
+    let checked_vm = seed_vm_server(&pool, owner_id, "edge-checked").await;
+    let freeform_vm = seed_vm_server(&pool, owner_id, "edge-freeform").await;
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/runs",
+            post(backend::remediation_api::enqueue_run_handler),
+        )
+        .layer(Extension(pool.clone()));
+
+    // rejected before a run exists
+    let (status, body) = enqueue(
+        &app,
+        "edge-checked",
+        checked_vm,
+        "vm.restart.service",
+        json!({ "timeout_seconds": 0, "force": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let paths: Vec<&str> = body["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|violation| violation["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, vec!["", "/force", "/timeout_seconds"]);
+    assert_eq!(run_count(&pool, checked_vm).await, 0);
+
+    let (status, body) = enqueue(
+        &app,
+        "edge-checked",
+        checked_vm,
+        "vm.restart.service",
+        json!({ "service": "nginx", "timeout_seconds": 30 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["automation_payload"]["service"], "nginx");
+
+    let (status, _) = enqueue(
+        &app,
+        "edge-freeform",
+        freeform_vm,
+        "vm.collect.diagnostics",
+        json!(["anything", { "goes": true }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(run_count(&pool, freeform_vm).await, 1);
+}
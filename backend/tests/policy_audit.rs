@@ -0,0 +1,98 @@
+mod common;
+
+use axum::{routing::get, Extension, Router};
+use backend::db::runtime_policy_audit::RuntimePolicyAuditRecord;
+use backend::policy::{RuntimeBackend, RuntimeExecutorDescriptor, RuntimePolicyEngine};
+use hyper::{Body, Request, StatusCode};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{seed_server, seed_user, token, use_test_jwt_secret};
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn allowed_and_denied_placements_are_audited(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let user_id = seed_user(&pool, "auditor@example.com").await;
+    let server_id = seed_server(&pool, user_id, "audited-router").await;
+
+    let engine = RuntimePolicyEngine::new(RuntimeBackend::Docker);
+    engine
+        .register_executor(RuntimeExecutorDescriptor::new(
+            RuntimeBackend::Docker,
+            "Docker containers",
+            [],
+        ))
+        .await;
+
+    let allowed = engine
+        .decide_and_record(&pool, server_id, "Router", None, false)
+        .await
+        .unwrap();
+    assert!(allowed.veto_reasons().is_empty());
+
+    // GPU routes to Kubernetes, and the only registered executor cannot satisfy it
+    let denied = engine
+        .decide_and_record(&pool, server_id, "Router", None, true)
+        .await
+        .unwrap();
+    assert!(!denied.capabilities_satisfied);
+
+    use_test_jwt_secret();
+    let token = token(user_id, "user");
+
+    let app = Router::new()
+        .route(
+            "/api/runtime/policy/decisions",
+            get(backend::policy::list_policy_decisions),
+        )
+        .layer(Extension(pool.clone()));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/runtime/policy/decisions?server_id={server_id}"
+                ))
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let entries: Vec<RuntimePolicyAuditRecord> = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    // newest first
+    let denied_entry = &entries[0];
+    assert!(!denied_entry.allowed);
+    assert_eq!(denied_entry.candidate_backend, "kubernetes");
+    assert_eq!(denied_entry.backend, "kubernetes");
+    assert_eq!(
+        denied_entry.executors_considered,
+        vec!["docker".to_string()]
+    );
+    assert_eq!(denied_entry.governance_verdict, "not-required");
+    assert_eq!(
+        denied_entry.veto_reasons,
+        vec!["capabilities:unsatisfied:gpu".to_string()]
+    );
+    assert!(denied_entry.decision_id.is_some());
+
+    let allowed_entry = &entries[1];
+    assert!(allowed_entry.allowed);
+    assert_eq!(allowed_entry.backend, "docker");
+    assert_eq!(
+        allowed_entry.executor_name.as_deref(),
+        Some("Docker containers")
+    );
+    assert_eq!(
+        allowed_entry.executors_considered,
+        vec!["docker".to_string()]
+    );
+    assert!(allowed_entry.veto_reasons.is_empty());
+    assert_eq!(allowed_entry.policy_version, allowed.policy_version);
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use axum::{Extension, Router};
+use backend::governance::GovernanceEngine;
+use backend::promotions::auto_advance::{advance_due, AutoAdvanceStatus};
+use chrono::{Duration, Utc};
+use hyper::{Body, Request, StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{seed_user, token, use_test_jwt_secret};
+
+// key: release-train -> promotion-auto-advance tests
+
+async fn seed_track(pool: &PgPool, owner_id: i32, name: &str, require_attestation: bool) -> i32 {
+    sqlx::query_scalar(
+        "INSERT INTO promotion_tracks (owner_id, name, tier, stages, require_attestation) VALUES ($1, $2, 'staging', ARRAY['candidate','staging','production']::TEXT[], $3) RETURNING id",
+    )
+    .bind(owner_id)
+    .bind(name)
+    .bind(require_attestation)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn seed_active(pool: &PgPool, track_id: i32, digest: &str, active_for: Duration) -> i64 {
+    sqlx::query_scalar(
+        "INSERT INTO artifact_promotions (promotion_track_id, manifest_digest, stage, status, activated_at) VALUES ($1, $2, 'candidate', 'active', $3) RETURNING id",
+    )
+    .bind(track_id)
+    .bind(digest)
+    .bind(Utc::now() - active_for)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn put_rule(app: &Router, owner_id: i32, track_id: i32, enabled: bool) {
+    let token = token(owner_id, "operator");
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/promotions/tracks/{track_id}/auto-advance"))
+                .header("content-type", "application/json")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::from(
+                    json!({ "enabled": enabled, "min_dwell_seconds": 3600 }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+async fn stage_count(pool: &PgPool, digest: &str, stage: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM artifact_promotions WHERE manifest_digest = $1 AND stage = $2",
+    )
+    .bind(digest)
+    .bind(stage)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn healthy_manifests_advance_after_dwell_and_vetoed_ones_stay(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let owner_id = seed_user(&pool, "trains@example.com").await;
+    let app = Router::new()
+        .merge(backend::promotions::routes())
+        .layer(Extension(pool.clone()));
+
+    let healthy_track = seed_track(&pool, owner_id, "healthy", false).await;
+    // no attestation is stored, so a track that requires one vetoes every digest
+    let signed_track = seed_track(&pool, owner_id, "signed", true).await;
+    let paused_track = seed_track(&pool, owner_id, "paused", false).await;
+    put_rule(&app, owner_id, healthy_track, true).await;
+    put_rule(&app, owner_id, signed_track, true).await;
+    put_rule(&app, owner_id, paused_track, false).await;
+
+    let healthy = seed_active(&pool, healthy_track, "sha256:healthy", Duration::hours(2)).await;
+    seed_active(&pool, healthy_track, "sha256:fresh", Duration::minutes(10)).await;
+    let vetoed = seed_active(&pool, signed_track, "sha256:unsigned", Duration::hours(2)).await;
+    seed_active(&pool, paused_track, "sha256:paused", Duration::hours(2)).await;
+
+    let engine = GovernanceEngine::new();
+    let outcomes = advance_due(&pool, &engine, Utc::now()).await.unwrap();
+    assert_eq!(outcomes.len(), 2);
+
+    let advanced = outcomes
+        .iter()
+        .find(|outcome| outcome.source_promotion_id == healthy)
+        .unwrap();
+    assert_eq!(advanced.to_stage, "staging");
+    let AutoAdvanceStatus::Advanced { promotion_id } = advanced.status else {
+        panic!("expected an advance, got {:?}", advanced.status);
+    };
+    let (status, scheduled_by, notes): (String, Option<i32>, Vec<String>) = sqlx::query_as(
+        "SELECT status::TEXT, scheduled_by, notes FROM artifact_promotions WHERE id = $1",
+    )
+    .bind(promotion_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(status, "scheduled");
+    assert_eq!(scheduled_by, None);
+    assert!(notes
+        .iter()
+        .any(|note| note.starts_with("promotion:auto-advanced:candidate:staging")));
+
+    let held = outcomes
+        .iter()
+        .find(|outcome| outcome.source_promotion_id == vetoed)
+        .unwrap();
+    assert_eq!(
+        held.status,
+        AutoAdvanceStatus::Vetoed {
+            reasons: vec!["artifact.attestation=missing".to_string()]
+        }
+    );
+    assert_eq!(stage_count(&pool, "sha256:unsigned", "staging").await, 0);
+    assert_eq!(stage_count(&pool, "sha256:fresh", "staging").await, 0);
+    assert_eq!(stage_count(&pool, "sha256:paused", "staging").await, 0);
+
+    // an hour later the fresh digest has dwelt long enough; nothing advances twice and the
+    // veto is noted once
+    let later = advance_due(&pool, &engine, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(later.len(), 2);
+    assert_eq!(stage_count(&pool, "sha256:fresh", "staging").await, 1);
+    assert_eq!(stage_count(&pool, "sha256:healthy", "staging").await, 1);
+    let veto_notes: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM artifact_promotions, unnest(notes) note WHERE id = $1 AND note LIKE 'promotion:auto-advance:vetoed:%'",
+    )
+    .bind(vetoed)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(veto_notes, 1);
+}
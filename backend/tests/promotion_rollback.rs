@@ -0,0 +1,166 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::{Extension, Router};
+use backend::governance::GovernanceEngine;
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{token, use_test_jwt_secret};
+
+// key: release-train -> promotion-rollback tests
+
+async fn rollback(
+    app: &Router,
+    user_id: i32,
+    role: &str,
+    promotion_id: i64,
+    body: Value,
+) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/promotions/{promotion_id}/rollback"))
+                .header("content-type", "application/json")
+                .header("Authorization", format!("Bearer {}", token(user_id, role)))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn seed_promotion(pool: &PgPool, track_id: i32, stage: &str, status: &str) -> i64 {
+    sqlx::query_scalar(
+        "INSERT INTO artifact_promotions (promotion_track_id, manifest_digest, stage, status, activated_at) VALUES ($1, 'sha256:release', $2, $3::promotion_status, NOW()) RETURNING id",
+    )
+    .bind(track_id)
+    .bind(stage)
+    .bind(status)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn status_of(pool: &PgPool, promotion_id: i64) -> String {
+    sqlx::query_scalar("SELECT status::TEXT FROM artifact_promotions WHERE id = $1")
+        .bind(promotion_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn production_rollback_reinstates_staging_and_records_reason(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let admin_id: i32 = sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash, role) VALUES ('oncall@example.com', 'hashed', 'admin') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let track_id: i32 = sqlx::query_scalar(
+        "INSERT INTO promotion_tracks (owner_id, name, tier) VALUES ($1, 'edge', 'production') RETURNING id",
+    )
+    .bind(admin_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    // staging was superseded when production went live
+    let staging = seed_promotion(&pool, track_id, "staging", "rolled_back").await;
+    let production = seed_promotion(&pool, track_id, "production", "active").await;
+
+    let app = Router::new()
+        .merge(backend::promotions::routes())
+        .layer(Extension(Arc::new(GovernanceEngine::new())))
+        .layer(Extension(pool.clone()));
+
+    let (status, _) = rollback(
+        &app,
+        admin_id,
+        "operator",
+        production,
+        json!({ "reason": "p99 latency regression", "to_stage": "staging" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = rollback(
+        &app,
+        admin_id,
+        "admin",
+        production,
+        json!({ "reason": "  ", "to_stage": "staging" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = rollback(
+        &app,
+        admin_id,
+        "admin",
+        staging,
+        json!({ "reason": "wrong way", "to_stage": "production" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let mut posture_changes = backend::lifecycle_console::subscribe_posture_changes();
+    let (status, body) = rollback(
+        &app,
+        admin_id,
+        "admin",
+        production,
+        json!({ "reason": "p99 latency regression", "to_stage": "Staging" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["promotion"]["status"], "rolled_back");
+    assert_eq!(body["restored"]["id"], staging);
+    assert_eq!(body["restored"]["status"], "active");
+    assert_eq!(body["rollback"]["reason"], "p99 latency regression");
+    assert_eq!(body["rollback"]["rolled_back_by"], admin_id);
+    assert_eq!(body["rollback"]["to_stage"], "staging");
+    assert!(posture_changes.has_changed().unwrap());
+
+    assert_eq!(status_of(&pool, production).await, "rolled_back");
+    assert_eq!(status_of(&pool, staging).await, "active");
+    let notes: Vec<String> =
+        sqlx::query_scalar("SELECT notes FROM artifact_promotions WHERE id = $1")
+            .bind(production)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(notes.contains(&format!(
+        "promotion:rolled-back:user:{admin_id}:p99 latency regression"
+    )));
+    let (reason, actor): (String, Option<i32>) = sqlx::query_as(
+        "SELECT reason, rolled_back_by FROM promotion_rollbacks WHERE promotion_id = $1",
+    )
+    .bind(production)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(reason, "p99 latency regression");
+    assert_eq!(actor, Some(admin_id));
+
+    // a promotion can only be rolled back once
+    let (status, _) = rollback(
+        &app,
+        admin_id,
+        "admin",
+        production,
+        json!({ "reason": "again" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use axum::{Extension, Router};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{token, use_test_jwt_secret};
+
+// key: release-train -> promotion-track-management tests
+
+async fn send(
+    app: &Router,
+    user_id: i32,
+    method: &str,
+    uri: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let body = if body.is_null() {
+        Body::empty()
+    } else {
+        Body::from(body.to_string())
+    };
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", token(user_id, "operator")),
+                )
+                .body(body)
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn promotion_tracks_enforce_tiers_and_guard_deletion(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let owner_id: i32 = sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash) VALUES ('release@example.com', 'hashed') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let app = Router::new()
+        .merge(backend::promotions::routes())
+        .layer(Extension(pool.clone()));
+
+    // create with the default stages
+    let (status, track) = send(
+        &app,
+        owner_id,
+        "POST",
+        "/api/promotions/tracks",
+        json!({ "name": "edge", "tier": "Staging", "description": "edge fleet" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(track["tier"], "staging");
+    assert_eq!(
+        track["stages"],
+        json!(["candidate", "staging", "production"])
+    );
+    let track_id = track["id"].as_i64().unwrap();
+    let track_uri = format!("/api/promotions/tracks/{track_id}");
+
+    let (status, fetched) = send(&app, owner_id, "GET", &track_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["name"], "edge");
+
+    let (status, _) = send(
+        &app,
+        owner_id,
+        "POST",
+        "/api/promotions/tracks",
+        json!({ "name": "edge", "tier": "pilot" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // unknown tiers and stages that step down a tier are rejected
+    let (status, _) = send(
+        &app,
+        owner_id,
+        "POST",
+        "/api/promotions/tracks",
+        json!({ "name": "gold", "tier": "gold" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(
+        &app,
+        owner_id,
+        "POST",
+        "/api/promotions/tracks",
+        json!({ "name": "backwards", "tier": "production", "stages": ["production", "pilot"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(
+        &app,
+        owner_id,
+        "PATCH",
+        &track_uri,
+        json!({ "stages": ["candidate", "production", "staging"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, patched) = send(
+        &app,
+        owner_id,
+        "PATCH",
+        &track_uri,
+        json!({ "tier": "production", "stages": ["pilot", "staging", "production"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(patched["tier"], "production");
+    assert_eq!(patched["description"], "edge fleet");
+
+    // a live promotion keeps the track; rolling it back releases it
+    let promotion_id: i64 = sqlx::query_scalar(
+        "INSERT INTO artifact_promotions (promotion_track_id, manifest_digest, stage, status) VALUES ($1, 'sha256:edge', 'pilot', 'active') RETURNING id",
+    )
+    .bind(track_id as i32)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let (status, _) = send(&app, owner_id, "DELETE", &track_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    sqlx::query("UPDATE artifact_promotions SET status = 'rolled_back' WHERE id = $1")
+        .bind(promotion_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, body) = send(&app, owner_id, "DELETE", &track_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["deleted"], true);
+    let (status, _) = send(&app, owner_id, "GET", &track_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -20,6 +20,7 @@ async fn revoke_key_marks_compromised_and_emits_events(pool: PgPool) {
                 attestation_digest: Some(STANDARD.encode(b"digest")),
                 attestation_signature: Some(STANDARD.encode(b"signature")),
                 rotation_due_at: Some(Utc::now() + Duration::hours(24)),
+                organization_id: None,
             },
         )
         .await
@@ -62,6 +63,7 @@ async fn enforce_rotation_slas_emits_breach_once(pool: PgPool) {
                 attestation_digest: Some(STANDARD.encode(b"digest")),
                 attestation_signature: Some(STANDARD.encode(b"signature")),
                 rotation_due_at: Some(Utc::now() - Duration::hours(1)),
+                organization_id: None,
             },
         )
         .await
@@ -6,10 +6,11 @@ use axum::{
     routing::{get, post},
     Extension, Router,
 };
-use backend::db::runtime_vm_remediation_artifacts::insert_artifact;
 use backend::db::runtime_vm_remediation_runs::{mark_run_completed, mark_run_failed};
+use backend::db::runtime_vm_remediation_workspaces::prune_validation_snapshots;
 use backend::db::runtime_vm_trust_registry::{upsert_state, UpsertRuntimeVmTrustRegistryState};
 use backend::policy::trust::evaluate_placement_gate;
+use backend::remediation::artifact_content::record_artifact;
 use chrono::{Duration as ChronoDuration, Utc};
 use futures_util::future::join_all;
 use hyper::body;
@@ -113,6 +114,10 @@ async fn bootstrap_remediation_harness(pool: &PgPool) -> RemediationHarness {
             "/api/trust/remediation/workspaces/:workspace_id/revisions/:revision_id/promotion",
             post(backend::remediation_api::apply_workspace_promotion_handler),
         )
+        .route(
+            "/api/trust/remediation/workspaces/:workspace_id/revisions/:revision_id/promotion/preview",
+            post(backend::remediation_api::preview_workspace_promotion_handler),
+        )
         .route(
             "/api/trust/remediation/playbooks",
             get(backend::remediation_api::list_all_playbooks)
@@ -147,7 +152,7 @@ async fn bootstrap_remediation_harness(pool: &PgPool) -> RemediationHarness {
         )
         .layer(Extension(pool.clone()));
 
-    backend::remediation::spawn(pool.clone());
+    backend::remediation::spawn(pool.clone(), None);
 
     RemediationHarness {
         app,
@@ -1172,6 +1177,7 @@ async fn remediation_workspace_lifecycle_end_to_end(pool: PgPool) {
             "notes": ["ready"],
             "expected_workspace_version": workspace_version,
             "expected_revision_version": stale_promotion_revision_version,
+            "gate_override": {"reason": "policy veto accepted for harness"},
         }),
     )
     .await;
@@ -1183,6 +1189,7 @@ async fn remediation_workspace_lifecycle_end_to_end(pool: PgPool) {
         "gate_context": {"lane": "alpha", "stage": "production"},
         "expected_workspace_version": workspace_version,
         "expected_revision_version": revision_version,
+        "gate_override": {"reason": "policy veto accepted for harness"},
     });
 
     let after_promotion = apply_workspace_promotion(
@@ -1225,6 +1232,11 @@ async fn remediation_workspace_lifecycle_end_to_end(pool: PgPool) {
                 .map(|entry| entry.starts_with("requested_by="))
                 .unwrap_or(false)
         }));
+    assert!(promotion_snapshot["notes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|note| note.as_str() == Some("gate_override_bypassed=policy=vetoed")));
     tokio::time::sleep(StdDuration::from_millis(50)).await;
     let automation_runs = list_workspace_runs(&app, &token, workspace_id, latest_revision_id).await;
     assert!(automation_runs.iter().any(|run| {
@@ -1495,6 +1507,608 @@ async fn remediation_workspace_promotion_multiple_targets(pool: PgPool) {
     );
 }
 
+// key: validation -> remediation-workspace-promotion-preview
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn remediation_workspace_promotion_preview_matches_staged_runs(pool: PgPool) {
+    let harness = bootstrap_remediation_harness(&pool).await;
+    let app = harness.app.clone();
+    let token = harness.token.clone();
+
+    let secondary_vm: i64 = sqlx::query_scalar(
+        "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(harness.server_id)
+    .bind("vm-remediation-preview")
+    .fetch_one(&harness.pool)
+    .await
+    .unwrap();
+
+    for (key, name) in [("vm.restart", "VM Restart"), ("vm.redeploy", "VM Redeploy")] {
+        create_playbook(
+            &app,
+            &token,
+            json!({
+                "playbook_key": key,
+                "display_name": name,
+                "executor_type": "shell",
+                "approval_required": false,
+                "metadata": {"origin": "promotion-preview"},
+            }),
+        )
+        .await;
+    }
+
+    let workspace = create_workspace(
+        &app,
+        &token,
+        json!({
+            "workspace_key": "workspace.preview",
+            "display_name": "Workspace Preview",
+            "plan": {
+                "playbooks": ["vm.restart"],
+                "targets": {
+                    "lanes": [
+                        {
+                            "lane": "cli",
+                            "stage": "promotion",
+                            "targets": [
+                                {
+                                    "instance_id": secondary_vm.to_string(),
+                                    "automation_payload": {"kind": "lane"}
+                                }
+                            ]
+                        }
+                    ],
+                    "direct": [
+                        {
+                            "runtime_vm_instance_id": harness.vm_instance_id,
+                            "playbook": "vm.redeploy",
+                            "automation_payload": {"kind": "direct"}
+                        }
+                    ]
+                }
+            },
+            "metadata": {"origin": "integration"},
+        }),
+    )
+    .await;
+    let workspace_id = workspace["workspace"]["id"].as_i64().unwrap();
+    let revision_id = workspace["workspace"]["active_revision_id"]
+        .as_i64()
+        .unwrap();
+    let workspace_version = workspace["workspace"]["version"].as_i64().unwrap();
+    let mut revision_version = select_revision(&workspace, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+
+    let gate_context = json!({"lane": "cli", "stage": "promotion"});
+    let preview_response = post_workspace_request(
+        &app,
+        &token,
+        format!(
+            "/api/trust/remediation/workspaces/{workspace_id}/revisions/{revision_id}/promotion/preview"
+        ),
+        json!({"gate_context": gate_context}),
+    )
+    .await;
+    assert_eq!(preview_response.status(), StatusCode::OK);
+    let preview_bytes = body::to_bytes(preview_response.into_body()).await.unwrap();
+    let preview: Value = serde_json::from_slice(&preview_bytes).unwrap();
+
+    let runs_before = list_workspace_runs(&app, &token, workspace_id, revision_id).await;
+    assert!(runs_before.is_empty(), "preview must not persist runs");
+
+    let after_schema = apply_workspace_schema(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "result_status": "passed",
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    revision_version = select_revision(&after_schema, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+    let after_policy = apply_workspace_policy(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "policy_status": "approved",
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    revision_version = select_revision(&after_policy, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+    let after_simulation = apply_workspace_simulation(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "simulator_kind": "harness",
+            "execution_state": "succeeded",
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    revision_version = select_revision(&after_simulation, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+
+    let promotion = apply_workspace_promotion(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "promotion_status": "completed",
+            "gate_context": gate_context,
+            "expected_workspace_version": workspace_version,
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+
+    let project = |entry: &Value| {
+        (
+            entry["runtime_vm_instance_id"].as_i64().unwrap(),
+            entry["playbook"].as_str().unwrap().to_string(),
+            entry["automation_payload"].to_string(),
+            entry["promotion_gate_context"].to_string(),
+        )
+    };
+    let previewed: BTreeSet<_> = preview["runs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(project)
+        .collect();
+    let staged: BTreeSet<_> = promotion["promotion_runs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(project)
+        .collect();
+
+    assert_eq!(previewed.len(), 2);
+    assert_eq!(previewed, staged);
+}
+
+// key: validation -> remediation-workspace-idempotent-create
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn remediation_workspace_create_is_idempotent_per_organization(pool: PgPool) {
+    let harness = bootstrap_remediation_harness(&pool).await;
+    let app = harness.app.clone();
+    let token = harness.token.clone();
+
+    let mut organization_ids = Vec::new();
+    for name in ["org-alpha", "org-beta"] {
+        let organization_id: i32 = sqlx::query_scalar(
+            "INSERT INTO organizations (name, owner_id) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(name)
+        .bind(harness.operator_id)
+        .fetch_one(&harness.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, 'owner')",
+        )
+        .bind(organization_id)
+        .bind(harness.operator_id)
+        .execute(&harness.pool)
+        .await
+        .unwrap();
+        organization_ids.push(organization_id);
+    }
+
+    let payload = json!({
+        "workspace_key": "workspace.idempotent",
+        "organization_id": organization_ids[0],
+        "display_name": "Workspace Idempotent",
+        "plan": {"playbooks": ["vm.restart"]},
+        "metadata": {"source": "integration"},
+        "lineage_tags": ["validation:idempotent"],
+    });
+
+    let created = create_workspace(&app, &token, payload.clone()).await;
+    let workspace_id = created["workspace"]["id"].as_i64().unwrap();
+    assert_eq!(
+        created["workspace"]["organization_id"].as_i64(),
+        Some(organization_ids[0] as i64)
+    );
+
+    let replayed = create_workspace(&app, &token, payload.clone()).await;
+    assert_eq!(replayed["workspace"]["id"].as_i64(), Some(workspace_id));
+    assert_eq!(
+        replayed["workspace"]["version"],
+        created["workspace"]["version"]
+    );
+
+    let mut conflicting = payload.clone();
+    conflicting["display_name"] = json!("Workspace Renamed");
+    conflicting["plan"] = json!({"playbooks": ["vm.redeploy"]});
+    let conflict_response = post_workspace_request(
+        &app,
+        &token,
+        "/api/trust/remediation/workspaces".to_string(),
+        conflicting,
+    )
+    .await;
+    assert_eq!(conflict_response.status(), StatusCode::CONFLICT);
+    let conflict_body = body::to_bytes(conflict_response.into_body()).await.unwrap();
+    let conflict_message = String::from_utf8(conflict_body.to_vec()).unwrap();
+    assert!(conflict_message.contains("display_name"));
+    assert!(conflict_message.contains("plan"));
+
+    let mut other_org = payload.clone();
+    other_org["organization_id"] = json!(organization_ids[1]);
+    let scoped = create_workspace(&app, &token, other_org).await;
+    assert_ne!(scoped["workspace"]["id"].as_i64(), Some(workspace_id));
+    assert_eq!(
+        scoped["workspace"]["organization_id"].as_i64(),
+        Some(organization_ids[1] as i64)
+    );
+
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM runtime_vm_remediation_workspaces WHERE workspace_key = $1",
+    )
+    .bind("workspace.idempotent")
+    .fetch_one(&harness.pool)
+    .await
+    .unwrap();
+    assert_eq!(count, 2);
+}
+
+// key: validation -> organization-role-guards
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn remediation_workspace_create_requires_org_admin(pool: PgPool) {
+    let harness = bootstrap_remediation_harness(&pool).await;
+    let app = harness.app.clone();
+
+    let organization_id: i32 = sqlx::query_scalar(
+        "INSERT INTO organizations (name, owner_id) VALUES ($1, $2) RETURNING id",
+    )
+    .bind("org-roles")
+    .bind(harness.operator_id)
+    .fetch_one(&harness.pool)
+    .await
+    .unwrap();
+
+    let (viewer_id, viewer_token) = harness.create_operator("viewer@example.com").await;
+    let (admin_id, admin_token) = harness.create_operator("admin@example.com").await;
+    for (user_id, role) in [(viewer_id, "viewer"), (admin_id, "admin")] {
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3)",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(&harness.pool)
+        .await
+        .unwrap();
+    }
+
+    let payload = json!({
+        "workspace_key": "workspace.roles",
+        "organization_id": organization_id,
+        "display_name": "Workspace Roles",
+        "plan": {"playbooks": ["vm.restart"]},
+    });
+
+    let denied = post_workspace_request(
+        &app,
+        &viewer_token,
+        "/api/trust/remediation/workspaces".to_string(),
+        payload.clone(),
+    )
+    .await;
+    assert_eq!(denied.status(), StatusCode::FORBIDDEN);
+
+    let created = create_workspace(&app, &admin_token, payload).await;
+    let workspace_id = created["workspace"]["id"].as_i64().unwrap();
+    assert_eq!(
+        created["workspace"]["organization_id"].as_i64(),
+        Some(organization_id as i64)
+    );
+
+    // Viewers keep read access to the organization's workspaces.
+    let details = fetch_workspace_details(&app, &viewer_token, workspace_id).await;
+    assert_eq!(details["workspace"]["id"].as_i64(), Some(workspace_id));
+
+    // Listings only include member organizations and the caller's own unscoped workspaces.
+    let (_, outsider_token) = harness.create_operator("outsider@example.com").await;
+    let unscoped = create_workspace(
+        &app,
+        &outsider_token,
+        json!({
+            "workspace_key": "workspace.outsider",
+            "display_name": "Outsider Workspace",
+            "plan": {"playbooks": ["vm.restart"]},
+        }),
+    )
+    .await;
+    let unscoped_id = unscoped["workspace"]["id"].as_i64().unwrap();
+    let listed_ids = |listed: Vec<Value>| -> Vec<i64> {
+        listed
+            .iter()
+            .filter_map(|entry| entry["workspace"]["id"].as_i64())
+            .collect()
+    };
+    assert_eq!(
+        listed_ids(list_workspaces(&app, &viewer_token).await),
+        vec![workspace_id]
+    );
+    assert_eq!(
+        listed_ids(list_workspaces(&app, &outsider_token).await),
+        vec![unscoped_id]
+    );
+
+    // Other operators can neither read nor change another user's unscoped workspace.
+    let hidden = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::GET)
+                .uri(format!("/api/trust/remediation/workspaces/{unscoped_id}"))
+                .header("Authorization", format!("Bearer {}", viewer_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(hidden.status(), StatusCode::NOT_FOUND);
+    let revision = post_workspace_request(
+        &app,
+        &viewer_token,
+        format!("/api/trust/remediation/workspaces/{unscoped_id}/revisions"),
+        json!({
+            "plan": {"playbooks": ["vm.restart"]},
+            "expected_workspace_version": unscoped["workspace"]["version"],
+        }),
+    )
+    .await;
+    assert_eq!(revision.status(), StatusCode::NOT_FOUND);
+    let own = fetch_workspace_details(&app, &outsider_token, unscoped_id).await;
+    assert_eq!(own["workspace"]["id"].as_i64(), Some(unscoped_id));
+}
+
+// key: validation -> remediation-workspace-gate-ordering
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn remediation_workspace_promotion_enforces_gate_order(pool: PgPool) {
+    let harness = bootstrap_remediation_harness(&pool).await;
+    let app = harness.app.clone();
+    let token = harness.token.clone();
+
+    create_playbook(
+        &app,
+        &token,
+        json!({
+            "playbook_key": "vm.restart",
+            "display_name": "VM Restart",
+            "executor_type": "shell",
+            "approval_required": false,
+            "metadata": {"origin": "gate-ordering"},
+        }),
+    )
+    .await;
+
+    let workspace = create_workspace(
+        &app,
+        &token,
+        json!({
+            "workspace_key": "workspace.gate-order",
+            "display_name": "Workspace Gate Order",
+            "plan": {
+                "playbooks": ["vm.restart"],
+                "targets": [{"runtime_vm_instance_id": harness.vm_instance_id}]
+            },
+        }),
+    )
+    .await;
+    let workspace_id = workspace["workspace"]["id"].as_i64().unwrap();
+    let workspace_version = workspace["workspace"]["version"].as_i64().unwrap();
+    let revision_id = workspace["workspace"]["active_revision_id"]
+        .as_i64()
+        .unwrap();
+    let mut revision_version = select_revision(&workspace, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+
+    let after_schema = apply_workspace_schema(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "result_status": "passed",
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    revision_version = select_revision(&after_schema, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+    let after_policy = apply_workspace_policy(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "policy_status": "approved",
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    revision_version = select_revision(&after_policy, revision_id)["revision"]["version"]
+        .as_i64()
+        .unwrap();
+
+    let promotion_uri = format!(
+        "/api/trust/remediation/workspaces/{workspace_id}/revisions/{revision_id}/promotion"
+    );
+    let premature = post_workspace_request(
+        &app,
+        &token,
+        promotion_uri.clone(),
+        json!({
+            "promotion_status": "completed",
+            "expected_workspace_version": workspace_version,
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    assert_eq!(premature.status(), StatusCode::CONFLICT);
+    let premature_body = body::to_bytes(premature.into_body()).await.unwrap();
+    let premature_message = String::from_utf8(premature_body.to_vec()).unwrap();
+    assert!(premature_message.contains("simulation=pending"));
+
+    let premature_pending = post_workspace_request(
+        &app,
+        &token,
+        promotion_uri.clone(),
+        json!({
+            "promotion_status": "pending",
+            "expected_workspace_version": workspace_version,
+            "expected_revision_version": revision_version,
+        }),
+    )
+    .await;
+    assert_eq!(premature_pending.status(), StatusCode::CONFLICT);
+
+    let blank_override = post_workspace_request(
+        &app,
+        &token,
+        promotion_uri.clone(),
+        json!({
+            "promotion_status": "completed",
+            "expected_workspace_version": workspace_version,
+            "expected_revision_version": revision_version,
+            "gate_override": {"reason": "  "},
+        }),
+    )
+    .await;
+    assert_eq!(blank_override.status(), StatusCode::BAD_REQUEST);
+
+    let runs_before = list_workspace_runs(&app, &token, workspace_id, revision_id).await;
+    assert!(runs_before.is_empty());
+
+    let overridden = apply_workspace_promotion(
+        &app,
+        &token,
+        workspace_id,
+        revision_id,
+        json!({
+            "promotion_status": "completed",
+            "notes": ["hotfix"],
+            "expected_workspace_version": workspace_version,
+            "expected_revision_version": revision_version,
+            "gate_override": {"reason": "incident INC-7 hotfix"},
+        }),
+    )
+    .await;
+    assert_eq!(
+        overridden["workspace"]["lifecycle_state"].as_str(),
+        Some("promoted")
+    );
+    let promoted_revision = select_revision(&overridden, revision_id);
+    let promotion_snapshot = find_snapshot(promoted_revision, "promotion").unwrap();
+    let notes: Vec<&str> = promotion_snapshot["notes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert!(notes.contains(&"gate_override_reason=incident INC-7 hotfix"));
+    assert!(notes.contains(&"gate_override_bypassed=simulation=pending"));
+    assert!(notes.contains(&format!("gate_override_by={}", harness.operator_id).as_str()));
+    assert_eq!(overridden["promotion_runs"].as_array().unwrap().len(), 1);
+}
+
+// key: validation -> remediation-workspace-snapshot-retention
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn remediation_workspace_snapshot_retention_keeps_newest(pool: PgPool) {
+    let harness = bootstrap_remediation_harness(&pool).await;
+    let workspace = create_workspace(
+        &harness.app,
+        &harness.token,
+        json!({
+            "workspace_key": "workspace.retention",
+            "display_name": "Workspace Retention",
+            "plan": {"playbooks": ["vm.restart"]},
+        }),
+    )
+    .await;
+    let revision_id = workspace["workspace"]["active_revision_id"]
+        .as_i64()
+        .unwrap();
+
+    let base = Utc::now() - ChronoDuration::hours(1);
+    let mut snapshot_ids = Vec::new();
+    for offset in 0..10 {
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO runtime_vm_remediation_workspace_validation_snapshots \
+             (workspace_revision_id, snapshot_type, status, gate_context, notes, recorded_at) \
+             VALUES ($1, 'schema', 'passed', '{}'::JSONB, ARRAY[]::TEXT[], $2) RETURNING id",
+        )
+        .bind(revision_id)
+        .bind(base + ChronoDuration::minutes(offset))
+        .fetch_one(&harness.pool)
+        .await
+        .unwrap();
+        snapshot_ids.push(id);
+    }
+    let policy_snapshot_id: i64 = sqlx::query_scalar(
+        "INSERT INTO runtime_vm_remediation_workspace_validation_snapshots \
+         (workspace_revision_id, snapshot_type, status, gate_context, notes, recorded_at) \
+         VALUES ($1, 'policy', 'approved', '{}'::JSONB, ARRAY[]::TEXT[], $2) RETURNING id",
+    )
+    .bind(revision_id)
+    .bind(base)
+    .fetch_one(&harness.pool)
+    .await
+    .unwrap();
+
+    let pruned = prune_validation_snapshots(&harness.pool, 3).await.unwrap();
+    assert_eq!(pruned, 7);
+
+    let remaining: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM runtime_vm_remediation_workspace_validation_snapshots \
+         WHERE workspace_revision_id = $1 ORDER BY recorded_at DESC, id DESC",
+    )
+    .bind(revision_id)
+    .fetch_all(&harness.pool)
+    .await
+    .unwrap();
+    let mut expected: Vec<i64> = snapshot_ids.iter().rev().take(3).copied().collect();
+    expected.push(policy_snapshot_id);
+    assert_eq!(remaining, expected);
+
+    let clamped = prune_validation_snapshots(&harness.pool, 0).await.unwrap();
+    assert_eq!(clamped, 2);
+    let latest_schema: i64 = sqlx::query_scalar(
+        "SELECT id FROM runtime_vm_remediation_workspace_validation_snapshots \
+         WHERE workspace_revision_id = $1 AND snapshot_type = 'schema'",
+    )
+    .bind(revision_id)
+    .fetch_one(&harness.pool)
+    .await
+    .unwrap();
+    assert_eq!(latest_schema, snapshot_ids[9]);
+}
+
 // key: validation -> remediation-workspace:pending-refresh
 #[sqlx::test]
 #[ignore = "requires DATABASE_URL with Postgres server"]
@@ -1906,6 +2520,113 @@ async fn remediation_workspace_promotion_stream_includes_workspace_context(pool:
     );
 }
 
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn remediation_runs_ndjson_streams_same_records_as_array(pool: PgPool) {
+    let harness = bootstrap_remediation_harness(&pool).await;
+    let app = harness.app.clone();
+    let token = harness.token.clone();
+
+    for offset in 0..5 {
+        sqlx::query(
+            r#"
+            INSERT INTO runtime_vm_remediation_runs (
+                runtime_vm_instance_id,
+                playbook,
+                status,
+                started_at
+            ) VALUES ($1, $2, 'completed', $3)
+            "#,
+        )
+        .bind(harness.vm_instance_id)
+        .bind(format!("ndjson-playbook-{offset}"))
+        .bind(Utc::now() - ChronoDuration::minutes(offset))
+        .execute(&pool)
+        .await
+        .expect("insert remediation run");
+    }
+
+    let all_runs = list_runs_for_instance(&app, &token, harness.vm_instance_id).await;
+    assert!(all_runs.len() >= 5);
+    let all_lines = list_runs_ndjson(&app, &token, harness.vm_instance_id, None).await;
+    assert_eq!(all_lines, all_runs);
+
+    // pagination caps the stream exactly like the array form
+    let page_uri = format!(
+        "/api/trust/remediation/runs?runtime_vm_instance_id={}&limit=3&offset=1",
+        harness.vm_instance_id
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&page_uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = body::to_bytes(response.into_body()).await.unwrap();
+    let page: Vec<Value> = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(page.len(), 3);
+    assert_eq!(page, all_runs[1..4].to_vec());
+
+    let page_lines = list_runs_ndjson(
+        &app,
+        &token,
+        harness.vm_instance_id,
+        Some("&limit=3&offset=1"),
+    )
+    .await;
+    assert_eq!(page_lines, page);
+}
+
+/// Reads the NDJSON body chunk by chunk and checks each chunk carries exactly one
+/// complete record, i.e. rows were flushed as the cursor produced them rather than
+/// serialized into one buffered payload.
+async fn list_runs_ndjson(
+    app: &Router,
+    token: &str,
+    runtime_vm_instance_id: i64,
+    extra_query: Option<&str>,
+) -> Vec<Value> {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::GET)
+                .uri(format!(
+                    "/api/trust/remediation/runs?runtime_vm_instance_id={}{}",
+                    runtime_vm_instance_id,
+                    extra_query.unwrap_or_default()
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Accept", "application/x-ndjson")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+
+    let mut body = response.into_body();
+    let mut records = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.expect("failed to read NDJSON chunk");
+        let line = std::str::from_utf8(&chunk).expect("NDJSON chunk not UTF-8");
+        let line = line
+            .strip_suffix('\n')
+            .expect("each NDJSON chunk ends with a newline");
+        assert!(!line.contains('\n'), "chunk held more than one record");
+        records.push(serde_json::from_str(line).expect("NDJSON line is a JSON object"));
+    }
+    records
+}
+
 async fn create_playbook(
     app: &Router,
     token: &str,
@@ -2255,11 +2976,12 @@ async fn remediation_lifecycle_harness(pool: PgPool) {
     let approved_run: Value = serde_json::from_slice(&body_bytes).unwrap();
     assert_eq!(approved_run["approval_state"], "approved");
 
-    insert_artifact(
+    record_artifact(
         &pool,
         run_id,
         "log",
         None,
+        None,
         &json!({"message": "executor started"}),
         Some(operator_id),
     )
@@ -2282,6 +3004,8 @@ async fn remediation_lifecycle_harness(pool: PgPool) {
     let body_bytes = body::to_bytes(response.into_body()).await.unwrap();
     let artifacts: Value = serde_json::from_slice(&body_bytes).unwrap();
     assert_eq!(artifacts.as_array().unwrap().len(), 1);
+    assert_eq!(artifacts[0]["content_type"], "application/json");
+    assert_eq!(artifacts[0]["render_hint"], "json");
 
     let _final_state = upsert_state(
         &pool,
@@ -0,0 +1,160 @@
+mod common;
+
+use axum::{routing::post, Extension, Router};
+use backend::db::runtime_vm_remediation_runs::{
+    ensure_remediation_run, EnsureRemediationRunRequest,
+};
+use backend::db::runtime_vm_remediation_workspaces::{create_workspace, CreateWorkspace};
+use hyper::{Body, Method, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{seed_user, seed_vm_server, token, use_test_jwt_secret};
+
+// key: remediation_surface -> workspace clone
+
+async fn clone(
+    app: &Router,
+    token: &str,
+    workspace_id: i64,
+    payload: Value,
+) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!(
+                    "/api/trust/remediation/workspaces/{workspace_id}/clone"
+                ))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn clone_copies_the_active_plan_without_runs(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let owner_id = seed_user(&pool, "owner@example.com").await;
+    let stranger_id = seed_user(&pool, "stranger@example.com").await;
+    let plan = json!({"steps": [{"action": "restart", "target": "db"}]});
+    let metadata = json!({"severity": "high"});
+    let source = create_workspace(
+        &pool,
+        CreateWorkspace {
+            workspace_key: "source-workspace",
+            display_name: "Source",
+            description: Some("original"),
+            owner_id,
+            organization_id: None,
+            plan: &plan,
+            metadata: Some(&metadata),
+            lineage_tags: &["db"],
+            lineage_labels: &["baseline"],
+        },
+    )
+    .await
+    .unwrap();
+    let source_id = source.workspace.id;
+
+    let vm_instance_id = seed_vm_server(&pool, owner_id, "clone-server").await;
+    ensure_remediation_run(
+        &pool,
+        EnsureRemediationRunRequest {
+            runtime_vm_instance_id: vm_instance_id,
+            playbook_key: "shell:baseline",
+            playbook_id: None,
+            metadata: None,
+            automation_payload: None,
+            approval_required: false,
+            assigned_owner_id: Some(owner_id),
+            sla_duration_seconds: None,
+            workspace_id: Some(source_id),
+            workspace_revision_id: source.workspace.active_revision_id,
+            promotion_gate_context: None,
+            scheduled_for: None,
+        },
+    )
+    .await
+    .unwrap()
+    .expect("source run inserted");
+
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/workspaces/:workspace_id/clone",
+            post(backend::remediation_api::clone_workspace_handler),
+        )
+        .layer(Extension(pool.clone()));
+
+    // unscoped workspaces can only be cloned by their owner
+    let (status, _) = clone(&app, &token(stranger_id, "operator"), source_id, json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, cloned) = clone(&app, &token(owner_id, "operator"), source_id, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let workspace = &cloned["workspace"];
+    let clone_id = workspace["id"].as_i64().expect("clone id");
+    assert_ne!(clone_id, source_id);
+    let clone_key = workspace["workspace_key"].as_str().unwrap();
+    assert_ne!(clone_key, "source-workspace");
+    assert!(clone_key.starts_with("source-workspace-clone-"));
+    assert_eq!(workspace["owner_id"].as_i64(), Some(owner_id as i64));
+    assert_eq!(workspace["lifecycle_state"], "draft");
+    assert_eq!(workspace["metadata"], metadata);
+    assert_eq!(workspace["lineage_tags"], json!(["db"]));
+
+    let revisions = cloned["revisions"].as_array().expect("revisions");
+    assert_eq!(revisions.len(), 1);
+    let revision = &revisions[0]["revision"];
+    assert_eq!(revision["revision_number"].as_i64(), Some(1));
+    assert_eq!(revision["plan"], plan);
+    assert_eq!(revision["lineage_labels"], json!(["baseline"]));
+    assert_eq!(revision["schema_status"], "pending");
+    assert_eq!(revision["policy_status"], "pending");
+    assert_eq!(
+        workspace["active_revision_id"].as_i64(),
+        revision["id"].as_i64()
+    );
+
+    let inherited_runs: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM runtime_vm_remediation_runs WHERE workspace_id = $1",
+    )
+    .bind(clone_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(inherited_runs, 0);
+
+    // an explicit key is used as given and must be free
+    let (status, named) = clone(
+        &app,
+        &token(owner_id, "operator"),
+        source_id,
+        json!({"workspace_key": "named-clone"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(named["workspace"]["workspace_key"], "named-clone");
+    let (status, _) = clone(
+        &app,
+        &token(owner_id, "operator"),
+        source_id,
+        json!({"workspace_key": "named-clone"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
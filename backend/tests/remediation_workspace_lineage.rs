@@ -0,0 +1,157 @@
+mod common;
+
+use axum::{routing::get, Extension, Router};
+use backend::db::runtime_vm_remediation_workspaces::{
+    apply_promotion, create_revision, create_workspace, CreateWorkspace, CreateWorkspaceRevision,
+    PromotionUpdate, WorkspaceDetails,
+};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{seed_user, token, use_test_jwt_secret};
+
+// key: remediation-lineage -> revision chain endpoint
+
+fn newest_revision_id(details: &WorkspaceDetails) -> i64 {
+    details
+        .revisions
+        .iter()
+        .map(|entry| &entry.revision)
+        .max_by_key(|revision| revision.revision_number)
+        .expect("revision")
+        .id
+}
+
+async fn append_revision(
+    pool: &PgPool,
+    details: &WorkspaceDetails,
+    step: &str,
+) -> WorkspaceDetails {
+    let plan = json!({"steps": [step]});
+    create_revision(
+        pool,
+        CreateWorkspaceRevision {
+            workspace_id: details.workspace.id,
+            previous_revision_id: Some(newest_revision_id(details)),
+            created_by: details.workspace.owner_id,
+            plan: &plan,
+            metadata: None,
+            lineage_labels: &[],
+            expected_workspace_version: details.workspace.version,
+        },
+    )
+    .await
+    .unwrap()
+    .expect("revision appended")
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn lineage_lists_the_chain_in_order_and_flags_the_promotion(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let owner_id = seed_user(&pool, "lineage@example.com").await;
+    let plan = json!({"steps": ["inspect"]});
+    let first = create_workspace(
+        &pool,
+        CreateWorkspace {
+            workspace_key: "lineage-workspace",
+            display_name: "Lineage",
+            description: None,
+            owner_id,
+            organization_id: None,
+            plan: &plan,
+            metadata: None,
+            lineage_tags: &[],
+            lineage_labels: &[],
+        },
+    )
+    .await
+    .unwrap();
+    let workspace_id = first.workspace.id;
+    let revision_one = newest_revision_id(&first);
+
+    let second = append_revision(&pool, &first, "restart").await;
+    let revision_two = newest_revision_id(&second);
+    let revision_two_version = second
+        .revisions
+        .iter()
+        .find(|entry| entry.revision.id == revision_two)
+        .unwrap()
+        .revision
+        .version;
+    let promoted = apply_promotion(
+        &pool,
+        PromotionUpdate {
+            workspace_id,
+            revision_id: revision_two,
+            requested_by: owner_id,
+            promotion_status: "completed",
+            notes: &[],
+            expected_workspace_version: second.workspace.version,
+            expected_revision_version: revision_two_version,
+        },
+    )
+    .await
+    .unwrap()
+    .expect("revision promoted");
+    let third = append_revision(&pool, &promoted, "verify").await;
+    let revision_three = newest_revision_id(&third);
+
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/workspaces/:workspace_id/lineage",
+            get(backend::remediation_api::get_workspace_lineage_handler),
+        )
+        .layer(Extension(pool.clone()));
+    let token = token(owner_id, "operator");
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/trust/remediation/workspaces/{workspace_id}/lineage"
+                ))
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let lineage: Value = serde_json::from_slice(&bytes).unwrap();
+
+    let revisions = lineage["revisions"].as_array().expect("revisions");
+    let chain: Vec<(i64, i64, Option<i64>)> = revisions
+        .iter()
+        .map(|revision| {
+            (
+                revision["revision_id"].as_i64().unwrap(),
+                revision["revision_number"].as_i64().unwrap(),
+                revision["previous_revision_id"].as_i64(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        chain,
+        vec![
+            (revision_one, 1, None),
+            (revision_two, 2, Some(revision_one)),
+            (revision_three, 3, Some(revision_two)),
+        ]
+    );
+    let promoted_flags: Vec<bool> = revisions
+        .iter()
+        .map(|revision| revision["promoted"].as_bool().unwrap())
+        .collect();
+    assert_eq!(promoted_flags, vec![false, true, false]);
+    assert_eq!(revisions[1]["promotion_status"], "completed");
+    assert_eq!(revisions[0]["schema_status"], "pending");
+    assert_eq!(revisions[2]["active"], true);
+    assert_eq!(lineage["promoted_revision_id"].as_i64(), Some(revision_two));
+    assert_eq!(lineage["active_revision_id"].as_i64(), Some(revision_three));
+    assert_eq!(lineage["branch_points"], json!([]));
+}
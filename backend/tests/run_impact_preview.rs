@@ -0,0 +1,116 @@
+mod common;
+
+use axum::{routing::get, Extension, Router};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{token, use_test_jwt_secret};
+
+// key: remediation-impact-preview-tests -> affected instances with trust states
+
+async fn seed_instance(
+    pool: &PgPool,
+    owner_id: i32,
+    name: &str,
+    server_status: &str,
+    lifecycle_state: &str,
+) -> i64 {
+    let server_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, $2, 'virtual-machine', '{}'::jsonb, $3, $2) RETURNING id",
+    )
+    .bind(owner_id)
+    .bind(name)
+    .bind(server_status)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let vm_instance_id: i64 = sqlx::query_scalar(
+        "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, $2) RETURNING id::BIGINT",
+    )
+    .bind(server_id)
+    .bind(format!("{name}-vm"))
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO runtime_vm_trust_registry (runtime_vm_instance_id, attestation_status, lifecycle_state) VALUES ($1, 'trusted', $2)",
+    )
+    .bind(vm_instance_id)
+    .bind(lifecycle_state)
+    .execute(pool)
+    .await
+    .unwrap();
+    vm_instance_id
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn impact_lists_both_targets_with_trust_states(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let operator_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("impact@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let serving_vm = seed_instance(&pool, operator_id, "edge-east", "running", "restored").await;
+    let quarantined_vm =
+        seed_instance(&pool, operator_id, "edge-west", "running", "quarantined").await;
+    let run_id: i64 = sqlx::query_scalar(
+        "INSERT INTO runtime_vm_remediation_runs (runtime_vm_instance_id, playbook, status, metadata) VALUES ($1, 'vm.restart.service', 'pending', $2) RETURNING id",
+    )
+    .bind(serving_vm)
+    .bind(json!({ "target_instance_ids": [quarantined_vm, serving_vm] }))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/runs/:run_id/impact",
+            get(backend::remediation_api::run_impact_handler),
+        )
+        .layer(Extension(pool.clone()));
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/trust/remediation/runs/{run_id}/impact"))
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", token(operator_id, "operator")),
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let preview: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(preview["scope"], "instances");
+    let instances = preview["instances"].as_array().unwrap();
+    assert_eq!(instances.len(), 2);
+
+    let east = &instances[0];
+    assert_eq!(east["runtime_vm_instance_id"], serving_vm);
+    assert_eq!(east["server_name"], "edge-east");
+    assert_eq!(east["attestation_status"], "trusted");
+    assert_eq!(east["lifecycle_state"], "restored");
+    assert_eq!(east["serving_traffic"], true);
+    assert_eq!(east["primary"], true);
+
+    let west = &instances[1];
+    assert_eq!(west["runtime_vm_instance_id"], quarantined_vm);
+    assert_eq!(west["lifecycle_state"], "quarantined");
+    assert_eq!(west["serving_traffic"], false);
+    assert_eq!(west["primary"], false);
+
+    assert_eq!(preview["serving_instances"], 1);
+    assert_eq!(preview["unresolved_instance_ids"], json!([]));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use axum::{routing::post, Extension, Router};
+use backend::db::runtime_vm_remediation_runs::{release_due_scheduled_runs, try_acquire_next_run};
+use chrono::{Duration, Utc};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::seed_vm_server;
+
+// key: remediation-run-scheduling-tests -> deferred runs fire at their time or not at all
+
+async fn post_json(app: &Router, api_key: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("X-API-Key", api_key)
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn schedule(app: &Router, api_key: &str, vm_instance_id: i64) -> i64 {
+    let (status, body) = post_json(
+        app,
+        api_key,
+        "/api/trust/remediation/runs",
+        json!({
+            "runtime_vm_instance_id": vm_instance_id,
+            "playbook": "vm.restart.service",
+            "scheduled_for": Utc::now() + Duration::hours(2),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["run"]["status"], "scheduled");
+    body["run"]["id"].as_i64().unwrap()
+}
+
+async fn make_due(pool: &PgPool, run_id: i64) {
+    sqlx::query(
+        "UPDATE runtime_vm_remediation_runs SET scheduled_for = NOW() - INTERVAL '1 second' WHERE id = $1",
+    )
+    .bind(run_id)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn run_status(pool: &PgPool, run_id: i64) -> String {
+    sqlx::query_scalar("SELECT status FROM runtime_vm_remediation_runs WHERE id = $1")
+        .bind(run_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn scheduled_runs_wait_fire_and_cancel(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("scheduler@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    sqlx::query(
+        "INSERT INTO runtime_vm_remediation_playbooks (playbook_key, display_name, executor_type, owner_id, approval_required) VALUES ('vm.restart.service', 'Restart service', 'shell', $1, FALSE)",
+    )
+    .bind(owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let window_vm = seed_vm_server(&pool, owner_id, "edge-window").await;
+    let cancelled_vm = seed_vm_server(&pool, owner_id, "edge-cancelled").await;
+    let app = Router::new()
+        .route(
+            "/api/trust/remediation/runs",
+            post(backend::remediation_api::enqueue_run_handler),
+        )
+        .route(
+            "/api/trust/remediation/runs/:run_id/cancel",
+            post(backend::remediation_api::cancel_run_handler),
+        )
+        .layer(Extension(pool.clone()));
+
+    // not executed before its time
+    let window_run = schedule(&app, "edge-window", window_vm).await;
+    assert_eq!(release_due_scheduled_runs(&pool).await.unwrap(), 0);
+    assert!(try_acquire_next_run(&pool).await.unwrap().is_none());
+
+    // fires once due
+    make_due(&pool, window_run).await;
+    assert_eq!(release_due_scheduled_runs(&pool).await.unwrap(), 1);
+    assert_eq!(run_status(&pool, window_run).await, "pending");
+    let acquired = try_acquire_next_run(&pool).await.unwrap().unwrap();
+    assert_eq!(acquired.id, window_run);
+
+    // cancelled beforehand, it never fires
+    let cancelled_run = schedule(&app, "edge-cancelled", cancelled_vm).await;
+    let (status, body) = post_json(
+        &app,
+        "edge-cancelled",
+        &format!("/api/trust/remediation/runs/{cancelled_run}/cancel"),
+        json!({ "reason": "maintenance window moved" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "cancelled");
+    assert_eq!(body["cancellation_reason"], "maintenance window moved");
+    make_due(&pool, cancelled_run).await;
+    assert_eq!(release_due_scheduled_runs(&pool).await.unwrap(), 0);
+    assert_eq!(run_status(&pool, cancelled_run).await, "cancelled");
+
+    // a run that already started cannot be cancelled
+    let (status, _) = post_json(
+        &app,
+        "edge-window",
+        &format!("/api/trust/remediation/runs/{window_run}/cancel"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
@@ -0,0 +1,157 @@
+// key: secrets-tests -> versioned references
+use axum::{extract::Path, extract::Query, Extension, Json};
+use sqlx::PgPool;
+
+use backend::extractor::AuthUser;
+use backend::secrets::{
+    create_secret, list_secret_versions, resolve_secret_reference, update_secret, CreateSecret,
+    SecretVersionsQuery, UpdateSecret,
+};
+
+async fn seed_server(pool: &PgPool) -> (i32, i32) {
+    let owner_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1,$2) RETURNING id")
+            .bind("secrets@example.com")
+            .bind("hash")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    let server_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, $2, $3, '{}'::jsonb, $4, $5) RETURNING id",
+    )
+    .bind(owner_id)
+    .bind("secret-consumer")
+    .bind("custom")
+    .bind("running")
+    .bind("api-key")
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (owner_id, server_id)
+}
+
+fn auth(user_id: i32) -> AuthUser {
+    AuthUser {
+        user_id,
+        role: "user".into(),
+    }
+}
+
+async fn rotate(pool: &PgPool, owner_id: i32, server_id: i32, secret_id: i32, value: &str) {
+    update_secret(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Path((server_id, secret_id)),
+        Json(UpdateSecret {
+            value: value.into(),
+        }),
+    )
+    .await
+    .unwrap();
+}
+
+// key: validation -> secrets:pinned-version-resolution
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn pinned_secret_reference_survives_rotation(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    std::env::remove_var("VAULT_ADDR");
+
+    let (owner_id, server_id) = seed_server(&pool).await;
+    create_secret(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Path(server_id),
+        Json(CreateSecret {
+            name: "db-password".into(),
+            value: "first".into(),
+        }),
+    )
+    .await
+    .unwrap();
+    let secret_id: i32 = sqlx::query_scalar(
+        "SELECT id FROM server_secrets WHERE server_id = $1 AND name = 'db-password'",
+    )
+    .bind(server_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    rotate(&pool, owner_id, server_id, secret_id, "second").await;
+
+    let pinned = resolve_secret_reference(&pool, server_id, "secret://db-password@1")
+        .await
+        .unwrap();
+    assert_eq!(pinned, "first");
+
+    let latest = resolve_secret_reference(&pool, server_id, "secret://db-password")
+        .await
+        .unwrap();
+    assert_eq!(latest, "second");
+
+    let versions = list_secret_versions(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Path("db-password".to_string()),
+        Query(SecretVersionsQuery { server_id }),
+    )
+    .await
+    .unwrap()
+    .0;
+    let numbers: Vec<i32> = versions.iter().map(|v| v.version).collect();
+    assert_eq!(numbers, vec![2, 1]);
+    assert!(versions[0].current);
+    assert!(!versions[1].current);
+}
+
+// key: validation -> secrets:latest-resolution-after-rotation
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn latest_secret_reference_follows_rotations_within_retention(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    std::env::remove_var("VAULT_ADDR");
+
+    let (owner_id, server_id) = seed_server(&pool).await;
+    create_secret(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Path(server_id),
+        Json(CreateSecret {
+            name: "api-token".into(),
+            value: "v1".into(),
+        }),
+    )
+    .await
+    .unwrap();
+    let secret_id: i32 = sqlx::query_scalar(
+        "SELECT id FROM server_secrets WHERE server_id = $1 AND name = 'api-token'",
+    )
+    .bind(server_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    for version in 2..=7 {
+        rotate(
+            &pool,
+            owner_id,
+            server_id,
+            secret_id,
+            &format!("v{version}"),
+        )
+        .await;
+    }
+
+    let latest = resolve_secret_reference(&pool, server_id, "secret://api-token@latest")
+        .await
+        .unwrap();
+    assert_eq!(latest, "v7");
+
+    // Default retention keeps the five newest versions.
+    let pruned = resolve_secret_reference(&pool, server_id, "secret://api-token@2").await;
+    assert_eq!(pruned.unwrap_err().0, axum::http::StatusCode::NOT_FOUND);
+    let retained = resolve_secret_reference(&pool, server_id, "secret://api-token@3")
+        .await
+        .unwrap();
+    assert_eq!(retained, "v3");
+}
@@ -0,0 +1,254 @@
+mod common;
+
+use axum::{routing::delete, Extension, Router};
+use backend::db::mcp_servers::{
+    is_live_owned_by, list_servers, restore, soft_delete, RestoreOutcome,
+};
+use backend::extractor::ServicePrincipal;
+use backend::{delete_server, sweep_deleted_servers, QueuedJob};
+use chrono::{Duration, Utc};
+use hyper::{Body, Request, StatusCode};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tower::ServiceExt;
+
+use common::{seed_server, seed_user, seed_vm_server, token, use_test_jwt_secret};
+
+// key: server-soft-delete -> listings, restore window, retention sweep
+
+async fn listed_ids(pool: &PgPool, owner_id: Option<i32>) -> Vec<i32> {
+    list_servers(pool, owner_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|server| server.id)
+        .collect()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn soft_delete_hides_and_restore_brings_back(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner = seed_user(&pool, "owner@example.com").await;
+    let other = seed_user(&pool, "other@example.com").await;
+    let kept = seed_server(&pool, owner, "kept").await;
+    let deleted = seed_server(&pool, owner, "deleted").await;
+    let run_id: i32 = sqlx::query_scalar(
+        "INSERT INTO build_artifact_runs (server_id, local_image, started_at, completed_at, status, credential_health_status) VALUES ($1, 'mcp:deleted', NOW(), NOW(), 'succeeded', 'healthy') RETURNING id",
+    )
+    .bind(deleted)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // only the owner can delete, and only once
+    assert!(soft_delete(&pool, deleted, other).await.unwrap().is_none());
+    assert!(soft_delete(&pool, deleted, owner).await.unwrap().is_some());
+    assert!(soft_delete(&pool, deleted, owner).await.unwrap().is_none());
+
+    assert_eq!(listed_ids(&pool, Some(owner)).await, vec![kept]);
+    assert_eq!(listed_ids(&pool, None).await, vec![kept]);
+
+    // ownership checks and the server's api key stop answering while it is deleted
+    assert!(is_live_owned_by(&pool, kept, owner).await.unwrap());
+    assert!(!is_live_owned_by(&pool, deleted, owner).await.unwrap());
+    assert!(ServicePrincipal::resolve(&pool, "deleted")
+        .await
+        .unwrap()
+        .is_none());
+
+    // history that points at the deleted server still resolves
+    let server_name: String = sqlx::query_scalar(
+        "SELECT s.name FROM build_artifact_runs r JOIN mcp_servers s ON s.id = r.server_id WHERE r.id = $1",
+    )
+    .bind(run_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(server_name, "deleted");
+
+    let retention_cutoff = Utc::now() - Duration::hours(168);
+    assert!(matches!(
+        restore(&pool, deleted, other, retention_cutoff)
+            .await
+            .unwrap(),
+        RestoreOutcome::NotFound
+    ));
+    match restore(&pool, deleted, owner, retention_cutoff)
+        .await
+        .unwrap()
+    {
+        RestoreOutcome::Restored(server) => {
+            assert_eq!(server.id, deleted);
+            assert_eq!(server.name, "deleted");
+        }
+        other => panic!("expected a restore, got {other:?}"),
+    }
+    assert_eq!(listed_ids(&pool, Some(owner)).await, vec![kept, deleted]);
+    assert!(is_live_owned_by(&pool, deleted, owner).await.unwrap());
+    assert!(ServicePrincipal::resolve(&pool, "deleted")
+        .await
+        .unwrap()
+        .is_some());
+    // a live server has nothing to restore
+    assert!(matches!(
+        restore(&pool, kept, owner, retention_cutoff).await.unwrap(),
+        RestoreOutcome::NotFound
+    ));
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn sweep_hard_deletes_only_past_retention(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner = seed_user(&pool, "owner@example.com").await;
+    let expired = seed_server(&pool, owner, "expired").await;
+    let recent = seed_server(&pool, owner, "recent").await;
+    let live = seed_server(&pool, owner, "live").await;
+    soft_delete(&pool, recent, owner).await.unwrap();
+    soft_delete(&pool, expired, owner).await.unwrap();
+    sqlx::query("UPDATE mcp_servers SET deleted_at = NOW() - INTERVAL '8 days' WHERE id = $1")
+        .bind(expired)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cutoff = Utc::now() - Duration::hours(168);
+    assert!(matches!(
+        restore(&pool, expired, owner, cutoff).await.unwrap(),
+        RestoreOutcome::RetentionExpired { .. }
+    ));
+
+    let (job_tx, _job_rx) = mpsc::channel::<QueuedJob>(8);
+    assert_eq!(
+        sweep_deleted_servers(&pool, &job_tx, cutoff).await,
+        vec![expired]
+    );
+    let remaining: Vec<i32> = sqlx::query_scalar("SELECT id FROM mcp_servers ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec![recent, live]);
+    assert!(matches!(
+        restore(&pool, expired, owner, cutoff).await.unwrap(),
+        RestoreOutcome::NotFound
+    ));
+
+    // once the recent deletion ages out too, the next sweep takes it
+    let later = Utc::now() + Duration::seconds(1);
+    assert_eq!(
+        sweep_deleted_servers(&pool, &job_tx, later).await,
+        vec![recent]
+    );
+    assert_eq!(listed_ids(&pool, Some(owner)).await, vec![live]);
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn handler_deleted_server_is_swept_and_history_kept(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let owner = seed_user(&pool, "owner@example.com").await;
+    let vm_instance_id = seed_vm_server(&pool, owner, "swept").await;
+    let server_id: i32 =
+        sqlx::query_scalar("SELECT server_id FROM runtime_vm_instances WHERE id = $1")
+            .bind(vm_instance_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let docker = seed_server(&pool, owner, "docker").await;
+    let run_id: i64 = sqlx::query_scalar(
+        "INSERT INTO runtime_vm_remediation_runs (runtime_vm_instance_id, playbook, status, metadata) VALUES ($1, 'vm.restart.service', 'completed', '{}'::jsonb) RETURNING id",
+    )
+    .bind(vm_instance_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO context_sessions (server_id, user_id) VALUES ($1, $2)")
+        .bind(server_id)
+        .bind(owner)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (job_tx, mut job_rx) = mpsc::channel::<QueuedJob>(8);
+    let app = Router::new()
+        .route("/api/servers/:id", delete(delete_server))
+        .layer(Extension(job_tx.clone()))
+        .layer(Extension(pool.clone()));
+    let bearer = format!("Bearer {}", token(owner, "user"));
+    for id in [server_id, docker] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(format!("/api/servers/{id}"))
+                    .header("Authorization", &bearer)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+    // the handler's own soft_delete metric used to block the hard delete
+    let metrics: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM usage_metrics WHERE server_id = $1")
+            .bind(server_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(metrics, 1);
+    while job_rx.try_recv().is_ok() {}
+
+    sqlx::query("UPDATE mcp_servers SET deleted_at = NOW() - INTERVAL '8 days'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let cutoff = Utc::now() - Duration::hours(168);
+    assert_eq!(
+        sweep_deleted_servers(&pool, &job_tx, cutoff).await,
+        vec![server_id, docker]
+    );
+
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM mcp_servers")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+    let metrics: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM usage_metrics")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(metrics, 0);
+
+    // the vm instance lets go of the server but its remediation history stays
+    let instance_server: Option<i32> =
+        sqlx::query_scalar("SELECT server_id FROM runtime_vm_instances WHERE id = $1")
+            .bind(vm_instance_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(instance_server, None);
+    let run_instance: i64 = sqlx::query_scalar(
+        "SELECT runtime_vm_instance_id FROM runtime_vm_remediation_runs WHERE id = $1",
+    )
+    .bind(run_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(run_instance, vm_instance_id);
+
+    // teardown is persisted before it is handed to the worker
+    let queued_deletes: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM job_queue WHERE payload ? 'Delete'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(queued_deletes, 2);
+    assert!(job_rx.try_recv().is_ok());
+}
@@ -0,0 +1,62 @@
+use axum_prometheus::metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use backend::telemetry::tenant::{OrgLabeler, TenantMetrics, NO_ORG, OTHER_ORG};
+use once_cell::sync::Lazy;
+
+// one recorder per test binary; the tests below use distinct org ids so they can share it
+static HANDLE: Lazy<PrometheusHandle> = Lazy::new(|| {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let handle = recorder.handle();
+    metrics::set_boxed_recorder(Box::new(recorder)).expect("install test recorder");
+    handle
+});
+
+#[test]
+fn key_metrics_carry_the_org_label() {
+    let handle = Lazy::force(&HANDLE);
+    let tenant = TenantMetrics::new(OrgLabeler::new(10, 1));
+
+    tenant.run_enqueued(Some(41));
+    tenant.run_completed(Some(41));
+    tenant.run_failed(Some(41));
+    tenant.quota_denied(41);
+    tenant.build_duration(Some(41), 12.5);
+    tenant.run_enqueued(None);
+
+    let rendered = handle.render();
+    assert!(rendered.contains(r#"mcp_remediation_runs_enqueued_total{org_id="41"} 1"#));
+    assert!(rendered.contains(r#"mcp_remediation_runs_completed_total{org_id="41"} 1"#));
+    assert!(rendered.contains(r#"mcp_remediation_runs_failed_total{org_id="41"} 1"#));
+    assert!(rendered.contains(r#"mcp_quota_denials_total{org_id="41"} 1"#));
+    assert!(rendered.contains(r#"mcp_build_duration_seconds_count{org_id="41"} 1"#));
+    assert!(rendered.contains(r#"mcp_remediation_runs_enqueued_total{org_id="none"}"#));
+}
+
+#[test]
+fn orgs_past_the_cap_collapse_into_other() {
+    let handle = Lazy::force(&HANDLE);
+    let tenant = TenantMetrics::new(OrgLabeler::new(2, 1));
+
+    for org_id in [51, 52, 53, 54] {
+        tenant.quota_denied(org_id);
+    }
+    tenant.quota_denied(51);
+
+    let rendered = handle.render();
+    assert!(rendered.contains(r#"mcp_quota_denials_total{org_id="51"} 2"#));
+    assert!(rendered.contains(r#"mcp_quota_denials_total{org_id="52"} 1"#));
+    assert!(!rendered.contains(r#"org_id="53""#));
+    assert!(!rendered.contains(r#"org_id="54""#));
+    assert!(rendered.contains(r#"mcp_quota_denials_total{org_id="other"} 2"#));
+}
+
+#[test]
+fn low_volume_orgs_stay_in_other_until_they_reach_the_threshold() {
+    let labeler = OrgLabeler::new(5, 3);
+
+    assert_eq!(labeler.label(Some(7)), OTHER_ORG);
+    assert_eq!(labeler.label(Some(7)), OTHER_ORG);
+    assert_eq!(labeler.label(Some(7)), "7");
+    assert_eq!(labeler.label(Some(7)), "7");
+    assert_eq!(labeler.label(Some(8)), OTHER_ORG);
+    assert_eq!(labeler.label(None), NO_ORG);
+}
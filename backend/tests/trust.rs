@@ -1,7 +1,11 @@
-use axum::{routing::post, Extension, Router};
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
 use backend::db::runtime_vm_remediation_runs::{
     ensure_remediation_run, update_run_workspace_linkage, EnsureRemediationRunRequest,
 };
+use backend::db::runtime_vm_trust_registry::{upsert_state, UpsertRuntimeVmTrustRegistryState};
 use backend::policy::trust::evaluate_placement_gate;
 use backend::trust::TrustRegistryView;
 use chrono::{Duration, Utc};
@@ -153,6 +157,7 @@ async fn placement_gate_blocks_active_remediation(pool: PgPool) {
             workspace_id: None,
             workspace_revision_id: None,
             promotion_gate_context: None,
+            scheduled_for: None,
         },
     )
     .await
@@ -226,6 +231,7 @@ async fn placement_gate_flags_structural_failure(pool: PgPool) {
             workspace_id: None,
             workspace_revision_id: None,
             promotion_gate_context: None,
+            scheduled_for: None,
         },
     )
     .await
@@ -341,6 +347,7 @@ async fn update_run_workspace_linkage_merges_json_metadata(pool: PgPool) {
             workspace_id: None,
             workspace_revision_id: None,
             promotion_gate_context: None,
+            scheduled_for: None,
         },
     )
     .await
@@ -397,3 +404,108 @@ async fn update_run_workspace_linkage_merges_json_metadata(pool: PgPool) {
         Some("value"),
     );
 }
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn registry_by_server_includes_instances_without_trust_rows(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let user_id: i32 =
+        sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id")
+            .bind("fleet@example.com")
+            .bind("hashed")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    let server_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, $2, $3, '{}'::jsonb, $4, $5) RETURNING id",
+    )
+    .bind(user_id)
+    .bind("fleet-node")
+    .bind("virtual-machine")
+    .bind("active")
+    .bind("fleet-key")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let mut vm_instance_ids = Vec::new();
+    for instance in ["vm-fleet-1", "vm-fleet-2"] {
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(server_id)
+        .bind(instance)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        vm_instance_ids.push(id);
+    }
+
+    upsert_state(
+        &pool,
+        UpsertRuntimeVmTrustRegistryState {
+            runtime_vm_instance_id: vm_instance_ids[0],
+            attestation_status: "trusted",
+            lifecycle_state: "restored",
+            remediation_state: None,
+            remediation_attempts: 0,
+            freshness_deadline: None,
+            provenance_ref: None,
+            provenance: None,
+            expected_version: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    std::env::set_var("JWT_SECRET", "integration-secret");
+    let exp = (Utc::now() + Duration::hours(1)).timestamp();
+    let claims = json!({"sub": user_id, "role": "operator", "exp": exp});
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(b"integration-secret"),
+    )
+    .unwrap();
+
+    let app = Router::new()
+        .route(
+            "/api/trust/registry/by-server/:server_id",
+            get(backend::trust::get_registry_states_by_server),
+        )
+        .layer(Extension(pool.clone()));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/trust/registry/by-server/{server_id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let payload: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(payload["server_id"].as_i64(), Some(server_id as i64));
+    let instances = payload["instances"].as_array().expect("instances array");
+    assert_eq!(instances.len(), 2);
+
+    assert_eq!(
+        instances[0]["runtime_vm_instance_id"].as_i64(),
+        Some(vm_instance_ids[0])
+    );
+    assert_eq!(instances[0]["state"]["attestation_status"], "trusted");
+    assert_eq!(instances[0]["state"]["lifecycle_state"], "restored");
+
+    assert_eq!(
+        instances[1]["runtime_vm_instance_id"].as_i64(),
+        Some(vm_instance_ids[1])
+    );
+    assert_eq!(instances[1]["instance_id"], "vm-fleet-2");
+    assert!(instances[1]["state"].is_null());
+}
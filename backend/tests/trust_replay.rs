@@ -0,0 +1,134 @@
+mod common;
+
+use axum::{routing::post, Extension, Router};
+use chrono::{Duration, Utc};
+use hyper::{Body, Request, StatusCode};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{token, use_test_jwt_secret};
+
+// key: trust-replay-tests -> replayed ranges settle on the last event's state
+
+async fn replay(app: &Router, bearer: &str, body: &Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/trust/replay")
+                .header("content-type", "application/json")
+                .header("Authorization", format!("Bearer {bearer}"))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn registry(pool: &PgPool, vm_instance_id: i64) -> (String, String, i32) {
+    sqlx::query_as(
+        "SELECT attestation_status, lifecycle_state, remediation_attempts FROM runtime_vm_trust_registry WHERE runtime_vm_instance_id = $1",
+    )
+    .bind(vm_instance_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn replaying_a_range_rederives_trust_state_once(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    use_test_jwt_secret();
+
+    let admin_id: i32 = sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash) VALUES ('replay@example.com', 'hashed') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let server_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mcp_servers (owner_id, name, server_type, config, status, api_key) VALUES ($1, 'edge-replay', 'virtual-machine', '{}'::jsonb, 'active', 'edge-replay') RETURNING id",
+    )
+    .bind(admin_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let vm_instance_id: i64 = sqlx::query_scalar(
+        "INSERT INTO runtime_vm_instances (server_id, instance_id) VALUES ($1, 'edge-replay-vm') RETURNING id",
+    )
+    .bind(server_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // a buggy handler double-counted attempts and never left `restored`
+    sqlx::query(
+        "INSERT INTO runtime_vm_trust_registry (runtime_vm_instance_id, attestation_status, lifecycle_state, remediation_attempts) VALUES ($1, 'trusted', 'restored', 5)",
+    )
+    .bind(vm_instance_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let now = Utc::now();
+    for (minutes_ago, lifecycle, attempts) in [(30, "quarantined", 1), (20, "remediating", 2)] {
+        sqlx::query(
+            "INSERT INTO runtime_vm_trust_history (runtime_vm_instance_id, current_status, current_lifecycle_state, remediation_attempts, triggered_at) VALUES ($1, 'untrusted', $2, $3, $4)",
+        )
+        .bind(vm_instance_id)
+        .bind(lifecycle)
+        .bind(attempts)
+        .bind(now - Duration::minutes(minutes_ago))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let (job_tx, _job_rx) = tokio::sync::mpsc::channel::<backend::job_queue::QueuedJob>(8);
+    let app = Router::new()
+        .route(
+            "/api/trust/replay",
+            post(backend::trust::replay::replay_trust_events),
+        )
+        .layer(Extension(job_tx))
+        .layer(Extension(pool.clone()));
+    let range = json!({
+        "from": now - Duration::hours(1),
+        "to": now,
+        "vm_instance_id": vm_instance_id,
+    });
+
+    let (status, _) = replay(&app, &token(admin_id, "operator"), &range).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, report) = replay(&app, &token(admin_id, "admin"), &range).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["events_replayed"], 2);
+    assert_eq!(report["instances"][0]["status"], "applied");
+    assert_eq!(
+        registry(&pool, vm_instance_id).await,
+        ("untrusted".to_string(), "remediating".to_string(), 2)
+    );
+
+    // replaying the same range again neither re-runs the handler nor adds attempts
+    let (status, report) = replay(&app, &token(admin_id, "admin"), &range).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["instances"][0]["status"], "already_replayed");
+    assert_eq!(
+        registry(&pool, vm_instance_id).await,
+        ("untrusted".to_string(), "remediating".to_string(), 2)
+    );
+
+    let (status, _) = replay(
+        &app,
+        &token(admin_id, "admin"),
+        &json!({ "from": now, "to": now - Duration::hours(1) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
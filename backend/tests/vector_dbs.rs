@@ -3,6 +3,7 @@ use axum::{extract::Path, http::StatusCode, Extension, Json};
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine as _;
 use chrono::{Duration, Utc};
+use httpmock::prelude::*;
 use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -14,11 +15,15 @@ use backend::keys::{
 use backend::vector_dbs::{
     create_vector_db, create_vector_db_attachment, detach_vector_db_attachment,
     list_vector_db_attachments, list_vector_db_incidents, log_vector_db_incident,
-    resolve_vector_db_incident, upsert_vector_db_residency_policy, CreateVectorDb,
-    CreateVectorDbAttachment, CreateVectorDbIncident, DetachVectorDbAttachment,
-    ResolveVectorDbIncident, UpsertVectorDbResidencyPolicy,
+    resolve_vector_db_incident, search_vector_db, upsert_vector_db_residency_policy,
+    CreateVectorDb, CreateVectorDbAttachment, CreateVectorDbIncident, DetachVectorDbAttachment,
+    ResolveVectorDbIncident, UpsertVectorDbResidencyPolicy, VectorSearchMetric,
+    VectorSearchRequest,
 };
 
+const CHROMA_COLLECTIONS: &str =
+    "/api/v2/tenants/default_tenant/databases/default_database/collections";
+
 async fn seed_owner(pool: &PgPool) -> i32 {
     sqlx::query_scalar("INSERT INTO users (email, password_hash) VALUES ($1,$2) RETURNING id")
         .bind("owner@example.com")
@@ -47,6 +52,7 @@ async fn seed_provider_binding(pool: &PgPool, binding_type: &str) -> (Uuid, Uuid
                 attestation_digest: Some(STANDARD.encode(b"digest")),
                 attestation_signature: Some(STANDARD.encode(b"signature")),
                 rotation_due_at: Some(rotation_due),
+                organization_id: None,
             },
         )
         .await
@@ -80,6 +86,7 @@ async fn vector_db_attachment_requires_residency_and_vector_binding(pool: PgPool
         Json(CreateVectorDb {
             name: "governed".into(),
             db_type: "chroma".into(),
+            dimension: None,
         }),
     )
     .await
@@ -172,6 +179,7 @@ async fn vector_db_incident_requires_owned_attachment(pool: PgPool) {
         Json(CreateVectorDb {
             name: "audited".into(),
             db_type: "chroma".into(),
+            dimension: None,
         }),
     )
     .await
@@ -267,6 +275,7 @@ async fn vector_db_attachment_can_be_detached(pool: PgPool) {
         Json(CreateVectorDb {
             name: "detach-me".into(),
             db_type: "chroma".into(),
+            dimension: None,
         }),
     )
     .await
@@ -356,6 +365,7 @@ async fn vector_db_incident_can_be_resolved(pool: PgPool) {
         Json(CreateVectorDb {
             name: "incident-lifecycle".into(),
             db_type: "chroma".into(),
+            dimension: None,
         }),
     )
     .await
@@ -441,3 +451,190 @@ async fn vector_db_incident_can_be_resolved(pool: PgPool) {
     .unwrap();
     assert_eq!(err.0, StatusCode::CONFLICT);
 }
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn vector_db_cosine_search_returns_ordered_results(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let server = MockServer::start_async().await;
+    let collection_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{CHROMA_COLLECTIONS}/searchable"));
+        then.status(200).json_body(json!({
+            "id": "c-1",
+            "name": "searchable",
+            "metadata": { "hnsw:space": "cosine" },
+            "dimension": 3
+        }));
+    });
+    let search_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path(format!("{CHROMA_COLLECTIONS}/c-1/query"))
+            .json_body_partial(r#"{"n_results": 2}"#);
+        then.status(200).json_body(json!({
+            "ids": [["doc-low", "doc-high", "doc-mid"]],
+            "distances": [[0.88, 0.03, 0.46]],
+            "metadatas": [[{}, { "title": "best" }, null]]
+        }));
+    });
+
+    let owner_id = seed_owner(&pool).await;
+    let vector = create_vector_db(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Json(CreateVectorDb {
+            name: "searchable".into(),
+            db_type: "chroma".into(),
+            dimension: Some(3),
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+    assert_eq!(vector.dimension, Some(3));
+
+    sqlx::query("UPDATE vector_dbs SET url = $1 WHERE id = $2")
+        .bind(server.base_url())
+        .bind(vector.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let response = search_vector_db(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Path(vector.id),
+        Json(VectorSearchRequest {
+            embedding: vec![0.1, 0.2, 0.3],
+            top_k: 2,
+            metric: VectorSearchMetric::Cosine,
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+
+    collection_mock.assert();
+    search_mock.assert();
+    assert_eq!(response.metric, VectorSearchMetric::Cosine);
+    let ids: Vec<&str> = response.results.iter().map(|hit| hit.id.as_str()).collect();
+    assert_eq!(ids, vec!["doc-high", "doc-mid"]);
+    assert!((response.results[0].score - 0.97).abs() < 1e-9);
+    assert_eq!(response.results[0].metadata["title"], "best");
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn vector_db_search_checks_the_chroma_collection_without_a_stored_dimension(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let server = MockServer::start_async().await;
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{CHROMA_COLLECTIONS}/untyped"));
+        then.status(200).json_body(json!({
+            "id": "c-2",
+            "name": "untyped",
+            "metadata": null,
+            "dimension": 4
+        }));
+    });
+    let query_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path(format!("{CHROMA_COLLECTIONS}/c-2/query"));
+        then.status(200)
+            .json_body(json!({ "ids": [[]], "distances": [[]] }));
+    });
+
+    let owner_id = seed_owner(&pool).await;
+    let vector = create_vector_db(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Json(CreateVectorDb {
+            name: "untyped".into(),
+            db_type: "chroma".into(),
+            dimension: None,
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+    sqlx::query("UPDATE vector_dbs SET url = $1 WHERE id = $2")
+        .bind(server.base_url())
+        .bind(vector.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let search = |embedding: Vec<f32>, metric| {
+        search_vector_db(
+            Extension(pool.clone()),
+            auth(owner_id),
+            Path(vector.id),
+            Json(VectorSearchRequest {
+                embedding,
+                top_k: 5,
+                metric,
+            }),
+        )
+    };
+
+    // the collection defaults to l2, so a cosine search cannot be answered
+    let err = search(vec![0.1; 4], VectorSearchMetric::Cosine)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    assert!(err.1.contains("`l2`"));
+
+    let err = search(vec![0.1; 2], VectorSearchMetric::L2)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    assert!(err.1.contains("expects 4"));
+    query_mock.assert_hits(0);
+
+    let response = search(vec![0.1; 4], VectorSearchMetric::L2)
+        .await
+        .unwrap()
+        .0;
+    assert!(response.results.is_empty());
+    query_mock.assert();
+}
+
+#[sqlx::test]
+#[ignore = "requires DATABASE_URL with Postgres server"]
+async fn vector_db_search_rejects_dimension_mismatch(pool: PgPool) {
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let owner_id = seed_owner(&pool).await;
+    let vector = create_vector_db(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Json(CreateVectorDb {
+            name: "fixed-width".into(),
+            db_type: "chroma".into(),
+            dimension: Some(4),
+        }),
+    )
+    .await
+    .unwrap()
+    .0;
+
+    let err = search_vector_db(
+        Extension(pool.clone()),
+        auth(owner_id),
+        Path(vector.id),
+        Json(VectorSearchRequest {
+            embedding: vec![0.5, 0.5],
+            top_k: 5,
+            metric: VectorSearchMetric::L2,
+        }),
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    assert!(err.1.contains("expects 4"));
+}
@@ -1,10 +1,14 @@
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
 
 use backend::policy::{PolicyDecision, RuntimeBackend};
 use backend::runtime::vm::attestation::{
     detect_kind, normalize_evidence, sev_outcome_from_normalized, AttestationKind,
-    AttestationStatus, NormalizedAttestation,
+    AttestationStatus, AttestationVerifier, AttestationVerifierRegistry, NormalizedAttestation,
+    SevSnpAttestationVerifier, TpmAttestationVerifier,
 };
+use backend::runtime::vm::VmProvisioningResult;
 use base64::engine::general_purpose::STANDARD as Base64Engine;
 use base64::Engine;
 use chrono::{Duration as ChronoDuration, Utc};
@@ -69,6 +73,7 @@ fn sev_outcome_tracks_trust_and_freshness() {
         normalized.clone(),
         &HashSet::from(["trusted".to_string()]),
         ChronoDuration::minutes(5),
+        ChronoDuration::seconds(30),
     );
     assert_eq!(trusted.status, AttestationStatus::Trusted);
     assert!(trusted
@@ -94,17 +99,142 @@ fn sev_outcome_tracks_trust_and_freshness() {
         },
         &HashSet::from(["trusted".to_string()]),
         ChronoDuration::minutes(5),
+        ChronoDuration::seconds(30),
     );
     assert_eq!(stale.status, AttestationStatus::Untrusted);
     assert!(stale.notes.iter().any(|note| note == "attestation:stale"));
 }
 
+#[test]
+fn sev_outcome_tolerates_clock_skew_for_future_timestamps() {
+    let normalized = NormalizedAttestation {
+        kind: AttestationKind::AmdSevSnp,
+        measurement: Some("trusted".to_string()),
+        timestamp: Some(Utc::now() + ChronoDuration::seconds(20)),
+        nonce: None,
+        claims: json!({ "measurement": "trusted" }),
+        raw_quote: None,
+    };
+    let measurements = HashSet::from(["trusted".to_string()]);
+
+    let within = sev_outcome_from_normalized(
+        &sample_decision(),
+        normalized.clone(),
+        &measurements,
+        ChronoDuration::minutes(5),
+        ChronoDuration::seconds(30),
+    );
+    assert_eq!(within.status, AttestationStatus::Trusted);
+
+    let beyond = sev_outcome_from_normalized(
+        &sample_decision(),
+        NormalizedAttestation {
+            timestamp: Some(Utc::now() + ChronoDuration::minutes(2)),
+            ..normalized
+        },
+        &measurements,
+        ChronoDuration::minutes(5),
+        ChronoDuration::seconds(30),
+    );
+    assert_eq!(beyond.status, AttestationStatus::Untrusted);
+    assert_eq!(beyond.notes, vec!["attestation:not-yet-valid"]);
+}
+
 #[test]
 fn detect_kind_handles_unknown_payloads() {
     let evidence = json!({ "custom": { "field": "value" } });
     assert_eq!(detect_kind(&evidence), AttestationKind::Unknown);
 }
 
+#[tokio::test]
+async fn registry_routes_evidence_to_the_verifier_for_its_format() {
+    let measurements = HashSet::from(["sev-good".to_string(), "tpm-good".to_string()]);
+    let max_age = Duration::from_secs(300);
+    let registry = AttestationVerifierRegistry::new()
+        .register(
+            AttestationKind::Tpm,
+            Arc::new(TpmAttestationVerifier::new(
+                measurements.clone(),
+                Vec::new(),
+                max_age,
+            )),
+        )
+        .register(
+            AttestationKind::AmdSevSnp,
+            Arc::new(SevSnpAttestationVerifier::new(measurements, max_age)),
+        );
+    let provisioned = |evidence: serde_json::Value| {
+        VmProvisioningResult::new(
+            "vm-1".to_string(),
+            Some("confidential".to_string()),
+            Some(evidence),
+            "ghcr.io/example/app:latest".to_string(),
+            None,
+        )
+    };
+    let timestamp = Utc::now().to_rfc3339();
+
+    let sev = registry
+        .verify(
+            7,
+            &sample_decision(),
+            &provisioned(json!({
+                "format": "amd-sev-snp",
+                "amd_sev_snp": {"measurement": "SEV-GOOD", "timestamp": timestamp},
+            })),
+            None,
+        )
+        .await
+        .expect("sev verification");
+    assert_eq!(sev.attestation_kind, AttestationKind::AmdSevSnp);
+    assert_eq!(sev.status, AttestationStatus::Trusted);
+
+    // the TPM verifier rejects the unsigned quote, proving it (not the SEV stub) ran
+    let tpm = registry
+        .verify(
+            7,
+            &sample_decision(),
+            &provisioned(json!({
+                "format": "tpm",
+                "quote": {
+                    "report": {"measurement": "tpm-good", "timestamp": timestamp},
+                    "signature": Base64Engine.encode([0u8; 64]),
+                },
+            })),
+            None,
+        )
+        .await;
+    let tpm = tpm.expect_err("no trust roots configured for the TPM verifier");
+    assert!(tpm.to_string().contains("trust roots"));
+
+    let unknown = registry
+        .verify(
+            7,
+            &sample_decision(),
+            &provisioned(json!({"format": "arm-cca", "token": "opaque"})),
+            None,
+        )
+        .await
+        .expect("unknown formats are rejected, not errors");
+    assert_eq!(unknown.status, AttestationStatus::Untrusted);
+    assert_eq!(
+        unknown.notes,
+        vec!["attestation:unsupported-format:arm-cca"]
+    );
+
+    let unregistered = registry
+        .verify(
+            7,
+            &sample_decision(),
+            &provisioned(json!({"tdx_quote": {"mrseam": "abc"}})),
+            None,
+        )
+        .await
+        .expect("unregistered formats are rejected, not errors");
+    assert_eq!(unregistered.status, AttestationStatus::Untrusted);
+    assert_eq!(unregistered.attestation_kind, AttestationKind::IntelTdx);
+}
+
 fn sample_decision() -> PolicyDecision {
     PolicyDecision {
         backend: RuntimeBackend::VirtualMachine,